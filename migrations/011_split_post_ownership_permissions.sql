@@ -0,0 +1,32 @@
+-- 011: Split post update/delete permissions by ownership
+-- Migration: Writers may change their own posts, editors may change anyone's
+
+INSERT INTO permissions (name, description, resource, action) VALUES
+    ('posts:update_own', 'Update own posts', 'posts', 'update_own'),
+    ('posts:update_any', 'Update any post', 'posts', 'update_any'),
+    ('posts:delete_own', 'Delete own posts', 'posts', 'delete_own'),
+    ('posts:delete_any', 'Delete any post', 'posts', 'delete_any');
+
+-- Admin: all permissions
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE r.slug = 'admin'
+  AND p.name IN ('posts:update_own', 'posts:update_any', 'posts:delete_own', 'posts:delete_any');
+
+-- Editor: any post
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE r.slug = 'editor'
+  AND p.name IN ('posts:update_own', 'posts:update_any', 'posts:delete_own', 'posts:delete_any');
+
+-- Writer: own posts only
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE r.slug = 'writer'
+  AND p.name IN ('posts:update_own', 'posts:delete_own');
+
+-- The unscoped permissions are superseded (role_permissions rows cascade)
+DELETE FROM permissions WHERE name IN ('posts:update', 'posts:delete');
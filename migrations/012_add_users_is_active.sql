@@ -0,0 +1,4 @@
+-- 012: Add active flag to users
+-- Migration: Deactivated users keep their data but cannot sign in
+
+ALTER TABLE users ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT TRUE;
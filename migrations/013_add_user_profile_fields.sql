@@ -0,0 +1,8 @@
+-- 013: Add self-service profile fields to users
+-- Migration: Bio, avatar, social links and an email awaiting verification
+
+ALTER TABLE users
+    ADD COLUMN bio TEXT,
+    ADD COLUMN avatar_url TEXT,
+    ADD COLUMN social_links JSONB NOT NULL DEFAULT '{}'::jsonb,
+    ADD COLUMN pending_email VARCHAR(255);
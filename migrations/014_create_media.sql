@@ -0,0 +1,17 @@
+-- 014: Create media table
+-- Migration: Uploaded files and user avatars
+
+CREATE TABLE media (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    uploader_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    filename VARCHAR(255) NOT NULL,          -- Original client filename
+    storage_key VARCHAR(255) UNIQUE NOT NULL, -- File name inside UPLOAD_DIR
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_media_uploader ON media(uploader_id);
+
+-- Users can pick an uploaded image as their avatar
+ALTER TABLE users ADD COLUMN avatar_media_id UUID REFERENCES media(id) ON DELETE SET NULL;
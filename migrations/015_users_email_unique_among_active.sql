@@ -0,0 +1,5 @@
+-- 015: Only require unique emails among non-deleted users
+-- Migration: A soft-deleted account no longer blocks its email address
+
+ALTER TABLE users DROP CONSTRAINT users_email_key;
+CREATE UNIQUE INDEX idx_users_email_active ON users(email) WHERE deleted_at IS NULL;
@@ -0,0 +1,5 @@
+-- 016: Track approval of self-registered users
+-- Migration: NULL approved_at marks an account awaiting admin approval
+
+ALTER TABLE users ADD COLUMN approved_at TIMESTAMPTZ DEFAULT NOW();
+CREATE INDEX idx_users_pending ON users(created_at) WHERE approved_at IS NULL;
@@ -0,0 +1,13 @@
+-- 017: Create login_events table
+-- Migration: History of successful logins per user
+
+CREATE TABLE login_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    ip_address VARCHAR(45) NOT NULL,  -- IPv4 or IPv6 text form
+    user_agent TEXT,
+    country CHAR(2),                  -- ISO 3166-1 alpha-2, when known
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_login_events_user ON login_events(user_id, created_at DESC);
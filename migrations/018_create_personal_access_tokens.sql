@@ -0,0 +1,16 @@
+-- 018: Create personal_access_tokens table
+-- Migration: Long-lived, scoped API tokens for scripts
+
+CREATE TABLE personal_access_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    token_prefix VARCHAR(16) NOT NULL,        -- Leading characters shown to identify the token
+    token_hash CHAR(64) UNIQUE NOT NULL,      -- SHA-256 of the full token, hex encoded
+    scopes TEXT[] NOT NULL,                   -- Permission names the token may use
+    last_used_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_personal_access_tokens_user ON personal_access_tokens(user_id);
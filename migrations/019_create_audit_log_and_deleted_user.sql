@@ -0,0 +1,20 @@
+-- 019: Create audit_log table and deleted-user placeholder
+-- Migration: Append-only record of sensitive actions; erased users' content is reassigned to the placeholder
+
+CREATE TABLE audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    actor_id UUID,                    -- No FK: entries outlive the users they mention
+    action VARCHAR(100) NOT NULL,     -- e.g. 'user.erased'
+    target_type VARCHAR(50) NOT NULL,
+    target_id UUID,
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_log_target ON audit_log(target_type, target_id);
+CREATE INDEX idx_audit_log_created_at ON audit_log(created_at DESC);
+
+-- Placeholder author for content of erased accounts; soft-deleted and unable to sign in
+INSERT INTO users (id, email, password_hash, name, role_id, is_active, approved_at, deleted_at)
+SELECT '00000000-0000-0000-0000-000000000000', 'deleted-user@invalid', '!', 'Deleted user', id, false, NULL, NOW()
+FROM roles WHERE slug = 'viewer';
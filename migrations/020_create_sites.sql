@@ -0,0 +1,43 @@
+-- 020: Create sites table and scope content per site
+-- Migration: One deployment serves several sites, resolved from the request Host header
+
+CREATE TABLE sites (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    host VARCHAR(255) UNIQUE NOT NULL,        -- Lowercase, without port, e.g. 'blog.example.com'
+    is_default BOOLEAN NOT NULL DEFAULT false, -- Serves requests for unknown hosts
+    settings JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- At most one fallback site
+CREATE UNIQUE INDEX idx_sites_default ON sites(is_default) WHERE is_default;
+
+CREATE TRIGGER update_sites_updated_at
+    BEFORE UPDATE ON sites
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Existing content belongs to the default site
+INSERT INTO sites (name, host, is_default) VALUES ('Main site', 'localhost', true);
+
+ALTER TABLE categories ADD COLUMN site_id UUID REFERENCES sites(id) ON DELETE CASCADE;
+ALTER TABLE tags ADD COLUMN site_id UUID REFERENCES sites(id) ON DELETE CASCADE;
+ALTER TABLE posts ADD COLUMN site_id UUID REFERENCES sites(id) ON DELETE CASCADE;
+
+UPDATE categories SET site_id = (SELECT id FROM sites WHERE is_default);
+UPDATE tags SET site_id = (SELECT id FROM sites WHERE is_default);
+UPDATE posts SET site_id = (SELECT id FROM sites WHERE is_default);
+
+ALTER TABLE categories ALTER COLUMN site_id SET NOT NULL;
+ALTER TABLE tags ALTER COLUMN site_id SET NOT NULL;
+ALTER TABLE posts ALTER COLUMN site_id SET NOT NULL;
+
+-- Slugs only need to be unique within a site
+ALTER TABLE categories DROP CONSTRAINT categories_slug_key;
+ALTER TABLE tags DROP CONSTRAINT tags_slug_key;
+ALTER TABLE posts DROP CONSTRAINT posts_slug_key;
+
+CREATE UNIQUE INDEX idx_categories_site_slug ON categories(site_id, slug);
+CREATE UNIQUE INDEX idx_tags_site_slug ON tags(site_id, slug);
+CREATE UNIQUE INDEX idx_posts_site_slug ON posts(site_id, slug);
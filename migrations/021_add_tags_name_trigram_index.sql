@@ -0,0 +1,6 @@
+-- 021: Add trigram index on tag names
+-- Migration: Fuzzy tag search for the editor's tag picker
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_tags_name_trgm ON tags USING gin (name gin_trgm_ops);
@@ -0,0 +1,6 @@
+-- 022: Add parent to categories
+-- Migration: Nested categories; children are kept when their parent is deleted
+
+ALTER TABLE categories ADD COLUMN parent_id UUID REFERENCES categories(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_categories_parent ON categories(parent_id);
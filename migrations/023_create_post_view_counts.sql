@@ -0,0 +1,13 @@
+-- 023: Create post_view_counts table
+-- Migration: Daily view counters per post, rolled up into trending lists
+
+CREATE TABLE post_view_counts (
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    day DATE NOT NULL DEFAULT CURRENT_DATE,
+    views BIGINT NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (post_id, day)
+);
+
+-- Create index for window scans
+CREATE INDEX idx_post_view_counts_day ON post_view_counts(day);
@@ -0,0 +1,11 @@
+-- 024: Add search vector to posts
+-- Migration: Full-text search over posts for the built-in Postgres search backend
+
+-- The 'simple' configuration skips language-specific stemming, so posts in any language match
+ALTER TABLE posts ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', coalesce(title, '')), 'A') ||
+    setweight(to_tsvector('simple', coalesce(excerpt, '')), 'B') ||
+    setweight(to_tsvector('simple', coalesce(content, '')), 'C')
+) STORED;
+
+CREATE INDEX idx_posts_search_vector ON posts USING GIN (search_vector);
@@ -0,0 +1,6 @@
+-- 025: Add visibility to posts
+-- Migration: Members-only and private posts
+
+CREATE TYPE post_visibility AS ENUM ('public', 'members', 'private');
+
+ALTER TABLE posts ADD COLUMN visibility post_visibility NOT NULL DEFAULT 'public';
@@ -0,0 +1,4 @@
+-- 026: Add token version to users
+-- Migration: Bumping the version invalidates every token issued before it
+
+ALTER TABLE users ADD COLUMN token_version INTEGER NOT NULL DEFAULT 0;
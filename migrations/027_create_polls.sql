@@ -0,0 +1,43 @@
+-- 027: Create polls and poll_options tables
+-- Migration: Reader polls embedded into posts with a [poll id="..."] shortcode
+
+CREATE TABLE polls (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID NOT NULL REFERENCES sites(id) ON DELETE CASCADE,
+    question VARCHAR(500) NOT NULL,
+    opens_at TIMESTAMPTZ,                     -- NULL: open from creation
+    closes_at TIMESTAMPTZ,                    -- NULL: never closes
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CHECK (closes_at IS NULL OR opens_at IS NULL OR closes_at > opens_at)
+);
+
+CREATE TABLE poll_options (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    poll_id UUID NOT NULL REFERENCES polls(id) ON DELETE CASCADE,
+    label VARCHAR(200) NOT NULL,
+    position INTEGER NOT NULL,
+    votes BIGINT NOT NULL DEFAULT 0,
+
+    UNIQUE (poll_id, position)
+);
+
+CREATE INDEX idx_polls_site_id ON polls(site_id, created_at DESC);
+
+CREATE TRIGGER update_polls_updated_at
+    BEFORE UPDATE ON polls
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+INSERT INTO permissions (name, description, resource, action) VALUES
+    ('polls:create', 'Create polls', 'polls', 'create'),
+    ('polls:update', 'Update polls', 'polls', 'update'),
+    ('polls:delete', 'Delete polls', 'polls', 'delete');
+
+-- Admins and editors manage polls
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE r.slug IN ('admin', 'editor')
+  AND p.name IN ('polls:create', 'polls:update', 'polls:delete');
@@ -0,0 +1,20 @@
+-- 028: Create changelog_entries table
+-- Migration: "What's new on this site" entries kept apart from blog posts
+
+CREATE TABLE changelog_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID NOT NULL REFERENCES sites(id) ON DELETE CASCADE,
+    title VARCHAR(200) NOT NULL,
+    body TEXT NOT NULL,
+    version VARCHAR(50),                      -- optional release label, e.g. "v2.1"
+    published_at TIMESTAMPTZ,                 -- NULL: draft; future: scheduled
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_changelog_entries_site_published ON changelog_entries(site_id, published_at DESC);
+
+CREATE TRIGGER update_changelog_entries_updated_at
+    BEFORE UPDATE ON changelog_entries
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
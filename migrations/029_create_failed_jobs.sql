@@ -0,0 +1,17 @@
+-- 029: Create failed_jobs table
+-- Migration: Dead-letter queue for background deliveries that ran out of attempts
+
+CREATE TYPE job_kind AS ENUM ('revalidation', 'email');
+
+CREATE TABLE failed_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind job_kind NOT NULL,
+    payload JSONB NOT NULL,                   -- everything needed to run the job again
+    error TEXT NOT NULL,                      -- error of the latest attempt
+    attempts INTEGER NOT NULL DEFAULT 1,      -- failed runs, counting manual retries
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_failed_jobs_failed_at ON failed_jobs(failed_at DESC);
+CREATE INDEX idx_failed_jobs_kind ON failed_jobs(kind);
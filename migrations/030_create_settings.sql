@@ -0,0 +1,14 @@
+-- 030: Create settings table
+-- Migration: Admin-editable settings that apply to the whole installation, e.g. email template overrides
+
+CREATE TABLE settings (
+    key VARCHAR(100) PRIMARY KEY,             -- namespaced, e.g. "email_template.invitation"
+    value JSONB NOT NULL,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_settings_updated_at
+    BEFORE UPDATE ON settings
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
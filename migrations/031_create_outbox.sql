@@ -0,0 +1,17 @@
+-- 031: Create outbox table
+-- Migration: Domain events written in the same transaction as the change, delivered by a relay
+
+CREATE TABLE outbox (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_type VARCHAR(100) NOT NULL,         -- e.g. "post.published"
+    payload JSONB NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,      -- failed deliveries so far
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_outbox_next_attempt_at ON outbox(next_attempt_at);
+
+-- Events that run out of delivery attempts move to the dead-letter queue
+ALTER TYPE job_kind ADD VALUE 'event';
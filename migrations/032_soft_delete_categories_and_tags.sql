@@ -0,0 +1,11 @@
+-- 032: Soft delete categories and tags
+-- Migration: Deleted categories and tags keep their post links until restored
+
+ALTER TABLE categories ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE tags ADD COLUMN deleted_at TIMESTAMPTZ;
+
+-- A deleted category or tag no longer blocks its slug
+DROP INDEX idx_categories_site_slug;
+DROP INDEX idx_tags_site_slug;
+CREATE UNIQUE INDEX idx_categories_site_slug ON categories(site_id, slug) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX idx_tags_site_slug ON tags(site_id, slug) WHERE deleted_at IS NULL;
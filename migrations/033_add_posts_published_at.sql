@@ -0,0 +1,9 @@
+-- 033: Add first-publish time to posts
+-- Migration: Set when a post is first published and kept across edits; used for public ordering
+
+ALTER TABLE posts ADD COLUMN published_at TIMESTAMPTZ;
+
+-- Posts published (or archived) before this migration count as published when created
+UPDATE posts SET published_at = created_at WHERE status IN ('published', 'archived');
+
+CREATE INDEX idx_posts_site_published_at ON posts(site_id, (COALESCE(published_at, created_at)) DESC);
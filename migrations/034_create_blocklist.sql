@@ -0,0 +1,14 @@
+-- 034: Create blocklist_entries table
+-- Migration: Admin-managed IP/CIDR and email-domain blocklist for public write endpoints
+
+CREATE TYPE blocklist_kind AS ENUM ('ip', 'email_domain');
+
+CREATE TABLE blocklist_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind blocklist_kind NOT NULL,
+    value VARCHAR(255) NOT NULL,              -- IP address, CIDR range ("10.0.0.0/8") or domain
+    reason TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (kind, value)
+);
@@ -0,0 +1,18 @@
+-- 035: Media library
+-- Migration: Alt text for uploaded files and permissions to manage them
+
+ALTER TABLE media ADD COLUMN alt_text VARCHAR(500);
+
+CREATE INDEX idx_media_created_at ON media(created_at DESC);
+
+INSERT INTO permissions (name, description, resource, action) VALUES
+    ('media:create', 'Upload media', 'media', 'create'),
+    ('media:update', 'Rename media and edit alt text', 'media', 'update'),
+    ('media:delete', 'Delete media', 'media', 'delete');
+
+-- Admins hold every permission; editors manage the library and writers upload to it
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE (r.slug IN ('admin', 'editor') AND p.name IN ('media:create', 'media:update', 'media:delete'))
+   OR (r.slug = 'writer' AND p.name = 'media:create');
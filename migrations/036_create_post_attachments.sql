@@ -0,0 +1,15 @@
+-- 036: Create post_attachments table
+-- Migration: Files attached to posts and how often each was downloaded
+
+ALTER TABLE media ADD COLUMN download_count BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE post_attachments (
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    media_id UUID NOT NULL REFERENCES media(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,                -- order in which the post lists its attachments
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (post_id, media_id)
+);
+
+CREATE INDEX idx_post_attachments_media ON post_attachments(media_id);
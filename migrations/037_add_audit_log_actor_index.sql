@@ -0,0 +1,4 @@
+-- 037: Index audit log by actor
+-- Migration: The admin activity feed pages through entries, optionally of one actor
+
+CREATE INDEX idx_audit_log_actor_id ON audit_log(actor_id, created_at DESC);
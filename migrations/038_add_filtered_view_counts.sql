@@ -0,0 +1,6 @@
+-- 038: Filtered view counters
+-- Migration: Views from crawlers and spam referrers are counted apart from human views
+
+ALTER TABLE post_view_counts
+    ADD COLUMN bot_views BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN spam_views BIGINT NOT NULL DEFAULT 0;
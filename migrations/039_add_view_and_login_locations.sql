@@ -0,0 +1,17 @@
+-- 039: Country-level locations for views and logins
+-- Migration: Daily human views per post by country and region, and the region of each login
+
+CREATE TABLE post_view_locations (
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    day DATE NOT NULL DEFAULT CURRENT_DATE,
+    country CHAR(2) NOT NULL,            -- ISO 3166-1 alpha-2
+    region VARCHAR(3) NOT NULL DEFAULT '', -- ISO 3166-2 subdivision code, '' when unknown
+    views BIGINT NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (post_id, day, country, region)
+);
+
+-- Create index for window scans
+CREATE INDEX idx_post_view_locations_day ON post_view_locations(day);
+
+ALTER TABLE login_events ADD COLUMN region VARCHAR(3);
@@ -0,0 +1,15 @@
+-- 040: Create post_title_variants table
+-- Migration: A/B tests of alternative post titles, with impressions and clicks per variant
+
+CREATE TABLE post_title_variants (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    title VARCHAR(255),                      -- NULL: the post's own title (position 0)
+    impressions BIGINT NOT NULL DEFAULT 0,   -- served in a post list
+    clicks BIGINT NOT NULL DEFAULT 0,        -- served on the post page
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (post_id, position),
+    CHECK ((position = 0) = (title IS NULL))
+);
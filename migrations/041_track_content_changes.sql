@@ -0,0 +1,46 @@
+-- 041: Track content changes for incremental sync
+-- Migration: Tag updated_at, change indexes and tombstones of deleted posts, categories and tags
+
+ALTER TABLE tags ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE TRIGGER update_tags_updated_at
+    BEFORE UPDATE ON tags
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX idx_posts_site_updated_at ON posts(site_id, updated_at);
+CREATE INDEX idx_categories_site_updated_at ON categories(site_id, updated_at);
+CREATE INDEX idx_tags_site_updated_at ON tags(site_id, updated_at);
+
+-- Rows removed for good, so mirrors syncing changes can drop them too
+CREATE TABLE deleted_content (
+    kind VARCHAR(20) NOT NULL,               -- 'post', 'category' or 'tag'
+    id UUID NOT NULL,
+    site_id UUID NOT NULL,                   -- no foreign key: outlives a deleted site
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (kind, id)
+);
+
+CREATE INDEX idx_deleted_content_site_deleted_at ON deleted_content(site_id, deleted_at);
+
+CREATE OR REPLACE FUNCTION record_deleted_content()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO deleted_content (kind, id, site_id)
+    VALUES (TG_ARGV[0], OLD.id, OLD.site_id)
+    ON CONFLICT (kind, id) DO UPDATE SET deleted_at = NOW();
+    RETURN OLD;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER record_deleted_posts
+    AFTER DELETE ON posts
+    FOR EACH ROW EXECUTE FUNCTION record_deleted_content('post');
+
+CREATE TRIGGER record_deleted_categories
+    AFTER DELETE ON categories
+    FOR EACH ROW EXECUTE FUNCTION record_deleted_content('category');
+
+CREATE TRIGGER record_deleted_tags
+    AFTER DELETE ON tags
+    FOR EACH ROW EXECUTE FUNCTION record_deleted_content('tag');
@@ -0,0 +1,4 @@
+-- 042: Index the user listing
+-- Migration: Keyset pagination of users newest first
+
+CREATE INDEX idx_users_created_at_id ON users(created_at DESC, id DESC);
@@ -0,0 +1,11 @@
+-- 043: Create notification_preferences table
+-- Migration: Which emails each user wants; users without a row get the defaults
+
+CREATE TABLE notification_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    email_on_comment BOOLEAN NOT NULL DEFAULT TRUE,
+    email_on_mention BOOLEAN NOT NULL DEFAULT TRUE,
+    weekly_digest BOOLEAN NOT NULL DEFAULT FALSE,
+    security_alerts BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
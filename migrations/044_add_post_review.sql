@@ -0,0 +1,28 @@
+-- 044: Post review workflow
+-- Migration: Writers submit posts for review; reviewers approve or reject them with a note
+
+ALTER TYPE post_status ADD VALUE 'in_review' AFTER 'draft';
+
+CREATE TABLE post_reviews (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    reviewer_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    approved BOOLEAN NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_post_reviews_post ON post_reviews(post_id, created_at DESC);
+
+ALTER TABLE notification_preferences
+    ADD COLUMN email_on_review BOOLEAN NOT NULL DEFAULT TRUE;
+
+INSERT INTO permissions (name, description, resource, action) VALUES
+    ('posts:review', 'Approve or reject posts submitted for review', 'posts', 'review');
+
+-- Admins and editors review
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE r.slug IN ('admin', 'editor')
+  AND p.name = 'posts:review';
@@ -0,0 +1,12 @@
+-- 045: Create post_notes table
+-- Migration: Internal editorial notes on posts, never shown to readers
+
+CREATE TABLE post_notes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_post_notes_post ON post_notes(post_id, created_at);
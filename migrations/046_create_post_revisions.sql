@@ -0,0 +1,18 @@
+-- 046: Create post_revisions table
+-- Migration: Snapshot of a post's title, excerpt and content after each change to them
+
+CREATE TABLE post_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    post_id UUID NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    excerpt TEXT,
+    content TEXT NOT NULL,
+    edited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_post_revisions_post ON post_revisions(post_id, created_at DESC);
+
+-- Existing posts start from their current text
+INSERT INTO post_revisions (post_id, title, excerpt, content, created_at)
+SELECT id, title, excerpt, content, updated_at FROM posts;
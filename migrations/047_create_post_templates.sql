@@ -0,0 +1,21 @@
+-- 047: Create post_templates table
+-- Migration: Starting points for recurring post formats, like "Weekly Notes"
+
+CREATE TABLE post_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID NOT NULL REFERENCES sites(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    title_pattern VARCHAR(255) NOT NULL,       -- e.g. "Weekly Notes {year}-W{week}"
+    content TEXT NOT NULL DEFAULT '',
+    category_id UUID REFERENCES categories(id) ON DELETE SET NULL,
+    tag_ids UUID[] NOT NULL DEFAULT '{}',
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (site_id, name)
+);
+
+CREATE TRIGGER update_post_templates_updated_at
+    BEFORE UPDATE ON post_templates
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,21 @@
+-- 048: Create hook_deliveries table
+-- Migration: Inbound webhooks from third-party services, kept verbatim for replay and debugging
+
+CREATE TYPE hook_integration AS ENUM ('github', 'stripe', 'newsletter');
+CREATE TYPE hook_status AS ENUM ('received', 'processed', 'ignored', 'failed');
+
+CREATE TABLE hook_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    integration hook_integration NOT NULL,
+    event_type VARCHAR(255),                  -- e.g. "push", "invoice.paid"
+    headers JSONB NOT NULL DEFAULT '{}',      -- request headers, without credentials
+    payload TEXT NOT NULL,                    -- body exactly as received
+    status hook_status NOT NULL DEFAULT 'received',
+    error TEXT,                               -- error of the latest handling attempt
+    attempts INTEGER NOT NULL DEFAULT 0,      -- handling runs, counting replays
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    processed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_hook_deliveries_received_at ON hook_deliveries(received_at DESC);
+CREATE INDEX idx_hook_deliveries_integration ON hook_deliveries(integration, status);
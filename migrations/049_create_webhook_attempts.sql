@@ -0,0 +1,18 @@
+-- 049: Create webhook_attempts table
+-- Migration: Every outgoing webhook request and its response, for debugging and redelivery
+
+CREATE TABLE webhook_attempts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind job_kind NOT NULL,                   -- 'revalidation' or 'event'
+    url TEXT NOT NULL,
+    request_headers JSONB NOT NULL DEFAULT '{}', -- credentials redacted
+    payload TEXT NOT NULL,                    -- body exactly as sent
+    response_status INTEGER,                  -- missing when no response arrived
+    response_body TEXT,                       -- first part of the response body
+    error TEXT,                               -- why the attempt did not succeed
+    duration_ms INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_webhook_attempts_created_at ON webhook_attempts(created_at DESC);
+CREATE INDEX idx_webhook_attempts_kind ON webhook_attempts(kind, created_at DESC);
@@ -0,0 +1,7 @@
+-- 050: Create changelog_feeds table
+-- Migration: When entries last left each site's changelog feed, for its Last-Modified
+
+CREATE TABLE changelog_feeds (
+    site_id UUID PRIMARY KEY REFERENCES sites(id) ON DELETE CASCADE,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW() -- last delete or unpublish of a published entry
+);
@@ -0,0 +1,8 @@
+-- 051: Add delivery_id to hook_deliveries
+-- Migration: The sender's ID of each delivery, so retried deliveries are only handled once
+
+ALTER TABLE hook_deliveries ADD COLUMN delivery_id VARCHAR(255); -- e.g. X-GitHub-Delivery
+
+CREATE UNIQUE INDEX idx_hook_deliveries_delivery_id
+    ON hook_deliveries(integration, delivery_id)
+    WHERE delivery_id IS NOT NULL;
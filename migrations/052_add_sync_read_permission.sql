@@ -0,0 +1,12 @@
+-- 052: Sync read permission
+-- Migration: Let mirrors read /sync/changes with a personal access token scoped to sync:read
+
+INSERT INTO permissions (name, description, resource, action) VALUES
+    ('sync:read', 'List content changed since a time for mirrors', 'sync', 'read');
+
+-- Admins hold it; other roles can be granted it for their mirrors
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE r.slug = 'admin'
+  AND p.name = 'sync:read';
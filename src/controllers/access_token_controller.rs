@@ -0,0 +1,55 @@
+//! Personal access token controller for the authenticated user.
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{CreateAccessTokenRequest, CreatedAccessTokenResponse, PersonalAccessToken};
+use crate::response::{success, ApiResponse, MessageResponse};
+use crate::services::AccessTokenService;
+
+/// Tokens are managed from a signed-in session, never with another token.
+fn require_session(auth_user: &AuthUser) -> Result<(), AppError> {
+    if auth_user.token_id.is_some() {
+        return Err(AppError::Forbidden(
+            "Access tokens cannot manage access tokens".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// List the current user's personal access tokens.
+pub async fn list_my_tokens(
+    State(token_service): State<AccessTokenService>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<ApiResponse<Vec<PersonalAccessToken>>>, AppError> {
+    require_session(&auth_user)?;
+    let tokens = token_service.list(auth_user.id).await?;
+    Ok(success(tokens))
+}
+
+/// Create a personal access token; the secret is only shown in this response.
+pub async fn create_my_token(
+    State(token_service): State<AccessTokenService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<CreateAccessTokenRequest>,
+) -> Result<Json<ApiResponse<CreatedAccessTokenResponse>>, AppError> {
+    require_session(&auth_user)?;
+    let created = token_service.create(&auth_user, request).await?;
+    Ok(success(created))
+}
+
+/// Revoke one of the current user's personal access tokens.
+pub async fn revoke_my_token(
+    State(token_service): State<AccessTokenService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    require_session(&auth_user)?;
+    token_service.revoke(auth_user.id, id).await?;
+    Ok(success(MessageResponse::new("Token revoked")))
+}
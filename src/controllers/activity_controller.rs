@@ -0,0 +1,20 @@
+//! Activity controller for the admin dashboard feed (admin only).
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::error::AppError;
+use crate::models::{ActivityItem, ActivityQuery};
+use crate::response::{paginated, ApiResponse};
+use crate::services::ActivityService;
+
+/// List recent activity, optionally by one actor (admin only).
+pub async fn list_activity(
+    State(activity_service): State<ActivityService>,
+    Query(query): Query<ActivityQuery>,
+) -> Result<Json<ApiResponse<Vec<ActivityItem>>>, AppError> {
+    let (items, meta) = activity_service.list(query).await?;
+    Ok(paginated(items, meta.page, meta.per_page, meta.total))
+}
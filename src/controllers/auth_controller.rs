@@ -1,20 +1,25 @@
 //! Authentication controller for login, refresh, and logout.
 
 use axum::{extract::State, Extension, Json};
+use jsonwebtoken::jwk::JwkSet;
 
 use crate::error::AppError;
-use crate::middleware::AuthUser;
-use crate::models::{LoginRequest, LoginResponse, RefreshTokenRequest, RefreshTokenResponse};
+use crate::middleware::{AuthUser, ClientInfo};
+use crate::models::{
+    AcceptInviteRequest, AuthStatus, LoginRequest, LoginResponse, RefreshTokenRequest,
+    RefreshTokenResponse, RegisterRequest, SudoRequest, SudoResponse,
+};
 use crate::response::{success, ApiResponse, MessageResponse};
-use crate::services::AuthService;
+use crate::services::{AuthService, BlocklistService};
 
 /// Login endpoint.
 pub async fn login(
     State(auth_service): State<AuthService>,
+    client: ClientInfo,
     Json(request): Json<LoginRequest>,
 ) -> Result<Json<ApiResponse<LoginResponse>>, AppError> {
     let response = auth_service
-        .login(&request.email, &request.password)
+        .login(&request.email, &request.password, &client)
         .await?;
     Ok(success(response))
 }
@@ -28,6 +33,34 @@ pub async fn refresh_token(
     Ok(success(response))
 }
 
+/// Accept an invitation by choosing a name and password.
+pub async fn accept_invite(
+    State(auth_service): State<AuthService>,
+    Json(request): Json<AcceptInviteRequest>,
+) -> Result<Json<ApiResponse<LoginResponse>>, AppError> {
+    let response = auth_service
+        .accept_invite(&request.token, &request.name, &request.password)
+        .await?;
+    Ok(success(response))
+}
+
+/// Public self-registration; the account awaits admin approval.
+///
+/// Email addresses on a blocked domain are refused.
+pub async fn register(
+    State(auth_service): State<AuthService>,
+    State(blocklist_service): State<BlocklistService>,
+    Json(request): Json<RegisterRequest>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    blocklist_service.check_email(&request.email).await?;
+    auth_service
+        .register(&request.email, &request.name, &request.password)
+        .await?;
+    Ok(success(MessageResponse::new(
+        "Registration received; an administrator will review your account",
+    )))
+}
+
 /// Logout endpoint - requires authentication.
 pub async fn logout(
     State(auth_service): State<AuthService>,
@@ -37,6 +70,38 @@ pub async fn logout(
     Ok(success(MessageResponse::new("Successfully logged out")))
 }
 
+/// Confirm the password to enter sudo mode for destructive actions.
+pub async fn sudo(
+    State(auth_service): State<AuthService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<SudoRequest>,
+) -> Result<Json<ApiResponse<SudoResponse>>, AppError> {
+    // Personal access tokens cannot enter sudo mode
+    let jti = auth_user
+        .session_id
+        .as_deref()
+        .ok_or(AppError::ReauthenticationRequired)?;
+    let response = auth_service
+        .sudo(auth_user.id, jti, &request.password)
+        .await?;
+    Ok(success(response))
+}
+
+/// JWKS endpoint publishing the public token verification keys.
+///
+/// Returned as a bare JWKS document (not wrapped in `ApiResponse`) so standard
+/// JWT libraries can consume it directly.
+pub async fn jwks(State(auth_service): State<AuthService>) -> Json<JwkSet> {
+    Json(auth_service.jwks())
+}
+
+/// Degraded-mode setting and fallback counters (admin only).
+pub async fn get_auth_status(
+    State(auth_service): State<AuthService>,
+) -> Json<ApiResponse<AuthStatus>> {
+    success(auth_service.status())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
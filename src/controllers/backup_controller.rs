@@ -0,0 +1,27 @@
+//! Backup controller for listing and triggering database backups.
+
+use axum::{extract::State, Json};
+
+use crate::error::AppError;
+use crate::models::Backup;
+use crate::response::{success, ApiResponse};
+use crate::services::BackupService;
+
+/// List database backups in storage, newest first (admin only).
+pub async fn list_backups(
+    State(backup_service): State<BackupService>,
+) -> Result<Json<ApiResponse<Vec<Backup>>>, AppError> {
+    let backups = backup_service.list().await?;
+    Ok(success(backups))
+}
+
+/// Take a database backup now and wait for it to be stored (admin only).
+pub async fn create_backup(
+    State(backup_service): State<BackupService>,
+) -> Result<Json<ApiResponse<Backup>>, AppError> {
+    // Run detached so a dropped connection does not abort the dump halfway
+    let backup = tokio::spawn(async move { backup_service.run().await })
+        .await
+        .map_err(|e| AppError::InternalError(format!("Backup task failed: {}", e)))??;
+    Ok(success(backup))
+}
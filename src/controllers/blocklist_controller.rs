@@ -0,0 +1,43 @@
+//! Blocklist controller for blocked IP addresses and email domains (admin only).
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{BlocklistEntry, BlocklistQuery, CreateBlocklistEntryRequest};
+use crate::response::{paginated, success, ApiResponse, MessageResponse};
+use crate::services::BlocklistService;
+
+/// List blocklist entries, optionally of one kind (admin only).
+pub async fn list_blocklist(
+    State(blocklist_service): State<BlocklistService>,
+    Query(query): Query<BlocklistQuery>,
+) -> Result<Json<ApiResponse<Vec<BlocklistEntry>>>, AppError> {
+    let (entries, meta) = blocklist_service.list(query).await?;
+    Ok(paginated(entries, meta.page, meta.per_page, meta.total))
+}
+
+/// Block an IP address, CIDR range or email domain (admin only).
+pub async fn create_blocklist_entry(
+    State(blocklist_service): State<BlocklistService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<CreateBlocklistEntryRequest>,
+) -> Result<Json<ApiResponse<BlocklistEntry>>, AppError> {
+    let entry = blocklist_service.create(auth_user.id, request).await?;
+    Ok(success(entry))
+}
+
+/// Remove a blocklist entry (admin only).
+pub async fn delete_blocklist_entry(
+    State(blocklist_service): State<BlocklistService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    blocklist_service.delete(id).await?;
+    Ok(success(MessageResponse::new(
+        "Blocklist entry deleted successfully",
+    )))
+}
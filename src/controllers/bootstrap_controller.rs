@@ -0,0 +1,19 @@
+//! Bootstrap controller serving the frontend's first-paint data in one request.
+
+use axum::{extract::State, Extension, Json};
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{Bootstrap, Site};
+use crate::response::{success, ApiResponse};
+use crate::services::BootstrapService;
+
+/// Get the site, menus, categories, feature flags and current user.
+pub async fn get_bootstrap(
+    State(bootstrap_service): State<BootstrapService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<Option<AuthUser>>,
+) -> Result<Json<ApiResponse<Bootstrap>>, AppError> {
+    let bootstrap = bootstrap_service.load(site, auth_user.as_ref()).await?;
+    Ok(success(bootstrap))
+}
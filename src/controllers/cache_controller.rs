@@ -0,0 +1,16 @@
+//! Cache controller for warming the Redis caches.
+
+use axum::{extract::State, Json};
+
+use crate::error::AppError;
+use crate::models::CacheWarmReport;
+use crate::response::{success, ApiResponse};
+use crate::services::CacheWarmer;
+
+/// Recompute the cached trending lists and archives now (admin only).
+pub async fn warm_cache(
+    State(cache_warmer): State<CacheWarmer>,
+) -> Result<Json<ApiResponse<CacheWarmReport>>, AppError> {
+    let report = cache_warmer.warm().await?;
+    Ok(success(report))
+}
@@ -7,66 +7,91 @@ use axum::{
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::middleware::AuthUser;
-use crate::models::{Category, CategoryWithCount, CreateCategoryRequest, UpdateCategoryRequest};
+use crate::models::{
+    Category, CategoryMergeResponse, CategoryWithCount, CreateCategoryRequest,
+    MergeCategoryRequest, Site, UpdateCategoryRequest,
+};
 use crate::response::{success, ApiResponse, MessageResponse};
 use crate::services::CategoryService;
 
 /// List all categories.
 pub async fn list_categories(
     State(category_service): State<CategoryService>,
+    Extension(site): Extension<Site>,
 ) -> Result<Json<ApiResponse<Vec<CategoryWithCount>>>, AppError> {
-    let categories = category_service.list().await?;
+    let categories = category_service.list(site.id).await?;
     Ok(success(categories))
 }
 
 /// Get a single category by ID.
 pub async fn get_category(
     State(category_service): State<CategoryService>,
+    Extension(site): Extension<Site>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<Category>>, AppError> {
-    let category = category_service.get_by_id(id).await?;
+    let category = category_service.get_by_id(site.id, id).await?;
     Ok(success(category))
 }
 
-/// Create a new category (admin only).
+/// Create a new category (requires `categories:create`).
 pub async fn create_category(
     State(category_service): State<CategoryService>,
-    Extension(auth_user): Extension<AuthUser>,
+    Extension(site): Extension<Site>,
     Json(request): Json<CreateCategoryRequest>,
 ) -> Result<Json<ApiResponse<Category>>, AppError> {
-    if !auth_user.can_create("categories") {
-        return Err(AppError::Forbidden("Cannot create categories".to_string()));
-    }
-    let category = category_service.create(request).await?;
+    let category = category_service.create(site.id, request).await?;
     Ok(success(category))
 }
 
-/// Update a category (admin only).
+/// Update a category (requires `categories:update`).
 pub async fn update_category(
     State(category_service): State<CategoryService>,
-    Extension(auth_user): Extension<AuthUser>,
+    Extension(site): Extension<Site>,
     Path(id): Path<Uuid>,
     Json(request): Json<UpdateCategoryRequest>,
 ) -> Result<Json<ApiResponse<Category>>, AppError> {
-    if !auth_user.can_update("categories") {
-        return Err(AppError::Forbidden("Cannot update categories".to_string()));
-    }
-    let category = category_service.update(id, request).await?;
+    let category = category_service.update(site.id, id, request).await?;
     Ok(success(category))
 }
 
-/// Delete a category (admin only).
+/// Delete a category (requires `categories:delete`).
 pub async fn delete_category(
     State(category_service): State<CategoryService>,
-    Extension(auth_user): Extension<AuthUser>,
+    Extension(site): Extension<Site>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
-    if !auth_user.can_delete("categories") {
-        return Err(AppError::Forbidden("Cannot delete categories".to_string()));
-    }
-    category_service.delete(id).await?;
+    category_service.delete(site.id, id).await?;
     Ok(success(MessageResponse::new(
         "Category deleted successfully",
     )))
 }
+
+/// Merge a category into another, moving its posts (requires `categories:delete`).
+pub async fn merge_category(
+    State(category_service): State<CategoryService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<MergeCategoryRequest>,
+) -> Result<Json<ApiResponse<CategoryMergeResponse>>, AppError> {
+    let merged = category_service.merge(site.id, id, request).await?;
+    Ok(success(merged))
+}
+
+/// List soft-deleted categories (requires `categories:delete`).
+pub async fn list_deleted_categories(
+    State(category_service): State<CategoryService>,
+    Extension(site): Extension<Site>,
+) -> Result<Json<ApiResponse<Vec<Category>>>, AppError> {
+    let categories = category_service.list_deleted(site.id).await?;
+    Ok(success(categories))
+}
+
+/// Restore a soft-deleted category with its posts (requires `categories:delete`).
+pub async fn restore_category(
+    State(category_service): State<CategoryService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Category>>, AppError> {
+    let category = category_service.restore(site.id, id).await?;
+    Ok(success(category))
+}
@@ -0,0 +1,98 @@
+//! Changelog controller for "what's new" entries and their feed.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::{last_modified_header, not_modified_since, AuthUser};
+use crate::models::{
+    ChangelogEntry, ChangelogQuery, CreateChangelogEntryRequest, Site, UpdateChangelogEntryRequest,
+};
+use crate::response::{paginated, success, ApiResponse, MessageResponse};
+use crate::services::ChangelogService;
+
+/// List published changelog entries, newest first.
+pub async fn list_changelog(
+    State(changelog_service): State<ChangelogService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<ChangelogQuery>,
+) -> Result<Json<ApiResponse<Vec<ChangelogEntry>>>, AppError> {
+    let (entries, meta) = changelog_service.list_published(site.id, query).await?;
+    Ok(paginated(entries, meta.page, meta.per_page, meta.total))
+}
+
+/// Atom feed of the latest published changelog entries.
+///
+/// Answers `304 Not Modified` when nothing changed since `If-Modified-Since`.
+pub async fn changelog_feed(
+    State(changelog_service): State<ChangelogService>,
+    Extension(site): Extension<Site>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let last_modified = changelog_service.feed_modified(&site).await?;
+    let mut response = if not_modified_since(&headers, last_modified) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let feed = changelog_service.feed(&site).await?;
+        (
+            [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+            feed,
+        )
+            .into_response()
+    };
+    if let Some(value) = last_modified_header(last_modified) {
+        response.headers_mut().insert(header::LAST_MODIFIED, value);
+    }
+    Ok(response)
+}
+
+/// List all changelog entries, including drafts (admin only).
+pub async fn list_changelog_entries(
+    State(changelog_service): State<ChangelogService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<ChangelogQuery>,
+) -> Result<Json<ApiResponse<Vec<ChangelogEntry>>>, AppError> {
+    let (entries, meta) = changelog_service.list_all(site.id, query).await?;
+    Ok(paginated(entries, meta.page, meta.per_page, meta.total))
+}
+
+/// Create a changelog entry (admin only).
+pub async fn create_changelog_entry(
+    State(changelog_service): State<ChangelogService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<CreateChangelogEntryRequest>,
+) -> Result<Json<ApiResponse<ChangelogEntry>>, AppError> {
+    let entry = changelog_service
+        .create(site.id, auth_user.id, request)
+        .await?;
+    Ok(success(entry))
+}
+
+/// Update a changelog entry (admin only).
+pub async fn update_changelog_entry(
+    State(changelog_service): State<ChangelogService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateChangelogEntryRequest>,
+) -> Result<Json<ApiResponse<ChangelogEntry>>, AppError> {
+    let entry = changelog_service.update(site.id, id, request).await?;
+    Ok(success(entry))
+}
+
+/// Delete a changelog entry (admin only).
+pub async fn delete_changelog_entry(
+    State(changelog_service): State<ChangelogService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    changelog_service.delete(site.id, id).await?;
+    Ok(success(MessageResponse::new(
+        "Changelog entry deleted successfully",
+    )))
+}
@@ -0,0 +1,23 @@
+//! Runtime configuration controller (admin only).
+
+use axum::{extract::State, Json};
+
+use crate::config::RuntimeConfig;
+use crate::error::AppError;
+use crate::response::{success, ApiResponse};
+use crate::runtime::RuntimeSettings;
+
+/// Get the runtime settings currently in effect.
+pub async fn get_runtime_config(
+    State(runtime): State<RuntimeSettings>,
+) -> Json<ApiResponse<RuntimeConfig>> {
+    success(runtime.current().as_ref().clone())
+}
+
+/// Reload runtime settings from the configuration sources.
+pub async fn reload_runtime_config(
+    State(runtime): State<RuntimeSettings>,
+) -> Result<Json<ApiResponse<RuntimeConfig>>, AppError> {
+    let applied = runtime.reload()?;
+    Ok(success(applied.as_ref().clone()))
+}
@@ -0,0 +1,23 @@
+//! Diagnostics controller for performance triage.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::models::{SlowQueryListQuery, DEFAULT_SLOW_QUERY_LIMIT};
+use crate::pkg::slow_queries::{SlowQuery, SLOW_QUERY_LOG_CAPACITY};
+use crate::pkg::SlowQueryLog;
+use crate::response::{success, ApiResponse};
+
+/// List the slowest recently logged queries (admin only).
+pub async fn list_slow_queries(
+    State(slow_queries): State<SlowQueryLog>,
+    Query(query): Query<SlowQueryListQuery>,
+) -> Json<ApiResponse<Vec<SlowQuery>>> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SLOW_QUERY_LIMIT)
+        .min(SLOW_QUERY_LOG_CAPACITY);
+    success(slow_queries.slowest(limit))
+}
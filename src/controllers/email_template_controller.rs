@@ -0,0 +1,66 @@
+//! Email template controller for customizing transactional emails.
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{
+    EmailTemplateResponse, PreviewEmailTemplateRequest, UpdateEmailTemplateRequest,
+};
+use crate::pkg::email::RenderedEmail;
+use crate::response::{success, ApiResponse, MessageResponse};
+use crate::services::EmailService;
+
+/// List email templates with their defaults and overrides (admin only).
+pub async fn list_email_templates(
+    State(email_service): State<EmailService>,
+) -> Result<Json<ApiResponse<Vec<EmailTemplateResponse>>>, AppError> {
+    let templates = email_service.list_templates().await?;
+    Ok(success(templates))
+}
+
+/// Get an email template (admin only).
+pub async fn get_email_template(
+    State(email_service): State<EmailService>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<EmailTemplateResponse>>, AppError> {
+    let template = email_service.get_template(&name).await?;
+    Ok(success(template))
+}
+
+/// Override an email template's subject and body (admin only).
+pub async fn update_email_template(
+    State(email_service): State<EmailService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(name): Path<String>,
+    Json(request): Json<UpdateEmailTemplateRequest>,
+) -> Result<Json<ApiResponse<EmailTemplateResponse>>, AppError> {
+    let template = email_service
+        .set_override(&name, request, auth_user.id)
+        .await?;
+    Ok(success(template))
+}
+
+/// Remove an email template's override, restoring the default (admin only).
+pub async fn reset_email_template(
+    State(email_service): State<EmailService>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    email_service.delete_override(&name).await?;
+    Ok(success(MessageResponse::new(
+        "Email template reset to the default",
+    )))
+}
+
+/// Render an email template, or an unsaved draft of it (admin only).
+pub async fn preview_email_template(
+    State(email_service): State<EmailService>,
+    Path(name): Path<String>,
+    Json(request): Json<PreviewEmailTemplateRequest>,
+) -> Result<Json<ApiResponse<RenderedEmail>>, AppError> {
+    let email = email_service.preview(&name, request).await?;
+    Ok(success(email))
+}
@@ -1,14 +1,65 @@
 //! Health check controller.
 
-use axum::Json;
+use std::time::Duration;
 
-use crate::response::{ApiResponse, HealthResponse};
+use axum::{extract::State, Json};
+use redis::aio::ConnectionManager;
+use sqlx::PgPool;
+
+use crate::error::AppError;
+use crate::models::StatusResponse;
+use crate::response::{ApiResponse, HealthResponse, VersionResponse};
+use crate::services::StatusMonitor;
+
+/// How long each dependency may take to answer a readiness probe.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// Health check endpoint.
 pub async fn health_check() -> Json<ApiResponse<HealthResponse>> {
     Json(ApiResponse::success(HealthResponse::default()))
 }
 
+/// Readiness probe: fails with `503` while Postgres or Redis is unreachable.
+///
+/// Before startup has connected to them, the startup router answers this
+/// path with `503` as well.
+pub async fn readiness_check(
+    State(pool): State<PgPool>,
+    State(mut redis): State<ConnectionManager>,
+) -> Result<Json<ApiResponse<HealthResponse>>, AppError> {
+    let database = tokio::time::timeout(READINESS_TIMEOUT, sqlx::query("SELECT 1").execute(&pool));
+    if !matches!(database.await, Ok(Ok(_))) {
+        tracing::warn!("Readiness probe: database is unreachable");
+        return Err(AppError::ServiceUnavailable(
+            "Database is unreachable".to_string(),
+        ));
+    }
+
+    let ping = redis::cmd("PING");
+    let cache = tokio::time::timeout(READINESS_TIMEOUT, ping.query_async::<String>(&mut redis));
+    if !matches!(cache.await, Ok(Ok(_))) {
+        tracing::warn!("Readiness probe: Redis is unreachable");
+        return Err(AppError::ServiceUnavailable(
+            "Redis is unreachable".to_string(),
+        ));
+    }
+
+    Ok(Json(ApiResponse::success(HealthResponse::ready())))
+}
+
+/// Version, commit and build time of the running binary.
+pub async fn version() -> Json<ApiResponse<VersionResponse>> {
+    Json(ApiResponse::success(VersionResponse::current()))
+}
+
+/// Public status page data: current state of each dependency and uptime
+/// over the recorded self-checks.
+pub async fn status(
+    State(status_monitor): State<StatusMonitor>,
+) -> Json<ApiResponse<StatusResponse>> {
+    Json(ApiResponse::success(status_monitor.status()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
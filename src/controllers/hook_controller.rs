@@ -0,0 +1,121 @@
+//! Hook controller receiving webhooks from third-party services and replaying them.
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    Json,
+};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{HookDelivery, HookDeliveryQuery, HookIntegration, HookReceipt};
+use crate::response::{paginated, success, ApiResponse};
+use crate::services::HookService;
+
+/// Headers left out of stored deliveries.
+const REDACTED_HEADERS: [header::HeaderName; 3] = [
+    header::AUTHORIZATION,
+    header::COOKIE,
+    header::PROXY_AUTHORIZATION,
+];
+
+/// Store and handle a signed delivery from an integration (`github`, `stripe`, `newsletter`).
+pub async fn receive_hook(
+    State(hook_service): State<HookService>,
+    Path(integration): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<ApiResponse<HookReceipt>>, AppError> {
+    let integration = integration
+        .parse::<HookIntegration>()
+        .map_err(|_| AppError::NotFound("Integration not found".to_string()))?;
+    receive(&hook_service, integration, &headers, &body).await
+}
+
+/// Store and handle a GitHub delivery sent to the Git sync webhook URL.
+///
+/// Kept for repositories set up before `/api/hooks/github`; both URLs share
+/// one handler, so a delivery is only handled once.
+pub async fn receive_github_hook(
+    State(hook_service): State<HookService>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<ApiResponse<HookReceipt>>, AppError> {
+    receive(&hook_service, HookIntegration::Github, &headers, &body).await
+}
+
+/// Verify a delivery's signature, then store and handle it.
+async fn receive(
+    hook_service: &HookService,
+    integration: HookIntegration,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<Json<ApiResponse<HookReceipt>>, AppError> {
+    if !hook_service.is_enabled(integration) {
+        return Err(AppError::NotFound("Integration not found".to_string()));
+    }
+    let signature = header_value(headers, Some(integration.signature_header())).unwrap_or_default();
+    if !hook_service.verify(integration, body, signature) {
+        return Err(AppError::Unauthorized);
+    }
+
+    let receipt = hook_service
+        .receive(
+            integration,
+            header_value(headers, integration.delivery_header()),
+            header_value(headers, integration.event_header()),
+            recorded_headers(headers),
+            body,
+        )
+        .await?;
+    Ok(success(receipt))
+}
+
+/// List received webhook deliveries (admin only).
+pub async fn list_hook_deliveries(
+    State(hook_service): State<HookService>,
+    Query(query): Query<HookDeliveryQuery>,
+) -> Result<Json<ApiResponse<Vec<HookDelivery>>>, AppError> {
+    let (deliveries, meta) = hook_service.list(query).await?;
+    Ok(paginated(deliveries, meta.page, meta.per_page, meta.total))
+}
+
+/// Get a webhook delivery with its headers and payload (admin only).
+pub async fn get_hook_delivery(
+    State(hook_service): State<HookService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<HookDelivery>>, AppError> {
+    let delivery = hook_service.get(id).await?;
+    Ok(success(delivery))
+}
+
+/// Hand a stored webhook delivery to its handler again (admin only).
+pub async fn replay_hook_delivery(
+    State(hook_service): State<HookService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<HookDelivery>>, AppError> {
+    let delivery = hook_service.replay(id).await?;
+    Ok(success(delivery))
+}
+
+/// Value of the header `name`, if there is one and it is text.
+fn header_value<'a>(headers: &'a HeaderMap, name: Option<&str>) -> Option<&'a str> {
+    headers.get(name?).and_then(|value| value.to_str().ok())
+}
+
+/// Request headers as a JSON object, without credentials.
+fn recorded_headers(headers: &HeaderMap) -> Value {
+    let mut recorded = Map::new();
+    for (name, value) in headers {
+        if REDACTED_HEADERS.contains(name) {
+            continue;
+        }
+        recorded.insert(
+            name.as_str().to_string(),
+            Value::String(String::from_utf8_lossy(value.as_bytes()).into_owned()),
+        );
+    }
+    Value::Object(recorded)
+}
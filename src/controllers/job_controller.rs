@@ -0,0 +1,88 @@
+//! Job controller for inspecting and retrying dead-lettered background jobs
+//! and the outgoing webhook log.
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    FailedJob, FailedJobQuery, PurgeFailedJobsQuery, PurgeFailedJobsResponse, RetryResult,
+    WebhookAttempt, WebhookAttemptQuery,
+};
+use crate::response::{paginated, success, ApiResponse, BulkResponse, MessageResponse};
+use crate::services::JobService;
+
+/// List failed jobs with their payload and last error (admin only).
+pub async fn list_failed_jobs(
+    State(job_service): State<JobService>,
+    Query(query): Query<FailedJobQuery>,
+) -> Result<Json<ApiResponse<Vec<FailedJob>>>, AppError> {
+    let (jobs, meta) = job_service.list_failed(query).await?;
+    Ok(paginated(jobs, meta.page, meta.per_page, meta.total))
+}
+
+/// Run a failed job again (admin only).
+pub async fn retry_failed_job(
+    State(job_service): State<JobService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<RetryResult>>, AppError> {
+    let result = job_service.retry(id).await?;
+    Ok(success(result))
+}
+
+/// Run every failed job again (admin only).
+pub async fn retry_failed_jobs(
+    State(job_service): State<JobService>,
+) -> Result<BulkResponse<RetryResult>, AppError> {
+    job_service.retry_all().await
+}
+
+/// Delete a failed job without running it (admin only).
+pub async fn delete_failed_job(
+    State(job_service): State<JobService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    job_service.delete_failed(id).await?;
+    Ok(success(MessageResponse::new(
+        "Failed job deleted successfully",
+    )))
+}
+
+/// Delete failed jobs, optionally only those older than `older_than_days` (admin only).
+pub async fn purge_failed_jobs(
+    State(job_service): State<JobService>,
+    Query(query): Query<PurgeFailedJobsQuery>,
+) -> Result<Json<ApiResponse<PurgeFailedJobsResponse>>, AppError> {
+    let purged = job_service.purge_failed(query).await?;
+    Ok(success(PurgeFailedJobsResponse { purged }))
+}
+
+/// List logged webhook attempts with request, response status and latency (admin only).
+pub async fn list_webhook_attempts(
+    State(job_service): State<JobService>,
+    Query(query): Query<WebhookAttemptQuery>,
+) -> Result<Json<ApiResponse<Vec<WebhookAttempt>>>, AppError> {
+    let (attempts, meta) = job_service.list_webhook_attempts(query).await?;
+    Ok(paginated(attempts, meta.page, meta.per_page, meta.total))
+}
+
+/// Get a logged webhook attempt (admin only).
+pub async fn get_webhook_attempt(
+    State(job_service): State<JobService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<WebhookAttempt>>, AppError> {
+    let attempt = job_service.get_webhook_attempt(id).await?;
+    Ok(success(attempt))
+}
+
+/// Send a logged webhook delivery again (admin only).
+pub async fn redeliver_webhook_attempt(
+    State(job_service): State<JobService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<RetryResult>>, AppError> {
+    let result = job_service.redeliver_webhook(id).await?;
+    Ok(success(result))
+}
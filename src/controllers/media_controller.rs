@@ -0,0 +1,184 @@
+//! Media controller for the media library.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Multipart, Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue},
+    response::Response,
+    Extension, Json,
+};
+use tower::ServiceExt;
+use tower_http::services::ServeFile;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{
+    CreateUploadRequest, MediaQuery, MediaResponse, MediaUsage, UpdateMediaRequest, UploadProgress,
+    UploadSession,
+};
+use crate::response::{paginated, success, ApiResponse, MessageResponse};
+use crate::services::MediaService;
+
+/// List media, newest first, filtered by `type` and `usage`.
+pub async fn list_media(
+    State(media_service): State<MediaService>,
+    Query(query): Query<MediaQuery>,
+) -> Result<Json<ApiResponse<Vec<MediaResponse>>>, AppError> {
+    let (media, meta) = media_service.list(query).await?;
+    let media = media.into_iter().map(MediaResponse::from).collect();
+    Ok(paginated(media, meta.page, meta.per_page, meta.total))
+}
+
+/// Get a media item by ID.
+pub async fn get_media(
+    State(media_service): State<MediaService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MediaResponse>>, AppError> {
+    let media = media_service.get_by_id(id).await?;
+    Ok(success(media.into()))
+}
+
+/// Download a media item's file as an attachment.
+///
+/// Only requests for the start of the file count as a download, so resumed
+/// and seeking range requests are not counted again.
+pub async fn download_media(
+    State(media_service): State<MediaService>,
+    Path(id): Path<Uuid>,
+    request: Request,
+) -> Result<Response, AppError> {
+    let from_start = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(true, |range| range.trim().starts_with("bytes=0-"));
+    let (media, path) = if from_start {
+        media_service.record_download(id).await?
+    } else {
+        media_service.file(id).await?
+    };
+
+    let response = ServeFile::new(path)
+        .oneshot(request)
+        .await
+        .map_err(|e| AppError::InternalError(e.to_string()))?;
+    let mut response = response.map(Body::new);
+    if response.status().is_success() {
+        if let Ok(value) = HeaderValue::from_str(&media.content_disposition()) {
+            response
+                .headers_mut()
+                .insert(header::CONTENT_DISPOSITION, value);
+        }
+    }
+    Ok(response)
+}
+
+/// Upload an image or another library file (multipart field `file`).
+pub async fn upload_media(
+    State(media_service): State<MediaService>,
+    Extension(auth_user): Extension<AuthUser>,
+    mut multipart: Multipart,
+) -> Result<Json<ApiResponse<MediaResponse>>, AppError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::ValidationError(e.to_string()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let filename = field.file_name().unwrap_or("upload").to_string();
+        let content_type = field.content_type().unwrap_or_default().to_string();
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let media = media_service
+            .store_file(auth_user.id, &filename, &content_type, &bytes)
+            .await?;
+        return Ok(success(media.into()));
+    }
+
+    Err(AppError::ValidationError(
+        "Multipart field 'file' is required".to_string(),
+    ))
+}
+
+/// Start a chunked upload for a large image, PDF or video.
+pub async fn start_upload(
+    State(media_service): State<MediaService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<CreateUploadRequest>,
+) -> Result<Json<ApiResponse<UploadSession>>, AppError> {
+    let session = media_service.start_upload(auth_user.id, request).await?;
+    Ok(success(session))
+}
+
+/// Get a chunked upload's progress, e.g. the offset to resume from.
+pub async fn get_upload(
+    State(media_service): State<MediaService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<UploadSession>>, AppError> {
+    let session = media_service.get_upload(&id, auth_user.id).await?;
+    Ok(success(session))
+}
+
+/// Append the request body to a chunked upload at the `Upload-Offset` header.
+pub async fn upload_chunk(
+    State(media_service): State<MediaService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<ApiResponse<UploadProgress>>, AppError> {
+    let offset = headers
+        .get("upload-offset")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .ok_or_else(|| AppError::ValidationError("Upload-Offset header is required".to_string()))?;
+    let progress = media_service
+        .append_chunk(&id, auth_user.id, offset, &body)
+        .await?;
+    Ok(success(progress))
+}
+
+/// Abandon a chunked upload.
+pub async fn cancel_upload(
+    State(media_service): State<MediaService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    media_service.cancel_upload(&id, auth_user.id).await?;
+    Ok(success(MessageResponse::new("Upload cancelled")))
+}
+
+/// Rename a media item or edit its alt text.
+pub async fn update_media(
+    State(media_service): State<MediaService>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateMediaRequest>,
+) -> Result<Json<ApiResponse<MediaResponse>>, AppError> {
+    let media = media_service.update(id, request).await?;
+    Ok(success(media.into()))
+}
+
+/// Posts and avatars using a media item.
+pub async fn get_media_usage(
+    State(media_service): State<MediaService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MediaUsage>>, AppError> {
+    let usage = media_service.usage(id).await?;
+    Ok(success(usage))
+}
+
+/// Delete a media item unless a published post still uses it.
+pub async fn delete_media(
+    State(media_service): State<MediaService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    media_service.delete(id).await?;
+    Ok(success(MessageResponse::new("Media deleted successfully")))
+}
@@ -1,19 +1,75 @@
 //! Controller modules for HTTP handlers.
 
+pub mod access_token_controller;
+pub mod activity_controller;
 pub mod auth_controller;
+pub mod backup_controller;
+pub mod blocklist_controller;
+pub mod bootstrap_controller;
+pub mod cache_controller;
 pub mod category_controller;
+pub mod changelog_controller;
+pub mod config_controller;
+pub mod diagnostics_controller;
+pub mod email_template_controller;
 pub mod health_controller;
+pub mod hook_controller;
+pub mod job_controller;
+pub mod media_controller;
+pub mod notification_controller;
 pub mod permission_controller;
+pub mod poll_controller;
 pub mod post_controller;
+pub mod post_lock_controller;
+pub mod post_note_controller;
+pub mod post_template_controller;
+pub mod preview_controller;
+pub mod profile_controller;
+pub mod quota_controller;
+pub mod revision_controller;
 pub mod role_controller;
+pub mod route_controller;
+pub mod search_controller;
+pub mod site_controller;
+pub mod sync_controller;
 pub mod tag_controller;
+pub mod taxonomy_controller;
+pub mod title_test_controller;
 pub mod user_controller;
 
+pub use access_token_controller::*;
+pub use activity_controller::*;
 pub use auth_controller::*;
+pub use backup_controller::*;
+pub use blocklist_controller::*;
+pub use bootstrap_controller::*;
+pub use cache_controller::*;
 pub use category_controller::*;
+pub use changelog_controller::*;
+pub use config_controller::*;
+pub use diagnostics_controller::*;
+pub use email_template_controller::*;
 pub use health_controller::*;
+pub use hook_controller::*;
+pub use job_controller::*;
+pub use media_controller::*;
+pub use notification_controller::*;
 pub use permission_controller::*;
+pub use poll_controller::*;
 pub use post_controller::*;
+pub use post_lock_controller::*;
+pub use post_note_controller::*;
+pub use post_template_controller::*;
+pub use preview_controller::*;
+pub use profile_controller::*;
+pub use quota_controller::*;
+pub use revision_controller::*;
 pub use role_controller::*;
+pub use route_controller::*;
+pub use search_controller::*;
+pub use site_controller::*;
+pub use sync_controller::*;
 pub use tag_controller::*;
+pub use taxonomy_controller::*;
+pub use title_test_controller::*;
 pub use user_controller::*;
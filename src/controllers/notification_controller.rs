@@ -0,0 +1,30 @@
+//! Notification controller for the current user's email preferences.
+
+use axum::{extract::State, Extension, Json};
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{NotificationPreferences, UpdateNotificationPreferencesRequest};
+use crate::response::{success, ApiResponse};
+use crate::services::NotificationService;
+
+/// Get which emails the current user receives.
+pub async fn get_my_notifications(
+    State(notification_service): State<NotificationService>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<ApiResponse<NotificationPreferences>>, AppError> {
+    let preferences = notification_service.preferences(auth_user.id).await?;
+    Ok(success(preferences))
+}
+
+/// Change which emails the current user receives.
+pub async fn update_my_notifications(
+    State(notification_service): State<NotificationService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<UpdateNotificationPreferencesRequest>,
+) -> Result<Json<ApiResponse<NotificationPreferences>>, AppError> {
+    let preferences = notification_service
+        .update_preferences(auth_user.id, request)
+        .await?;
+    Ok(success(preferences))
+}
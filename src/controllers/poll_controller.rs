@@ -0,0 +1,86 @@
+//! Poll controller for poll management, voting and live results.
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::{AuthUser, ClientIp};
+use crate::models::{
+    CreatePollRequest, PollQuery, PollResponse, PollVoter, Site, UpdatePollRequest, VoteRequest,
+};
+use crate::response::{paginated, success, ApiResponse, MessageResponse};
+use crate::services::PollService;
+
+/// List polls with their results (requires `polls:update`).
+pub async fn list_polls(
+    State(poll_service): State<PollService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<PollQuery>,
+) -> Result<Json<ApiResponse<Vec<PollResponse>>>, AppError> {
+    let (polls, meta) = poll_service.list(site.id, query).await?;
+    Ok(paginated(polls, meta.page, meta.per_page, meta.total))
+}
+
+/// Get a poll with its current results.
+pub async fn get_poll(
+    State(poll_service): State<PollService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<PollResponse>>, AppError> {
+    let poll = poll_service.get(site.id, id).await?;
+    Ok(success(poll))
+}
+
+/// Vote in a poll, once per user or, for anonymous readers, per IP address.
+pub async fn vote_poll(
+    State(poll_service): State<PollService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<Option<AuthUser>>,
+    ClientIp(ip): ClientIp,
+    Path(id): Path<Uuid>,
+    Json(request): Json<VoteRequest>,
+) -> Result<Json<ApiResponse<PollResponse>>, AppError> {
+    let voter = match auth_user {
+        Some(user) => PollVoter::User(user.id),
+        None => PollVoter::Ip(ip),
+    };
+    let poll = poll_service
+        .vote(site.id, id, request.option_id, voter)
+        .await?;
+    Ok(success(poll))
+}
+
+/// Create a new poll (requires `polls:create`).
+pub async fn create_poll(
+    State(poll_service): State<PollService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<CreatePollRequest>,
+) -> Result<Json<ApiResponse<PollResponse>>, AppError> {
+    let poll = poll_service.create(site.id, auth_user.id, request).await?;
+    Ok(success(poll))
+}
+
+/// Update a poll (requires `polls:update`).
+pub async fn update_poll(
+    State(poll_service): State<PollService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdatePollRequest>,
+) -> Result<Json<ApiResponse<PollResponse>>, AppError> {
+    let poll = poll_service.update(site.id, id, request).await?;
+    Ok(success(poll))
+}
+
+/// Delete a poll (requires `polls:delete`).
+pub async fn delete_poll(
+    State(poll_service): State<PollService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    poll_service.delete(site.id, id).await?;
+    Ok(success(MessageResponse::new("Poll deleted successfully")))
+}
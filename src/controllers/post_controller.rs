@@ -2,74 +2,334 @@
 
 use axum::{
     extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue},
+    response::{IntoResponse, Response},
     Extension, Json,
 };
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+use super::post_lock_controller::post_lock_id;
 use crate::error::AppError;
-use crate::middleware::AuthUser;
-use crate::models::{CreatePostRequest, PostListItem, PostQuery, PostResponse, UpdatePostRequest};
+use crate::middleware::{AuthUser, ClientInfo};
+use crate::models::{
+    ApprovePostRequest, BatchPostsRequest, CreatePostRequest, DailyViews, DailyViewsQuery,
+    LocationViews, PostArchive, PostListItem, PostQuery, PostResponse, PostReview, PostStatus,
+    PostViewer, RejectPostRequest, Site, TrendingPost, TrendingQuery, TrendingWindow,
+    UpdatePostRequest,
+};
 use crate::response::{paginated, success, ApiResponse, MessageResponse};
-use crate::services::PostService;
+use crate::services::{
+    ArchiveService, PostLockService, PostService, TitleTestService, TrendingService,
+};
+
+/// Trending posts returned when no `limit` is given.
+const DEFAULT_TRENDING_LIMIT: usize = 10;
+/// Largest `limit` accepted for trending posts.
+const MAX_TRENDING_LIMIT: usize = 50;
+/// Days of view counts returned when no `days` is given.
+const DEFAULT_DAILY_VIEWS_DAYS: i32 = 30;
+/// Most days of view counts returned at once.
+const MAX_DAILY_VIEWS_DAYS: i32 = 365;
 
 /// List posts (public - shows only published, admin - shows all).
+///
+/// Non-admin readers see the headline their title tests picked for them;
+/// such responses must not be shared between readers.
 pub async fn list_posts(
     State(post_service): State<PostService>,
+    State(title_test_service): State<TitleTestService>,
+    Extension(site): Extension<Site>,
     Extension(auth_user): Extension<Option<AuthUser>>,
     Query(query): Query<PostQuery>,
-) -> Result<Json<ApiResponse<Vec<PostListItem>>>, AppError> {
-    let is_admin = auth_user.map(|u| u.is_admin()).unwrap_or(false);
-    let (posts, meta) = post_service.list(query, is_admin).await?;
-    Ok(paginated(posts, meta.page, meta.per_page, meta.total))
+    client: ClientInfo,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let viewer = post_viewer(auth_user.as_ref());
+    let (mut posts, meta) = post_service.list(site.id, query, viewer).await?;
+    let tested = !viewer.is_admin()
+        && title_test_service
+            .serve_list(&mut posts, &client, referrer(&headers))
+            .await?;
+    let mut response = paginated(posts, meta.page, meta.per_page, meta.total).into_response();
+    if tested {
+        mark_private(&mut response);
+    }
+    Ok(response)
 }
 
-/// Get a single post by slug.
+/// Get a single post by slug with its JSON-LD, counting the view for non-admin readers.
+///
+/// Members-only posts need a signed-in reader; private posts are only shown to
+/// their author. Non-admin readers of a post under a title test see the
+/// headline picked for them.
+#[allow(clippy::too_many_arguments)]
 pub async fn get_post_by_slug(
     State(post_service): State<PostService>,
+    State(trending_service): State<TrendingService>,
+    State(title_test_service): State<TitleTestService>,
+    Extension(site): Extension<Site>,
     Extension(auth_user): Extension<Option<AuthUser>>,
     Path(slug): Path<String>,
-) -> Result<Json<ApiResponse<PostResponse>>, AppError> {
-    let is_admin = auth_user.map(|u| u.is_admin()).unwrap_or(false);
-    let post = post_service.get_by_slug(&slug, is_admin).await?;
-    Ok(success(post))
+    client: ClientInfo,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let viewer = post_viewer(auth_user.as_ref());
+    let mut post = post_service.get_by_slug(site.id, &slug, viewer).await?;
+    let mut tested = false;
+    if !viewer.is_admin() && post.status == PostStatus::Published {
+        let referrer = referrer(&headers);
+        trending_service.record_view(post.id, &client, referrer);
+        tested = title_test_service
+            .serve_post(&mut post, &client, referrer)
+            .await?;
+    }
+    let mut response = success(post_service.with_structured_data(&site, post)).into_response();
+    if tested {
+        mark_private(&mut response);
+    }
+    Ok(response)
+}
+
+/// Get up to 50 posts by ID and/or slug in one request, e.g. to fill
+/// several post cards. Posts the reader may not see are left out, and views
+/// are not counted.
+pub async fn get_posts_batch(
+    State(post_service): State<PostService>,
+    State(title_test_service): State<TitleTestService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<Option<AuthUser>>,
+    client: ClientInfo,
+    headers: HeaderMap,
+    Json(request): Json<BatchPostsRequest>,
+) -> Result<Response, AppError> {
+    let viewer = post_viewer(auth_user.as_ref());
+    let mut posts = post_service.get_batch(site.id, request, viewer).await?;
+    let tested = !viewer.is_admin()
+        && title_test_service
+            .serve_batch(&mut posts, &client, referrer(&headers))
+            .await?;
+    let mut response = success(posts).into_response();
+    if tested {
+        mark_private(&mut response);
+    }
+    Ok(response)
+}
+
+/// Most viewed published posts within a window (`1d`, `7d` or `30d`).
+pub async fn get_trending_posts(
+    State(trending_service): State<TrendingService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<TrendingQuery>,
+) -> Result<Json<ApiResponse<Vec<TrendingPost>>>, AppError> {
+    let window = match query.window.as_deref() {
+        Some(window) => window
+            .parse::<TrendingWindow>()
+            .map_err(AppError::ValidationError)?,
+        None => TrendingWindow::default(),
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_TRENDING_LIMIT)
+        .clamp(1, MAX_TRENDING_LIMIT);
+    let posts = trending_service.trending(site.id, window, limit).await?;
+    Ok(success(posts))
+}
+
+/// All of the site's public posts published in a year, grouped by month.
+pub async fn get_post_archive(
+    State(archive_service): State<ArchiveService>,
+    Extension(site): Extension<Site>,
+    Path(year): Path<i32>,
+) -> Result<Json<ApiResponse<PostArchive>>, AppError> {
+    let archive = archive_service.year(site.id, year).await?;
+    Ok(success(archive))
+}
+
+/// Human, bot and spam views of the site's posts per day (admin only).
+pub async fn get_daily_views(
+    State(trending_service): State<TrendingService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<DailyViewsQuery>,
+) -> Result<Json<ApiResponse<Vec<DailyViews>>>, AppError> {
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_DAILY_VIEWS_DAYS)
+        .clamp(1, MAX_DAILY_VIEWS_DAYS);
+    let views = trending_service.daily_views(site.id, days).await?;
+    Ok(success(views))
+}
+
+/// Human views of the site's posts by country and region (admin only).
+pub async fn get_view_locations(
+    State(trending_service): State<TrendingService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<DailyViewsQuery>,
+) -> Result<Json<ApiResponse<Vec<LocationViews>>>, AppError> {
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_DAILY_VIEWS_DAYS)
+        .clamp(1, MAX_DAILY_VIEWS_DAYS);
+    let locations = trending_service.view_locations(site.id, days).await?;
+    Ok(success(locations))
 }
 
-/// Create a new post (admin only).
+/// Create a new post (requires `posts:create`).
 pub async fn create_post(
     State(post_service): State<PostService>,
+    Extension(site): Extension<Site>,
     Extension(auth_user): Extension<AuthUser>,
     Json(request): Json<CreatePostRequest>,
 ) -> Result<Json<ApiResponse<PostResponse>>, AppError> {
-    if !auth_user.can_create("posts") {
-        return Err(AppError::Forbidden("Cannot create posts".to_string()));
-    }
-    let post = post_service.create(auth_user.id, request).await?;
+    let post = post_service.create(site.id, &auth_user, request).await?;
     Ok(success(post))
 }
 
-/// Update a post (admin only).
+/// Update a post (requires `posts:update_own` or `posts:update_any`).
+///
+/// Refused with 409 while another session holds the post's editing lock, and
+/// with 412 when the post changed after `If-Unmodified-Since`.
 pub async fn update_post(
     State(post_service): State<PostService>,
+    State(post_lock_service): State<PostLockService>,
+    Extension(site): Extension<Site>,
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
-    Json(request): Json<UpdatePostRequest>,
+    headers: HeaderMap,
+    Json(mut request): Json<UpdatePostRequest>,
 ) -> Result<Json<ApiResponse<PostResponse>>, AppError> {
-    if !auth_user.can_update("posts") {
-        return Err(AppError::Forbidden("Cannot update posts".to_string()));
+    post_lock_service
+        .check(id, &auth_user, post_lock_id(&headers))
+        .await?;
+    if request.unmodified_since.is_none() {
+        // Invalid dates are ignored, as HTTP requires
+        request.unmodified_since = headers
+            .get(header::IF_UNMODIFIED_SINCE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+            .map(|date| date.with_timezone(&Utc));
     }
-    let post = post_service.update(id, request).await?;
+    let post = post_service
+        .update(site.id, id, &auth_user, request)
+        .await?;
+    Ok(success(post))
+}
+
+/// Publish a draft (requires `posts:publish` and the right to update the post).
+pub async fn publish_post(
+    State(post_service): State<PostService>,
+    State(post_lock_service): State<PostLockService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<PostResponse>>, AppError> {
+    post_lock_service
+        .check(id, &auth_user, post_lock_id(&headers))
+        .await?;
+    let post = post_service.publish(site.id, id, &auth_user).await?;
     Ok(success(post))
 }
 
-/// Delete a post (admin only).
+/// Submit a draft for review (requires `posts:update_own` or `posts:update_any`).
+pub async fn submit_post(
+    State(post_service): State<PostService>,
+    State(post_lock_service): State<PostLockService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<PostResponse>>, AppError> {
+    post_lock_service
+        .check(id, &auth_user, post_lock_id(&headers))
+        .await?;
+    let post = post_service
+        .submit_for_review(site.id, id, &auth_user)
+        .await?;
+    Ok(success(post))
+}
+
+/// List posts waiting for review (requires `posts:review`).
+pub async fn get_review_queue(
+    State(post_service): State<PostService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<PostQuery>,
+) -> Result<Json<ApiResponse<Vec<PostListItem>>>, AppError> {
+    let (posts, meta) = post_service.review_queue(site.id, query).await?;
+    Ok(paginated(posts, meta.page, meta.per_page, meta.total))
+}
+
+/// Approve a post in review, publishing it (requires `posts:review`).
+pub async fn approve_post(
+    State(post_service): State<PostService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    request: Option<Json<ApprovePostRequest>>,
+) -> Result<Json<ApiResponse<PostResponse>>, AppError> {
+    let Json(request) = request.unwrap_or_default();
+    let post = post_service
+        .approve(site.id, id, &auth_user, request.note.as_deref())
+        .await?;
+    Ok(success(post))
+}
+
+/// Reject a post in review with a note for its author (requires `posts:review`).
+pub async fn reject_post(
+    State(post_service): State<PostService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<RejectPostRequest>,
+) -> Result<Json<ApiResponse<PostResponse>>, AppError> {
+    let post = post_service
+        .reject(site.id, id, &auth_user, &request.note)
+        .await?;
+    Ok(success(post))
+}
+
+/// A post's review history (requires `posts:review`, or the right to update the post).
+pub async fn get_post_reviews(
+    State(post_service): State<PostService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<PostReview>>>, AppError> {
+    let reviews = post_service.reviews(site.id, id, &auth_user).await?;
+    Ok(success(reviews))
+}
+
+/// Delete a post (requires `posts:delete_own` or `posts:delete_any`).
 pub async fn delete_post(
     State(post_service): State<PostService>,
+    Extension(site): Extension<Site>,
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
-    if !auth_user.can_delete("posts") {
-        return Err(AppError::Forbidden("Cannot delete posts".to_string()));
-    }
-    post_service.delete(id).await?;
+    post_service.delete(site.id, id, &auth_user).await?;
     Ok(success(MessageResponse::new("Post deleted successfully")))
 }
+
+/// The `Referer` header, if any.
+fn referrer(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::REFERER)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Keep a response personalized for one reader out of shared caches.
+fn mark_private(response: &mut Response) {
+    response.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-cache"),
+    );
+}
+
+/// Map the optional signed-in user to the posts they may read.
+fn post_viewer(auth_user: Option<&AuthUser>) -> PostViewer {
+    match auth_user {
+        Some(user) if user.is_admin() => PostViewer::Admin,
+        Some(user) => PostViewer::Member(user.id),
+        None => PostViewer::Anonymous,
+    }
+}
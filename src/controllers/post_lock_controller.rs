@@ -0,0 +1,66 @@
+//! Post lock controller for editing locks and their heartbeats.
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{PostLock, PostLockGrant, Site, POST_LOCK_HEADER};
+use crate::response::{success, ApiResponse, MessageResponse};
+use crate::services::PostLockService;
+
+/// Who is editing a post, if anyone (requires `posts:update_own` or `posts:update_any`).
+pub async fn get_post_lock(
+    State(post_lock_service): State<PostLockService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Option<PostLock>>>, AppError> {
+    let lock = post_lock_service.get(site.id, id, &auth_user).await?;
+    Ok(success(lock))
+}
+
+/// Take a post's editing lock, or renew the one held in the `Post-Lock` header.
+///
+/// Answers 409 with the current editor while another session holds the lock.
+pub async fn lock_post(
+    State(post_lock_service): State<PostLockService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<PostLockGrant>>, AppError> {
+    let grant = post_lock_service
+        .acquire(site.id, id, &auth_user, post_lock_id(&headers))
+        .await?;
+    Ok(success(grant))
+}
+
+/// Release the editing lock held in the `Post-Lock` header.
+pub async fn unlock_post(
+    State(post_lock_service): State<PostLockService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    let lock_id = post_lock_id(&headers)
+        .ok_or_else(|| AppError::ValidationError("Post-Lock header is required".to_string()))?;
+    post_lock_service
+        .release(site.id, id, &auth_user, lock_id)
+        .await?;
+    Ok(success(MessageResponse::new("Post unlocked")))
+}
+
+/// The `lock_id` sent in the `Post-Lock` header, if any.
+pub(crate) fn post_lock_id(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(POST_LOCK_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
@@ -0,0 +1,53 @@
+//! Post note controller for internal editorial notes.
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{CreatePostNoteRequest, PostNote, Site};
+use crate::response::{success, ApiResponse, MessageResponse};
+use crate::services::PostNoteService;
+
+/// Notes on a post (requires `posts:update_own` or `posts:update_any`).
+pub async fn list_post_notes(
+    State(post_note_service): State<PostNoteService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<PostNote>>>, AppError> {
+    let notes = post_note_service.list(site.id, id, &auth_user).await?;
+    Ok(success(notes))
+}
+
+/// Add a note to a post, emailing users it mentions
+/// (requires `posts:update_own` or `posts:update_any`).
+pub async fn create_post_note(
+    State(post_note_service): State<PostNoteService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<CreatePostNoteRequest>,
+) -> Result<Json<ApiResponse<PostNote>>, AppError> {
+    let note = post_note_service
+        .create(site.id, id, &auth_user, request)
+        .await?;
+    Ok(success(note))
+}
+
+/// Delete one of your notes, or any as an admin
+/// (requires `posts:update_own` or `posts:update_any`).
+pub async fn delete_post_note(
+    State(post_note_service): State<PostNoteService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((id, note_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    post_note_service
+        .delete(site.id, id, note_id, &auth_user)
+        .await?;
+    Ok(success(MessageResponse::new("Note deleted successfully")))
+}
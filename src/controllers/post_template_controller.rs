@@ -0,0 +1,76 @@
+//! Post template controller for recurring post formats.
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{
+    CreatePostTemplateRequest, PostFromTemplateRequest, PostResponse, PostTemplate, Site,
+    UpdatePostTemplateRequest,
+};
+use crate::response::{success, ApiResponse, MessageResponse};
+use crate::services::PostTemplateService;
+
+/// List post templates (requires `posts:create`).
+pub async fn list_post_templates(
+    State(post_template_service): State<PostTemplateService>,
+    Extension(site): Extension<Site>,
+) -> Result<Json<ApiResponse<Vec<PostTemplate>>>, AppError> {
+    let templates = post_template_service.list(site.id).await?;
+    Ok(success(templates))
+}
+
+/// Create a draft from a template (requires `posts:create`).
+pub async fn create_post_from_template(
+    State(post_template_service): State<PostTemplateService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    request: Option<Json<PostFromTemplateRequest>>,
+) -> Result<Json<ApiResponse<PostResponse>>, AppError> {
+    let Json(request) = request.unwrap_or_default();
+    let post = post_template_service
+        .create_post(site.id, id, &auth_user, request)
+        .await?;
+    Ok(success(post))
+}
+
+/// Create a post template (admin only).
+pub async fn create_post_template(
+    State(post_template_service): State<PostTemplateService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<CreatePostTemplateRequest>,
+) -> Result<Json<ApiResponse<PostTemplate>>, AppError> {
+    let template = post_template_service
+        .create(site.id, auth_user.id, request)
+        .await?;
+    Ok(success(template))
+}
+
+/// Update a post template (admin only).
+pub async fn update_post_template(
+    State(post_template_service): State<PostTemplateService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdatePostTemplateRequest>,
+) -> Result<Json<ApiResponse<PostTemplate>>, AppError> {
+    let template = post_template_service.update(site.id, id, request).await?;
+    Ok(success(template))
+}
+
+/// Delete a post template (admin only).
+pub async fn delete_post_template(
+    State(post_template_service): State<PostTemplateService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    post_template_service.delete(site.id, id).await?;
+    Ok(success(MessageResponse::new(
+        "Post template deleted successfully",
+    )))
+}
@@ -0,0 +1,42 @@
+//! Preview controller for sharing drafts with the frontend's preview route.
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{PreviewToken, Site};
+use crate::response::{success, ApiResponse};
+use crate::services::PreviewService;
+
+/// Mint a short-lived preview token for a post (requires `posts:update_own` or `posts:update_any`).
+pub async fn create_preview_token(
+    State(preview_service): State<PreviewService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<PreviewToken>>, AppError> {
+    let token = preview_service.mint(site.id, id, &auth_user).await?;
+    Ok(success(token))
+}
+
+/// Fetch the post behind a preview token, whatever its status (public).
+///
+/// Unpublished content must never end up in a shared cache.
+pub async fn get_preview(
+    State(preview_service): State<PreviewService>,
+    Path(token): Path<String>,
+) -> Result<Response, AppError> {
+    let preview = preview_service.exchange(&token).await?;
+    let mut response = success(preview).into_response();
+    response.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-store"),
+    );
+    Ok(response)
+}
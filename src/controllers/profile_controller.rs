@@ -0,0 +1,149 @@
+//! Profile controller for the authenticated user's own account.
+
+use axum::{
+    extract::{Multipart, State},
+    Extension, Json,
+};
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{
+    ChangePasswordRequest, ErasureReceipt, LoginEvent, MyPermissionsResponse, UpdateProfileRequest,
+    UserProfile, VerifyEmailRequest,
+};
+use crate::response::{success, ApiResponse, MessageResponse};
+use crate::services::{AccountService, AuthService, MediaService, ProfileService};
+
+/// Get the current user's profile.
+pub async fn get_me(
+    State(profile_service): State<ProfileService>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<ApiResponse<UserProfile>>, AppError> {
+    let profile = profile_service.get(auth_user.id).await?;
+    Ok(success(profile))
+}
+
+/// Update the current user's profile.
+pub async fn update_me(
+    State(profile_service): State<ProfileService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<UpdateProfileRequest>,
+) -> Result<Json<ApiResponse<UserProfile>>, AppError> {
+    let profile = profile_service.update(auth_user.id, request).await?;
+    Ok(success(profile))
+}
+
+/// Upload a new avatar image (multipart field `file`).
+pub async fn upload_my_avatar(
+    State(profile_service): State<ProfileService>,
+    State(media_service): State<MediaService>,
+    Extension(auth_user): Extension<AuthUser>,
+    mut multipart: Multipart,
+) -> Result<Json<ApiResponse<UserProfile>>, AppError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::ValidationError(e.to_string()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let filename = field.file_name().unwrap_or("avatar").to_string();
+        let content_type = field.content_type().unwrap_or_default().to_string();
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let media = media_service
+            .store_image(auth_user.id, &filename, &content_type, &bytes)
+            .await?;
+        let profile = profile_service.set_avatar(auth_user.id, &media).await?;
+        return Ok(success(profile));
+    }
+
+    Err(AppError::ValidationError(
+        "Multipart field 'file' is required".to_string(),
+    ))
+}
+
+/// Remove the current user's avatar (falls back to Gravatar).
+pub async fn delete_my_avatar(
+    State(profile_service): State<ProfileService>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<ApiResponse<UserProfile>>, AppError> {
+    let profile = profile_service.clear_avatar(auth_user.id).await?;
+    Ok(success(profile))
+}
+
+/// List the current user's effective permissions.
+pub async fn get_my_permissions(
+    Extension(auth_user): Extension<AuthUser>,
+) -> Json<ApiResponse<MyPermissionsResponse>> {
+    success(MyPermissionsResponse {
+        is_admin: auth_user.is_admin(),
+        role_slug: auth_user.role_slug,
+        permissions: auth_user.permissions,
+    })
+}
+
+/// Change the current user's password; all sessions are signed out.
+pub async fn change_my_password(
+    State(auth_service): State<AuthService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(request): Json<ChangePasswordRequest>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    auth_service
+        .change_password(
+            auth_user.id,
+            &request.current_password,
+            &request.new_password,
+        )
+        .await?;
+    Ok(success(MessageResponse::new(
+        "Password changed; please sign in again",
+    )))
+}
+
+/// Permanently erase the current user's account (requires sudo mode).
+///
+/// Authored posts stay published under the deleted-user placeholder.
+pub async fn delete_me(
+    State(account_service): State<AccountService>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<ApiResponse<ErasureReceipt>>, AppError> {
+    if auth_user.token_id.is_some() {
+        return Err(AppError::Forbidden(
+            "Accounts cannot be erased with an access token".to_string(),
+        ));
+    }
+    let receipt = account_service.erase(auth_user.id, auth_user.id).await?;
+    Ok(success(receipt))
+}
+
+/// List the current user's recent logins, newest first.
+pub async fn get_my_logins(
+    State(auth_service): State<AuthService>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<ApiResponse<Vec<LoginEvent>>>, AppError> {
+    let logins = auth_service.login_history(auth_user.id).await?;
+    Ok(success(logins))
+}
+
+/// Confirm a pending email change.
+pub async fn verify_email(
+    State(profile_service): State<ProfileService>,
+    Json(request): Json<VerifyEmailRequest>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    profile_service.verify_email(&request.token).await?;
+    Ok(success(MessageResponse::new("Email address updated")))
+}
+
+/// Cancel a pending email change from the link sent to the current address.
+pub async fn cancel_email_change(
+    State(profile_service): State<ProfileService>,
+    Json(request): Json<VerifyEmailRequest>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    profile_service.cancel_email_change(&request.token).await?;
+    Ok(success(MessageResponse::new("Email change cancelled")))
+}
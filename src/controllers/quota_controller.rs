@@ -0,0 +1,40 @@
+//! Quota controller for inspecting and resetting client request quotas.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::error::AppError;
+use crate::models::{QuotaClient, QuotaClientQuery, QuotaStatus};
+use crate::response::{success, ApiResponse};
+use crate::services::QuotaService;
+
+/// Show a client's usage in the current window (admin only).
+pub async fn get_quota(
+    State(quota_service): State<QuotaService>,
+    Query(query): Query<QuotaClientQuery>,
+) -> Result<Json<ApiResponse<QuotaStatus>>, AppError> {
+    let status = quota_service.status(quota_client(query)?).await?;
+    Ok(success(status))
+}
+
+/// Clear a client's usage in the current window (admin only).
+pub async fn reset_quota(
+    State(quota_service): State<QuotaService>,
+    Query(query): Query<QuotaClientQuery>,
+) -> Result<Json<ApiResponse<QuotaStatus>>, AppError> {
+    let status = quota_service.reset(quota_client(query)?).await?;
+    Ok(success(status))
+}
+
+/// Exactly one of `ip` or `user_id` selects the client.
+fn quota_client(query: QuotaClientQuery) -> Result<QuotaClient, AppError> {
+    match (query.ip, query.user_id) {
+        (Some(ip), None) => Ok(QuotaClient::Ip(ip)),
+        (None, Some(user_id)) => Ok(QuotaClient::User(user_id)),
+        _ => Err(AppError::ValidationError(
+            "Specify exactly one of ip or user_id".to_string(),
+        )),
+    }
+}
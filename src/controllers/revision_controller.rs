@@ -0,0 +1,40 @@
+//! Revision controller for post history and diffs.
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{RevisionDiff, RevisionSummary, Site};
+use crate::response::{success, ApiResponse};
+use crate::services::{PostService, RevisionService};
+
+/// A post's revisions, newest first (requires `posts:update_own` or `posts:update_any`).
+pub async fn list_post_revisions(
+    State(post_service): State<PostService>,
+    State(revision_service): State<RevisionService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<RevisionSummary>>>, AppError> {
+    let post = post_service.get_editable(site.id, id, &auth_user).await?;
+    let revisions = revision_service.list(post.id).await?;
+    Ok(success(revisions))
+}
+
+/// Word-level diff from revision `a` to revision `b` of a post
+/// (requires `posts:update_own` or `posts:update_any`).
+pub async fn get_revision_diff(
+    State(post_service): State<PostService>,
+    State(revision_service): State<RevisionService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((id, a, b)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<Json<ApiResponse<RevisionDiff>>, AppError> {
+    let post = post_service.get_editable(site.id, id, &auth_user).await?;
+    let diff = revision_service.diff(post.id, a, b).await?;
+    Ok(success(diff))
+}
@@ -1,27 +1,45 @@
 //! Role controller for role management (admin only).
 
 use axum::{
-    extract::{Path, State},
-    Extension, Json,
+    extract::{Path, Query, State},
+    Json,
 };
 use uuid::Uuid;
 
+use super::user_controller::user_page;
 use crate::error::AppError;
-use crate::middleware::AuthUser;
-use crate::models::{CreateRoleRequest, RoleResponse, UpdateRoleRequest};
-use crate::repositories::RoleRepository;
+use crate::models::{
+    CreateRoleRequest, DeleteRoleQuery, RoleResponse, UpdateRoleRequest, UserListQuery,
+    UserWithRoleResponse,
+};
+use crate::pkg::slug::{self, slugify};
+use crate::repositories::{RoleRepository, UserRepository};
 use crate::response::{success, ApiResponse, MessageResponse};
+use crate::services::AuthService;
+
+/// Longest role slug (the `roles.slug` column is `VARCHAR(100)`).
+const MAX_ROLE_SLUG_LEN: usize = 100;
 
-/// List all roles.
+/// List all roles with how many users hold each.
 pub async fn list_roles(
     State(role_repo): State<RoleRepository>,
 ) -> Result<Json<ApiResponse<Vec<RoleResponse>>>, AppError> {
     let roles = role_repo.find_all().await?;
-    let responses: Vec<RoleResponse> = roles.into_iter().map(|r| r.into()).collect();
+    let counts = role_repo.count_users_by_role().await?;
+    let responses: Vec<RoleResponse> = roles
+        .into_iter()
+        .map(|role| {
+            let user_count = counts
+                .iter()
+                .find(|(role_id, _)| *role_id == role.id)
+                .map_or(0, |(_, count)| *count);
+            RoleResponse::from(role).with_user_count(user_count)
+        })
+        .collect();
     Ok(success(responses))
 }
 
-/// Get a role by ID.
+/// Get a role by ID with how many users hold it.
 pub async fn get_role(
     State(role_repo): State<RoleRepository>,
     Path(id): Path<Uuid>,
@@ -30,7 +48,26 @@ pub async fn get_role(
         .find_by_id(id)
         .await?
         .ok_or_else(|| AppError::NotFound("Role not found".to_string()))?;
-    Ok(success(role.into()))
+    let user_count = role_repo.count_users(id).await?;
+    Ok(success(
+        RoleResponse::from(role).with_user_count(user_count),
+    ))
+}
+
+/// List the users holding a role, a page at a time, with the filters and
+/// pagination of the user listing.
+pub async fn list_role_users(
+    State(role_repo): State<RoleRepository>,
+    State(user_repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+    Query(mut query): Query<UserListQuery>,
+) -> Result<Json<ApiResponse<Vec<UserWithRoleResponse>>>, AppError> {
+    let role = role_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Role not found".to_string()))?;
+    query.role = Some(role.slug);
+    user_page(&user_repo, &query).await
 }
 
 /// Get permissions for a role.
@@ -51,20 +88,25 @@ pub async fn get_role_permissions(
 /// Create a new role (admin only).
 pub async fn create_role(
     State(role_repo): State<RoleRepository>,
-    Extension(auth_user): Extension<AuthUser>,
     Json(request): Json<CreateRoleRequest>,
 ) -> Result<Json<ApiResponse<RoleResponse>>, AppError> {
-    if !auth_user.is_admin() {
-        return Err(AppError::Forbidden("Admin access required".to_string()));
-    }
-
-    // Generate slug if not provided
-    let slug = request.slug.unwrap_or_else(|| slugify(&request.name));
-
-    // Check if slug exists
-    if role_repo.find_by_slug(&slug).await?.is_some() {
-        return Err(AppError::Conflict("Role slug already exists".to_string()));
-    }
+    // Generate a free slug if not provided
+    let slug =
+        match request.slug {
+            Some(slug) => {
+                if role_repo.find_by_slug(&slug).await?.is_some() {
+                    return Err(AppError::Conflict("Role slug already exists".to_string()));
+                }
+                slug
+            }
+            None => {
+                let role_repo = &role_repo;
+                slug::unique(&slugify(&request.name), MAX_ROLE_SLUG_LEN, |candidate| async move {
+                Ok(role_repo.find_by_slug(&candidate).await?.is_some())
+            })
+            .await?
+            }
+        };
 
     let role = role_repo
         .create(&request.name, &slug, request.description.as_deref())
@@ -75,17 +117,13 @@ pub async fn create_role(
 
 /// Update a role (admin only).
 pub async fn update_role(
+    State(auth_service): State<AuthService>,
     State(role_repo): State<RoleRepository>,
-    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
     Json(request): Json<UpdateRoleRequest>,
 ) -> Result<Json<ApiResponse<RoleResponse>>, AppError> {
-    if !auth_user.is_admin() {
-        return Err(AppError::Forbidden("Admin access required".to_string()));
-    }
-
     // Check if role exists
-    role_repo
+    let existing = role_repo
         .find_by_id(id)
         .await?
         .ok_or_else(|| AppError::NotFound("Role not found".to_string()))?;
@@ -108,19 +146,24 @@ pub async fn update_role(
         )
         .await?;
 
+    // Tokens carry the role slug, so holders of the role must sign in again
+    if role.slug != existing.slug {
+        auth_service.revoke_role_tokens(id).await?;
+    }
+
     Ok(success(role.into()))
 }
 
 /// Delete a role (admin only).
+///
+/// A role still held by users is only deleted with `?reassign_to=<role id>`,
+/// which moves them to that role first and signs them out.
 pub async fn delete_role(
+    State(auth_service): State<AuthService>,
     State(role_repo): State<RoleRepository>,
-    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
+    Query(query): Query<DeleteRoleQuery>,
 ) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
-    if !auth_user.is_admin() {
-        return Err(AppError::Forbidden("Admin access required".to_string()));
-    }
-
     // Prevent deleting built-in roles
     let role = role_repo
         .find_by_id(id)
@@ -133,8 +176,37 @@ pub async fn delete_role(
         ));
     }
 
-    role_repo.delete(id).await?;
-    Ok(success(MessageResponse::new("Role deleted successfully")))
+    let user_count = role_repo.count_users(id).await?;
+    if user_count == 0 {
+        role_repo.delete(id).await?;
+        return Ok(success(MessageResponse::new("Role deleted successfully")));
+    }
+
+    let Some(reassign_to) = query.reassign_to else {
+        return Err(AppError::Conflict(format!(
+            "Role is held by {} user(s); pass reassign_to to move them to another role",
+            user_count
+        )));
+    };
+    if reassign_to == id {
+        return Err(AppError::ValidationError(
+            "Users cannot be reassigned to the role being deleted".to_string(),
+        ));
+    }
+    role_repo
+        .find_by_id(reassign_to)
+        .await?
+        .ok_or_else(|| AppError::ValidationError("Role to reassign to not found".to_string()))?;
+
+    // Tokens carry the role slug, so moved users must sign in again
+    let moved = role_repo.delete_reassigning(id, reassign_to).await?;
+    for user_id in &moved {
+        auth_service.revoke_user_tokens(*user_id).await?;
+    }
+    Ok(success(MessageResponse::new(format!(
+        "Role deleted; {} user(s) reassigned",
+        moved.len()
+    ))))
 }
 
 /// Request payload for assigning permission to a role.
@@ -146,14 +218,9 @@ pub struct AssignPermissionRequest {
 /// Assign a permission to a role (admin only).
 pub async fn assign_permission(
     State(role_repo): State<RoleRepository>,
-    Extension(auth_user): Extension<AuthUser>,
     Path(role_id): Path<Uuid>,
     Json(request): Json<AssignPermissionRequest>,
 ) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
-    if !auth_user.is_admin() {
-        return Err(AppError::Forbidden("Admin access required".to_string()));
-    }
-
     // Verify role exists
     role_repo
         .find_by_id(role_id)
@@ -176,13 +243,8 @@ pub async fn assign_permission(
 /// Remove a permission from a role (admin only).
 pub async fn remove_permission(
     State(role_repo): State<RoleRepository>,
-    Extension(auth_user): Extension<AuthUser>,
     Path((role_id, permission_id)): Path<(Uuid, Uuid)>,
 ) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
-    if !auth_user.is_admin() {
-        return Err(AppError::Forbidden("Admin access required".to_string()));
-    }
-
     // Verify role exists
     role_repo
         .find_by_id(role_id)
@@ -201,14 +263,3 @@ pub async fn remove_permission(
         )))
     }
 }
-
-fn slugify(text: &str) -> String {
-    text.to_lowercase()
-        .chars()
-        .map(|c| if c.is_alphanumeric() { c } else { '-' })
-        .collect::<String>()
-        .split('-')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join("-")
-}
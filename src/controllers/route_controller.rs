@@ -0,0 +1,27 @@
+//! Route controller describing which API routes the current user may call.
+
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+
+use crate::middleware::AuthUser;
+use crate::models::{RouteAccess, RoutePermission};
+use crate::response::{success, ApiResponse};
+
+/// List every API route with its access rule and whether the current user passes it.
+pub async fn list_routes(
+    Extension(routes): Extension<Arc<Vec<RouteAccess>>>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Json<ApiResponse<Vec<RoutePermission>>> {
+    let is_admin = auth_user.is_admin();
+    let routes = routes
+        .iter()
+        .map(|route| RoutePermission {
+            allowed: route.allows(true, is_admin, |permission| {
+                auth_user.permission_source(permission).is_some()
+            }),
+            route: route.clone(),
+        })
+        .collect();
+    success(routes)
+}
@@ -0,0 +1,21 @@
+//! Search controller for the admin-wide search.
+
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+
+use crate::error::AppError;
+use crate::models::{AdminSearchQuery, AdminSearchResponse, Site};
+use crate::response::{success, ApiResponse};
+use crate::services::SearchService;
+
+/// Search posts, users, categories and tags at once (admin only).
+pub async fn admin_search(
+    State(search_service): State<SearchService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<AdminSearchQuery>,
+) -> Result<Json<ApiResponse<AdminSearchResponse>>, AppError> {
+    let results = search_service.admin_search(site.id, query).await?;
+    Ok(success(results))
+}
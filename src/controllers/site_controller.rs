@@ -0,0 +1,44 @@
+//! Site controller for the current site and site management.
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{CreateSiteRequest, Site, UpdateSiteRequest};
+use crate::response::{success, ApiResponse};
+use crate::services::SiteService;
+
+/// Get the site serving this request, including its public settings.
+pub async fn get_current_site(Extension(site): Extension<Site>) -> Json<ApiResponse<Site>> {
+    success(site)
+}
+
+/// List all sites (admin only).
+pub async fn list_sites(
+    State(site_service): State<SiteService>,
+) -> Result<Json<ApiResponse<Vec<Site>>>, AppError> {
+    let sites = site_service.list().await?;
+    Ok(success(sites))
+}
+
+/// Create a new site (admin only).
+pub async fn create_site(
+    State(site_service): State<SiteService>,
+    Json(request): Json<CreateSiteRequest>,
+) -> Result<Json<ApiResponse<Site>>, AppError> {
+    let site = site_service.create(request).await?;
+    Ok(success(site))
+}
+
+/// Update a site's name, host or settings (admin only).
+pub async fn update_site(
+    State(site_service): State<SiteService>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateSiteRequest>,
+) -> Result<Json<ApiResponse<Site>>, AppError> {
+    let site = site_service.update(id, request).await?;
+    Ok(success(site))
+}
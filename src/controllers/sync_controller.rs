@@ -0,0 +1,21 @@
+//! Sync controller for incremental content mirrors.
+
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+
+use crate::error::AppError;
+use crate::models::{Site, SyncChanges, SyncChangesQuery};
+use crate::response::{success, ApiResponse};
+use crate::services::SyncService;
+
+/// Ids of posts, categories and tags changed since a time (requires `sync:read`).
+pub async fn get_sync_changes(
+    State(sync_service): State<SyncService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<SyncChangesQuery>,
+) -> Result<Json<ApiResponse<SyncChanges>>, AppError> {
+    let changes = sync_service.changes(site.id, query).await?;
+    Ok(success(changes))
+}
@@ -1,70 +1,133 @@
 //! Tag controller for tag CRUD operations.
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Extension, Json,
 };
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::middleware::AuthUser;
-use crate::models::{CreateTagRequest, Tag, TagWithCount, UpdateTagRequest};
+use crate::models::{
+    CreateTagRequest, MergeTagRequest, Site, Tag, TagCloudItem, TagCloudQuery, TagMergeResponse,
+    TagSearchQuery, TagWithCount, UpdateTagRequest,
+};
 use crate::response::{success, ApiResponse, MessageResponse};
 use crate::services::TagService;
 
 /// List all tags.
 pub async fn list_tags(
     State(tag_service): State<TagService>,
+    Extension(site): Extension<Site>,
 ) -> Result<Json<ApiResponse<Vec<TagWithCount>>>, AppError> {
-    let tags = tag_service.list().await?;
+    let tags = tag_service.list(site.id).await?;
+    Ok(success(tags))
+}
+
+/// Autocomplete tags by name (`?q=ru&limit=10`).
+pub async fn search_tags(
+    State(tag_service): State<TagService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<TagSearchQuery>,
+) -> Result<Json<ApiResponse<Vec<Tag>>>, AppError> {
+    let tags = tag_service.search(site.id, query).await?;
+    Ok(success(tags))
+}
+
+/// Tags weighted by published, public posts for a tag cloud (`?limit=50&half_life_days=90`).
+pub async fn get_tag_cloud(
+    State(tag_service): State<TagService>,
+    Extension(site): Extension<Site>,
+    Query(query): Query<TagCloudQuery>,
+) -> Result<Json<ApiResponse<Vec<TagCloudItem>>>, AppError> {
+    let tags = tag_service.cloud(site.id, query).await?;
     Ok(success(tags))
 }
 
 /// Get a single tag by ID.
 pub async fn get_tag(
     State(tag_service): State<TagService>,
+    Extension(site): Extension<Site>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<Tag>>, AppError> {
-    let tag = tag_service.get_by_id(id).await?;
+    let tag = tag_service.get_by_id(site.id, id).await?;
     Ok(success(tag))
 }
 
-/// Create a new tag (admin only).
+/// Create a new tag (requires `tags:create`).
 pub async fn create_tag(
     State(tag_service): State<TagService>,
-    Extension(auth_user): Extension<AuthUser>,
+    Extension(site): Extension<Site>,
     Json(request): Json<CreateTagRequest>,
 ) -> Result<Json<ApiResponse<Tag>>, AppError> {
-    if !auth_user.can_create("tags") {
-        return Err(AppError::Forbidden("Cannot create tags".to_string()));
-    }
-    let tag = tag_service.create(request).await?;
+    let tag = tag_service.create(site.id, request).await?;
     Ok(success(tag))
 }
 
-/// Update a tag (admin only).
+/// Update a tag (requires `tags:update`).
 pub async fn update_tag(
     State(tag_service): State<TagService>,
-    Extension(auth_user): Extension<AuthUser>,
+    Extension(site): Extension<Site>,
     Path(id): Path<Uuid>,
     Json(request): Json<UpdateTagRequest>,
 ) -> Result<Json<ApiResponse<Tag>>, AppError> {
-    if !auth_user.can_update("tags") {
-        return Err(AppError::Forbidden("Cannot update tags".to_string()));
-    }
-    let tag = tag_service.update(id, request).await?;
+    let tag = tag_service.update(site.id, id, request).await?;
     Ok(success(tag))
 }
 
-/// Delete a tag (admin only).
+/// Delete a tag (requires `tags:delete`).
 pub async fn delete_tag(
     State(tag_service): State<TagService>,
-    Extension(auth_user): Extension<AuthUser>,
+    Extension(site): Extension<Site>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
-    if !auth_user.can_delete("tags") {
-        return Err(AppError::Forbidden("Cannot delete tags".to_string()));
-    }
-    tag_service.delete(id).await?;
+    tag_service.delete(site.id, id).await?;
     Ok(success(MessageResponse::new("Tag deleted successfully")))
 }
+
+/// Merge a tag into another, retagging its posts (requires `tags:delete`).
+pub async fn merge_tag(
+    State(tag_service): State<TagService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<MergeTagRequest>,
+) -> Result<Json<ApiResponse<TagMergeResponse>>, AppError> {
+    let merged = tag_service.merge(site.id, id, request).await?;
+    Ok(success(merged))
+}
+
+/// List tags no post uses (requires `tags:delete`).
+pub async fn list_orphan_tags(
+    State(tag_service): State<TagService>,
+    Extension(site): Extension<Site>,
+) -> Result<Json<ApiResponse<Vec<Tag>>>, AppError> {
+    let tags = tag_service.orphans(site.id).await?;
+    Ok(success(tags))
+}
+
+/// Delete tags no post uses, returning them (requires `tags:delete`).
+pub async fn delete_orphan_tags(
+    State(tag_service): State<TagService>,
+    Extension(site): Extension<Site>,
+) -> Result<Json<ApiResponse<Vec<Tag>>>, AppError> {
+    let tags = tag_service.delete_orphans(site.id).await?;
+    Ok(success(tags))
+}
+
+/// List soft-deleted tags (requires `tags:delete`).
+pub async fn list_deleted_tags(
+    State(tag_service): State<TagService>,
+    Extension(site): Extension<Site>,
+) -> Result<Json<ApiResponse<Vec<Tag>>>, AppError> {
+    let tags = tag_service.list_deleted(site.id).await?;
+    Ok(success(tags))
+}
+
+/// Restore a soft-deleted tag onto its posts (requires `tags:delete`).
+pub async fn restore_tag(
+    State(tag_service): State<TagService>,
+    Extension(site): Extension<Site>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Tag>>, AppError> {
+    let tag = tag_service.restore(site.id, id).await?;
+    Ok(success(tag))
+}
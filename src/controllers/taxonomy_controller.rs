@@ -0,0 +1,46 @@
+//! Taxonomy controller for bulk category and tag imports.
+
+use axum::{
+    extract::{Multipart, State},
+    Extension, Json,
+};
+
+use crate::error::AppError;
+use crate::models::{ImportFormat, Site, TaxonomyImportReport};
+use crate::response::{success, ApiResponse};
+use crate::services::TaxonomyService;
+
+/// Import categories and tags from a CSV or JSON file (multipart field `file`, admin only).
+pub async fn import_taxonomy(
+    State(taxonomy_service): State<TaxonomyService>,
+    Extension(site): Extension<Site>,
+    mut multipart: Multipart,
+) -> Result<Json<ApiResponse<TaxonomyImportReport>>, AppError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::ValidationError(e.to_string()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let format = ImportFormat::detect(
+            field.file_name().unwrap_or_default(),
+            field.content_type().unwrap_or_default(),
+        )
+        .ok_or_else(|| {
+            AppError::ValidationError("Import file must be .csv or .json".to_string())
+        })?;
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+        let report = taxonomy_service.import(site.id, format, &bytes).await?;
+        return Ok(success(report));
+    }
+
+    Err(AppError::ValidationError(
+        "Multipart field 'file' is required".to_string(),
+    ))
+}
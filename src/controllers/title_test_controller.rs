@@ -0,0 +1,68 @@
+//! Title test controller for A/B testing post headlines.
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{
+    PickTitleRequest, PostResponse, SetTitleVariantsRequest, Site, TitleTestResponse,
+};
+use crate::response::{success, ApiResponse, MessageResponse};
+use crate::services::TitleTestService;
+
+/// Results of a post's title test (requires `posts:update_own` or `posts:update_any`).
+pub async fn get_title_test(
+    State(title_test_service): State<TitleTestService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<TitleTestResponse>>, AppError> {
+    let test = title_test_service.get(site.id, id, &auth_user).await?;
+    Ok(success(test))
+}
+
+/// Start testing alternative titles of a post, restarting any running test
+/// (requires `posts:update_own` or `posts:update_any`).
+pub async fn start_title_test(
+    State(title_test_service): State<TitleTestService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<SetTitleVariantsRequest>,
+) -> Result<Json<ApiResponse<TitleTestResponse>>, AppError> {
+    let test = title_test_service
+        .start(site.id, id, &auth_user, request)
+        .await?;
+    Ok(success(test))
+}
+
+/// End a post's title test without changing its title
+/// (requires `posts:update_own` or `posts:update_any`).
+pub async fn stop_title_test(
+    State(title_test_service): State<TitleTestService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    title_test_service.stop(site.id, id, &auth_user).await?;
+    Ok(success(MessageResponse::new("Title test ended")))
+}
+
+/// End a post's title test, making the picked headline its title
+/// (requires `posts:update_own` or `posts:update_any`).
+pub async fn pick_title(
+    State(title_test_service): State<TitleTestService>,
+    Extension(site): Extension<Site>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<PickTitleRequest>,
+) -> Result<Json<ApiResponse<PostResponse>>, AppError> {
+    let post = title_test_service
+        .pick(site.id, id, &auth_user, request)
+        .await?;
+    Ok(success(post))
+}
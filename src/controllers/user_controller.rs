@@ -1,40 +1,74 @@
 //! User controller for user management (admin only).
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Extension, Json,
 };
 use uuid::Uuid;
 
 use crate::error::AppError;
 use crate::middleware::AuthUser;
-use crate::models::{CreateUserRequest, UserWithRoleResponse};
-use crate::repositories::UserRepository;
-use crate::response::{success, ApiResponse, MessageResponse};
-use crate::services::AuthService;
+use crate::models::{
+    CreateUserRequest, ErasureReceipt, InvitationResponse, InviteUserRequest, UpdateUserRequest,
+    UserListQuery, UserWithRoleResponse,
+};
+use crate::pkg::Cursor;
+use crate::repositories::{RoleRepository, UserRepository};
+use crate::response::{success, ApiResponse, MessageResponse, Meta};
+use crate::services::{AccountService, AuthService};
 
-/// List all users (admin only).
+/// List users newest first, a page at a time (admin only).
+///
+/// `?include_deleted=true` adds soft-deleted users, and `search` and `role`
+/// narrow the list. Pages are picked with `page` or, to walk a long list,
+/// with the previous page's `meta.next_cursor` as `cursor`.
 pub async fn list_users(
     State(user_repo): State<UserRepository>,
-    Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<UserListQuery>,
 ) -> Result<Json<ApiResponse<Vec<UserWithRoleResponse>>>, AppError> {
-    if !auth_user.is_admin() {
-        return Err(AppError::Forbidden("Admin access required".to_string()));
-    }
-    let users = user_repo.find_all().await?;
+    user_page(&user_repo, &query).await
+}
+
+/// One page of the users matching `query`, with the next page's cursor.
+pub(crate) async fn user_page(
+    user_repo: &UserRepository,
+    query: &UserListQuery,
+) -> Result<Json<ApiResponse<Vec<UserWithRoleResponse>>>, AppError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    let cursor = query.cursor.as_deref().map(Cursor::decode).transpose()?;
+    let offset = if cursor.is_some() {
+        0
+    } else {
+        (page - 1) * per_page
+    };
+    let filter = query.filter();
+
+    let mut users = user_repo
+        .find_all(&filter, cursor.as_ref(), per_page + 1, offset)
+        .await?;
+    let next_cursor = if users.len() as i64 > per_page {
+        users.truncate(per_page as usize);
+        users
+            .last()
+            .map(|user| Cursor::new(user.created_at, user.id).encode())
+    } else {
+        None
+    };
+    let total = user_repo.count(&filter).await?;
+
     let responses: Vec<UserWithRoleResponse> = users.into_iter().map(|u| u.into()).collect();
-    Ok(success(responses))
+    Ok(Json(ApiResponse::with_meta(
+        responses,
+        Meta::new(page, per_page, total).with_next_cursor(next_cursor),
+    )))
 }
 
 /// Get a user by ID (admin only).
 pub async fn get_user(
     State(user_repo): State<UserRepository>,
-    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<UserWithRoleResponse>>, AppError> {
-    if !auth_user.is_admin() {
-        return Err(AppError::Forbidden("Admin access required".to_string()));
-    }
     let user = user_repo
         .find_by_id_with_role(id)
         .await?
@@ -46,20 +80,22 @@ pub async fn get_user(
 pub async fn create_user(
     State(auth_service): State<AuthService>,
     State(user_repo): State<UserRepository>,
-    Extension(auth_user): Extension<AuthUser>,
     Json(request): Json<CreateUserRequest>,
 ) -> Result<Json<ApiResponse<UserWithRoleResponse>>, AppError> {
-    if !auth_user.is_admin() {
-        return Err(AppError::Forbidden("Admin access required".to_string()));
-    }
-
-    // Hash password
+    // Enforce the password policy, then hash
+    auth_service
+        .check_new_password(
+            "password",
+            &request.password,
+            &[&request.email, &request.name],
+        )
+        .await?;
     let password_hash = auth_service.hash_password(&request.password)?;
 
-    // Default to viewer role if not specified
-    let role_id = request
-        .role_id
-        .ok_or_else(|| AppError::ValidationError("role_id is required".to_string()))?;
+    let role_id = match request.role_id {
+        Some(role_id) => role_id,
+        None => auth_service.default_role_id().await?,
+    };
 
     let user = user_repo
         .create(&request.email, &password_hash, &request.name, role_id)
@@ -74,16 +110,92 @@ pub async fn create_user(
     Ok(success(user_with_role.into()))
 }
 
+/// Invite a user by email with a pre-assigned role (admin only).
+pub async fn invite_user(
+    State(auth_service): State<AuthService>,
+    Json(request): Json<InviteUserRequest>,
+) -> Result<Json<ApiResponse<InvitationResponse>>, AppError> {
+    let invitation = auth_service.invite(&request.email, request.role_id).await?;
+    Ok(success(invitation))
+}
+
+/// Update a user's name, email, role or active status (admin only).
+///
+/// Changing the role or deactivating the user revokes their tokens so stale
+/// role claims cannot outlive the change.
+pub async fn update_user(
+    State(auth_service): State<AuthService>,
+    State(user_repo): State<UserRepository>,
+    State(role_repo): State<RoleRepository>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateUserRequest>,
+) -> Result<Json<ApiResponse<UserWithRoleResponse>>, AppError> {
+    let existing = user_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let role_changed = request
+        .role_id
+        .is_some_and(|role_id| role_id != existing.role_id);
+    let deactivated = existing.is_active && request.is_active == Some(false);
+
+    // Prevent admins from locking themselves out
+    if auth_user.id == id && (role_changed || deactivated) {
+        return Err(AppError::ValidationError(
+            "Cannot change your own role or deactivate yourself".to_string(),
+        ));
+    }
+
+    let name = request.name.as_deref().map(str::trim);
+    if name.is_some_and(str::is_empty) {
+        return Err(AppError::ValidationError(
+            "name cannot be empty".to_string(),
+        ));
+    }
+
+    let email = request.email.as_deref().map(str::trim);
+    if let Some(email) = email {
+        if !email.contains('@') {
+            return Err(AppError::ValidationError("email is invalid".to_string()));
+        }
+        if let Some(other) = user_repo.find_by_email_with_role(email).await? {
+            if other.id != id {
+                return Err(AppError::Conflict("Email already in use".to_string()));
+            }
+        }
+    }
+
+    if let Some(role_id) = request.role_id {
+        role_repo
+            .find_by_id(role_id)
+            .await?
+            .ok_or_else(|| AppError::ValidationError("Role not found".to_string()))?;
+    }
+
+    user_repo
+        .update(id, name, email, request.role_id, request.is_active)
+        .await?;
+
+    if role_changed || deactivated {
+        auth_service.revoke_user_tokens(id).await?;
+    }
+
+    let user = user_repo
+        .find_by_id_with_role(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    Ok(success(user.into()))
+}
+
 /// Delete a user (admin only).
 pub async fn delete_user(
+    State(auth_service): State<AuthService>,
     State(user_repo): State<UserRepository>,
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
-    if !auth_user.is_admin() {
-        return Err(AppError::Forbidden("Admin access required".to_string()));
-    }
-
     // Prevent self-deletion
     if auth_user.id == id {
         return Err(AppError::ValidationError(
@@ -91,6 +203,122 @@ pub async fn delete_user(
         ));
     }
 
-    user_repo.delete(id).await?;
+    if user_repo.delete(id).await? {
+        auth_service.revoke_user_tokens(id).await?;
+    }
     Ok(success(MessageResponse::new("User deleted successfully")))
 }
+
+/// Sign a user out of every device (admin only).
+pub async fn logout_user(
+    State(auth_service): State<AuthService>,
+    State(user_repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    user_repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    auth_service.revoke_user_tokens(id).await?;
+    Ok(success(MessageResponse::new("User signed out everywhere")))
+}
+
+/// Permanently erase a user's personal data (admin only, sudo mode).
+pub async fn erase_user(
+    State(account_service): State<AccountService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<ErasureReceipt>>, AppError> {
+    let receipt = account_service.erase(id, auth_user.id).await?;
+    Ok(success(receipt))
+}
+
+/// Approve a pending self-registered user (admin only).
+pub async fn approve_user(
+    State(auth_service): State<AuthService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<UserWithRoleResponse>>, AppError> {
+    let user = auth_service.approve(id).await?;
+    Ok(success(user.into()))
+}
+
+/// Deactivate a user, signing them out everywhere (admin only).
+///
+/// Unlike deleting, their posts and history stay as they are.
+pub async fn deactivate_user(
+    State(auth_service): State<AuthService>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<UserWithRoleResponse>>, AppError> {
+    if auth_user.id == id {
+        return Err(AppError::ValidationError(
+            "Cannot deactivate yourself".to_string(),
+        ));
+    }
+    let user = auth_service.set_active(id, false).await?;
+    Ok(success(user.into()))
+}
+
+/// Reactivate a deactivated user (admin only).
+pub async fn activate_user(
+    State(auth_service): State<AuthService>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<UserWithRoleResponse>>, AppError> {
+    let user = auth_service.set_active(id, true).await?;
+    Ok(success(user.into()))
+}
+
+/// Restore a soft-deleted user (admin only).
+///
+/// Fails with a conflict if their email has since been taken by another account.
+pub async fn restore_user(
+    State(user_repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<UserWithRoleResponse>>, AppError> {
+    let deleted = user_repo
+        .find_deleted_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Deleted user not found".to_string()))?;
+
+    if user_repo
+        .find_by_email_with_role(&deleted.email)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::Conflict(format!(
+            "Email {} is now used by another account; change it before restoring",
+            deleted.email
+        )));
+    }
+
+    user_repo.restore(id).await?;
+    let user = user_repo
+        .find_by_id_with_role(id)
+        .await?
+        .ok_or_else(|| AppError::InternalError("Failed to fetch restored user".to_string()))?;
+    Ok(success(user.into()))
+}
+
+/// Permanently delete a soft-deleted user (admin only).
+///
+/// Refused while the user still authors posts, since those would be deleted too.
+pub async fn purge_user(
+    State(user_repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiResponse<MessageResponse>>, AppError> {
+    user_repo.find_deleted_by_id(id).await?.ok_or_else(|| {
+        AppError::NotFound("Deleted user not found; soft-delete the user first".to_string())
+    })?;
+
+    let posts = user_repo.count_posts(id).await?;
+    if posts > 0 {
+        return Err(AppError::Conflict(format!(
+            "User still authors {} post(s); reassign or delete them first",
+            posts
+        )));
+    }
+
+    user_repo.purge(id).await?;
+    Ok(success(MessageResponse::new("User permanently deleted")))
+}
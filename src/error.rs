@@ -3,7 +3,7 @@
 //! Provides a unified error type that integrates with Axum's response system.
 
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -18,15 +18,31 @@ pub enum AppError {
     #[error("Access denied: {0}")]
     Forbidden(String),
 
+    /// The account was deactivated by an admin.
+    #[error("Account is deactivated")]
+    AccountDeactivated,
+
+    /// The action needs a recent password confirmation (sudo mode).
+    #[error("Please confirm your password to continue")]
+    ReauthenticationRequired,
+
     #[error("Resource not found: {0}")]
     NotFound(String),
 
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    /// Validation failed for specific request fields.
+    #[error("Validation error: {}", describe_fields(.0))]
+    InvalidFields(Vec<FieldError>),
+
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// A conditional request's precondition, like `If-Unmodified-Since`, did not hold.
+    #[error("Precondition failed: {0}")]
+    PreconditionFailed(String),
+
     #[error("Database error: {0}")]
     DatabaseError(String),
 
@@ -38,6 +54,38 @@ pub enum AppError {
 
     #[error("Internal server error: {0}")]
     InternalError(String),
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    /// Rate limited; carries the number of seconds until the client may retry.
+    #[error("Too many requests: retry after {0} seconds")]
+    TooManyRequests(u64),
+}
+
+/// A validation problem with one request field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    /// Create a field error.
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+fn describe_fields(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|error| format!("{} {}", error.field, error.message))
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 /// Error response structure for API.
@@ -45,6 +93,9 @@ pub enum AppError {
 pub struct ErrorResponse {
     pub code: String,
     pub message: String,
+    /// Per-field problems, present for field validation errors
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
 }
 
 /// API error wrapper matching our response format.
@@ -61,28 +112,50 @@ impl AppError {
         match self {
             AppError::Unauthorized => "UNAUTHORIZED",
             AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::AccountDeactivated => "ACCOUNT_DEACTIVATED",
+            AppError::ReauthenticationRequired => "REAUTHENTICATION_REQUIRED",
             AppError::NotFound(_) => "NOT_FOUND",
-            AppError::ValidationError(_) => "VALIDATION_ERROR",
+            AppError::ValidationError(_) | AppError::InvalidFields(_) => "VALIDATION_ERROR",
             AppError::Conflict(_) => "CONFLICT",
+            AppError::PreconditionFailed(_) => "PRECONDITION_FAILED",
             AppError::DatabaseError(_) => "DATABASE_ERROR",
             AppError::RedisError(_) => "REDIS_ERROR",
             AppError::JwtError(_) => "JWT_ERROR",
             AppError::InternalError(_) => "INTERNAL_ERROR",
+            AppError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            AppError::TooManyRequests(_) => "TOO_MANY_REQUESTS",
         }
     }
 
     /// Get the HTTP status code.
-    fn status_code(&self) -> StatusCode {
+    /// Body describing the error, as sent in the `error` field.
+    pub(crate) fn error_response(&self) -> ErrorResponse {
+        ErrorResponse {
+            code: self.error_code().to_string(),
+            message: self.to_string(),
+            fields: match self {
+                AppError::InvalidFields(fields) => fields.clone(),
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    pub(crate) fn status_code(&self) -> StatusCode {
         match self {
             AppError::Unauthorized => StatusCode::UNAUTHORIZED,
-            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Forbidden(_)
+            | AppError::AccountDeactivated
+            | AppError::ReauthenticationRequired => StatusCode::FORBIDDEN,
             AppError::NotFound(_) => StatusCode::NOT_FOUND,
-            AppError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            AppError::ValidationError(_) | AppError::InvalidFields(_) => StatusCode::BAD_REQUEST,
             AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
             AppError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::RedisError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::JwtError(_) => StatusCode::UNAUTHORIZED,
             AppError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 }
@@ -93,13 +166,16 @@ impl IntoResponse for AppError {
         let body = ApiErrorResponse {
             success: false,
             data: None,
-            error: ErrorResponse {
-                code: self.error_code().to_string(),
-                message: self.to_string(),
-            },
+            error: self.error_response(),
         };
 
-        (status, Json(body)).into_response()
+        let mut response = (status, Json(body)).into_response();
+        if let AppError::TooManyRequests(retry_after) = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        response
     }
 }
 
@@ -161,6 +237,14 @@ mod tests {
             AppError::Conflict("test".to_string()).error_code(),
             "CONFLICT"
         );
+        assert_eq!(
+            AppError::ReauthenticationRequired.error_code(),
+            "REAUTHENTICATION_REQUIRED"
+        );
+        assert_eq!(
+            AppError::AccountDeactivated.error_code(),
+            "ACCOUNT_DEACTIVATED"
+        );
     }
 
     #[test]
@@ -185,11 +269,31 @@ mod tests {
             AppError::Conflict("test".to_string()).status_code(),
             StatusCode::CONFLICT
         );
+        assert_eq!(
+            AppError::PreconditionFailed("test".to_string()).status_code(),
+            StatusCode::PRECONDITION_FAILED
+        );
+    }
+
+    #[test]
+    fn test_too_many_requests_sets_retry_after() {
+        let response = AppError::TooManyRequests(30).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "30");
     }
 
     #[test]
     fn test_error_display() {
         let error = AppError::NotFound("User".to_string());
         assert_eq!(error.to_string(), "Resource not found: User");
+
+        let error = AppError::InvalidFields(vec![
+            FieldError::new("password", "is too short"),
+            FieldError::new("email", "is invalid"),
+        ]);
+        assert_eq!(
+            error.to_string(),
+            "Validation error: password is too short; email is invalid"
+        );
     }
 }
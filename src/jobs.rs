@@ -0,0 +1,249 @@
+//! Background jobs run on a fixed schedule.
+//!
+//! Jobs run in every server process; each must be safe to run concurrently
+//! with itself on another instance.
+
+use std::future::Future;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::time::MissedTickBehavior;
+
+use crate::config::Config;
+use crate::pkg::geoip::{GeoIp, GEOIP_RELOAD_INTERVAL};
+use crate::runtime::RuntimeSettings;
+use crate::services::{
+    BackupService, CacheWarmer, DigestService, EventRelay, JobService, MediaService, StatusMonitor,
+    TagService, TrendingService,
+};
+
+/// How often partial files of abandoned chunked uploads are looked for.
+const STALE_UPLOAD_CHECK_PERIOD: Duration = Duration::from_secs(60 * 60);
+/// How often webhook attempts past their retention are deleted.
+const WEBHOOK_LOG_PRUNE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// Run `task` every `period` until the process exits, starting one period
+/// after startup.
+pub fn spawn_periodic<F, Fut>(name: &'static str, period: Duration, mut task: F)
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // The first tick completes immediately
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            tracing::debug!(job = name, "Running scheduled job");
+            task().await;
+        }
+    });
+}
+
+/// Take database backups on the `BACKUP_SCHEDULE` cron schedule.
+///
+/// Every instance wakes at each scheduled time; the first to claim it runs the backup.
+pub fn spawn_backups(config: &Config, backup_service: BackupService) {
+    let Some(schedule) = config.backup_schedule.clone() else {
+        return;
+    };
+    if !backup_service.is_enabled() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        while let Some(slot) = schedule.next_after(Utc::now()) {
+            let wait = (slot - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+            tracing::debug!(job = "backup", "Running scheduled job");
+            match backup_service.run_scheduled(slot).await {
+                Ok(Some(backup)) => {
+                    tracing::info!(key = %backup.key, size = backup.size, "Scheduled backup complete")
+                }
+                Ok(None) => tracing::debug!("Scheduled backup taken by another instance"),
+                Err(err) => tracing::warn!(error = %err, "Scheduled backup failed"),
+            }
+        }
+        tracing::warn!("Backup schedule never comes due again");
+    });
+}
+
+/// Email weekly digests on the `DIGEST_SCHEDULE` cron schedule.
+///
+/// Every instance wakes at each scheduled time; the first to claim it sends the digests.
+pub fn spawn_weekly_digest(config: &Config, digest_service: DigestService) {
+    let Some(schedule) = config.digest_schedule.clone() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        while let Some(slot) = schedule.next_after(Utc::now()) {
+            let wait = (slot - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+            tracing::debug!(job = "weekly_digest", "Running scheduled job");
+            match digest_service.run_scheduled(slot).await {
+                Ok(Some(sent)) => tracing::info!(sent, "Weekly digests sent"),
+                Ok(None) => tracing::debug!("Weekly digests taken by another instance"),
+                Err(err) => tracing::warn!(error = %err, "Weekly digests failed"),
+            }
+        }
+        tracing::warn!("Digest schedule never comes due again");
+    });
+}
+
+/// Relay outbox events every `EVENT_RELAY_INTERVAL_SECONDS`.
+pub fn spawn_event_relay(config: &Config, event_relay: EventRelay) {
+    if config.event_relay_interval_seconds == 0 {
+        return;
+    }
+    let period = Duration::from_secs(config.event_relay_interval_seconds);
+
+    spawn_periodic("event_relay", period, move || {
+        let event_relay = event_relay.clone();
+        async move {
+            event_relay.relay().await;
+        }
+    });
+}
+
+/// Delete logged webhook attempts older than `WEBHOOK_LOG_RETENTION_DAYS`, hourly.
+pub fn spawn_webhook_log_pruning(config: &Config, job_service: JobService) {
+    let retention_days = config.webhook_log_retention_days;
+    if retention_days == 0 {
+        return;
+    }
+
+    spawn_periodic("webhook_log_pruning", WEBHOOK_LOG_PRUNE_PERIOD, move || {
+        let job_service = job_service.clone();
+        async move {
+            match job_service.prune_webhook_attempts(retention_days).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "Pruned webhook attempts"),
+                Err(err) => tracing::warn!(error = %err, "Webhook log pruning failed"),
+            }
+        }
+    });
+}
+
+/// Schedule the orphaned tag cleanup configured by `ORPHAN_TAG_CLEANUP_*`.
+pub fn spawn_orphan_tag_cleanup(config: &Config, tag_service: TagService) {
+    if config.orphan_tag_cleanup_interval_hours == 0 {
+        return;
+    }
+    let period = Duration::from_secs(config.orphan_tag_cleanup_interval_hours * 60 * 60);
+    let delete = config.orphan_tag_cleanup_delete;
+
+    spawn_periodic("orphan_tag_cleanup", period, move || {
+        let tag_service = tag_service.clone();
+        async move {
+            match tag_service.cleanup_orphans(delete).await {
+                Ok(tags) if tags.is_empty() => {}
+                Ok(tags) => {
+                    let slugs: Vec<&str> = tags.iter().map(|tag| tag.slug.as_str()).collect();
+                    if delete {
+                        tracing::info!(count = tags.len(), tags = ?slugs, "Deleted orphaned tags");
+                    } else {
+                        tracing::info!(count = tags.len(), tags = ?slugs, "Found orphaned tags");
+                    }
+                }
+                Err(err) => tracing::warn!(error = %err, "Orphaned tag cleanup failed"),
+            }
+        }
+    });
+}
+
+/// Remove partial files of chunked uploads that expired unfinished, hourly.
+pub fn spawn_stale_upload_cleanup(media_service: MediaService) {
+    spawn_periodic(
+        "stale_upload_cleanup",
+        STALE_UPLOAD_CHECK_PERIOD,
+        move || {
+            let media_service = media_service.clone();
+            async move {
+                match media_service.purge_stale_uploads().await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::info!(count, "Removed stale partial uploads"),
+                    Err(err) => tracing::warn!(error = %err, "Stale upload cleanup failed"),
+                }
+            }
+        },
+    );
+}
+
+/// Load the GeoIP database now and follow changes to `GEOIP_DATABASE_PATH`
+/// and to the file itself every [`GEOIP_RELOAD_INTERVAL`].
+pub fn spawn_geoip_reload(runtime: RuntimeSettings, geoip: GeoIp) {
+    let reload = move || {
+        let path = runtime.current().geoip_database_path.clone();
+        match geoip.reload_if_changed(path.as_deref().map(Path::new)) {
+            Ok(true) => match &path {
+                Some(path) => tracing::info!(path = %path, "Loaded GeoIP database"),
+                None => tracing::info!("GeoIP database unloaded"),
+            },
+            Ok(false) => {}
+            Err(err) => tracing::warn!(
+                error = %err,
+                "GeoIP database reload failed; keeping the current database"
+            ),
+        }
+    };
+    reload();
+    spawn_periodic("geoip_reload", GEOIP_RELOAD_INTERVAL, move || {
+        reload();
+        async {}
+    });
+}
+
+/// Run status self-checks every `STATUS_CHECK_INTERVAL_SECONDS`, starting right away
+/// so the status page has a reading after startup.
+pub fn spawn_status_checks(config: &Config, status_monitor: StatusMonitor) {
+    if config.status_check_interval_seconds == 0 {
+        return;
+    }
+    let period = Duration::from_secs(config.status_check_interval_seconds);
+
+    let first = status_monitor.clone();
+    tokio::spawn(async move { first.check().await });
+    spawn_periodic("status_checks", period, move || {
+        let status_monitor = status_monitor.clone();
+        async move { status_monitor.check().await }
+    });
+}
+
+/// Warm the Redis caches once at startup unless `CACHE_WARM_ON_STARTUP=false`.
+pub fn spawn_cache_warming(config: &Config, cache_warmer: CacheWarmer) {
+    if !config.cache_warm_on_startup {
+        return;
+    }
+
+    tokio::spawn(async move {
+        match cache_warmer.warm().await {
+            Ok(report) => tracing::info!(
+                sites = report.sites,
+                duration_ms = report.duration_ms,
+                "Caches warmed"
+            ),
+            Err(err) => tracing::warn!(error = %err, "Cache warming failed"),
+        }
+    });
+}
+
+/// Schedule the trending post rollup every `TRENDING_REFRESH_MINUTES`.
+pub fn spawn_trending_rollup(config: &Config, trending_service: TrendingService) {
+    if config.trending_refresh_minutes == 0 {
+        return;
+    }
+    let period = Duration::from_secs(config.trending_refresh_minutes * 60);
+
+    spawn_periodic("trending_rollup", period, move || {
+        let trending_service = trending_service.clone();
+        async move {
+            if let Err(err) = trending_service.refresh_all().await {
+                tracing::warn!(error = %err, "Trending post rollup failed");
+            }
+        }
+    });
+}
@@ -6,13 +6,17 @@ pub mod config;
 pub mod controllers;
 pub mod db;
 pub mod error;
+pub mod jobs;
 pub mod middleware;
 pub mod models;
 pub mod pkg;
 pub mod repositories;
 pub mod response;
 pub mod routes;
+pub mod runtime;
 pub mod services;
+pub mod startup;
+pub mod tls;
 
 pub use config::Config;
 pub use error::AppError;
@@ -3,19 +3,44 @@
 //! A Rust backend with PostgreSQL, Redis-backed JWT authentication, and Blog CMS features.
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
 
+use axum::serve::ListenerExt;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use tracing_subscriber::{
+    filter::LevelFilter, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer,
+};
 
 use personal_website::{
-    config::Config,
-    create_router, db,
-    pkg::redis,
+    config::{Config, RuntimeConfig, DEFAULT_LOG_FILTER},
+    create_router, db, jobs,
+    pkg::{redis, search, storage, GeoIp, JwtKeys, Mailer, ResponseCache, SlowQueryLog},
     repositories::{
-        CategoryRepository, PostRepository, RoleRepository, TagRepository, UserRepository,
+        AccessTokenRepository, AuditRepository, BlocklistRepository, CategoryRepository,
+        ChangelogRepository, FailedJobRepository, HookRepository, LoginEventRepository,
+        MediaRepository, NotificationRepository, OutboxRepository, PollRepository,
+        PostNoteRepository, PostRepository, PostTemplateRepository, RevisionRepository,
+        RoleRepository, SearchRepository, SettingsRepository, SiteRepository, SyncRepository,
+        TagRepository, TaxonomyRepository, TitleTestRepository, UserRepository,
+        WebhookAttemptRepository,
     },
     routes::AppState,
-    services::{AuthService, CategoryService, PostService, TagService},
+    runtime::RuntimeSettings,
+    services::{
+        AccessTokenService, AccountService, ActivityService, ArchiveService, AuthService,
+        BackupService, BlocklistService, BootstrapService, CacheService, CacheWarmer,
+        CategoryService, ChangelogService, DigestService, EmailService, EventBus, EventRelay,
+        GitSync, HookService, JobService, MediaService, NotificationService, PollService,
+        PostLockService, PostNoteService, PostService, PostTemplateService, PreviewService,
+        ProfileService, QuotaService, Revalidator, RevisionService, SearchIndexer, SearchService,
+        SiteService, StatusMonitor, SyncService, TagService, TaxonomyService, TitleTestService,
+        TrendingService, WebhookLog,
+    },
+    startup::{self, AppSlot},
+    tls::{CertStore, TlsListener},
 };
 
 #[tokio::main]
@@ -23,29 +48,139 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load .env file
     dotenvy::dotenv().ok();
 
-    // Initialize tracing
+    // Initialize tracing with a reloadable filter; slow statements are
+    // recorded regardless of the log filter
+    let (filter_layer, filter_handle) = reload::Layer::new(
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| DEFAULT_LOG_FILTER.into()),
+    );
+    let slow_queries = SlowQueryLog::new();
     tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "personal_website=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_filter(filter_layer))
+        .with(slow_queries.layer().with_filter(LevelFilter::WARN))
         .init();
 
-    // Load configuration
-    let config = Config::from_env();
-    tracing::info!("Starting server on {}", config.server_addr());
+    // Load and validate configuration, reporting every problem before exiting
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            tracing::error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    tracing::info!(
+        "Starting server on {} ({} profile)",
+        config.server_addr(),
+        config.profile
+    );
 
-    // Create database pool
-    let db_pool = db::create_pool(&config.database_url)
-        .await
-        .expect("Failed to create database pool");
+    // Load runtime-tunable settings and apply the configured log filter
+    let runtime_config = match RuntimeConfig::load() {
+        Ok(runtime_config) => runtime_config,
+        Err(err) => {
+            tracing::error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    let runtime = RuntimeSettings::default().with_log_reloader(Arc::new(move |directives| {
+        let filter = EnvFilter::try_new(directives).map_err(|e| e.to_string())?;
+        filter_handle.reload(filter).map_err(|e| e.to_string())
+    }));
+    runtime.apply(runtime_config)?;
+
+    // Reload runtime settings on SIGHUP
+    #[cfg(unix)]
+    {
+        let runtime = runtime.clone();
+        tokio::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let mut hangup = match signal(SignalKind::hangup()) {
+                Ok(hangup) => hangup,
+                Err(err) => {
+                    tracing::warn!("Failed to install SIGHUP handler: {}", err);
+                    return;
+                }
+            };
+            while hangup.recv().await.is_some() {
+                if let Err(err) = runtime.reload() {
+                    tracing::warn!("Runtime configuration reload failed: {}", err);
+                }
+            }
+        });
+    }
+
+    // Load the TLS certificate when HTTPS is configured, watching for renewals
+    let cert_store = match config.tls_paths() {
+        Some((cert_path, key_path)) => match CertStore::load(cert_path, key_path) {
+            Ok(cert_store) => {
+                let cert_store = Arc::new(cert_store);
+                cert_store.spawn_reload();
+                Some(cert_store)
+            }
+            Err(err) => {
+                tracing::error!("Failed to load TLS certificate: {}", err);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    // Start listening right away; requests get "starting" answers until the
+    // application router is installed
+    let listener = TcpListener::bind(config.server_addr()).await?;
+    let addr = listener.local_addr()?;
+    let slot = AppSlot::new();
+    let startup_service = slot
+        .router()
+        .into_make_service_with_connect_info::<SocketAddr>();
+    let server = match cert_store {
+        Some(cert_store) => {
+            tracing::info!("Listening on https://{}", addr);
+            let listener = TlsListener::new(listener, cert_store.acceptor()?)?.tap_io(|stream| {
+                if let Err(err) = stream.get_ref().0.set_nodelay(true) {
+                    tracing::debug!("Failed to set TCP_NODELAY: {}", err);
+                }
+            });
+            tokio::spawn(async move { axum::serve(listener, startup_service).await })
+        }
+        None => {
+            tracing::info!("Listening on http://{}", addr);
+            tokio::spawn(async move { axum::serve(listener, startup_service).await })
+        }
+    };
+
+    // Connect to PostgreSQL and Redis, retrying with backoff until reachable
+    let max_backoff = Duration::from_secs(config.startup_max_backoff_seconds);
+    let slow_query_threshold = (config.slow_query_threshold_ms > 0)
+        .then(|| Duration::from_millis(config.slow_query_threshold_ms));
+    let db_pool = match startup::retry_with_backoff(
+        "PostgreSQL",
+        config.startup_max_attempts,
+        max_backoff,
+        || db::create_pool(&config.database_url, slow_query_threshold),
+    )
+    .await
+    {
+        Ok(db_pool) => db_pool,
+        Err(err) => {
+            tracing::error!("Failed to create database pool: {}", err);
+            std::process::exit(1);
+        }
+    };
     tracing::info!("Connected to PostgreSQL");
 
-    // Create Redis connection
-    let redis_conn = redis::create_connection(&config.redis_url)
+    let redis_conn =
+        match startup::retry_with_backoff("Redis", config.startup_max_attempts, max_backoff, || {
+            redis::create_connection(&config.redis_url)
+        })
         .await
-        .expect("Failed to connect to Redis");
+        {
+            Ok(redis_conn) => redis_conn,
+            Err(err) => {
+                tracing::error!("Failed to connect to Redis: {}", err);
+                std::process::exit(1);
+            }
+        };
     tracing::info!("Connected to Redis");
 
     // Create repositories
@@ -54,44 +189,235 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let post_repo = PostRepository::new(db_pool.clone());
     let category_repo = CategoryRepository::new(db_pool.clone());
     let tag_repo = TagRepository::new(db_pool.clone());
+    let media_repo = MediaRepository::new(db_pool.clone());
+    let login_event_repo = LoginEventRepository::new(db_pool.clone());
+    let notification_repo = NotificationRepository::new(db_pool.clone());
+    let access_token_repo = AccessTokenRepository::new(db_pool.clone());
+    let audit_repo = AuditRepository::new(db_pool.clone());
+    let site_repo = SiteRepository::new(db_pool.clone());
+    let taxonomy_repo = TaxonomyRepository::new(db_pool.clone());
+    let search_repo = SearchRepository::new(db_pool.clone());
+    let sync_repo = SyncRepository::new(db_pool.clone());
+    let poll_repo = PollRepository::new(db_pool.clone());
+    let changelog_repo = ChangelogRepository::new(db_pool.clone());
+    let failed_job_repo = FailedJobRepository::new(db_pool.clone());
+    let settings_repo = SettingsRepository::new(db_pool.clone());
+    let outbox_repo = OutboxRepository::new(db_pool.clone());
+    let blocklist_repo = BlocklistRepository::new(db_pool.clone());
+    let title_test_repo = TitleTestRepository::new(db_pool.clone());
+    let post_note_repo = PostNoteRepository::new(db_pool.clone());
+    let revision_repo = RevisionRepository::new(db_pool.clone());
+    let post_template_repo = PostTemplateRepository::new(db_pool.clone());
+    let hook_repo = HookRepository::new(db_pool.clone());
+    let webhook_attempt_repo = WebhookAttemptRepository::new(db_pool.clone());
+
+    // Load JWT signing and verification keys
+    let jwt_keys = JwtKeys::from_config(&config).expect("Failed to load JWT keys");
+
+    // Create the outgoing email sender
+    let mailer = Mailer::from_config(&config).expect("Failed to configure mailer");
+    if config.smtp_url.is_none() {
+        tracing::warn!("SMTP_URL is not set; outgoing emails will only be logged");
+    }
+
+    // Load the GeoIP database, following changes to its path and file
+    let geoip = GeoIp::new();
+    jobs::spawn_geoip_reload(runtime.clone(), geoip.clone());
 
     // Create services
+    let response_cache = ResponseCache::new(redis_conn.clone());
+    let webhook_log = WebhookLog::new(webhook_attempt_repo.clone());
+    let revalidator = Revalidator::spawn(&config, failed_job_repo.clone(), webhook_log.clone());
+    let email_service = EmailService::new(settings_repo, mailer);
+    let event_relay = EventRelay::new(&config, outbox_repo, failed_job_repo.clone(), webhook_log);
+    let job_service = JobService::new(
+        failed_job_repo,
+        webhook_attempt_repo,
+        email_service.clone(),
+        revalidator.clone(),
+        event_relay.clone(),
+    );
+    let notification_service =
+        NotificationService::new(notification_repo.clone(), job_service.clone());
     let auth_service = AuthService::new(
         config.clone(),
+        jwt_keys,
         user_repo.clone(),
         role_repo.clone(),
-        redis_conn,
+        login_event_repo,
+        redis_conn.clone(),
+        email_service.clone(),
+        notification_service.clone(),
+        geoip.clone(),
+    );
+    let profile_service = ProfileService::new(
+        config.clone(),
+        user_repo.clone(),
+        redis_conn.clone(),
+        email_service.clone(),
+        auth_service.clone(),
+    );
+    let trending_service = TrendingService::new(
+        &config,
+        post_repo.clone(),
+        site_repo.clone(),
+        response_cache.clone(),
+        geoip,
+    );
+    let quota_service = QuotaService::new(&config, redis_conn.clone());
+    let blocklist_service = BlocklistService::new(&config, blocklist_repo, redis_conn.clone());
+    let cache_service = CacheService::new(&config, redis_conn.clone());
+    let search_engine = search::from_config(&config, db_pool.clone());
+    tracing::info!(engine = search_engine.name(), "Post search configured");
+    let search_indexer = SearchIndexer::spawn(search_engine.clone(), post_repo.clone());
+    let (git_sync, git_sync_worker) = GitSync::new(&config);
+    let hook_service = HookService::new(hook_repo, git_sync.clone(), &config);
+    let status_monitor = StatusMonitor::new(
+        &config,
+        db_pool.clone(),
+        redis_conn.clone(),
+        vec![
+            search_indexer.queue_depth(),
+            revalidator.queue_depth(),
+            git_sync.queue_depth(),
+        ],
     );
+    let activity_service = ActivityService::new(audit_repo.clone());
+    let revision_service = RevisionService::new(revision_repo);
+    let archive_service = ArchiveService::new(post_repo.clone(), response_cache.clone());
+    let event_bus = EventBus::new(vec![
+        Arc::new(search_indexer),
+        Arc::new(revalidator),
+        Arc::new(git_sync.clone()),
+        Arc::new(activity_service.clone()),
+        Arc::new(revision_service.clone()),
+        Arc::new(archive_service.clone()),
+    ]);
+    let storage = storage::from_config(&config);
+    tracing::info!(backend = storage.name(), "Object storage configured");
+    let backup_service = BackupService::new(&config, storage, redis_conn.clone());
+    let digest_service = DigestService::new(
+        notification_repo,
+        post_repo.clone(),
+        notification_service.clone(),
+        redis_conn.clone(),
+    );
+    let poll_service = PollService::new(poll_repo, redis_conn.clone());
     let post_service = PostService::new(
+        &config,
         post_repo,
         user_repo.clone(),
         category_repo.clone(),
         tag_repo.clone(),
+        media_repo.clone(),
+        search_engine,
+        event_bus,
+        poll_service.clone(),
+        notification_service.clone(),
+    );
+    if let Some(worker) = git_sync_worker {
+        worker.spawn(post_service.clone(), site_repo.clone(), user_repo.clone());
+    }
+    let preview_service = PreviewService::new(&config, redis_conn.clone(), post_service.clone());
+    let post_lock_service = PostLockService::new(&config, redis_conn.clone(), post_service.clone());
+    let post_template_service = PostTemplateService::new(post_template_repo, post_service.clone());
+    let post_note_service = PostNoteService::new(
+        post_note_repo,
+        post_service.clone(),
+        notification_service.clone(),
+    );
+    let title_test_service = TitleTestService::new(&config, title_test_repo, post_service.clone());
+    let access_token_service =
+        AccessTokenService::new(access_token_repo, user_repo.clone(), role_repo.clone());
+    let media_service = MediaService::new(&config, media_repo.clone(), redis_conn.clone());
+    let account_service = AccountService::new(
+        user_repo.clone(),
+        audit_repo,
+        auth_service.clone(),
+        media_service.clone(),
     );
-    let category_service = CategoryService::new(category_repo);
-    let tag_service = TagService::new(tag_repo);
+    let category_service = CategoryService::new(&config, category_repo);
+    let tag_service = TagService::new(&config, tag_repo);
+    let changelog_service = ChangelogService::new(changelog_repo);
+    let cache_warmer = CacheWarmer::new(
+        site_repo.clone(),
+        trending_service.clone(),
+        archive_service.clone(),
+    );
+    let bootstrap_service = BootstrapService::new(
+        &config,
+        category_service.clone(),
+        profile_service.clone(),
+        cache_service.clone(),
+        response_cache,
+    );
+    let site_service = SiteService::new(site_repo);
+    let taxonomy_service = TaxonomyService::new(&config, taxonomy_repo);
+    let search_service = SearchService::new(search_repo);
+    let sync_service = SyncService::new(sync_repo);
+
+    // Start background jobs
+    jobs::spawn_orphan_tag_cleanup(&config, tag_service.clone());
+    jobs::spawn_cache_warming(&config, cache_warmer.clone());
+    jobs::spawn_trending_rollup(&config, trending_service.clone());
+    jobs::spawn_status_checks(&config, status_monitor.clone());
+    jobs::spawn_backups(&config, backup_service.clone());
+    jobs::spawn_weekly_digest(&config, digest_service);
+    jobs::spawn_event_relay(&config, event_relay);
+    jobs::spawn_webhook_log_pruning(&config, job_service.clone());
+    jobs::spawn_stale_upload_cleanup(media_service.clone());
 
     // Create app state
     let app_state = AppState {
         db_pool,
+        redis: redis_conn,
         auth_service,
+        access_token_service,
+        account_service,
+        activity_service,
+        archive_service,
         post_service,
+        post_lock_service,
+        post_note_service,
+        post_template_service,
+        revision_service,
+        poll_service,
+        preview_service,
+        git_sync,
+        hook_service,
+        profile_service,
+        media_service,
+        notification_service,
         category_service,
+        changelog_service,
         tag_service,
+        site_service,
+        status_monitor,
+        backup_service,
+        job_service,
+        email_service,
+        search_service,
+        sync_service,
+        quota_service,
+        blocklist_service,
+        bootstrap_service,
+        taxonomy_service,
+        trending_service,
+        title_test_service,
+        cache_service,
+        cache_warmer,
         user_repo,
         role_repo,
+        runtime,
+        slow_queries,
+        frontend_dir: config.frontend_dir.as_ref().map(PathBuf::from),
     };
 
-    // Create router
-    let app = create_router(app_state);
-
-    // Create listener
-    let addr: SocketAddr = config.server_addr().parse()?;
-    let listener = TcpListener::bind(addr).await?;
-    tracing::info!("Listening on http://{}", addr);
+    // Start serving the application
+    slot.install(create_router(app_state));
+    tracing::info!("Ready to serve requests");
 
-    // Run server
-    axum::serve(listener, app).await?;
+    server.await??;
 
     Ok(())
 }
@@ -1,6 +1,13 @@
 //! Authentication middleware for JWT validation and permission-based RBAC.
+//!
+//! With the runtime setting `permission_debug` on, every permission check of
+//! an authenticated request is logged with its decision and source, and with
+//! `permission_debug_header` also listed in an `X-Permission-Checks` response
+//! header, to find out why a request was refused.
 
-use axum::http::header;
+use std::sync::{Arc, Mutex};
+
+use axum::http::{header, HeaderValue};
 use axum::{
     extract::{Request, State},
     middleware::Next,
@@ -9,9 +16,92 @@ use axum::{
 use uuid::Uuid;
 
 use crate::error::AppError;
+use crate::models::ACCESS_TOKEN_PREFIX;
 use crate::routes::AppState;
 use crate::services::Claims;
 
+/// Response header listing the request's permission checks in debug mode.
+pub const PERMISSION_CHECKS_HEADER: &str = "x-permission-checks";
+/// Most checks listed in the header, keeping it a reasonable size.
+const MAX_HEADER_CHECKS: usize = 50;
+
+/// Why a user holds a permission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionSource {
+    /// The admin role holds every permission
+    Admin,
+    /// The user's role or access token lists the permission
+    Grant,
+}
+
+impl PermissionSource {
+    /// Name used in logs and the debug header.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Grant => "grant",
+        }
+    }
+}
+
+/// One permission check made while handling a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionCheck {
+    pub permission: String,
+    /// Where the permission came from; `None` when it was denied
+    pub source: Option<PermissionSource>,
+}
+
+impl PermissionCheck {
+    /// `permission=allow(source)` or `permission=deny`.
+    fn describe(&self) -> String {
+        match self.source {
+            Some(source) => format!("{}=allow({})", self.permission, source.as_str()),
+            None => format!("{}=deny", self.permission),
+        }
+    }
+}
+
+/// Permission checks recorded for one request while `permission_debug` is on.
+///
+/// Shared by every clone of the request's [`AuthUser`], so checks made in
+/// guards, extractors and services all end up in the same list.
+#[derive(Debug, Clone, Default)]
+pub struct PermissionAudit(Arc<Mutex<Vec<PermissionCheck>>>);
+
+impl PermissionAudit {
+    /// Checks recorded so far, in the order they were made.
+    pub fn checks(&self) -> Vec<PermissionCheck> {
+        self.0
+            .lock()
+            .map(|checks| checks.clone())
+            .unwrap_or_default()
+    }
+
+    fn record(&self, check: PermissionCheck) {
+        if let Ok(mut checks) = self.0.lock() {
+            checks.push(check);
+        }
+    }
+
+    /// The checks as an `X-Permission-Checks` value, if any were made.
+    fn header_value(&self) -> Option<HeaderValue> {
+        let checks = self.checks();
+        if checks.is_empty() {
+            return None;
+        }
+        let mut listed: Vec<String> = checks
+            .iter()
+            .take(MAX_HEADER_CHECKS)
+            .map(PermissionCheck::describe)
+            .collect();
+        if checks.len() > MAX_HEADER_CHECKS {
+            listed.push(format!("+{} more", checks.len() - MAX_HEADER_CHECKS));
+        }
+        HeaderValue::from_str(&listed.join(", ")).ok()
+    }
+}
+
 /// Authenticated user information extracted from JWT.
 #[derive(Debug, Clone)]
 pub struct AuthUser {
@@ -21,21 +111,53 @@ pub struct AuthUser {
     pub role_slug: String,
     /// Cached permissions (loaded on first check)
     pub permissions: Vec<String>,
+    /// Personal access token the request was made with, if not a session
+    pub token_id: Option<Uuid>,
+    /// ID (`jti`) of the session's access token; `None` for personal access tokens
+    pub session_id: Option<String>,
+    /// Where permission checks are recorded while `permission_debug` is on
+    pub permission_audit: Option<PermissionAudit>,
 }
 
 impl AuthUser {
     /// Check if user has admin role.
+    ///
+    /// Always false for personal access tokens, which are limited to their scopes.
     pub fn is_admin(&self) -> bool {
-        self.role_slug == "admin"
+        self.role_slug == "admin" && self.token_id.is_none()
     }
 
     /// Check if user has a specific permission.
     pub fn has_permission(&self, permission: &str) -> bool {
+        let source = self.permission_source(permission);
+        if let Some(audit) = &self.permission_audit {
+            tracing::info!(
+                user_id = %self.id,
+                role = %self.role_slug,
+                token_id = ?self.token_id,
+                permission,
+                decision = if source.is_some() { "allow" } else { "deny" },
+                source = source.map(PermissionSource::as_str),
+                "Permission check"
+            );
+            audit.record(PermissionCheck {
+                permission: permission.to_string(),
+                source,
+            });
+        }
+        source.is_some()
+    }
+
+    /// Where the user's `permission` comes from, or `None` if they lack it.
+    pub fn permission_source(&self, permission: &str) -> Option<PermissionSource> {
         // Admin has all permissions
         if self.is_admin() {
-            return true;
+            return Some(PermissionSource::Admin);
         }
-        self.permissions.iter().any(|p| p == permission)
+        self.permissions
+            .iter()
+            .any(|p| p == permission)
+            .then_some(PermissionSource::Grant)
     }
 
     /// Check if user can create resources of a given type.
@@ -65,7 +187,7 @@ impl AuthUser {
 }
 
 /// Extract bearer token from Authorization header.
-fn extract_bearer_token(request: &Request) -> Option<String> {
+pub(crate) fn extract_bearer_token(request: &Request) -> Option<String> {
     request
         .headers()
         .get(header::AUTHORIZATION)
@@ -89,21 +211,46 @@ async fn create_auth_user(claims: &Claims, state: &AppState) -> Result<AuthUser,
         role_id,
         role_slug: claims.role_slug.clone(),
         permissions,
+        token_id: None,
+        session_id: Some(claims.jti.clone()),
+        permission_audit: None,
     })
 }
 
+/// Resolve a bearer token, either a session JWT or a personal access token.
+pub(crate) async fn authenticate(token: &str, state: &AppState) -> Result<AuthUser, AppError> {
+    if token.starts_with(ACCESS_TOKEN_PREFIX) {
+        return state.access_token_service.authenticate(token).await;
+    }
+    let claims = state.auth_service.validate_access_token(token).await?;
+    create_auth_user(&claims, state).await
+}
+
+/// The request's user, reusing the one resolved by the quota middleware when present.
+async fn authenticate_request(
+    request: &mut Request,
+    state: &AppState,
+) -> Result<AuthUser, AppError> {
+    if let Some(auth_user) = request.extensions_mut().remove::<AuthUser>() {
+        return Ok(auth_user);
+    }
+    let token = extract_bearer_token(request).ok_or(AppError::Unauthorized)?;
+    authenticate(&token, state).await
+}
+
 /// Authentication middleware - requires valid JWT token.
 pub async fn auth_middleware(
     State(state): State<AppState>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, AppError> {
-    let token = extract_bearer_token(&request).ok_or(AppError::Unauthorized)?;
-    let claims = state.auth_service.validate_access_token(&token).await?;
-    let auth_user = create_auth_user(&claims, &state).await?;
+    let mut auth_user = authenticate_request(&mut request, &state).await?;
+    let audit = start_permission_audit(&mut auth_user, &state);
 
     request.extensions_mut().insert(auth_user);
-    Ok(next.run(request).await)
+    let mut response = next.run(request).await;
+    add_permission_header(&mut response, audit);
+    Ok(response)
 }
 
 /// Admin-only middleware - requires admin role.
@@ -112,16 +259,17 @@ pub async fn admin_middleware(
     mut request: Request,
     next: Next,
 ) -> Result<Response, AppError> {
-    let token = extract_bearer_token(&request).ok_or(AppError::Unauthorized)?;
-    let claims = state.auth_service.validate_access_token(&token).await?;
-    let auth_user = create_auth_user(&claims, &state).await?;
+    let mut auth_user = authenticate_request(&mut request, &state).await?;
 
     if !auth_user.is_admin() {
         return Err(AppError::Forbidden("Admin access required".to_string()));
     }
+    let audit = start_permission_audit(&mut auth_user, &state);
 
     request.extensions_mut().insert(auth_user);
-    Ok(next.run(request).await)
+    let mut response = next.run(request).await;
+    add_permission_header(&mut response, audit);
+    Ok(response)
 }
 
 /// Optional auth middleware - extracts user if token present, continues if not.
@@ -130,18 +278,36 @@ pub async fn optional_auth_middleware(
     mut request: Request,
     next: Next,
 ) -> Response {
-    let auth_user: Option<AuthUser> = if let Some(token) = extract_bearer_token(&request) {
-        if let Ok(claims) = state.auth_service.validate_access_token(&token).await {
-            create_auth_user(&claims, &state).await.ok()
-        } else {
-            None
-        }
-    } else {
-        None
-    };
+    let mut auth_user = authenticate_request(&mut request, &state).await.ok();
+    let audit = auth_user
+        .as_mut()
+        .and_then(|auth_user| start_permission_audit(auth_user, &state));
 
     request.extensions_mut().insert(auth_user);
-    next.run(request).await
+    let mut response = next.run(request).await;
+    add_permission_header(&mut response, audit);
+    response
+}
+
+/// Record the user's permission checks while `permission_debug` is on,
+/// returning the audit when they should also be listed in the response.
+fn start_permission_audit(auth_user: &mut AuthUser, state: &AppState) -> Option<PermissionAudit> {
+    let runtime = state.runtime.current();
+    if !runtime.permission_debug {
+        return None;
+    }
+    let audit = PermissionAudit::default();
+    auth_user.permission_audit = Some(audit.clone());
+    runtime.permission_debug_header.then_some(audit)
+}
+
+/// List the request's permission checks in `X-Permission-Checks`.
+fn add_permission_header(response: &mut Response, audit: Option<PermissionAudit>) {
+    if let Some(value) = audit.and_then(|audit| audit.header_value()) {
+        response
+            .headers_mut()
+            .insert(PERMISSION_CHECKS_HEADER, value);
+    }
 }
 
 #[cfg(test)]
@@ -156,6 +322,9 @@ mod tests {
             role_id: Uuid::new_v4(),
             role_slug: "admin".to_string(),
             permissions: vec![],
+            token_id: None,
+            session_id: None,
+            permission_audit: None,
         };
         assert!(admin.is_admin());
         assert!(admin.has_permission("anything")); // Admin has all permissions
@@ -170,6 +339,9 @@ mod tests {
                 "posts:create".to_string(),
                 "posts:update".to_string(),
             ],
+            token_id: None,
+            session_id: None,
+            permission_audit: None,
         };
         assert!(!writer.is_admin());
         assert!(writer.can_create("posts"));
@@ -187,6 +359,9 @@ mod tests {
             role_id: Uuid::new_v4(),
             role_slug: "admin".to_string(),
             permissions: vec![],
+            token_id: None,
+            session_id: None,
+            permission_audit: None,
         };
         assert!(admin.is_admin());
 
@@ -196,7 +371,67 @@ mod tests {
             role_id: Uuid::new_v4(),
             role_slug: "viewer".to_string(),
             permissions: vec!["posts:read".to_string()],
+            token_id: None,
+            session_id: None,
+            permission_audit: None,
         };
         assert!(!viewer.is_admin());
+
+        // Tokens never carry admin privileges, only their scopes
+        let admin_token = AuthUser {
+            token_id: Some(Uuid::new_v4()),
+            session_id: None,
+            permission_audit: None,
+            permissions: vec!["posts:create".to_string()],
+            ..admin
+        };
+        assert!(!admin_token.is_admin());
+        assert!(admin_token.has_permission("posts:create"));
+        assert!(!admin_token.has_permission("users:delete"));
+    }
+
+    #[test]
+    fn test_permission_audit() {
+        let audit = PermissionAudit::default();
+        let writer = AuthUser {
+            id: Uuid::new_v4(),
+            email: "writer@test.com".to_string(),
+            role_id: Uuid::new_v4(),
+            role_slug: "writer".to_string(),
+            permissions: vec!["posts:create".to_string()],
+            token_id: None,
+            session_id: None,
+            permission_audit: Some(audit.clone()),
+        };
+        let admin = AuthUser {
+            role_slug: "admin".to_string(),
+            ..writer.clone()
+        };
+
+        assert!(writer.can_create("posts"));
+        assert!(!writer.clone().can_publish());
+        assert!(admin.has_permission("users:delete"));
+        assert_eq!(
+            audit.checks(),
+            vec![
+                PermissionCheck {
+                    permission: "posts:create".to_string(),
+                    source: Some(PermissionSource::Grant),
+                },
+                PermissionCheck {
+                    permission: "posts:publish".to_string(),
+                    source: None,
+                },
+                PermissionCheck {
+                    permission: "users:delete".to_string(),
+                    source: Some(PermissionSource::Admin),
+                },
+            ]
+        );
+        assert_eq!(
+            audit.header_value().unwrap(),
+            "posts:create=allow(grant), posts:publish=deny, users:delete=allow(admin)"
+        );
+        assert!(PermissionAudit::default().header_value().is_none());
     }
 }
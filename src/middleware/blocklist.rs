@@ -0,0 +1,36 @@
+//! Blocklist middleware for public write endpoints.
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use super::ClientIp;
+use crate::error::AppError;
+use crate::services::BlocklistService;
+
+/// Reject requests from blocked IP addresses with 403.
+///
+/// Fails open when the verdict cannot be determined, so an outage of the
+/// database or Redis does not take registration down with it.
+pub async fn blocklist_middleware(
+    State(blocklist_service): State<BlocklistService>,
+    ClientIp(ip): ClientIp,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    match blocklist_service.is_ip_blocked(ip).await {
+        Ok(true) => {
+            tracing::info!(%ip, path = %request.uri().path(), "Blocked request from blocklisted IP");
+            Err(AppError::Forbidden(
+                "Requests from your network are not accepted".to_string(),
+            ))
+        }
+        Ok(false) => Ok(next.run(request).await),
+        Err(err) => {
+            tracing::warn!(error = %err, "Blocklist check failed");
+            Ok(next.run(request).await)
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! HTTP caching headers for public content, driven by `CACHE_POLICIES`.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+
+use crate::models::Site;
+use crate::services::CacheService;
+
+/// Set `Cache-Control`, `Last-Modified` and `Vary` on successful GET responses
+/// for paths covered by a cache policy.
+///
+/// Responses that already carry `Cache-Control` are left alone. Requests with
+/// credentials may see unpublished content, so theirs are marked private.
+pub async fn cache_headers_middleware(
+    State(cache_service): State<CacheService>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let max_age = match *request.method() {
+        Method::GET | Method::HEAD => cache_service.max_age(request.uri().path()),
+        _ => None,
+    };
+    let Some(max_age) = max_age else {
+        return next.run(request).await;
+    };
+    let authenticated = request.headers().contains_key(header::AUTHORIZATION);
+    let last_modified = match request.extensions().get::<Site>() {
+        Some(site) => match cache_service.last_modified(site.id).await {
+            Ok(last_modified) => last_modified,
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to read content modification time");
+                None
+            }
+        },
+        None => None,
+    };
+
+    let mut response = next.run(request).await;
+    if response.status().is_success() && !response.headers().contains_key(header::CACHE_CONTROL) {
+        set_cache_headers(
+            response.headers_mut(),
+            max_age,
+            authenticated,
+            last_modified,
+        );
+    }
+    response
+}
+
+/// Record a content change after every successful write, moving the site's
+/// `Last-Modified`.
+pub async fn content_modified_middleware(
+    State(cache_service): State<CacheService>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let writes = !matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    let site_id = request.extensions().get::<Site>().map(|site| site.id);
+    let response = next.run(request).await;
+    if let Some(site_id) = site_id.filter(|_| writes && response.status().is_success()) {
+        if let Err(err) = cache_service.touch(site_id).await {
+            tracing::warn!(error = %err, "Failed to record content modification");
+        }
+    }
+    response
+}
+
+fn set_cache_headers(
+    headers: &mut HeaderMap,
+    max_age: u64,
+    authenticated: bool,
+    last_modified: Option<DateTime<Utc>>,
+) {
+    let cache_control = match (authenticated, max_age) {
+        (true, _) => "private, no-cache".to_string(),
+        (false, 0) => "public, no-cache".to_string(),
+        (false, max_age) => format!("public, max-age={}", max_age),
+    };
+    if let Ok(value) = HeaderValue::from_str(&cache_control) {
+        headers.insert(header::CACHE_CONTROL, value);
+    }
+    // Handlers that know when their own content changed set it themselves
+    if !headers.contains_key(header::LAST_MODIFIED) {
+        if let Some(value) = last_modified.and_then(last_modified_header) {
+            headers.insert(header::LAST_MODIFIED, value);
+        }
+    }
+    headers.append(header::VARY, HeaderValue::from_static("Authorization"));
+}
+
+/// `Last-Modified` header value for `time`.
+pub fn last_modified_header(time: DateTime<Utc>) -> Option<HeaderValue> {
+    HeaderValue::from_str(&http_date(time)).ok()
+}
+
+/// Whether the request's `If-Modified-Since` is no earlier than
+/// `last_modified`, so a `304 Not Modified` can be sent instead.
+///
+/// HTTP dates have whole seconds, so sub-second changes are ignored.
+pub fn not_modified_since(headers: &HeaderMap, last_modified: DateTime<Utc>) -> bool {
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+        .is_some_and(|since| last_modified.timestamp() <= since.timestamp())
+}
+
+/// Format a timestamp as an HTTP date (RFC 9110 IMF-fixdate).
+fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_date() {
+        let time = DateTime::from_timestamp(784_111_777, 0).unwrap();
+        assert_eq!(http_date(time), "Sun, 06 Nov 1994 08:49:37 GMT");
+    }
+
+    #[test]
+    fn test_not_modified_since() {
+        let last_modified = DateTime::from_timestamp_millis(784_111_777_500).unwrap();
+        let request = |since: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                header::IF_MODIFIED_SINCE,
+                HeaderValue::from_str(since).unwrap(),
+            );
+            headers
+        };
+
+        assert!(not_modified_since(
+            &request("Sun, 06 Nov 1994 08:49:37 GMT"),
+            last_modified
+        ));
+        assert!(not_modified_since(
+            &request("Mon, 07 Nov 1994 08:49:37 GMT"),
+            last_modified
+        ));
+        assert!(!not_modified_since(
+            &request("Sun, 06 Nov 1994 08:49:36 GMT"),
+            last_modified
+        ));
+        assert!(!not_modified_since(&request("yesterday"), last_modified));
+        assert!(!not_modified_since(&HeaderMap::new(), last_modified));
+    }
+
+    #[test]
+    fn test_set_cache_headers() {
+        let last_modified = DateTime::from_timestamp(784_111_777, 0);
+
+        let mut headers = HeaderMap::new();
+        set_cache_headers(&mut headers, 60, false, last_modified);
+        assert_eq!(headers[header::CACHE_CONTROL], "public, max-age=60");
+        assert_eq!(
+            headers[header::LAST_MODIFIED],
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+        assert_eq!(headers[header::VARY], "Authorization");
+
+        let mut headers = HeaderMap::new();
+        set_cache_headers(&mut headers, 60, true, None);
+        assert_eq!(headers[header::CACHE_CONTROL], "private, no-cache");
+        assert!(!headers.contains_key(header::LAST_MODIFIED));
+
+        let mut headers = HeaderMap::new();
+        set_cache_headers(&mut headers, 0, false, None);
+        assert_eq!(headers[header::CACHE_CONTROL], "public, no-cache");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::LAST_MODIFIED,
+            HeaderValue::from_static("Sat, 05 Nov 1994 08:49:37 GMT"),
+        );
+        set_cache_headers(&mut headers, 60, false, last_modified);
+        assert_eq!(
+            headers[header::LAST_MODIFIED],
+            "Sat, 05 Nov 1994 08:49:37 GMT"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Client IP address and request origin extraction.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, FromRef, FromRequestParts},
+    http::{header, request::Parts, HeaderMap},
+};
+
+use crate::runtime::RuntimeSettings;
+
+/// IP address of the client making the request.
+///
+/// Uses the peer address of the connection, or the last `X-Forwarded-For`
+/// entry when `trust_proxy_headers` is enabled in the runtime settings. Falls
+/// back to `0.0.0.0` when neither is available (e.g. in router tests).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    RuntimeSettings: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let runtime = RuntimeSettings::from_ref(state);
+        let forwarded = if runtime.current().trust_proxy_headers {
+            forwarded_for(&parts.headers)
+        } else {
+            None
+        };
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        Ok(Self(
+            forwarded
+                .or(peer)
+                .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        ))
+    }
+}
+
+/// Headers a trusted proxy or CDN may use to pass the client's country.
+const COUNTRY_HEADERS: &[&str] = &["cf-ipcountry", "x-country-code"];
+/// Longest user agent kept; anything beyond is truncated.
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// Where a request comes from: IP address, user agent and, when a trusted
+/// proxy provides it, the client's country.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientInfo {
+    pub ip: IpAddr,
+    pub user_agent: Option<String>,
+    /// ISO 3166-1 alpha-2 code, only read when proxy headers are trusted
+    pub country: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    RuntimeSettings: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, state).await?;
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(MAX_USER_AGENT_LEN).collect());
+        let country = if RuntimeSettings::from_ref(state)
+            .current()
+            .trust_proxy_headers
+        {
+            country(&parts.headers)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            ip,
+            user_agent,
+            country,
+        })
+    }
+}
+
+/// Country code from the first proxy header carrying a valid one.
+///
+/// `XX` (unknown) and `T1` (Tor) as sent by Cloudflare are ignored.
+fn country(headers: &HeaderMap) -> Option<String> {
+    COUNTRY_HEADERS
+        .iter()
+        .filter_map(|name| headers.get(*name)?.to_str().ok())
+        .map(|value| value.trim().to_ascii_uppercase())
+        .find(|code| {
+            code.len() == 2
+                && code.chars().all(|c| c.is_ascii_alphabetic())
+                && code != "XX"
+                && code != "T1"
+        })
+}
+
+/// Last address in the `X-Forwarded-For` header, i.e. the one the nearest
+/// proxy saw. Earlier entries are client-supplied and cannot be trusted.
+fn forwarded_for(headers: &HeaderMap) -> Option<IpAddr> {
+    headers
+        .get("x-forwarded-for")?
+        .to_str()
+        .ok()?
+        .rsplit(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarded_for() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(forwarded_for(&headers), None);
+
+        headers.insert("x-forwarded-for", "10.0.0.1, 203.0.113.7".parse().unwrap());
+        assert_eq!(
+            forwarded_for(&headers),
+            Some("203.0.113.7".parse().unwrap())
+        );
+
+        headers.insert("x-forwarded-for", "garbage".parse().unwrap());
+        assert_eq!(forwarded_for(&headers), None);
+    }
+
+    #[test]
+    fn test_country() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(country(&headers), None);
+
+        headers.insert("cf-ipcountry", "XX".parse().unwrap());
+        assert_eq!(country(&headers), None);
+
+        headers.insert("x-country-code", "id".parse().unwrap());
+        assert_eq!(country(&headers), Some("ID".to_string()));
+
+        headers.insert("cf-ipcountry", "NL".parse().unwrap());
+        assert_eq!(country(&headers), Some("NL".to_string()));
+    }
+}
@@ -0,0 +1,40 @@
+//! Headers and API 404s for the single-page frontend served by the binary.
+
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+
+/// Path prefix of the bundler's content-hashed assets.
+pub const FRONTEND_ASSETS_PREFIX: &str = "/assets/";
+
+/// Keep unknown `/api` paths out of the frontend fallback and set cache headers.
+///
+/// Hashed assets never change under the same name, so they are cached for a
+/// year; `index.html` and other files are revalidated on every use so a new
+/// deployment is picked up right away.
+pub async fn frontend_middleware(request: Request, next: Next) -> Response {
+    let path = request.uri().path();
+    if path == "/api" || path.starts_with("/api/") {
+        return AppError::NotFound("Route not found".to_string()).into_response();
+    }
+    let immutable = path.starts_with(FRONTEND_ASSETS_PREFIX);
+
+    let mut response = next.run(request).await;
+    if response.status().is_success() {
+        let cache_control = if immutable {
+            "public, max-age=31536000, immutable"
+        } else {
+            "no-cache"
+        };
+        response.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
+        );
+    }
+    response
+}
@@ -0,0 +1,49 @@
+//! Maintenance mode middleware driven by the runtime settings.
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::AppError;
+use crate::runtime::RuntimeSettings;
+
+/// Path prefixes that stay reachable during maintenance so admins can sign in
+/// and switch it off again.
+const MAINTENANCE_EXEMPT_PREFIXES: &[&str] =
+    &["/api/health", "/api/auth/", "/api/admin/", "/.well-known/"];
+
+/// Reject requests with 503 while maintenance mode is enabled.
+pub async fn maintenance_middleware(
+    State(runtime): State<RuntimeSettings>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if runtime.current().maintenance_mode && !is_exempt(request.uri().path()) {
+        return Err(AppError::ServiceUnavailable(
+            "Site is under maintenance".to_string(),
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+fn is_exempt(path: &str) -> bool {
+    MAINTENANCE_EXEMPT_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_exempt() {
+        assert!(is_exempt("/api/health"));
+        assert!(is_exempt("/api/auth/login"));
+        assert!(is_exempt("/api/admin/config/reload"));
+        assert!(!is_exempt("/api/posts"));
+    }
+}
@@ -1,5 +1,23 @@
 //! Middleware modules.
 
 pub mod auth;
+pub mod blocklist;
+pub mod cache;
+pub mod client_ip;
+pub mod frontend;
+pub mod maintenance;
+pub mod permission;
+pub mod quota;
+pub mod site;
+pub mod sudo;
 
 pub use auth::*;
+pub use blocklist::*;
+pub use cache::*;
+pub use client_ip::*;
+pub use frontend::*;
+pub use maintenance::*;
+pub use permission::*;
+pub use quota::*;
+pub use site::*;
+pub use sudo::*;
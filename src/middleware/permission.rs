@@ -0,0 +1,238 @@
+//! Per-permission route guards.
+//!
+//! Routes declare the permission they need in `routes.rs` with
+//! [`require_permission`], or handlers can ask for it in their signature with
+//! the [`RequirePermission`] extractor. Both expect [`AuthUser`] to have been
+//! inserted by `auth_middleware` first.
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::pin::Pin;
+
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::request::Parts,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+
+/// Future returned by the [`require_permission`] guard.
+pub type GuardFuture = Pin<Box<dyn Future<Output = Result<Response, AppError>> + Send>>;
+
+/// Check that the authenticated user holds `permission`.
+fn authorize(auth_user: Option<&AuthUser>, permission: &str) -> Result<(), AppError> {
+    authorize_any(auth_user, &[permission])
+}
+
+/// Check that the authenticated user holds at least one of `permissions`.
+fn authorize_any(auth_user: Option<&AuthUser>, permissions: &[&str]) -> Result<(), AppError> {
+    let auth_user = auth_user.ok_or(AppError::Unauthorized)?;
+    if !permissions.iter().any(|p| auth_user.has_permission(p)) {
+        return Err(AppError::Forbidden(format!(
+            "Missing permission: {}",
+            permissions.join(" or ")
+        )));
+    }
+    Ok(())
+}
+
+/// Build a middleware that rejects requests whose user lacks `permission`.
+///
+/// Use with `middleware::from_fn` as a route layer inside a router that is
+/// already wrapped in `auth_middleware`:
+///
+/// ```ignore
+/// .route("/posts", post(create_post).route_layer(from_fn(require_permission("posts:create"))))
+/// ```
+pub fn require_permission(
+    permission: &'static str,
+) -> impl Fn(Request, Next) -> GuardFuture + Clone + Send + Sync + 'static {
+    move |request: Request, next: Next| {
+        Box::pin(async move {
+            authorize(request.extensions().get::<AuthUser>(), permission)?;
+            Ok(next.run(request).await)
+        })
+    }
+}
+
+/// Like [`require_permission`], but any one of `permissions` is enough.
+///
+/// Useful when the handler makes the final decision, e.g. `posts:update_own`
+/// versus `posts:update_any`.
+pub fn require_any_permission(
+    permissions: &'static [&'static str],
+) -> impl Fn(Request, Next) -> GuardFuture + Clone + Send + Sync + 'static {
+    move |request: Request, next: Next| {
+        Box::pin(async move {
+            authorize_any(request.extensions().get::<AuthUser>(), permissions)?;
+            Ok(next.run(request).await)
+        })
+    }
+}
+
+/// A permission that can be required at the type level.
+pub trait Permission {
+    /// Permission name, e.g. `posts:create`.
+    const NAME: &'static str;
+}
+
+macro_rules! permissions {
+    ($($name:ident => $permission:literal),* $(,)?) => {
+        $(
+            #[doc = concat!("The `", $permission, "` permission.")]
+            pub struct $name;
+
+            impl Permission for $name {
+                const NAME: &'static str = $permission;
+            }
+        )*
+    };
+}
+
+permissions! {
+    PostsCreate => "posts:create",
+    PostsUpdateOwn => "posts:update_own",
+    PostsUpdateAny => "posts:update_any",
+    PostsDeleteOwn => "posts:delete_own",
+    PostsDeleteAny => "posts:delete_any",
+    PostsPublish => "posts:publish",
+    CategoriesCreate => "categories:create",
+    CategoriesUpdate => "categories:update",
+    CategoriesDelete => "categories:delete",
+    TagsCreate => "tags:create",
+    TagsUpdate => "tags:update",
+    TagsDelete => "tags:delete",
+    PollsCreate => "polls:create",
+    PollsUpdate => "polls:update",
+    PollsDelete => "polls:delete",
+    MediaCreate => "media:create",
+    MediaUpdate => "media:update",
+    MediaDelete => "media:delete",
+    UsersRead => "users:read",
+    UsersCreate => "users:create",
+    UsersUpdate => "users:update",
+    UsersDelete => "users:delete",
+}
+
+/// Extractor yielding the [`AuthUser`] only if they hold permission `P`.
+pub struct RequirePermission<P: Permission> {
+    pub user: AuthUser,
+    _permission: PhantomData<P>,
+}
+
+impl<P: Permission> Deref for RequirePermission<P> {
+    type Target = AuthUser;
+
+    fn deref(&self) -> &Self::Target {
+        &self.user
+    }
+}
+
+impl<P, S> FromRequestParts<S> for RequirePermission<P>
+where
+    P: Permission,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let auth_user = parts.extensions.get::<AuthUser>();
+        authorize(auth_user, P::NAME)?;
+        Ok(Self {
+            user: auth_user.cloned().ok_or(AppError::Unauthorized)?,
+            _permission: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request as HttpRequest;
+    use uuid::Uuid;
+
+    fn user(role_slug: &str, permissions: &[&str]) -> AuthUser {
+        AuthUser {
+            id: Uuid::new_v4(),
+            email: "user@test.com".to_string(),
+            role_id: Uuid::new_v4(),
+            role_slug: role_slug.to_string(),
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            token_id: None,
+            session_id: None,
+            permission_audit: None,
+        }
+    }
+
+    #[test]
+    fn test_authorize() {
+        assert!(matches!(
+            authorize(None, "posts:create"),
+            Err(AppError::Unauthorized)
+        ));
+
+        let writer = user("writer", &["posts:create"]);
+        assert!(authorize(Some(&writer), "posts:create").is_ok());
+        assert!(matches!(
+            authorize(Some(&writer), "posts:delete"),
+            Err(AppError::Forbidden(_))
+        ));
+
+        let admin = user("admin", &[]);
+        assert!(authorize(Some(&admin), "posts:delete").is_ok());
+
+        let own = ["posts:update_own", "posts:update_any"];
+        let writer = user("writer", &["posts:update_own"]);
+        assert!(authorize_any(Some(&writer), &own).is_ok());
+        assert!(matches!(
+            authorize_any(Some(&user("viewer", &[])), &own),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_require_permission_extractor() {
+        let (mut parts, _) = HttpRequest::new(()).into_parts();
+        parts.extensions.insert(user("writer", &["posts:create"]));
+
+        let allowed = RequirePermission::<PostsCreate>::from_request_parts(&mut parts, &()).await;
+        assert_eq!(allowed.unwrap().email, "user@test.com");
+
+        let denied = RequirePermission::<PostsDeleteAny>::from_request_parts(&mut parts, &()).await;
+        assert!(matches!(denied, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_require_permission_access_token() {
+        use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+        use tower::ServiceExt;
+
+        let router = Router::new().route(
+            "/sync/changes",
+            get(|| async {}).route_layer(middleware::from_fn(require_permission("sync:read"))),
+        );
+        let call = |auth_user: AuthUser| {
+            let mut request = HttpRequest::get("/sync/changes")
+                .body(Body::empty())
+                .unwrap();
+            request.extensions_mut().insert(auth_user);
+            router.clone().oneshot(request)
+        };
+
+        // A token scoped to sync:read passes even though tokens are never admins
+        let mut scoped = user("admin", &["sync:read"]);
+        scoped.token_id = Some(Uuid::new_v4());
+        assert!(!scoped.is_admin());
+        let response = call(scoped).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut unscoped = user("admin", &["posts:create"]);
+        unscoped.token_id = Some(Uuid::new_v4());
+        let response = call(unscoped).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,105 @@
+//! Request quota middleware reporting usage in `X-RateLimit-*` headers.
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+
+use super::auth::{authenticate, extract_bearer_token};
+use super::ClientIp;
+use crate::error::AppError;
+use crate::models::{QuotaClient, QuotaStatus};
+use crate::routes::AppState;
+
+const LIMIT_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+const REMAINING_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+const RESET_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+/// Count API requests against the caller's quota and reject them with 429
+/// once it is used up.
+///
+/// Requests with a valid bearer token count against the user, everything else
+/// against the client IP. The resolved user is kept in the request so the auth
+/// middlewares do not verify the token again. Quotas fail open when Redis is
+/// unavailable.
+pub async fn quota_middleware(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if !request.uri().path().starts_with("/api/") {
+        return next.run(request).await;
+    }
+
+    let mut client = QuotaClient::Ip(ip);
+    if let Some(token) = extract_bearer_token(&request) {
+        if let Ok(auth_user) = authenticate(&token, &state).await {
+            client = QuotaClient::User(auth_user.id);
+            request.extensions_mut().insert(auth_user);
+        }
+    }
+
+    let status = match state.quota_service.consume(client).await {
+        Ok(status) => status,
+        Err(err) => {
+            tracing::warn!(error = %err, "Request quota check failed");
+            return next.run(request).await;
+        }
+    };
+
+    let mut response = if status.exceeded() {
+        let retry_after = (status.reset_at - Utc::now()).num_seconds().max(1) as u64;
+        AppError::TooManyRequests(retry_after).into_response()
+    } else {
+        next.run(request).await
+    };
+    set_quota_headers(response.headers_mut(), &status);
+    response
+}
+
+/// Add the `X-RateLimit-*` headers; unlimited clients get none.
+fn set_quota_headers(headers: &mut HeaderMap, status: &QuotaStatus) {
+    if status.limit == 0 {
+        return;
+    }
+    headers.insert(LIMIT_HEADER, HeaderValue::from(status.limit));
+    headers.insert(REMAINING_HEADER, HeaderValue::from(status.remaining));
+    headers.insert(
+        RESET_HEADER,
+        HeaderValue::from(status.reset_at.timestamp().max(0) as u64),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    #[test]
+    fn test_set_quota_headers() {
+        let mut status = QuotaStatus {
+            client: "ip:203.0.113.7".to_string(),
+            limit: 100,
+            used: 101,
+            remaining: 0,
+            reset_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        };
+        assert!(status.exceeded());
+
+        let mut headers = HeaderMap::new();
+        set_quota_headers(&mut headers, &status);
+        assert_eq!(headers[LIMIT_HEADER], "100");
+        assert_eq!(headers[REMAINING_HEADER], "0");
+        assert_eq!(headers[RESET_HEADER], "1700000000");
+
+        status.limit = 0;
+        let mut headers = HeaderMap::new();
+        set_quota_headers(&mut headers, &status);
+        assert!(headers.is_empty());
+        assert!(!status.exceeded());
+    }
+}
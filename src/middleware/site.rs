@@ -0,0 +1,88 @@
+//! Site resolution from the request host.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, Uri},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::AppError;
+use crate::runtime::RuntimeSettings;
+use crate::services::SiteService;
+
+/// Resolve the site a request is for and insert it as an `Extension<Site>`.
+///
+/// Uses the `Host` header, or `X-Forwarded-Host` when `trust_proxy_headers`
+/// is enabled. Hosts matching no site are served by the default site.
+pub async fn site_middleware(
+    State(site_service): State<SiteService>,
+    State(runtime): State<RuntimeSettings>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let host = request_host(
+        request.headers(),
+        request.uri(),
+        runtime.current().trust_proxy_headers,
+    );
+    let site = site_service.resolve(host.as_deref()).await?;
+    request.extensions_mut().insert(site);
+
+    Ok(next.run(request).await)
+}
+
+/// Host the client addressed, before normalisation.
+///
+/// Like `X-Forwarded-For`, only the last `X-Forwarded-Host` entry (set by the
+/// nearest proxy) is used; earlier ones are client-supplied.
+fn request_host(headers: &HeaderMap, uri: &Uri, trust_proxy_headers: bool) -> Option<String> {
+    let header_value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.rsplit(',').next().unwrap_or(value).trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    let forwarded = if trust_proxy_headers {
+        header_value("x-forwarded-host")
+    } else {
+        None
+    };
+    forwarded
+        .or_else(|| header_value(header::HOST.as_str()))
+        .or_else(|| uri.host().map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_host() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, "blog.example.com:8080".parse().unwrap());
+        headers.insert(
+            "x-forwarded-host",
+            "spoofed.example, side.dev".parse().unwrap(),
+        );
+        let uri: Uri = "/api/posts".parse().unwrap();
+
+        assert_eq!(
+            request_host(&headers, &uri, false).as_deref(),
+            Some("blog.example.com:8080")
+        );
+        assert_eq!(
+            request_host(&headers, &uri, true).as_deref(),
+            Some("side.dev")
+        );
+
+        let absolute: Uri = "http://other.example/api/posts".parse().unwrap();
+        assert_eq!(
+            request_host(&HeaderMap::new(), &absolute, false).as_deref(),
+            Some("other.example")
+        );
+        assert_eq!(request_host(&HeaderMap::new(), &uri, true), None);
+    }
+}
@@ -0,0 +1,107 @@
+//! Sudo mode guard for destructive actions.
+
+use std::future::Future;
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::services::AuthService;
+
+/// Require the authenticated user to have confirmed their password recently.
+///
+/// Apply per route with `route_layer` inside routes that already run
+/// `auth_middleware` or `admin_middleware`. Clients receive
+/// `REAUTHENTICATION_REQUIRED` and should call `POST /api/auth/sudo` before
+/// retrying. The confirmation only counts for the session it was made in, so
+/// other sessions and personal access tokens of the same user stay guarded.
+pub async fn sudo_middleware(
+    State(auth_service): State<AuthService>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    require_sudo(request.extensions().get::<AuthUser>(), |jti| {
+        auth_service.has_sudo(jti)
+    })
+    .await?;
+
+    Ok(next.run(request).await)
+}
+
+/// Check that the request's session is in sudo mode, asking `has_sudo` about
+/// its access token ID.
+async fn require_sudo<'a, F, Fut>(
+    auth_user: Option<&'a AuthUser>,
+    has_sudo: F,
+) -> Result<(), AppError>
+where
+    F: FnOnce(&'a str) -> Fut,
+    Fut: Future<Output = Result<bool, AppError>>,
+{
+    let auth_user = auth_user.ok_or(AppError::Unauthorized)?;
+    let jti = auth_user
+        .session_id
+        .as_deref()
+        .ok_or(AppError::ReauthenticationRequired)?;
+    if !has_sudo(jti).await? {
+        return Err(AppError::ReauthenticationRequired);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use uuid::Uuid;
+
+    fn session(user_id: Uuid, jti: Option<&str>) -> AuthUser {
+        AuthUser {
+            id: user_id,
+            email: "admin@test.com".to_string(),
+            role_id: Uuid::new_v4(),
+            role_slug: "admin".to_string(),
+            permissions: vec![],
+            token_id: None,
+            session_id: jti.map(str::to_string),
+            permission_audit: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_require_sudo_per_session() {
+        let user_id = Uuid::new_v4();
+        let granted: HashSet<&str> = ["first"].into();
+        let has_sudo = |jti: &str| {
+            let granted = granted.contains(jti);
+            async move { Ok(granted) }
+        };
+
+        let first = session(user_id, Some("first"));
+        assert!(require_sudo(Some(&first), has_sudo).await.is_ok());
+
+        // Another token of the same user did not confirm the password
+        let second = session(user_id, Some("second"));
+        assert!(matches!(
+            require_sudo(Some(&second), has_sudo).await,
+            Err(AppError::ReauthenticationRequired)
+        ));
+
+        let mut access_token = session(user_id, None);
+        access_token.token_id = Some(Uuid::new_v4());
+        assert!(matches!(
+            require_sudo(Some(&access_token), has_sudo).await,
+            Err(AppError::ReauthenticationRequired)
+        ));
+        assert!(matches!(
+            require_sudo(None, has_sudo).await,
+            Err(AppError::Unauthorized)
+        ));
+    }
+}
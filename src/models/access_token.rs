@@ -0,0 +1,79 @@
+//! Personal access token models.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Prefix identifying personal access tokens in the `Authorization` header.
+pub const ACCESS_TOKEN_PREFIX: &str = "pat_";
+
+/// Personal access token entity from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct PersonalAccessToken {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PersonalAccessToken {
+    /// Whether the token is past its expiry.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+}
+
+/// Request payload for creating a personal access token.
+#[derive(Debug, Deserialize)]
+pub struct CreateAccessTokenRequest {
+    pub name: String,
+    /// Permission names, each of which the owner must hold
+    pub scopes: Vec<String>,
+    /// Days until the token expires; omit for no expiry
+    pub expires_in_days: Option<i64>,
+}
+
+/// A newly created token; the secret is only ever returned here.
+#[derive(Debug, Serialize)]
+pub struct CreatedAccessTokenResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub details: PersonalAccessToken,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_is_expired() {
+        let mut token = PersonalAccessToken {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            name: "deploy".to_string(),
+            token_prefix: "pat_1234abcd".to_string(),
+            token_hash: String::new(),
+            scopes: vec!["posts:create".to_string()],
+            last_used_at: None,
+            expires_at: None,
+            created_at: Utc::now(),
+        };
+        assert!(!token.is_expired());
+
+        token.expires_at = Some(Utc::now() - Duration::minutes(1));
+        assert!(token.is_expired());
+
+        let json = serde_json::to_value(&token).unwrap();
+        assert!(json.get("token_hash").is_none());
+    }
+}
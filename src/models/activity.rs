@@ -0,0 +1,45 @@
+//! Admin activity feed models.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::AuditEntry;
+
+/// An audit log entry with the name of the user who made it, if still known.
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditEntryWithActor {
+    #[sqlx(flatten)]
+    pub entry: AuditEntry,
+    pub actor_name: Option<String>,
+}
+
+/// User who made a change in the activity feed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityActor {
+    pub id: Uuid,
+    pub name: Option<String>,
+}
+
+/// One line of the activity feed, e.g. "Ada published 'Hello'".
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityItem {
+    pub id: Uuid,
+    pub actor: Option<ActivityActor>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    /// Human-readable description of what happened
+    pub summary: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Query parameters for the activity feed.
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    /// Only changes made by this user
+    pub actor_id: Option<Uuid>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
@@ -0,0 +1,19 @@
+//! Audit log model.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::types::Json;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Audit log entry from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    pub details: Json<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,75 @@
+//! Backup models for encrypted database dumps kept in object storage.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+
+use crate::pkg::storage::StoredObject;
+
+/// Storage key prefix of database backups.
+pub const BACKUP_PREFIX: &str = "backups/";
+
+/// File name suffix of backups: a `pg_dump` custom-format archive encrypted with `age`.
+pub const BACKUP_SUFFIX: &str = ".dump.age";
+
+/// Format of the time a backup was taken in its key.
+const BACKUP_TIME_FORMAT: &str = "%Y-%m-%dT%H%M%SZ";
+
+/// A database backup in storage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Backup {
+    pub key: String,
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Backup {
+    /// Storage key of a backup taken at `time`; keys sort by time.
+    pub fn key_for(time: DateTime<Utc>) -> String {
+        format!(
+            "{}{}{}",
+            BACKUP_PREFIX,
+            time.format(BACKUP_TIME_FORMAT),
+            BACKUP_SUFFIX
+        )
+    }
+
+    /// The backup stored as `object`, or `None` if it is not a backup.
+    pub fn from_object(object: StoredObject) -> Option<Self> {
+        let time = object
+            .key
+            .strip_prefix(BACKUP_PREFIX)?
+            .strip_suffix(BACKUP_SUFFIX)?;
+        let created_at = NaiveDateTime::parse_from_str(time, BACKUP_TIME_FORMAT)
+            .ok()?
+            .and_utc();
+        Some(Self {
+            key: object.key,
+            size: object.size,
+            created_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_backup_keys() {
+        let time = Utc.with_ymd_and_hms(2026, 5, 1, 3, 0, 9).unwrap();
+        let key = Backup::key_for(time);
+        assert_eq!(key, "backups/2026-05-01T030009Z.dump.age");
+
+        let object = |key: &str| StoredObject {
+            key: key.to_string(),
+            size: 42,
+            modified_at: Utc::now(),
+        };
+        let backup = Backup::from_object(object(&key)).unwrap();
+        assert_eq!(backup.created_at, time);
+        assert_eq!(backup.size, 42);
+        assert_eq!(Backup::from_object(object("backups/notes.txt")), None);
+    }
+}
@@ -0,0 +1,45 @@
+//! Blocklist models for IP addresses and email domains barred from public write endpoints.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// What a blocklist entry matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "blocklist_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BlocklistKind {
+    /// A single IP address or a CIDR range
+    Ip,
+    /// An email domain and its subdomains
+    EmailDomain,
+}
+
+/// Blocklist entry entity from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct BlocklistEntry {
+    pub id: Uuid,
+    pub kind: BlocklistKind,
+    /// IP address, CIDR range (`10.0.0.0/8`) or domain (`example.com`)
+    pub value: String,
+    pub reason: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request payload for adding a blocklist entry.
+#[derive(Debug, Deserialize)]
+pub struct CreateBlocklistEntryRequest {
+    pub kind: BlocklistKind,
+    pub value: String,
+    pub reason: Option<String>,
+}
+
+/// Query parameters for listing blocklist entries.
+#[derive(Debug, Deserialize)]
+pub struct BlocklistQuery {
+    pub kind: Option<BlocklistKind>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
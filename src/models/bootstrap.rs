@@ -0,0 +1,29 @@
+//! Bootstrap model bundling what the frontend loads before first paint.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::models::{CategoryWithCount, Site, UserProfile};
+
+/// Site, navigation, taxonomy, feature flags and the signed-in user in one payload.
+#[derive(Debug, Serialize)]
+pub struct Bootstrap {
+    pub site: Site,
+    /// The site's `menus` setting, e.g. links keyed by menu location
+    pub menus: serde_json::Value,
+    pub categories: Vec<CategoryWithCount>,
+    /// The site's `features` setting, with installation-wide flags overriding it
+    pub features: BTreeMap<String, bool>,
+    /// `None` for anonymous visitors
+    pub user: Option<BootstrapUser>,
+}
+
+/// The signed-in user's profile and permissions.
+#[derive(Debug, Serialize)]
+pub struct BootstrapUser {
+    #[serde(flatten)]
+    pub profile: UserProfile,
+    pub is_admin: bool,
+    pub permissions: Vec<String>,
+}
@@ -0,0 +1,14 @@
+//! Cache model definitions.
+
+use serde::Serialize;
+
+/// Report returned by a cache warming run.
+#[derive(Debug, Serialize)]
+pub struct CacheWarmReport {
+    pub sites: usize,
+    /// Trending lists cached, one per site and window
+    pub trending_lists: usize,
+    /// Archives of the current year cached, one per site
+    pub archives: usize,
+    pub duration_ms: u64,
+}
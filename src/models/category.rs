@@ -1,7 +1,7 @@
 //! Category model.
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
@@ -12,8 +12,13 @@ pub struct Category {
     pub name: String,
     pub slug: String,
     pub description: Option<String>,
+    pub parent_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Only set on soft-deleted categories
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 /// Request payload for creating a category.
@@ -22,6 +27,7 @@ pub struct CreateCategoryRequest {
     pub name: String,
     pub slug: Option<String>,
     pub description: Option<String>,
+    pub parent_id: Option<Uuid>,
 }
 
 /// Request payload for updating a category.
@@ -30,15 +36,44 @@ pub struct UpdateCategoryRequest {
     pub name: Option<String>,
     pub slug: Option<String>,
     pub description: Option<String>,
+    /// `null` moves the category to the top level; omitted leaves it in place
+    #[serde(default, deserialize_with = "present")]
+    pub parent_id: Option<Option<Uuid>>,
+}
+
+/// Deserialize a field that is present in the payload, even as `null`, into
+/// `Some`, telling it apart from a missing one (`None` through `default`).
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Request payload for merging a category into another one.
+#[derive(Debug, Deserialize)]
+pub struct MergeCategoryRequest {
+    /// Category that receives the posts; the merged category is deleted
+    pub target_id: Uuid,
+}
+
+/// Outcome of merging a category.
+#[derive(Debug, Serialize)]
+pub struct CategoryMergeResponse {
+    pub target: Category,
+    /// Posts moved from the merged category
+    pub posts_moved: i64,
 }
 
 /// Category with post count for listing.
-#[derive(Debug, Clone, Serialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct CategoryWithCount {
     pub id: Uuid,
     pub name: String,
     pub slug: String,
     pub description: Option<String>,
+    pub parent_id: Option<Uuid>,
     pub post_count: Option<i64>,
     pub created_at: DateTime<Utc>,
 }
@@ -54,8 +89,10 @@ mod tests {
             name: "Technology".to_string(),
             slug: "technology".to_string(),
             description: Some("Tech posts".to_string()),
+            parent_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            deleted_at: None,
         };
 
         let json = serde_json::to_string(&category).unwrap();
@@ -70,4 +107,20 @@ mod tests {
         assert_eq!(request.name, "Tech");
         assert_eq!(request.slug, Some("tech".to_string()));
     }
+
+    #[test]
+    fn test_update_category_request_parent() {
+        let parent_id = Uuid::new_v4();
+
+        let request: UpdateCategoryRequest = serde_json::from_str(r#"{"name": "Tech"}"#).unwrap();
+        assert_eq!(request.parent_id, None);
+
+        let request: UpdateCategoryRequest =
+            serde_json::from_str(r#"{"parent_id": null}"#).unwrap();
+        assert_eq!(request.parent_id, Some(None));
+
+        let json = format!(r#"{{"parent_id": "{}"}}"#, parent_id);
+        let request: UpdateCategoryRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(request.parent_id, Some(Some(parent_id)));
+    }
 }
@@ -0,0 +1,48 @@
+//! Changelog models for "what's new on this site" entries, kept apart from posts.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Changelog entry entity from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ChangelogEntry {
+    pub id: Uuid,
+    pub site_id: Uuid,
+    pub title: String,
+    pub body: String,
+    /// Optional release label, e.g. `v2.1`
+    pub version: Option<String>,
+    /// When the entry becomes public; a draft when unset
+    pub published_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request payload for creating a changelog entry.
+#[derive(Debug, Deserialize)]
+pub struct CreateChangelogEntryRequest {
+    pub title: String,
+    pub body: String,
+    pub version: Option<String>,
+    /// Leave unset to keep the entry as a draft
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Request payload for updating a changelog entry.
+#[derive(Debug, Deserialize)]
+pub struct UpdateChangelogEntryRequest {
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub version: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for listing changelog entries.
+#[derive(Debug, Deserialize)]
+pub struct ChangelogQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
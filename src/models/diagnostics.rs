@@ -0,0 +1,13 @@
+//! Diagnostics model definitions.
+
+use serde::Deserialize;
+
+/// Default number of slow queries returned.
+pub const DEFAULT_SLOW_QUERY_LIMIT: usize = 20;
+
+/// Query parameters for listing slow queries.
+#[derive(Debug, Default, Deserialize)]
+pub struct SlowQueryListQuery {
+    /// Number of entries to return, slowest first
+    pub limit: Option<usize>,
+}
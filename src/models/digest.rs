@@ -0,0 +1,126 @@
+//! Weekly author digest models.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Posts listed in a digest.
+pub const DIGEST_TOP_POSTS: i64 = 3;
+
+/// A user who asked for the weekly digest.
+#[derive(Debug, Clone, FromRow)]
+pub struct DigestRecipient {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+}
+
+/// Totals over an author's posts for a week and the week before.
+#[derive(Debug, Clone, Copy, Default, FromRow, Serialize)]
+pub struct AuthorWeekStats {
+    pub views: i64,
+    pub previous_views: i64,
+    pub posts_published: i64,
+}
+
+/// One of an author's most viewed posts of the week.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct DigestTopPost {
+    pub title: String,
+    pub views: i64,
+}
+
+/// What an author's weekly digest reports, for the week ending `week_end`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyDigest {
+    pub week_start: NaiveDate,
+    pub week_end: NaiveDate,
+    pub stats: AuthorWeekStats,
+    pub top_posts: Vec<DigestTopPost>,
+}
+
+impl WeeklyDigest {
+    /// Whether nothing happened that week, so no email is worth sending.
+    pub fn is_empty(&self) -> bool {
+        self.stats.views == 0 && self.stats.posts_published == 0
+    }
+
+    /// Variables of the `weekly_digest` email template.
+    pub fn variables(&self, name: &str) -> Value {
+        let top_posts: Vec<String> = self
+            .top_posts
+            .iter()
+            .enumerate()
+            .map(|(i, post)| format!("{}. {} ({} views)", i + 1, post.title, post.views))
+            .collect();
+        json!({
+            "name": name,
+            "week": format!(
+                "{} – {}",
+                self.week_start.format("%b %-d"),
+                self.week_end.format("%b %-d, %Y")
+            ),
+            "views": self.stats.views,
+            "views_change": views_change(self.stats.views, self.stats.previous_views),
+            "posts_published": self.stats.posts_published,
+            "top_posts": if top_posts.is_empty() {
+                "No views this week.".to_string()
+            } else {
+                top_posts.join("\n")
+            },
+        })
+    }
+}
+
+/// Week-over-week change, like `+25%`, or `new` when there were no views before.
+fn views_change(views: i64, previous: i64) -> String {
+    if previous == 0 {
+        return if views == 0 { "±0%" } else { "new" }.to_string();
+    }
+    let percent = ((views - previous) as f64 / previous as f64 * 100.0).round() as i64;
+    if percent > 0 {
+        format!("+{}%", percent)
+    } else if percent == 0 {
+        "±0%".to_string()
+    } else {
+        format!("{}%", percent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_views_change() {
+        assert_eq!(views_change(125, 100), "+25%");
+        assert_eq!(views_change(50, 100), "-50%");
+        assert_eq!(views_change(100, 100), "±0%");
+        assert_eq!(views_change(10, 0), "new");
+        assert_eq!(views_change(0, 0), "±0%");
+    }
+
+    #[test]
+    fn test_digest_variables() {
+        let digest = WeeklyDigest {
+            week_start: NaiveDate::from_ymd_opt(2026, 5, 4).unwrap(),
+            week_end: NaiveDate::from_ymd_opt(2026, 5, 10).unwrap(),
+            stats: AuthorWeekStats {
+                views: 30,
+                previous_views: 20,
+                posts_published: 1,
+            },
+            top_posts: vec![DigestTopPost {
+                title: "Hello".to_string(),
+                views: 30,
+            }],
+        };
+        assert!(!digest.is_empty());
+        let variables = digest.variables("Ada");
+        assert_eq!(variables["week"], "May 4 – May 10, 2026");
+        assert_eq!(variables["views_change"], "+50%");
+        assert_eq!(variables["top_posts"], "1. Hello (30 views)");
+    }
+}
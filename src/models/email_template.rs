@@ -0,0 +1,46 @@
+//! Email template models for admin overrides of transactional emails.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::pkg::email::TemplateSource;
+
+/// A transactional email template with its default and any override.
+#[derive(Debug, Serialize)]
+pub struct EmailTemplateResponse {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Variables available to the template
+    pub variables: Vec<&'static str>,
+    pub default: TemplateSource,
+    /// Admin override used instead of the default when set
+    #[serde(rename = "override")]
+    pub custom: Option<EmailTemplateOverride>,
+}
+
+/// An admin's replacement subject and body for a template.
+#[derive(Debug, Serialize)]
+pub struct EmailTemplateOverride {
+    pub subject: String,
+    pub body: String,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request payload for overriding a template.
+#[derive(Debug, Deserialize)]
+pub struct UpdateEmailTemplateRequest {
+    pub subject: String,
+    pub body: String,
+}
+
+/// Request payload for previewing a template.
+///
+/// Unset parts use the current template; unset variables use sample values.
+#[derive(Debug, Default, Deserialize)]
+pub struct PreviewEmailTemplateRequest {
+    pub subject: Option<String>,
+    pub body: Option<String>,
+    pub variables: Option<serde_json::Map<String, serde_json::Value>>,
+}
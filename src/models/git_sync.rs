@@ -0,0 +1,36 @@
+//! Models for syncing posts with a Git repository of Markdown files.
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{PostStatus, PostVisibility};
+
+/// Front matter at the top of a post's Markdown file.
+///
+/// Unknown keys are ignored so files written for static site generators import as-is.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct PostFrontMatter {
+    /// Matches the file to an existing post before falling back to the slug
+    pub id: Option<Uuid>,
+    pub title: String,
+    /// Defaults to the file name without `.md`
+    pub slug: Option<String>,
+    pub excerpt: Option<String>,
+    pub status: Option<PostStatus>,
+    pub visibility: Option<PostVisibility>,
+    /// Category slug on the synced site
+    pub category: Option<String>,
+    /// Tag names, created when missing
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Author email for new posts; unknown authors fall back to `GIT_SYNC_AUTHOR_EMAIL`
+    pub author: Option<String>,
+}
+
+/// The parts of a push webhook payload used for syncing.
+#[derive(Debug, Deserialize)]
+pub struct GitPushEvent {
+    /// Pushed ref, e.g. `refs/heads/main`; missing for ping events
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+}
@@ -0,0 +1,214 @@
+//! Inbound webhook models for deliveries from third-party services.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Third-party service sending webhooks to `/api/hooks/{integration}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "hook_integration", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum HookIntegration {
+    /// GitHub repository events, signed in `X-Hub-Signature-256`
+    Github,
+    /// Stripe events, signed in `Stripe-Signature` with a timestamp
+    Stripe,
+    /// Newsletter provider events, signed in `X-Signature-256`
+    Newsletter,
+}
+
+impl HookIntegration {
+    /// Every integration.
+    pub const ALL: [HookIntegration; 3] = [Self::Github, Self::Stripe, Self::Newsletter];
+
+    /// Name used in the webhook URL.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Github => "github",
+            Self::Stripe => "stripe",
+            Self::Newsletter => "newsletter",
+        }
+    }
+
+    /// Header carrying the signature of the body.
+    pub fn signature_header(self) -> &'static str {
+        match self {
+            Self::Github => "x-hub-signature-256",
+            Self::Stripe => "stripe-signature",
+            Self::Newsletter => "x-signature-256",
+        }
+    }
+
+    /// Header naming the event, for services that don't put it in the payload.
+    pub fn event_header(self) -> Option<&'static str> {
+        match self {
+            Self::Github => Some("x-github-event"),
+            Self::Stripe | Self::Newsletter => None,
+        }
+    }
+
+    /// Header carrying the sender's ID of the delivery, kept when it is retried.
+    pub fn delivery_header(self) -> Option<&'static str> {
+        match self {
+            Self::Github => Some("x-github-delivery"),
+            Self::Stripe | Self::Newsletter => None,
+        }
+    }
+
+    /// Sender's ID of the delivery, from its delivery header or payload, so a
+    /// retried delivery can be recognized.
+    pub fn delivery_id(self, delivery_header: Option<&str>, payload: &Value) -> Option<String> {
+        let delivery_id = match self {
+            Self::Github => delivery_header,
+            // Stripe retries resend the same event
+            Self::Stripe => payload.get("id").and_then(Value::as_str),
+            Self::Newsletter => None,
+        };
+        delivery_id
+            .map(str::trim)
+            .filter(|delivery_id| !delivery_id.is_empty() && delivery_id.len() <= 255)
+            .map(str::to_string)
+    }
+
+    /// Type of the event delivered, from its event header or payload.
+    pub fn event_type(self, event_header: Option<&str>, payload: &Value) -> Option<String> {
+        let event_type = match self {
+            Self::Github => event_header,
+            Self::Stripe => payload.get("type").and_then(Value::as_str),
+            Self::Newsletter => payload
+                .get("type")
+                .or_else(|| payload.get("event"))
+                .and_then(Value::as_str),
+        };
+        event_type
+            .map(str::trim)
+            .filter(|event_type| !event_type.is_empty())
+            .map(|event_type| event_type.chars().take(255).collect())
+    }
+}
+
+impl std::fmt::Display for HookIntegration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for HookIntegration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|integration| integration.name() == s)
+            .ok_or_else(|| format!("unknown integration '{}'", s))
+    }
+}
+
+/// What became of a delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "hook_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum HookStatus {
+    /// Stored, not handled yet
+    Received,
+    /// Handled successfully
+    Processed,
+    /// Nothing handles this event
+    Ignored,
+    /// Handling failed; see the error
+    Failed,
+}
+
+/// A verified webhook delivery, kept verbatim.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct HookDelivery {
+    pub id: Uuid,
+    pub integration: HookIntegration,
+    /// Sender's ID of the delivery, when it sends one
+    pub delivery_id: Option<String>,
+    pub event_type: Option<String>,
+    /// Request headers, without credentials
+    pub headers: Value,
+    /// Body exactly as received
+    pub payload: String,
+    pub status: HookStatus,
+    /// Error of the latest handling attempt
+    pub error: Option<String>,
+    /// Handling runs, counting replays
+    pub attempts: i32,
+    pub received_at: DateTime<Utc>,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for listing webhook deliveries.
+#[derive(Debug, Deserialize)]
+pub struct HookDeliveryQuery {
+    pub integration: Option<HookIntegration>,
+    pub status: Option<HookStatus>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// Webhook response acknowledging a stored delivery.
+#[derive(Debug, Serialize)]
+pub struct HookReceipt {
+    pub id: Uuid,
+    pub status: HookStatus,
+    /// The delivery was received before and is not handled again
+    pub duplicate: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integration_names() {
+        for integration in HookIntegration::ALL {
+            assert_eq!(integration.name().parse(), Ok(integration));
+        }
+        assert!("paypal".parse::<HookIntegration>().is_err());
+    }
+
+    #[test]
+    fn test_delivery_id() {
+        let payload = serde_json::json!({ "id": "evt_123" });
+        assert_eq!(
+            HookIntegration::Github.delivery_id(Some(" 72d3162e "), &payload),
+            Some("72d3162e".to_string())
+        );
+        assert_eq!(HookIntegration::Github.delivery_id(None, &payload), None);
+        assert_eq!(
+            HookIntegration::Stripe.delivery_id(None, &payload),
+            Some("evt_123".to_string())
+        );
+        assert_eq!(
+            HookIntegration::Newsletter.delivery_id(None, &payload),
+            None
+        );
+    }
+
+    #[test]
+    fn test_event_type() {
+        let payload = serde_json::json!({ "type": "invoice.paid", "event": "bounce" });
+        assert_eq!(
+            HookIntegration::Github.event_type(Some("push"), &payload),
+            Some("push".to_string())
+        );
+        assert_eq!(HookIntegration::Github.event_type(None, &payload), None);
+        assert_eq!(
+            HookIntegration::Stripe.event_type(Some("push"), &payload),
+            Some("invoice.paid".to_string())
+        );
+        assert_eq!(
+            HookIntegration::Newsletter.event_type(None, &serde_json::json!({ "event": "bounce" })),
+            Some("bounce".to_string())
+        );
+        assert_eq!(
+            HookIntegration::Newsletter.event_type(None, &serde_json::json!({ "type": " " })),
+            None
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Background job models for the dead-letter queue of failed deliveries.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Kind of background job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "job_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum JobKind {
+    /// Frontend revalidation webhook
+    Revalidation,
+    /// Outgoing email sent in the background
+    Email,
+    /// Domain event delivered from the outbox to the event webhook
+    Event,
+}
+
+/// A job that ran out of attempts, kept until it is retried or purged.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct FailedJob {
+    pub id: Uuid,
+    pub kind: JobKind,
+    pub payload: serde_json::Value,
+    /// Error of the latest attempt
+    pub error: String,
+    /// Failed runs, counting manual retries
+    pub attempts: i32,
+    pub failed_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A background job and everything needed to run it again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Job {
+    Revalidation {
+        site_id: Uuid,
+        paths: Vec<String>,
+    },
+    Email {
+        to: String,
+        subject: String,
+        body: String,
+    },
+    Event {
+        event_id: Uuid,
+        event_type: String,
+        created_at: DateTime<Utc>,
+        data: serde_json::Value,
+    },
+}
+
+impl Job {
+    /// Kind stored alongside the payload.
+    pub fn kind(&self) -> JobKind {
+        match self {
+            Job::Revalidation { .. } => JobKind::Revalidation,
+            Job::Email { .. } => JobKind::Email,
+            Job::Event { .. } => JobKind::Event,
+        }
+    }
+
+    /// The job a dead-lettered entry describes.
+    pub fn from_failed(failed: &FailedJob) -> Result<Self, String> {
+        let job: Job = serde_json::from_value(failed.payload.clone())
+            .map_err(|e| format!("Invalid job payload: {}", e))?;
+        if job.kind() == failed.kind {
+            Ok(job)
+        } else {
+            Err(format!("Payload does not describe a {:?} job", failed.kind))
+        }
+    }
+}
+
+/// Query parameters for listing failed jobs.
+#[derive(Debug, Deserialize)]
+pub struct FailedJobQuery {
+    pub kind: Option<JobKind>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// Query parameters for purging failed jobs.
+#[derive(Debug, Deserialize)]
+pub struct PurgeFailedJobsQuery {
+    /// Only purge jobs that last failed more than this many days ago; all when unset
+    pub older_than_days: Option<i64>,
+}
+
+/// Outcome of retrying one failed job.
+#[derive(Debug, Serialize)]
+pub struct RetryResult {
+    pub id: Uuid,
+    pub succeeded: bool,
+    /// Error of the retry when it failed again
+    pub error: Option<String>,
+}
+
+/// Response after purging failed jobs.
+#[derive(Debug, Serialize)]
+pub struct PurgeFailedJobsResponse {
+    pub purged: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(kind: JobKind, payload: serde_json::Value) -> FailedJob {
+        FailedJob {
+            id: Uuid::new_v4(),
+            kind,
+            payload,
+            error: "status 502".to_string(),
+            attempts: 1,
+            failed_at: Utc::now(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_job_payload_round_trip() {
+        let jobs = [
+            Job::Revalidation {
+                site_id: Uuid::new_v4(),
+                paths: vec!["/".to_string(), "/blog/hello".to_string()],
+            },
+            Job::Email {
+                to: "owner@example.com".to_string(),
+                subject: "New sign-in".to_string(),
+                body: "Hi".to_string(),
+            },
+            Job::Event {
+                event_id: Uuid::new_v4(),
+                event_type: "post.published".to_string(),
+                created_at: Utc::now(),
+                data: serde_json::json!({ "slug": "hello" }),
+            },
+        ];
+        for job in jobs {
+            let entry = failed(job.kind(), serde_json::to_value(&job).unwrap());
+            assert_eq!(Job::from_failed(&entry), Ok(job));
+        }
+    }
+
+    #[test]
+    fn test_job_payload_kind_mismatch() {
+        let payload = serde_json::json!({ "to": "a@example.com", "subject": "s", "body": "b" });
+        assert!(Job::from_failed(&failed(JobKind::Revalidation, payload)).is_err());
+        assert!(Job::from_failed(&failed(JobKind::Email, serde_json::json!({}))).is_err());
+    }
+}
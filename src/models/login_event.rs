@@ -0,0 +1,72 @@
+//! Login event model for sign-in history.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A successful login, from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct LoginEvent {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub user_id: Uuid,
+    pub ip_address: String,
+    pub user_agent: Option<String>,
+    pub country: Option<String>,
+    /// ISO 3166-2 subdivision code within `country`, when known
+    pub region: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How a login compares with the user's earlier ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, FromRow)]
+pub struct LoginOrigin {
+    /// The user has logged in before
+    pub has_history: bool,
+    /// A previous login used the same user agent
+    pub known_device: bool,
+    /// A previous login came from the same country (false if unknown)
+    pub known_country: bool,
+}
+
+impl LoginOrigin {
+    /// Whether the owner should be told about this login.
+    ///
+    /// The first login is never reported; later ones are when the device is
+    /// new, or when the country is known and has not been seen before.
+    pub fn is_unfamiliar(&self, country_known: bool) -> bool {
+        self.has_history && (!self.known_device || (country_known && !self.known_country))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_login_origin_is_unfamiliar() {
+        let first = LoginOrigin::default();
+        assert!(!first.is_unfamiliar(true));
+
+        let familiar = LoginOrigin {
+            has_history: true,
+            known_device: true,
+            known_country: true,
+        };
+        assert!(!familiar.is_unfamiliar(true));
+
+        let new_device = LoginOrigin {
+            known_device: false,
+            ..familiar
+        };
+        assert!(new_device.is_unfamiliar(false));
+
+        let new_country = LoginOrigin {
+            known_country: false,
+            ..familiar
+        };
+        assert!(new_country.is_unfamiliar(true));
+        assert!(!new_country.is_unfamiliar(false));
+    }
+}
@@ -0,0 +1,257 @@
+//! Media model for uploaded files.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::PostStatus;
+
+/// Public URL prefix under which uploaded files are served.
+pub const MEDIA_URL_PREFIX: &str = "/uploads";
+
+/// Media entity from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Media {
+    pub id: Uuid,
+    pub uploader_id: Option<Uuid>,
+    pub filename: String,
+    pub storage_key: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Description for screen readers and when the image cannot load
+    pub alt_text: Option<String>,
+    /// Downloads through the counting download endpoint
+    pub download_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A file attached to a post.
+#[derive(Debug, Clone, FromRow)]
+pub struct PostAttachment {
+    pub post_id: Uuid,
+    #[sqlx(flatten)]
+    pub media: Media,
+}
+
+impl Media {
+    /// Public URL of the stored file.
+    pub fn url(&self) -> String {
+        format!("{}/{}", MEDIA_URL_PREFIX, self.storage_key)
+    }
+
+    /// URL of the endpoint that counts the download and serves the file.
+    pub fn download_url(&self) -> String {
+        format!("/api/media/{}/download", self.id)
+    }
+
+    /// `Content-Disposition` header saving the file under its original name.
+    ///
+    /// The quoted name falls back to `_` for characters that need the encoded form.
+    pub fn content_disposition(&self) -> String {
+        let fallback: String = self
+            .filename
+            .chars()
+            .map(|c| match c {
+                ' '..='~' if c != '"' && c != '\\' => c,
+                _ => '_',
+            })
+            .collect();
+        let mut encoded = String::new();
+        for byte in self.filename.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    encoded.push(byte as char)
+                }
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        format!(
+            "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+            fallback, encoded
+        )
+    }
+}
+
+/// Media item for API responses.
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaResponse {
+    pub id: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub alt_text: Option<String>,
+    pub url: String,
+    pub download_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Media> for MediaResponse {
+    fn from(media: Media) -> Self {
+        Self {
+            url: media.url(),
+            id: media.id,
+            filename: media.filename,
+            content_type: media.content_type,
+            size_bytes: media.size_bytes,
+            alt_text: media.alt_text,
+            download_count: media.download_count,
+            created_at: media.created_at,
+        }
+    }
+}
+
+/// File attached to a post, as listed with the post.
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentResponse {
+    pub id: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Counting download link; the file itself is also served at its media URL
+    pub download_url: String,
+}
+
+impl From<Media> for AttachmentResponse {
+    fn from(media: Media) -> Self {
+        Self {
+            download_url: media.download_url(),
+            id: media.id,
+            filename: media.filename,
+            content_type: media.content_type,
+            size_bytes: media.size_bytes,
+        }
+    }
+}
+
+/// Request payload for starting a chunked upload.
+#[derive(Debug, Deserialize)]
+pub struct CreateUploadRequest {
+    pub filename: String,
+    pub content_type: String,
+    /// Total size of the file in bytes
+    pub size_bytes: u64,
+}
+
+/// A chunked upload in progress, kept in Redis until it completes or expires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadSession {
+    pub id: String,
+    pub uploader_id: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+    /// Bytes received so far; the next chunk must start here
+    pub offset: u64,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl UploadSession {
+    /// Whether every byte has been received.
+    pub fn is_complete(&self) -> bool {
+        self.offset >= self.size_bytes
+    }
+}
+
+/// State of a chunked upload after receiving a chunk.
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadProgress {
+    pub upload: UploadSession,
+    /// The stored media, once the last chunk arrived
+    pub media: Option<MediaResponse>,
+}
+
+/// Whether media is referenced anywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaUsageFilter {
+    /// Referenced by a post or used as an avatar
+    Used,
+    /// Referenced nowhere
+    Unused,
+}
+
+/// Query parameters for listing media.
+#[derive(Debug, Deserialize)]
+pub struct MediaQuery {
+    /// Content type (`image/png`) or its top-level type (`image`)
+    #[serde(rename = "type")]
+    pub content_type: Option<String>,
+    pub usage: Option<MediaUsageFilter>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// Request payload for renaming media or editing its alt text.
+#[derive(Debug, Deserialize)]
+pub struct UpdateMediaRequest {
+    pub filename: Option<String>,
+    /// An empty string clears the alt text
+    pub alt_text: Option<String>,
+}
+
+/// Post referencing media in its content.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct MediaPostReference {
+    pub id: Uuid,
+    pub site_id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub status: PostStatus,
+}
+
+/// Where a media item is used.
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaUsage {
+    pub media_id: Uuid,
+    /// Posts on any site attaching the file or linking to it in their content
+    pub posts: Vec<MediaPostReference>,
+    /// Users with the file as their avatar
+    pub avatar_users: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_url() {
+        let media = Media {
+            id: Uuid::new_v4(),
+            uploader_id: None,
+            filename: "me.png".to_string(),
+            storage_key: "abc.png".to_string(),
+            content_type: "image/png".to_string(),
+            size_bytes: 10,
+            alt_text: None,
+            download_count: 0,
+            created_at: Utc::now(),
+        };
+        assert_eq!(media.url(), "/uploads/abc.png");
+        assert_eq!(
+            media.download_url(),
+            format!("/api/media/{}/download", media.id)
+        );
+        assert_eq!(MediaResponse::from(media).url, "/uploads/abc.png");
+    }
+
+    #[test]
+    fn test_content_disposition() {
+        let media = Media {
+            id: Uuid::new_v4(),
+            uploader_id: None,
+            filename: "Slides \"v2\" – final.pdf".to_string(),
+            storage_key: "abc.pdf".to_string(),
+            content_type: "application/pdf".to_string(),
+            size_bytes: 10,
+            alt_text: None,
+            download_count: 0,
+            created_at: Utc::now(),
+        };
+        assert_eq!(
+            media.content_disposition(),
+            "attachment; filename=\"Slides _v2_ _ final.pdf\"; \
+             filename*=UTF-8''Slides%20%22v2%22%20%E2%80%93%20final.pdf"
+        );
+    }
+}
@@ -1,15 +1,87 @@
 //! Domain models for the application.
 
+pub mod access_token;
+pub mod activity;
+pub mod audit;
+pub mod backup;
+pub mod blocklist;
+pub mod bootstrap;
+pub mod cache;
 pub mod category;
+pub mod changelog;
+pub mod diagnostics;
+pub mod digest;
+pub mod email_template;
+pub mod git_sync;
+pub mod hook;
+pub mod job;
+pub mod login_event;
+pub mod media;
+pub mod notification;
+pub mod outbox;
 pub mod permission;
+pub mod poll;
 pub mod post;
+pub mod post_lock;
+pub mod post_note;
+pub mod post_review;
+pub mod post_template;
+pub mod preview;
+pub mod quota;
+pub mod revision;
 pub mod role;
+pub mod route_access;
+pub mod search;
+pub mod setting;
+pub mod site;
+pub mod status;
+pub mod structured_data;
+pub mod sync;
 pub mod tag;
+pub mod taxonomy;
+pub mod title_test;
 pub mod user;
+pub mod webhook_attempt;
 
+pub use access_token::*;
+pub use activity::*;
+pub use audit::*;
+pub use backup::*;
+pub use blocklist::*;
+pub use bootstrap::*;
+pub use cache::*;
 pub use category::*;
+pub use changelog::*;
+pub use diagnostics::*;
+pub use digest::*;
+pub use email_template::*;
+pub use git_sync::*;
+pub use hook::*;
+pub use job::*;
+pub use login_event::*;
+pub use media::*;
+pub use notification::*;
+pub use outbox::*;
 pub use permission::*;
+pub use poll::*;
 pub use post::*;
+pub use post_lock::*;
+pub use post_note::*;
+pub use post_review::*;
+pub use post_template::*;
+pub use preview::*;
+pub use quota::*;
+pub use revision::*;
 pub use role::*;
+pub use route_access::*;
+pub use search::*;
+pub use setting::*;
+pub use site::*;
+pub use status::*;
+pub use structured_data::*;
+pub use sync::*;
 pub use tag::*;
+pub use taxonomy::*;
+pub use title_test::*;
 pub use user::*;
+pub use webhook_attempt::*;
@@ -0,0 +1,105 @@
+//! Notification preference models.
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Which emails a user wants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow, Serialize)]
+pub struct NotificationPreferences {
+    /// New comments on the user's posts
+    pub email_on_comment: bool,
+    /// Mentions of the user
+    pub email_on_mention: bool,
+    /// Rejections of the user's posts submitted for review
+    pub email_on_review: bool,
+    /// Weekly summary of the user's post stats
+    pub weekly_digest: bool,
+    /// Failed sign-in attempts and sign-ins from unfamiliar devices
+    pub security_alerts: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            email_on_comment: true,
+            email_on_mention: true,
+            email_on_review: true,
+            weekly_digest: false,
+            security_alerts: true,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    /// Whether the user wants notifications of `kind`.
+    pub fn allows(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::Comment => self.email_on_comment,
+            NotificationKind::Mention => self.email_on_mention,
+            NotificationKind::Review => self.email_on_review,
+            NotificationKind::WeeklyDigest => self.weekly_digest,
+            NotificationKind::SecurityAlert => self.security_alerts,
+        }
+    }
+
+    /// These preferences with the fields set in `request` changed.
+    pub fn apply(mut self, request: &UpdateNotificationPreferencesRequest) -> Self {
+        if let Some(value) = request.email_on_comment {
+            self.email_on_comment = value;
+        }
+        if let Some(value) = request.email_on_mention {
+            self.email_on_mention = value;
+        }
+        if let Some(value) = request.email_on_review {
+            self.email_on_review = value;
+        }
+        if let Some(value) = request.weekly_digest {
+            self.weekly_digest = value;
+        }
+        if let Some(value) = request.security_alerts {
+            self.security_alerts = value;
+        }
+        self
+    }
+}
+
+/// What a notification is about, each with its own preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    Comment,
+    Mention,
+    Review,
+    WeeklyDigest,
+    SecurityAlert,
+}
+
+/// Request payload for changing notification preferences; unset fields stay.
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateNotificationPreferencesRequest {
+    pub email_on_comment: Option<bool>,
+    pub email_on_mention: Option<bool>,
+    pub email_on_review: Option<bool>,
+    pub weekly_digest: Option<bool>,
+    pub security_alerts: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_and_allows() {
+        let defaults = NotificationPreferences::default();
+        assert!(defaults.allows(NotificationKind::SecurityAlert));
+        assert!(!defaults.allows(NotificationKind::WeeklyDigest));
+
+        let updated = defaults.apply(&UpdateNotificationPreferencesRequest {
+            weekly_digest: Some(true),
+            security_alerts: Some(false),
+            ..Default::default()
+        });
+        assert!(updated.allows(NotificationKind::WeeklyDigest));
+        assert!(!updated.allows(NotificationKind::SecurityAlert));
+        assert!(updated.allows(NotificationKind::Comment));
+    }
+}
@@ -0,0 +1,91 @@
+//! Outbox models for domain events awaiting delivery.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::{Post, User};
+
+/// A post became published, on creation or by an update.
+pub const POST_PUBLISHED: &str = "post.published";
+/// A user account was created, by an admin, an invitation or self-registration.
+pub const USER_CREATED: &str = "user.created";
+
+/// A domain event stored in the outbox until the relay delivers it.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct OutboxEvent {
+    pub id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+    /// Failed deliveries so far
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A domain event to write to the outbox alongside the change it describes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewEvent {
+    pub event_type: &'static str,
+    pub payload: Value,
+}
+
+impl NewEvent {
+    /// `post.published` for `post`.
+    pub fn post_published(post: &Post) -> Self {
+        Self {
+            event_type: POST_PUBLISHED,
+            payload: json!({
+                "id": post.id,
+                "site_id": post.site_id,
+                "title": post.title,
+                "slug": post.slug,
+                "excerpt": post.excerpt,
+                "visibility": post.visibility,
+                "author_id": post.author_id,
+                "category_id": post.category_id,
+                "published_at": post.published_at,
+            }),
+        }
+    }
+
+    /// `user.created` for `user`, `pending` when it awaits approval.
+    pub fn user_created(user: &User, pending: bool) -> Self {
+        Self {
+            event_type: USER_CREATED,
+            payload: json!({
+                "id": user.id,
+                "email": user.email,
+                "name": user.name,
+                "role_id": user.role_id,
+                "pending": pending,
+            }),
+        }
+    }
+}
+
+/// Body POSTed to the event webhook.
+#[derive(Debug, Serialize)]
+pub struct EventEnvelope<'a> {
+    /// Stable across redeliveries, so receivers can drop duplicates
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: &'a str,
+    pub created_at: DateTime<Utc>,
+    pub data: &'a Value,
+}
+
+impl<'a> EventEnvelope<'a> {
+    /// Envelope of an event read from the outbox.
+    pub fn new(id: Uuid, event_type: &'a str, created_at: DateTime<Utc>, data: &'a Value) -> Self {
+        Self {
+            id,
+            event_type,
+            created_at,
+            data,
+        }
+    }
+}
@@ -37,6 +37,11 @@ pub mod permissions {
     pub const TAGS_UPDATE: &str = "tags:update";
     pub const TAGS_DELETE: &str = "tags:delete";
 
+    // Polls
+    pub const POLLS_CREATE: &str = "polls:create";
+    pub const POLLS_UPDATE: &str = "polls:update";
+    pub const POLLS_DELETE: &str = "polls:delete";
+
     // Users
     pub const USERS_READ: &str = "users:read";
     pub const USERS_CREATE: &str = "users:create";
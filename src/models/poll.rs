@@ -0,0 +1,243 @@
+//! Poll models for reader polls embedded into posts.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Opening of the shortcode embedding a poll into post content, e.g. `[poll id="…"]`.
+pub const POLL_SHORTCODE: &str = "[poll ";
+
+/// Poll entity from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Poll {
+    pub id: Uuid,
+    pub site_id: Uuid,
+    pub question: String,
+    /// When voting starts; open from creation when unset
+    pub opens_at: Option<DateTime<Utc>>,
+    /// When voting ends; never closes when unset
+    pub closes_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Poll {
+    /// Whether votes are accepted at `now`.
+    pub fn is_open_at(&self, now: DateTime<Utc>) -> bool {
+        self.opens_at.map_or(true, |opens_at| opens_at <= now)
+            && self.closes_at.map_or(true, |closes_at| now < closes_at)
+    }
+}
+
+/// Poll option entity from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct PollOption {
+    pub id: Uuid,
+    pub poll_id: Uuid,
+    pub label: String,
+    pub position: i32,
+    pub votes: i64,
+}
+
+/// Poll with its options and current results.
+#[derive(Debug, Clone, Serialize)]
+pub struct PollResponse {
+    pub id: Uuid,
+    pub question: String,
+    pub opens_at: Option<DateTime<Utc>>,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub is_open: bool,
+    pub total_votes: i64,
+    pub options: Vec<PollOptionResult>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One option's share of the votes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PollOptionResult {
+    pub id: Uuid,
+    pub label: String,
+    pub votes: i64,
+    /// Share of all votes, 0–100 with one decimal
+    pub percentage: f64,
+}
+
+impl PollResponse {
+    /// Combine a poll with its options (in display order) as seen at `now`.
+    pub fn new(poll: Poll, options: Vec<PollOption>, now: DateTime<Utc>) -> Self {
+        let total_votes: i64 = options.iter().map(|option| option.votes).sum();
+        let options = options
+            .into_iter()
+            .map(|option| PollOptionResult {
+                percentage: if total_votes > 0 {
+                    (option.votes as f64 * 1000.0 / total_votes as f64).round() / 10.0
+                } else {
+                    0.0
+                },
+                id: option.id,
+                label: option.label,
+                votes: option.votes,
+            })
+            .collect();
+
+        Self {
+            is_open: poll.is_open_at(now),
+            id: poll.id,
+            question: poll.question,
+            opens_at: poll.opens_at,
+            closes_at: poll.closes_at,
+            total_votes,
+            options,
+            created_at: poll.created_at,
+        }
+    }
+}
+
+/// Who cast a vote, used to allow one vote per poll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollVoter {
+    /// Anonymous readers, per client IP
+    Ip(IpAddr),
+    /// Signed-in readers, per user
+    User(Uuid),
+}
+
+impl fmt::Display for PollVoter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PollVoter::Ip(ip) => write!(f, "ip:{}", ip),
+            PollVoter::User(id) => write!(f, "user:{}", id),
+        }
+    }
+}
+
+/// Request payload for creating a poll.
+#[derive(Debug, Deserialize)]
+pub struct CreatePollRequest {
+    pub question: String,
+    /// Option labels in display order
+    pub options: Vec<String>,
+    pub opens_at: Option<DateTime<Utc>>,
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+/// Request payload for updating a poll.
+///
+/// `options` replaces every option and is only accepted before the first vote.
+#[derive(Debug, Deserialize)]
+pub struct UpdatePollRequest {
+    pub question: Option<String>,
+    pub options: Option<Vec<String>>,
+    pub opens_at: Option<DateTime<Utc>>,
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+/// Request payload for voting in a poll.
+#[derive(Debug, Deserialize)]
+pub struct VoteRequest {
+    pub option_id: Uuid,
+}
+
+/// Query parameters for listing polls.
+#[derive(Debug, Deserialize)]
+pub struct PollQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// IDs of polls embedded in post content with `[poll id="…"]`, in order and without repeats.
+pub fn embedded_poll_ids(content: &str) -> Vec<Uuid> {
+    let mut ids = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(POLL_SHORTCODE) {
+        rest = &rest[start + POLL_SHORTCODE.len()..];
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let id = rest[..end]
+            .split_whitespace()
+            .find_map(|attribute| attribute.strip_prefix("id="))
+            .map(|value| value.trim_matches(['"', '\'']))
+            .and_then(|value| Uuid::parse_str(value).ok());
+        if let Some(id) = id.filter(|id| !ids.contains(id)) {
+            ids.push(id);
+        }
+        rest = &rest[end..];
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Duration;
+
+    fn poll(opens_at: Option<DateTime<Utc>>, closes_at: Option<DateTime<Utc>>) -> Poll {
+        Poll {
+            id: Uuid::new_v4(),
+            site_id: Uuid::new_v4(),
+            question: "Tabs or spaces?".to_string(),
+            opens_at,
+            closes_at,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn option(label: &str, votes: i64) -> PollOption {
+        PollOption {
+            id: Uuid::new_v4(),
+            poll_id: Uuid::new_v4(),
+            label: label.to_string(),
+            position: 0,
+            votes,
+        }
+    }
+
+    #[test]
+    fn test_is_open_at() {
+        let now = Utc::now();
+        let hour = Duration::hours(1);
+        assert!(poll(None, None).is_open_at(now));
+        assert!(poll(Some(now - hour), Some(now + hour)).is_open_at(now));
+        assert!(!poll(Some(now + hour), None).is_open_at(now));
+        assert!(!poll(None, Some(now)).is_open_at(now));
+    }
+
+    #[test]
+    fn test_poll_response_percentages() {
+        let options = vec![option("Tabs", 1), option("Spaces", 2), option("Both", 0)];
+        let response = PollResponse::new(poll(None, None), options, Utc::now());
+        assert_eq!(response.total_votes, 3);
+        let percentages: Vec<f64> = response.options.iter().map(|o| o.percentage).collect();
+        assert_eq!(percentages, vec![33.3, 66.7, 0.0]);
+
+        let response = PollResponse::new(poll(None, None), vec![option("Tabs", 0)], Utc::now());
+        assert_eq!(response.options[0].percentage, 0.0);
+    }
+
+    #[test]
+    fn test_embedded_poll_ids() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let content = format!(
+            "Intro\n\n[poll id=\"{a}\"]\n\nMore [poll id={b}] and [poll id='{a}'] again.\n\
+             [poll id=\"not-a-uuid\"] [poll"
+        );
+        assert_eq!(embedded_poll_ids(&content), vec![a, b]);
+        assert!(embedded_poll_ids("No polls here").is_empty());
+    }
+
+    #[test]
+    fn test_poll_voter_display() {
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        assert_eq!(PollVoter::Ip(ip).to_string(), "ip:203.0.113.7");
+    }
+}
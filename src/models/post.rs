@@ -1,11 +1,11 @@
 //! Blog post model and status definitions.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
-use super::{Category, Tag, User};
+use super::{AttachmentResponse, Category, PollResponse, Tag, User};
 
 /// Post status enum.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, sqlx::Type)]
@@ -14,6 +14,10 @@ use super::{Category, Tag, User};
 pub enum PostStatus {
     #[default]
     Draft,
+    /// Submitted by its author, waiting for a reviewer
+    #[sqlx(rename = "in_review")]
+    #[serde(rename = "in_review")]
+    InReview,
     Published,
     Archived,
 }
@@ -22,23 +26,111 @@ impl std::fmt::Display for PostStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PostStatus::Draft => write!(f, "draft"),
+            PostStatus::InReview => write!(f, "in_review"),
             PostStatus::Published => write!(f, "published"),
             PostStatus::Archived => write!(f, "archived"),
         }
     }
 }
 
+impl PostStatus {
+    /// Whether a post may move from this status to `next`.
+    ///
+    /// Drafts are published or submitted for review, posts in review are
+    /// approved (published) or rejected (back to draft), published posts are
+    /// unpublished or archived, and archived posts go back to draft. Keeping
+    /// the status is always allowed.
+    pub fn can_transition_to(self, next: PostStatus) -> bool {
+        use PostStatus::*;
+
+        self == next
+            || matches!(
+                (self, next),
+                (Draft, Published)
+                    | (Draft, InReview)
+                    | (InReview, Published)
+                    | (InReview, Draft)
+                    | (Published, Draft)
+                    | (Published, Archived)
+                    | (Archived, Draft)
+            )
+    }
+}
+
+/// Who may read a published post.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, sqlx::Type)]
+#[sqlx(type_name = "post_visibility", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PostVisibility {
+    /// Anyone, including anonymous readers
+    #[default]
+    Public,
+    /// Any signed-in user
+    Members,
+    /// Only the author (and admins)
+    Private,
+}
+
+impl std::fmt::Display for PostVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PostVisibility::Public => write!(f, "public"),
+            PostVisibility::Members => write!(f, "members"),
+            PostVisibility::Private => write!(f, "private"),
+        }
+    }
+}
+
+/// Who is reading posts, which decides the drafts and visibilities they see.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostViewer {
+    Anonymous,
+    /// A signed-in, non-admin user
+    Member(Uuid),
+    /// Admins see every post regardless of status or visibility
+    Admin,
+}
+
+impl PostViewer {
+    /// Whether the viewer bypasses status and visibility checks.
+    pub fn is_admin(&self) -> bool {
+        matches!(self, PostViewer::Admin)
+    }
+
+    /// The signed-in non-admin user, if any.
+    pub fn member_id(&self) -> Option<Uuid> {
+        match self {
+            PostViewer::Member(id) => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// Whether a post with this visibility and author may be shown.
+    pub fn can_see(&self, visibility: PostVisibility, author_id: Uuid) -> bool {
+        match (self, visibility) {
+            (PostViewer::Admin, _) | (_, PostVisibility::Public) => true,
+            (PostViewer::Member(_), PostVisibility::Members) => true,
+            (PostViewer::Member(id), PostVisibility::Private) => *id == author_id,
+            (PostViewer::Anonymous, _) => false,
+        }
+    }
+}
+
 /// Post entity from database.
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct Post {
     pub id: Uuid,
+    pub site_id: Uuid,
     pub title: String,
     pub slug: String,
     pub content: String,
     pub excerpt: Option<String>,
     pub status: PostStatus,
+    pub visibility: PostVisibility,
     pub author_id: Uuid,
     pub category_id: Option<Uuid>,
+    /// When the post was first published
+    pub published_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -49,11 +141,14 @@ pub struct AuthorResponse {
     pub id: Uuid,
     pub name: String,
     pub email: String,
+    /// Uploaded avatar or Gravatar fallback
+    pub avatar_url: String,
 }
 
 impl From<User> for AuthorResponse {
     fn from(user: User) -> Self {
         Self {
+            avatar_url: user.display_avatar_url(),
             id: user.id,
             name: user.name,
             email: user.email,
@@ -70,26 +165,191 @@ pub struct PostResponse {
     pub content: String,
     pub excerpt: Option<String>,
     pub status: PostStatus,
+    pub visibility: PostVisibility,
     pub author: Option<AuthorResponse>,
     pub category: Option<Category>,
     pub tags: Vec<Tag>,
+    /// Polls embedded in the content with `[poll id="…"]`, with their results
+    pub polls: Vec<PollResponse>,
+    /// Files attached to the post, in order
+    pub attachments: Vec<AttachmentResponse>,
+    /// When the post was first published; kept when it is unpublished
+    pub published_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// schema.org `BlogPosting` JSON-LD, included when reading a post by slug
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_data: Option<serde_json::Value>,
 }
 
 /// Post list item (lighter version for lists).
-#[derive(Debug, Clone, Serialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct PostListItem {
     pub id: Uuid,
     pub title: String,
     pub slug: String,
     pub excerpt: Option<String>,
     pub status: PostStatus,
+    pub visibility: PostVisibility,
     pub author_id: Uuid,
     pub author_name: Option<String>,
     pub category_id: Option<Uuid>,
     pub category_name: Option<String>,
+    /// When the post was first published
+    pub published_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    /// Highlighted text showing why the post matched a search
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+/// A site's published posts of one year, grouped by month.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostArchive {
+    pub year: i32,
+    /// Number of posts in the year
+    pub total: usize,
+    /// Months with posts, January first
+    pub months: Vec<ArchiveMonth>,
+}
+
+/// Posts first published in one month, oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveMonth {
+    /// Month number, 1 to 12
+    pub month: u32,
+    pub posts: Vec<PostListItem>,
+}
+
+impl PostArchive {
+    /// Group `posts`, ordered by publication time, into months.
+    pub fn new(year: i32, posts: Vec<PostListItem>) -> Self {
+        let total = posts.len();
+        let mut months: Vec<ArchiveMonth> = Vec::new();
+        for post in posts {
+            let month = post.published_at.unwrap_or(post.created_at).month();
+            match months.last_mut() {
+                Some(last) if last.month == month => last.posts.push(post),
+                _ => months.push(ArchiveMonth {
+                    month,
+                    posts: vec![post],
+                }),
+            }
+        }
+        Self {
+            year,
+            total,
+            months,
+        }
+    }
+}
+
+/// Post fields mirrored into an external search index.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PostSearchDocument {
+    pub id: Uuid,
+    pub site_id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub excerpt: Option<String>,
+    pub content: String,
+    pub status: PostStatus,
+    pub visibility: PostVisibility,
+    pub author_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Window over which trending posts are ranked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrendingWindow {
+    Day,
+    #[default]
+    Week,
+    Month,
+}
+
+impl TrendingWindow {
+    /// Every window, as refreshed by the rollup job.
+    pub const ALL: [TrendingWindow; 3] = [Self::Day, Self::Week, Self::Month];
+
+    /// Number of days (including today) the window covers.
+    pub fn days(self) -> i32 {
+        match self {
+            Self::Day => 1,
+            Self::Week => 7,
+            Self::Month => 30,
+        }
+    }
+}
+
+impl std::fmt::Display for TrendingWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}d", self.days())
+    }
+}
+
+impl std::str::FromStr for TrendingWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "1d" | "day" => Ok(Self::Day),
+            "7d" | "week" => Ok(Self::Week),
+            "30d" | "month" => Ok(Self::Month),
+            other => Err(format!("window must be 1d, 7d or 30d, got '{}'", other)),
+        }
+    }
+}
+
+/// Query parameters for trending posts.
+#[derive(Debug, Default, Deserialize)]
+pub struct TrendingQuery {
+    /// `1d`, `7d` (default) or `30d`
+    pub window: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Published post ranked by recent views.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TrendingPost {
+    pub id: Uuid,
+    pub title: String,
+    pub slug: String,
+    pub excerpt: Option<String>,
+    /// Views within the window
+    pub views: i64,
+}
+
+/// Views of a site's posts on one day, with filtered traffic counted apart.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DailyViews {
+    pub day: NaiveDate,
+    /// Human views, the ones trending lists rank by
+    pub views: i64,
+    /// Views from crawlers, monitors and scripts
+    pub bot_views: i64,
+    /// Views from spam referrers
+    pub spam_views: i64,
+}
+
+/// Human views of a site's posts from one country and region.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct LocationViews {
+    /// ISO 3166-1 alpha-2 country code
+    pub country: String,
+    /// ISO 3166-2 subdivision code within `country`, when known
+    pub region: Option<String>,
+    pub views: i64,
+}
+
+/// Query parameters for daily view counts.
+#[derive(Debug, Deserialize)]
+pub struct DailyViewsQuery {
+    /// Days to cover, up to 365 (default 30)
+    pub days: Option<i32>,
 }
 
 /// Request payload for creating a post.
@@ -100,20 +360,69 @@ pub struct CreatePostRequest {
     pub content: String,
     pub excerpt: Option<String>,
     pub status: Option<PostStatus>,
+    pub visibility: Option<PostVisibility>,
     pub category_id: Option<Uuid>,
     pub tag_ids: Option<Vec<Uuid>>,
+    /// Tags by name, created on the post's site when missing
+    pub tag_names: Option<Vec<String>>,
+    /// Media items to attach, in the order they are listed
+    pub attachment_ids: Option<Vec<Uuid>>,
 }
 
 /// Request payload for updating a post.
-#[derive(Debug, Deserialize)]
+///
+/// Sending `tag_ids` or `tag_names` replaces all of the post's tags, and
+/// sending `attachment_ids` all of its attachments.
+#[derive(Debug, Default, Deserialize)]
 pub struct UpdatePostRequest {
     pub title: Option<String>,
     pub slug: Option<String>,
     pub content: Option<String>,
     pub excerpt: Option<String>,
     pub status: Option<PostStatus>,
+    pub visibility: Option<PostVisibility>,
     pub category_id: Option<Uuid>,
     pub tag_ids: Option<Vec<Uuid>>,
+    /// Tags by name, created on the post's site when missing
+    pub tag_names: Option<Vec<String>>,
+    pub attachment_ids: Option<Vec<Uuid>>,
+    /// Refuse the update when the post changed after this time, like `If-Unmodified-Since`
+    pub unmodified_since: Option<DateTime<Utc>>,
+}
+
+/// Tags to give a post when it is saved, replacing the ones it has.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PostTags {
+    /// Existing tags on the post's site
+    pub ids: Vec<Uuid>,
+    /// `(name, slug)` of tags found or created by slug on the post's site
+    pub names: Vec<(String, String)>,
+}
+
+impl PostTags {
+    /// Tags from a request's `tag_ids` and normalised `tag_names`, or `None`
+    /// when neither was sent, leaving the post's tags as they are.
+    pub fn from_request(
+        tag_ids: Option<Vec<Uuid>>,
+        tag_names: Option<Vec<(String, String)>>,
+    ) -> Option<Self> {
+        if tag_ids.is_none() && tag_names.is_none() {
+            return None;
+        }
+        Some(Self {
+            ids: tag_ids.unwrap_or_default(),
+            names: tag_names.unwrap_or_default(),
+        })
+    }
+}
+
+/// Request body for fetching several posts at once, by ID and/or slug.
+#[derive(Debug, Default, Deserialize)]
+pub struct BatchPostsRequest {
+    #[serde(default)]
+    pub ids: Vec<Uuid>,
+    #[serde(default)]
+    pub slugs: Vec<String>,
 }
 
 /// Query parameters for listing posts.
@@ -147,6 +456,7 @@ mod tests {
     #[test]
     fn test_post_status_display() {
         assert_eq!(PostStatus::Draft.to_string(), "draft");
+        assert_eq!(PostStatus::InReview.to_string(), "in_review");
         assert_eq!(PostStatus::Published.to_string(), "published");
         assert_eq!(PostStatus::Archived.to_string(), "archived");
     }
@@ -156,6 +466,80 @@ mod tests {
         assert_eq!(PostStatus::default(), PostStatus::Draft);
     }
 
+    #[test]
+    fn test_post_status_transitions() {
+        use PostStatus::*;
+
+        assert!(Draft.can_transition_to(Published));
+        assert!(Published.can_transition_to(Draft));
+        assert!(Published.can_transition_to(Archived));
+        assert!(Archived.can_transition_to(Draft));
+        assert!(Archived.can_transition_to(Archived));
+        assert!(!Draft.can_transition_to(Archived));
+        assert!(!Archived.can_transition_to(Published));
+        assert!(Draft.can_transition_to(InReview));
+        assert!(InReview.can_transition_to(Published));
+        assert!(InReview.can_transition_to(Draft));
+        assert!(!InReview.can_transition_to(Archived));
+        assert!(!Published.can_transition_to(InReview));
+    }
+
+    #[test]
+    fn test_post_viewer_can_see() {
+        let author = Uuid::new_v4();
+        let other = PostViewer::Member(Uuid::new_v4());
+
+        assert!(PostViewer::Anonymous.can_see(PostVisibility::Public, author));
+        assert!(!PostViewer::Anonymous.can_see(PostVisibility::Members, author));
+        assert!(other.can_see(PostVisibility::Members, author));
+        assert!(!other.can_see(PostVisibility::Private, author));
+        assert!(PostViewer::Member(author).can_see(PostVisibility::Private, author));
+        assert!(PostViewer::Admin.can_see(PostVisibility::Private, author));
+    }
+
+    #[test]
+    fn test_trending_window_parsing() {
+        assert_eq!("7d".parse::<TrendingWindow>(), Ok(TrendingWindow::Week));
+        assert_eq!("Month".parse::<TrendingWindow>(), Ok(TrendingWindow::Month));
+        assert!("2w".parse::<TrendingWindow>().is_err());
+        assert_eq!(TrendingWindow::Day.to_string(), "1d");
+        assert_eq!(TrendingWindow::default().days(), 7);
+    }
+
+    #[test]
+    fn test_post_archive_groups_by_month() {
+        let item = |published_at: &str| PostListItem {
+            id: Uuid::new_v4(),
+            title: "Title".to_string(),
+            slug: "title".to_string(),
+            excerpt: None,
+            status: PostStatus::Published,
+            visibility: PostVisibility::Public,
+            author_id: Uuid::new_v4(),
+            author_name: None,
+            category_id: None,
+            category_name: None,
+            published_at: Some(published_at.parse().unwrap()),
+            created_at: Utc::now(),
+            snippet: None,
+        };
+        let archive = PostArchive::new(
+            2025,
+            vec![
+                item("2025-01-03T10:00:00Z"),
+                item("2025-01-20T10:00:00Z"),
+                item("2025-04-01T00:00:00Z"),
+            ],
+        );
+        assert_eq!(archive.total, 3);
+        let months: Vec<(u32, usize)> = archive
+            .months
+            .iter()
+            .map(|month| (month.month, month.posts.len()))
+            .collect();
+        assert_eq!(months, vec![(1, 2), (4, 1)]);
+    }
+
     #[test]
     fn test_post_query_default() {
         let query = PostQuery::default();
@@ -163,4 +547,28 @@ mod tests {
         assert_eq!(query.per_page, Some(10));
         assert!(query.status.is_none());
     }
+
+    #[test]
+    fn test_post_tags_from_request() {
+        assert_eq!(PostTags::from_request(None, None), None);
+
+        let id = Uuid::new_v4();
+        let names = vec![("Rust".to_string(), "rust".to_string())];
+        assert_eq!(
+            PostTags::from_request(Some(vec![id]), Some(names.clone())),
+            Some(PostTags {
+                ids: vec![id],
+                names: names.clone(),
+            })
+        );
+        assert_eq!(
+            PostTags::from_request(None, Some(names.clone())),
+            Some(PostTags { ids: vec![], names })
+        );
+        // An empty list clears the post's tags
+        assert_eq!(
+            PostTags::from_request(Some(vec![]), None),
+            Some(PostTags::default())
+        );
+    }
 }
@@ -0,0 +1,26 @@
+//! Post editing lock models.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Header carrying the `lock_id` of the editing lock a request holds.
+pub const POST_LOCK_HEADER: &str = "post-lock";
+
+/// Who is editing a post, and until when the lock holds without a heartbeat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostLock {
+    pub post_id: Uuid,
+    pub user_id: Uuid,
+    pub user_email: String,
+    pub acquired_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A held editing lock, as stored in Redis; the `lock_id` is only shown to its holder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostLockGrant {
+    pub lock_id: String,
+    #[serde(flatten)]
+    pub lock: PostLock,
+}
@@ -0,0 +1,71 @@
+//! Internal post note models.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// An editorial note on a post, visible only to those who may edit it.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct PostNote {
+    pub id: Uuid,
+    pub post_id: Uuid,
+    /// None once the author's account is gone
+    pub author_id: Option<Uuid>,
+    pub author_name: Option<String>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request payload for adding a note; `@email` mentions notify those users.
+#[derive(Debug, Deserialize)]
+pub struct CreatePostNoteRequest {
+    pub body: String,
+}
+
+/// A user mentioned in a note who may read it.
+#[derive(Debug, Clone, FromRow)]
+pub struct MentionedUser {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+}
+
+/// Email addresses mentioned as `@jane@example.com` in a note, lowercased
+/// and without duplicates.
+pub fn mentioned_emails(body: &str) -> Vec<String> {
+    let mut emails: Vec<String> = Vec::new();
+    for word in body.split_whitespace() {
+        let Some(email) = word.strip_prefix('@') else {
+            continue;
+        };
+        let email = email
+            .trim_end_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        let is_email = email
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+        if is_email && !emails.contains(&email) {
+            emails.push(email);
+        }
+    }
+    emails
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mentioned_emails() {
+        assert_eq!(
+            mentioned_emails("@Jane@Example.com, can you check this? cc @bob@test.io."),
+            vec!["jane@example.com", "bob@test.io"]
+        );
+        assert_eq!(
+            mentioned_emails("@jane@example.com @jane@example.com"),
+            vec!["jane@example.com"]
+        );
+        assert!(mentioned_emails("mail jane@example.com or @jane").is_empty());
+    }
+}
@@ -0,0 +1,33 @@
+//! Post review models.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A reviewer's decision on a post submitted for review.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct PostReview {
+    pub id: Uuid,
+    pub post_id: Uuid,
+    /// None once the reviewer's account is gone
+    pub reviewer_id: Option<Uuid>,
+    pub reviewer_name: Option<String>,
+    /// Whether the post was published or sent back to draft
+    pub approved: bool,
+    /// Why the post was rejected, or an optional remark on approval
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request payload for approving a post.
+#[derive(Debug, Default, Deserialize)]
+pub struct ApprovePostRequest {
+    pub note: Option<String>,
+}
+
+/// Request payload for rejecting a post; the note is sent to its author.
+#[derive(Debug, Deserialize)]
+pub struct RejectPostRequest {
+    pub note: String,
+}
@@ -0,0 +1,96 @@
+//! Post template models for recurring post formats.
+
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Post template entity from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct PostTemplate {
+    pub id: Uuid,
+    pub site_id: Uuid,
+    pub name: String,
+    /// Title of new posts; `{date}`, `{year}`, `{month}`, `{day}` and
+    /// `{week}` (ISO week) are replaced with the current date
+    pub title_pattern: String,
+    /// Starting content of new posts
+    pub content: String,
+    pub category_id: Option<Uuid>,
+    pub tag_ids: Vec<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PostTemplate {
+    /// The title of a post created from this template at `now`.
+    pub fn render_title(&self, now: DateTime<Utc>) -> String {
+        let date = now.date_naive();
+        self.title_pattern
+            .replace("{date}", &date.format("%Y-%m-%d").to_string())
+            .replace("{year}", &date.year().to_string())
+            .replace("{month}", &format!("{:02}", date.month()))
+            .replace("{day}", &format!("{:02}", date.day()))
+            .replace("{week}", &format!("{:02}", date.iso_week().week()))
+    }
+}
+
+/// Request payload for creating a post template.
+#[derive(Debug, Deserialize)]
+pub struct CreatePostTemplateRequest {
+    pub name: String,
+    pub title_pattern: String,
+    #[serde(default)]
+    pub content: String,
+    pub category_id: Option<Uuid>,
+    #[serde(default)]
+    pub tag_ids: Vec<Uuid>,
+}
+
+/// Request payload for updating a post template.
+#[derive(Debug, Deserialize)]
+pub struct UpdatePostTemplateRequest {
+    pub name: Option<String>,
+    pub title_pattern: Option<String>,
+    pub content: Option<String>,
+    pub category_id: Option<Uuid>,
+    /// Replaces all of the template's tags
+    pub tag_ids: Option<Vec<Uuid>>,
+}
+
+/// Request payload for creating a post from a template; all fields optional.
+#[derive(Debug, Default, Deserialize)]
+pub struct PostFromTemplateRequest {
+    /// Used instead of the template's rendered title
+    pub title: Option<String>,
+    pub slug: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render_title() {
+        let template = PostTemplate {
+            id: Uuid::new_v4(),
+            site_id: Uuid::new_v4(),
+            name: "Weekly Notes".to_string(),
+            title_pattern: "Weekly Notes {year}-W{week} ({date}, {month}/{day})".to_string(),
+            content: String::new(),
+            category_id: None,
+            tag_ids: Vec::new(),
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let now = Utc.with_ymd_and_hms(2026, 3, 5, 12, 0, 0).unwrap();
+        assert_eq!(
+            template.render_title(now),
+            "Weekly Notes 2026-W10 (2026-03-05, 03/05)"
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! Draft preview token models.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::PostResponse;
+
+/// Prefix identifying draft preview tokens.
+pub const PREVIEW_TOKEN_PREFIX: &str = "pvw_";
+
+/// What a preview token grants access to, as stored in Redis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewGrant {
+    pub post_id: Uuid,
+    pub site_id: Uuid,
+    /// User who minted the token
+    pub created_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A freshly minted preview token; the secret is only shown here.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewToken {
+    pub token: String,
+    pub post_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A post fetched through a preview token.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewResponse {
+    pub post: PostResponse,
+    /// Times the token has been exchanged, including this one
+    pub uses: u64,
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,53 @@
+//! Request quota model definitions.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Who a request is counted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaClient {
+    /// Anonymous requests, per client IP
+    Ip(IpAddr),
+    /// Signed-in requests (sessions and access tokens), per user
+    User(Uuid),
+}
+
+impl fmt::Display for QuotaClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaClient::Ip(ip) => write!(f, "ip:{}", ip),
+            QuotaClient::User(id) => write!(f, "user:{}", id),
+        }
+    }
+}
+
+/// Query parameters selecting the client whose quota to inspect or reset.
+#[derive(Debug, Default, Deserialize)]
+pub struct QuotaClientQuery {
+    pub ip: Option<IpAddr>,
+    pub user_id: Option<Uuid>,
+}
+
+/// Usage of a client's quota in the current window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuotaStatus {
+    /// `ip:<address>` or `user:<id>`
+    pub client: String,
+    /// Requests allowed per window (0 when unlimited)
+    pub limit: u64,
+    pub used: u64,
+    pub remaining: u64,
+    /// When the current window ends and usage starts over
+    pub reset_at: DateTime<Utc>,
+}
+
+impl QuotaStatus {
+    /// Whether the client has used up its quota.
+    pub fn exceeded(&self) -> bool {
+        self.limit > 0 && self.used > self.limit
+    }
+}
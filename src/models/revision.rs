@@ -0,0 +1,71 @@
+//! Post revision models.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::Post;
+use crate::pkg::diff::{DiffSegment, DiffStats};
+
+/// A post's title, excerpt and content after one change to them.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct PostRevision {
+    pub id: Uuid,
+    pub post_id: Uuid,
+    pub title: String,
+    pub excerpt: Option<String>,
+    pub content: String,
+    /// Who made the change; None for Git sync or once their account is gone
+    pub edited_by: Option<Uuid>,
+    pub editor_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PostRevision {
+    /// Whether saving `after` over `before` changed the text a revision keeps.
+    pub fn changed(before: Option<&Post>, after: &Post) -> bool {
+        match before {
+            Some(before) => {
+                before.title != after.title
+                    || before.excerpt != after.excerpt
+                    || before.content != after.content
+            }
+            None => true,
+        }
+    }
+}
+
+/// A revision in a post's history, without its text.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct RevisionSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub edited_by: Option<Uuid>,
+    pub editor_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&PostRevision> for RevisionSummary {
+    fn from(revision: &PostRevision) -> Self {
+        Self {
+            id: revision.id,
+            title: revision.title.clone(),
+            edited_by: revision.edited_by,
+            editor_name: revision.editor_name.clone(),
+            created_at: revision.created_at,
+        }
+    }
+}
+
+/// What changed from one revision to another, field by field.
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionDiff {
+    pub from: RevisionSummary,
+    pub to: RevisionSummary,
+    pub title: Vec<DiffSegment>,
+    pub excerpt: Vec<DiffSegment>,
+    pub content: Vec<DiffSegment>,
+    /// Words inserted and deleted across all fields
+    pub stats: DiffStats,
+}
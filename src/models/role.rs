@@ -25,6 +25,17 @@ pub struct RoleResponse {
     pub slug: String,
     pub description: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Users holding the role, deleted ones included
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_count: Option<i64>,
+}
+
+impl RoleResponse {
+    /// Add how many users hold the role.
+    pub fn with_user_count(mut self, user_count: i64) -> Self {
+        self.user_count = Some(user_count);
+        self
+    }
 }
 
 impl From<Role> for RoleResponse {
@@ -35,6 +46,7 @@ impl From<Role> for RoleResponse {
             slug: role.slug,
             description: role.description,
             created_at: role.created_at,
+            user_count: None,
         }
     }
 }
@@ -55,6 +67,13 @@ pub struct UpdateRoleRequest {
     pub description: Option<String>,
 }
 
+/// Query parameters for deleting a role.
+#[derive(Debug, Deserialize)]
+pub struct DeleteRoleQuery {
+    /// Role to move the role's users to; needed while it has any
+    pub reassign_to: Option<Uuid>,
+}
+
 /// Common role slugs (for convenience, not enforcement).
 pub mod slugs {
     pub const ADMIN: &str = "admin";
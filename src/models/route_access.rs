@@ -0,0 +1,94 @@
+//! Access rules of API routes, as registered in the router.
+
+use serde::Serialize;
+
+/// Who a route group lets through before any per-route permission check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthLevel {
+    /// Anyone, signed in or not
+    Public,
+    /// Anyone; signed-in users may see more
+    Optional,
+    /// Any signed-in user
+    Authenticated,
+    /// Admins only
+    Admin,
+}
+
+/// Access rule of one method on one route.
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteAccess {
+    pub method: &'static str,
+    /// Path with `{param}` placeholders, including the `/api` prefix
+    pub path: String,
+    pub auth: AuthLevel,
+    /// Any one of these permissions is required; empty when none is
+    pub permissions: Vec<&'static str>,
+    /// Whether the route also requires a recent password confirmation
+    pub sudo: bool,
+}
+
+impl RouteAccess {
+    /// Whether a user may call the route, signed in or not.
+    ///
+    /// `has_permission` answers for the user's grants; it is not called for
+    /// anonymous users. Sudo mode is left to the client to request.
+    pub fn allows(
+        &self,
+        signed_in: bool,
+        is_admin: bool,
+        has_permission: impl Fn(&str) -> bool,
+    ) -> bool {
+        let level = match self.auth {
+            AuthLevel::Public | AuthLevel::Optional => true,
+            AuthLevel::Authenticated => signed_in,
+            AuthLevel::Admin => is_admin,
+        };
+        level
+            && (self.permissions.is_empty()
+                || (signed_in && self.permissions.iter().any(|p| has_permission(p))))
+    }
+}
+
+/// A route's access rule and whether the current user passes it.
+#[derive(Debug, Serialize)]
+pub struct RoutePermission {
+    #[serde(flatten)]
+    pub route: RouteAccess,
+    pub allowed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(auth: AuthLevel, permissions: Vec<&'static str>) -> RouteAccess {
+        RouteAccess {
+            method: "POST",
+            path: "/api/posts".to_string(),
+            auth,
+            permissions,
+            sudo: false,
+        }
+    }
+
+    #[test]
+    fn test_allows() {
+        let writer = |p: &str| p == "posts:create";
+
+        assert!(route(AuthLevel::Public, vec![]).allows(false, false, writer));
+        assert!(!route(AuthLevel::Authenticated, vec![]).allows(false, false, writer));
+        assert!(route(AuthLevel::Authenticated, vec![]).allows(true, false, writer));
+        assert!(!route(AuthLevel::Admin, vec![]).allows(true, false, writer));
+        assert!(route(AuthLevel::Admin, vec![]).allows(true, true, writer));
+
+        let create = route(
+            AuthLevel::Authenticated,
+            vec!["posts:review", "posts:create"],
+        );
+        assert!(create.allows(true, false, writer));
+        assert!(!create.allows(true, false, |_| false));
+        assert!(!create.allows(false, false, writer));
+    }
+}
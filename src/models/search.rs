@@ -0,0 +1,90 @@
+//! Admin search model definitions.
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Entity types covered by the admin search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchResultKind {
+    Post,
+    User,
+    Category,
+    Tag,
+}
+
+impl SearchResultKind {
+    /// Every kind, in the order groups are returned.
+    pub const ALL: [SearchResultKind; 4] = [Self::Post, Self::User, Self::Category, Self::Tag];
+}
+
+impl std::str::FromStr for SearchResultKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "post" => Ok(Self::Post),
+            "user" => Ok(Self::User),
+            "category" => Ok(Self::Category),
+            "tag" => Ok(Self::Tag),
+            other => Err(format!("unknown search result kind '{}'", other)),
+        }
+    }
+}
+
+/// Query parameters for the admin search.
+#[derive(Debug, Default, Deserialize)]
+pub struct AdminSearchQuery {
+    pub q: Option<String>,
+    /// Results per entity type
+    pub limit: Option<i64>,
+}
+
+/// One match as returned by the search query.
+#[derive(Debug, Clone, FromRow)]
+pub struct AdminSearchRow {
+    pub kind: String,
+    pub id: Uuid,
+    pub title: String,
+    pub subtitle: Option<String>,
+}
+
+/// A matching entity, labelled for display in a result list.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminSearchItem {
+    pub id: Uuid,
+    /// Post title, user name, or category/tag name
+    pub title: String,
+    /// Post or taxonomy slug, or user email
+    pub subtitle: Option<String>,
+}
+
+/// Matches of one entity type.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminSearchGroup {
+    #[serde(rename = "type")]
+    pub kind: SearchResultKind,
+    pub items: Vec<AdminSearchItem>,
+}
+
+/// Admin search results grouped by entity type.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminSearchResponse {
+    pub query: String,
+    pub groups: Vec<AdminSearchGroup>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_result_kind() {
+        for kind in SearchResultKind::ALL {
+            let name = serde_json::to_value(kind).unwrap();
+            assert_eq!(name.as_str().unwrap().parse::<SearchResultKind>(), Ok(kind));
+        }
+        assert!("page".parse::<SearchResultKind>().is_err());
+    }
+}
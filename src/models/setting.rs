@@ -0,0 +1,17 @@
+//! Setting models for admin-editable, installation-wide settings.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Setting entity from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Setting {
+    /// Namespaced key, e.g. `email_template.invitation`
+    pub key: String,
+    pub value: serde_json::Value,
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,69 @@
+//! Site model for serving several sites from one deployment.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, FromRow};
+use uuid::Uuid;
+
+/// Site entity from database.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Site {
+    pub id: Uuid,
+    pub name: String,
+    /// Lowercase host name without port
+    pub host: String,
+    /// Serves requests whose host matches no site
+    pub is_default: bool,
+    /// Free-form per-site settings such as title, tagline or theme
+    pub settings: Json<BTreeMap<String, serde_json::Value>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request payload for creating a site.
+#[derive(Debug, Deserialize)]
+pub struct CreateSiteRequest {
+    pub name: String,
+    pub host: String,
+    #[serde(default)]
+    pub settings: BTreeMap<String, serde_json::Value>,
+}
+
+/// Request payload for updating a site; `settings` replaces all settings.
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateSiteRequest {
+    pub name: Option<String>,
+    pub host: Option<String>,
+    pub settings: Option<BTreeMap<String, serde_json::Value>>,
+}
+
+/// Normalise a host name for lookup: trimmed, lowercase and without port.
+pub fn normalize_host(host: &str) -> String {
+    let host = host.trim().trim_end_matches('.');
+    let without_port = if let Some(rest) = host.strip_prefix('[') {
+        // Bracketed IPv6 literal, e.g. `[::1]:8080`
+        rest.split(']').next().unwrap_or(rest)
+    } else {
+        match host.rsplit_once(':') {
+            Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+            _ => host,
+        }
+    };
+    without_port.to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_host() {
+        assert_eq!(normalize_host("Blog.Example.com"), "blog.example.com");
+        assert_eq!(normalize_host("localhost:8080"), "localhost");
+        assert_eq!(normalize_host("example.com."), "example.com");
+        assert_eq!(normalize_host("[::1]:3000"), "::1");
+        assert_eq!(normalize_host(" side.dev "), "side.dev");
+    }
+}
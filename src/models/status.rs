@@ -0,0 +1,245 @@
+//! Status models for the public status page.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Check round trip above which a dependency counts as degraded.
+pub const SLOW_CHECK_MS: f64 = 500.0;
+
+/// Queued background jobs above which processing counts as degraded.
+pub const QUEUE_DEPTH_DEGRADED: usize = 100;
+
+/// One self-check of the server and its dependencies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusSample {
+    pub checked_at: DateTime<Utc>,
+    /// Round trip of a trivial query; `None` when the database did not answer
+    pub database_ms: Option<f64>,
+    /// Round trip of a `PING`; `None` when Redis did not answer
+    pub redis_ms: Option<f64>,
+    /// Jobs waiting in background worker queues
+    pub queue_depth: usize,
+    /// When the backup job last reported success, if it ever did
+    pub last_backup_at: Option<DateTime<Utc>>,
+}
+
+/// Health of the site or one of its parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceState {
+    Operational,
+    Degraded,
+    Down,
+    /// No recent checks to go by
+    Unknown,
+}
+
+/// A dependency's latest check and its record over the kept history.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentStatus {
+    pub name: &'static str,
+    pub status: ServiceState,
+    pub latency_ms: Option<f64>,
+    pub avg_latency_ms: Option<f64>,
+    /// Share of checks the dependency answered, 0–100 with two decimals
+    pub uptime_percent: Option<f64>,
+}
+
+/// Background job queue state.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueueStatus {
+    pub status: ServiceState,
+    pub depth: usize,
+}
+
+/// Backup freshness.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BackupStatus {
+    pub status: ServiceState,
+    pub last_success_at: Option<DateTime<Utc>>,
+}
+
+/// Aggregated payload served to the status page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusResponse {
+    pub status: ServiceState,
+    pub checked_at: Option<DateTime<Utc>>,
+    /// Oldest check the uptime figures are based on
+    pub since: Option<DateTime<Utc>>,
+    pub checks: usize,
+    pub components: Vec<ComponentStatus>,
+    pub queue: QueueStatus,
+    /// Only reported when backups are monitored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<BackupStatus>,
+}
+
+impl StatusResponse {
+    /// Summarize `samples` (oldest first) as seen at `now`.
+    ///
+    /// The latest check decides the current states unless it is older than
+    /// `stale_after`, in which case they are unknown. With `backup_max_age`
+    /// set, backups older than that count as degraded.
+    pub fn from_samples(
+        samples: &[StatusSample],
+        now: DateTime<Utc>,
+        stale_after: Duration,
+        backup_max_age: Option<Duration>,
+    ) -> Self {
+        let latest = samples
+            .last()
+            .filter(|sample| now - sample.checked_at <= stale_after);
+
+        let components = vec![
+            component("database", samples, latest, |s| s.database_ms),
+            component("redis", samples, latest, |s| s.redis_ms),
+        ];
+        let queue = QueueStatus {
+            status: match latest {
+                None => ServiceState::Unknown,
+                Some(s) if s.queue_depth > QUEUE_DEPTH_DEGRADED => ServiceState::Degraded,
+                Some(_) => ServiceState::Operational,
+            },
+            depth: latest.map_or(0, |s| s.queue_depth),
+        };
+        let backup = backup_max_age.map(|max_age| {
+            let last_success_at = latest.and_then(|s| s.last_backup_at);
+            BackupStatus {
+                status: match (latest, last_success_at) {
+                    (None, _) => ServiceState::Unknown,
+                    (Some(_), Some(at)) if now - at <= max_age => ServiceState::Operational,
+                    (Some(_), _) => ServiceState::Degraded,
+                },
+                last_success_at,
+            }
+        });
+
+        let status = if latest.is_none() {
+            ServiceState::Unknown
+        } else if components[0].status == ServiceState::Down {
+            ServiceState::Down
+        } else {
+            let worst = components
+                .iter()
+                .map(|c| c.status)
+                .chain([queue.status])
+                .chain(backup.iter().map(|b| b.status))
+                .max()
+                .unwrap_or(ServiceState::Operational);
+            worst.min(ServiceState::Degraded)
+        };
+
+        Self {
+            status,
+            checked_at: latest.map(|s| s.checked_at),
+            since: samples.first().map(|s| s.checked_at),
+            checks: samples.len(),
+            components,
+            queue,
+            backup,
+        }
+    }
+}
+
+/// Summarize one dependency's latency readings.
+fn component(
+    name: &'static str,
+    samples: &[StatusSample],
+    latest: Option<&StatusSample>,
+    latency: impl Fn(&StatusSample) -> Option<f64>,
+) -> ComponentStatus {
+    let answered: Vec<f64> = samples.iter().filter_map(&latency).collect();
+    let latency_ms = latest.and_then(&latency);
+
+    ComponentStatus {
+        name,
+        status: match (latest, latency_ms) {
+            (None, _) => ServiceState::Unknown,
+            (Some(_), None) => ServiceState::Down,
+            (Some(_), Some(ms)) if ms > SLOW_CHECK_MS => ServiceState::Degraded,
+            (Some(_), Some(_)) => ServiceState::Operational,
+        },
+        latency_ms: latency_ms.map(|ms| round(ms, 1)),
+        avg_latency_ms: (!answered.is_empty())
+            .then(|| round(answered.iter().sum::<f64>() / answered.len() as f64, 1)),
+        uptime_percent: (!samples.is_empty())
+            .then(|| round(answered.len() as f64 * 100.0 / samples.len() as f64, 2)),
+    }
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(
+        checked_at: DateTime<Utc>,
+        database_ms: Option<f64>,
+        redis_ms: Option<f64>,
+    ) -> StatusSample {
+        StatusSample {
+            checked_at,
+            database_ms,
+            redis_ms,
+            queue_depth: 0,
+            last_backup_at: Some(checked_at),
+        }
+    }
+
+    #[test]
+    fn test_from_samples() {
+        let now = Utc::now();
+        let minute = Duration::minutes(1);
+        let samples = vec![
+            sample(now - minute * 2, Some(2.0), None),
+            sample(now - minute, Some(4.0), Some(1.0)),
+            sample(now, Some(3.0), Some(1.24)),
+        ];
+
+        let response = StatusResponse::from_samples(&samples, now, minute * 3, None);
+        assert_eq!(response.status, ServiceState::Operational);
+        assert_eq!(response.checks, 3);
+        assert_eq!(response.since, Some(now - minute * 2));
+        assert_eq!(response.backup, None);
+        let redis = &response.components[1];
+        assert_eq!(redis.latency_ms, Some(1.2));
+        assert_eq!(redis.avg_latency_ms, Some(1.1));
+        assert_eq!(redis.uptime_percent, Some(66.67));
+    }
+
+    #[test]
+    fn test_from_samples_states() {
+        let now = Utc::now();
+        let minute = Duration::minutes(1);
+        let day = Some(Duration::days(1));
+
+        let redis_down = [sample(now, Some(1.0), None)];
+        let response = StatusResponse::from_samples(&redis_down, now, minute, day);
+        assert_eq!(response.components[1].status, ServiceState::Down);
+        assert_eq!(response.status, ServiceState::Degraded);
+
+        let database_down = [sample(now, None, Some(1.0))];
+        let response = StatusResponse::from_samples(&database_down, now, minute, day);
+        assert_eq!(response.status, ServiceState::Down);
+
+        let mut old_backup = sample(now, Some(1.0), Some(SLOW_CHECK_MS + 1.0));
+        old_backup.last_backup_at = Some(now - Duration::days(2));
+        let response = StatusResponse::from_samples(&[old_backup], now, minute, day);
+        assert_eq!(response.components[1].status, ServiceState::Degraded);
+        assert_eq!(response.backup.unwrap().status, ServiceState::Degraded);
+
+        let stale = [sample(now - minute * 5, Some(1.0), Some(1.0))];
+        let response = StatusResponse::from_samples(&stale, now, minute, None);
+        assert_eq!(response.status, ServiceState::Unknown);
+        assert_eq!(response.components[0].status, ServiceState::Unknown);
+        assert_eq!(response.components[0].uptime_percent, Some(100.0));
+
+        let response = StatusResponse::from_samples(&[], now, minute, None);
+        assert_eq!(response.status, ServiceState::Unknown);
+        assert_eq!(response.checked_at, None);
+    }
+}
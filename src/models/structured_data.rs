@@ -0,0 +1,206 @@
+//! schema.org structured data for posts.
+
+use serde_json::{json, Map, Value};
+
+use super::{PostResponse, PostVisibility, Site};
+
+/// Per-site setting overriding the public base URL, e.g. `https://example.com`.
+pub const SITE_URL_SETTING: &str = "url";
+
+/// Public base URL of a site: its `url` setting, or `https://<host>`.
+pub fn site_base_url(site: &Site) -> String {
+    match site.settings.get(SITE_URL_SETTING).and_then(Value::as_str) {
+        Some(url) if !url.trim().is_empty() => url.trim().trim_end_matches('/').to_string(),
+        _ => format!("https://{}", site.host),
+    }
+}
+
+/// `BlogPosting` JSON-LD for a post shown at `base_url` + `path`.
+///
+/// Fields the post has no value for are left out rather than sent empty.
+pub fn blog_posting_json_ld(
+    post: &PostResponse,
+    base_url: &str,
+    path: &str,
+    site_name: &str,
+) -> Value {
+    let url = format!("{}{}", base_url, path);
+    let mut data = Map::new();
+    data.insert("@context".to_string(), json!("https://schema.org"));
+    data.insert("@type".to_string(), json!("BlogPosting"));
+    data.insert("@id".to_string(), json!(url));
+    data.insert("url".to_string(), json!(url));
+    data.insert(
+        "mainEntityOfPage".to_string(),
+        json!({ "@type": "WebPage", "@id": url }),
+    );
+    data.insert("headline".to_string(), json!(post.title));
+    if let Some(excerpt) = post.excerpt.as_deref().filter(|e| !e.trim().is_empty()) {
+        data.insert("description".to_string(), json!(excerpt));
+    }
+    if let Some(author) = &post.author {
+        data.insert(
+            "author".to_string(),
+            json!({ "@type": "Person", "name": author.name, "image": author.avatar_url }),
+        );
+    }
+    data.insert(
+        "publisher".to_string(),
+        json!({ "@type": "Organization", "name": site_name, "url": base_url }),
+    );
+    if let Some(published_at) = post.published_at {
+        data.insert(
+            "datePublished".to_string(),
+            json!(published_at.to_rfc3339()),
+        );
+    }
+    data.insert(
+        "dateModified".to_string(),
+        json!(post.updated_at.to_rfc3339()),
+    );
+    if let Some(image) = first_image(&post.content) {
+        data.insert("image".to_string(), json!(absolute_url(base_url, image)));
+    }
+    if let Some(category) = &post.category {
+        data.insert("articleSection".to_string(), json!(category.name));
+    }
+    if !post.tags.is_empty() {
+        let keywords: Vec<&str> = post.tags.iter().map(|tag| tag.name.as_str()).collect();
+        data.insert("keywords".to_string(), json!(keywords.join(", ")));
+    }
+    data.insert(
+        "isAccessibleForFree".to_string(),
+        json!(post.visibility == PostVisibility::Public),
+    );
+    Value::Object(data)
+}
+
+/// URL of the first Markdown image (`![alt](url "title")`) in `content`.
+fn first_image(content: &str) -> Option<&str> {
+    let mut rest = content;
+    while let Some(start) = rest.find("![") {
+        rest = &rest[start + 2..];
+        let target = &rest[rest.find("](")? + 2..];
+        let end = target.find(')')?;
+        let url = target[..end].split_whitespace().next().unwrap_or_default();
+        let url = url.trim_start_matches('<').trim_end_matches('>');
+        if !url.is_empty() {
+            return Some(url);
+        }
+        rest = &target[end..];
+    }
+    None
+}
+
+/// `url` as is when absolute, otherwise resolved against `base_url`.
+fn absolute_url(base_url: &str, url: &str) -> String {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        url.to_string()
+    } else if url.starts_with("//") {
+        format!("https:{}", url)
+    } else {
+        format!("{}/{}", base_url, url.trim_start_matches('/'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use chrono::{TimeZone, Utc};
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    use crate::models::{AuthorResponse, PostStatus, Tag};
+
+    fn site(settings: BTreeMap<String, Value>) -> Site {
+        Site {
+            id: Uuid::new_v4(),
+            name: "Blog".to_string(),
+            host: "blog.example.com".to_string(),
+            is_default: true,
+            settings: Json(settings),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_site_base_url() {
+        assert_eq!(
+            site_base_url(&site(BTreeMap::new())),
+            "https://blog.example.com"
+        );
+        let settings = BTreeMap::from([("url".to_string(), json!("http://localhost:3000/"))]);
+        assert_eq!(site_base_url(&site(settings)), "http://localhost:3000");
+    }
+
+    #[test]
+    fn test_first_image() {
+        assert_eq!(first_image("No images"), None);
+        assert_eq!(
+            first_image("Intro ![](  ) then ![Cover](/media/a.png \"Cover\") and ![b](b.png)"),
+            Some("/media/a.png")
+        );
+        assert_eq!(
+            absolute_url("https://x.dev", "/media/a.png"),
+            "https://x.dev/media/a.png"
+        );
+        assert_eq!(
+            absolute_url("https://x.dev", "https://cdn/a.png"),
+            "https://cdn/a.png"
+        );
+    }
+
+    #[test]
+    fn test_blog_posting_json_ld() {
+        let published_at = Utc.with_ymd_and_hms(2026, 5, 1, 8, 0, 0).unwrap();
+        let post = PostResponse {
+            id: Uuid::new_v4(),
+            title: "Hello".to_string(),
+            slug: "hello".to_string(),
+            content: "![Cover](/media/cover.png)\n\nBody".to_string(),
+            excerpt: None,
+            status: PostStatus::Published,
+            visibility: PostVisibility::Public,
+            author: Some(AuthorResponse {
+                id: Uuid::new_v4(),
+                name: "Ada".to_string(),
+                email: "ada@example.com".to_string(),
+                avatar_url: "https://example.com/ada.png".to_string(),
+            }),
+            category: None,
+            tags: ["Rust", "Web"]
+                .map(|name| Tag {
+                    id: Uuid::new_v4(),
+                    name: name.to_string(),
+                    slug: name.to_lowercase(),
+                    created_at: Utc::now(),
+                    deleted_at: None,
+                })
+                .to_vec(),
+            polls: vec![],
+            attachments: vec![],
+            published_at: Some(published_at),
+            created_at: published_at,
+            updated_at: published_at,
+            structured_data: None,
+        };
+
+        let data = blog_posting_json_ld(&post, "https://x.dev", "/blog/hello", "Blog");
+        assert_eq!(data["@type"], "BlogPosting");
+        assert_eq!(data["url"], "https://x.dev/blog/hello");
+        assert_eq!(data["headline"], "Hello");
+        assert_eq!(data["author"]["name"], "Ada");
+        assert_eq!(data["datePublished"], "2026-05-01T08:00:00+00:00");
+        assert_eq!(data["image"], "https://x.dev/media/cover.png");
+        assert_eq!(data["keywords"], "Rust, Web");
+        // Fields without a value are left out
+        assert!(data.get("description").is_none());
+        assert!(data.get("articleSection").is_none());
+        // Emails are never exposed
+        assert!(!data.to_string().contains("ada@example.com"));
+    }
+}
@@ -0,0 +1,104 @@
+//! Content sync model definitions.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Query parameters for listing content changes.
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncChangesQuery {
+    /// Only changes after this time; everything current when omitted
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// A post, category or tag changed or deleted, as returned by the changes query.
+#[derive(Debug, Clone, FromRow)]
+pub struct ContentChangeRow {
+    pub kind: String,
+    pub id: Uuid,
+    pub updated_at: DateTime<Utc>,
+    pub deleted: bool,
+}
+
+/// A changed entity.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContentChange {
+    pub id: Uuid,
+    /// When it last changed, or was deleted
+    pub updated_at: DateTime<Utc>,
+    /// Deleted (categories and tags may be restored later)
+    pub deleted: bool,
+}
+
+/// Posts, categories and tags changed within a time range.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncChanges {
+    pub since: Option<DateTime<Utc>>,
+    /// Pass as `since` on the next sync
+    pub until: DateTime<Utc>,
+    pub posts: Vec<ContentChange>,
+    pub categories: Vec<ContentChange>,
+    pub tags: Vec<ContentChange>,
+}
+
+impl SyncChanges {
+    /// Sort `rows` (oldest first) into changes per entity type.
+    pub fn new(
+        since: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        rows: Vec<ContentChangeRow>,
+    ) -> Self {
+        let mut changes = Self {
+            since,
+            until,
+            posts: Vec::new(),
+            categories: Vec::new(),
+            tags: Vec::new(),
+        };
+        for row in rows {
+            let list = match row.kind.as_str() {
+                "post" => &mut changes.posts,
+                "category" => &mut changes.categories,
+                "tag" => &mut changes.tags,
+                _ => continue,
+            };
+            list.push(ContentChange {
+                id: row.id,
+                updated_at: row.updated_at,
+                deleted: row.deleted,
+            });
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_changes_new() {
+        let now = Utc::now();
+        let row = |kind: &str, deleted| ContentChangeRow {
+            kind: kind.to_string(),
+            id: Uuid::new_v4(),
+            updated_at: now,
+            deleted,
+        };
+        let rows = vec![
+            row("post", false),
+            row("tag", true),
+            row("post", true),
+            row("page", false),
+        ];
+
+        let changes = SyncChanges::new(None, now, rows.clone());
+        assert_eq!(changes.posts.len(), 2);
+        assert_eq!(changes.posts[1].id, rows[2].id);
+        assert!(changes.posts[1].deleted);
+        assert!(changes.categories.is_empty());
+        assert_eq!(changes.tags.len(), 1);
+        assert!(changes.tags[0].deleted);
+    }
+}
@@ -12,6 +12,18 @@ pub struct Tag {
     pub name: String,
     pub slug: String,
     pub created_at: DateTime<Utc>,
+    /// Only set on soft-deleted tags
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// A tag together with a post it is on.
+#[derive(Debug, Clone, FromRow)]
+pub struct PostTag {
+    pub post_id: Uuid,
+    #[sqlx(flatten)]
+    pub tag: Tag,
 }
 
 /// Request payload for creating a tag.
@@ -28,6 +40,53 @@ pub struct UpdateTagRequest {
     pub slug: Option<String>,
 }
 
+/// Query parameters for tag autocomplete.
+#[derive(Debug, Default, Deserialize)]
+pub struct TagSearchQuery {
+    /// Text typed so far; matched as a name prefix, then by similarity
+    pub q: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Query parameters for the tag cloud.
+#[derive(Debug, Default, Deserialize)]
+pub struct TagCloudQuery {
+    pub limit: Option<i64>,
+    /// Halve a post's contribution every this many days; omit for plain counts
+    pub half_life_days: Option<f64>,
+}
+
+/// Tag with its tag cloud weight.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TagCloudItem {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    /// Published posts carrying the tag
+    pub post_count: i64,
+    /// Post count after time decay, used to rank and weigh tags
+    #[serde(skip_serializing)]
+    pub score: f64,
+    /// Relative size from 0.0 (least used) to 1.0 (most used)
+    #[sqlx(default)]
+    pub weight: f64,
+}
+
+/// Request payload for merging a tag into another one.
+#[derive(Debug, Deserialize)]
+pub struct MergeTagRequest {
+    /// Tag that replaces the merged tag on its posts; the merged tag is deleted
+    pub target_id: Uuid,
+}
+
+/// Outcome of merging a tag.
+#[derive(Debug, Serialize)]
+pub struct TagMergeResponse {
+    pub target: Tag,
+    /// Posts that carried the merged tag
+    pub posts_moved: i64,
+}
+
 /// Tag with post count for listing.
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct TagWithCount {
@@ -49,11 +108,13 @@ mod tests {
             name: "Rust".to_string(),
             slug: "rust".to_string(),
             created_at: Utc::now(),
+            deleted_at: None,
         };
 
         let json = serde_json::to_string(&tag).unwrap();
         assert!(json.contains("Rust"));
         assert!(json.contains("rust"));
+        assert!(!json.contains("deleted_at"));
     }
 
     #[test]
@@ -63,4 +124,13 @@ mod tests {
         assert_eq!(request.name, "Programming");
         assert!(request.slug.is_none());
     }
+
+    #[test]
+    fn test_merge_tag_request() {
+        let target_id = Uuid::new_v4();
+        let json = format!(r#"{{"target_id": "{}"}}"#, target_id);
+        let request: MergeTagRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(request.target_id, target_id);
+        assert!(serde_json::from_str::<MergeTagRequest>("{}").is_err());
+    }
 }
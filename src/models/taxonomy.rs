@@ -0,0 +1,118 @@
+//! Bulk taxonomy import models.
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of taxonomy term in an import file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxonomyKind {
+    Category,
+    Tag,
+}
+
+/// Format of an uploaded taxonomy file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    Json,
+}
+
+impl ImportFormat {
+    /// Detect the format from the upload's file name or content type.
+    pub fn detect(filename: &str, content_type: &str) -> Option<Self> {
+        let filename = filename.to_ascii_lowercase();
+        if filename.ends_with(".csv") || content_type.starts_with("text/csv") {
+            Some(Self::Csv)
+        } else if filename.ends_with(".json") || content_type.starts_with("application/json") {
+            Some(Self::Json)
+        } else {
+            None
+        }
+    }
+}
+
+/// One row of a taxonomy import file.
+///
+/// CSV files use the header `type,name,slug,description,parent`; JSON files
+/// are an array of objects with the same keys.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TaxonomyImportRow {
+    #[serde(rename = "type")]
+    pub kind: TaxonomyKind,
+    pub name: String,
+    pub slug: Option<String>,
+    /// Categories only
+    pub description: Option<String>,
+    /// Slug of the parent category (categories only)
+    pub parent: Option<String>,
+}
+
+/// Category to create from a validated import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewCategory {
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    pub parent_slug: Option<String>,
+}
+
+/// Tag to create from a validated import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewTag {
+    pub name: String,
+    pub slug: String,
+}
+
+/// What happened to an import row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportRowStatus {
+    /// Created (or would have been, had no row been invalid)
+    Created,
+    /// Skipped because the slug is already taken on the site
+    Exists,
+    Invalid,
+}
+
+/// Per-row outcome of a taxonomy import.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRowResult {
+    /// 1-based data row, not counting the CSV header
+    pub row: usize,
+    pub kind: Option<TaxonomyKind>,
+    pub slug: Option<String>,
+    pub status: ImportRowStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Report returned by a taxonomy import.
+#[derive(Debug, Serialize)]
+pub struct TaxonomyImportReport {
+    /// False when any row was invalid; nothing is written in that case
+    pub committed: bool,
+    pub categories_created: usize,
+    pub tags_created: usize,
+    pub rows: Vec<ImportRowResult>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_format_detect() {
+        assert_eq!(
+            ImportFormat::detect("terms.CSV", ""),
+            Some(ImportFormat::Csv)
+        );
+        assert_eq!(
+            ImportFormat::detect("upload", "application/json; charset=utf-8"),
+            Some(ImportFormat::Json)
+        );
+        assert_eq!(
+            ImportFormat::detect("terms.xlsx", "application/octet-stream"),
+            None
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Post title A/B test models.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// One headline in a post's title test, from database.
+///
+/// Position 0 is the post's own title and has no `title` of its own.
+#[derive(Debug, Clone, FromRow)]
+pub struct TitleVariant {
+    pub id: Uuid,
+    pub post_id: Uuid,
+    pub position: i32,
+    pub title: Option<String>,
+    pub impressions: i64,
+    pub clicks: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Results of one headline in a title test.
+#[derive(Debug, Clone, Serialize)]
+pub struct TitleVariantResult {
+    pub id: Uuid,
+    pub title: String,
+    /// Whether this is the post's own title
+    pub original: bool,
+    /// Times the headline was shown in a post list
+    pub impressions: i64,
+    /// Times the post page was read with this headline
+    pub clicks: i64,
+    /// `clicks / impressions`, 0 before the first impression
+    pub click_rate: f64,
+}
+
+impl TitleVariantResult {
+    /// Results of `variant`, showing `post_title` for the original.
+    pub fn new(variant: TitleVariant, post_title: &str) -> Self {
+        let click_rate = if variant.impressions > 0 {
+            variant.clicks as f64 / variant.impressions as f64
+        } else {
+            0.0
+        };
+        Self {
+            id: variant.id,
+            original: variant.title.is_none(),
+            title: variant.title.unwrap_or_else(|| post_title.to_string()),
+            impressions: variant.impressions,
+            clicks: variant.clicks,
+            click_rate,
+        }
+    }
+}
+
+/// A post's running title test.
+#[derive(Debug, Clone, Serialize)]
+pub struct TitleTestResponse {
+    pub post_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    /// The original title first, then the alternatives in order
+    pub variants: Vec<TitleVariantResult>,
+}
+
+/// Request payload for starting (or restarting) a title test.
+#[derive(Debug, Deserialize)]
+pub struct SetTitleVariantsRequest {
+    /// Alternative titles tried next to the post's own
+    pub titles: Vec<String>,
+}
+
+/// Request payload for ending a title test with a winner.
+#[derive(Debug, Deserialize)]
+pub struct PickTitleRequest {
+    pub variant_id: Uuid,
+}
+
+/// Anonymous key of a reader, stable across requests from the same client.
+pub fn visitor_key(ip: &str, user_agent: Option<&str>) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(ip.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(user_agent.unwrap_or_default().as_bytes());
+    hasher.finalize().into()
+}
+
+/// Which of `count` variants of a post a visitor is shown.
+///
+/// The same visitor always gets the same variant of a post, while their
+/// variants of different posts are independent.
+pub fn variant_for(visitor: &[u8; 32], post_id: Uuid, count: usize) -> usize {
+    if count <= 1 {
+        return 0;
+    }
+    let digest = Sha256::new()
+        .chain_update(visitor)
+        .chain_update(post_id.as_bytes())
+        .finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    (u64::from_be_bytes(bytes) % count as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variant_for_is_stable_and_spread() {
+        let post_id = Uuid::new_v4();
+        let visitor = visitor_key("203.0.113.7", Some("Firefox"));
+        let variant = variant_for(&visitor, post_id, 3);
+        assert!(variant < 3);
+        assert_eq!(variant_for(&visitor, post_id, 3), variant);
+        assert_eq!(variant_for(&visitor, post_id, 1), 0);
+
+        let mut seen = [0usize; 3];
+        for i in 0..300 {
+            let visitor = visitor_key(&format!("10.0.{}.{}", i / 256, i % 256), None);
+            seen[variant_for(&visitor, post_id, 3)] += 1;
+        }
+        assert!(seen.iter().all(|count| *count > 50));
+    }
+
+    #[test]
+    fn test_click_rate() {
+        let variant = TitleVariant {
+            id: Uuid::new_v4(),
+            post_id: Uuid::new_v4(),
+            position: 0,
+            title: None,
+            impressions: 200,
+            clicks: 30,
+            created_at: Utc::now(),
+        };
+        let result = TitleVariantResult::new(variant.clone(), "Hello");
+        assert!(result.original);
+        assert_eq!(result.title, "Hello");
+        assert!((result.click_rate - 0.15).abs() < f64::EPSILON);
+
+        let unseen = TitleVariantResult::new(
+            TitleVariant {
+                impressions: 0,
+                clicks: 0,
+                title: Some("Hi".to_string()),
+                ..variant
+            },
+            "Hello",
+        );
+        assert!(!unseen.original);
+        assert_eq!(unseen.click_rate, 0.0);
+    }
+}
@@ -1,11 +1,18 @@
 //! User model definitions.
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sha2::{Digest, Sha256};
+use sqlx::{types::Json, FromRow};
 use uuid::Uuid;
 
 use super::RoleResponse;
+use crate::pkg::sql::escape_like;
+
+/// Placeholder user that owns content of erased accounts (created by migration 019).
+pub const DELETED_USER_ID: Uuid = Uuid::nil();
 
 /// User entity from database.
 #[derive(Debug, Clone, FromRow, Serialize)]
@@ -16,11 +23,34 @@ pub struct User {
     pub password_hash: String,
     pub name: String,
     pub role_id: Uuid,
+    pub is_active: bool,
+    pub avatar_url: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
 }
 
+impl User {
+    /// Avatar to display: the user's own picture, or their Gravatar.
+    pub fn display_avatar_url(&self) -> String {
+        display_avatar_url(self.avatar_url.as_deref(), &self.email)
+    }
+}
+
+/// Gravatar URL for an email address (identicon when none is registered).
+pub fn gravatar_url(email: &str) -> String {
+    let hash = Sha256::digest(email.trim().to_lowercase().as_bytes());
+    format!("https://www.gravatar.com/avatar/{:x}?d=identicon", hash)
+}
+
+/// Resolve an optional avatar URL, falling back to Gravatar.
+pub fn display_avatar_url(avatar_url: Option<&str>, email: &str) -> String {
+    match avatar_url {
+        Some(url) if !url.is_empty() => url.to_string(),
+        _ => gravatar_url(email),
+    }
+}
+
 /// User with role info for API responses.
 #[derive(Debug, Clone, Serialize)]
 pub struct UserResponse {
@@ -42,8 +72,65 @@ pub struct UserWithRole {
     pub role_id: Uuid,
     pub role_slug: String,
     pub role_name: String,
+    pub is_active: bool,
+    pub avatar_url: Option<String>,
+    /// Unset while a self-registered account awaits approval
+    pub approved_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for listing users.
+#[derive(Debug, Default, Deserialize)]
+pub struct UserListQuery {
+    /// Include soft-deleted users
+    pub include_deleted: Option<bool>,
+    /// Only list accounts awaiting approval
+    pub pending: Option<bool>,
+    /// Match part of the name or email
+    pub search: Option<String>,
+    /// Only list users with this role slug
+    pub role: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// Continue after the previous page (its `meta.next_cursor`); `page` is ignored
+    pub cursor: Option<String>,
+}
+
+impl UserListQuery {
+    /// The filters of this query, with `search` matched literally.
+    pub fn filter(&self) -> UserFilter {
+        UserFilter {
+            include_deleted: self.include_deleted.unwrap_or(false),
+            pending_only: self.pending.unwrap_or(false),
+            pattern: self
+                .search
+                .as_deref()
+                .map(str::trim)
+                .filter(|search| !search.is_empty())
+                .map(|search| format!("%{}%", escape_like(search))),
+            role_slug: self.role.clone().filter(|role| !role.is_empty()),
+        }
+    }
+}
+
+/// Filters of a user listing.
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    pub include_deleted: bool,
+    pub pending_only: bool,
+    /// `ILIKE` pattern matched against name and email
+    pub pattern: Option<String>,
+    pub role_slug: Option<String>,
+}
+
+/// Request payload for public self-registration.
+#[derive(Debug, Deserialize)]
+pub struct RegisterRequest {
+    pub email: String,
+    pub name: String,
+    pub password: String,
 }
 
 /// Request payload for creating a user.
@@ -55,6 +142,117 @@ pub struct CreateUserRequest {
     pub role_id: Option<Uuid>,
 }
 
+/// Request payload for inviting a user.
+#[derive(Debug, Deserialize)]
+pub struct InviteUserRequest {
+    pub email: String,
+    /// Defaults to `DEFAULT_ROLE`
+    pub role_id: Option<Uuid>,
+}
+
+/// Invitation details returned to the inviting admin.
+#[derive(Debug, Serialize)]
+pub struct InvitationResponse {
+    pub email: String,
+    pub role_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Request payload for completing registration from an invitation.
+#[derive(Debug, Deserialize)]
+pub struct AcceptInviteRequest {
+    pub token: String,
+    pub name: String,
+    pub password: String,
+}
+
+/// Request payload for updating a user (passwords are changed separately).
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateUserRequest {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub role_id: Option<Uuid>,
+    pub is_active: Option<bool>,
+}
+
+/// The authenticated user's own profile.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct UserProfile {
+    pub id: Uuid,
+    pub email: String,
+    /// New email address awaiting verification
+    pub pending_email: Option<String>,
+    pub name: String,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    /// Uploaded media item backing `avatar_url`, if any
+    pub avatar_media_id: Option<Uuid>,
+    /// Social profile links keyed by network, e.g. `github`
+    pub social_links: Json<BTreeMap<String, String>>,
+    pub role_id: Uuid,
+    pub role_slug: String,
+    pub role_name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request payload for editing one's own profile.
+///
+/// Empty `bio` or `avatar_url` strings clear the field; a new email only takes
+/// effect once verified.
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub social_links: Option<BTreeMap<String, String>>,
+}
+
+/// Request payload for confirming an email change.
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+/// Outcome of permanently erasing an account.
+#[derive(Debug, Serialize)]
+pub struct ErasureReceipt {
+    pub user_id: Uuid,
+    /// Posts now attributed to the deleted-user placeholder
+    pub posts_reassigned: i64,
+    /// Audit log entry recording the erasure
+    pub audit_id: Uuid,
+    pub erased_at: DateTime<Utc>,
+}
+
+/// Request payload for entering sudo mode.
+#[derive(Debug, Deserialize)]
+pub struct SudoRequest {
+    pub password: String,
+}
+
+/// Sudo mode window granted after confirming the password.
+#[derive(Debug, Serialize)]
+pub struct SudoResponse {
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Request payload for changing the authenticated user's password.
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// The authenticated user's effective permissions.
+#[derive(Debug, Serialize)]
+pub struct MyPermissionsResponse {
+    pub role_slug: String,
+    pub is_admin: bool,
+    pub permissions: Vec<String>,
+}
+
 /// Request payload for login.
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
@@ -81,21 +279,44 @@ pub struct UserWithRoleResponse {
     pub role_id: Uuid,
     pub role_slug: String,
     pub role_name: String,
+    pub is_active: bool,
+    /// Uploaded avatar or Gravatar fallback
+    pub avatar_url: String,
+    /// Self-registered account not yet approved by an admin
+    pub pending_approval: bool,
+    /// Set when the user has been soft-deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl From<UserWithRole> for UserWithRoleResponse {
     fn from(user: UserWithRole) -> Self {
         Self {
+            avatar_url: display_avatar_url(user.avatar_url.as_deref(), &user.email),
             id: user.id,
             email: user.email,
             name: user.name,
             role_id: user.role_id,
             role_slug: user.role_slug,
             role_name: user.role_name,
+            is_active: user.is_active,
+            pending_approval: user.approved_at.is_none(),
+            deleted_at: user.deleted_at,
         }
     }
 }
 
+/// Health of session token validation.
+#[derive(Debug, Serialize)]
+pub struct AuthStatus {
+    /// Whether `AUTH_DEGRADED_MODE` is enabled
+    pub degraded_mode: bool,
+    /// Access tokens accepted without Redis checks since startup
+    pub degraded_validations: u64,
+    /// When the last such token was accepted
+    pub last_degraded_at: Option<DateTime<Utc>>,
+}
+
 /// Request payload for token refresh.
 #[derive(Debug, Deserialize)]
 pub struct RefreshTokenRequest {
@@ -114,6 +335,21 @@ pub struct RefreshTokenResponse {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_user_list_filter() {
+        let query: UserListQuery =
+            serde_json::from_str(r#"{"search":" 50%_off ","role":"editor","pending":true}"#)
+                .unwrap();
+        let filter = query.filter();
+        assert_eq!(filter.pattern.as_deref(), Some("%50\\%\\_off%"));
+        assert_eq!(filter.role_slug.as_deref(), Some("editor"));
+        assert!(filter.pending_only);
+        assert!(!filter.include_deleted);
+
+        let query: UserListQuery = serde_json::from_str(r#"{"search":"  "}"#).unwrap();
+        assert!(query.filter().pattern.is_none());
+    }
+
     #[test]
     fn test_login_request_deserialization() {
         let json = r#"{"email":"test@example.com","password":"secret"}"#;
@@ -132,10 +368,68 @@ mod tests {
             role_id: Uuid::new_v4(),
             role_slug: "admin".to_string(),
             role_name: "Administrator".to_string(),
+            is_active: true,
+            avatar_url: None,
+            approved_at: Some(Utc::now()),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            deleted_at: None,
         };
         let response: UserWithRoleResponse = user.into();
         assert_eq!(response.role_slug, "admin");
+        assert!(response.is_active);
+        assert!(!response.pending_approval);
+        assert!(response
+            .avatar_url
+            .starts_with("https://www.gravatar.com/avatar/"));
+    }
+
+    #[test]
+    fn test_deleted_at_only_serialized_when_set() {
+        let mut response = UserWithRoleResponse {
+            id: Uuid::new_v4(),
+            email: "test@test.com".to_string(),
+            name: "Test".to_string(),
+            role_id: Uuid::new_v4(),
+            role_slug: "viewer".to_string(),
+            role_name: "Viewer".to_string(),
+            is_active: true,
+            avatar_url: gravatar_url("test@test.com"),
+            pending_approval: false,
+            deleted_at: None,
+        };
+        assert!(!serde_json::to_string(&response)
+            .unwrap()
+            .contains("deleted_at"));
+
+        response.deleted_at = Some(Utc::now());
+        assert!(serde_json::to_string(&response)
+            .unwrap()
+            .contains("deleted_at"));
+    }
+
+    #[test]
+    fn test_display_avatar_url() {
+        assert_eq!(
+            gravatar_url(" Me@Example.com "),
+            gravatar_url("me@example.com")
+        );
+        assert_eq!(
+            display_avatar_url(Some("/uploads/a.png"), "me@example.com"),
+            "/uploads/a.png"
+        );
+        assert_eq!(
+            display_avatar_url(Some(""), "me@example.com"),
+            gravatar_url("me@example.com")
+        );
+    }
+
+    #[test]
+    fn test_update_user_request_partial() {
+        let json = r#"{"role_id":null,"is_active":false}"#;
+        let req: UpdateUserRequest = serde_json::from_str(json).unwrap();
+        assert!(req.name.is_none());
+        assert!(req.role_id.is_none());
+        assert_eq!(req.is_active, Some(false));
     }
 }
@@ -0,0 +1,146 @@
+//! Outgoing webhook attempt models for the delivery log.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::{Job, JobKind};
+
+/// One request to a webhook (frontend revalidation or event delivery) and its outcome.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct WebhookAttempt {
+    pub id: Uuid,
+    pub kind: JobKind,
+    pub url: String,
+    /// Headers sent, with credentials redacted
+    pub request_headers: Value,
+    /// Body exactly as sent
+    pub payload: String,
+    /// Missing when no response arrived
+    pub response_status: Option<i32>,
+    /// First part of the response body
+    pub response_body: Option<String>,
+    /// Why the attempt did not succeed
+    pub error: Option<String>,
+    pub duration_ms: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WebhookAttempt {
+    /// The job sending the same delivery again.
+    pub fn job(&self) -> Result<Job, String> {
+        let invalid = |e: serde_json::Error| format!("Invalid {:?} payload: {}", self.kind, e);
+        match self.kind {
+            JobKind::Revalidation => {
+                #[derive(Deserialize)]
+                struct Body {
+                    site_id: Uuid,
+                    paths: Vec<String>,
+                }
+                let body: Body = serde_json::from_str(&self.payload).map_err(invalid)?;
+                Ok(Job::Revalidation {
+                    site_id: body.site_id,
+                    paths: body.paths,
+                })
+            }
+            JobKind::Event => {
+                #[derive(Deserialize)]
+                struct Body {
+                    id: Uuid,
+                    #[serde(rename = "type")]
+                    event_type: String,
+                    created_at: DateTime<Utc>,
+                    data: Value,
+                }
+                let body: Body = serde_json::from_str(&self.payload).map_err(invalid)?;
+                Ok(Job::Event {
+                    event_id: body.id,
+                    event_type: body.event_type,
+                    created_at: body.created_at,
+                    data: body.data,
+                })
+            }
+            JobKind::Email => Err("Emails are not webhooks".to_string()),
+        }
+    }
+}
+
+/// An attempt about to be logged.
+#[derive(Debug)]
+pub struct NewWebhookAttempt<'a> {
+    pub kind: JobKind,
+    pub url: &'a str,
+    pub request_headers: Value,
+    pub payload: &'a str,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<&'a str>,
+    pub duration_ms: i32,
+}
+
+/// Query parameters for listing webhook attempts.
+#[derive(Debug, Deserialize)]
+pub struct WebhookAttemptQuery {
+    pub kind: Option<JobKind>,
+    /// Only failed (`true`) or successful (`false`) attempts
+    pub failed: Option<bool>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(kind: JobKind, payload: &str) -> WebhookAttempt {
+        WebhookAttempt {
+            id: Uuid::new_v4(),
+            kind,
+            url: "https://example.com/hook".to_string(),
+            request_headers: serde_json::json!({}),
+            payload: payload.to_string(),
+            response_status: Some(502),
+            response_body: None,
+            error: Some("status 502".to_string()),
+            duration_ms: 12,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_attempt_job() {
+        let site_id = Uuid::new_v4();
+        let body = serde_json::json!({ "site_id": site_id, "paths": ["/", "/blog"] }).to_string();
+        assert_eq!(
+            attempt(JobKind::Revalidation, &body).job(),
+            Ok(Job::Revalidation {
+                site_id,
+                paths: vec!["/".to_string(), "/blog".to_string()],
+            })
+        );
+
+        let event_id = Uuid::new_v4();
+        let created_at = Utc::now();
+        let body = serde_json::json!({
+            "id": event_id,
+            "type": "post.published",
+            "created_at": created_at,
+            "data": { "slug": "hello" },
+        })
+        .to_string();
+        assert_eq!(
+            attempt(JobKind::Event, &body).job(),
+            Ok(Job::Event {
+                event_id,
+                event_type: "post.published".to_string(),
+                created_at,
+                data: serde_json::json!({ "slug": "hello" }),
+            })
+        );
+
+        assert!(attempt(JobKind::Event, "{}").job().is_err());
+        assert!(attempt(JobKind::Email, "{}").job().is_err());
+    }
+}
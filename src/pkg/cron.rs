@@ -0,0 +1,185 @@
+//! Five-field cron schedules evaluated in UTC.
+//!
+//! Fields are `minute hour day-of-month month day-of-week`, each `*`, a
+//! number, a range `a-b`, a step `*/n` or `a-b/n`, or a comma-separated list
+//! of those. Day of week runs from 0 (Sunday) to 6, with 7 also meaning
+//! Sunday. As in classic cron, when both day fields are restricted a day
+//! matching either one runs.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+
+/// Years searched for the next run before a schedule is considered never due.
+const SEARCH_YEARS: i32 = 5;
+
+/// A parsed cron schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronSchedule {
+    /// First time strictly after `after`, at a whole minute, that the schedule is due.
+    ///
+    /// Returns `None` for schedules that never come due, like `0 0 31 2 *`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut time = after.naive_utc().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let last_year = after.year() + SEARCH_YEARS;
+
+        while time.year() <= last_year {
+            let date = time.date();
+            if !has(self.months, date.month()) {
+                let (year, month) = match date.month() {
+                    12 => (date.year() + 1, 1),
+                    month => (date.year(), month + 1),
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_time(NaiveTime::MIN);
+            } else if !self.day_matches(date) {
+                time = date.succ_opt()?.and_time(NaiveTime::MIN);
+            } else if !has(self.hours, time.hour()) {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if !has(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time.and_utc());
+            }
+        }
+        None
+    }
+
+    // Private helper methods
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "'{}' must have five fields: minute hour day-of-month month day-of-week",
+                s
+            ));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7, "day of week")?;
+        // 7 is another name for Sunday
+        if has(weekdays, 7) {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days: parse_field(day, 1, 31, "day of month")?,
+            months: parse_field(month, 1, 12, "month")?,
+            weekdays,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+        })
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Bit set of the values a field allows.
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid {} field '{}'", name, field);
+    let mut set = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (
+                    start.parse().map_err(|_| invalid())?,
+                    end.parse().map_err(|_| invalid())?,
+                ),
+                None => {
+                    let value = range.parse().map_err(|_| invalid())?;
+                    // `5/15` means from 5 to the end in steps of 15
+                    (value, if part.contains('/') { max } else { value })
+                }
+            },
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn next(schedule: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        schedule.parse::<CronSchedule>().unwrap().next_after(after)
+    }
+
+    #[test]
+    fn test_next_after() {
+        let now = at(2026, 5, 1, 3, 0) + Duration::seconds(30);
+        assert_eq!(next("* * * * *", now), Some(at(2026, 5, 1, 3, 1)));
+        assert_eq!(next("0 3 * * *", now), Some(at(2026, 5, 2, 3, 0)));
+        assert_eq!(next("*/15 * * * *", now), Some(at(2026, 5, 1, 3, 15)));
+        assert_eq!(next("30 2 1 * *", now), Some(at(2026, 6, 1, 2, 30)));
+        assert_eq!(next("0 0 1 1 *", now), Some(at(2027, 1, 1, 0, 0)));
+        // 2026-05-01 is a Friday; the next Sunday is the 3rd
+        assert_eq!(next("0 4 * * 7", now), Some(at(2026, 5, 3, 4, 0)));
+        assert_eq!(next("0 4 * * 1-5", now), Some(at(2026, 5, 1, 4, 0)));
+        // Either day field matches when both are restricted
+        assert_eq!(next("0 0 15 * 0", now), Some(at(2026, 5, 3, 0, 0)));
+        assert_eq!(next("0 0 29 2 *", now), Some(at(2028, 2, 29, 0, 0)));
+        assert_eq!(next("0 0 31 2 *", now), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for schedule in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(schedule.parse::<CronSchedule>().is_err(), "{}", schedule);
+        }
+        assert!("0,30 1-5/2 * * 1,3,5".parse::<CronSchedule>().is_ok());
+    }
+}
@@ -0,0 +1,63 @@
+//! Opaque cursors for keyset pagination.
+//!
+//! Listings ordered newest first by `(created_at, id)` continue after the
+//! last row of a page instead of skipping rows with `OFFSET`, so pages stay
+//! fast and stable while rows are added. The cursor is that row's key, base64
+//! encoded so clients treat it as a token.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// Position after a row in a listing ordered by `(created_at, id)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    /// Cursor continuing after the row with this key.
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+
+    /// The token handed to clients.
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}|{}", self.created_at.to_rfc3339(), self.id))
+    }
+
+    /// Parse a token from [`Cursor::encode`].
+    pub fn decode(token: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::ValidationError("Invalid cursor".to_string());
+        let bytes = URL_SAFE_NO_PAD.decode(token).map_err(|_| invalid())?;
+        let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (created_at, id) = text.split_once('|').ok_or_else(invalid)?;
+        Ok(Self {
+            created_at: DateTime::parse_from_rfc3339(created_at)
+                .map_err(|_| invalid())?
+                .with_timezone(&Utc),
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let cursor = Cursor::new(Utc::now(), Uuid::new_v4());
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(Cursor::decode("").is_err());
+        assert!(Cursor::decode("not a cursor").is_err());
+        assert!(Cursor::decode(&URL_SAFE_NO_PAD.encode("2024-01-01|x")).is_err());
+    }
+}
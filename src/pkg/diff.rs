@@ -0,0 +1,337 @@
+//! Line and word diffs of text, for highlighting changes between revisions.
+//!
+//! Texts are compared line by line first, then the words of each block of
+//! changed lines, both with Myers' algorithm. The result is a list of
+//! segments: joining the `equal` and `delete` ones gives the old text, the
+//! `equal` and `insert` ones the new text.
+
+use serde::Serialize;
+
+/// Most edits searched for between two sequences before giving up and
+/// treating them as entirely replaced, bounding time and memory.
+const MAX_EDIT_DISTANCE: usize = 2_000;
+
+/// What happened to a segment of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// A run of text that was kept, inserted or deleted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffSegment {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// Words inserted and deleted by a diff.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DiffStats {
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl DiffStats {
+    /// Count the words in the changed segments of `segments`.
+    pub fn add(&mut self, segments: &[DiffSegment]) {
+        for segment in segments {
+            let words = segment.text.split_whitespace().count();
+            match segment.op {
+                DiffOp::Insert => self.insertions += words,
+                DiffOp::Delete => self.deletions += words,
+                DiffOp::Equal => {}
+            }
+        }
+    }
+}
+
+/// Diff `old` against `new`: unchanged lines whole, changed lines word by word.
+pub fn diff_text(old: &str, new: &str) -> Vec<DiffSegment> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let mut segments = Vec::new();
+    let Some(ops) = myers(&old_lines, &new_lines) else {
+        push(&mut segments, DiffOp::Delete, old);
+        push(&mut segments, DiffOp::Insert, new);
+        return segments;
+    };
+
+    let (mut i, mut j) = (0, 0);
+    let (mut deleted, mut inserted) = (String::new(), String::new());
+    for op in ops {
+        match op {
+            DiffOp::Equal => {
+                diff_words(&mut segments, &deleted, &inserted);
+                deleted.clear();
+                inserted.clear();
+                push(&mut segments, DiffOp::Equal, old_lines[i]);
+                i += 1;
+                j += 1;
+            }
+            DiffOp::Delete => {
+                deleted.push_str(old_lines[i]);
+                i += 1;
+            }
+            DiffOp::Insert => {
+                inserted.push_str(new_lines[j]);
+                j += 1;
+            }
+        }
+    }
+    diff_words(&mut segments, &deleted, &inserted);
+    segments
+}
+
+/// Append the word diff of a block of deleted and inserted lines.
+fn diff_words(segments: &mut Vec<DiffSegment>, old: &str, new: &str) {
+    if old.is_empty() || new.is_empty() {
+        push(segments, DiffOp::Delete, old);
+        push(segments, DiffOp::Insert, new);
+        return;
+    }
+    let old_words = tokenize(old);
+    let new_words = tokenize(new);
+    let Some(ops) = myers(&old_words, &new_words) else {
+        push(segments, DiffOp::Delete, old);
+        push(segments, DiffOp::Insert, new);
+        return;
+    };
+    let (mut i, mut j) = (0, 0);
+    for op in ops {
+        match op {
+            DiffOp::Equal => {
+                push(segments, op, old_words[i]);
+                i += 1;
+                j += 1;
+            }
+            DiffOp::Delete => {
+                push(segments, op, old_words[i]);
+                i += 1;
+            }
+            DiffOp::Insert => {
+                push(segments, op, new_words[j]);
+                j += 1;
+            }
+        }
+    }
+}
+
+/// Append `text`, merging it into the last segment when that has the same op.
+fn push(segments: &mut Vec<DiffSegment>, op: DiffOp, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match segments.last_mut() {
+        Some(last) if last.op == op => last.text.push_str(text),
+        _ => segments.push(DiffSegment {
+            op,
+            text: text.to_string(),
+        }),
+    }
+}
+
+/// Split text into words, runs of whitespace and single punctuation marks.
+fn tokenize(text: &str) -> Vec<&str> {
+    #[derive(PartialEq)]
+    enum Class {
+        Word,
+        Space,
+        Mark,
+    }
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' || c == '\'' {
+            Class::Word
+        } else if c.is_whitespace() {
+            Class::Space
+        } else {
+            Class::Mark
+        }
+    };
+
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut current: Option<Class> = None;
+    for (index, c) in text.char_indices() {
+        let next = class(c);
+        let joins = current
+            .as_ref()
+            .is_some_and(|current| *current == next && next != Class::Mark);
+        if !joins && index > start {
+            tokens.push(&text[start..index]);
+            start = index;
+        }
+        current = Some(next);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// Shortest edit script turning `a` into `b`, or `None` when it needs more
+/// than [`MAX_EDIT_DISTANCE`] insertions and deletions.
+fn myers<T: PartialEq>(a: &[T], b: &[T]) -> Option<Vec<DiffOp>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = ((n + m) as usize).min(MAX_EDIT_DISTANCE) as isize;
+    // Furthest x reached on each diagonal k = x - y, indexed by k + max + 1
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    let index = |k: isize| (k + max + 1) as usize;
+    // The diagonals -d..=d of `v` before each round d, for backtracking
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    for d in 0..=max {
+        trace.push(v[index(-d)..=index(d)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                return Some(backtrack(&trace, n, m));
+            }
+        }
+    }
+    None
+}
+
+/// Walk back from `(n, m)` through the rounds recorded by [`myers`].
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<DiffOp> {
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().skip(1).rev() {
+        let d = d as isize;
+        // `v` holds diagonals -d..=d
+        let at = |k: isize| v[(k + d) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            ops.push(DiffOp::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        ops.push(if x == prev_x {
+            DiffOp::Insert
+        } else {
+            DiffOp::Delete
+        });
+        x = prev_x;
+        y = prev_y;
+    }
+    while x > 0 && y > 0 {
+        ops.push(DiffOp::Equal);
+        x -= 1;
+        y -= 1;
+    }
+    ops.reverse();
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rebuild(segments: &[DiffSegment], skip: DiffOp) -> String {
+        segments
+            .iter()
+            .filter(|segment| segment.op != skip)
+            .map(|segment| segment.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_text_words() {
+        let old = "Intro line\nThe quick brown fox.\nLast line\n";
+        let new = "Intro line\nThe slow brown fox!\nLast line\nAdded\n";
+        let segments = diff_text(old, new);
+        assert_eq!(rebuild(&segments, DiffOp::Insert), old);
+        assert_eq!(rebuild(&segments, DiffOp::Delete), new);
+        assert_eq!(
+            segments,
+            vec![
+                DiffSegment {
+                    op: DiffOp::Equal,
+                    text: "Intro line\nThe ".to_string()
+                },
+                DiffSegment {
+                    op: DiffOp::Delete,
+                    text: "quick".to_string()
+                },
+                DiffSegment {
+                    op: DiffOp::Insert,
+                    text: "slow".to_string()
+                },
+                DiffSegment {
+                    op: DiffOp::Equal,
+                    text: " brown fox".to_string()
+                },
+                DiffSegment {
+                    op: DiffOp::Delete,
+                    text: ".".to_string()
+                },
+                DiffSegment {
+                    op: DiffOp::Insert,
+                    text: "!".to_string()
+                },
+                DiffSegment {
+                    op: DiffOp::Equal,
+                    text: "\nLast line\n".to_string()
+                },
+                DiffSegment {
+                    op: DiffOp::Insert,
+                    text: "Added\n".to_string()
+                },
+            ]
+        );
+
+        let mut stats = DiffStats::default();
+        stats.add(&segments);
+        assert_eq!(
+            stats,
+            DiffStats {
+                insertions: 3,
+                deletions: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_text_edge_cases() {
+        assert!(diff_text("", "").is_empty());
+        assert_eq!(
+            diff_text("same", "same"),
+            vec![DiffSegment {
+                op: DiffOp::Equal,
+                text: "same".to_string()
+            }]
+        );
+        let segments = diff_text("", "new text");
+        assert_eq!(rebuild(&segments, DiffOp::Delete), "new text");
+        let segments = diff_text("café au lait", "café noir");
+        assert_eq!(rebuild(&segments, DiffOp::Insert), "café au lait");
+        assert_eq!(rebuild(&segments, DiffOp::Delete), "café noir");
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("It's a  test, ok?"),
+            vec!["It's", " ", "a", "  ", "test", ",", " ", "ok", "?"]
+        );
+    }
+}
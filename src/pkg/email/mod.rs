@@ -0,0 +1,307 @@
+//! Templates for transactional emails.
+//!
+//! Each [`EmailTemplate`] has a default subject and plain-text body compiled
+//! into the binary (`templates/*.txt`), rendered with [Tera] against the
+//! variables the sending flow provides. Admins can replace either part with an
+//! override kept in the database; overrides are checked against sample values
+//! before they are saved, so a typo in a variable name is caught up front.
+//!
+//! [Tera]: https://keats.github.io/tera/docs/#templates
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tera::{Context, Tera};
+
+/// A transactional email the server sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailTemplate {
+    /// Sent when an admin approves a self-registered account
+    AccountApproved,
+    /// Invitation to create an account
+    Invitation,
+    /// Confirmation link for a new email address
+    EmailChange,
+    /// Notice to the old address of a requested email change
+    EmailChangeNotice,
+    /// Alert after repeated failed sign-in attempts
+    FailedLogins,
+    /// Alert after a sign-in from an unfamiliar device or country
+    NewLogin,
+    /// Weekly summary of an author's post stats
+    WeeklyDigest,
+    /// A reviewer sent the author's post back to draft
+    PostRejected,
+    /// Someone mentioned the user in an internal post note
+    NoteMention,
+}
+
+/// Subject and body of a template, before rendering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateSource {
+    pub subject: String,
+    pub body: String,
+}
+
+/// A rendered email, ready to send.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub body: String,
+}
+
+impl EmailTemplate {
+    /// Every template, in the order they are listed to admins.
+    pub const ALL: [EmailTemplate; 9] = [
+        EmailTemplate::AccountApproved,
+        EmailTemplate::Invitation,
+        EmailTemplate::EmailChange,
+        EmailTemplate::EmailChangeNotice,
+        EmailTemplate::FailedLogins,
+        EmailTemplate::NewLogin,
+        EmailTemplate::WeeklyDigest,
+        EmailTemplate::PostRejected,
+        EmailTemplate::NoteMention,
+    ];
+
+    /// Name used in admin URLs and as the override's settings key.
+    pub fn name(self) -> &'static str {
+        match self {
+            EmailTemplate::AccountApproved => "account_approved",
+            EmailTemplate::Invitation => "invitation",
+            EmailTemplate::EmailChange => "email_change",
+            EmailTemplate::EmailChangeNotice => "email_change_notice",
+            EmailTemplate::FailedLogins => "failed_logins",
+            EmailTemplate::NewLogin => "new_login",
+            EmailTemplate::WeeklyDigest => "weekly_digest",
+            EmailTemplate::PostRejected => "post_rejected",
+            EmailTemplate::NoteMention => "note_mention",
+        }
+    }
+
+    /// When the email is sent.
+    pub fn description(self) -> &'static str {
+        match self {
+            EmailTemplate::AccountApproved => "An admin approved a self-registered account",
+            EmailTemplate::Invitation => "Someone was invited to create an account",
+            EmailTemplate::EmailChange => "A user changed their email address",
+            EmailTemplate::EmailChangeNotice => {
+                "A user asked to change their email address (sent to the old one)"
+            }
+            EmailTemplate::FailedLogins => "Repeated failed sign-in attempts on an account",
+            EmailTemplate::NewLogin => "A sign-in from an unfamiliar device or country",
+            EmailTemplate::WeeklyDigest => "Weekly post stats for authors who opted in",
+            EmailTemplate::PostRejected => "A post submitted for review was sent back to draft",
+            EmailTemplate::NoteMention => "A mention in an internal note on a post",
+        }
+    }
+
+    /// Variables the sending flow provides, with sample values for previews.
+    pub fn variables(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            EmailTemplate::AccountApproved => &[
+                ("name", "Jane Doe"),
+                ("login_url", "https://example.com/login"),
+            ],
+            EmailTemplate::Invitation => &[
+                (
+                    "invite_url",
+                    "https://example.com/accept-invite?token=abc123",
+                ),
+                ("expiry_hours", "72"),
+            ],
+            EmailTemplate::EmailChange => &[(
+                "verify_url",
+                "https://example.com/verify-email?token=abc123",
+            )],
+            EmailTemplate::EmailChangeNotice => &[
+                ("name", "Jane Doe"),
+                ("new_email", "jane@example.org"),
+                (
+                    "cancel_url",
+                    "https://example.com/cancel-email-change?token=abc123",
+                ),
+            ],
+            EmailTemplate::FailedLogins => &[
+                ("name", "Jane Doe"),
+                ("failures", "5"),
+                ("ip", "203.0.113.7"),
+            ],
+            EmailTemplate::NewLogin => &[
+                ("name", "Jane Doe"),
+                ("ip", "203.0.113.7"),
+                ("country", "NL"),
+                ("device", "Mozilla/5.0 (X11; Linux x86_64) Firefox/128.0"),
+            ],
+            EmailTemplate::WeeklyDigest => &[
+                ("name", "Jane Doe"),
+                ("week", "May 4 – May 10, 2026"),
+                ("views", "1280"),
+                ("views_change", "+12%"),
+                ("posts_published", "2"),
+                (
+                    "top_posts",
+                    "1. Hello World (640 views)\n2. Rust Tips (320 views)",
+                ),
+            ],
+            EmailTemplate::PostRejected => &[
+                ("name", "Jane Doe"),
+                ("title", "Hello World"),
+                ("reviewer", "John Smith"),
+                ("note", "Please add a source for the benchmark numbers."),
+            ],
+            EmailTemplate::NoteMention => &[
+                ("name", "Jane Doe"),
+                ("author", "John Smith"),
+                ("title", "Hello World"),
+                ("note", "@jane@example.com can you check the intro?"),
+            ],
+        }
+    }
+
+    /// The compiled-in subject and body.
+    pub fn default_source(self) -> TemplateSource {
+        let (subject, body) = match self {
+            EmailTemplate::AccountApproved => (
+                "Your account has been approved",
+                include_str!("templates/account_approved.txt"),
+            ),
+            EmailTemplate::Invitation => {
+                ("You're invited", include_str!("templates/invitation.txt"))
+            }
+            EmailTemplate::EmailChange => (
+                "Confirm your new email address",
+                include_str!("templates/email_change.txt"),
+            ),
+            EmailTemplate::EmailChangeNotice => (
+                "Your email address is being changed",
+                include_str!("templates/email_change_notice.txt"),
+            ),
+            EmailTemplate::FailedLogins => (
+                "Failed sign-in attempts on your account",
+                include_str!("templates/failed_logins.txt"),
+            ),
+            EmailTemplate::NewLogin => (
+                "New sign-in to your account",
+                include_str!("templates/new_login.txt"),
+            ),
+            EmailTemplate::WeeklyDigest => (
+                "Your week in numbers",
+                include_str!("templates/weekly_digest.txt"),
+            ),
+            EmailTemplate::PostRejected => (
+                "Changes requested on {{ title }}",
+                include_str!("templates/post_rejected.txt"),
+            ),
+            EmailTemplate::NoteMention => (
+                "{{ author }} mentioned you on {{ title }}",
+                include_str!("templates/note_mention.txt"),
+            ),
+        };
+        TemplateSource {
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    /// Context filled with the sample value of every variable.
+    pub fn sample_context(self) -> Value {
+        Value::Object(
+            self.variables()
+                .iter()
+                .map(|(name, sample)| (name.to_string(), json!(sample)))
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for EmailTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for EmailTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|template| template.name() == s)
+            .ok_or_else(|| format!("Unknown email template '{}'", s))
+    }
+}
+
+/// Render `source` with the variables in `context`, an object.
+///
+/// Output is plain text, so nothing is HTML-escaped. Line breaks in the
+/// rendered subject are folded into spaces.
+pub fn render(source: &TemplateSource, context: &Value) -> Result<RenderedEmail, String> {
+    let context = Context::from_value(context.clone()).map_err(|e| e.to_string())?;
+    let subject = render_one(&source.subject, &context).map_err(|e| format!("subject: {}", e))?;
+    let body = render_one(&source.body, &context).map_err(|e| format!("body: {}", e))?;
+    Ok(RenderedEmail {
+        subject: subject.split_whitespace().collect::<Vec<_>>().join(" "),
+        body,
+    })
+}
+
+fn render_one(template: &str, context: &Context) -> Result<String, String> {
+    Tera::one_off(template, context, false).map_err(|err| {
+        // Tera nests the useful detail (e.g. the missing variable) in the source chain
+        let mut message = err.to_string();
+        let mut source = std::error::Error::source(&err);
+        while let Some(cause) = source {
+            message = format!("{}: {}", message, cause);
+            source = cause.source();
+        }
+        message
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_render_with_sample_context() {
+        for template in EmailTemplate::ALL {
+            let rendered = render(&template.default_source(), &template.sample_context())
+                .unwrap_or_else(|err| panic!("{}: {}", template, err));
+            assert!(!rendered.subject.is_empty());
+            for (_, sample) in template.variables() {
+                assert!(
+                    rendered.body.contains(sample),
+                    "{} uses {}",
+                    template,
+                    sample
+                );
+            }
+            assert_eq!(template.name().parse::<EmailTemplate>(), Ok(template));
+        }
+        assert!("password_reset".parse::<EmailTemplate>().is_err());
+    }
+
+    #[test]
+    fn test_render_errors_and_subject_folding() {
+        let source = TemplateSource {
+            subject: "Hello\n{{ name }}".to_string(),
+            body: "<{{ name }}> & {{ name | upper }}".to_string(),
+        };
+        let rendered = render(&source, &json!({ "name": "Jane" })).unwrap();
+        assert_eq!(rendered.subject, "Hello Jane");
+        assert_eq!(rendered.body, "<Jane> & JANE");
+
+        let err = render(&source, &json!({})).unwrap_err();
+        assert!(err.contains("name"), "{}", err);
+        let broken = TemplateSource {
+            subject: "Hi".to_string(),
+            body: "{% if %}".to_string(),
+        };
+        assert!(render(&broken, &json!({}))
+            .unwrap_err()
+            .starts_with("body:"));
+    }
+}
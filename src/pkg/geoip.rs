@@ -0,0 +1,161 @@
+//! Country-level IP geolocation with a MaxMind GeoLite2 database.
+//!
+//! Only the country and first-level region (state, province) are read from
+//! the database; city, postal code and coordinates are never looked at. The
+//! database path is a runtime setting, and the file is checked for changes
+//! every minute so updates from `geoipupdate` are picked up without a restart.
+
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use arc_swap::ArcSwapOption;
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+
+use crate::error::AppError;
+
+/// How often the database path and file are checked for changes.
+pub const GEOIP_RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Where an IP address is located, at country level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeoLocation {
+    /// ISO 3166-1 alpha-2 country code
+    pub country: String,
+    /// ISO 3166-2 subdivision code without the country prefix (e.g. `JK`), when known
+    pub region: Option<String>,
+}
+
+/// The database file in use, to notice when it is replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LoadedFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// Shared handle to the current GeoLite2 database, if one is configured.
+#[derive(Clone, Default)]
+pub struct GeoIp {
+    reader: Arc<ArcSwapOption<Reader<Vec<u8>>>>,
+    loaded: Arc<Mutex<Option<LoadedFile>>>,
+}
+
+impl GeoIp {
+    /// Handle without a database; every lookup returns `None`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a database is loaded.
+    pub fn is_enabled(&self) -> bool {
+        self.reader.load().is_some()
+    }
+
+    /// Country and region of `ip`, or `None` without a database or for
+    /// addresses it does not know (private ranges, loopback).
+    pub fn locate(&self, ip: IpAddr) -> Option<GeoLocation> {
+        let reader = self.reader.load_full()?;
+        let record = match reader.lookup::<geoip2::City>(ip) {
+            Ok(record) => record,
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return None,
+            Err(err) => {
+                tracing::debug!(error = %err, %ip, "GeoIP lookup failed");
+                return None;
+            }
+        };
+        let country = record
+            .country
+            .and_then(|country| country.iso_code)
+            .filter(|code| code.len() == 2)?
+            .to_ascii_uppercase();
+        let region = record
+            .subdivisions
+            .and_then(|subdivisions| subdivisions.into_iter().next())
+            .and_then(|subdivision| subdivision.iso_code)
+            .map(|code| code.to_ascii_uppercase());
+        Some(GeoLocation { country, region })
+    }
+
+    /// Location of a client, preferring the country a trusted proxy or CDN
+    /// passed along; the region is only kept when the database agrees on it.
+    pub fn locate_client(&self, ip: IpAddr, proxy_country: Option<&str>) -> Option<GeoLocation> {
+        let looked_up = self.locate(ip);
+        let Some(country) = proxy_country else {
+            return looked_up;
+        };
+        let region = looked_up
+            .filter(|location| location.country == country)
+            .and_then(|location| location.region);
+        Some(GeoLocation {
+            country: country.to_string(),
+            region,
+        })
+    }
+
+    /// Load the database at `path`, or drop it when `path` is `None`, if the
+    /// path or the file's modification time changed since the last load.
+    ///
+    /// Returns whether the database was swapped. On error the current
+    /// database stays in place and the next call tries again.
+    pub fn reload_if_changed(&self, path: Option<&Path>) -> Result<bool, AppError> {
+        let wanted = path.map(|path| LoadedFile {
+            path: path.to_path_buf(),
+            modified: std::fs::metadata(path).and_then(|m| m.modified()).ok(),
+        });
+        let mut loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        if *loaded == wanted {
+            return Ok(false);
+        }
+        match &wanted {
+            Some(file) => {
+                let reader = Reader::open_readfile(&file.path).map_err(|e| {
+                    AppError::InternalError(format!(
+                        "Failed to load GeoIP database '{}': {}",
+                        file.path.display(),
+                        e
+                    ))
+                })?;
+                self.reader.store(Some(Arc::new(reader)));
+            }
+            None => self.reader.store(None),
+        }
+        *loaded = wanted;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_database() {
+        let geoip = GeoIp::new();
+        assert!(!geoip.is_enabled());
+        assert_eq!(geoip.locate("8.8.8.8".parse().unwrap()), None);
+        assert!(!geoip.reload_if_changed(None).unwrap());
+    }
+
+    #[test]
+    fn test_missing_database_keeps_current() {
+        let geoip = GeoIp::new();
+        let path = Path::new("/nonexistent/GeoLite2-Country.mmdb");
+        assert!(geoip.reload_if_changed(Some(path)).is_err());
+        assert!(!geoip.is_enabled());
+    }
+
+    #[test]
+    fn test_locate_client_prefers_proxy_country() {
+        let geoip = GeoIp::new();
+        let ip = "203.0.113.7".parse().unwrap();
+        assert_eq!(geoip.locate_client(ip, None), None);
+        assert_eq!(
+            geoip.locate_client(ip, Some("NL")),
+            Some(GeoLocation {
+                country: "NL".to_string(),
+                region: None,
+            })
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Thin wrapper around the `git` command line for a local working copy.
+//!
+//! Commands never prompt for credentials; remotes needing them should embed a
+//! token in the URL or rely on an SSH key available to the server.
+
+use std::path::{Path, PathBuf};
+
+use tokio::process::Command;
+
+/// How a file changed between two commits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChange {
+    /// Added or modified
+    Changed(String),
+    Deleted(String),
+}
+
+/// A local working copy of a Git repository.
+#[derive(Debug, Clone)]
+pub struct GitRepo {
+    dir: PathBuf,
+}
+
+impl GitRepo {
+    /// Wrap the working copy at `dir`, which may not exist yet.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Directory of the working copy.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Whether the directory holds a working copy.
+    pub fn exists(&self) -> bool {
+        self.dir.join(".git").exists()
+    }
+
+    /// Clone `branch` of `remote` into the directory.
+    pub async fn clone_from(&self, remote: &str, branch: &str) -> Result<(), String> {
+        let dir = self.dir.to_string_lossy();
+        run(
+            None,
+            &[
+                "clone",
+                "--branch",
+                branch,
+                "--single-branch",
+                remote,
+                dir.as_ref(),
+            ],
+        )
+        .await
+        .map(drop)
+    }
+
+    /// Commit ID of `HEAD`.
+    pub async fn head(&self) -> Result<String, String> {
+        self.git(&["rev-parse", "HEAD"]).await
+    }
+
+    /// Fetch `branch` from `origin`.
+    pub async fn fetch(&self, branch: &str) -> Result<(), String> {
+        self.git(&["fetch", "origin", branch]).await.map(drop)
+    }
+
+    /// Replay local commits on top of `origin/<branch>`.
+    ///
+    /// On a conflict the local commits are dropped and the working copy is
+    /// reset to the remote branch; returns whether that happened.
+    pub async fn rebase_onto_remote(&self, branch: &str) -> Result<bool, String> {
+        let upstream = format!("origin/{}", branch);
+        if self.git(&["rebase", &upstream]).await.is_ok() {
+            return Ok(false);
+        }
+        // Nothing to abort when the rebase failed before starting
+        let _ = self.git(&["rebase", "--abort"]).await;
+        self.git(&["reset", "--hard", &upstream]).await?;
+        Ok(true)
+    }
+
+    /// Files under `path` changed between commits `from` and `to`.
+    pub async fn changes(
+        &self,
+        from: &str,
+        to: &str,
+        path: &str,
+    ) -> Result<Vec<FileChange>, String> {
+        let output = self
+            .git(&[
+                "diff",
+                "--name-status",
+                "--no-renames",
+                from,
+                to,
+                "--",
+                path,
+            ])
+            .await?;
+        Ok(parse_name_status(&output))
+    }
+
+    /// Stage everything under `path` and commit it, returning whether there
+    /// was anything to commit.
+    pub async fn commit_all(
+        &self,
+        path: &str,
+        message: &str,
+        author_name: &str,
+        author_email: &str,
+    ) -> Result<bool, String> {
+        self.git(&["add", "--all", "--", path]).await?;
+        if self
+            .git(&["status", "--porcelain", "--", path])
+            .await?
+            .is_empty()
+        {
+            return Ok(false);
+        }
+
+        let name = format!("user.name={}", author_name);
+        let email = format!("user.email={}", author_email);
+        self.git(&[
+            "-c", &name, "-c", &email, "commit", "--quiet", "-m", message,
+        ])
+        .await?;
+        Ok(true)
+    }
+
+    /// Push `HEAD` to `branch` on `origin`.
+    pub async fn push(&self, branch: &str) -> Result<(), String> {
+        let refspec = format!("HEAD:refs/heads/{}", branch);
+        self.git(&["push", "origin", &refspec]).await.map(drop)
+    }
+
+    // Private helper methods
+
+    async fn git(&self, args: &[&str]) -> Result<String, String> {
+        run(Some(&self.dir), args).await
+    }
+}
+
+/// Run `git` with `args`, returning trimmed stdout or the error output.
+async fn run(dir: Option<&Path>, args: &[&str]) -> Result<String, String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.arg("-C").arg(dir);
+    }
+    let output = command
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        // Name the subcommand, skipping `-c key=value` options before it
+        let subcommand = args
+            .iter()
+            .find(|arg| !arg.starts_with('-') && !arg.contains('='))
+            .copied()
+            .unwrap_or_default();
+        Err(format!(
+            "git {} failed: {}",
+            subcommand,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Parse `git diff --name-status --no-renames` output.
+fn parse_name_status(output: &str) -> Vec<FileChange> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (status, path) = line.split_once('\t')?;
+            let path = path.to_string();
+            Some(match status {
+                "D" => FileChange::Deleted(path),
+                _ => FileChange::Changed(path),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name_status() {
+        let output = "A\tposts/new.md\nM\tposts/edited.md\nD\tposts/gone.md\n";
+        assert_eq!(
+            parse_name_status(output),
+            vec![
+                FileChange::Changed("posts/new.md".to_string()),
+                FileChange::Changed("posts/edited.md".to_string()),
+                FileChange::Deleted("posts/gone.md".to_string()),
+            ]
+        );
+        assert!(parse_name_status("").is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+//! Minimal outgoing HTTP(S) client.
+//!
+//! Opens one connection per request, which suits the occasional calls made to
+//! external APIs. HTTPS uses the bundled webpki roots. Every request takes a
+//! timeout covering the whole exchange, so a stalled upstream cannot hold up
+//! the caller (usually `Config::http_client_timeout`).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper::{Method, Uri};
+use hyper_util::rt::TokioIo;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_rustls::{
+    rustls::{crypto::ring, pki_types::ServerName, ClientConfig, RootCertStore},
+    TlsConnector,
+};
+
+/// Status and body of a completed request.
+#[derive(Debug)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Whether the status is in the 2xx range.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Send a request to an `http://` or `https://` URL.
+///
+/// `Host` and `User-Agent` are always set; a body is sent as JSON. Fails if
+/// no complete response arrived within `timeout`.
+pub async fn send(
+    method: Method,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<String>,
+    timeout: Duration,
+) -> Result<HttpResponse, String> {
+    match body {
+        Some(body) => {
+            let mut headers = headers.to_vec();
+            headers.push(("Content-Type", "application/json"));
+            send_bytes(method, url, &headers, body.into_bytes(), timeout).await
+        }
+        None => send_bytes(method, url, headers, Vec::new(), timeout).await,
+    }
+}
+
+/// Send a request with a raw body; callers set `Content-Type` themselves.
+pub async fn send_bytes(
+    method: Method,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Vec<u8>,
+    timeout: Duration,
+) -> Result<HttpResponse, String> {
+    tokio::time::timeout(timeout, request(method, url, headers, body))
+        .await
+        .map_err(|_| format!("Request to '{}' timed out after {:?}", url, timeout))?
+}
+
+async fn request(
+    method: Method,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Vec<u8>,
+) -> Result<HttpResponse, String> {
+    let uri: Uri = url
+        .parse()
+        .map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    let host = uri
+        .host()
+        .ok_or_else(|| format!("URL '{}' has no host", url))?
+        .to_string();
+    let https = match uri.scheme_str() {
+        Some("https") => true,
+        Some("http") => false,
+        _ => return Err(format!("URL '{}' must use http or https", url)),
+    };
+    let port = uri.port_u16().unwrap_or(if https { 443 } else { 80 });
+    let path = uri
+        .path_and_query()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "/".to_string());
+
+    let mut request = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .header(hyper::header::HOST, host.as_str())
+        .header(hyper::header::USER_AGENT, "personal-website");
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let request = request
+        .body(Full::new(Bytes::from(body)))
+        .map_err(|e| e.to_string())?;
+
+    let tcp = TcpStream::connect((host.as_str(), port))
+        .await
+        .map_err(|e| e.to_string())?;
+    if https {
+        let server_name = ServerName::try_from(host).map_err(|e| e.to_string())?;
+        let tls = TlsConnector::from(Arc::new(tls_config()?))
+            .connect(server_name, tcp)
+            .await
+            .map_err(|e| e.to_string())?;
+        exchange(tls, request).await
+    } else {
+        exchange(tcp, request).await
+    }
+}
+
+fn tls_config() -> Result<ClientConfig, String> {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    Ok(
+        ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| e.to_string())?
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    )
+}
+
+async fn exchange<S>(
+    stream: S,
+    request: hyper::Request<Full<Bytes>>,
+) -> Result<HttpResponse, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::spawn(async move {
+        let _ = connection.await;
+    });
+
+    let response = sender
+        .send_request(request)
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status().as_u16();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| e.to_string())?
+        .to_bytes();
+
+    Ok(HttpResponse {
+        status,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_send_times_out() {
+        // Accepts the connection but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(stream);
+        });
+
+        let url = format!("http://{}/indexes", addr);
+        let err = send(Method::GET, &url, &[], None, Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(err.contains("timed out"), "{}", err);
+        server.abort();
+    }
+}
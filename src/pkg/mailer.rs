@@ -0,0 +1,111 @@
+//! Outgoing email delivery.
+//!
+//! Sends through SMTP when `SMTP_URL` is configured and otherwise logs each
+//! message, so flows that email links still work in development.
+
+use std::sync::Arc;
+
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::config::Config;
+use crate::error::AppError;
+
+/// How messages leave the process.
+enum Transport {
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    Log,
+}
+
+/// Email sender shared across services.
+#[derive(Clone)]
+pub struct Mailer {
+    transport: Arc<Transport>,
+    from: Mailbox,
+}
+
+impl Mailer {
+    /// Build a mailer from `SMTP_URL` and `MAIL_FROM`.
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let from = config
+            .mail_from
+            .parse::<Mailbox>()
+            .map_err(|e| format!("Invalid MAIL_FROM: {}", e))?;
+        let transport = match &config.smtp_url {
+            Some(url) => Transport::Smtp(
+                AsyncSmtpTransport::<Tokio1Executor>::from_url(url)
+                    .map_err(|e| format!("Invalid SMTP_URL: {}", e))?
+                    .build(),
+            ),
+            None => Transport::Log,
+        };
+
+        Ok(Self {
+            transport: Arc::new(transport),
+            from,
+        })
+    }
+
+    /// A mailer that only logs messages.
+    pub fn log_only(from: Mailbox) -> Self {
+        Self {
+            transport: Arc::new(Transport::Log),
+            from,
+        }
+    }
+
+    /// Send a plain-text email.
+    pub async fn send(&self, to: &str, subject: &str, body: String) -> Result<(), AppError> {
+        let to = to
+            .parse::<Mailbox>()
+            .map_err(|_| AppError::ValidationError(format!("Invalid email address: {}", to)))?;
+
+        match self.transport.as_ref() {
+            Transport::Smtp(smtp) => {
+                let message = Message::builder()
+                    .from(self.from.clone())
+                    .to(to)
+                    .subject(subject)
+                    .header(ContentType::TEXT_PLAIN)
+                    .body(body)
+                    .map_err(|e| {
+                        AppError::InternalError(format!("Failed to build email: {}", e))
+                    })?;
+                smtp.send(message)
+                    .await
+                    .map_err(|e| AppError::InternalError(format!("Failed to send email: {}", e)))?;
+            }
+            Transport::Log => {
+                tracing::info!(to = %to, subject, "Email (not sent, SMTP_URL unset):\n{}", body);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_log_only_mailer() {
+        let mailer = Mailer::log_only("Blog <blog@example.com>".parse().unwrap());
+        assert!(mailer
+            .send("reader@example.com", "Hello", "Body".to_string())
+            .await
+            .is_ok());
+        assert!(matches!(
+            mailer.send("not-an-address", "Hello", String::new()).await,
+            Err(AppError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_config_without_smtp_logs() {
+        let mailer = Mailer::from_config(&Config::default()).unwrap();
+        assert!(matches!(mailer.transport.as_ref(), Transport::Log));
+    }
+}
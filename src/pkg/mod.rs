@@ -2,8 +2,60 @@
 //!
 //! This module contains wrappers for third-party services and external dependencies:
 //! - Redis for caching and session storage
-//! - Future: WhatsApp OTP, email services, payment gateways, etc.
+//! - Stale-while-revalidate caching of computed responses in Redis
+//! - Opaque cursors for keyset pagination
+//! - Line and word diffs of text
+//! - JWT signing keys and JWKS publication
+//! - Outgoing email over SMTP and templates for transactional emails
+//! - Password strength rules and breached-password lookups
+//! - URL slugs with transliteration and collision suffixes
+//! - Escaping user input for SQL `LIKE` patterns
+//! - Post search backends (Postgres full-text search, Meilisearch)
+//! - A minimal outgoing HTTP(S) client
+//! - Git working copies driven through the `git` command line
+//! - HMAC signatures of webhook bodies
+//! - Slow database statement logging
+//! - Queue depth gauges for background workers
+//! - Object storage (local directory, S3-compatible buckets)
+//! - Cron schedules for background jobs
+//! - Crawler and referrer spam detection for view counts
+//! - Country-level IP geolocation (MaxMind GeoLite2)
+//! - Future: WhatsApp OTP, payment gateways, etc.
 
+pub mod cron;
+pub mod cursor;
+pub mod diff;
+pub mod email;
+pub mod geoip;
+pub mod git;
+pub mod http_client;
+pub mod jwt;
+pub mod mailer;
+pub mod password_policy;
+pub mod queue_depth;
 pub mod redis;
+pub mod response_cache;
+pub mod search;
+pub mod signature;
+pub mod slow_queries;
+pub mod slug;
+pub mod sql;
+pub mod storage;
+pub mod traffic;
 
+pub use cron::CronSchedule;
+pub use cursor::Cursor;
+pub use email::EmailTemplate;
+pub use geoip::{GeoIp, GeoLocation};
+pub use git::GitRepo;
+pub use jwt::JwtKeys;
+pub use mailer::Mailer;
+pub use password_policy::PasswordPolicy;
+pub use queue_depth::QueueDepth;
 pub use redis::*;
+pub use response_cache::{CachePolicy, ResponseCache};
+pub use search::SearchEngine;
+pub use slow_queries::SlowQueryLog;
+pub use slug::SlugGenerator;
+pub use storage::Storage;
+pub use traffic::{TrafficFilter, ViewSource};
@@ -0,0 +1,296 @@
+//! Password strength policy.
+//!
+//! New passwords must meet a minimum length, reach a minimum strength score
+//! and, when enabled, must not appear in the Have I Been Pwned breach corpus.
+//! The score uses zxcvbn's 0–4 scale from a built-in estimate of how many
+//! guesses an attacker would need. Breach lookups use the k-anonymity range
+//! API, so only the first five hex characters of the SHA-1 hash leave the
+//! server.
+
+use std::time::Duration;
+
+use hyper::Method;
+use sha1::{Digest, Sha1};
+
+use crate::config::Config;
+use crate::error::{AppError, FieldError};
+use crate::pkg::http_client;
+
+/// Passwords rejected outright regardless of their estimated strength.
+const COMMON_PASSWORDS: &[&str] = &[
+    "password",
+    "password1",
+    "password123",
+    "passw0rd",
+    "p@ssw0rd",
+    "123456",
+    "12345678",
+    "123456789",
+    "1234567890",
+    "qwerty",
+    "qwerty123",
+    "qwertyuiop",
+    "asdfghjkl",
+    "zxcvbnm",
+    "1q2w3e4r",
+    "1qaz2wsx",
+    "abc123",
+    "iloveyou",
+    "letmein",
+    "welcome",
+    "welcome1",
+    "admin",
+    "admin123",
+    "administrator",
+    "monkey",
+    "dragon",
+    "sunshine",
+    "princess",
+    "football",
+    "baseball",
+    "master",
+    "shadow",
+    "superman",
+    "trustno1",
+    "starwars",
+    "whatever",
+    "freedom",
+    "changeme",
+    "secret",
+    "default",
+    "login",
+    "root",
+    "test1234",
+    "111111",
+    "000000",
+];
+
+/// Configured password rules.
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    min_length: usize,
+    min_score: u8,
+    check_breached: bool,
+    /// Base URL of the Pwned Passwords range API
+    breach_api_url: String,
+    /// Give up on a breach lookup after this long and accept the password
+    breach_timeout: Duration,
+}
+
+impl PasswordPolicy {
+    /// Build the policy from `PASSWORD_*` settings.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            min_length: config.password_min_length,
+            min_score: config.password_min_score,
+            check_breached: config.password_check_breached,
+            breach_api_url: config.password_breach_api_url.clone(),
+            breach_timeout: Duration::from_secs(config.password_breach_timeout_seconds),
+        }
+    }
+
+    /// Check a new password, reporting problems against `field`.
+    ///
+    /// `user_inputs` are values such as the user's email and name; passwords
+    /// built from them are scored as weak.
+    pub async fn check(
+        &self,
+        field: &str,
+        password: &str,
+        user_inputs: &[&str],
+    ) -> Result<(), AppError> {
+        if let Some(message) = self.local_violation(password, user_inputs) {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                field, message,
+            )]));
+        }
+
+        if self.check_breached {
+            match pwned_count(&self.breach_api_url, password, self.breach_timeout).await {
+                Ok(0) => {}
+                Ok(_) => {
+                    return Err(AppError::InvalidFields(vec![FieldError::new(
+                        field,
+                        "has appeared in a data breach; choose a different password",
+                    )]));
+                }
+                // Fail open: an unreachable or stalled breach API must not block sign-ups
+                Err(err) => tracing::warn!(error = %err, "Breached-password lookup failed"),
+            }
+        }
+
+        Ok(())
+    }
+
+    // Private helper methods
+
+    fn local_violation(&self, password: &str, user_inputs: &[&str]) -> Option<String> {
+        if password.chars().count() < self.min_length {
+            return Some(format!("must be at least {} characters", self.min_length));
+        }
+        if strength_score(password, user_inputs) < self.min_score {
+            return Some(
+                "is too easy to guess; use a longer password or mix in more kinds of characters"
+                    .to_string(),
+            );
+        }
+        None
+    }
+}
+
+/// Estimate password strength on zxcvbn's 0 (trivial) to 4 (very strong) scale.
+pub fn strength_score(password: &str, user_inputs: &[&str]) -> u8 {
+    let lowered = password.to_lowercase();
+    if COMMON_PASSWORDS.contains(&lowered.as_str()) || contains_user_input(&lowered, user_inputs) {
+        return 0;
+    }
+
+    let log10_guesses = effective_length(password) as f64 * (charset_size(password) as f64).log10();
+    match log10_guesses {
+        g if g < 3.0 => 0,
+        g if g < 6.0 => 1,
+        g if g < 8.0 => 2,
+        g if g < 10.0 => 3,
+        _ => 4,
+    }
+}
+
+/// Whether the password contains a meaningful part of the user's own details.
+fn contains_user_input(lowered: &str, user_inputs: &[&str]) -> bool {
+    user_inputs
+        .iter()
+        .flat_map(|input| input.split(|c: char| !c.is_alphanumeric()))
+        .filter(|token| token.chars().count() >= 4)
+        .any(|token| lowered.contains(&token.to_lowercase()))
+}
+
+/// Size of the alphabet the password draws from.
+fn charset_size(password: &str) -> u32 {
+    let has = |pred: fn(&char) -> bool| password.chars().any(|c| pred(&c));
+    let mut size = 0;
+    if has(char::is_ascii_lowercase) {
+        size += 26;
+    }
+    if has(char::is_ascii_uppercase) {
+        size += 26;
+    }
+    if has(char::is_ascii_digit) {
+        size += 10;
+    }
+    if has(|c| c.is_ascii() && !c.is_ascii_alphanumeric()) {
+        size += 33;
+    }
+    if has(|c| !c.is_ascii()) {
+        size += 100;
+    }
+    size.max(1)
+}
+
+/// Length ignoring repeated characters and runs like `abcd` or `4321`.
+fn effective_length(password: &str) -> usize {
+    let chars: Vec<i64> = password.chars().map(|c| c as i64).collect();
+    (0..chars.len())
+        .filter(|&i| {
+            let repeat = i >= 1 && chars[i] == chars[i - 1];
+            let sequence = i >= 2
+                && (chars[i] - chars[i - 1]).abs() == 1
+                && chars[i] - chars[i - 1] == chars[i - 1] - chars[i - 2];
+            !repeat && !sequence
+        })
+        .count()
+}
+
+/// Number of times the password appears in the Pwned Passwords corpus.
+async fn pwned_count(api_url: &str, password: &str, timeout: Duration) -> Result<u64, String> {
+    let hash: String = Sha1::digest(password.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect();
+    let (prefix, suffix) = hash.split_at(5);
+    let range = fetch_range(api_url, prefix, timeout).await?;
+    Ok(range_count(&range, suffix))
+}
+
+/// Find a hash suffix in a range API response (`SUFFIX:COUNT` per line).
+fn range_count(range: &str, suffix: &str) -> u64 {
+    range
+        .lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// GET `/range/{prefix}` from the Pwned Passwords API at `api_url`.
+async fn fetch_range(api_url: &str, prefix: &str, timeout: Duration) -> Result<String, String> {
+    let url = format!("{}/range/{}", api_url.trim_end_matches('/'), prefix);
+    let headers = [("Add-Padding", "true")];
+    let response = http_client::send(Method::GET, &url, &headers, None, timeout).await?;
+    if !response.is_success() {
+        return Err(format!("Pwned Passwords API returned {}", response.status));
+    }
+    Ok(response.body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_strength_score() {
+        assert_eq!(strength_score("password1", &[]), 0);
+        assert_eq!(strength_score("aaaaaaaaaaaa", &[]), 0);
+        assert_eq!(strength_score("abcdefghijkl", &[]), 0);
+        assert_eq!(strength_score("budhilaw2024", &["budhilaw@example.com"]), 0);
+        assert!(strength_score("correct horse battery staple", &[]) >= 4);
+        assert!(strength_score("Tr0ub4dor&3", &[]) >= 3);
+    }
+
+    #[test]
+    fn test_local_violation() {
+        let policy = PasswordPolicy::from_config(&Config::default());
+        assert!(policy.local_violation("short", &[]).is_some());
+        assert!(policy.local_violation("qwertyuiop", &[]).is_some());
+        assert!(policy
+            .local_violation("violet-anchor-19-drift", &["me@example.com"])
+            .is_none());
+    }
+
+    #[test]
+    fn test_range_count() {
+        let range = "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n\
+                     00D4F6E8FA6EECAD2A3AA415EEC418D38EC:2\r\n\
+                     011053FD0102E94D6AE2F8B83D76FAF94F6:0";
+        assert_eq!(range_count(range, "00D4F6E8FA6EECAD2A3AA415EEC418D38EC"), 2);
+        assert_eq!(range_count(range, "011053fd0102e94d6ae2f8b83d76faf94f6"), 0);
+        assert_eq!(range_count(range, "FFFFF"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_check_fails_open_on_stalled_lookup() {
+        // Accepts the connection but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(stream);
+        });
+
+        let policy = PasswordPolicy {
+            check_breached: true,
+            breach_api_url: format!("http://{}", addr),
+            breach_timeout: Duration::from_millis(200),
+            ..PasswordPolicy::from_config(&Config::default())
+        };
+        let started = std::time::Instant::now();
+        policy
+            .check("password", "violet-anchor-19-drift", &[])
+            .await
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        server.abort();
+    }
+}
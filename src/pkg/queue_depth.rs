@@ -0,0 +1,57 @@
+//! Gauge of jobs waiting in a background worker's queue.
+//!
+//! Tokio's unbounded channels only report their length on the receiving
+//! side, which the worker task owns, so handles count jobs as they queue
+//! them and workers as they take them off.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Number of queued jobs not yet taken by a worker, shared by its handles.
+#[derive(Debug, Clone, Default)]
+pub struct QueueDepth(Arc<AtomicUsize>);
+
+impl QueueDepth {
+    /// Create a gauge for an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a job added to the queue.
+    pub fn queued(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count `count` jobs taken off the queue.
+    pub fn taken(&self, count: usize) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |depth| {
+                Some(depth.saturating_sub(count))
+            });
+    }
+
+    /// Jobs currently waiting.
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_depth() {
+        let depth = QueueDepth::new();
+        let handle = depth.clone();
+        handle.queued();
+        handle.queued();
+        assert_eq!(depth.get(), 2);
+
+        depth.taken(1);
+        assert_eq!(handle.get(), 1);
+        depth.taken(5);
+        assert_eq!(depth.get(), 0);
+    }
+}
@@ -22,6 +22,52 @@ pub mod keys {
     pub const REFRESH_TOKEN_PREFIX: &str = "refresh_token:";
     /// Prefix for user tokens (stores all token IDs for a user)
     pub const USER_TOKENS_PREFIX: &str = "user_tokens:";
+    /// Prefix for pending email verification tokens
+    pub const EMAIL_VERIFICATION_PREFIX: &str = "email_verification:";
+    /// Prefix for tokens cancelling a pending email change
+    pub const EMAIL_CHANGE_CANCEL_PREFIX: &str = "email_change_cancel:";
+    /// Prefix for outstanding invitation token IDs
+    pub const INVITATION_PREFIX: &str = "invitation:";
+    /// Prefix for cached token versions per user
+    pub const TOKEN_VERSION_PREFIX: &str = "token_version:";
+    /// Prefix for sudo mode grants per access token ID
+    pub const SUDO_PREFIX: &str = "sudo:";
+    /// Prefix for failed login counters per email and IP
+    pub const LOGIN_FAILURES_PREFIX: &str = "login_failures:";
+    /// Prefix for login lockouts per email and IP
+    pub const LOGIN_LOCKOUT_PREFIX: &str = "login_lockout:";
+    /// Prefix for cached trending post lists per site and window
+    pub const TRENDING_PREFIX: &str = "trending:";
+    /// Prefix for cached post archives per site and year
+    pub const POST_ARCHIVE_PREFIX: &str = "post_archive:";
+    /// Prefix for cached bootstrap categories per site and content version
+    pub const BOOTSTRAP_PREFIX: &str = "bootstrap:";
+    /// Prefix for locks held while a stale cached response is recomputed
+    pub const CACHE_REFRESH_PREFIX: &str = "cache_refresh:";
+    /// Prefix for request quota counters per client
+    pub const QUOTA_PREFIX: &str = "quota:";
+    /// Prefix for draft preview grants per token
+    pub const PREVIEW_PREFIX: &str = "preview:";
+    /// Prefix for draft preview use counters per token
+    pub const PREVIEW_USES_PREFIX: &str = "preview_uses:";
+    /// Prefix for post editing locks
+    pub const POST_LOCK_PREFIX: &str = "post_lock:";
+    /// Prefix for the set of voters per poll
+    pub const POLL_VOTERS_PREFIX: &str = "poll_voters:";
+    /// Prefix for chunked upload sessions
+    pub const UPLOAD_SESSION_PREFIX: &str = "upload_session:";
+    /// Prefix for locks held while a chunk is written
+    pub const UPLOAD_LOCK_PREFIX: &str = "upload_lock:";
+    /// Prefix for cached blocklist verdicts per client IP
+    pub const IP_VERDICT_PREFIX: &str = "ip_verdict:";
+    /// Prefix for when a site's public content last changed (Unix seconds)
+    pub const CONTENT_MODIFIED_PREFIX: &str = "content_modified:";
+    /// Held by the instance running a database backup
+    pub const BACKUP_RUNNING: &str = "backup_running";
+    /// Prefix for scheduled backup runs claimed by an instance
+    pub const BACKUP_SLOT_PREFIX: &str = "backup_slot:";
+    /// Prefix for scheduled digest runs claimed by an instance
+    pub const DIGEST_SLOT_PREFIX: &str = "digest_slot:";
 
     /// Generate access token key.
     pub fn access_token(token_id: &str) -> String {
@@ -37,6 +83,126 @@ pub mod keys {
     pub fn user_tokens(user_id: &uuid::Uuid) -> String {
         format!("{}{}", USER_TOKENS_PREFIX, user_id)
     }
+
+    /// Generate email verification token key.
+    pub fn email_verification(token: &str) -> String {
+        format!("{}{}", EMAIL_VERIFICATION_PREFIX, token)
+    }
+
+    /// Generate email change cancellation token key.
+    pub fn email_change_cancel(token: &str) -> String {
+        format!("{}{}", EMAIL_CHANGE_CANCEL_PREFIX, token)
+    }
+
+    /// Generate invitation token key.
+    pub fn invitation(token_id: &str) -> String {
+        format!("{}{}", INVITATION_PREFIX, token_id)
+    }
+
+    /// Generate token version cache key.
+    pub fn token_version(user_id: &uuid::Uuid) -> String {
+        format!("{}{}", TOKEN_VERSION_PREFIX, user_id)
+    }
+
+    /// Generate sudo mode key for a session's access token ID.
+    pub fn sudo(token_id: &str) -> String {
+        format!("{}{}", SUDO_PREFIX, token_id)
+    }
+
+    /// Generate failed login counter key (email is case-insensitive).
+    pub fn login_failures(email: &str, ip: &std::net::IpAddr) -> String {
+        format!(
+            "{}{}:{}",
+            LOGIN_FAILURES_PREFIX,
+            email.trim().to_lowercase(),
+            ip
+        )
+    }
+
+    /// Generate login lockout key (email is case-insensitive).
+    pub fn login_lockout(email: &str, ip: &std::net::IpAddr) -> String {
+        format!(
+            "{}{}:{}",
+            LOGIN_LOCKOUT_PREFIX,
+            email.trim().to_lowercase(),
+            ip
+        )
+    }
+
+    /// Generate request quota counter key for the window starting at `window_start` (Unix seconds).
+    pub fn quota(client: &str, window_start: u64) -> String {
+        format!("{}{}:{}", QUOTA_PREFIX, client, window_start)
+    }
+
+    /// Generate draft preview grant key.
+    pub fn preview(token: &str) -> String {
+        format!("{}{}", PREVIEW_PREFIX, token)
+    }
+
+    /// Generate draft preview use counter key.
+    pub fn preview_uses(token: &str) -> String {
+        format!("{}{}", PREVIEW_USES_PREFIX, token)
+    }
+
+    /// Generate post editing lock key.
+    pub fn post_lock(post_id: &uuid::Uuid) -> String {
+        format!("{}{}", POST_LOCK_PREFIX, post_id)
+    }
+
+    /// Generate poll voter set key.
+    pub fn poll_voters(poll_id: &uuid::Uuid) -> String {
+        format!("{}{}", POLL_VOTERS_PREFIX, poll_id)
+    }
+
+    /// Generate chunked upload session key.
+    pub fn upload_session(upload_id: &str) -> String {
+        format!("{}{}", UPLOAD_SESSION_PREFIX, upload_id)
+    }
+
+    /// Generate chunked upload lock key.
+    pub fn upload_lock(upload_id: &str) -> String {
+        format!("{}{}", UPLOAD_LOCK_PREFIX, upload_id)
+    }
+
+    /// Generate blocklist verdict cache key.
+    pub fn ip_verdict(ip: &std::net::IpAddr) -> String {
+        format!("{}{}", IP_VERDICT_PREFIX, ip)
+    }
+
+    /// Generate the claim key of the backup scheduled at `slot` (Unix seconds).
+    pub fn backup_slot(slot: i64) -> String {
+        format!("{}{}", BACKUP_SLOT_PREFIX, slot)
+    }
+
+    /// Generate the claim key of the digest run scheduled at `slot` (Unix seconds).
+    pub fn digest_slot(slot: i64) -> String {
+        format!("{}{}", DIGEST_SLOT_PREFIX, slot)
+    }
+
+    /// Generate post archive cache key.
+    pub fn post_archive(site_id: &uuid::Uuid, year: i32) -> String {
+        format!("{}{}:{}", POST_ARCHIVE_PREFIX, site_id, year)
+    }
+
+    /// Generate a site's content modification time key.
+    pub fn content_modified(site_id: &uuid::Uuid) -> String {
+        format!("{}{}", CONTENT_MODIFIED_PREFIX, site_id)
+    }
+
+    /// Generate bootstrap categories cache key.
+    pub fn bootstrap(site_id: &uuid::Uuid, version: i64) -> String {
+        format!("{}{}:{}", BOOTSTRAP_PREFIX, site_id, version)
+    }
+
+    /// Generate the refresh lock key of a cached response.
+    pub fn cache_refresh(cache_key: &str) -> String {
+        format!("{}{}", CACHE_REFRESH_PREFIX, cache_key)
+    }
+
+    /// Generate trending posts cache key.
+    pub fn trending(site_id: &uuid::Uuid, window: &str) -> String {
+        format!("{}{}:{}", TRENDING_PREFIX, site_id, window)
+    }
 }
 
 #[cfg(test)]
@@ -63,10 +229,89 @@ mod tests {
         assert_eq!(key, "user_tokens:550e8400-e29b-41d4-a716-446655440000");
     }
 
+    #[test]
+    fn test_email_verification_key() {
+        let key = email_verification("tok");
+        assert_eq!(key, "email_verification:tok");
+    }
+
     #[test]
     fn test_create_connection_invalid_url_format() {
         // Test that an invalid URL format fails at client creation (sync, no network)
         let result = redis::Client::open("not-a-valid-url");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_sudo_key() {
+        assert_eq!(
+            sudo("550e8400-e29b-41d4-a716-446655440000"),
+            "sudo:550e8400-e29b-41d4-a716-446655440000"
+        );
+    }
+
+    #[test]
+    fn test_content_modified_key() {
+        let site_id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert_eq!(
+            content_modified(&site_id),
+            "content_modified:550e8400-e29b-41d4-a716-446655440000"
+        );
+    }
+
+    #[test]
+    fn test_preview_keys() {
+        assert_eq!(preview("abc"), "preview:abc");
+        assert_eq!(preview_uses("abc"), "preview_uses:abc");
+    }
+
+    #[test]
+    fn test_poll_voters_key() {
+        let poll_id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert_eq!(
+            poll_voters(&poll_id),
+            "poll_voters:550e8400-e29b-41d4-a716-446655440000"
+        );
+    }
+
+    #[test]
+    fn test_ip_verdict_key() {
+        let ip = "203.0.113.7".parse().unwrap();
+        assert_eq!(ip_verdict(&ip), "ip_verdict:203.0.113.7");
+    }
+
+    #[test]
+    fn test_backup_slot_key() {
+        assert_eq!(backup_slot(1_777_777_200), "backup_slot:1777777200");
+    }
+
+    #[test]
+    fn test_token_version_key() {
+        let user_id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert_eq!(
+            token_version(&user_id),
+            "token_version:550e8400-e29b-41d4-a716-446655440000"
+        );
+    }
+
+    #[test]
+    fn test_login_throttle_keys() {
+        let ip: std::net::IpAddr = "203.0.113.7".parse().unwrap();
+        assert_eq!(
+            login_failures(" Admin@Example.com", &ip),
+            "login_failures:admin@example.com:203.0.113.7"
+        );
+        assert_eq!(
+            login_lockout("admin@example.com", &ip),
+            "login_lockout:admin@example.com:203.0.113.7"
+        );
+    }
+
+    #[test]
+    fn test_quota_key() {
+        assert_eq!(
+            quota("ip:203.0.113.7", 1_700_000_000),
+            "quota:ip:203.0.113.7:1700000000"
+        );
+    }
 }
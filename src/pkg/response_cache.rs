@@ -0,0 +1,194 @@
+//! Redis cache for computed responses with stale-while-revalidate reads.
+//!
+//! Each entry records when it was computed. Until `fresh_for` has passed it
+//! is served as is; after that, and until Redis expires it `stale_for` later,
+//! it is still served right away while one background task recomputes it.
+//! Only a missing entry makes a request wait for the loader.
+
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::Utc;
+use redis::AsyncCommands;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::pkg::redis::keys;
+
+/// How long one instance holds the right to refresh a stale entry.
+const REFRESH_LOCK_SECONDS: u64 = 30;
+
+/// A cached value and when it was computed (Unix seconds).
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    refreshed_at: i64,
+    value: T,
+}
+
+/// How long a cached value is fresh, then served stale while refreshed.
+#[derive(Debug, Clone, Copy)]
+pub struct CachePolicy {
+    pub fresh_for: Duration,
+    pub stale_for: Duration,
+}
+
+impl CachePolicy {
+    /// How long Redis keeps an entry.
+    fn ttl_seconds(&self) -> u64 {
+        (self.fresh_for + self.stale_for).as_secs().max(1)
+    }
+}
+
+/// Handle for reading and writing cached responses.
+#[derive(Clone)]
+pub struct ResponseCache {
+    redis: redis::aio::ConnectionManager,
+}
+
+impl ResponseCache {
+    /// Create a cache on the given Redis connection.
+    pub fn new(redis: redis::aio::ConnectionManager) -> Self {
+        Self { redis }
+    }
+
+    /// The cached value at `key`, computing it with `load` when missing and
+    /// refreshing it in the background once stale.
+    ///
+    /// Entries that fail to parse, for example after their type changed in
+    /// a deploy, are treated as missing.
+    pub async fn get_or_load<T, F, Fut>(
+        &self,
+        key: String,
+        policy: CachePolicy,
+        load: F,
+    ) -> Result<T, AppError>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, AppError>> + Send,
+    {
+        let mut redis = self.redis.clone();
+        let cached: Option<String> = redis.get(&key).await?;
+        let Some(entry) = cached.and_then(|json| serde_json::from_str::<Entry<T>>(&json).ok())
+        else {
+            let value = load().await?;
+            self.put(&key, &value, policy).await?;
+            return Ok(value);
+        };
+
+        if is_stale(entry.refreshed_at, policy, Utc::now().timestamp()) {
+            let cache = self.clone();
+            tokio::spawn(async move {
+                if let Err(err) = cache.refresh(&key, policy, load).await {
+                    tracing::warn!(error = %err, %key, "Failed to refresh cached response");
+                }
+            });
+        }
+        Ok(entry.value)
+    }
+
+    /// Cache `value` at `key` as freshly computed.
+    pub async fn put<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        policy: CachePolicy,
+    ) -> Result<(), AppError> {
+        let entry = Entry {
+            refreshed_at: Utc::now().timestamp(),
+            value,
+        };
+        let json = serde_json::to_string(&entry)
+            .map_err(|e| AppError::InternalError(format!("Failed to cache response: {}", e)))?;
+        let mut redis = self.redis.clone();
+        let _: () = redis.set_ex(key, json, policy.ttl_seconds()).await?;
+        Ok(())
+    }
+
+    /// Drop cached values, so the next read computes them again.
+    pub async fn invalidate(&self, keys: &[String]) -> Result<(), AppError> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let mut redis = self.redis.clone();
+        let _: () = redis.del(keys).await?;
+        Ok(())
+    }
+
+    // Private helper methods
+
+    /// Recompute a stale entry unless another request or instance already is.
+    async fn refresh<T, F, Fut>(
+        &self,
+        key: &str,
+        policy: CachePolicy,
+        load: F,
+    ) -> Result<(), AppError>
+    where
+        T: Serialize,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        let lock = keys::cache_refresh(key);
+        let mut redis = self.redis.clone();
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(&lock)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(REFRESH_LOCK_SECONDS)
+            .query_async(&mut redis)
+            .await?;
+        if acquired.is_none() {
+            return Ok(());
+        }
+
+        let result = match load().await {
+            Ok(value) => self.put(key, &value, policy).await,
+            Err(err) => Err(err),
+        };
+        let _: () = redis.del(&lock).await?;
+        result
+    }
+}
+
+/// Whether an entry computed at `refreshed_at` is past its fresh period at `now`.
+fn is_stale(refreshed_at: i64, policy: CachePolicy, now: i64) -> bool {
+    now.saturating_sub(refreshed_at) >= policy.fresh_for.as_secs() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: CachePolicy = CachePolicy {
+        fresh_for: Duration::from_secs(60),
+        stale_for: Duration::from_secs(600),
+    };
+
+    #[test]
+    fn test_is_stale() {
+        assert!(!is_stale(1_000, POLICY, 1_000));
+        assert!(!is_stale(1_000, POLICY, 1_059));
+        assert!(is_stale(1_000, POLICY, 1_060));
+        // Clocks of other instances may run behind
+        assert!(!is_stale(1_000, POLICY, 900));
+    }
+
+    #[test]
+    fn test_ttl_seconds() {
+        assert_eq!(POLICY.ttl_seconds(), 660);
+    }
+
+    #[test]
+    fn test_entry_round_trip() {
+        let json = serde_json::to_string(&Entry {
+            refreshed_at: 42,
+            value: &vec![1, 2, 3],
+        })
+        .unwrap();
+        let entry: Entry<Vec<i32>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(entry.refreshed_at, 42);
+        assert_eq!(entry.value, vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,249 @@
+//! Meilisearch search backend.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use hyper::Method;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{
+    SearchEngine, SearchHit, SearchHits, SearchQuery, SnippetOptions, MATCH_END, MATCH_START,
+};
+use crate::error::AppError;
+use crate::models::{PostSearchDocument, PostViewer};
+use crate::pkg::http_client;
+
+/// Searches a Meilisearch index of posts.
+///
+/// Every post is indexed, whatever its status, and filtered by site, status,
+/// category and visibility at query time. Writes are asynchronous on the Meilisearch side,
+/// so changes show up shortly after the indexer pushes them. Snippets are the
+/// post content cropped around the matched terms, with its markup removed.
+#[derive(Clone)]
+pub struct MeilisearchEngine {
+    base_url: String,
+    api_key: Option<String>,
+    index: String,
+    snippets: SnippetOptions,
+    timeout: Duration,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    hits: Vec<Hit>,
+    #[serde(rename = "estimatedTotalHits", default)]
+    estimated_total_hits: i64,
+}
+
+#[derive(Deserialize)]
+struct Hit {
+    id: Uuid,
+    #[serde(rename = "_formatted")]
+    formatted: Option<FormattedHit>,
+}
+
+#[derive(Deserialize)]
+struct FormattedHit {
+    content: Option<String>,
+}
+
+impl MeilisearchEngine {
+    /// Create a client for `index` on the server at `base_url`.
+    pub fn new(
+        base_url: &str,
+        api_key: Option<String>,
+        index: &str,
+        snippets: SnippetOptions,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            index: index.to_string(),
+            snippets,
+            timeout,
+        }
+    }
+
+    // Private helper methods
+
+    async fn call(
+        &self,
+        method: Method,
+        path: &str,
+        body: serde_json::Value,
+    ) -> Result<String, AppError> {
+        let url = format!("{}/indexes/{}{}", self.base_url, self.index, path);
+        let authorization = self.api_key.as_ref().map(|key| format!("Bearer {}", key));
+        let headers: Vec<(&str, &str)> = authorization
+            .as_deref()
+            .map(|value| ("Authorization", value))
+            .into_iter()
+            .collect();
+
+        let response =
+            http_client::send(method, &url, &headers, Some(body.to_string()), self.timeout)
+                .await
+                .map_err(|err| unavailable(&err))?;
+        if !response.is_success() {
+            return Err(unavailable(&format!(
+                "{} returned {}: {}",
+                path, response.status, response.body
+            )));
+        }
+        Ok(response.body)
+    }
+}
+
+#[async_trait]
+impl SearchEngine for MeilisearchEngine {
+    fn name(&self) -> &'static str {
+        "meilisearch"
+    }
+
+    fn needs_sync(&self) -> bool {
+        true
+    }
+
+    async fn prepare(&self) -> Result<(), AppError> {
+        // Updating settings also creates the index when it does not exist yet
+        self.call(
+            Method::PATCH,
+            "/settings",
+            json!({
+                "searchableAttributes": ["title", "excerpt", "content"],
+                "filterableAttributes": [
+                    "site_id",
+                    "status",
+                    "category_id",
+                    "visibility",
+                    "author_id",
+                ],
+            }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn search(&self, query: &SearchQuery<'_>) -> Result<SearchHits, AppError> {
+        let body = self
+            .call(
+                Method::POST,
+                "/search",
+                json!({
+                    "q": query.text,
+                    "filter": filter_expression(query),
+                    "limit": query.limit,
+                    "offset": query.offset,
+                    "attributesToRetrieve": ["id"],
+                    "attributesToCrop": ["content"],
+                    "cropLength": self.snippets.words,
+                    "attributesToHighlight": ["content"],
+                    "highlightPreTag": MATCH_START.to_string(),
+                    "highlightPostTag": MATCH_END.to_string(),
+                }),
+            )
+            .await?;
+        let response: SearchResponse =
+            serde_json::from_str(&body).map_err(|err| unavailable(&err.to_string()))?;
+
+        Ok(SearchHits {
+            hits: response
+                .hits
+                .into_iter()
+                .map(|hit| SearchHit {
+                    id: hit.id,
+                    snippet: hit
+                        .formatted
+                        .and_then(|formatted| formatted.content)
+                        .map(|content| self.snippets.render(&content)),
+                })
+                .collect(),
+            total: response.estimated_total_hits,
+        })
+    }
+
+    async fn upsert(&self, documents: &[PostSearchDocument]) -> Result<(), AppError> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+        self.call(Method::POST, "/documents?primaryKey=id", json!(documents))
+            .await?;
+        Ok(())
+    }
+
+    async fn remove(&self, ids: &[Uuid]) -> Result<(), AppError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        self.call(Method::POST, "/documents/delete-batch", json!(ids))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Meilisearch filter restricting hits to the query's site, status, category
+/// and the visibilities its viewer may see.
+fn filter_expression(query: &SearchQuery<'_>) -> String {
+    let mut filters = vec![format!("site_id = \"{}\"", query.site_id)];
+    match query.viewer {
+        PostViewer::Admin => {}
+        PostViewer::Anonymous => filters.push("visibility = \"public\"".to_string()),
+        PostViewer::Member(id) => filters.push(format!(
+            "(visibility IN [\"public\", \"members\"] OR (visibility = \"private\" AND author_id = \"{}\"))",
+            id
+        )),
+    }
+    if let Some(status) = query.status {
+        filters.push(format!("status = \"{}\"", status));
+    }
+    if let Some(category_id) = query.category_id {
+        filters.push(format!("category_id = \"{}\"", category_id));
+    }
+    filters.join(" AND ")
+}
+
+fn unavailable(detail: &str) -> AppError {
+    tracing::warn!(error = %detail, "Meilisearch request failed");
+    AppError::ServiceUnavailable("Search is temporarily unavailable".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PostStatus;
+
+    #[test]
+    fn test_filter_expression() {
+        let site_id = Uuid::nil();
+        let mut query = SearchQuery {
+            site_id,
+            viewer: PostViewer::Admin,
+            text: "rust",
+            status: Some(PostStatus::Published),
+            category_id: None,
+            limit: 10,
+            offset: 0,
+        };
+        assert_eq!(
+            filter_expression(&query),
+            format!("site_id = \"{}\" AND status = \"published\"", site_id)
+        );
+
+        query.status = None;
+        query.category_id = Some(site_id);
+        assert_eq!(
+            filter_expression(&query),
+            format!("site_id = \"{0}\" AND category_id = \"{0}\"", site_id)
+        );
+
+        query.category_id = None;
+        query.viewer = PostViewer::Anonymous;
+        assert_eq!(
+            filter_expression(&query),
+            format!("site_id = \"{}\" AND visibility = \"public\"", site_id)
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Pluggable post search.
+//!
+//! Services only talk to [`SearchEngine`], which answers a query with post IDs.
+//! [`PostgresSearch`] (the default) reads a generated `tsvector` column on
+//! `posts` and needs no syncing; [`MeilisearchEngine`] queries an external
+//! index that the search indexer keeps in sync with post writes. Both return
+//! a highlighted snippet showing where each post matched, rendered by
+//! [`SnippetOptions::render`] so that the configured highlight tags are its
+//! only markup.
+
+mod meilisearch;
+mod postgres;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::{Config, SearchBackend};
+use crate::error::AppError;
+use crate::models::{PostSearchDocument, PostStatus, PostViewer};
+
+pub use meilisearch::MeilisearchEngine;
+pub use postgres::PostgresSearch;
+
+/// A search over the posts of one site that `viewer` may see.
+#[derive(Debug, Clone)]
+pub struct SearchQuery<'a> {
+    pub site_id: Uuid,
+    pub viewer: PostViewer,
+    pub text: &'a str,
+    pub status: Option<PostStatus>,
+    pub category_id: Option<Uuid>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// A matching post and the snippet of it that matched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub id: Uuid,
+    pub snippet: Option<String>,
+}
+
+/// Matching posts, best match first, and the total number of matches.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchHits {
+    pub hits: Vec<SearchHit>,
+    pub total: i64,
+}
+
+/// Marks the start of a match in engine output, before [`SnippetOptions::render`]
+/// swaps in the configured tag (a private-use character no post should contain).
+const MATCH_START: char = '\u{E000}';
+/// Marks the end of a match in engine output.
+const MATCH_END: char = '\u{E001}';
+
+/// How result snippets are cut and highlighted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnippetOptions {
+    /// Longest snippet, in words
+    pub words: usize,
+    pub pre_tag: String,
+    pub post_tag: String,
+}
+
+impl SnippetOptions {
+    /// Read the `SEARCH_SNIPPET_*` and `SEARCH_HIGHLIGHT_*` settings.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            words: config.search_snippet_words,
+            pre_tag: config.search_highlight_pre_tag.clone(),
+            post_tag: config.search_highlight_post_tag.clone(),
+        }
+    }
+
+    /// Turn a snippet whose matches are wrapped in [`MATCH_START`] and
+    /// [`MATCH_END`] into HTML.
+    ///
+    /// Markup from the post is dropped and everything else escaped, including
+    /// tags cut in half at the fragment's edges, so the snippet is safe to
+    /// render and only carries the configured highlight tags.
+    pub fn render(&self, raw: &str) -> String {
+        let mut html = String::with_capacity(raw.len());
+        let mut highlighted = false;
+        let mut in_tag = false;
+        for (i, c) in raw.char_indices() {
+            match c {
+                MATCH_START if !highlighted => {
+                    html.push_str(&self.pre_tag);
+                    highlighted = true;
+                }
+                MATCH_END if highlighted => {
+                    html.push_str(&self.post_tag);
+                    highlighted = false;
+                }
+                MATCH_START | MATCH_END => {}
+                '>' if in_tag => in_tag = false,
+                _ if in_tag => {}
+                '<' if starts_tag(&raw[i + 1..]) => in_tag = true,
+                '&' => html.push_str("&amp;"),
+                '<' => html.push_str("&lt;"),
+                '>' => html.push_str("&gt;"),
+                '"' => html.push_str("&quot;"),
+                '\'' => html.push_str("&#39;"),
+                c => html.push(c),
+            }
+        }
+        if highlighted {
+            html.push_str(&self.post_tag);
+        }
+        html
+    }
+}
+
+/// Whether text following a `<` is a complete HTML tag or comment.
+fn starts_tag(rest: &str) -> bool {
+    rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!')
+        && rest.contains('>')
+}
+
+/// Backend that answers post searches.
+#[async_trait]
+pub trait SearchEngine: Send + Sync {
+    /// Short backend name for logs.
+    fn name(&self) -> &'static str;
+
+    /// Whether post writes must be pushed to the engine by the indexer.
+    fn needs_sync(&self) -> bool {
+        false
+    }
+
+    /// Prepare the index (settings, schema) before the first sync.
+    async fn prepare(&self) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Find posts matching the query.
+    async fn search(&self, query: &SearchQuery<'_>) -> Result<SearchHits, AppError>;
+
+    /// Add or replace posts in the index.
+    async fn upsert(&self, _documents: &[PostSearchDocument]) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Remove posts from the index.
+    async fn remove(&self, _ids: &[Uuid]) -> Result<(), AppError> {
+        Ok(())
+    }
+}
+
+/// Build the engine selected by `SEARCH_BACKEND`.
+pub fn from_config(config: &Config, pool: PgPool) -> Arc<dyn SearchEngine> {
+    let snippets = SnippetOptions::from_config(config);
+    match (config.search_backend, &config.meilisearch_url) {
+        (SearchBackend::Meilisearch, Some(url)) => Arc::new(MeilisearchEngine::new(
+            url,
+            config.meilisearch_api_key.clone(),
+            &config.meilisearch_index,
+            snippets,
+            config.http_client_timeout(),
+        )),
+        _ => Arc::new(PostgresSearch::new(pool, snippets)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> SnippetOptions {
+        SnippetOptions {
+            words: 30,
+            pre_tag: "<mark>".to_string(),
+            post_tag: "</mark>".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_snippet() {
+        let raw = format!(
+            "<p>Learn <b>{}Rust</b>{} now</p><script>alert(\"x\")</script> & more",
+            MATCH_START, MATCH_END
+        );
+        assert_eq!(
+            options().render(&raw),
+            "Learn <mark>Rust</mark> nowalert(&quot;x&quot;) &amp; more"
+        );
+
+        // Tags cut at the fragment's edges are escaped, not kept as markup
+        let raw = format!(
+            "ipt>{}rust{} <img src=x onerror=alert(1)",
+            MATCH_START, MATCH_END
+        );
+        assert_eq!(
+            options().render(&raw),
+            "ipt&gt;<mark>rust</mark> &lt;img src=x onerror=alert(1)"
+        );
+
+        // An unclosed match still ends the highlight
+        let raw = format!("{}rust", MATCH_START);
+        assert_eq!(options().render(&raw), "<mark>rust</mark>");
+    }
+}
@@ -0,0 +1,133 @@
+//! Postgres full-text search backend.
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{
+    SearchEngine, SearchHit, SearchHits, SearchQuery, SnippetOptions, MATCH_END, MATCH_START,
+};
+use crate::error::AppError;
+
+/// Searches the generated `posts.search_vector` column (migration 024).
+///
+/// Queries use `websearch_to_tsquery`, so quoted phrases, `or` and `-word`
+/// work as on common search engines. Title matches rank above body matches.
+/// Snippets come from `ts_headline` over the excerpt and content.
+#[derive(Clone)]
+pub struct PostgresSearch {
+    pool: PgPool,
+    snippets: SnippetOptions,
+    headline_options: String,
+}
+
+impl PostgresSearch {
+    /// Create a search backend on the application database.
+    pub fn new(pool: PgPool, snippets: SnippetOptions) -> Self {
+        Self {
+            pool,
+            headline_options: headline_options(&snippets),
+            snippets,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchEngine for PostgresSearch {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn search(&self, query: &SearchQuery<'_>) -> Result<SearchHits, AppError> {
+        let hits: Vec<(Uuid, String)> = sqlx::query_as(
+            r#"
+            SELECT p.id,
+                ts_headline('simple', concat_ws(' ', p.excerpt, p.content), q, $7) as snippet
+            FROM posts p, websearch_to_tsquery('simple', $2) q
+            WHERE p.site_id = $1
+              AND p.search_vector @@ q
+              AND ($3::post_status IS NULL OR p.status = $3)
+              AND ($4::uuid IS NULL OR p.category_id = $4)
+              AND ($8 OR p.visibility = 'public'
+                   OR (p.visibility = 'members' AND $9::uuid IS NOT NULL)
+                   OR (p.visibility = 'private' AND p.author_id = $9))
+            ORDER BY ts_rank(p.search_vector, q) DESC, COALESCE(p.published_at, p.created_at) DESC
+            LIMIT $5 OFFSET $6
+            "#,
+        )
+        .bind(query.site_id)
+        .bind(query.text)
+        .bind(query.status)
+        .bind(query.category_id)
+        .bind(query.limit)
+        .bind(query.offset)
+        .bind(&self.headline_options)
+        .bind(query.viewer.is_admin())
+        .bind(query.viewer.member_id())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM posts p, websearch_to_tsquery('simple', $2) q
+            WHERE p.site_id = $1
+              AND p.search_vector @@ q
+              AND ($3::post_status IS NULL OR p.status = $3)
+              AND ($4::uuid IS NULL OR p.category_id = $4)
+              AND ($5 OR p.visibility = 'public'
+                   OR (p.visibility = 'members' AND $6::uuid IS NOT NULL)
+                   OR (p.visibility = 'private' AND p.author_id = $6))
+            "#,
+        )
+        .bind(query.site_id)
+        .bind(query.text)
+        .bind(query.status)
+        .bind(query.category_id)
+        .bind(query.viewer.is_admin())
+        .bind(query.viewer.member_id())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(SearchHits {
+            hits: hits
+                .into_iter()
+                .map(|(id, snippet)| SearchHit {
+                    id,
+                    snippet: Some(self.snippets.render(&snippet)),
+                })
+                .collect(),
+            total: total.0,
+        })
+    }
+}
+
+/// `ts_headline` options producing a single fragment of at most `words` words,
+/// with matches marked for [`SnippetOptions::render`].
+fn headline_options(snippets: &SnippetOptions) -> String {
+    format!(
+        "StartSel=\"{}\", StopSel=\"{}\", MaxWords={}, MinWords={}, MaxFragments=1",
+        MATCH_START,
+        MATCH_END,
+        snippets.words,
+        (snippets.words / 2).max(1)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headline_options() {
+        let snippets = SnippetOptions {
+            words: 30,
+            pre_tag: "<mark>".to_string(),
+            post_tag: "</mark>".to_string(),
+        };
+        assert_eq!(
+            headline_options(&snippets),
+            "StartSel=\"\u{E000}\", StopSel=\"\u{E001}\", MaxWords=30, MinWords=15, MaxFragments=1"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Slow database statement log.
+//!
+//! sqlx times every statement and reports those exceeding the pool's slow
+//! statement threshold as `sqlx::query` tracing events. [`SlowQueryLayer`]
+//! picks those events up, logs them with string literals redacted and keeps
+//! the most recent ones in a [`SlowQueryLog`] for the diagnostics endpoint.
+//! Bound parameters (`$1`, `$2`, ...) are never part of the statement text.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Tracing target of sqlx's statement timings.
+const SQLX_QUERY_TARGET: &str = "sqlx::query";
+/// Number of recent slow statements kept in memory.
+pub const SLOW_QUERY_LOG_CAPACITY: usize = 100;
+
+/// A statement that exceeded the slow query threshold.
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowQuery {
+    /// Statement text with string literals replaced by `'?'`
+    pub statement: String,
+    pub duration_ms: f64,
+    pub rows_returned: u64,
+    pub rows_affected: u64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Bounded in-memory log of recent slow statements.
+#[derive(Clone, Default)]
+pub struct SlowQueryLog {
+    entries: Arc<Mutex<VecDeque<SlowQuery>>>,
+}
+
+impl SlowQueryLog {
+    /// Create an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tracing layer feeding this log from sqlx's slow statement events.
+    pub fn layer(&self) -> SlowQueryLayer {
+        SlowQueryLayer { log: self.clone() }
+    }
+
+    /// Add a statement, evicting the oldest once the log is full.
+    pub fn record(&self, query: SlowQuery) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == SLOW_QUERY_LOG_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(query);
+    }
+
+    /// Up to `limit` of the recent slow statements, slowest first.
+    pub fn slowest(&self, limit: usize) -> Vec<SlowQuery> {
+        let mut queries: Vec<SlowQuery> = self
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect();
+        queries.sort_by(|a, b| b.duration_ms.total_cmp(&a.duration_ms));
+        queries.truncate(limit);
+        queries
+    }
+}
+
+/// Tracing layer recording sqlx's slow statement events.
+pub struct SlowQueryLayer {
+    log: SlowQueryLog,
+}
+
+impl<S: Subscriber> Layer<S> for SlowQueryLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != SQLX_QUERY_TARGET {
+            return;
+        }
+        let mut fields = QueryFields::default();
+        event.record(&mut fields);
+        if !fields.slow {
+            return;
+        }
+
+        let statement = match fields.statement.trim() {
+            "" => redact_literals(&fields.summary),
+            statement => redact_literals(statement),
+        };
+        let query = SlowQuery {
+            statement,
+            duration_ms: fields.elapsed_secs * 1000.0,
+            rows_returned: fields.rows_returned,
+            rows_affected: fields.rows_affected,
+            recorded_at: Utc::now(),
+        };
+        tracing::warn!(
+            duration_ms = query.duration_ms,
+            rows_returned = query.rows_returned,
+            rows_affected = query.rows_affected,
+            statement = %query.statement,
+            "Slow query"
+        );
+        self.log.record(query);
+    }
+}
+
+/// Fields of a `sqlx::query` event.
+#[derive(Default)]
+struct QueryFields {
+    summary: String,
+    statement: String,
+    elapsed_secs: f64,
+    rows_returned: u64,
+    rows_affected: u64,
+    /// Only slow statement events carry `slow_threshold`
+    slow: bool,
+}
+
+impl Visit for QueryFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "summary" => self.summary = value.to_string(),
+            "db.statement" => self.statement = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            "rows_returned" => self.rows_returned = value,
+            "rows_affected" => self.rows_affected = value,
+            _ => {}
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = value;
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, _value: &dyn fmt::Debug) {
+        if field.name() == "slow_threshold" {
+            self.slow = true;
+        }
+    }
+}
+
+/// Collapse whitespace and replace single-quoted string literals with `'?'`.
+pub fn redact_literals(sql: &str) -> String {
+    let mut redacted = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\'' {
+            redacted.push(c);
+            continue;
+        }
+        // Skip to the closing quote; '' inside a literal is an escaped quote
+        while let Some(c) = chars.next() {
+            if c == '\'' {
+                if chars.peek() == Some(&'\'') {
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+        }
+        redacted.push_str("'?'");
+    }
+    redacted.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slow_query(duration_ms: f64) -> SlowQuery {
+        SlowQuery {
+            statement: "SELECT 1".to_string(),
+            duration_ms,
+            rows_returned: 1,
+            rows_affected: 0,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_redact_literals() {
+        assert_eq!(
+            redact_literals("SELECT *\n  FROM users WHERE email = 'a@b.c' AND id = $1"),
+            "SELECT * FROM users WHERE email = '?' AND id = $1"
+        );
+        assert_eq!(
+            redact_literals("UPDATE posts SET title = 'it''s' WHERE status = 'draft'"),
+            "UPDATE posts SET title = '?' WHERE status = '?'"
+        );
+        assert_eq!(redact_literals("SELECT 'unterminated"), "SELECT '?'");
+    }
+
+    #[test]
+    fn test_layer_records_slow_statement_events() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let log = SlowQueryLog::new();
+        let subscriber = tracing_subscriber::registry().with(log.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(
+                target: "sqlx::query",
+                summary = "SELECT * FROM posts …",
+                db.statement = "\n\nSELECT * FROM posts WHERE slug = 'hello'\n",
+                rows_affected = 0u64,
+                rows_returned = 2u64,
+                elapsed_secs = 1.5,
+                slow_threshold = ?std::time::Duration::from_millis(500),
+                "slow statement: execution time exceeded alert threshold"
+            );
+            tracing::debug!(
+                target: "sqlx::query",
+                summary = "SELECT 1",
+                db.statement = "",
+                rows_affected = 0u64,
+                rows_returned = 1u64,
+                elapsed_secs = 0.001,
+            );
+        });
+
+        let recorded = log.slowest(10);
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(
+            recorded[0].statement,
+            "SELECT * FROM posts WHERE slug = '?'"
+        );
+        assert_eq!(recorded[0].duration_ms, 1500.0);
+        assert_eq!(recorded[0].rows_returned, 2);
+    }
+
+    #[test]
+    fn test_slow_query_log_keeps_recent_and_sorts_slowest_first() {
+        let log = SlowQueryLog::new();
+        for i in 0..SLOW_QUERY_LOG_CAPACITY + 5 {
+            log.record(slow_query(i as f64));
+        }
+
+        let slowest = log.slowest(3);
+        assert_eq!(slowest.len(), 3);
+        assert_eq!(slowest[0].duration_ms, (SLOW_QUERY_LOG_CAPACITY + 4) as f64);
+        assert!(slowest[0].duration_ms > slowest[2].duration_ms);
+        assert_eq!(log.slowest(usize::MAX).len(), SLOW_QUERY_LOG_CAPACITY);
+    }
+}
@@ -0,0 +1,333 @@
+//! URL slugs for posts, categories, tags and roles.
+//!
+//! Text is transliterated to ASCII where a Latin equivalent exists: accents
+//! are stripped (`café` becomes `cafe`) and letters like `ß` or `ø` are
+//! spelled out. Letters of other scripts are kept as they are. Generated
+//! slugs may also drop stop words and are capped in length; when one is
+//! already taken, `-2`, `-3`… is appended instead of failing.
+//!
+//! Post and category slugs may not be one of the reserved slugs, which name
+//! routes of the API and the frontend (`RESERVED_SLUGS` adds more), so
+//! content can never shadow them.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use crate::config::Config;
+use crate::error::{AppError, FieldError};
+
+/// Suffixes tried before giving up on finding a free slug.
+const MAX_SUFFIX: u32 = 100;
+
+/// Slugs of application routes, never given to posts or categories.
+pub const BUILTIN_RESERVED_SLUGS: &[&str] = &[
+    "admin",
+    "api",
+    "archive",
+    "assets",
+    "atom",
+    "auth",
+    "blog",
+    "categories",
+    "category",
+    "dashboard",
+    "feed",
+    "login",
+    "logout",
+    "me",
+    "media",
+    "page",
+    "pages",
+    "post",
+    "posts",
+    "preview",
+    "register",
+    "rss",
+    "search",
+    "settings",
+    "signup",
+    "sitemap",
+    "static",
+    "tag",
+    "tags",
+    "uploads",
+];
+
+/// Turn `text` into a slug: lowercase ASCII where possible, with runs of
+/// anything but letters and digits collapsed into single hyphens.
+pub fn slugify(text: &str) -> String {
+    words(text).join("-")
+}
+
+/// Cut `slug` to at most `max_len` bytes, at a hyphen when there is one.
+pub fn truncate(slug: &str, max_len: usize) -> &str {
+    if slug.len() <= max_len {
+        return slug;
+    }
+    let mut end = max_len;
+    while !slug.is_char_boundary(end) {
+        end -= 1;
+    }
+    match slug[..end].rfind('-') {
+        Some(hyphen) if slug.as_bytes()[end] != b'-' && hyphen > 0 => &slug[..hyphen],
+        _ => slug[..end].trim_end_matches('-'),
+    }
+}
+
+/// The first of `base`, `base-2`, `base-3`… (each at most `max_len` bytes)
+/// that `is_taken` reports as free.
+pub async fn unique<F, Fut>(base: &str, max_len: usize, mut is_taken: F) -> Result<String, AppError>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<bool, AppError>>,
+{
+    if base.is_empty() {
+        return Err(AppError::ValidationError(
+            "A slug needs letters or digits; provide one".to_string(),
+        ));
+    }
+    let candidate = truncate(base, max_len).to_string();
+    if !is_taken(candidate.clone()).await? {
+        return Ok(candidate);
+    }
+    for n in 2..=MAX_SUFFIX {
+        let suffix = format!("-{}", n);
+        let candidate = format!(
+            "{}{}",
+            truncate(base, max_len.saturating_sub(suffix.len())),
+            suffix
+        );
+        if !is_taken(candidate.clone()).await? {
+            return Ok(candidate);
+        }
+    }
+    Err(AppError::Conflict("Slug already exists".to_string()))
+}
+
+/// Settings for slugs generated from titles and names (`SLUG_*`), and the
+/// reserved slugs content may not use.
+#[derive(Debug, Clone)]
+pub struct SlugGenerator {
+    max_length: usize,
+    stop_words: Arc<HashSet<String>>,
+    reserved: Arc<HashSet<String>>,
+}
+
+impl SlugGenerator {
+    /// Build the generator from `SLUG_*` settings.
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.slug_max_length,
+            &config.slug_stop_words,
+            &config.reserved_slugs,
+        )
+    }
+
+    /// Create a generator capping slugs at `max_length` bytes and leaving
+    /// out `stop_words`, reserving `reserved` on top of the built-in slugs.
+    pub fn new(max_length: usize, stop_words: &[String], reserved: &[String]) -> Self {
+        Self {
+            max_length,
+            stop_words: Arc::new(stop_words.iter().map(|word| slugify(word)).collect()),
+            reserved: Arc::new(
+                BUILTIN_RESERVED_SLUGS
+                    .iter()
+                    .map(|slug| slug.to_string())
+                    .chain(reserved.iter().map(|slug| slugify(slug)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Whether `slug` names an application route once slugified, so `API`
+    /// or ` admin ` count as well.
+    pub fn is_reserved(&self, slug: &str) -> bool {
+        self.reserved.contains(&slugify(slug))
+    }
+
+    /// Reject a reserved `slug`, reporting it against `field`.
+    pub fn check_reserved(&self, field: &str, slug: &str) -> Result<(), AppError> {
+        if self.is_reserved(slug) {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                field,
+                format!("\"{}\" is reserved for site routes", slug),
+            )]));
+        }
+        Ok(())
+    }
+
+    /// Longest generated slug.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    /// Slug for `text`, without stop words unless nothing else is left, at
+    /// most `max_length` bytes long.
+    pub fn generate(&self, text: &str) -> String {
+        let words = words(text);
+        let kept: Vec<&str> = words
+            .iter()
+            .map(String::as_str)
+            .filter(|word| !self.stop_words.contains(*word))
+            .collect();
+        let slug = if kept.is_empty() {
+            words.join("-")
+        } else {
+            kept.join("-")
+        };
+        truncate(&slug, self.max_length).to_string()
+    }
+}
+
+/// Lowercase, transliterated words of `text`.
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in text.chars().flat_map(latinize) {
+        if matches!(c, '\'' | '’') {
+            // "Don't" is one word
+            continue;
+        }
+        if c.is_ascii_alphanumeric() {
+            word.push(c.to_ascii_lowercase());
+        } else if let Some(ascii) = transliterate(c) {
+            word.push_str(ascii);
+        } else if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        } else if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// `c` without accents when it is a Latin letter, otherwise only normalized
+/// (so Japanese kana keep their voicing marks).
+fn latinize(c: char) -> Vec<char> {
+    let stripped: Vec<char> = std::iter::once(c)
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .collect();
+    if !stripped.is_empty()
+        && stripped
+            .iter()
+            .all(|c| c.is_ascii() || transliterate(*c).is_some())
+    {
+        stripped
+    } else {
+        std::iter::once(c).nfkc().collect()
+    }
+}
+
+/// ASCII spelling of a Latin letter without a decomposition.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' | 'ẞ' => "ss",
+        'æ' | 'Æ' => "ae",
+        'œ' | 'Œ' => "oe",
+        'ø' | 'Ø' => "o",
+        'đ' | 'Đ' | 'ð' | 'Ð' => "d",
+        'ł' | 'Ł' => "l",
+        'ı' => "i",
+        'þ' | 'Þ' => "th",
+        'ħ' | 'Ħ' => "h",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello World"), "hello-world");
+        assert_eq!(slugify("  Hello   World!  "), "hello-world");
+        assert_eq!(slugify("Rust 2024"), "rust-2024");
+        assert_eq!(slugify("Rust & Go"), "rust-go");
+        assert_eq!(slugify("C++"), "c");
+        assert_eq!(slugify("Don't Panic"), "dont-panic");
+        assert_eq!(slugify("Café Crème à Paris"), "cafe-creme-a-paris");
+        assert_eq!(slugify("Straße nach Øresund"), "strasse-nach-oresund");
+        assert_eq!(
+            slugify("Kopi Tubruk Khas Đà Lạt"),
+            "kopi-tubruk-khas-da-lat"
+        );
+        assert_eq!(slugify("Ｒｕｓｔ"), "rust");
+        assert_eq!(slugify("日本語 の ブログ"), "日本語-の-ブログ");
+        assert_eq!(slugify("!!!"), "");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("hello-world", 20), "hello-world");
+        assert_eq!(truncate("hello-world", 11), "hello-world");
+        assert_eq!(truncate("hello-world", 8), "hello");
+        assert_eq!(truncate("hello-world", 6), "hello");
+        assert_eq!(truncate("helloworld", 5), "hello");
+        assert_eq!(truncate("日本語", 4), "日");
+    }
+
+    #[test]
+    fn test_generate() {
+        let stop_words = ["the", "dan", "yang", "di"].map(String::from);
+        let slugs = SlugGenerator::new(24, &stop_words, &[]);
+        assert_eq!(
+            slugs.generate("Belajar Rust dan Go yang Menyenangkan"),
+            "belajar-rust-go"
+        );
+        assert_eq!(slugs.generate("The Rust Book"), "rust-book");
+        assert_eq!(slugs.generate("The The"), "the-the");
+    }
+
+    #[test]
+    fn test_reserved() {
+        let slugs = SlugGenerator::new(80, &[], &["Shop".to_string()]);
+        assert!(slugs.is_reserved("admin"));
+        assert!(slugs.is_reserved("feed"));
+        assert!(slugs.is_reserved("shop"));
+        assert!(!slugs.is_reserved("admin-tips"));
+        assert!(slugs.check_reserved("slug", "api").is_err());
+        assert!(slugs.check_reserved("slug", "hello-world").is_ok());
+    }
+
+    #[test]
+    fn test_reserved_mixed_case() {
+        let slugs = SlugGenerator::new(80, &[], &["Shop".to_string()]);
+        assert!(slugs.is_reserved("Admin"));
+        assert!(slugs.is_reserved("API"));
+        assert!(slugs.is_reserved("api "));
+        assert!(slugs.is_reserved(" FEED"));
+        assert!(slugs.is_reserved("SHOP"));
+        assert!(!slugs.is_reserved("Admin Tips"));
+        assert!(slugs.check_reserved("slug", "Api").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unique() {
+        let taken = ["hello", "hello-2"];
+        let slug = unique("hello", 20, |candidate| async move {
+            Ok(taken.contains(&candidate.as_str()))
+        })
+        .await
+        .unwrap();
+        assert_eq!(slug, "hello-3");
+
+        let slug = unique("hello-world", 11, |candidate| async move {
+            Ok(candidate == "hello-world")
+        })
+        .await
+        .unwrap();
+        assert_eq!(slug, "hello-2");
+
+        let slug = unique("fresh", 20, |_| async { Ok(false) }).await.unwrap();
+        assert_eq!(slug, "fresh");
+        assert!(unique("", 20, |_| async { Ok(false) }).await.is_err());
+    }
+}
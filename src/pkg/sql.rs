@@ -0,0 +1,29 @@
+//! Helpers for passing user input to SQL queries.
+
+/// Escape `LIKE` wildcards so user input only matches literally.
+///
+/// Postgres uses `\` as the default `LIKE` escape character, so the result
+/// can be wrapped in `%…%` and bound as a pattern as is.
+pub fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("ru"), "ru");
+        assert_eq!(escape_like("100%_"), "100\\%\\_");
+        assert_eq!(escape_like("a\\b"), "a\\\\b");
+        assert_eq!(escape_like("café_"), "café\\_");
+    }
+}
@@ -0,0 +1,137 @@
+//! Local directory storage backend.
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{is_valid_key, Storage, StoredObject};
+use crate::error::AppError;
+
+/// Keeps objects as files under a directory, one file per key.
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    dir: PathBuf,
+}
+
+impl LocalStorage {
+    /// Store objects under `dir`, created on the first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    // Private helper methods
+
+    fn path(&self, key: &str) -> Result<PathBuf, AppError> {
+        if !is_valid_key(key) {
+            return Err(AppError::InternalError(format!(
+                "Invalid storage key '{}'",
+                key
+            )));
+        }
+        Ok(self.dir.join(key))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), AppError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+        }
+        // Write next to the target and rename so readers never see a partial file
+        let mut partial = path.clone().into_os_string();
+        partial.push(".partial");
+        tokio::fs::write(&partial, bytes).await.map_err(io_error)?;
+        tokio::fs::rename(&partial, &path).await.map_err(io_error)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, AppError> {
+        let mut objects = Vec::new();
+        let mut dirs = vec![self.dir.clone()];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => return Err(io_error(err)),
+            };
+            while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+                let metadata = entry.metadata().await.map_err(io_error)?;
+                if metadata.is_dir() {
+                    dirs.push(entry.path());
+                    continue;
+                }
+                let Some(key) = key_of(&self.dir, &entry.path()) else {
+                    continue;
+                };
+                if !key.starts_with(prefix) || key.ends_with(".partial") {
+                    continue;
+                }
+                let modified_at: DateTime<Utc> = metadata
+                    .modified()
+                    .map(Into::into)
+                    .unwrap_or_else(|_| Utc::now());
+                objects.push(StoredObject {
+                    key,
+                    size: metadata.len(),
+                    modified_at,
+                });
+            }
+        }
+        Ok(objects)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(io_error(err)),
+        }
+    }
+}
+
+/// Key of the file at `path` under `dir`, with `/` separators.
+fn key_of(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    let segments: Option<Vec<&str>> = relative.iter().map(|s| s.to_str()).collect();
+    Some(segments?.join("/"))
+}
+
+fn io_error(err: std::io::Error) -> AppError {
+    AppError::InternalError(format!("Storage error: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_storage() {
+        let dir = std::env::temp_dir().join(format!("storage-test-{}", uuid::Uuid::new_v4()));
+        let storage = LocalStorage::new(&dir);
+        assert!(storage.list("").await.unwrap().is_empty());
+
+        storage
+            .put("backups/a.dump", b"one".to_vec())
+            .await
+            .unwrap();
+        storage.put("other/b.txt", b"two!".to_vec()).await.unwrap();
+        let objects = storage.list("backups/").await.unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].key, "backups/a.dump");
+        assert_eq!(objects[0].size, 3);
+
+        storage.delete("backups/a.dump").await.unwrap();
+        storage.delete("backups/a.dump").await.unwrap();
+        assert!(storage.list("backups/").await.unwrap().is_empty());
+        assert!(storage.put("../escape", Vec::new()).await.is_err());
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+//! Pluggable object storage.
+//!
+//! Services only talk to [`Storage`], which keeps whole objects under
+//! `/`-separated keys. [`LocalStorage`] (the default) writes them to a
+//! directory on disk; [`S3Storage`] talks to Amazon S3 or any S3-compatible
+//! service (Cloudflare R2, MinIO, Backblaze B2, ...).
+
+mod local;
+mod s3;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::config::{Config, StorageBackend};
+use crate::error::AppError;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+/// An object kept in storage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoredObject {
+    pub key: String,
+    pub size: u64,
+    pub modified_at: DateTime<Utc>,
+}
+
+/// Backend keeping whole objects under string keys.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Short backend name for logs.
+    fn name(&self) -> &'static str;
+
+    /// Store `bytes` under `key`, replacing any object already there.
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), AppError>;
+
+    /// Objects whose key starts with `prefix`, in no particular order.
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, AppError>;
+
+    /// Remove the object under `key`; missing objects are not an error.
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+}
+
+/// Build the storage backend selected by `STORAGE_BACKEND`.
+pub fn from_config(config: &Config) -> Arc<dyn Storage> {
+    match (
+        config.storage_backend,
+        &config.s3_endpoint,
+        &config.s3_bucket,
+        &config.s3_access_key_id,
+        &config.s3_secret_access_key,
+    ) {
+        (
+            StorageBackend::S3,
+            Some(endpoint),
+            Some(bucket),
+            Some(access_key_id),
+            Some(secret_access_key),
+        ) => Arc::new(S3Storage::new(
+            endpoint,
+            bucket,
+            &config.s3_region,
+            access_key_id,
+            secret_access_key,
+            config.http_client_timeout(),
+        )),
+        _ => Arc::new(LocalStorage::new(&config.storage_dir)),
+    }
+}
+
+/// Whether `key` is a relative `/`-separated path without `.` or `..` segments.
+pub fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_key() {
+        assert!(is_valid_key("backups/2026-01-01T000000Z.dump.age"));
+        assert!(is_valid_key("file"));
+        for key in ["", "/abs", "a//b", "a/../b", "./a", "a/"] {
+            assert!(!is_valid_key(key), "{}", key);
+        }
+    }
+}
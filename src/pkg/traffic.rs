@@ -0,0 +1,164 @@
+//! Telling human page views apart from crawlers and referrer spam.
+
+/// User-agent fragments of crawlers, link previewers and HTTP libraries, in lowercase.
+const BOT_USER_AGENTS: &[&str] = &[
+    "bot",
+    "crawl",
+    "spider",
+    "slurp",
+    "facebookexternalhit",
+    "mediapartners",
+    "lighthouse",
+    "headless",
+    "curl/",
+    "wget/",
+    "python-requests",
+    "python-urllib",
+    "go-http-client",
+    "okhttp",
+    "java/",
+    "libwww",
+    "httpclient",
+    "node-fetch",
+    "axios/",
+    "scrapy",
+];
+
+/// Where a page view came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewSource {
+    Human,
+    /// A crawler, monitor or script, or a client sending no user agent
+    Bot,
+    /// A referrer on the spam list
+    SpamReferrer,
+}
+
+/// Classifies page views by their `User-Agent` and `Referer` headers.
+#[derive(Debug, Clone, Default)]
+pub struct TrafficFilter {
+    spam_domains: Vec<String>,
+    bot_user_agents: Vec<String>,
+}
+
+impl TrafficFilter {
+    /// Filter referrers from `spam_domains` (and their subdomains) and user
+    /// agents containing a built-in or one of `extra_bot_user_agents` fragments.
+    pub fn new(spam_domains: &[String], extra_bot_user_agents: &[String]) -> Self {
+        let normalize = |entries: &[String]| -> Vec<String> {
+            entries
+                .iter()
+                .map(|entry| entry.trim().trim_end_matches('.').to_lowercase())
+                .filter(|entry| !entry.is_empty())
+                .collect()
+        };
+        let mut bot_user_agents: Vec<String> =
+            BOT_USER_AGENTS.iter().map(|s| s.to_string()).collect();
+        bot_user_agents.extend(normalize(extra_bot_user_agents));
+        Self {
+            spam_domains: normalize(spam_domains),
+            bot_user_agents,
+        }
+    }
+
+    /// Classify a view by its user agent and referrer.
+    pub fn classify(&self, user_agent: Option<&str>, referrer: Option<&str>) -> ViewSource {
+        let user_agent = user_agent.unwrap_or_default().trim().to_lowercase();
+        if user_agent.is_empty()
+            || self
+                .bot_user_agents
+                .iter()
+                .any(|fragment| user_agent.contains(fragment.as_str()))
+        {
+            return ViewSource::Bot;
+        }
+        let spam = referrer.and_then(referrer_host).is_some_and(|host| {
+            self.spam_domains.iter().any(|domain| {
+                host == *domain
+                    || host
+                        .strip_suffix(domain.as_str())
+                        .is_some_and(|rest| rest.ends_with('.'))
+            })
+        });
+        if spam {
+            ViewSource::SpamReferrer
+        } else {
+            ViewSource::Human
+        }
+    }
+}
+
+/// Lowercase host name of a referrer URL.
+fn referrer_host(referrer: &str) -> Option<String> {
+    let rest = referrer
+        .trim()
+        .split_once("://")
+        .map_or(referrer.trim(), |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = match host.rsplit_once(':') {
+        Some((host, port)) if port.bytes().all(|b| b.is_ascii_digit()) => host,
+        _ => host,
+    };
+    let host = host.trim_end_matches('.').to_lowercase();
+    (!host.is_empty()).then_some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BROWSER: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+
+    #[test]
+    fn test_classify() {
+        let filter = TrafficFilter::new(&["Spam.example".to_string()], &["Uptime".to_string()]);
+        assert_eq!(filter.classify(Some(BROWSER), None), ViewSource::Human);
+        assert_eq!(
+            filter.classify(
+                Some(BROWSER),
+                Some("https://news.ycombinator.com/item?id=1")
+            ),
+            ViewSource::Human
+        );
+        assert_eq!(filter.classify(None, None), ViewSource::Bot);
+        assert_eq!(
+            filter.classify(
+                Some("Googlebot/2.1 (+http://www.google.com/bot.html)"),
+                None
+            ),
+            ViewSource::Bot
+        );
+        assert_eq!(filter.classify(Some("curl/8.5.0"), None), ViewSource::Bot);
+        assert_eq!(
+            filter.classify(Some("UptimeMonitor/1.0"), None),
+            ViewSource::Bot
+        );
+        assert_eq!(
+            filter.classify(Some(BROWSER), Some("http://seo.spam.example:8080/offer")),
+            ViewSource::SpamReferrer
+        );
+        assert_eq!(
+            filter.classify(Some(BROWSER), Some("https://spam.example./")),
+            ViewSource::SpamReferrer
+        );
+        // Only whole labels match
+        assert_eq!(
+            filter.classify(Some(BROWSER), Some("https://notspam.example/")),
+            ViewSource::Human
+        );
+    }
+
+    #[test]
+    fn test_referrer_host() {
+        assert_eq!(
+            referrer_host("https://user@Example.com:443/a?b#c").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            referrer_host("example.org/path").as_deref(),
+            Some("example.org")
+        );
+        assert_eq!(referrer_host(""), None);
+    }
+}
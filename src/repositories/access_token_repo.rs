@@ -0,0 +1,114 @@
+//! Personal access token repository for database operations.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::PersonalAccessToken;
+
+/// Repository for personal access token database operations.
+#[derive(Clone)]
+pub struct AccessTokenRepository {
+    pool: PgPool,
+}
+
+impl AccessTokenRepository {
+    /// Create a new access token repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a token by the hash of its secret.
+    pub async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PersonalAccessToken>, AppError> {
+        let token = sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            SELECT id, user_id, name, token_prefix, token_hash, scopes, last_used_at, expires_at, created_at
+            FROM personal_access_tokens
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Get all tokens of a user, newest first.
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<PersonalAccessToken>, AppError> {
+        let tokens = sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            SELECT id, user_id, name, token_prefix, token_hash, scopes, last_used_at, expires_at, created_at
+            FROM personal_access_tokens
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    /// Store a new token.
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        token_prefix: &str,
+        token_hash: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<PersonalAccessToken, AppError> {
+        let token = sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            INSERT INTO personal_access_tokens (user_id, name, token_prefix, token_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, name, token_prefix, token_hash, scopes, last_used_at, expires_at, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(token_prefix)
+        .bind(token_hash)
+        .bind(scopes)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Record that a token was used, at most once a minute.
+    pub async fn touch(&self, id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE personal_access_tokens
+            SET last_used_at = NOW()
+            WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delete one of a user's tokens.
+    pub async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, AppError> {
+        let result =
+            sqlx::query("DELETE FROM personal_access_tokens WHERE id = $1 AND user_id = $2")
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
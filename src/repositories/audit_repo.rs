@@ -0,0 +1,87 @@
+//! Audit log repository for database operations.
+
+use sqlx::types::Json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{AuditEntry, AuditEntryWithActor};
+
+/// Repository for the append-only audit log.
+#[derive(Clone)]
+pub struct AuditRepository {
+    pool: PgPool,
+}
+
+impl AuditRepository {
+    /// Create a new audit repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Append an entry to the audit log.
+    pub async fn record(
+        &self,
+        actor_id: Option<Uuid>,
+        action: &str,
+        target_type: &str,
+        target_id: Option<Uuid>,
+        details: serde_json::Value,
+    ) -> Result<AuditEntry, AppError> {
+        let entry = sqlx::query_as::<_, AuditEntry>(
+            r#"
+            INSERT INTO audit_log (actor_id, action, target_type, target_id, details)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, actor_id, action, target_type, target_id, details, created_at
+            "#,
+        )
+        .bind(actor_id)
+        .bind(action)
+        .bind(target_type)
+        .bind(target_id)
+        .bind(Json(details))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// List entries, newest first, optionally made by one actor.
+    pub async fn find_all(
+        &self,
+        actor_id: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditEntryWithActor>, AppError> {
+        let entries = sqlx::query_as::<_, AuditEntryWithActor>(
+            r#"
+            SELECT a.id, a.actor_id, a.action, a.target_type, a.target_id, a.details,
+                   a.created_at, u.name AS actor_name
+            FROM audit_log a
+            LEFT JOIN users u ON u.id = a.actor_id
+            WHERE $1::uuid IS NULL OR a.actor_id = $1
+            ORDER BY a.created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(actor_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Count entries, optionally made by one actor.
+    pub async fn count(&self, actor_id: Option<Uuid>) -> Result<i64, AppError> {
+        let result: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM audit_log WHERE $1::uuid IS NULL OR actor_id = $1",
+        )
+        .bind(actor_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result.0)
+    }
+}
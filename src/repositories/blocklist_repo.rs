@@ -0,0 +1,168 @@
+//! Blocklist repository for database operations.
+
+use std::net::IpAddr;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{BlocklistEntry, BlocklistKind};
+
+/// Repository for blocklist entry database operations.
+#[derive(Clone)]
+pub struct BlocklistRepository {
+    pool: PgPool,
+}
+
+impl BlocklistRepository {
+    /// Create a new blocklist repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a blocklist entry by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<BlocklistEntry>, AppError> {
+        let entry = sqlx::query_as::<_, BlocklistEntry>(
+            r#"
+            SELECT id, kind, value, reason, created_by, created_at
+            FROM blocklist_entries
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Find the entry of `kind` with exactly `value`.
+    pub async fn find_by_value(
+        &self,
+        kind: BlocklistKind,
+        value: &str,
+    ) -> Result<Option<BlocklistEntry>, AppError> {
+        let entry = sqlx::query_as::<_, BlocklistEntry>(
+            r#"
+            SELECT id, kind, value, reason, created_by, created_at
+            FROM blocklist_entries
+            WHERE kind = $1 AND value = $2
+            "#,
+        )
+        .bind(kind)
+        .bind(value)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Find entries, optionally of one kind, newest first.
+    pub async fn find_all(
+        &self,
+        kind: Option<BlocklistKind>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BlocklistEntry>, AppError> {
+        let entries = sqlx::query_as::<_, BlocklistEntry>(
+            r#"
+            SELECT id, kind, value, reason, created_by, created_at
+            FROM blocklist_entries
+            WHERE $1::blocklist_kind IS NULL OR kind = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(kind)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Count entries, optionally of one kind.
+    pub async fn count(&self, kind: Option<BlocklistKind>) -> Result<i64, AppError> {
+        let result: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM blocklist_entries WHERE $1::blocklist_kind IS NULL OR kind = $1",
+        )
+        .bind(kind)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
+    /// Find an IP entry whose address or range contains `ip`.
+    pub async fn find_ip_match(&self, ip: IpAddr) -> Result<Option<BlocklistEntry>, AppError> {
+        let entry = sqlx::query_as::<_, BlocklistEntry>(
+            r#"
+            SELECT id, kind, value, reason, created_by, created_at
+            FROM blocklist_entries
+            WHERE kind = 'ip' AND value::inet >>= $1::inet
+            LIMIT 1
+            "#,
+        )
+        .bind(ip.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Find an email domain entry equal to one of `domains`.
+    pub async fn find_email_domain_match(
+        &self,
+        domains: &[String],
+    ) -> Result<Option<BlocklistEntry>, AppError> {
+        let entry = sqlx::query_as::<_, BlocklistEntry>(
+            r#"
+            SELECT id, kind, value, reason, created_by, created_at
+            FROM blocklist_entries
+            WHERE kind = 'email_domain' AND value = ANY($1)
+            LIMIT 1
+            "#,
+        )
+        .bind(domains)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Add a blocklist entry.
+    pub async fn create(
+        &self,
+        kind: BlocklistKind,
+        value: &str,
+        reason: Option<&str>,
+        created_by: Uuid,
+    ) -> Result<BlocklistEntry, AppError> {
+        let entry = sqlx::query_as::<_, BlocklistEntry>(
+            r#"
+            INSERT INTO blocklist_entries (kind, value, reason, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, kind, value, reason, created_by, created_at
+            "#,
+        )
+        .bind(kind)
+        .bind(value)
+        .bind(reason)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Remove a blocklist entry.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM blocklist_entries WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -18,15 +18,18 @@ impl CategoryRepository {
         Self { pool }
     }
 
-    /// Find a category by ID.
-    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Category>, AppError> {
+    /// Find a category by ID within a site.
+    pub async fn find_by_id(&self, site_id: Uuid, id: Uuid) -> Result<Option<Category>, AppError> {
         let category = sqlx::query_as::<_, Category>(
             r#"
-            SELECT id, name, slug, description, created_at, updated_at
-            FROM categories
-            WHERE id = $1
+            SELECT c.id, c.name, c.slug, c.description, parent.id as parent_id,
+                c.created_at, c.updated_at
+            FROM categories c
+            LEFT JOIN categories parent ON c.parent_id = parent.id AND parent.deleted_at IS NULL
+            WHERE c.site_id = $1 AND c.id = $2 AND c.deleted_at IS NULL
             "#,
         )
+        .bind(site_id)
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
@@ -34,15 +37,45 @@ impl CategoryRepository {
         Ok(category)
     }
 
-    /// Find a category by slug.
-    pub async fn find_by_slug(&self, slug: &str) -> Result<Option<Category>, AppError> {
+    /// Find a site's categories among `ids`.
+    pub async fn find_by_ids(
+        &self,
+        site_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<Category>, AppError> {
+        let categories = sqlx::query_as::<_, Category>(
+            r#"
+            SELECT c.id, c.name, c.slug, c.description, parent.id as parent_id,
+                c.created_at, c.updated_at
+            FROM categories c
+            LEFT JOIN categories parent ON c.parent_id = parent.id AND parent.deleted_at IS NULL
+            WHERE c.site_id = $1 AND c.id = ANY($2) AND c.deleted_at IS NULL
+            "#,
+        )
+        .bind(site_id)
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(categories)
+    }
+
+    /// Find a category by slug within a site.
+    pub async fn find_by_slug(
+        &self,
+        site_id: Uuid,
+        slug: &str,
+    ) -> Result<Option<Category>, AppError> {
         let category = sqlx::query_as::<_, Category>(
             r#"
-            SELECT id, name, slug, description, created_at, updated_at
-            FROM categories
-            WHERE slug = $1
+            SELECT c.id, c.name, c.slug, c.description, parent.id as parent_id,
+                c.created_at, c.updated_at
+            FROM categories c
+            LEFT JOIN categories parent ON c.parent_id = parent.id AND parent.deleted_at IS NULL
+            WHERE c.site_id = $1 AND c.slug = $2 AND c.deleted_at IS NULL
             "#,
         )
+        .bind(site_id)
         .bind(slug)
         .fetch_optional(&self.pool)
         .await?;
@@ -50,55 +83,71 @@ impl CategoryRepository {
         Ok(category)
     }
 
-    /// Find all categories with post counts.
-    pub async fn find_all_with_count(&self) -> Result<Vec<CategoryWithCount>, AppError> {
+    /// Find a site's categories with counts of their published, public posts.
+    ///
+    /// Drafts and members-only or private posts are left out of the counts,
+    /// which are shown to (and cached for) everyone.
+    pub async fn find_all_with_count(
+        &self,
+        site_id: Uuid,
+    ) -> Result<Vec<CategoryWithCount>, AppError> {
         let categories = sqlx::query_as::<_, CategoryWithCount>(
             r#"
             SELECT 
-                c.id, c.name, c.slug, c.description,
+                c.id, c.name, c.slug, c.description, parent.id as parent_id,
                 COUNT(p.id) as post_count, c.created_at
             FROM categories c
-            LEFT JOIN posts p ON c.id = p.category_id AND p.status = 'published'
-            GROUP BY c.id
+            LEFT JOIN categories parent ON c.parent_id = parent.id AND parent.deleted_at IS NULL
+            LEFT JOIN posts p ON c.id = p.category_id
+                AND p.status = 'published' AND p.visibility = 'public'
+            WHERE c.site_id = $1 AND c.deleted_at IS NULL
+            GROUP BY c.id, parent.id
             ORDER BY c.name ASC
             "#,
         )
+        .bind(site_id)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(categories)
     }
 
-    /// Create a new category.
+    /// Create a new category on a site.
     pub async fn create(
         &self,
+        site_id: Uuid,
         name: &str,
         slug: &str,
         description: Option<&str>,
+        parent_id: Option<Uuid>,
     ) -> Result<Category, AppError> {
         let category = sqlx::query_as::<_, Category>(
             r#"
-            INSERT INTO categories (name, slug, description)
-            VALUES ($1, $2, $3)
-            RETURNING id, name, slug, description, created_at, updated_at
+            INSERT INTO categories (site_id, name, slug, description, parent_id)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, name, slug, description, parent_id, created_at, updated_at
             "#,
         )
+        .bind(site_id)
         .bind(name)
         .bind(slug)
         .bind(description)
+        .bind(parent_id)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(category)
     }
 
-    /// Update a category.
+    /// Update a category. `parent_id` is left alone when `None`; `Some(None)`
+    /// moves the category to the top level.
     pub async fn update(
         &self,
         id: Uuid,
         name: Option<&str>,
         slug: Option<&str>,
         description: Option<&str>,
+        parent_id: Option<Option<Uuid>>,
     ) -> Result<Category, AppError> {
         let category = sqlx::query_as::<_, Category>(
             r#"
@@ -106,28 +155,135 @@ impl CategoryRepository {
             SET 
                 name = COALESCE($2, name),
                 slug = COALESCE($3, slug),
-                description = COALESCE($4, description)
-            WHERE id = $1
-            RETURNING id, name, slug, description, created_at, updated_at
+                description = COALESCE($4, description),
+                parent_id = CASE WHEN $6 THEN $5 ELSE parent_id END
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING id, name, slug, description, parent_id, created_at, updated_at
             "#,
         )
         .bind(id)
         .bind(name)
         .bind(slug)
         .bind(description)
+        .bind(parent_id.flatten())
+        .bind(parent_id.is_some())
         .fetch_one(&self.pool)
         .await?;
 
         Ok(category)
     }
 
-    /// Delete a category by ID.
-    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
-        let result = sqlx::query("DELETE FROM categories WHERE id = $1")
+    /// Whether `ancestor_id` is `id` itself or one of its ancestors.
+    pub async fn is_self_or_ancestor(&self, ancestor_id: Uuid, id: Uuid) -> Result<bool, AppError> {
+        let (found,): (bool,) = sqlx::query_as(
+            r#"
+            WITH RECURSIVE chain AS (
+                SELECT id, parent_id FROM categories WHERE id = $2
+                UNION
+                SELECT c.id, c.parent_id FROM categories c JOIN chain ON c.id = chain.parent_id
+            )
+            SELECT EXISTS (SELECT 1 FROM chain WHERE id = $1)
+            "#,
+        )
+        .bind(ancestor_id)
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(found)
+    }
+
+    /// Find a site's soft-deleted categories, most recently deleted first.
+    pub async fn find_deleted(&self, site_id: Uuid) -> Result<Vec<Category>, AppError> {
+        let categories = sqlx::query_as::<_, Category>(
+            r#"
+            SELECT id, name, slug, description, parent_id, created_at, updated_at, deleted_at
+            FROM categories
+            WHERE site_id = $1 AND deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC
+            "#,
+        )
+        .bind(site_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(categories)
+    }
+
+    /// Find a soft-deleted category by ID within a site.
+    pub async fn find_deleted_by_id(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<Category>, AppError> {
+        let category = sqlx::query_as::<_, Category>(
+            r#"
+            SELECT id, name, slug, description, parent_id, created_at, updated_at, deleted_at
+            FROM categories
+            WHERE site_id = $1 AND id = $2 AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(site_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(category)
+    }
+
+    /// Count posts in a category, whatever their status.
+    pub async fn count_posts(&self, id: Uuid) -> Result<i64, AppError> {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM posts WHERE category_id = $1")
             .bind(id)
-            .execute(&self.pool)
+            .fetch_one(&self.pool)
             .await?;
 
+        Ok(count)
+    }
+
+    /// Soft delete a category by ID; its posts keep pointing to it.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE categories SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Restore a soft-deleted category.
+    pub async fn restore(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE categories SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
         Ok(result.rows_affected() > 0)
     }
+
+    /// Move all posts from `source_id` to `target_id` and delete the source,
+    /// in one transaction. Returns the number of posts moved.
+    pub async fn merge(&self, source_id: Uuid, target_id: Uuid) -> Result<i64, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let moved = sqlx::query("UPDATE posts SET category_id = $2 WHERE category_id = $1")
+            .bind(source_id)
+            .bind(target_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        sqlx::query("DELETE FROM categories WHERE id = $1")
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(moved as i64)
+    }
 }
@@ -0,0 +1,209 @@
+//! Changelog repository for database operations.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::ChangelogEntry;
+
+/// Repository for changelog entry database operations.
+#[derive(Clone)]
+pub struct ChangelogRepository {
+    pool: PgPool,
+}
+
+impl ChangelogRepository {
+    /// Create a new changelog repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a changelog entry by ID within a site.
+    pub async fn find_by_id(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<ChangelogEntry>, AppError> {
+        let entry = sqlx::query_as::<_, ChangelogEntry>(
+            r#"
+            SELECT id, site_id, title, body, version, published_at, created_by, created_at, updated_at
+            FROM changelog_entries
+            WHERE site_id = $1 AND id = $2
+            "#,
+        )
+        .bind(site_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Find a site's entries, drafts first and then newest published first.
+    ///
+    /// With `published_before`, only entries published by then are returned.
+    pub async fn find_all(
+        &self,
+        site_id: Uuid,
+        published_before: Option<DateTime<Utc>>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ChangelogEntry>, AppError> {
+        let entries = sqlx::query_as::<_, ChangelogEntry>(
+            r#"
+            SELECT id, site_id, title, body, version, published_at, created_by, created_at, updated_at
+            FROM changelog_entries
+            WHERE site_id = $1 AND ($2::timestamptz IS NULL OR published_at <= $2)
+            ORDER BY published_at DESC NULLS FIRST, created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(site_id)
+        .bind(published_before)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Count a site's entries, optionally only those published by `published_before`.
+    pub async fn count(
+        &self,
+        site_id: Uuid,
+        published_before: Option<DateTime<Utc>>,
+    ) -> Result<i64, AppError> {
+        let result: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM changelog_entries
+            WHERE site_id = $1 AND ($2::timestamptz IS NULL OR published_at <= $2)
+            "#,
+        )
+        .bind(site_id)
+        .bind(published_before)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
+    /// When a site's entries published by `now` last changed, by edit or by
+    /// publication; `None` without any.
+    pub async fn last_published_change(
+        &self,
+        site_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        let result: (Option<DateTime<Utc>>,) = sqlx::query_as(
+            r#"
+            SELECT MAX(GREATEST(updated_at, published_at)) FROM changelog_entries
+            WHERE site_id = $1 AND published_at <= $2
+            "#,
+        )
+        .bind(site_id)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
+    /// When a published entry last left a site's feed, by deletion or unpublishing.
+    pub async fn feed_changed_at(&self, site_id: Uuid) -> Result<Option<DateTime<Utc>>, AppError> {
+        let result: Option<(DateTime<Utc>,)> =
+            sqlx::query_as("SELECT changed_at FROM changelog_feeds WHERE site_id = $1")
+                .bind(site_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(result.map(|(changed_at,)| changed_at))
+    }
+
+    /// Record that a published entry just left a site's feed.
+    pub async fn touch_feed(&self, site_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO changelog_feeds (site_id) VALUES ($1)
+            ON CONFLICT (site_id) DO UPDATE SET changed_at = NOW()
+            "#,
+        )
+        .bind(site_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Create a changelog entry on a site.
+    pub async fn create(
+        &self,
+        site_id: Uuid,
+        title: &str,
+        body: &str,
+        version: Option<&str>,
+        published_at: Option<DateTime<Utc>>,
+        created_by: Uuid,
+    ) -> Result<ChangelogEntry, AppError> {
+        let entry = sqlx::query_as::<_, ChangelogEntry>(
+            r#"
+            INSERT INTO changelog_entries (site_id, title, body, version, published_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, site_id, title, body, version, published_at, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(site_id)
+        .bind(title)
+        .bind(body)
+        .bind(version)
+        .bind(published_at)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Update a changelog entry.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        title: Option<&str>,
+        body: Option<&str>,
+        version: Option<&str>,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<ChangelogEntry, AppError> {
+        let entry = sqlx::query_as::<_, ChangelogEntry>(
+            r#"
+            UPDATE changelog_entries
+            SET
+                title = COALESCE($2, title),
+                body = COALESCE($3, body),
+                version = COALESCE($4, version),
+                published_at = COALESCE($5, published_at)
+            WHERE id = $1
+            RETURNING id, site_id, title, body, version, published_at, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(title)
+        .bind(body)
+        .bind(version)
+        .bind(published_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Delete a changelog entry.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM changelog_entries WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,149 @@
+//! Failed job repository for the dead-letter queue.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{FailedJob, Job, JobKind};
+
+/// Repository for dead-lettered job database operations.
+#[derive(Clone)]
+pub struct FailedJobRepository {
+    pool: PgPool,
+}
+
+impl FailedJobRepository {
+    /// Create a new failed job repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a failed job by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<FailedJob>, AppError> {
+        let job = sqlx::query_as::<_, FailedJob>(
+            r#"
+            SELECT id, kind, payload, error, attempts, failed_at, created_at
+            FROM failed_jobs
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// Find failed jobs, optionally of one kind, most recently failed first.
+    pub async fn find_all(
+        &self,
+        kind: Option<JobKind>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<FailedJob>, AppError> {
+        let jobs = sqlx::query_as::<_, FailedJob>(
+            r#"
+            SELECT id, kind, payload, error, attempts, failed_at, created_at
+            FROM failed_jobs
+            WHERE $1::job_kind IS NULL OR kind = $1
+            ORDER BY failed_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(kind)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    /// Count failed jobs, optionally of one kind.
+    pub async fn count(&self, kind: Option<JobKind>) -> Result<i64, AppError> {
+        let result: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM failed_jobs WHERE $1::job_kind IS NULL OR kind = $1",
+        )
+        .bind(kind)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
+    /// IDs of the `limit` jobs that failed longest ago.
+    pub async fn find_oldest_ids(&self, limit: i64) -> Result<Vec<Uuid>, AppError> {
+        let ids: Vec<(Uuid,)> =
+            sqlx::query_as("SELECT id FROM failed_jobs ORDER BY failed_at LIMIT $1")
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Dead-letter a job that failed with `error` after `attempts` attempts.
+    pub async fn create(
+        &self,
+        job: &Job,
+        error: &str,
+        attempts: i32,
+    ) -> Result<FailedJob, AppError> {
+        let payload = serde_json::to_value(job)
+            .map_err(|e| AppError::InternalError(format!("Failed to encode job: {}", e)))?;
+        let failed = sqlx::query_as::<_, FailedJob>(
+            r#"
+            INSERT INTO failed_jobs (kind, payload, error, attempts)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, kind, payload, error, attempts, failed_at, created_at
+            "#,
+        )
+        .bind(job.kind())
+        .bind(payload)
+        .bind(error)
+        .bind(attempts)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(failed)
+    }
+
+    /// Record another failed attempt at a dead-lettered job.
+    pub async fn record_attempt(&self, id: Uuid, error: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE failed_jobs
+            SET error = $2, attempts = attempts + 1, failed_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delete a failed job.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM failed_jobs WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete jobs that last failed before `before` (all of them when unset).
+    pub async fn delete_older_than(&self, before: Option<DateTime<Utc>>) -> Result<u64, AppError> {
+        let result =
+            sqlx::query("DELETE FROM failed_jobs WHERE $1::timestamptz IS NULL OR failed_at < $1")
+                .bind(before)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+}
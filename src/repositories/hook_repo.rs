@@ -0,0 +1,165 @@
+//! Hook repository for stored inbound webhook deliveries.
+
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{HookDelivery, HookIntegration, HookStatus};
+
+/// Repository for webhook delivery database operations.
+#[derive(Clone)]
+pub struct HookRepository {
+    pool: PgPool,
+}
+
+impl HookRepository {
+    /// Create a new hook repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a delivery by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<HookDelivery>, AppError> {
+        let delivery = sqlx::query_as::<_, HookDelivery>(
+            r#"
+            SELECT id, integration, delivery_id, event_type, headers, payload, status, error,
+                   attempts, received_at, processed_at
+            FROM hook_deliveries
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    /// Find deliveries, optionally of one integration and status, newest first.
+    pub async fn find_all(
+        &self,
+        integration: Option<HookIntegration>,
+        status: Option<HookStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<HookDelivery>, AppError> {
+        let deliveries = sqlx::query_as::<_, HookDelivery>(
+            r#"
+            SELECT id, integration, delivery_id, event_type, headers, payload, status, error,
+                   attempts, received_at, processed_at
+            FROM hook_deliveries
+            WHERE ($1::hook_integration IS NULL OR integration = $1)
+              AND ($2::hook_status IS NULL OR status = $2)
+            ORDER BY received_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(integration)
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    /// Count deliveries, optionally of one integration and status.
+    pub async fn count(
+        &self,
+        integration: Option<HookIntegration>,
+        status: Option<HookStatus>,
+    ) -> Result<i64, AppError> {
+        let result: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM hook_deliveries
+            WHERE ($1::hook_integration IS NULL OR integration = $1)
+              AND ($2::hook_status IS NULL OR status = $2)
+            "#,
+        )
+        .bind(integration)
+        .bind(status)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
+    /// Find a delivery by the ID its sender gave it.
+    pub async fn find_by_delivery_id(
+        &self,
+        integration: HookIntegration,
+        delivery_id: &str,
+    ) -> Result<Option<HookDelivery>, AppError> {
+        let delivery = sqlx::query_as::<_, HookDelivery>(
+            r#"
+            SELECT id, integration, delivery_id, event_type, headers, payload, status, error,
+                   attempts, received_at, processed_at
+            FROM hook_deliveries
+            WHERE integration = $1 AND delivery_id = $2
+            "#,
+        )
+        .bind(integration)
+        .bind(delivery_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    /// Store a verified delivery before it is handled; `None` when one with
+    /// the same delivery ID is already stored.
+    pub async fn create(
+        &self,
+        integration: HookIntegration,
+        delivery_id: Option<&str>,
+        event_type: Option<&str>,
+        headers: Value,
+        payload: &str,
+    ) -> Result<Option<HookDelivery>, AppError> {
+        let delivery = sqlx::query_as::<_, HookDelivery>(
+            r#"
+            INSERT INTO hook_deliveries (integration, delivery_id, event_type, headers, payload)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (integration, delivery_id) WHERE delivery_id IS NOT NULL DO NOTHING
+            RETURNING id, integration, delivery_id, event_type, headers, payload, status, error,
+                      attempts, received_at, processed_at
+            "#,
+        )
+        .bind(integration)
+        .bind(delivery_id)
+        .bind(event_type)
+        .bind(headers)
+        .bind(payload)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    /// Record the outcome of handling a delivery.
+    pub async fn record_attempt(
+        &self,
+        id: Uuid,
+        status: HookStatus,
+        error: Option<&str>,
+    ) -> Result<HookDelivery, AppError> {
+        let delivery = sqlx::query_as::<_, HookDelivery>(
+            r#"
+            UPDATE hook_deliveries
+            SET status = $2, error = $3, attempts = attempts + 1, processed_at = NOW()
+            WHERE id = $1
+            RETURNING id, integration, delivery_id, event_type, headers, payload, status, error,
+                      attempts, received_at, processed_at
+            "#,
+        )
+        .bind(id)
+        .bind(status)
+        .bind(error)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(delivery)
+    }
+}
@@ -0,0 +1,96 @@
+//! Login event repository for database operations.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{LoginEvent, LoginOrigin};
+
+/// Repository for login history database operations.
+#[derive(Clone)]
+pub struct LoginEventRepository {
+    pool: PgPool,
+}
+
+impl LoginEventRepository {
+    /// Create a new login event repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Compare a login with the user's previous ones.
+    pub async fn origin(
+        &self,
+        user_id: Uuid,
+        user_agent: Option<&str>,
+        country: Option<&str>,
+    ) -> Result<LoginOrigin, AppError> {
+        let origin = sqlx::query_as::<_, LoginOrigin>(
+            r#"
+            SELECT
+                COUNT(*) > 0 as has_history,
+                COALESCE(BOOL_OR(user_agent IS NOT DISTINCT FROM $2), false) as known_device,
+                COALESCE(BOOL_OR(country = $3), false) as known_country
+            FROM login_events
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(user_agent)
+        .bind(country)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(origin)
+    }
+
+    /// Record a successful login.
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        ip_address: &str,
+        user_agent: Option<&str>,
+        country: Option<&str>,
+        region: Option<&str>,
+    ) -> Result<LoginEvent, AppError> {
+        let event = sqlx::query_as::<_, LoginEvent>(
+            r#"
+            INSERT INTO login_events (user_id, ip_address, user_agent, country, region)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, ip_address, user_agent, country, region, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(ip_address)
+        .bind(user_agent)
+        .bind(country)
+        .bind(region)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Get a user's most recent logins, newest first.
+    pub async fn find_recent_by_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<LoginEvent>, AppError> {
+        let events = sqlx::query_as::<_, LoginEvent>(
+            r#"
+            SELECT id, user_id, ip_address, user_agent, country, region, created_at
+            FROM login_events
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+}
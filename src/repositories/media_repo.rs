@@ -0,0 +1,294 @@
+//! Media repository for database operations.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{Media, MediaPostReference, MediaUsageFilter, PostAttachment};
+
+/// Repository for media database operations.
+#[derive(Clone)]
+pub struct MediaRepository {
+    pool: PgPool,
+}
+
+impl MediaRepository {
+    /// Create a new media repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a media item by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Media>, AppError> {
+        let media = sqlx::query_as::<_, Media>(
+            r#"
+            SELECT id, uploader_id, filename, storage_key, content_type, size_bytes, alt_text,
+                   download_count, created_at
+            FROM media
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Find media, optionally filtered by content type and usage, newest first.
+    pub async fn find_all(
+        &self,
+        content_type: Option<&str>,
+        usage: Option<MediaUsageFilter>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Media>, AppError> {
+        // Posts reference files by their URL, which contains the storage key, or attach them
+        let media = sqlx::query_as::<_, Media>(
+            r#"
+            SELECT m.id, m.uploader_id, m.filename, m.storage_key, m.content_type, m.size_bytes,
+                   m.alt_text, m.download_count, m.created_at
+            FROM media m
+            WHERE ($1::text IS NULL OR m.content_type = $1 OR m.content_type LIKE $1 || '/%')
+              AND ($2::boolean IS NULL OR $2 = (
+                  EXISTS (SELECT 1 FROM posts p WHERE strpos(p.content, m.storage_key) > 0)
+                  OR EXISTS (SELECT 1 FROM post_attachments a WHERE a.media_id = m.id)
+                  OR EXISTS (SELECT 1 FROM users u WHERE u.avatar_media_id = m.id)
+              ))
+            ORDER BY m.created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(content_type)
+        .bind(usage.map(|usage| usage == MediaUsageFilter::Used))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Count media matching the same filters as [`Self::find_all`].
+    pub async fn count(
+        &self,
+        content_type: Option<&str>,
+        usage: Option<MediaUsageFilter>,
+    ) -> Result<i64, AppError> {
+        let result: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM media m
+            WHERE ($1::text IS NULL OR m.content_type = $1 OR m.content_type LIKE $1 || '/%')
+              AND ($2::boolean IS NULL OR $2 = (
+                  EXISTS (SELECT 1 FROM posts p WHERE strpos(p.content, m.storage_key) > 0)
+                  OR EXISTS (SELECT 1 FROM post_attachments a WHERE a.media_id = m.id)
+                  OR EXISTS (SELECT 1 FROM users u WHERE u.avatar_media_id = m.id)
+              ))
+            "#,
+        )
+        .bind(content_type)
+        .bind(usage.map(|usage| usage == MediaUsageFilter::Used))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
+    /// Posts on any site that attach a media item or link to it in their content, newest first.
+    pub async fn find_referencing_posts(
+        &self,
+        media: &Media,
+    ) -> Result<Vec<MediaPostReference>, AppError> {
+        let posts = sqlx::query_as::<_, MediaPostReference>(
+            r#"
+            SELECT id, site_id, title, slug, status
+            FROM posts
+            WHERE strpos(content, $1) > 0
+               OR id IN (SELECT post_id FROM post_attachments WHERE media_id = $2)
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(&media.storage_key)
+        .bind(media.id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
+    /// Count users with a media item as their avatar.
+    pub async fn count_avatar_users(&self, id: Uuid) -> Result<i64, AppError> {
+        let result: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM users WHERE avatar_media_id = $1")
+                .bind(id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(result.0)
+    }
+
+    /// Find the files attached to a post, in order.
+    pub async fn find_by_post(&self, post_id: Uuid) -> Result<Vec<Media>, AppError> {
+        let media = sqlx::query_as::<_, Media>(
+            r#"
+            SELECT m.id, m.uploader_id, m.filename, m.storage_key, m.content_type, m.size_bytes,
+                   m.alt_text, m.download_count, m.created_at
+            FROM media m
+            JOIN post_attachments a ON a.media_id = m.id
+            WHERE a.post_id = $1
+            ORDER BY a.position
+            "#,
+        )
+        .bind(post_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Find the files attached to each of `post_ids`, in order per post.
+    pub async fn find_by_posts(&self, post_ids: &[Uuid]) -> Result<Vec<PostAttachment>, AppError> {
+        let media = sqlx::query_as::<_, PostAttachment>(
+            r#"
+            SELECT a.post_id, m.id, m.uploader_id, m.filename, m.storage_key, m.content_type,
+                   m.size_bytes, m.alt_text, m.download_count, m.created_at
+            FROM media m
+            JOIN post_attachments a ON a.media_id = m.id
+            WHERE a.post_id = ANY($1)
+            ORDER BY a.post_id, a.position
+            "#,
+        )
+        .bind(post_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Find the media items among `ids` that exist.
+    pub async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Media>, AppError> {
+        let media = sqlx::query_as::<_, Media>(
+            r#"
+            SELECT id, uploader_id, filename, storage_key, content_type, size_bytes, alt_text,
+                   download_count, created_at
+            FROM media
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Replace the files attached to a post, keeping the given order.
+    pub async fn set_post_attachments(
+        &self,
+        post_id: Uuid,
+        media_ids: &[Uuid],
+    ) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM post_attachments WHERE post_id = $1")
+            .bind(post_id)
+            .execute(&mut *tx)
+            .await?;
+        for (position, media_id) in media_ids.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO post_attachments (post_id, media_id, position) VALUES ($1, $2, $3)",
+            )
+            .bind(post_id)
+            .bind(media_id)
+            .bind(position as i32)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Count a download and return the media item, if it exists.
+    pub async fn record_download(&self, id: Uuid) -> Result<Option<Media>, AppError> {
+        let media = sqlx::query_as::<_, Media>(
+            r#"
+            UPDATE media
+            SET download_count = download_count + 1
+            WHERE id = $1
+            RETURNING id, uploader_id, filename, storage_key, content_type, size_bytes, alt_text,
+                      download_count, created_at
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Record a stored file.
+    pub async fn create(
+        &self,
+        uploader_id: Uuid,
+        filename: &str,
+        storage_key: &str,
+        content_type: &str,
+        size_bytes: i64,
+    ) -> Result<Media, AppError> {
+        let media = sqlx::query_as::<_, Media>(
+            r#"
+            INSERT INTO media (uploader_id, filename, storage_key, content_type, size_bytes)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, uploader_id, filename, storage_key, content_type, size_bytes, alt_text,
+                      download_count, created_at
+            "#,
+        )
+        .bind(uploader_id)
+        .bind(filename)
+        .bind(storage_key)
+        .bind(content_type)
+        .bind(size_bytes)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Update a media item's filename and alt text (`Some("")` clears the alt text).
+    pub async fn update(
+        &self,
+        id: Uuid,
+        filename: Option<&str>,
+        alt_text: Option<&str>,
+    ) -> Result<Media, AppError> {
+        let media = sqlx::query_as::<_, Media>(
+            r#"
+            UPDATE media
+            SET
+                filename = COALESCE($2, filename),
+                alt_text = CASE WHEN $3::text IS NULL THEN alt_text ELSE NULLIF($3, '') END
+            WHERE id = $1
+            RETURNING id, uploader_id, filename, storage_key, content_type, size_bytes, alt_text,
+                      download_count, created_at
+            "#,
+        )
+        .bind(id)
+        .bind(filename)
+        .bind(alt_text)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(media)
+    }
+
+    /// Delete a media record by ID.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM media WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,13 +1,55 @@
 //! Repository modules for data access.
 
+pub mod access_token_repo;
+pub mod audit_repo;
+pub mod blocklist_repo;
 pub mod category_repo;
+pub mod changelog_repo;
+pub mod failed_job_repo;
+pub mod hook_repo;
+pub mod login_event_repo;
+pub mod media_repo;
+pub mod notification_repo;
+pub mod outbox_repo;
+pub mod poll_repo;
+pub mod post_note_repo;
 pub mod post_repo;
+pub mod post_template_repo;
+pub mod revision_repo;
 pub mod role_repo;
+pub mod search_repo;
+pub mod settings_repo;
+pub mod site_repo;
+pub mod sync_repo;
 pub mod tag_repo;
+pub mod taxonomy_repo;
+pub mod title_test_repo;
 pub mod user_repo;
+pub mod webhook_attempt_repo;
 
+pub use access_token_repo::AccessTokenRepository;
+pub use audit_repo::AuditRepository;
+pub use blocklist_repo::BlocklistRepository;
 pub use category_repo::CategoryRepository;
+pub use changelog_repo::ChangelogRepository;
+pub use failed_job_repo::FailedJobRepository;
+pub use hook_repo::HookRepository;
+pub use login_event_repo::LoginEventRepository;
+pub use media_repo::MediaRepository;
+pub use notification_repo::NotificationRepository;
+pub use outbox_repo::OutboxRepository;
+pub use poll_repo::PollRepository;
+pub use post_note_repo::PostNoteRepository;
 pub use post_repo::PostRepository;
+pub use post_template_repo::PostTemplateRepository;
+pub use revision_repo::RevisionRepository;
 pub use role_repo::RoleRepository;
+pub use search_repo::SearchRepository;
+pub use settings_repo::SettingsRepository;
+pub use site_repo::SiteRepository;
+pub use sync_repo::SyncRepository;
 pub use tag_repo::TagRepository;
+pub use taxonomy_repo::TaxonomyRepository;
+pub use title_test_repo::TitleTestRepository;
 pub use user_repo::UserRepository;
+pub use webhook_attempt_repo::WebhookAttemptRepository;
@@ -0,0 +1,87 @@
+//! Notification preference repository for database operations.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{DigestRecipient, NotificationPreferences};
+
+/// Repository for notification preference database operations.
+#[derive(Clone)]
+pub struct NotificationRepository {
+    pool: PgPool,
+}
+
+impl NotificationRepository {
+    /// Create a new notification repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a user's stored preferences.
+    pub async fn find(&self, user_id: Uuid) -> Result<Option<NotificationPreferences>, AppError> {
+        let preferences = sqlx::query_as::<_, NotificationPreferences>(
+            r#"
+            SELECT email_on_comment, email_on_mention, email_on_review, weekly_digest, security_alerts
+            FROM notification_preferences
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(preferences)
+    }
+
+    /// Find active users who asked for the weekly digest.
+    pub async fn find_digest_recipients(&self) -> Result<Vec<DigestRecipient>, AppError> {
+        let recipients = sqlx::query_as::<_, DigestRecipient>(
+            r#"
+            SELECT u.id, u.email, u.name
+            FROM notification_preferences n
+            JOIN users u ON n.user_id = u.id
+            WHERE n.weekly_digest
+              AND u.is_active AND u.approved_at IS NOT NULL AND u.deleted_at IS NULL
+            ORDER BY u.id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(recipients)
+    }
+
+    /// Store a user's preferences.
+    pub async fn upsert(
+        &self,
+        user_id: Uuid,
+        preferences: &NotificationPreferences,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO notification_preferences
+                (user_id, email_on_comment, email_on_mention, email_on_review, weekly_digest,
+                 security_alerts)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id) DO UPDATE
+            SET email_on_comment = EXCLUDED.email_on_comment,
+                email_on_mention = EXCLUDED.email_on_mention,
+                email_on_review = EXCLUDED.email_on_review,
+                weekly_digest = EXCLUDED.weekly_digest,
+                security_alerts = EXCLUDED.security_alerts,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(preferences.email_on_comment)
+        .bind(preferences.email_on_mention)
+        .bind(preferences.email_on_review)
+        .bind(preferences.weekly_digest)
+        .bind(preferences.security_alerts)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+//! Outbox repository for domain events awaiting delivery.
+//!
+//! Other repositories call [`OutboxRepository::insert`] inside the transaction
+//! making a change, so its event is stored if and only if the change commits.
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{NewEvent, OutboxEvent};
+
+/// Repository for outbox database operations.
+#[derive(Clone)]
+pub struct OutboxRepository {
+    pool: PgPool,
+}
+
+impl OutboxRepository {
+    /// Create a new outbox repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Write an event on `conn`, normally an open transaction.
+    pub async fn insert(conn: &mut PgConnection, event: &NewEvent) -> Result<(), AppError> {
+        sqlx::query("INSERT INTO outbox (event_type, payload) VALUES ($1, $2)")
+            .bind(event.event_type)
+            .bind(&event.payload)
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Take up to `limit` due events, oldest first, hiding them from other
+    /// relays for `lease_seconds` while they are delivered.
+    pub async fn claim(
+        &self,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<OutboxEvent>, AppError> {
+        let mut events = sqlx::query_as::<_, OutboxEvent>(
+            r#"
+            WITH due AS (
+                SELECT id FROM outbox
+                WHERE next_attempt_at <= NOW()
+                ORDER BY created_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE outbox o
+            SET next_attempt_at = NOW() + make_interval(secs => $2)
+            FROM due
+            WHERE o.id = due.id
+            RETURNING o.id, o.event_type, o.payload, o.attempts, o.last_error, o.next_attempt_at, o.created_at
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // UPDATE ... RETURNING does not keep the subquery's order
+        events.sort_by_key(|event| event.created_at);
+        Ok(events)
+    }
+
+    /// Record a failed delivery and when to try again.
+    pub async fn reschedule(
+        &self,
+        id: Uuid,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE outbox
+            SET attempts = attempts + 1, last_error = $2, next_attempt_at = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .bind(next_attempt_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove a delivered or dead-lettered event.
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM outbox WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+//! Poll repository for database operations.
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{Poll, PollOption};
+
+/// Repository for poll database operations.
+#[derive(Clone)]
+pub struct PollRepository {
+    pool: PgPool,
+}
+
+impl PollRepository {
+    /// Create a new poll repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a poll by ID within a site.
+    pub async fn find_by_id(&self, site_id: Uuid, id: Uuid) -> Result<Option<Poll>, AppError> {
+        let poll = sqlx::query_as::<_, Poll>(
+            r#"
+            SELECT id, site_id, question, opens_at, closes_at, created_by, created_at, updated_at
+            FROM polls
+            WHERE site_id = $1 AND id = $2
+            "#,
+        )
+        .bind(site_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(poll)
+    }
+
+    /// Find a site's polls among `ids`, in no particular order.
+    pub async fn find_by_ids(&self, site_id: Uuid, ids: &[Uuid]) -> Result<Vec<Poll>, AppError> {
+        let polls = sqlx::query_as::<_, Poll>(
+            r#"
+            SELECT id, site_id, question, opens_at, closes_at, created_by, created_at, updated_at
+            FROM polls
+            WHERE site_id = $1 AND id = ANY($2)
+            "#,
+        )
+        .bind(site_id)
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(polls)
+    }
+
+    /// Find a site's polls, newest first.
+    pub async fn find_all(
+        &self,
+        site_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Poll>, AppError> {
+        let polls = sqlx::query_as::<_, Poll>(
+            r#"
+            SELECT id, site_id, question, opens_at, closes_at, created_by, created_at, updated_at
+            FROM polls
+            WHERE site_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(site_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(polls)
+    }
+
+    /// Count a site's polls.
+    pub async fn count(&self, site_id: Uuid) -> Result<i64, AppError> {
+        let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM polls WHERE site_id = $1")
+            .bind(site_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(result.0)
+    }
+
+    /// Find the options of several polls, ordered by poll and position.
+    pub async fn find_options(&self, poll_ids: &[Uuid]) -> Result<Vec<PollOption>, AppError> {
+        let options = sqlx::query_as::<_, PollOption>(
+            r#"
+            SELECT id, poll_id, label, position, votes
+            FROM poll_options
+            WHERE poll_id = ANY($1)
+            ORDER BY poll_id, position
+            "#,
+        )
+        .bind(poll_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(options)
+    }
+
+    /// Create a poll with its options on a site.
+    pub async fn create(
+        &self,
+        site_id: Uuid,
+        question: &str,
+        opens_at: Option<DateTime<Utc>>,
+        closes_at: Option<DateTime<Utc>>,
+        created_by: Uuid,
+        options: &[String],
+    ) -> Result<Poll, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let poll = sqlx::query_as::<_, Poll>(
+            r#"
+            INSERT INTO polls (site_id, question, opens_at, closes_at, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, site_id, question, opens_at, closes_at, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(site_id)
+        .bind(question)
+        .bind(opens_at)
+        .bind(closes_at)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+        Self::insert_options(&mut tx, poll.id, options).await?;
+
+        tx.commit().await?;
+
+        Ok(poll)
+    }
+
+    /// Update a poll.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        question: Option<&str>,
+        opens_at: Option<DateTime<Utc>>,
+        closes_at: Option<DateTime<Utc>>,
+    ) -> Result<Poll, AppError> {
+        let poll = sqlx::query_as::<_, Poll>(
+            r#"
+            UPDATE polls
+            SET
+                question = COALESCE($2, question),
+                opens_at = COALESCE($3, opens_at),
+                closes_at = COALESCE($4, closes_at)
+            WHERE id = $1
+            RETURNING id, site_id, question, opens_at, closes_at, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(question)
+        .bind(opens_at)
+        .bind(closes_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(poll)
+    }
+
+    /// Replace a poll's options, unless it already has votes; returns whether they were replaced.
+    pub async fn replace_options(&self, id: Uuid, options: &[String]) -> Result<bool, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Lock the poll so a vote cannot land between the check and the delete
+        sqlx::query("SELECT id FROM polls WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let votes: (i64,) = sqlx::query_as(
+            "SELECT COALESCE(SUM(votes), 0)::BIGINT FROM poll_options WHERE poll_id = $1",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if votes.0 > 0 {
+            return Ok(false);
+        }
+
+        sqlx::query("DELETE FROM poll_options WHERE poll_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        Self::insert_options(&mut tx, id, options).await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Count a vote for an option of a poll; returns false if the option is not part of it.
+    pub async fn record_vote(&self, poll_id: Uuid, option_id: Uuid) -> Result<bool, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT id FROM polls WHERE id = $1 FOR SHARE")
+            .bind(poll_id)
+            .execute(&mut *tx)
+            .await?;
+        let result =
+            sqlx::query("UPDATE poll_options SET votes = votes + 1 WHERE id = $1 AND poll_id = $2")
+                .bind(option_id)
+                .bind(poll_id)
+                .execute(&mut *tx)
+                .await?;
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a poll and its options.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM polls WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Private helper methods
+
+    async fn insert_options(
+        tx: &mut Transaction<'_, Postgres>,
+        poll_id: Uuid,
+        options: &[String],
+    ) -> Result<(), AppError> {
+        let positions: Vec<i32> = (0..options.len() as i32).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO poll_options (poll_id, label, position)
+            SELECT $1, label, position FROM UNNEST($2::text[], $3::int[]) AS input(label, position)
+            "#,
+        )
+        .bind(poll_id)
+        .bind(options)
+        .bind(&positions)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+//! Post note repository for database operations.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{MentionedUser, PostNote};
+
+/// Repository for internal post note database operations.
+#[derive(Clone)]
+pub struct PostNoteRepository {
+    pool: PgPool,
+}
+
+impl PostNoteRepository {
+    /// Create a new post note repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a post's notes, oldest first.
+    pub async fn find_by_post(&self, post_id: Uuid) -> Result<Vec<PostNote>, AppError> {
+        let notes = sqlx::query_as::<_, PostNote>(
+            r#"
+            SELECT n.id, n.post_id, n.author_id, u.name AS author_name, n.body, n.created_at
+            FROM post_notes n
+            LEFT JOIN users u ON n.author_id = u.id
+            WHERE n.post_id = $1
+            ORDER BY n.created_at, n.id
+            "#,
+        )
+        .bind(post_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notes)
+    }
+
+    /// Find one of a post's notes.
+    pub async fn find_by_id(&self, post_id: Uuid, id: Uuid) -> Result<Option<PostNote>, AppError> {
+        let note = sqlx::query_as::<_, PostNote>(
+            r#"
+            SELECT n.id, n.post_id, n.author_id, u.name AS author_name, n.body, n.created_at
+            FROM post_notes n
+            LEFT JOIN users u ON n.author_id = u.id
+            WHERE n.post_id = $1 AND n.id = $2
+            "#,
+        )
+        .bind(post_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(note)
+    }
+
+    /// Add a note to a post.
+    pub async fn create(
+        &self,
+        post_id: Uuid,
+        author_id: Uuid,
+        body: &str,
+    ) -> Result<PostNote, AppError> {
+        let note = sqlx::query_as::<_, PostNote>(
+            r#"
+            WITH n AS (
+                INSERT INTO post_notes (post_id, author_id, body)
+                VALUES ($1, $2, $3)
+                RETURNING id, post_id, author_id, body, created_at
+            )
+            SELECT n.id, n.post_id, n.author_id, u.name AS author_name, n.body, n.created_at
+            FROM n
+            LEFT JOIN users u ON n.author_id = u.id
+            "#,
+        )
+        .bind(post_id)
+        .bind(author_id)
+        .bind(body)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(note)
+    }
+
+    /// Delete a note.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM post_notes WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Find the active users with these emails who may edit a post by
+    /// `post_author_id`: its author, admins and holders of `posts:update_any`.
+    pub async fn find_mentionable(
+        &self,
+        emails: &[String],
+        post_author_id: Uuid,
+    ) -> Result<Vec<MentionedUser>, AppError> {
+        let users = sqlx::query_as::<_, MentionedUser>(
+            r#"
+            SELECT u.id, u.email, u.name
+            FROM users u
+            JOIN roles r ON u.role_id = r.id
+            WHERE LOWER(u.email) = ANY($1)
+              AND u.is_active AND u.approved_at IS NOT NULL AND u.deleted_at IS NULL
+              AND (
+                  u.id = $2
+                  OR r.slug = 'admin'
+                  OR EXISTS (
+                      SELECT 1
+                      FROM role_permissions rp
+                      JOIN permissions p ON rp.permission_id = p.id
+                      WHERE rp.role_id = u.role_id AND p.name = 'posts:update_any'
+                  )
+              )
+            "#,
+        )
+        .bind(emails)
+        .bind(post_author_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+}
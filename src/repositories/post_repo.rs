@@ -1,10 +1,16 @@
 //! Post repository for database operations.
 
-use sqlx::PgPool;
+use chrono::NaiveDate;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::{Post, PostListItem, PostStatus};
+use crate::models::{
+    AuthorWeekStats, DailyViews, DigestTopPost, LocationViews, NewEvent, Post, PostListItem,
+    PostReview, PostSearchDocument, PostStatus, PostTags, PostViewer, PostVisibility, TrendingPost,
+};
+use crate::pkg::{GeoLocation, ViewSource};
+use crate::repositories::{OutboxRepository, TagRepository};
 
 /// Repository for post database operations.
 #[derive(Clone)]
@@ -18,15 +24,16 @@ impl PostRepository {
         Self { pool }
     }
 
-    /// Find a post by ID.
-    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Post>, AppError> {
+    /// Find a post by ID within a site.
+    pub async fn find_by_id(&self, site_id: Uuid, id: Uuid) -> Result<Option<Post>, AppError> {
         let post = sqlx::query_as::<_, Post>(
             r#"
-            SELECT id, title, slug, content, excerpt, status, author_id, category_id, created_at, updated_at
+            SELECT id, site_id, title, slug, content, excerpt, status, visibility, author_id, category_id, published_at, created_at, updated_at
             FROM posts
-            WHERE id = $1
+            WHERE site_id = $1 AND id = $2
             "#,
         )
+        .bind(site_id)
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
@@ -34,15 +41,56 @@ impl PostRepository {
         Ok(post)
     }
 
-    /// Find a post by slug.
-    pub async fn find_by_slug(&self, slug: &str) -> Result<Option<Post>, AppError> {
+    /// Find a site's posts among `ids`.
+    pub async fn find_by_ids(&self, site_id: Uuid, ids: &[Uuid]) -> Result<Vec<Post>, AppError> {
+        let posts = sqlx::query_as::<_, Post>(
+            r#"
+            SELECT id, site_id, title, slug, content, excerpt, status, visibility, author_id, category_id, published_at, created_at, updated_at
+            FROM posts
+            WHERE site_id = $1 AND id = ANY($2)
+            "#,
+        )
+        .bind(site_id)
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
+    /// Find a site's posts with one of `ids` or `slugs`.
+    pub async fn find_by_ids_or_slugs(
+        &self,
+        site_id: Uuid,
+        ids: &[Uuid],
+        slugs: &[String],
+    ) -> Result<Vec<Post>, AppError> {
+        let posts = sqlx::query_as::<_, Post>(
+            r#"
+            SELECT id, site_id, title, slug, content, excerpt, status, visibility, author_id, category_id, published_at, created_at, updated_at
+            FROM posts
+            WHERE site_id = $1 AND (id = ANY($2) OR slug = ANY($3))
+            "#,
+        )
+        .bind(site_id)
+        .bind(ids)
+        .bind(slugs)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
+    /// Find a post by slug within a site.
+    pub async fn find_by_slug(&self, site_id: Uuid, slug: &str) -> Result<Option<Post>, AppError> {
         let post = sqlx::query_as::<_, Post>(
             r#"
-            SELECT id, title, slug, content, excerpt, status, author_id, category_id, created_at, updated_at
+            SELECT id, site_id, title, slug, content, excerpt, status, visibility, author_id, category_id, published_at, created_at, updated_at
             FROM posts
-            WHERE slug = $1
+            WHERE site_id = $1 AND slug = $2
             "#,
         )
+        .bind(site_id)
         .bind(slug)
         .fetch_optional(&self.pool)
         .await?;
@@ -50,9 +98,11 @@ impl PostRepository {
         Ok(post)
     }
 
-    /// Find all posts with pagination and optional filters.
+    /// Find a site's posts visible to `viewer`, with pagination and optional filters.
     pub async fn find_all(
         &self,
+        site_id: Uuid,
+        viewer: PostViewer,
         status: Option<PostStatus>,
         category_id: Option<Uuid>,
         limit: i64,
@@ -61,30 +111,94 @@ impl PostRepository {
         let posts = sqlx::query_as::<_, PostListItem>(
             r#"
             SELECT 
-                p.id, p.title, p.slug, p.excerpt, p.status, p.author_id,
-                u.name as author_name, p.category_id, c.name as category_name, p.created_at
+                p.id, p.title, p.slug, p.excerpt, p.status, p.visibility, p.author_id,
+                u.name as author_name, c.id as category_id, c.name as category_name, p.published_at, p.created_at
             FROM posts p
             LEFT JOIN users u ON p.author_id = u.id
-            LEFT JOIN categories c ON p.category_id = c.id
-            WHERE ($1::post_status IS NULL OR p.status = $1)
-              AND ($2::uuid IS NULL OR p.category_id = $2)
-            ORDER BY p.created_at DESC
-            LIMIT $3 OFFSET $4
+            LEFT JOIN categories c ON p.category_id = c.id AND c.deleted_at IS NULL
+            WHERE p.site_id = $1
+              AND ($2::post_status IS NULL OR p.status = $2)
+              AND ($3::uuid IS NULL OR p.category_id = $3)
+              AND ($6 OR p.visibility = 'public'
+                   OR (p.visibility = 'members' AND $7::uuid IS NOT NULL)
+                   OR (p.visibility = 'private' AND p.author_id = $7))
+            ORDER BY COALESCE(p.published_at, p.created_at) DESC
+            LIMIT $4 OFFSET $5
             "#,
         )
+        .bind(site_id)
         .bind(status)
         .bind(category_id)
         .bind(limit)
         .bind(offset)
+        .bind(viewer.is_admin())
+        .bind(viewer.member_id())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
+    /// Find a site's list items by ID, in no particular order.
+    pub async fn find_list_items_by_ids(
+        &self,
+        site_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<PostListItem>, AppError> {
+        let posts = sqlx::query_as::<_, PostListItem>(
+            r#"
+            SELECT 
+                p.id, p.title, p.slug, p.excerpt, p.status, p.visibility, p.author_id,
+                u.name as author_name, c.id as category_id, c.name as category_name, p.published_at, p.created_at
+            FROM posts p
+            LEFT JOIN users u ON p.author_id = u.id
+            LEFT JOIN categories c ON p.category_id = c.id AND c.deleted_at IS NULL
+            WHERE p.site_id = $1 AND p.id = ANY($2)
+            "#,
+        )
+        .bind(site_id)
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
+    /// Find a site's public, published posts first published in `year`
+    /// (UTC), oldest first.
+    pub async fn find_published_in_year(
+        &self,
+        site_id: Uuid,
+        year: i32,
+    ) -> Result<Vec<PostListItem>, AppError> {
+        let posts = sqlx::query_as::<_, PostListItem>(
+            r#"
+            SELECT
+                p.id, p.title, p.slug, p.excerpt, p.status, p.visibility, p.author_id,
+                u.name as author_name, c.id as category_id, c.name as category_name, p.published_at, p.created_at
+            FROM posts p
+            LEFT JOIN users u ON p.author_id = u.id
+            LEFT JOIN categories c ON p.category_id = c.id AND c.deleted_at IS NULL
+            WHERE p.site_id = $1
+              AND p.status = 'published'
+              AND p.visibility = 'public'
+              AND EXTRACT(YEAR FROM COALESCE(p.published_at, p.created_at) AT TIME ZONE 'UTC') = $2
+            ORDER BY COALESCE(p.published_at, p.created_at)
+            "#,
+        )
+        .bind(site_id)
+        .bind(year)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(posts)
     }
 
-    /// Count posts with optional filters.
+    /// Count a site's posts visible to `viewer`, with optional filters.
     pub async fn count(
         &self,
+        site_id: Uuid,
+        viewer: PostViewer,
         status: Option<PostStatus>,
         category_id: Option<Uuid>,
     ) -> Result<i64, AppError> {
@@ -92,51 +206,77 @@ impl PostRepository {
             r#"
             SELECT COUNT(*) as count
             FROM posts
-            WHERE ($1::post_status IS NULL OR status = $1)
-              AND ($2::uuid IS NULL OR category_id = $2)
+            WHERE site_id = $1
+              AND ($2::post_status IS NULL OR status = $2)
+              AND ($3::uuid IS NULL OR category_id = $3)
+              AND ($4 OR visibility = 'public'
+                   OR (visibility = 'members' AND $5::uuid IS NOT NULL)
+                   OR (visibility = 'private' AND author_id = $5))
             "#,
         )
+        .bind(site_id)
         .bind(status)
         .bind(category_id)
+        .bind(viewer.is_admin())
+        .bind(viewer.member_id())
         .fetch_one(&self.pool)
         .await?;
 
         Ok(result.0)
     }
 
-    /// Create a new post.
+    /// Create a new post on a site, with a `post.published` event if it is published.
+    ///
+    /// `tags` are created and linked in the same transaction.
     #[allow(clippy::too_many_arguments)]
     pub async fn create(
         &self,
+        site_id: Uuid,
         title: &str,
         slug: &str,
         content: &str,
         excerpt: Option<&str>,
         status: PostStatus,
+        visibility: PostVisibility,
         author_id: Uuid,
         category_id: Option<Uuid>,
+        tags: Option<&PostTags>,
     ) -> Result<Post, AppError> {
+        let mut tx = self.pool.begin().await?;
         let post = sqlx::query_as::<_, Post>(
             r#"
-            INSERT INTO posts (title, slug, content, excerpt, status, author_id, category_id)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id, title, slug, content, excerpt, status, author_id, category_id, created_at, updated_at
+            INSERT INTO posts (site_id, title, slug, content, excerpt, status, visibility, author_id, category_id, published_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, CASE WHEN $6 = 'published'::post_status THEN NOW() END)
+            RETURNING id, site_id, title, slug, content, excerpt, status, visibility, author_id, category_id, published_at, created_at, updated_at
             "#,
         )
+        .bind(site_id)
         .bind(title)
         .bind(slug)
         .bind(content)
         .bind(excerpt)
         .bind(status)
+        .bind(visibility)
         .bind(author_id)
         .bind(category_id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
+        if let Some(tags) = tags {
+            Self::replace_tags(&mut tx, &post, tags).await?;
+        }
+        if post.status == PostStatus::Published {
+            OutboxRepository::insert(&mut tx, &NewEvent::post_published(&post)).await?;
+        }
+        tx.commit().await?;
+
         Ok(post)
     }
 
-    /// Update a post.
+    /// Update a post, with a `post.published` event if this publishes it.
+    ///
+    /// `published_at` is set the first time the post is published and kept after
+    /// that. `tags`, when given, replace the post's tags in the same transaction.
     #[allow(clippy::too_many_arguments)]
     pub async fn update(
         &self,
@@ -146,8 +286,17 @@ impl PostRepository {
         content: Option<&str>,
         excerpt: Option<&str>,
         status: Option<PostStatus>,
+        visibility: Option<PostVisibility>,
         category_id: Option<Uuid>,
+        tags: Option<&PostTags>,
     ) -> Result<Post, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let previous: PostStatus =
+            sqlx::query_scalar("SELECT status FROM posts WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+
         let post = sqlx::query_as::<_, Post>(
             r#"
             UPDATE posts
@@ -157,9 +306,14 @@ impl PostRepository {
                 content = COALESCE($4, content),
                 excerpt = COALESCE($5, excerpt),
                 status = COALESCE($6, status),
-                category_id = COALESCE($7, category_id)
+                visibility = COALESCE($7, visibility),
+                category_id = COALESCE($8, category_id),
+                published_at = CASE
+                    WHEN published_at IS NULL AND COALESCE($6, status) = 'published' THEN NOW()
+                    ELSE published_at
+                END
             WHERE id = $1
-            RETURNING id, title, slug, content, excerpt, status, author_id, category_id, created_at, updated_at
+            RETURNING id, site_id, title, slug, content, excerpt, status, visibility, author_id, category_id, published_at, created_at, updated_at
             "#,
         )
         .bind(id)
@@ -168,10 +322,19 @@ impl PostRepository {
         .bind(content)
         .bind(excerpt)
         .bind(status)
+        .bind(visibility)
         .bind(category_id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
+        if let Some(tags) = tags {
+            Self::replace_tags(&mut tx, &post, tags).await?;
+        }
+        if post.status == PostStatus::Published && previous != PostStatus::Published {
+            OutboxRepository::insert(&mut tx, &NewEvent::post_published(&post)).await?;
+        }
+        tx.commit().await?;
+
         Ok(post)
     }
 
@@ -185,6 +348,236 @@ impl PostRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Count one view of a post for today, as human, bot or referrer spam traffic.
+    pub async fn record_view(&self, post_id: Uuid, source: ViewSource) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO post_view_counts (post_id, day, views, bot_views, spam_views)
+            VALUES ($1, CURRENT_DATE, $2, $3, $4)
+            ON CONFLICT (post_id, day) DO UPDATE SET
+                views = post_view_counts.views + EXCLUDED.views,
+                bot_views = post_view_counts.bot_views + EXCLUDED.bot_views,
+                spam_views = post_view_counts.spam_views + EXCLUDED.spam_views
+            "#,
+        )
+        .bind(post_id)
+        .bind(i64::from(source == ViewSource::Human))
+        .bind(i64::from(source == ViewSource::Bot))
+        .bind(i64::from(source == ViewSource::SpamReferrer))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count one human view of a post for today from `location`.
+    pub async fn record_view_location(
+        &self,
+        post_id: Uuid,
+        location: &GeoLocation,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO post_view_locations (post_id, day, country, region, views)
+            VALUES ($1, CURRENT_DATE, $2, $3, 1)
+            ON CONFLICT (post_id, day, country, region) DO UPDATE SET
+                views = post_view_locations.views + 1
+            "#,
+        )
+        .bind(post_id)
+        .bind(&location.country)
+        .bind(location.region.as_deref().unwrap_or_default())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Human views of a site's posts by country and region over the last
+    /// `days` days, most viewed first.
+    pub async fn find_view_locations(
+        &self,
+        site_id: Uuid,
+        days: i32,
+    ) -> Result<Vec<LocationViews>, AppError> {
+        let locations = sqlx::query_as::<_, LocationViews>(
+            r#"
+            SELECT v.country, NULLIF(v.region, '') AS region, SUM(v.views)::bigint AS views
+            FROM post_view_locations v
+            JOIN posts p ON v.post_id = p.id
+            WHERE p.site_id = $1 AND v.day > CURRENT_DATE - $2
+            GROUP BY v.country, v.region
+            ORDER BY views DESC, v.country, v.region
+            "#,
+        )
+        .bind(site_id)
+        .bind(days)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(locations)
+    }
+
+    /// Total views of a site's posts per day over the last `days` days, oldest first.
+    pub async fn find_daily_views(
+        &self,
+        site_id: Uuid,
+        days: i32,
+    ) -> Result<Vec<DailyViews>, AppError> {
+        let days = sqlx::query_as::<_, DailyViews>(
+            r#"
+            SELECT v.day, SUM(v.views)::bigint AS views, SUM(v.bot_views)::bigint AS bot_views,
+                   SUM(v.spam_views)::bigint AS spam_views
+            FROM post_view_counts v
+            JOIN posts p ON v.post_id = p.id
+            WHERE p.site_id = $1 AND v.day > CURRENT_DATE - $2
+            GROUP BY v.day
+            ORDER BY v.day
+            "#,
+        )
+        .bind(site_id)
+        .bind(days)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(days)
+    }
+
+    /// Find search documents for the given posts on any site.
+    pub async fn find_search_documents(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<Vec<PostSearchDocument>, AppError> {
+        let documents = sqlx::query_as::<_, PostSearchDocument>(
+            r#"
+            SELECT id, site_id, title, slug, excerpt, content, status, visibility, author_id, category_id, published_at, created_at
+            FROM posts
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Page through search documents for every post, ordered by ID.
+    pub async fn find_search_documents_after(
+        &self,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<PostSearchDocument>, AppError> {
+        let documents = sqlx::query_as::<_, PostSearchDocument>(
+            r#"
+            SELECT id, site_id, title, slug, excerpt, content, status, visibility, author_id, category_id, published_at, created_at
+            FROM posts
+            WHERE $1::uuid IS NULL OR id > $1
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Find a site's published posts with the most views over the last `days` days.
+    pub async fn find_trending(
+        &self,
+        site_id: Uuid,
+        days: i32,
+        limit: i64,
+    ) -> Result<Vec<TrendingPost>, AppError> {
+        let posts = sqlx::query_as::<_, TrendingPost>(
+            r#"
+            SELECT p.id, p.title, p.slug, p.excerpt, SUM(v.views)::bigint as views
+            FROM post_view_counts v
+            JOIN posts p ON v.post_id = p.id
+            WHERE p.site_id = $1
+              AND p.status = 'published'
+              AND p.visibility = 'public'
+              AND v.day > CURRENT_DATE - $2
+            GROUP BY p.id
+            ORDER BY views DESC, COALESCE(p.published_at, p.created_at) DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(site_id)
+        .bind(days)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
+    /// Human views of an author's posts from `start` to `end` (inclusive) and
+    /// the seven days before, and how many posts they published meanwhile.
+    pub async fn find_author_week_stats(
+        &self,
+        author_id: Uuid,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<AuthorWeekStats, AppError> {
+        let stats = sqlx::query_as::<_, AuthorWeekStats>(
+            r#"
+            SELECT
+                COALESCE(SUM(v.views) FILTER (WHERE v.day >= $2), 0)::bigint AS views,
+                COALESCE(SUM(v.views) FILTER (WHERE v.day < $2), 0)::bigint AS previous_views,
+                (
+                    SELECT COUNT(*)
+                    FROM posts
+                    WHERE author_id = $1 AND status = 'published'
+                      AND published_at::date BETWEEN $2 AND $3
+                ) AS posts_published
+            FROM post_view_counts v
+            JOIN posts p ON v.post_id = p.id
+            WHERE p.author_id = $1 AND v.day BETWEEN $2 - 7 AND $3
+            "#,
+        )
+        .bind(author_id)
+        .bind(start)
+        .bind(end)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// An author's most viewed posts from `start` to `end` (inclusive).
+    pub async fn find_author_top_posts(
+        &self,
+        author_id: Uuid,
+        start: NaiveDate,
+        end: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<DigestTopPost>, AppError> {
+        let posts = sqlx::query_as::<_, DigestTopPost>(
+            r#"
+            SELECT p.title, SUM(v.views)::bigint AS views
+            FROM post_view_counts v
+            JOIN posts p ON v.post_id = p.id
+            WHERE p.author_id = $1 AND v.day BETWEEN $2 AND $3
+            GROUP BY p.id
+            HAVING SUM(v.views) > 0
+            ORDER BY views DESC, p.title
+            LIMIT $4
+            "#,
+        )
+        .bind(author_id)
+        .bind(start)
+        .bind(end)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
     /// Get tags for a post.
     pub async fn get_tag_ids(&self, post_id: Uuid) -> Result<Vec<Uuid>, AppError> {
         let tags: Vec<(Uuid,)> = sqlx::query_as("SELECT tag_id FROM post_tags WHERE post_id = $1")
@@ -195,20 +588,126 @@ impl PostRepository {
         Ok(tags.into_iter().map(|(id,)| id).collect())
     }
 
-    /// Set tags for a post (replaces existing).
-    pub async fn set_tags(&self, post_id: Uuid, tag_ids: &[Uuid]) -> Result<(), AppError> {
+    /// Find which of a category and tags a post refers to are not on the
+    /// site (or are deleted), in one query.
+    ///
+    /// Returns whether the category is missing and the missing tag IDs.
+    pub async fn find_missing_taxonomy(
+        &self,
+        site_id: Uuid,
+        category_id: Option<Uuid>,
+        tag_ids: &[Uuid],
+    ) -> Result<(bool, Vec<Uuid>), AppError> {
+        let missing: Vec<(String, Uuid)> = sqlx::query_as(
+            r#"
+            SELECT 'category', c.id
+            FROM (SELECT $2::uuid AS id) c
+            WHERE c.id IS NOT NULL AND NOT EXISTS (
+                SELECT 1 FROM categories
+                WHERE id = c.id AND site_id = $1 AND deleted_at IS NULL
+            )
+            UNION ALL
+            SELECT 'tag', t.id
+            FROM UNNEST($3::uuid[]) AS t(id)
+            WHERE NOT EXISTS (
+                SELECT 1 FROM tags
+                WHERE id = t.id AND site_id = $1 AND deleted_at IS NULL
+            )
+            "#,
+        )
+        .bind(site_id)
+        .bind(category_id)
+        .bind(tag_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let category_missing = missing.iter().any(|(kind, _)| kind == "category");
+        let tag_ids = missing
+            .into_iter()
+            .filter(|(kind, _)| kind == "tag")
+            .map(|(_, id)| id)
+            .collect();
+        Ok((category_missing, tag_ids))
+    }
+
+    /// Record a reviewer's decision on a post.
+    pub async fn create_review(
+        &self,
+        post_id: Uuid,
+        reviewer_id: Uuid,
+        approved: bool,
+        note: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO post_reviews (post_id, reviewer_id, approved, note)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(post_id)
+        .bind(reviewer_id)
+        .bind(approved)
+        .bind(note)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Reviews of a post, newest first.
+    pub async fn find_reviews(&self, post_id: Uuid) -> Result<Vec<PostReview>, AppError> {
+        let reviews = sqlx::query_as::<_, PostReview>(
+            r#"
+            SELECT r.id, r.post_id, r.reviewer_id, u.name AS reviewer_name, r.approved, r.note, r.created_at
+            FROM post_reviews r
+            LEFT JOIN users u ON r.reviewer_id = u.id
+            WHERE r.post_id = $1
+            ORDER BY r.created_at DESC
+            "#,
+        )
+        .bind(post_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(reviews)
+    }
+
+    /// Replace a post's tags on `conn`, creating tags given by name that do
+    /// not exist yet.
+    ///
+    /// Links to deleted tags are kept, so restoring a tag puts it back on the post.
+    async fn replace_tags(
+        conn: &mut PgConnection,
+        post: &Post,
+        tags: &PostTags,
+    ) -> Result<(), AppError> {
+        let mut tag_ids = tags.ids.clone();
+        if !tags.names.is_empty() {
+            let created =
+                TagRepository::find_or_create(&mut *conn, post.site_id, &tags.names).await?;
+            tag_ids.extend(created.into_iter().map(|tag| tag.id));
+        }
+        tag_ids.sort_unstable();
+        tag_ids.dedup();
+
         // Delete existing tags
-        sqlx::query("DELETE FROM post_tags WHERE post_id = $1")
-            .bind(post_id)
-            .execute(&self.pool)
-            .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM post_tags
+            WHERE post_id = $1
+              AND tag_id IN (SELECT id FROM tags WHERE deleted_at IS NULL)
+            "#,
+        )
+        .bind(post.id)
+        .execute(&mut *conn)
+        .await?;
 
         // Insert new tags
-        for tag_id in tag_ids {
+        for tag_id in &tag_ids {
             sqlx::query("INSERT INTO post_tags (post_id, tag_id) VALUES ($1, $2)")
-                .bind(post_id)
+                .bind(post.id)
                 .bind(tag_id)
-                .execute(&self.pool)
+                .execute(&mut *conn)
                 .await?;
         }
 
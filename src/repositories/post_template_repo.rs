@@ -0,0 +1,135 @@
+//! Post template repository for database operations.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::PostTemplate;
+
+/// Repository for post template database operations.
+#[derive(Clone)]
+pub struct PostTemplateRepository {
+    pool: PgPool,
+}
+
+impl PostTemplateRepository {
+    /// Create a new post template repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a site's templates by name.
+    pub async fn find_all(&self, site_id: Uuid) -> Result<Vec<PostTemplate>, AppError> {
+        let templates = sqlx::query_as::<_, PostTemplate>(
+            r#"
+            SELECT id, site_id, name, title_pattern, content, category_id, tag_ids, created_by, created_at, updated_at
+            FROM post_templates
+            WHERE site_id = $1
+            ORDER BY name
+            "#,
+        )
+        .bind(site_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(templates)
+    }
+
+    /// Find a template by ID on a site.
+    pub async fn find_by_id(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<PostTemplate>, AppError> {
+        let template = sqlx::query_as::<_, PostTemplate>(
+            r#"
+            SELECT id, site_id, name, title_pattern, content, category_id, tag_ids, created_by, created_at, updated_at
+            FROM post_templates
+            WHERE site_id = $1 AND id = $2
+            "#,
+        )
+        .bind(site_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(template)
+    }
+
+    /// Create a template on a site.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        &self,
+        site_id: Uuid,
+        name: &str,
+        title_pattern: &str,
+        content: &str,
+        category_id: Option<Uuid>,
+        tag_ids: &[Uuid],
+        created_by: Uuid,
+    ) -> Result<PostTemplate, AppError> {
+        let template = sqlx::query_as::<_, PostTemplate>(
+            r#"
+            INSERT INTO post_templates (site_id, name, title_pattern, content, category_id, tag_ids, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, site_id, name, title_pattern, content, category_id, tag_ids, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(site_id)
+        .bind(name)
+        .bind(title_pattern)
+        .bind(content)
+        .bind(category_id)
+        .bind(tag_ids)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(template)
+    }
+
+    /// Update a template.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        name: Option<&str>,
+        title_pattern: Option<&str>,
+        content: Option<&str>,
+        category_id: Option<Uuid>,
+        tag_ids: Option<&[Uuid]>,
+    ) -> Result<PostTemplate, AppError> {
+        let template = sqlx::query_as::<_, PostTemplate>(
+            r#"
+            UPDATE post_templates
+            SET
+                name = COALESCE($2, name),
+                title_pattern = COALESCE($3, title_pattern),
+                content = COALESCE($4, content),
+                category_id = COALESCE($5, category_id),
+                tag_ids = COALESCE($6, tag_ids)
+            WHERE id = $1
+            RETURNING id, site_id, name, title_pattern, content, category_id, tag_ids, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .bind(title_pattern)
+        .bind(content)
+        .bind(category_id)
+        .bind(tag_ids)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(template)
+    }
+
+    /// Delete a template.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM post_templates WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,80 @@
+//! Post revision repository for database operations.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{Post, PostRevision, RevisionSummary};
+
+/// Repository for post revision database operations.
+#[derive(Clone)]
+pub struct RevisionRepository {
+    pool: PgPool,
+}
+
+impl RevisionRepository {
+    /// Create a new revision repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record the current text of a post.
+    pub async fn create(&self, post: &Post, edited_by: Option<Uuid>) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO post_revisions (post_id, title, excerpt, content, edited_by)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(post.id)
+        .bind(&post.title)
+        .bind(&post.excerpt)
+        .bind(&post.content)
+        .bind(edited_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// A post's revisions, newest first.
+    pub async fn find_by_post(&self, post_id: Uuid) -> Result<Vec<RevisionSummary>, AppError> {
+        let revisions = sqlx::query_as::<_, RevisionSummary>(
+            r#"
+            SELECT r.id, r.title, r.edited_by, u.name AS editor_name, r.created_at
+            FROM post_revisions r
+            LEFT JOIN users u ON r.edited_by = u.id
+            WHERE r.post_id = $1
+            ORDER BY r.created_at DESC, r.id
+            "#,
+        )
+        .bind(post_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(revisions)
+    }
+
+    /// Find one of a post's revisions.
+    pub async fn find_by_id(
+        &self,
+        post_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<PostRevision>, AppError> {
+        let revision = sqlx::query_as::<_, PostRevision>(
+            r#"
+            SELECT r.id, r.post_id, r.title, r.excerpt, r.content, r.edited_by,
+                   u.name AS editor_name, r.created_at
+            FROM post_revisions r
+            LEFT JOIN users u ON r.edited_by = u.id
+            WHERE r.post_id = $1 AND r.id = $2
+            "#,
+        )
+        .bind(post_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(revision)
+    }
+}
@@ -4,7 +4,7 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::Role;
+use crate::models::{Role, DELETED_USER_ID};
 
 /// Repository for role database operations.
 #[derive(Clone)]
@@ -132,6 +132,53 @@ impl RoleRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Count the users holding a role, soft-deleted ones included.
+    pub async fn count_users(&self, id: Uuid) -> Result<i64, AppError> {
+        let count: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM users WHERE role_id = $1 AND id <> $2")
+                .bind(id)
+                .bind(DELETED_USER_ID)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(count.0)
+    }
+
+    /// Count the users holding each role that has any.
+    pub async fn count_users_by_role(&self) -> Result<Vec<(Uuid, i64)>, AppError> {
+        let counts =
+            sqlx::query_as("SELECT role_id, COUNT(*) FROM users WHERE id <> $1 GROUP BY role_id")
+                .bind(DELETED_USER_ID)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(counts)
+    }
+
+    /// Move a role's users to `reassign_to` and soft delete the role, in one
+    /// transaction. Returns the moved users.
+    pub async fn delete_reassigning(
+        &self,
+        id: Uuid,
+        reassign_to: Uuid,
+    ) -> Result<Vec<Uuid>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let moved: Vec<(Uuid,)> =
+            sqlx::query_as("UPDATE users SET role_id = $2 WHERE role_id = $1 RETURNING id")
+                .bind(id)
+                .bind(reassign_to)
+                .fetch_all(&mut *tx)
+                .await?;
+        sqlx::query("UPDATE roles SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(moved.into_iter().map(|(id,)| id).collect())
+    }
+
     /// Get permissions for a role.
     pub async fn get_permissions(&self, role_id: Uuid) -> Result<Vec<String>, AppError> {
         let permissions: Vec<(String,)> = sqlx::query_as(
@@ -149,6 +196,16 @@ impl RoleRepository {
         Ok(permissions.into_iter().map(|(name,)| name).collect())
     }
 
+    /// Get the names of all defined permissions.
+    pub async fn get_all_permission_names(&self) -> Result<Vec<String>, AppError> {
+        let permissions: Vec<(String,)> =
+            sqlx::query_as("SELECT name FROM permissions ORDER BY name")
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(permissions.into_iter().map(|(name,)| name).collect())
+    }
+
     /// Assign a permission to a role.
     pub async fn assign_permission(
         &self,
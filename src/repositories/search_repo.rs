@@ -0,0 +1,72 @@
+//! Search repository for cross-entity admin search.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{AdminSearchRow, DELETED_USER_ID};
+
+/// Repository for queries spanning several entity tables.
+#[derive(Clone)]
+pub struct SearchRepository {
+    pool: PgPool,
+}
+
+impl SearchRepository {
+    /// Create a new search repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a site's posts, categories and tags plus active users matching
+    /// `contains_pattern`, at most `limit` per type.
+    ///
+    /// Matches on `prefix_pattern` rank first, then alphabetically.
+    pub async fn admin_search(
+        &self,
+        site_id: Uuid,
+        contains_pattern: &str,
+        prefix_pattern: &str,
+        limit: i64,
+    ) -> Result<Vec<AdminSearchRow>, AppError> {
+        let rows = sqlx::query_as::<_, AdminSearchRow>(
+            r#"
+            SELECT kind, id, title, subtitle
+            FROM (
+                SELECT m.*, row_number() OVER (
+                    PARTITION BY kind ORDER BY is_prefix DESC, lower(title), id
+                ) AS rank
+                FROM (
+                    SELECT 'post' AS kind, id, title, slug AS subtitle,
+                        title ILIKE $3 AS is_prefix
+                    FROM posts
+                    WHERE site_id = $1 AND (title ILIKE $2 OR slug ILIKE $2)
+                    UNION ALL
+                    SELECT 'user', id, name, email, name ILIKE $3 OR email ILIKE $3
+                    FROM users
+                    WHERE deleted_at IS NULL AND id <> $5 AND (name ILIKE $2 OR email ILIKE $2)
+                    UNION ALL
+                    SELECT 'category', id, name, slug, name ILIKE $3
+                    FROM categories
+                    WHERE site_id = $1 AND deleted_at IS NULL AND (name ILIKE $2 OR slug ILIKE $2)
+                    UNION ALL
+                    SELECT 'tag', id, name, slug, name ILIKE $3
+                    FROM tags
+                    WHERE site_id = $1 AND deleted_at IS NULL AND (name ILIKE $2 OR slug ILIKE $2)
+                ) m
+            ) ranked
+            WHERE rank <= $4
+            ORDER BY kind, rank
+            "#,
+        )
+        .bind(site_id)
+        .bind(contains_pattern)
+        .bind(prefix_pattern)
+        .bind(limit)
+        .bind(DELETED_USER_ID)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,87 @@
+//! Settings repository for database operations.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::Setting;
+
+/// Repository for installation-wide setting database operations.
+#[derive(Clone)]
+pub struct SettingsRepository {
+    pool: PgPool,
+}
+
+impl SettingsRepository {
+    /// Create a new settings repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a setting by key.
+    pub async fn find(&self, key: &str) -> Result<Option<Setting>, AppError> {
+        let setting = sqlx::query_as::<_, Setting>(
+            r#"
+            SELECT key, value, updated_by, created_at, updated_at
+            FROM settings
+            WHERE key = $1
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(setting)
+    }
+
+    /// Find the settings whose key starts with `prefix`, ordered by key.
+    pub async fn find_by_prefix(&self, prefix: &str) -> Result<Vec<Setting>, AppError> {
+        let settings = sqlx::query_as::<_, Setting>(
+            r#"
+            SELECT key, value, updated_by, created_at, updated_at
+            FROM settings
+            WHERE starts_with(key, $1)
+            ORDER BY key
+            "#,
+        )
+        .bind(prefix)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(settings)
+    }
+
+    /// Create or replace a setting.
+    pub async fn upsert(
+        &self,
+        key: &str,
+        value: &serde_json::Value,
+        updated_by: Uuid,
+    ) -> Result<Setting, AppError> {
+        let setting = sqlx::query_as::<_, Setting>(
+            r#"
+            INSERT INTO settings (key, value, updated_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, updated_by = EXCLUDED.updated_by
+            RETURNING key, value, updated_by, created_at, updated_at
+            "#,
+        )
+        .bind(key)
+        .bind(value)
+        .bind(updated_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(setting)
+    }
+
+    /// Delete a setting.
+    pub async fn delete(&self, key: &str) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM settings WHERE key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,140 @@
+//! Site repository for database operations.
+
+use std::collections::BTreeMap;
+
+use sqlx::types::Json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::Site;
+
+/// Repository for site database operations.
+#[derive(Clone)]
+pub struct SiteRepository {
+    pool: PgPool,
+}
+
+impl SiteRepository {
+    /// Create a new site repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find the site serving `host`, falling back to the default site.
+    pub async fn find_for_host(&self, host: &str) -> Result<Option<Site>, AppError> {
+        let site = sqlx::query_as::<_, Site>(
+            r#"
+            SELECT id, name, host, is_default, settings, created_at, updated_at
+            FROM sites
+            WHERE host = $1 OR is_default
+            ORDER BY (host = $1) DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(host)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(site)
+    }
+
+    /// Find a site by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Site>, AppError> {
+        let site = sqlx::query_as::<_, Site>(
+            r#"
+            SELECT id, name, host, is_default, settings, created_at, updated_at
+            FROM sites
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(site)
+    }
+
+    /// Find a site by its exact host.
+    pub async fn find_by_host(&self, host: &str) -> Result<Option<Site>, AppError> {
+        let site = sqlx::query_as::<_, Site>(
+            r#"
+            SELECT id, name, host, is_default, settings, created_at, updated_at
+            FROM sites
+            WHERE host = $1
+            "#,
+        )
+        .bind(host)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(site)
+    }
+
+    /// Find all sites.
+    pub async fn find_all(&self) -> Result<Vec<Site>, AppError> {
+        let sites = sqlx::query_as::<_, Site>(
+            r#"
+            SELECT id, name, host, is_default, settings, created_at, updated_at
+            FROM sites
+            ORDER BY is_default DESC, name ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sites)
+    }
+
+    /// Create a new site.
+    pub async fn create(
+        &self,
+        name: &str,
+        host: &str,
+        settings: &BTreeMap<String, serde_json::Value>,
+    ) -> Result<Site, AppError> {
+        let site = sqlx::query_as::<_, Site>(
+            r#"
+            INSERT INTO sites (name, host, settings)
+            VALUES ($1, $2, $3)
+            RETURNING id, name, host, is_default, settings, created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(host)
+        .bind(Json(settings))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(site)
+    }
+
+    /// Update a site.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        name: Option<&str>,
+        host: Option<&str>,
+        settings: Option<&BTreeMap<String, serde_json::Value>>,
+    ) -> Result<Site, AppError> {
+        let site = sqlx::query_as::<_, Site>(
+            r#"
+            UPDATE sites
+            SET
+                name = COALESCE($2, name),
+                host = COALESCE($3, host),
+                settings = COALESCE($4, settings)
+            WHERE id = $1
+            RETURNING id, name, host, is_default, settings, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .bind(host)
+        .bind(settings.map(Json))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(site)
+    }
+}
@@ -0,0 +1,75 @@
+//! Sync repository for changes to a site's content.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::ContentChangeRow;
+
+/// Repository for changes spanning posts, categories and tags.
+#[derive(Clone)]
+pub struct SyncRepository {
+    pool: PgPool,
+}
+
+impl SyncRepository {
+    /// Create a new sync repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Current database time, the upper bound of a changes query.
+    pub async fn now(&self) -> Result<DateTime<Utc>, AppError> {
+        let now: DateTime<Utc> = sqlx::query_scalar("SELECT NOW()")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(now)
+    }
+
+    /// Find a site's posts, categories and tags changed after `since` (any
+    /// time when `None`) up to `until`, oldest first.
+    ///
+    /// Rows deleted for good are only included when `since` is given; a full
+    /// sync has nothing to remove.
+    pub async fn find_changes(
+        &self,
+        site_id: Uuid,
+        since: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<ContentChangeRow>, AppError> {
+        let rows = sqlx::query_as::<_, ContentChangeRow>(
+            r#"
+            SELECT kind, id, updated_at, deleted
+            FROM (
+                SELECT 'post' AS kind, id, updated_at, FALSE AS deleted
+                FROM posts
+                WHERE site_id = $1
+                  AND ($2::timestamptz IS NULL OR updated_at > $2) AND updated_at <= $3
+                UNION ALL
+                SELECT 'category', id, updated_at, deleted_at IS NOT NULL
+                FROM categories
+                WHERE site_id = $1
+                  AND ($2::timestamptz IS NULL OR updated_at > $2) AND updated_at <= $3
+                UNION ALL
+                SELECT 'tag', id, updated_at, deleted_at IS NOT NULL
+                FROM tags
+                WHERE site_id = $1
+                  AND ($2::timestamptz IS NULL OR updated_at > $2) AND updated_at <= $3
+                UNION ALL
+                SELECT kind, id, deleted_at, TRUE
+                FROM deleted_content
+                WHERE site_id = $1 AND deleted_at > $2 AND deleted_at <= $3
+            ) changes
+            ORDER BY updated_at, id
+            "#,
+        )
+        .bind(site_id)
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
@@ -1,10 +1,11 @@
 //! Tag repository for database operations.
 
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::{Tag, TagWithCount};
+use crate::models::{PostTag, Tag, TagCloudItem, TagWithCount};
 
 /// Repository for tag database operations.
 #[derive(Clone)]
@@ -18,15 +19,16 @@ impl TagRepository {
         Self { pool }
     }
 
-    /// Find a tag by ID.
-    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Tag>, AppError> {
+    /// Find a tag by ID within a site.
+    pub async fn find_by_id(&self, site_id: Uuid, id: Uuid) -> Result<Option<Tag>, AppError> {
         let tag = sqlx::query_as::<_, Tag>(
             r#"
             SELECT id, name, slug, created_at
             FROM tags
-            WHERE id = $1
+            WHERE site_id = $1 AND id = $2 AND deleted_at IS NULL
             "#,
         )
+        .bind(site_id)
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
@@ -34,15 +36,16 @@ impl TagRepository {
         Ok(tag)
     }
 
-    /// Find a tag by slug.
-    pub async fn find_by_slug(&self, slug: &str) -> Result<Option<Tag>, AppError> {
+    /// Find a tag by slug within a site.
+    pub async fn find_by_slug(&self, site_id: Uuid, slug: &str) -> Result<Option<Tag>, AppError> {
         let tag = sqlx::query_as::<_, Tag>(
             r#"
             SELECT id, name, slug, created_at
             FROM tags
-            WHERE slug = $1
+            WHERE site_id = $1 AND slug = $2 AND deleted_at IS NULL
             "#,
         )
+        .bind(site_id)
         .bind(slug)
         .fetch_optional(&self.pool)
         .await?;
@@ -50,15 +53,16 @@ impl TagRepository {
         Ok(tag)
     }
 
-    /// Find tags by IDs.
-    pub async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Tag>, AppError> {
+    /// Find a site's tags by IDs.
+    pub async fn find_by_ids(&self, site_id: Uuid, ids: &[Uuid]) -> Result<Vec<Tag>, AppError> {
         let tags = sqlx::query_as::<_, Tag>(
             r#"
             SELECT id, name, slug, created_at
             FROM tags
-            WHERE id = ANY($1)
+            WHERE site_id = $1 AND id = ANY($2) AND deleted_at IS NULL
             "#,
         )
+        .bind(site_id)
         .bind(ids)
         .fetch_all(&self.pool)
         .await?;
@@ -66,35 +70,215 @@ impl TagRepository {
         Ok(tags)
     }
 
-    /// Find all tags with post counts.
-    pub async fn find_all_with_count(&self) -> Result<Vec<TagWithCount>, AppError> {
+    /// Find the tags on each of `post_ids`, by post and name.
+    pub async fn find_by_posts(
+        &self,
+        site_id: Uuid,
+        post_ids: &[Uuid],
+    ) -> Result<Vec<PostTag>, AppError> {
+        let tags = sqlx::query_as::<_, PostTag>(
+            r#"
+            SELECT pt.post_id, t.id, t.name, t.slug, t.created_at
+            FROM post_tags pt
+            JOIN tags t ON pt.tag_id = t.id
+            WHERE t.site_id = $1 AND pt.post_id = ANY($2) AND t.deleted_at IS NULL
+            ORDER BY pt.post_id, t.name
+            "#,
+        )
+        .bind(site_id)
+        .bind(post_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Find a site's tags with counts of their published, public posts.
+    ///
+    /// Drafts and members-only or private posts are left out of the counts,
+    /// which are shown to everyone.
+    pub async fn find_all_with_count(&self, site_id: Uuid) -> Result<Vec<TagWithCount>, AppError> {
         let tags = sqlx::query_as::<_, TagWithCount>(
             r#"
             SELECT 
                 t.id, t.name, t.slug,
-                COUNT(pt.post_id) as post_count, t.created_at
+                COUNT(p.id) as post_count, t.created_at
             FROM tags t
             LEFT JOIN post_tags pt ON t.id = pt.tag_id
-            LEFT JOIN posts p ON pt.post_id = p.id AND p.status = 'published'
+            LEFT JOIN posts p ON pt.post_id = p.id
+                AND p.status = 'published' AND p.visibility = 'public'
+            WHERE t.site_id = $1 AND t.deleted_at IS NULL
             GROUP BY t.id
             ORDER BY t.name ASC
             "#,
         )
+        .bind(site_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Search a site's tags by name: prefix matches first, then trigram
+    /// similarity. `prefix_pattern` is a `LIKE` pattern such as `ru%`.
+    pub async fn search(
+        &self,
+        site_id: Uuid,
+        term: &str,
+        prefix_pattern: &str,
+        limit: i64,
+    ) -> Result<Vec<Tag>, AppError> {
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT id, name, slug, created_at
+            FROM tags
+            WHERE site_id = $1 AND deleted_at IS NULL
+              AND (name ILIKE $3 OR slug ILIKE $3 OR name % $2)
+            ORDER BY (name ILIKE $3 OR slug ILIKE $3) DESC, similarity(name, $2) DESC, name ASC
+            LIMIT $4
+            "#,
+        )
+        .bind(site_id)
+        .bind(term)
+        .bind(prefix_pattern)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Find a site's tags by slug, creating those that do not exist yet.
+    ///
+    /// `names_and_slugs` must not repeat a slug. Runs as one statement on
+    /// `conn`, normally the transaction saving the post, so concurrent saves
+    /// creating the same tag both get the same row.
+    pub async fn find_or_create(
+        conn: &mut PgConnection,
+        site_id: Uuid,
+        names_and_slugs: &[(String, String)],
+    ) -> Result<Vec<Tag>, AppError> {
+        let (names, slugs): (Vec<&str>, Vec<&str>) = names_and_slugs
+            .iter()
+            .map(|(name, slug)| (name.as_str(), slug.as_str()))
+            .unzip();
+
+        // DO UPDATE (a no-op) rather than DO NOTHING so existing rows are returned
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            INSERT INTO tags (site_id, name, slug)
+            SELECT $1, name, slug FROM UNNEST($2::text[], $3::text[]) AS input(name, slug)
+            ON CONFLICT (site_id, slug) WHERE deleted_at IS NULL DO UPDATE SET name = tags.name
+            RETURNING id, name, slug, created_at
+            "#,
+        )
+        .bind(site_id)
+        .bind(&names)
+        .bind(&slugs)
+        .fetch_all(conn)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Find tags without posts created before `created_before`, optionally
+    /// limited to one site.
+    pub async fn find_orphans(
+        &self,
+        site_id: Option<Uuid>,
+        created_before: DateTime<Utc>,
+    ) -> Result<Vec<Tag>, AppError> {
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT id, name, slug, created_at
+            FROM tags t
+            WHERE ($1::uuid IS NULL OR t.site_id = $1)
+              AND t.deleted_at IS NULL
+              AND t.created_at < $2
+              AND NOT EXISTS (SELECT 1 FROM post_tags pt WHERE pt.tag_id = t.id)
+            ORDER BY t.name ASC
+            "#,
+        )
+        .bind(site_id)
+        .bind(created_before)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(tags)
     }
 
-    /// Create a new tag.
-    pub async fn create(&self, name: &str, slug: &str) -> Result<Tag, AppError> {
+    /// Delete tags without posts created before `created_before`, optionally
+    /// limited to one site. Returns the deleted tags.
+    pub async fn delete_orphans(
+        &self,
+        site_id: Option<Uuid>,
+        created_before: DateTime<Utc>,
+    ) -> Result<Vec<Tag>, AppError> {
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            DELETE FROM tags t
+            WHERE ($1::uuid IS NULL OR t.site_id = $1)
+              AND t.deleted_at IS NULL
+              AND t.created_at < $2
+              AND NOT EXISTS (SELECT 1 FROM post_tags pt WHERE pt.tag_id = t.id)
+            RETURNING id, name, slug, created_at
+            "#,
+        )
+        .bind(site_id)
+        .bind(created_before)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Find a site's most used tags by published, public posts, each post counting
+    /// `0.5^(age / half_life_days)` when a half-life is given.
+    pub async fn find_cloud(
+        &self,
+        site_id: Uuid,
+        half_life_days: Option<f64>,
+        limit: i64,
+    ) -> Result<Vec<TagCloudItem>, AppError> {
+        let tags = sqlx::query_as::<_, TagCloudItem>(
+            r#"
+            SELECT
+                t.id, t.name, t.slug,
+                COUNT(p.id) as post_count,
+                SUM(
+                    CASE WHEN $2::float8 IS NULL THEN 1.0
+                    ELSE power(0.5, EXTRACT(EPOCH FROM NOW() - p.created_at)::float8 / 86400.0 / $2)
+                    END
+                )::float8 as score
+            FROM tags t
+            JOIN post_tags pt ON t.id = pt.tag_id
+            JOIN posts p ON pt.post_id = p.id
+                AND p.status = 'published' AND p.visibility = 'public'
+            WHERE t.site_id = $1 AND t.deleted_at IS NULL
+            GROUP BY t.id
+            ORDER BY score DESC, t.name ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(site_id)
+        .bind(half_life_days)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Create a new tag on a site.
+    pub async fn create(&self, site_id: Uuid, name: &str, slug: &str) -> Result<Tag, AppError> {
         let tag = sqlx::query_as::<_, Tag>(
             r#"
-            INSERT INTO tags (name, slug)
-            VALUES ($1, $2)
+            INSERT INTO tags (site_id, name, slug)
+            VALUES ($1, $2, $3)
             RETURNING id, name, slug, created_at
             "#,
         )
+        .bind(site_id)
         .bind(name)
         .bind(slug)
         .fetch_one(&self.pool)
@@ -116,7 +300,7 @@ impl TagRepository {
             SET 
                 name = COALESCE($2, name),
                 slug = COALESCE($3, slug)
-            WHERE id = $1
+            WHERE id = $1 AND deleted_at IS NULL
             RETURNING id, name, slug, created_at
             "#,
         )
@@ -129,13 +313,108 @@ impl TagRepository {
         Ok(tag)
     }
 
-    /// Delete a tag by ID.
-    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
-        let result = sqlx::query("DELETE FROM tags WHERE id = $1")
+    /// Find a site's soft-deleted tags, most recently deleted first.
+    pub async fn find_deleted(&self, site_id: Uuid) -> Result<Vec<Tag>, AppError> {
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT id, name, slug, created_at, deleted_at
+            FROM tags
+            WHERE site_id = $1 AND deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC
+            "#,
+        )
+        .bind(site_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Find a soft-deleted tag by ID within a site.
+    pub async fn find_deleted_by_id(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<Tag>, AppError> {
+        let tag = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT id, name, slug, created_at, deleted_at
+            FROM tags
+            WHERE site_id = $1 AND id = $2 AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(site_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(tag)
+    }
+
+    /// Count posts carrying a tag, whatever their status.
+    pub async fn count_posts(&self, id: Uuid) -> Result<i64, AppError> {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM post_tags WHERE tag_id = $1")
             .bind(id)
-            .execute(&self.pool)
+            .fetch_one(&self.pool)
             .await?;
 
+        Ok(count)
+    }
+
+    /// Soft delete a tag by ID; its posts keep carrying it.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+        let result =
+            sqlx::query("UPDATE tags SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL")
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Restore a soft-deleted tag.
+    pub async fn restore(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE tags SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
         Ok(result.rows_affected() > 0)
     }
+
+    /// Retag all posts carrying `source_id` with `target_id` and delete the
+    /// source, in one transaction. Returns the number of posts retagged.
+    pub async fn merge(&self, source_id: Uuid, target_id: Uuid) -> Result<i64, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let (moved,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM post_tags WHERE tag_id = $1")
+            .bind(source_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        // Posts already carrying the target keep their single row
+        sqlx::query(
+            r#"
+            INSERT INTO post_tags (post_id, tag_id)
+            SELECT post_id, $2 FROM post_tags WHERE tag_id = $1
+            ON CONFLICT (post_id, tag_id) DO NOTHING
+            "#,
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Cascades to the source's post_tags rows
+        sqlx::query("DELETE FROM tags WHERE id = $1")
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(moved)
+    }
 }
@@ -0,0 +1,102 @@
+//! Taxonomy repository for operations spanning categories and tags.
+
+use std::collections::HashSet;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{NewCategory, NewTag};
+
+/// Repository for bulk category and tag operations.
+#[derive(Clone)]
+pub struct TaxonomyRepository {
+    pool: PgPool,
+}
+
+impl TaxonomyRepository {
+    /// Create a new taxonomy repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Category and tag slugs already used on a site.
+    pub async fn existing_slugs(
+        &self,
+        site_id: Uuid,
+    ) -> Result<(HashSet<String>, HashSet<String>), AppError> {
+        let categories: Vec<(String,)> =
+            sqlx::query_as("SELECT slug FROM categories WHERE site_id = $1 AND deleted_at IS NULL")
+                .bind(site_id)
+                .fetch_all(&self.pool)
+                .await?;
+        let tags: Vec<(String,)> =
+            sqlx::query_as("SELECT slug FROM tags WHERE site_id = $1 AND deleted_at IS NULL")
+                .bind(site_id)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok((
+            categories.into_iter().map(|(slug,)| slug).collect(),
+            tags.into_iter().map(|(slug,)| slug).collect(),
+        ))
+    }
+
+    /// Create categories and tags on a site in one transaction.
+    ///
+    /// Parents are linked by slug after every category exists, so rows may
+    /// reference categories defined later in the same import.
+    pub async fn import(
+        &self,
+        site_id: Uuid,
+        categories: &[NewCategory],
+        tags: &[NewTag],
+    ) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        for category in categories {
+            sqlx::query(
+                "INSERT INTO categories (site_id, name, slug, description) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(site_id)
+            .bind(&category.name)
+            .bind(&category.slug)
+            .bind(&category.description)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for category in categories {
+            let Some(parent_slug) = &category.parent_slug else {
+                continue;
+            };
+            sqlx::query(
+                r#"
+                UPDATE categories
+                SET parent_id = (
+                    SELECT id FROM categories WHERE site_id = $1 AND slug = $3 AND deleted_at IS NULL
+                )
+                WHERE site_id = $1 AND slug = $2 AND deleted_at IS NULL
+                "#,
+            )
+            .bind(site_id)
+            .bind(&category.slug)
+            .bind(parent_slug)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for tag in tags {
+            sqlx::query("INSERT INTO tags (site_id, name, slug) VALUES ($1, $2, $3)")
+                .bind(site_id)
+                .bind(&tag.name)
+                .bind(&tag.slug)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Title test repository for database operations.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::TitleVariant;
+
+/// Repository for post title test database operations.
+#[derive(Clone)]
+pub struct TitleTestRepository {
+    pool: PgPool,
+}
+
+impl TitleTestRepository {
+    /// Create a new title test repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find the variants of several posts' title tests, ordered by post and position.
+    pub async fn find_by_posts(&self, post_ids: &[Uuid]) -> Result<Vec<TitleVariant>, AppError> {
+        let variants = sqlx::query_as::<_, TitleVariant>(
+            r#"
+            SELECT id, post_id, position, title, impressions, clicks, created_at
+            FROM post_title_variants
+            WHERE post_id = ANY($1)
+            ORDER BY post_id, position
+            "#,
+        )
+        .bind(post_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(variants)
+    }
+
+    /// Start a post's title test over with the original and `titles`,
+    /// dropping any earlier variants and their counts.
+    pub async fn replace(
+        &self,
+        post_id: Uuid,
+        titles: &[String],
+    ) -> Result<Vec<TitleVariant>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM post_title_variants WHERE post_id = $1")
+            .bind(post_id)
+            .execute(&mut *tx)
+            .await?;
+        let positions: Vec<i32> = (0..=titles.len() as i32).collect();
+        let titles: Vec<Option<&str>> = std::iter::once(None)
+            .chain(titles.iter().map(|title| Some(title.as_str())))
+            .collect();
+        let variants = sqlx::query_as::<_, TitleVariant>(
+            r#"
+            INSERT INTO post_title_variants (post_id, position, title)
+            SELECT $1, position, title
+            FROM UNNEST($2::int[], $3::varchar[]) AS v(position, title)
+            RETURNING id, post_id, position, title, impressions, clicks, created_at
+            "#,
+        )
+        .bind(post_id)
+        .bind(&positions)
+        .bind(&titles)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let mut variants = variants;
+        variants.sort_by_key(|variant| variant.position);
+        Ok(variants)
+    }
+
+    /// End a post's title test, returning whether one was running.
+    pub async fn delete(&self, post_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM post_title_variants WHERE post_id = $1")
+            .bind(post_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count one impression of each of the given variants.
+    pub async fn record_impressions(&self, ids: &[Uuid]) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE post_title_variants SET impressions = impressions + 1 WHERE id = ANY($1)",
+        )
+        .bind(ids)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count one click on a variant.
+    pub async fn record_click(&self, id: Uuid) -> Result<(), AppError> {
+        sqlx::query("UPDATE post_title_variants SET clicks = clicks + 1 WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
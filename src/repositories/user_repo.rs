@@ -1,10 +1,14 @@
 //! User repository for database operations.
 
-use sqlx::PgPool;
+use std::collections::BTreeMap;
+
+use sqlx::{types::Json, PgPool};
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::{User, UserWithRole};
+use crate::models::{NewEvent, User, UserFilter, UserProfile, UserWithRole, DELETED_USER_ID};
+use crate::pkg::Cursor;
+use crate::repositories::OutboxRepository;
 
 /// Repository for user database operations.
 #[derive(Clone)]
@@ -22,7 +26,7 @@ impl UserRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, AppError> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, email, password_hash, name, role_id, created_at, updated_at, deleted_at
+            SELECT id, email, password_hash, name, role_id, is_active, avatar_url, created_at, updated_at, deleted_at
             FROM users
             WHERE id = $1 AND deleted_at IS NULL
             "#,
@@ -34,6 +38,22 @@ impl UserRepository {
         Ok(user)
     }
 
+    /// Find the users among `ids` that exist.
+    pub async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<User>, AppError> {
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, email, password_hash, name, role_id, is_active, avatar_url, created_at, updated_at, deleted_at
+            FROM users
+            WHERE id = ANY($1) AND deleted_at IS NULL
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
     /// Find a user by email with role info.
     pub async fn find_by_email_with_role(
         &self,
@@ -43,8 +63,8 @@ impl UserRepository {
             r#"
             SELECT 
                 u.id, u.email, u.password_hash, u.name, u.role_id,
-                r.slug as role_slug, r.name as role_name,
-                u.created_at, u.updated_at
+                r.slug as role_slug, r.name as role_name, u.is_active, u.avatar_url, u.approved_at,
+                u.created_at, u.updated_at, u.deleted_at
             FROM users u
             JOIN roles r ON u.role_id = r.id
             WHERE u.email = $1 AND u.deleted_at IS NULL
@@ -63,8 +83,8 @@ impl UserRepository {
             r#"
             SELECT 
                 u.id, u.email, u.password_hash, u.name, u.role_id,
-                r.slug as role_slug, r.name as role_name,
-                u.created_at, u.updated_at
+                r.slug as role_slug, r.name as role_name, u.is_active, u.avatar_url, u.approved_at,
+                u.created_at, u.updated_at, u.deleted_at
             FROM users u
             JOIN roles r ON u.role_id = r.id
             WHERE u.id = $1 AND u.deleted_at IS NULL
@@ -77,7 +97,7 @@ impl UserRepository {
         Ok(user)
     }
 
-    /// Create a new user.
+    /// Create a new user, with a `user.created` event.
     pub async fn create(
         &self,
         email: &str,
@@ -85,43 +105,431 @@ impl UserRepository {
         name: &str,
         role_id: Uuid,
     ) -> Result<User, AppError> {
+        let mut tx = self.pool.begin().await?;
         let user = sqlx::query_as::<_, User>(
             r#"
             INSERT INTO users (email, password_hash, name, role_id)
             VALUES ($1, $2, $3, $4)
-            RETURNING id, email, password_hash, name, role_id, created_at, updated_at, deleted_at
+            RETURNING id, email, password_hash, name, role_id, is_active, avatar_url, created_at, updated_at, deleted_at
             "#,
         )
         .bind(email)
         .bind(password_hash)
         .bind(name)
         .bind(role_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        OutboxRepository::insert(&mut tx, &NewEvent::user_created(&user, false)).await?;
+        tx.commit().await?;
+
+        Ok(user)
+    }
+
+    /// Update a user's profile, role and active flag (unset fields are kept).
+    pub async fn update(
+        &self,
+        id: Uuid,
+        name: Option<&str>,
+        email: Option<&str>,
+        role_id: Option<Uuid>,
+        is_active: Option<bool>,
+    ) -> Result<User, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET name = COALESCE($2, name),
+                email = COALESCE($3, email),
+                role_id = COALESCE($4, role_id),
+                is_active = COALESCE($5, is_active)
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING id, email, password_hash, name, role_id, is_active, avatar_url, created_at, updated_at, deleted_at
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .bind(email)
+        .bind(role_id)
+        .bind(is_active)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(user)
     }
 
-    /// Get all users.
-    pub async fn find_all(&self) -> Result<Vec<UserWithRole>, AppError> {
+    /// Find a user's own profile with role info.
+    pub async fn find_profile(&self, id: Uuid) -> Result<Option<UserProfile>, AppError> {
+        let profile = sqlx::query_as::<_, UserProfile>(
+            r#"
+            SELECT
+                u.id, u.email, u.pending_email, u.name, u.bio, u.avatar_url, u.avatar_media_id,
+                u.social_links,
+                u.role_id, r.slug as role_slug, r.name as role_name,
+                u.created_at, u.updated_at, u.deleted_at
+            FROM users u
+            JOIN roles r ON u.role_id = r.id
+            WHERE u.id = $1 AND u.deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(profile)
+    }
+
+    /// Update profile fields (unset fields are kept, empty strings clear them).
+    pub async fn update_profile(
+        &self,
+        id: Uuid,
+        name: Option<&str>,
+        bio: Option<&str>,
+        avatar_url: Option<&str>,
+        social_links: Option<&BTreeMap<String, String>>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET name = COALESCE($2, name),
+                bio = NULLIF(COALESCE($3, bio), ''),
+                avatar_url = NULLIF(COALESCE($4, avatar_url), ''),
+                avatar_media_id = CASE WHEN $4 IS NULL THEN avatar_media_id END,
+                social_links = COALESCE($5, social_links)
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .bind(bio)
+        .bind(avatar_url)
+        .bind(social_links.map(Json))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Point a user's avatar at an uploaded media item, or clear it with `None`.
+    pub async fn set_avatar(
+        &self,
+        id: Uuid,
+        media_id: Option<Uuid>,
+        url: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET avatar_media_id = $2, avatar_url = $3
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(media_id)
+        .bind(url)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record an email address awaiting verification.
+    pub async fn set_pending_email(&self, id: Uuid, email: &str) -> Result<(), AppError> {
+        sqlx::query("UPDATE users SET pending_email = $2 WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .bind(email)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Drop the pending email if it still matches.
+    pub async fn clear_pending_email(&self, id: Uuid, email: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE users SET pending_email = NULL WHERE id = $1 AND pending_email = $2",
+        )
+        .bind(id)
+        .bind(email)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Promote the pending email to the account email if it still matches.
+    pub async fn confirm_pending_email(&self, id: Uuid, email: &str) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET email = pending_email, pending_email = NULL
+            WHERE id = $1 AND pending_email = $2 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(email)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace a user's password hash.
+    pub async fn update_password(&self, id: Uuid, password_hash: &str) -> Result<(), AppError> {
+        sqlx::query("UPDATE users SET password_hash = $2 WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .bind(password_hash)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get the current token version of a user (`None` if the user is gone).
+    pub async fn find_token_version(&self, id: Uuid) -> Result<Option<i32>, AppError> {
+        let version = sqlx::query_scalar::<_, i32>(
+            "SELECT token_version FROM users WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(version)
+    }
+
+    /// Increment a user's token version, returning the new value.
+    ///
+    /// Soft-deleted users are included so their tokens stay invalid if restored.
+    pub async fn bump_token_version(&self, id: Uuid) -> Result<Option<i32>, AppError> {
+        let version = sqlx::query_scalar::<_, i32>(
+            "UPDATE users SET token_version = token_version + 1 WHERE id = $1 RETURNING token_version",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(version)
+    }
+
+    /// Increment the token version of every user holding a role.
+    pub async fn bump_token_versions_by_role(
+        &self,
+        role_id: Uuid,
+    ) -> Result<Vec<(Uuid, i32)>, AppError> {
+        let versions = sqlx::query_as::<_, (Uuid, i32)>(
+            r#"
+            UPDATE users SET token_version = token_version + 1
+            WHERE role_id = $1
+            RETURNING id, token_version
+            "#,
+        )
+        .bind(role_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(versions)
+    }
+
+    /// Create a self-registered user awaiting approval, with a `user.created` event.
+    pub async fn create_pending(
+        &self,
+        email: &str,
+        password_hash: &str,
+        name: &str,
+        role_id: Uuid,
+    ) -> Result<User, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (email, password_hash, name, role_id, approved_at)
+            VALUES ($1, $2, $3, $4, NULL)
+            RETURNING id, email, password_hash, name, role_id, is_active, avatar_url, created_at, updated_at, deleted_at
+            "#,
+        )
+        .bind(email)
+        .bind(password_hash)
+        .bind(name)
+        .bind(role_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        OutboxRepository::insert(&mut tx, &NewEvent::user_created(&user, true)).await?;
+        tx.commit().await?;
+
+        Ok(user)
+    }
+
+    /// Approve a pending user.
+    pub async fn approve(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE users SET approved_at = NOW() WHERE id = $1 AND approved_at IS NULL AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Permanently erase a user and their personal data.
+    ///
+    /// Authored posts are reassigned to the deleted-user placeholder and the
+    /// user's avatar media record is removed; login history and access tokens
+    /// go with the user row. Returns the number of reassigned posts and the
+    /// storage key of the removed avatar file, if any.
+    pub async fn erase(&self, id: Uuid) -> Result<(i64, Option<String>), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let reassigned = sqlx::query("UPDATE posts SET author_id = $2 WHERE author_id = $1")
+            .bind(id)
+            .bind(DELETED_USER_ID)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+
+        let avatar: Option<(Uuid, String)> = sqlx::query_as(
+            r#"
+            SELECT m.id, m.storage_key
+            FROM users u
+            JOIN media m ON m.id = u.avatar_media_id
+            WHERE u.id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if let Some((media_id, _)) = &avatar {
+            sqlx::query("DELETE FROM media WHERE id = $1")
+                .bind(media_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok((reassigned, avatar.map(|(_, storage_key)| storage_key)))
+    }
+
+    /// Get a page of users matching `filter`, newest first, starting after
+    /// `cursor` when given and otherwise `offset` rows in.
+    pub async fn find_all(
+        &self,
+        filter: &UserFilter,
+        cursor: Option<&Cursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<UserWithRole>, AppError> {
         let users = sqlx::query_as::<_, UserWithRole>(
             r#"
             SELECT 
                 u.id, u.email, u.password_hash, u.name, u.role_id,
-                r.slug as role_slug, r.name as role_name,
-                u.created_at, u.updated_at
+                r.slug as role_slug, r.name as role_name, u.is_active, u.avatar_url, u.approved_at,
+                u.created_at, u.updated_at, u.deleted_at
             FROM users u
             JOIN roles r ON u.role_id = r.id
-            WHERE u.deleted_at IS NULL
-            ORDER BY u.created_at DESC
+            WHERE ($1 OR u.deleted_at IS NULL)
+              AND (NOT $2 OR u.approved_at IS NULL)
+              AND u.id <> $3
+              AND ($4::text IS NULL OR u.name ILIKE $4 OR u.email ILIKE $4)
+              AND ($5::text IS NULL OR r.slug = $5)
+              AND ($6::timestamptz IS NULL OR (u.created_at, u.id) < ($6, $7))
+            ORDER BY u.created_at DESC, u.id DESC
+            LIMIT $8 OFFSET $9
             "#,
         )
+        .bind(filter.include_deleted)
+        .bind(filter.pending_only)
+        .bind(DELETED_USER_ID)
+        .bind(&filter.pattern)
+        .bind(&filter.role_slug)
+        .bind(cursor.map(|cursor| cursor.created_at))
+        .bind(cursor.map(|cursor| cursor.id))
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(users)
     }
 
+    /// Count users matching `filter`.
+    pub async fn count(&self, filter: &UserFilter) -> Result<i64, AppError> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM users u
+            JOIN roles r ON u.role_id = r.id
+            WHERE ($1 OR u.deleted_at IS NULL)
+              AND (NOT $2 OR u.approved_at IS NULL)
+              AND u.id <> $3
+              AND ($4::text IS NULL OR u.name ILIKE $4 OR u.email ILIKE $4)
+              AND ($5::text IS NULL OR r.slug = $5)
+            "#,
+        )
+        .bind(filter.include_deleted)
+        .bind(filter.pending_only)
+        .bind(DELETED_USER_ID)
+        .bind(&filter.pattern)
+        .bind(&filter.role_slug)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    /// Find a soft-deleted user by ID.
+    pub async fn find_deleted_by_id(&self, id: Uuid) -> Result<Option<User>, AppError> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, email, password_hash, name, role_id, is_active, avatar_url, created_at, updated_at, deleted_at
+            FROM users
+            WHERE id = $1 AND deleted_at IS NOT NULL AND id <> $2
+            "#,
+        )
+        .bind(id)
+        .bind(DELETED_USER_ID)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Restore a soft-deleted user.
+    pub async fn restore(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE users SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Permanently delete a user that has already been soft-deleted.
+    pub async fn purge(&self, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query("DELETE FROM users WHERE id = $1 AND deleted_at IS NOT NULL")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count posts authored by a user.
+    pub async fn count_posts(&self, id: Uuid) -> Result<i64, AppError> {
+        let result: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) as count FROM posts WHERE author_id = $1")
+                .bind(id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(result.0)
+    }
+
     /// Soft delete a user by ID.
     pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
         let result =
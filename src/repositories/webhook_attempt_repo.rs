@@ -0,0 +1,122 @@
+//! Webhook attempt repository for the outgoing delivery log.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{JobKind, NewWebhookAttempt, WebhookAttempt};
+
+/// Repository for webhook attempt database operations.
+#[derive(Clone)]
+pub struct WebhookAttemptRepository {
+    pool: PgPool,
+}
+
+impl WebhookAttemptRepository {
+    /// Create a new webhook attempt repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find an attempt by ID.
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<WebhookAttempt>, AppError> {
+        let attempt = sqlx::query_as::<_, WebhookAttempt>(
+            r#"
+            SELECT id, kind, url, request_headers, payload, response_status, response_body,
+                   error, duration_ms, created_at
+            FROM webhook_attempts
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(attempt)
+    }
+
+    /// Find attempts, optionally of one kind and only failed or successful ones, newest first.
+    pub async fn find_all(
+        &self,
+        kind: Option<JobKind>,
+        failed: Option<bool>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WebhookAttempt>, AppError> {
+        let attempts = sqlx::query_as::<_, WebhookAttempt>(
+            r#"
+            SELECT id, kind, url, request_headers, payload, response_status, response_body,
+                   error, duration_ms, created_at
+            FROM webhook_attempts
+            WHERE ($1::job_kind IS NULL OR kind = $1)
+              AND ($2::boolean IS NULL OR (error IS NOT NULL) = $2)
+            ORDER BY created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(kind)
+        .bind(failed)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(attempts)
+    }
+
+    /// Count attempts, optionally of one kind and only failed or successful ones.
+    pub async fn count(
+        &self,
+        kind: Option<JobKind>,
+        failed: Option<bool>,
+    ) -> Result<i64, AppError> {
+        let result: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM webhook_attempts
+            WHERE ($1::job_kind IS NULL OR kind = $1)
+              AND ($2::boolean IS NULL OR (error IS NOT NULL) = $2)
+            "#,
+        )
+        .bind(kind)
+        .bind(failed)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
+    /// Log an attempt.
+    pub async fn create(&self, attempt: NewWebhookAttempt<'_>) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_attempts
+                (kind, url, request_headers, payload, response_status, response_body, error,
+                 duration_ms)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(attempt.kind)
+        .bind(attempt.url)
+        .bind(attempt.request_headers)
+        .bind(attempt.payload)
+        .bind(attempt.response_status)
+        .bind(attempt.response_body)
+        .bind(attempt.error)
+        .bind(attempt.duration_ms)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delete attempts made before `before`.
+    pub async fn delete_older_than(&self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM webhook_attempts WHERE created_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -4,10 +4,16 @@
 //! ```json
 //! {"success": true, "data": {...}, "error": null}
 //! ```
+//!
+//! Bulk endpoints answer with a [`BulkResponse`] instead, reporting each item
+//! on its own and using `207 Multi-Status` when some of them failed.
 
 use axum::{http::StatusCode, response::IntoResponse, Json};
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 
+use crate::error::{AppError, ErrorResponse};
+
 /// Standardized API response wrapper.
 #[derive(Debug, Serialize)]
 pub struct ApiResponse<T: Serialize> {
@@ -26,6 +32,9 @@ pub struct Meta {
     pub per_page: i64,
     pub total: i64,
     pub total_pages: i64,
+    /// Token for the next page of a listing with cursor pagination
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 impl Meta {
@@ -37,8 +46,15 @@ impl Meta {
             per_page,
             total,
             total_pages,
+            next_cursor: None,
         }
     }
+
+    /// Add the cursor of the next page, if there is one.
+    pub fn with_next_cursor(mut self, next_cursor: Option<String>) -> Self {
+        self.next_cursor = next_cursor;
+        self
+    }
 }
 
 impl<T: Serialize> ApiResponse<T> {
@@ -84,6 +100,106 @@ pub fn paginated<T: Serialize>(
     ))
 }
 
+/// Outcome of one item of a bulk request.
+#[derive(Debug, Serialize)]
+pub struct BulkItem<T: Serialize> {
+    /// Position of the item in the batch
+    pub index: usize,
+    /// Identifies the item when the request did not list it, e.g. a job ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Status the item would have had as a request of its own
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorResponse>,
+}
+
+/// Per-item results of a bulk request, so partial failures are reported
+/// precisely instead of failing the whole batch.
+///
+/// Sent as `200 OK` when every item succeeded and `207 Multi-Status`
+/// otherwise, with `success` false.
+#[derive(Debug, Serialize)]
+pub struct BulkResponse<T: Serialize> {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub items: Vec<BulkItem<T>>,
+}
+
+impl<T: Serialize> Default for BulkResponse<T> {
+    fn default() -> Self {
+        Self {
+            succeeded: 0,
+            failed: 0,
+            items: Vec::new(),
+        }
+    }
+}
+
+impl<T: Serialize> BulkResponse<T> {
+    /// Record the outcome of the next item.
+    pub fn push(&mut self, id: Option<String>, result: Result<T, AppError>) {
+        let index = self.items.len();
+        let item = match result {
+            Ok(data) => {
+                self.succeeded += 1;
+                BulkItem {
+                    index,
+                    id,
+                    status: StatusCode::OK.as_u16(),
+                    data: Some(data),
+                    error: None,
+                }
+            }
+            Err(err) => {
+                self.failed += 1;
+                BulkItem {
+                    index,
+                    id,
+                    status: err.status_code().as_u16(),
+                    data: None,
+                    error: Some(err.error_response()),
+                }
+            }
+        };
+        self.items.push(item);
+    }
+
+    /// `207 Multi-Status` when any item failed, `200 OK` otherwise.
+    pub fn status(&self) -> StatusCode {
+        if self.failed == 0 {
+            StatusCode::OK
+        } else {
+            StatusCode::MULTI_STATUS
+        }
+    }
+}
+
+impl<T: Serialize> FromIterator<Result<T, AppError>> for BulkResponse<T> {
+    fn from_iter<I: IntoIterator<Item = Result<T, AppError>>>(results: I) -> Self {
+        let mut response = Self::default();
+        for result in results {
+            response.push(None, result);
+        }
+        response
+    }
+}
+
+impl<T: Serialize> IntoResponse for BulkResponse<T> {
+    fn into_response(self) -> axum::response::Response {
+        let status = self.status();
+        let body = ApiResponse {
+            success: self.failed == 0,
+            data: Some(self),
+            error: None,
+            meta: None,
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
 /// Simple message response for operations that don't return data.
 #[derive(Debug, Serialize)]
 pub struct MessageResponse {
@@ -112,6 +228,46 @@ impl Default for HealthResponse {
     }
 }
 
+impl HealthResponse {
+    /// Response for a passed readiness probe.
+    pub fn ready() -> Self {
+        Self {
+            status: "ready".to_string(),
+        }
+    }
+}
+
+/// Build information of the running binary, embedded by `build.rs`.
+#[derive(Debug, Serialize)]
+pub struct VersionResponse {
+    /// Crate version from `Cargo.toml`
+    pub version: &'static str,
+    /// Commit the binary was built from, or `unknown`
+    pub git_sha: &'static str,
+    /// When the binary was built
+    pub built_at: Option<DateTime<Utc>>,
+    /// Cargo features enabled at compile time
+    pub features: Vec<&'static str>,
+}
+
+impl VersionResponse {
+    /// Build information compiled into this binary.
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: env!("BUILD_GIT_SHA"),
+            built_at: env!("BUILD_TIMESTAMP")
+                .parse()
+                .ok()
+                .and_then(|seconds| DateTime::from_timestamp(seconds, 0)),
+            features: env!("BUILD_FEATURES")
+                .split(',')
+                .filter(|feature| !feature.is_empty())
+                .collect(),
+        }
+    }
+}
+
 impl<T: Serialize> IntoResponse for ApiResponse<T> {
     fn into_response(self) -> axum::response::Response {
         (StatusCode::OK, Json(self)).into_response()
@@ -158,6 +314,31 @@ mod tests {
         assert_eq!(meta.total_pages, 1);
     }
 
+    #[test]
+    fn test_bulk_response() {
+        let response: BulkResponse<i32> = vec![Ok(1), Ok(2)].into_iter().collect();
+        assert_eq!(response.succeeded, 2);
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut response = BulkResponse::default();
+        response.push(Some("a".to_string()), Ok("created"));
+        response.push(
+            Some("b".to_string()),
+            Err(AppError::Conflict("Slug taken".to_string())),
+        );
+        assert_eq!((response.succeeded, response.failed), (1, 1));
+        assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json["items"][0],
+            serde_json::json!({ "index": 0, "id": "a", "status": 200, "data": "created" })
+        );
+        assert_eq!(json["items"][1]["status"], 409);
+        assert_eq!(json["items"][1]["error"]["code"], "CONFLICT");
+        assert!(json["items"][1].get("data").is_none());
+    }
+
     #[test]
     fn test_message_response() {
         let msg = MessageResponse::new("Operation successful");
@@ -169,4 +350,13 @@ mod tests {
         let health = HealthResponse::default();
         assert_eq!(health.status, "ok");
     }
+
+    #[test]
+    fn test_version_response_current() {
+        let version = VersionResponse::current();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+        assert!(!version.git_sha.is_empty());
+        assert!(version.built_at.is_some());
+        assert!(version.features.iter().all(|feature| !feature.is_empty()));
+    }
 }
@@ -1,29 +1,90 @@
 //! Application routing configuration.
 
-use axum::{
-    middleware,
-    routing::{delete, get, post, put},
-    Router,
-};
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::extract::{DefaultBodyLimit, Request};
+use axum::handler::Handler;
+use axum::http::HeaderValue;
+use axum::response::IntoResponse;
+use axum::routing::{self, MethodRouter, Route};
+use axum::{middleware, Extension, Router};
 use sqlx::PgPool;
-use tower_http::cors::{Any, CorsLayer};
+use tower::{Layer, Service};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tower_http::services::{ServeDir, ServeFile};
 use tower_http::trace::TraceLayer;
 
 use crate::controllers;
-use crate::middleware::{admin_middleware, auth_middleware, optional_auth_middleware};
+use crate::middleware::FRONTEND_ASSETS_PREFIX;
+use crate::middleware::{
+    admin_middleware, auth_middleware, blocklist_middleware, cache_headers_middleware,
+    content_modified_middleware, frontend_middleware, maintenance_middleware,
+    optional_auth_middleware, quota_middleware, require_any_permission, require_permission,
+    site_middleware, sudo_middleware,
+};
+use crate::models::MEDIA_URL_PREFIX;
+use crate::models::{AuthLevel, RouteAccess};
+use crate::pkg::SlowQueryLog;
 use crate::repositories::{RoleRepository, UserRepository};
-use crate::services::{AuthService, CategoryService, PostService, TagService};
+use crate::runtime::RuntimeSettings;
+use crate::services::{
+    AccessTokenService, AccountService, ActivityService, ArchiveService, AuthService,
+    BackupService, BlocklistService, BootstrapService, CacheService, CacheWarmer, CategoryService,
+    ChangelogService, EmailService, GitSync, HookService, JobService, MediaService,
+    NotificationService, PollService, PostLockService, PostNoteService, PostService,
+    PostTemplateService, PreviewService, ProfileService, QuotaService, RevisionService,
+    SearchService, SiteService, StatusMonitor, SyncService, TagService, TaxonomyService,
+    TitleTestService, TrendingService,
+};
 
 /// Application state containing all services.
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: PgPool,
+    pub redis: redis::aio::ConnectionManager,
     pub auth_service: AuthService,
+    pub access_token_service: AccessTokenService,
+    pub account_service: AccountService,
+    pub activity_service: ActivityService,
+    pub archive_service: ArchiveService,
     pub post_service: PostService,
+    pub post_lock_service: PostLockService,
+    pub post_note_service: PostNoteService,
+    pub post_template_service: PostTemplateService,
+    pub revision_service: RevisionService,
+    pub poll_service: PollService,
+    pub preview_service: PreviewService,
+    pub git_sync: GitSync,
+    pub hook_service: HookService,
+    pub profile_service: ProfileService,
+    pub media_service: MediaService,
+    pub notification_service: NotificationService,
     pub category_service: CategoryService,
+    pub changelog_service: ChangelogService,
     pub tag_service: TagService,
+    pub site_service: SiteService,
+    pub status_monitor: StatusMonitor,
+    pub backup_service: BackupService,
+    pub job_service: JobService,
+    pub email_service: EmailService,
+    pub search_service: SearchService,
+    pub sync_service: SyncService,
+    pub quota_service: QuotaService,
+    pub blocklist_service: BlocklistService,
+    pub bootstrap_service: BootstrapService,
+    pub taxonomy_service: TaxonomyService,
+    pub trending_service: TrendingService,
+    pub title_test_service: TitleTestService,
+    pub cache_service: CacheService,
+    pub cache_warmer: CacheWarmer,
     pub user_repo: UserRepository,
     pub role_repo: RoleRepository,
+    pub runtime: RuntimeSettings,
+    pub slow_queries: SlowQueryLog,
+    /// Built frontend to serve for non-API paths, if any
+    pub frontend_dir: Option<PathBuf>,
 }
 
 // Implement FromRef for extracting individual services from AppState
@@ -33,12 +94,54 @@ impl axum::extract::FromRef<AppState> for AuthService {
     }
 }
 
+impl axum::extract::FromRef<AppState> for NotificationService {
+    fn from_ref(state: &AppState) -> Self {
+        state.notification_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for AccessTokenService {
+    fn from_ref(state: &AppState) -> Self {
+        state.access_token_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for AccountService {
+    fn from_ref(state: &AppState) -> Self {
+        state.account_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for ActivityService {
+    fn from_ref(state: &AppState) -> Self {
+        state.activity_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for ArchiveService {
+    fn from_ref(state: &AppState) -> Self {
+        state.archive_service.clone()
+    }
+}
+
 impl axum::extract::FromRef<AppState> for PostService {
     fn from_ref(state: &AppState) -> Self {
         state.post_service.clone()
     }
 }
 
+impl axum::extract::FromRef<AppState> for ProfileService {
+    fn from_ref(state: &AppState) -> Self {
+        state.profile_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for MediaService {
+    fn from_ref(state: &AppState) -> Self {
+        state.media_service.clone()
+    }
+}
+
 impl axum::extract::FromRef<AppState> for CategoryService {
     fn from_ref(state: &AppState) -> Self {
         state.category_service.clone()
@@ -51,6 +154,60 @@ impl axum::extract::FromRef<AppState> for TagService {
     }
 }
 
+impl axum::extract::FromRef<AppState> for SiteService {
+    fn from_ref(state: &AppState) -> Self {
+        state.site_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for QuotaService {
+    fn from_ref(state: &AppState) -> Self {
+        state.quota_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for BlocklistService {
+    fn from_ref(state: &AppState) -> Self {
+        state.blocklist_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for BootstrapService {
+    fn from_ref(state: &AppState) -> Self {
+        state.bootstrap_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for SearchService {
+    fn from_ref(state: &AppState) -> Self {
+        state.search_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for SyncService {
+    fn from_ref(state: &AppState) -> Self {
+        state.sync_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for TrendingService {
+    fn from_ref(state: &AppState) -> Self {
+        state.trending_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for TitleTestService {
+    fn from_ref(state: &AppState) -> Self {
+        state.title_test_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for TaxonomyService {
+    fn from_ref(state: &AppState) -> Self {
+        state.taxonomy_service.clone()
+    }
+}
+
 impl axum::extract::FromRef<AppState> for UserRepository {
     fn from_ref(state: &AppState) -> Self {
         state.user_repo.clone()
@@ -63,92 +220,678 @@ impl axum::extract::FromRef<AppState> for RoleRepository {
     }
 }
 
+impl axum::extract::FromRef<AppState> for RuntimeSettings {
+    fn from_ref(state: &AppState) -> Self {
+        state.runtime.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for ChangelogService {
+    fn from_ref(state: &AppState) -> Self {
+        state.changelog_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for StatusMonitor {
+    fn from_ref(state: &AppState) -> Self {
+        state.status_monitor.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for BackupService {
+    fn from_ref(state: &AppState) -> Self {
+        state.backup_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for HookService {
+    fn from_ref(state: &AppState) -> Self {
+        state.hook_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for JobService {
+    fn from_ref(state: &AppState) -> Self {
+        state.job_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for EmailService {
+    fn from_ref(state: &AppState) -> Self {
+        state.email_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for PollService {
+    fn from_ref(state: &AppState) -> Self {
+        state.poll_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for PostLockService {
+    fn from_ref(state: &AppState) -> Self {
+        state.post_lock_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for PostNoteService {
+    fn from_ref(state: &AppState) -> Self {
+        state.post_note_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for PostTemplateService {
+    fn from_ref(state: &AppState) -> Self {
+        state.post_template_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for RevisionService {
+    fn from_ref(state: &AppState) -> Self {
+        state.revision_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for PreviewService {
+    fn from_ref(state: &AppState) -> Self {
+        state.preview_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for GitSync {
+    fn from_ref(state: &AppState) -> Self {
+        state.git_sync.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for CacheService {
+    fn from_ref(state: &AppState) -> Self {
+        state.cache_service.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for CacheWarmer {
+    fn from_ref(state: &AppState) -> Self {
+        state.cache_warmer.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for SlowQueryLog {
+    fn from_ref(state: &AppState) -> Self {
+        state.slow_queries.clone()
+    }
+}
+
 impl axum::extract::FromRef<AppState> for PgPool {
     fn from_ref(state: &AppState) -> Self {
         state.db_pool.clone()
     }
 }
 
+impl axum::extract::FromRef<AppState> for redis::aio::ConnectionManager {
+    fn from_ref(state: &AppState) -> Self {
+        state.redis.clone()
+    }
+}
+
+/// Any of these lets a user browse the media library.
+const MEDIA_PERMISSIONS: &[&str] = &["media:create", "media:update", "media:delete"];
+
+/// Handler for the methods of one route, with the permissions and sudo mode
+/// it requires recorded alongside its layers.
+struct Endpoint {
+    router: MethodRouter<AppState>,
+    methods: Vec<&'static str>,
+    permissions: Vec<&'static str>,
+    sudo: bool,
+}
+
+fn get<H, T>(handler: H) -> Endpoint
+where
+    H: Handler<T, AppState>,
+    T: 'static,
+{
+    Endpoint::new("GET", routing::get(handler))
+}
+
+fn post<H, T>(handler: H) -> Endpoint
+where
+    H: Handler<T, AppState>,
+    T: 'static,
+{
+    Endpoint::new("POST", routing::post(handler))
+}
+
+fn put<H, T>(handler: H) -> Endpoint
+where
+    H: Handler<T, AppState>,
+    T: 'static,
+{
+    Endpoint::new("PUT", routing::put(handler))
+}
+
+fn patch<H, T>(handler: H) -> Endpoint
+where
+    H: Handler<T, AppState>,
+    T: 'static,
+{
+    Endpoint::new("PATCH", routing::patch(handler))
+}
+
+fn delete<H, T>(handler: H) -> Endpoint
+where
+    H: Handler<T, AppState>,
+    T: 'static,
+{
+    Endpoint::new("DELETE", routing::delete(handler))
+}
+
+impl Endpoint {
+    fn new(method: &'static str, router: MethodRouter<AppState>) -> Self {
+        Self {
+            router,
+            methods: vec![method],
+            permissions: Vec::new(),
+            sudo: false,
+        }
+    }
+
+    fn post<H, T>(mut self, handler: H) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        self.router = self.router.post(handler);
+        self.methods.push("POST");
+        self
+    }
+
+    fn put<H, T>(mut self, handler: H) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        self.router = self.router.put(handler);
+        self.methods.push("PUT");
+        self
+    }
+
+    fn delete<H, T>(mut self, handler: H) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        self.router = self.router.delete(handler);
+        self.methods.push("DELETE");
+        self
+    }
+
+    /// Reject users without `permission`.
+    fn guard(self, permission: &'static str) -> Self {
+        self.guard_with(vec![permission], require_permission(permission))
+    }
+
+    /// Reject users holding none of `permissions`.
+    fn guard_any(self, permissions: &'static [&'static str]) -> Self {
+        self.guard_with(permissions.to_vec(), require_any_permission(permissions))
+    }
+
+    fn guard_with<F>(mut self, permissions: Vec<&'static str>, check: F) -> Self
+    where
+        F: Fn(Request, middleware::Next) -> crate::middleware::GuardFuture
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.router = self.router.route_layer(middleware::from_fn(check));
+        self.permissions = permissions;
+        self
+    }
+
+    /// Require a recent password confirmation.
+    fn sudo(mut self, state: &AppState) -> Self {
+        self.router = self.router.route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            sudo_middleware,
+        ));
+        self.sudo = true;
+        self
+    }
+
+    /// Run a middleware only for requests matching this route.
+    fn route_layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: Service<Request, Error = Infallible> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.router = self.router.route_layer(layer);
+        self
+    }
+
+    /// Wrap the handlers in a layer.
+    fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: Service<Request> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.router = self.router.layer(layer);
+        self
+    }
+}
+
+/// Routes of one group, recording the access rule of each as it is added so
+/// `/api/admin/routes` can describe them.
+struct Routes {
+    router: Router<AppState>,
+    auth: AuthLevel,
+    access: Vec<RouteAccess>,
+}
+
+impl Routes {
+    /// Start a group whose middleware lets through users at `auth`.
+    fn new(auth: AuthLevel) -> Self {
+        Self {
+            router: Router::new(),
+            auth,
+            access: Vec::new(),
+        }
+    }
+
+    fn route(mut self, path: &'static str, endpoint: Endpoint) -> Self {
+        self.access
+            .extend(endpoint.methods.iter().map(|&method| RouteAccess {
+                method,
+                path: format!("/api{}", path),
+                auth: self.auth,
+                permissions: endpoint.permissions.clone(),
+                sudo: endpoint.sudo,
+            }));
+        self.router = self.router.route(path, endpoint.router);
+        self
+    }
+
+    /// The group's router, adding its access rules to `catalog`.
+    fn into_router(self, catalog: &mut Vec<RouteAccess>) -> Router<AppState> {
+        catalog.extend(self.access);
+        self.router
+    }
+}
+
 /// Create the application router with all routes.
 pub fn create_router(state: AppState) -> Router {
-    // CORS configuration
+    // CORS configuration (allowed origins are read from the reloadable runtime settings)
+    let runtime = state.runtime.clone();
     let cors = CorsLayer::new()
-        .allow_origin(Any)
+        .allow_origin(AllowOrigin::predicate(
+            move |origin: &HeaderValue, _parts| {
+                origin
+                    .to_str()
+                    .map(|origin| runtime.current().allows_origin(origin))
+                    .unwrap_or(false)
+            },
+        ))
         .allow_methods(Any)
         .allow_headers(Any);
 
+    // Access rules of every API route, filled in as the groups are built
+    let mut catalog = Vec::new();
+
     // Public routes (no auth required)
-    let public_routes = Router::new()
+    let public_routes = Routes::new(AuthLevel::Public)
         .route("/health", get(controllers::health_check))
+        .route("/health/ready", get(controllers::readiness_check))
+        .route("/version", get(controllers::version))
+        .route("/status", get(controllers::status))
         .route("/auth/login", post(controllers::login))
-        .route("/auth/refresh", post(controllers::refresh_token));
+        .route("/auth/refresh", post(controllers::refresh_token))
+        .route("/auth/verify-email", post(controllers::verify_email))
+        .route(
+            "/auth/cancel-email-change",
+            post(controllers::cancel_email_change),
+        )
+        .route("/auth/accept-invite", post(controllers::accept_invite))
+        .route(
+            "/auth/register",
+            post(controllers::register).route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                blocklist_middleware,
+            )),
+        )
+        .route("/preview/{token}", get(controllers::get_preview))
+        .route("/webhooks/git", post(controllers::receive_github_hook))
+        .route("/hooks/{integration}", post(controllers::receive_hook))
+        .into_router(&mut catalog);
 
     // Public routes with optional auth (for viewing content)
-    let public_view_routes = Router::new()
+    let public_view_routes = Routes::new(AuthLevel::Optional)
+        .route("/site", get(controllers::get_current_site))
+        .route("/bootstrap", get(controllers::get_bootstrap))
         .route("/posts", get(controllers::list_posts))
+        .route("/posts/batch", post(controllers::get_posts_batch))
+        .route("/posts/trending", get(controllers::get_trending_posts))
+        .route("/posts/archive/{year}", get(controllers::get_post_archive))
         .route("/posts/slug/{slug}", get(controllers::get_post_by_slug))
         .route("/categories", get(controllers::list_categories))
         .route("/categories/{id}", get(controllers::get_category))
         .route("/tags", get(controllers::list_tags))
+        .route("/tags/search", get(controllers::search_tags))
+        .route("/tags/cloud", get(controllers::get_tag_cloud))
         .route("/tags/{id}", get(controllers::get_tag))
+        .route("/polls/{id}", get(controllers::get_poll))
+        .route("/media/{id}/download", get(controllers::download_media))
+        .route(
+            "/polls/{id}/vote",
+            post(controllers::vote_poll).route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                blocklist_middleware,
+            )),
+        )
+        .route("/changelog", get(controllers::list_changelog))
+        .route("/changelog/feed.xml", get(controllers::changelog_feed))
+        .into_router(&mut catalog)
         .layer(middleware::from_fn_with_state(
             state.clone(),
             optional_auth_middleware,
         ));
 
-    // Auth-required routes (logout)
-    let auth_routes = Router::new()
+    // Auth-required routes (logout, own profile)
+    let auth_routes = Routes::new(AuthLevel::Authenticated)
         .route("/auth/logout", post(controllers::logout))
+        .route("/auth/sudo", post(controllers::sudo))
+        .route("/me", get(controllers::get_me))
+        .route("/me", put(controllers::update_me))
+        .route("/me", delete(controllers::delete_me).sudo(&state))
+        .route("/me/permissions", get(controllers::get_my_permissions))
+        .route("/admin/routes", get(controllers::list_routes))
+        .route("/me/logins", get(controllers::get_my_logins))
+        .route("/me/notifications", get(controllers::get_my_notifications))
+        .route(
+            "/me/notifications",
+            put(controllers::update_my_notifications),
+        )
+        .route("/me/password", put(controllers::change_my_password))
+        .route("/me/tokens", get(controllers::list_my_tokens))
+        .route("/me/tokens", post(controllers::create_my_token))
+        .route("/me/tokens/{id}", delete(controllers::revoke_my_token))
+        .route(
+            "/me/avatar",
+            put(controllers::upload_my_avatar).layer(DefaultBodyLimit::max(
+                state.media_service.max_upload_bytes(),
+            )),
+        )
+        .route("/me/avatar", delete(controllers::delete_my_avatar))
+        .into_router(&mut catalog)
         .layer(middleware::from_fn_with_state(
             state.clone(),
             auth_middleware,
         ));
 
-    // Admin-only content routes
-    let admin_post_routes = Router::new()
-        .route("/posts", post(controllers::create_post))
-        .route("/posts/{id}", put(controllers::update_post))
-        .route("/posts/{id}", delete(controllers::delete_post))
-        .layer(middleware::from_fn_with_state(
-            state.clone(),
-            admin_middleware,
-        ));
-
-    let admin_category_routes = Router::new()
-        .route("/categories", post(controllers::create_category))
-        .route("/categories/{id}", put(controllers::update_category))
-        .route("/categories/{id}", delete(controllers::delete_category))
+    // Content routes, guarded per permission
+    let content_routes = Routes::new(AuthLevel::Authenticated)
+        .route(
+            "/posts",
+            post(controllers::create_post).guard("posts:create"),
+        )
+        .route(
+            "/posts/from-template/{id}",
+            post(controllers::create_post_from_template).guard("posts:create"),
+        )
+        .route(
+            "/post-templates",
+            get(controllers::list_post_templates).guard("posts:create"),
+        )
+        .route(
+            "/posts/{id}",
+            put(controllers::update_post).guard_any(&["posts:update_own", "posts:update_any"]),
+        )
+        .route(
+            "/posts/{id}",
+            delete(controllers::delete_post).guard_any(&["posts:delete_own", "posts:delete_any"]),
+        )
+        .route(
+            "/posts/{id}/publish",
+            post(controllers::publish_post).guard("posts:publish"),
+        )
+        .route(
+            "/posts/{id}/submit",
+            post(controllers::submit_post).guard_any(&["posts:update_own", "posts:update_any"]),
+        )
+        .route(
+            "/posts/{id}/approve",
+            post(controllers::approve_post).guard("posts:review"),
+        )
+        .route(
+            "/posts/{id}/reject",
+            post(controllers::reject_post).guard("posts:review"),
+        )
+        .route(
+            "/posts/{id}/reviews",
+            get(controllers::get_post_reviews).guard_any(&[
+                "posts:update_own",
+                "posts:update_any",
+                "posts:review",
+            ]),
+        )
+        .route(
+            "/posts/review-queue",
+            get(controllers::get_review_queue).guard("posts:review"),
+        )
+        .route(
+            "/posts/{id}/notes",
+            get(controllers::list_post_notes)
+                .post(controllers::create_post_note)
+                .guard_any(&["posts:update_own", "posts:update_any"]),
+        )
+        .route(
+            "/posts/{id}/notes/{note_id}",
+            delete(controllers::delete_post_note)
+                .guard_any(&["posts:update_own", "posts:update_any"]),
+        )
+        .route(
+            "/posts/{id}/revisions",
+            get(controllers::list_post_revisions)
+                .guard_any(&["posts:update_own", "posts:update_any"]),
+        )
+        .route(
+            "/posts/{id}/revisions/{a}/diff/{b}",
+            get(controllers::get_revision_diff)
+                .guard_any(&["posts:update_own", "posts:update_any"]),
+        )
+        .route(
+            "/posts/{id}/lock",
+            get(controllers::get_post_lock)
+                .post(controllers::lock_post)
+                .delete(controllers::unlock_post)
+                .guard_any(&["posts:update_own", "posts:update_any"]),
+        )
+        .route(
+            "/posts/{id}/title-test",
+            get(controllers::get_title_test)
+                .put(controllers::start_title_test)
+                .delete(controllers::stop_title_test)
+                .guard_any(&["posts:update_own", "posts:update_any"]),
+        )
+        .route(
+            "/posts/{id}/title-test/pick",
+            post(controllers::pick_title).guard_any(&["posts:update_own", "posts:update_any"]),
+        )
+        .route(
+            "/posts/{id}/preview-token",
+            post(controllers::create_preview_token)
+                .guard_any(&["posts:update_own", "posts:update_any"]),
+        )
+        .route(
+            "/categories",
+            post(controllers::create_category).guard("categories:create"),
+        )
+        .route(
+            "/categories/{id}",
+            put(controllers::update_category).guard("categories:update"),
+        )
+        .route(
+            "/categories/{id}",
+            delete(controllers::delete_category).guard("categories:delete"),
+        )
+        .route(
+            "/categories/{id}/merge",
+            post(controllers::merge_category).guard("categories:delete"),
+        )
+        .route(
+            "/categories/deleted",
+            get(controllers::list_deleted_categories).guard("categories:delete"),
+        )
+        .route(
+            "/categories/{id}/restore",
+            post(controllers::restore_category).guard("categories:delete"),
+        )
+        .route("/tags", post(controllers::create_tag).guard("tags:create"))
+        .route(
+            "/tags/{id}",
+            put(controllers::update_tag).guard("tags:update"),
+        )
+        .route(
+            "/tags/{id}",
+            delete(controllers::delete_tag).guard("tags:delete"),
+        )
+        .route(
+            "/tags/orphans",
+            get(controllers::list_orphan_tags).guard("tags:delete"),
+        )
+        .route(
+            "/tags/orphans",
+            delete(controllers::delete_orphan_tags).guard("tags:delete"),
+        )
+        .route(
+            "/tags/{id}/merge",
+            post(controllers::merge_tag).guard("tags:delete"),
+        )
+        .route(
+            "/tags/deleted",
+            get(controllers::list_deleted_tags).guard("tags:delete"),
+        )
+        .route(
+            "/tags/{id}/restore",
+            post(controllers::restore_tag).guard("tags:delete"),
+        )
+        .route("/polls", get(controllers::list_polls).guard("polls:update"))
+        .route(
+            "/media",
+            get(controllers::list_media).guard_any(MEDIA_PERMISSIONS),
+        )
+        .route(
+            "/media",
+            post(controllers::upload_media)
+                .layer(DefaultBodyLimit::max(
+                    state.media_service.max_upload_bytes(),
+                ))
+                .guard("media:create"),
+        )
+        .route(
+            "/media/uploads",
+            post(controllers::start_upload).guard("media:create"),
+        )
+        .route(
+            "/media/uploads/{id}",
+            get(controllers::get_upload).guard("media:create"),
+        )
+        .route(
+            "/media/uploads/{id}",
+            patch(controllers::upload_chunk)
+                .layer(DefaultBodyLimit::max(
+                    state.media_service.max_upload_bytes(),
+                ))
+                .guard("media:create"),
+        )
+        .route(
+            "/media/uploads/{id}",
+            delete(controllers::cancel_upload).guard("media:create"),
+        )
+        .route(
+            "/media/{id}",
+            get(controllers::get_media).guard_any(MEDIA_PERMISSIONS),
+        )
+        .route(
+            "/media/{id}",
+            put(controllers::update_media).guard("media:update"),
+        )
+        .route(
+            "/media/{id}",
+            delete(controllers::delete_media).guard("media:delete"),
+        )
+        .route(
+            "/media/{id}/usage",
+            get(controllers::get_media_usage).guard_any(MEDIA_PERMISSIONS),
+        )
+        .route(
+            "/sync/changes",
+            get(controllers::get_sync_changes).guard("sync:read"),
+        )
+        .route(
+            "/polls",
+            post(controllers::create_poll).guard("polls:create"),
+        )
+        .route(
+            "/polls/{id}",
+            put(controllers::update_poll).guard("polls:update"),
+        )
+        .route(
+            "/polls/{id}",
+            delete(controllers::delete_poll).guard("polls:delete"),
+        )
+        .into_router(&mut catalog)
         .layer(middleware::from_fn_with_state(
             state.clone(),
-            admin_middleware,
-        ));
-
-    let admin_tag_routes = Router::new()
-        .route("/tags", post(controllers::create_tag))
-        .route("/tags/{id}", put(controllers::update_tag))
-        .route("/tags/{id}", delete(controllers::delete_tag))
+            content_modified_middleware,
+        ))
         .layer(middleware::from_fn_with_state(
             state.clone(),
-            admin_middleware,
+            auth_middleware,
         ));
 
-    // Admin-only RBAC management routes
-    let admin_user_routes = Router::new()
+    // Admin-only RBAC management routes (deletions also require sudo mode)
+    let admin_user_routes = Routes::new(AuthLevel::Admin)
         .route("/users", get(controllers::list_users))
         .route("/users", post(controllers::create_user))
+        .route("/users/invite", post(controllers::invite_user))
         .route("/users/{id}", get(controllers::get_user))
-        .route("/users/{id}", delete(controllers::delete_user))
+        .route("/users/{id}", put(controllers::update_user))
+        .route("/users/{id}", delete(controllers::delete_user).sudo(&state))
+        .route(
+            "/users/{id}/erase",
+            delete(controllers::erase_user).sudo(&state),
+        )
+        .route("/users/{id}/logout", post(controllers::logout_user))
+        .route("/users/{id}/approve", post(controllers::approve_user))
+        .route("/users/{id}/deactivate", post(controllers::deactivate_user))
+        .route("/users/{id}/activate", post(controllers::activate_user))
+        .route("/users/{id}/restore", post(controllers::restore_user))
+        .route(
+            "/users/{id}/purge",
+            delete(controllers::purge_user).sudo(&state),
+        )
+        .into_router(&mut catalog)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            content_modified_middleware,
+        ))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             admin_middleware,
         ));
 
-    let admin_role_routes = Router::new()
+    let admin_role_routes = Routes::new(AuthLevel::Admin)
         .route("/roles", get(controllers::list_roles))
         .route("/roles", post(controllers::create_role))
         .route("/roles/{id}", get(controllers::get_role))
         .route("/roles/{id}", put(controllers::update_role))
-        .route("/roles/{id}", delete(controllers::delete_role))
+        .route("/roles/{id}", delete(controllers::delete_role).sudo(&state))
+        .route("/roles/{id}/users", get(controllers::list_role_users))
         .route(
             "/roles/{id}/permissions",
             get(controllers::get_role_permissions),
@@ -162,22 +905,280 @@ pub fn create_router(state: AppState) -> Router {
             delete(controllers::remove_permission),
         )
         .route("/permissions", get(controllers::list_permissions))
+        .into_router(&mut catalog)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            admin_middleware,
+        ));
+
+    let admin_config_routes = Routes::new(AuthLevel::Admin)
+        .route(
+            "/admin/config/runtime",
+            get(controllers::get_runtime_config),
+        )
+        .route(
+            "/admin/config/reload",
+            post(controllers::reload_runtime_config),
+        )
+        .route("/admin/sites", get(controllers::list_sites))
+        .route("/admin/sites", post(controllers::create_site))
+        .route("/admin/sites/{id}", put(controllers::update_site))
+        .route("/admin/changelog", get(controllers::list_changelog_entries))
+        .route(
+            "/admin/changelog",
+            post(controllers::create_changelog_entry),
+        )
+        .route(
+            "/admin/changelog/{id}",
+            put(controllers::update_changelog_entry),
+        )
+        .route(
+            "/admin/changelog/{id}",
+            delete(controllers::delete_changelog_entry),
+        )
+        .route(
+            "/admin/post-templates",
+            post(controllers::create_post_template),
+        )
+        .route(
+            "/admin/post-templates/{id}",
+            put(controllers::update_post_template).delete(controllers::delete_post_template),
+        )
+        .route("/admin/backups", get(controllers::list_backups))
+        .route("/admin/backups", post(controllers::create_backup))
+        .route("/admin/cache/warm", post(controllers::warm_cache))
+        .route(
+            "/admin/email-templates",
+            get(controllers::list_email_templates),
+        )
+        .route(
+            "/admin/email-templates/{name}",
+            get(controllers::get_email_template),
+        )
+        .route(
+            "/admin/email-templates/{name}",
+            put(controllers::update_email_template),
+        )
+        .route(
+            "/admin/email-templates/{name}",
+            delete(controllers::reset_email_template),
+        )
+        .route(
+            "/admin/email-templates/{name}/preview",
+            post(controllers::preview_email_template),
+        )
+        .route("/admin/hooks", get(controllers::list_hook_deliveries))
+        .route("/admin/hooks/{id}", get(controllers::get_hook_delivery))
+        .route(
+            "/admin/hooks/{id}/replay",
+            post(controllers::replay_hook_delivery),
+        )
+        .route("/admin/jobs/failed", get(controllers::list_failed_jobs))
+        .route("/admin/jobs/failed", delete(controllers::purge_failed_jobs))
+        .route(
+            "/admin/jobs/failed/retry",
+            post(controllers::retry_failed_jobs),
+        )
+        .route(
+            "/admin/jobs/failed/{id}",
+            delete(controllers::delete_failed_job),
+        )
+        .route(
+            "/admin/jobs/failed/{id}/retry",
+            post(controllers::retry_failed_job),
+        )
+        .route(
+            "/admin/webhooks/attempts",
+            get(controllers::list_webhook_attempts),
+        )
+        .route(
+            "/admin/webhooks/attempts/{id}",
+            get(controllers::get_webhook_attempt),
+        )
+        .route(
+            "/admin/webhooks/attempts/{id}/redeliver",
+            post(controllers::redeliver_webhook_attempt),
+        )
+        .route("/admin/taxonomy/import", post(controllers::import_taxonomy))
+        .route("/admin/search", get(controllers::admin_search))
+        .route("/admin/auth/status", get(controllers::get_auth_status))
+        .route(
+            "/admin/diagnostics/slow-queries",
+            get(controllers::list_slow_queries),
+        )
+        .route("/admin/quotas", get(controllers::get_quota))
+        .route("/admin/quotas", delete(controllers::reset_quota))
+        .route("/admin/activity", get(controllers::list_activity))
+        .route("/admin/views", get(controllers::get_daily_views))
+        .route(
+            "/admin/views/locations",
+            get(controllers::get_view_locations),
+        )
+        .route("/admin/blocklist", get(controllers::list_blocklist))
+        .route(
+            "/admin/blocklist",
+            post(controllers::create_blocklist_entry),
+        )
+        .route(
+            "/admin/blocklist/{id}",
+            delete(controllers::delete_blocklist_entry),
+        )
+        .into_router(&mut catalog)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            content_modified_middleware,
+        ))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             admin_middleware,
         ));
 
     // Combine all routes under /api prefix
-    Router::new()
+    let mut router = Router::new()
+        .route("/.well-known/jwks.json", routing::get(controllers::jwks))
+        .nest_service(
+            MEDIA_URL_PREFIX,
+            ServeDir::new(state.media_service.upload_dir()),
+        )
         .nest("/api", public_routes)
         .nest("/api", public_view_routes)
         .nest("/api", auth_routes)
-        .nest("/api", admin_post_routes)
-        .nest("/api", admin_category_routes)
-        .nest("/api", admin_tag_routes)
+        .nest("/api", content_routes)
         .nest("/api", admin_user_routes)
         .nest("/api", admin_role_routes)
+        .nest("/api", admin_config_routes);
+    if let Some(dir) = &state.frontend_dir {
+        router = router.merge(frontend_routes(dir));
+    }
+
+    router
+        .layer(Extension(Arc::new(catalog)))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            cache_headers_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            quota_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            site_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            maintenance_middleware,
+        ))
         .with_state(state)
         .layer(TraceLayer::new_for_http())
         .layer(cors)
 }
+
+/// Serve a built single-page frontend: files from `dir`, hashed assets from
+/// `dir/assets`, and `index.html` for every other path so client-side routes
+/// load the app.
+fn frontend_routes<S>(dir: &Path) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let assets_dir = dir.join(FRONTEND_ASSETS_PREFIX.trim_matches('/'));
+    let index = ServeFile::new(dir.join("index.html"));
+    Router::new()
+        .nest_service(
+            FRONTEND_ASSETS_PREFIX.trim_end_matches('/'),
+            ServeDir::new(assets_dir),
+        )
+        .fallback_service(ServeDir::new(dir).fallback(index))
+        .layer(middleware::from_fn(frontend_middleware))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::{
+        body::Body,
+        extract::Request,
+        http::{header, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_routes_record_access() {
+        async fn ok() {}
+
+        let mut catalog = Vec::new();
+        let _router = Routes::new(AuthLevel::Authenticated)
+            .route(
+                "/posts/{id}/lock",
+                get(ok)
+                    .post(ok)
+                    .guard_any(&["posts:update_own", "posts:update_any"]),
+            )
+            .route(
+                "/me",
+                delete(ok).route_layer(middleware::from_fn(
+                    |request: Request, next: middleware::Next| next.run(request),
+                )),
+            )
+            .into_router(&mut catalog);
+
+        let methods: Vec<_> = catalog
+            .iter()
+            .map(|route| (route.method, route.path.as_str()))
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                ("GET", "/api/posts/{id}/lock"),
+                ("POST", "/api/posts/{id}/lock"),
+                ("DELETE", "/api/me"),
+            ]
+        );
+        assert_eq!(catalog[1].auth, AuthLevel::Authenticated);
+        assert_eq!(
+            catalog[1].permissions,
+            ["posts:update_own", "posts:update_any"]
+        );
+        assert!(catalog[2].permissions.is_empty());
+        assert!(!catalog[2].sudo);
+    }
+
+    #[tokio::test]
+    async fn test_frontend_routes() {
+        let dir = std::env::temp_dir().join(format!("pw-frontend-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        std::fs::write(dir.join("index.html"), "<html></html>").unwrap();
+        std::fs::write(dir.join("assets/app-3f2a.js"), "console.log(1)").unwrap();
+        let router: Router = frontend_routes(&dir);
+        let get = |path: &str| {
+            router
+                .clone()
+                .oneshot(Request::builder().uri(path).body(Body::empty()).unwrap())
+        };
+
+        let response = get("/assets/app-3f2a.js").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=31536000, immutable"
+        );
+
+        let response = get("/posts/hello-world").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+        assert!(response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html"));
+
+        let response = get("/assets/missing.js").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(!response.headers().contains_key(header::CACHE_CONTROL));
+
+        let response = get("/api/nope").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,112 @@
+//! Hot-reloadable runtime settings shared through `AppState`.
+//!
+//! Tunables are held in an [`ArcSwap`] so request handlers read them lock-free
+//! while a reload (SIGHUP or admin endpoint) atomically swaps in a new snapshot.
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::config::RuntimeConfig;
+use crate::error::AppError;
+
+/// Callback applying new filter directives to the tracing subscriber.
+pub type LogFilterReloader = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Shared handle to the current runtime settings.
+#[derive(Clone)]
+pub struct RuntimeSettings {
+    current: Arc<ArcSwap<RuntimeConfig>>,
+    log_reloader: Option<LogFilterReloader>,
+}
+
+impl RuntimeSettings {
+    /// Create a handle holding the given initial settings.
+    pub fn new(config: RuntimeConfig) -> Self {
+        Self {
+            current: Arc::new(ArcSwap::from_pointee(config)),
+            log_reloader: None,
+        }
+    }
+
+    /// Attach a callback used to apply log filter changes.
+    pub fn with_log_reloader(mut self, reloader: LogFilterReloader) -> Self {
+        self.log_reloader = Some(reloader);
+        self
+    }
+
+    /// Get a snapshot of the current settings.
+    pub fn current(&self) -> Arc<RuntimeConfig> {
+        self.current.load_full()
+    }
+
+    /// Apply new settings, updating the log filter before swapping them in.
+    ///
+    /// If the log filter is rejected the previous settings stay in effect.
+    pub fn apply(&self, config: RuntimeConfig) -> Result<Arc<RuntimeConfig>, AppError> {
+        if config.log_filter != self.current().log_filter {
+            if let Some(reloader) = &self.log_reloader {
+                reloader(&config.log_filter).map_err(|err| {
+                    AppError::ValidationError(format!("Invalid log filter: {}", err))
+                })?;
+            }
+        }
+
+        self.current.store(Arc::new(config));
+        Ok(self.current())
+    }
+
+    /// Re-read the configuration sources and apply the result.
+    pub fn reload(&self) -> Result<Arc<RuntimeConfig>, AppError> {
+        let config =
+            RuntimeConfig::load().map_err(|err| AppError::ValidationError(err.to_string()))?;
+        let applied = self.apply(config)?;
+        tracing::info!(
+            maintenance_mode = applied.maintenance_mode,
+            log_filter = %applied.log_filter,
+            "Runtime configuration reloaded"
+        );
+        Ok(applied)
+    }
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        Self::new(RuntimeConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_swaps_settings() {
+        let settings = RuntimeSettings::default();
+        let handle = settings.clone();
+
+        settings
+            .apply(RuntimeConfig {
+                maintenance_mode: true,
+                ..RuntimeConfig::default()
+            })
+            .unwrap();
+
+        assert!(handle.current().maintenance_mode);
+    }
+
+    #[test]
+    fn test_rejected_log_filter_keeps_previous_settings() {
+        let settings = RuntimeSettings::default()
+            .with_log_reloader(Arc::new(|_| Err("bad directive".to_string())));
+
+        let result = settings.apply(RuntimeConfig {
+            log_filter: "[[nope".to_string(),
+            maintenance_mode: true,
+            ..RuntimeConfig::default()
+        });
+
+        assert!(result.is_err());
+        assert!(!settings.current().maintenance_mode);
+    }
+}
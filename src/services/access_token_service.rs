@@ -0,0 +1,212 @@
+//! Personal access token service.
+
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{
+    CreateAccessTokenRequest, CreatedAccessTokenResponse, PersonalAccessToken, ACCESS_TOKEN_PREFIX,
+};
+use crate::repositories::{AccessTokenRepository, RoleRepository, UserRepository};
+
+/// Maximum number of tokens a user can hold.
+const MAX_TOKENS_PER_USER: usize = 20;
+/// Maximum length of a token name.
+const MAX_TOKEN_NAME_LEN: usize = 100;
+/// Number of leading token characters kept for display.
+const TOKEN_DISPLAY_PREFIX_LEN: usize = 12;
+
+/// Service for long-lived, scoped personal access tokens.
+#[derive(Clone)]
+pub struct AccessTokenService {
+    token_repo: AccessTokenRepository,
+    user_repo: UserRepository,
+    role_repo: RoleRepository,
+}
+
+impl AccessTokenService {
+    /// Create a new access token service.
+    pub fn new(
+        token_repo: AccessTokenRepository,
+        user_repo: UserRepository,
+        role_repo: RoleRepository,
+    ) -> Self {
+        Self {
+            token_repo,
+            user_repo,
+            role_repo,
+        }
+    }
+
+    /// List a user's tokens.
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<PersonalAccessToken>, AppError> {
+        self.token_repo.find_by_user(user_id).await
+    }
+
+    /// Create a token limited to `scopes`, each of which the owner must hold.
+    ///
+    /// The secret is returned once and only its hash is stored.
+    pub async fn create(
+        &self,
+        auth_user: &AuthUser,
+        request: CreateAccessTokenRequest,
+    ) -> Result<CreatedAccessTokenResponse, AppError> {
+        let name = request.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_TOKEN_NAME_LEN {
+            return Err(AppError::ValidationError(format!(
+                "name must be 1 to {} characters",
+                MAX_TOKEN_NAME_LEN
+            )));
+        }
+        if request.expires_in_days.is_some_and(|days| days <= 0) {
+            return Err(AppError::ValidationError(
+                "expires_in_days must be positive".to_string(),
+            ));
+        }
+        let mut scopes = request.scopes;
+        scopes.sort();
+        scopes.dedup();
+        if scopes.is_empty() {
+            return Err(AppError::ValidationError(
+                "At least one scope is required".to_string(),
+            ));
+        }
+        let held = self
+            .effective_permissions(auth_user.role_id, &auth_user.role_slug)
+            .await?;
+        if let Some(scope) = scopes.iter().find(|scope| !held.contains(scope)) {
+            return Err(AppError::Forbidden(format!(
+                "Cannot grant scope you do not hold: {}",
+                scope
+            )));
+        }
+        if self.token_repo.find_by_user(auth_user.id).await?.len() >= MAX_TOKENS_PER_USER {
+            return Err(AppError::Conflict(format!(
+                "At most {} tokens are allowed",
+                MAX_TOKENS_PER_USER
+            )));
+        }
+
+        let token = generate_token();
+        let expires_at = request
+            .expires_in_days
+            .map(|days| Utc::now() + Duration::days(days));
+        let details = self
+            .token_repo
+            .create(
+                auth_user.id,
+                name,
+                &token[..TOKEN_DISPLAY_PREFIX_LEN],
+                &hash_token(&token),
+                &scopes,
+                expires_at,
+            )
+            .await?;
+
+        Ok(CreatedAccessTokenResponse { token, details })
+    }
+
+    /// Revoke one of a user's tokens.
+    pub async fn revoke(&self, user_id: Uuid, id: Uuid) -> Result<(), AppError> {
+        if !self.token_repo.delete(user_id, id).await? {
+            return Err(AppError::NotFound("Token not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Resolve a personal access token to the user it acts for.
+    ///
+    /// The resulting permissions are the token's scopes that the owner still
+    /// holds, and admin privileges never carry over to tokens.
+    pub async fn authenticate(&self, token: &str) -> Result<AuthUser, AppError> {
+        let record = self
+            .token_repo
+            .find_by_hash(&hash_token(token))
+            .await?
+            .ok_or_else(|| AppError::JwtError("Invalid access token".to_string()))?;
+        if record.is_expired() {
+            return Err(AppError::JwtError("Access token has expired".to_string()));
+        }
+
+        let user = self
+            .user_repo
+            .find_by_id_with_role(record.user_id)
+            .await?
+            .filter(|user| user.is_active && user.approved_at.is_some())
+            .ok_or(AppError::Unauthorized)?;
+        let held = self
+            .effective_permissions(user.role_id, &user.role_slug)
+            .await?;
+        self.token_repo.touch(record.id).await?;
+
+        Ok(AuthUser {
+            id: user.id,
+            email: user.email,
+            role_id: user.role_id,
+            role_slug: user.role_slug,
+            permissions: record
+                .scopes
+                .into_iter()
+                .filter(|scope| held.contains(scope))
+                .collect(),
+            token_id: Some(record.id),
+            session_id: None,
+            permission_audit: None,
+        })
+    }
+
+    // Private helper methods
+
+    async fn effective_permissions(
+        &self,
+        role_id: Uuid,
+        role_slug: &str,
+    ) -> Result<Vec<String>, AppError> {
+        if role_slug == "admin" {
+            self.role_repo.get_all_permission_names().await
+        } else {
+            self.role_repo.get_permissions(role_id).await
+        }
+    }
+}
+
+/// Generate a new token secret: the prefix followed by 64 random hex digits.
+fn generate_token() -> String {
+    format!(
+        "{}{}{}",
+        ACCESS_TOKEN_PREFIX,
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+/// SHA-256 of a token, hex encoded.
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_token() {
+        let token = generate_token();
+        assert!(token.starts_with(ACCESS_TOKEN_PREFIX));
+        assert_eq!(token.len(), ACCESS_TOKEN_PREFIX.len() + 64);
+        assert_ne!(token, generate_token());
+    }
+
+    #[test]
+    fn test_hash_token() {
+        let hash = hash_token("pat_example");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_token("pat_example"));
+        assert_ne!(hash, hash_token("pat_other"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Account service for permanent account erasure.
+
+use chrono::Utc;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{ErasureReceipt, DELETED_USER_ID};
+use crate::repositories::{AuditRepository, UserRepository};
+use crate::services::{AuthService, MediaService};
+
+/// Service for erasing accounts on request (GDPR right to erasure).
+#[derive(Clone)]
+pub struct AccountService {
+    user_repo: UserRepository,
+    audit_repo: AuditRepository,
+    auth_service: AuthService,
+    media_service: MediaService,
+}
+
+impl AccountService {
+    /// Create a new account service.
+    pub fn new(
+        user_repo: UserRepository,
+        audit_repo: AuditRepository,
+        auth_service: AuthService,
+        media_service: MediaService,
+    ) -> Self {
+        Self {
+            user_repo,
+            audit_repo,
+            auth_service,
+            media_service,
+        }
+    }
+
+    /// Permanently erase an account.
+    ///
+    /// Personal data is deleted, authored posts are reassigned to the
+    /// deleted-user placeholder and all tokens are revoked. The audit receipt
+    /// holds no personal data beyond a SHA-256 of the email, so the erasure
+    /// can later be confirmed to the person who asked for it.
+    pub async fn erase(&self, user_id: Uuid, actor_id: Uuid) -> Result<ErasureReceipt, AppError> {
+        if user_id == DELETED_USER_ID {
+            return Err(AppError::Forbidden(
+                "The deleted-user placeholder cannot be erased".to_string(),
+            ));
+        }
+        let user = match self.user_repo.find_by_id(user_id).await? {
+            Some(user) => user,
+            None => self
+                .user_repo
+                .find_deleted_by_id(user_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound("User not found".to_string()))?,
+        };
+
+        self.auth_service.revoke_user_tokens(user_id).await?;
+        let (posts_reassigned, avatar_key) = self.user_repo.erase(user_id).await?;
+        if let Some(storage_key) = avatar_key {
+            if let Err(err) = self.media_service.remove_file(&storage_key).await {
+                tracing::warn!(error = %err, "Failed to remove erased user's avatar file");
+            }
+        }
+
+        let erased_at = Utc::now();
+        let entry = self
+            .audit_repo
+            .record(
+                Some(actor_id),
+                "user.erased",
+                "user",
+                Some(user_id),
+                json!({
+                    "requested_by": if actor_id == user_id { "self" } else { "admin" },
+                    "email_sha256": email_digest(&user.email),
+                    "posts_reassigned": posts_reassigned,
+                    "erased_at": erased_at,
+                }),
+            )
+            .await?;
+
+        Ok(ErasureReceipt {
+            user_id,
+            posts_reassigned,
+            audit_id: entry.id,
+            erased_at,
+        })
+    }
+}
+
+/// Hex SHA-256 of a normalized email address.
+fn email_digest(email: &str) -> String {
+    Sha256::digest(email.trim().to_lowercase().as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_digest_normalizes() {
+        assert_eq!(
+            email_digest(" Me@Example.com "),
+            email_digest("me@example.com")
+        );
+        assert_eq!(email_digest("me@example.com").len(), 64);
+    }
+}
@@ -0,0 +1,241 @@
+//! Activity service turning the audit log into the admin activity feed.
+//!
+//! Post changes reach the audit log as domain events, so every way of
+//! changing a post (the API or Git sync) shows up without the writers
+//! knowing about the feed. Entries are written in the background and a
+//! failed write is only logged.
+
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::models::{
+    ActivityActor, ActivityItem, ActivityQuery, AuditEntryWithActor, Post, PostStatus,
+};
+use crate::repositories::AuditRepository;
+use crate::response::Meta;
+use crate::services::{DomainEvent, EventOrigin, EventSubscriber};
+
+/// Service recording post changes to the audit log and listing recent activity.
+#[derive(Clone)]
+pub struct ActivityService {
+    audit_repo: AuditRepository,
+}
+
+impl ActivityService {
+    /// Create a new activity service.
+    pub fn new(audit_repo: AuditRepository) -> Self {
+        Self { audit_repo }
+    }
+
+    /// List activity, newest first, optionally by one actor.
+    pub async fn list(&self, query: ActivityQuery) -> Result<(Vec<ActivityItem>, Meta), AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+        let offset = (page - 1) * per_page;
+
+        let entries = self
+            .audit_repo
+            .find_all(query.actor_id, per_page, offset)
+            .await?;
+        let total = self.audit_repo.count(query.actor_id).await?;
+        let items = entries.into_iter().map(activity_item).collect();
+        Ok((items, Meta::new(page, per_page, total)))
+    }
+}
+
+impl EventSubscriber for ActivityService {
+    fn handle(&self, event: &DomainEvent) {
+        let (action, post) = post_action(event);
+        let origin = match event.origin() {
+            EventOrigin::Api => "api",
+            EventOrigin::Git => "git",
+        };
+        let details = json!({
+            "site_id": post.site_id,
+            "title": post.title,
+            "slug": post.slug,
+            "origin": origin,
+        });
+        let actor_id = event.actor_id();
+        let post_id = post.id;
+        let audit_repo = self.audit_repo.clone();
+        tokio::spawn(async move {
+            if let Err(err) = audit_repo
+                .record(actor_id, action, "post", Some(post_id), details)
+                .await
+            {
+                tracing::warn!(error = %err, action, %post_id, "Failed to record activity");
+            }
+        });
+    }
+}
+
+/// Audit action for a post event and the post it is about.
+fn post_action(event: &DomainEvent) -> (&'static str, &Post) {
+    match event {
+        DomainEvent::PostSaved { before, after, .. } => {
+            let action = match (before.as_ref().map(|post| post.status), after.status) {
+                (Some(PostStatus::Published), PostStatus::Published) => "post.updated",
+                (_, PostStatus::Published) => "post.published",
+                (Some(PostStatus::Published), _) => "post.unpublished",
+                (None, _) => "post.created",
+                (Some(PostStatus::InReview), PostStatus::Draft) => "post.rejected",
+                (Some(PostStatus::InReview), PostStatus::InReview) => "post.updated",
+                (_, PostStatus::InReview) => "post.submitted",
+                _ => "post.updated",
+            };
+            (action, after)
+        }
+        DomainEvent::PostDeleted { post, .. } => ("post.deleted", post),
+    }
+}
+
+fn activity_item(row: AuditEntryWithActor) -> ActivityItem {
+    let entry = row.entry;
+    let summary = describe(&entry.action, row.actor_name.as_deref(), &entry.details);
+    ActivityItem {
+        id: entry.id,
+        actor: entry.actor_id.map(|id| ActivityActor {
+            id,
+            name: row.actor_name,
+        }),
+        action: entry.action,
+        target_type: entry.target_type,
+        target_id: entry.target_id,
+        summary,
+        created_at: entry.created_at,
+    }
+}
+
+/// Human-readable line for an audit entry, e.g. "Ada published 'Hello'".
+fn describe(action: &str, actor_name: Option<&str>, details: &Value) -> String {
+    let actor = match actor_name {
+        Some(name) => name,
+        None if details["origin"] == "git" => "Git sync",
+        None => "Someone",
+    };
+    let title = details["title"].as_str().unwrap_or("a post");
+    match action {
+        "post.created" => format!("{} created '{}'", actor, title),
+        "post.updated" => format!("{} updated '{}'", actor, title),
+        "post.published" => format!("{} published '{}'", actor, title),
+        "post.unpublished" => format!("{} unpublished '{}'", actor, title),
+        "post.submitted" => format!("{} submitted '{}' for review", actor, title),
+        "post.rejected" => format!("{} sent '{}' back to draft", actor, title),
+        "post.deleted" => format!("{} deleted '{}'", actor, title),
+        "user.erased" if details["requested_by"] == "self" => {
+            format!("{} erased their account", actor)
+        }
+        "user.erased" => format!("{} erased a user account", actor),
+        _ => format!("{} performed {}", actor, action),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::models::PostVisibility;
+
+    fn post(status: PostStatus) -> Post {
+        Post {
+            id: Uuid::new_v4(),
+            site_id: Uuid::new_v4(),
+            title: "Hello".to_string(),
+            slug: "hello".to_string(),
+            content: String::new(),
+            excerpt: None,
+            status,
+            visibility: PostVisibility::default(),
+            author_id: Uuid::new_v4(),
+            category_id: None,
+            published_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn saved(before: Option<PostStatus>, after: PostStatus) -> DomainEvent {
+        DomainEvent::PostSaved {
+            before: before.map(post),
+            after: post(after),
+            origin: EventOrigin::Api,
+            actor_id: None,
+        }
+    }
+
+    #[test]
+    fn test_post_action() {
+        use PostStatus::*;
+
+        assert_eq!(post_action(&saved(None, Draft)).0, "post.created");
+        assert_eq!(post_action(&saved(None, Published)).0, "post.published");
+        assert_eq!(
+            post_action(&saved(Some(Draft), Published)).0,
+            "post.published"
+        );
+        assert_eq!(
+            post_action(&saved(Some(Published), Published)).0,
+            "post.updated"
+        );
+        assert_eq!(
+            post_action(&saved(Some(Published), Archived)).0,
+            "post.unpublished"
+        );
+        assert_eq!(post_action(&saved(Some(Draft), Draft)).0, "post.updated");
+        assert_eq!(
+            post_action(&saved(Some(Draft), InReview)).0,
+            "post.submitted"
+        );
+        assert_eq!(
+            post_action(&saved(Some(InReview), Draft)).0,
+            "post.rejected"
+        );
+        assert_eq!(
+            post_action(&saved(Some(InReview), Published)).0,
+            "post.published"
+        );
+        let deleted = DomainEvent::PostDeleted {
+            post: post(Draft),
+            origin: EventOrigin::Git,
+            actor_id: None,
+        };
+        assert_eq!(post_action(&deleted).0, "post.deleted");
+    }
+
+    #[test]
+    fn test_describe() {
+        let details = json!({ "title": "Hello", "origin": "api" });
+        assert_eq!(
+            describe("post.published", Some("Ega"), &details),
+            "Ega published 'Hello'"
+        );
+        assert_eq!(
+            describe("post.deleted", None, &details),
+            "Someone deleted 'Hello'"
+        );
+        assert_eq!(
+            describe(
+                "post.updated",
+                None,
+                &json!({ "title": "Hi", "origin": "git" })
+            ),
+            "Git sync updated 'Hi'"
+        );
+        assert_eq!(
+            describe(
+                "user.erased",
+                Some("Ada"),
+                &json!({ "requested_by": "admin" })
+            ),
+            "Ada erased a user account"
+        );
+        assert_eq!(
+            describe("site.moved", Some("Ada"), &json!({})),
+            "Ada performed site.moved"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Archive service serving a year of published posts in one payload.
+//!
+//! Archives are cached in Redis per site and year. Changes to posts published
+//! in a year drop that year's archive right away; anything else shown in it,
+//! like an author or category being renamed, shows up once it is refreshed.
+
+use std::time::Duration;
+
+use chrono::Datelike;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{Post, PostArchive, PostStatus};
+use crate::pkg::redis::keys;
+use crate::pkg::{CachePolicy, ResponseCache};
+use crate::repositories::PostRepository;
+use crate::services::{DomainEvent, EventSubscriber};
+
+/// Cached archives are refreshed hourly and served stale for up to a day.
+const ARCHIVE_CACHE: CachePolicy = CachePolicy {
+    fresh_for: Duration::from_secs(60 * 60),
+    stale_for: Duration::from_secs(24 * 60 * 60),
+};
+/// Earliest and latest year an archive can be asked for.
+const ARCHIVE_YEARS: std::ops::RangeInclusive<i32> = 1970..=9999;
+
+/// Service for yearly post archives.
+#[derive(Clone)]
+pub struct ArchiveService {
+    post_repo: PostRepository,
+    cache: ResponseCache,
+}
+
+impl ArchiveService {
+    /// Create a new archive service.
+    pub fn new(post_repo: PostRepository, cache: ResponseCache) -> Self {
+        Self { post_repo, cache }
+    }
+
+    /// A site's public, published posts of `year` grouped by month, from the
+    /// cache when possible.
+    pub async fn year(&self, site_id: Uuid, year: i32) -> Result<PostArchive, AppError> {
+        if !ARCHIVE_YEARS.contains(&year) {
+            return Err(AppError::NotFound("No archive for that year".to_string()));
+        }
+
+        let post_repo = self.post_repo.clone();
+        self.cache
+            .get_or_load(
+                keys::post_archive(&site_id, year),
+                ARCHIVE_CACHE,
+                move || async move {
+                    let posts = post_repo.find_published_in_year(site_id, year).await?;
+                    Ok(PostArchive::new(year, posts))
+                },
+            )
+            .await
+    }
+
+    /// Recompute and cache a site's archive of `year`.
+    pub async fn refresh(&self, site_id: Uuid, year: i32) -> Result<(), AppError> {
+        let posts = self.post_repo.find_published_in_year(site_id, year).await?;
+        self.cache
+            .put(
+                &keys::post_archive(&site_id, year),
+                &PostArchive::new(year, posts),
+                ARCHIVE_CACHE,
+            )
+            .await
+    }
+}
+
+impl EventSubscriber for ArchiveService {
+    fn handle(&self, event: &DomainEvent) {
+        let (before, after) = event.post_change();
+        let mut stale: Vec<String> = [before, after]
+            .into_iter()
+            .flatten()
+            .filter_map(archive_key)
+            .collect();
+        stale.dedup();
+        if stale.is_empty() {
+            return;
+        }
+
+        let cache = self.cache.clone();
+        tokio::spawn(async move {
+            if let Err(err) = cache.invalidate(&stale).await {
+                tracing::warn!(error = %err, "Failed to drop cached post archives");
+            }
+        });
+    }
+}
+
+/// Cache key of the archive a published post appears in.
+fn archive_key(post: &Post) -> Option<String> {
+    (post.status == PostStatus::Published).then(|| {
+        let year = post.published_at.unwrap_or(post.created_at).year();
+        keys::post_archive(&post.site_id, year)
+    })
+}
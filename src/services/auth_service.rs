@@ -1,20 +1,31 @@
 //! Authentication service with JWT and password handling.
 
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
-use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::jwk::JwkSet;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::config::Config;
-use crate::error::AppError;
-use crate::models::{LoginResponse, RefreshTokenResponse, UserWithRole};
+use crate::error::{AppError, FieldError};
+use crate::middleware::ClientInfo;
+use crate::models::{
+    AuthStatus, InvitationResponse, LoginEvent, LoginResponse, NotificationKind,
+    RefreshTokenResponse, SudoResponse, UserWithRole,
+};
+use crate::pkg::jwt::JwtKeys;
 use crate::pkg::redis::keys;
-use crate::repositories::{RoleRepository, UserRepository};
+use crate::pkg::{EmailTemplate, GeoIp, PasswordPolicy};
+use crate::repositories::{LoginEventRepository, RoleRepository, UserRepository};
+use crate::services::{EmailService, NotificationService};
 
 /// JWT claims structure.
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -35,30 +46,146 @@ pub struct Claims {
     pub iat: i64,
     /// Token type (access or refresh)
     pub token_type: String,
+    /// Issuer (this backend)
+    pub iss: String,
+    /// Audience the token is valid for
+    pub aud: String,
+    /// User's token version when issued; older versions are revoked
+    #[serde(default)]
+    pub ver: i32,
+}
+
+/// Invitation token claims.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InviteClaims {
+    /// Subject (invited email)
+    pub sub: String,
+    /// Role assigned on registration
+    pub role_id: String,
+    /// Token ID, consumed on acceptance
+    pub jti: String,
+    /// Expiration time
+    pub exp: i64,
+    /// Issued at
+    pub iat: i64,
+    /// Token type (always invite)
+    pub token_type: String,
+    /// Issuer (this backend)
+    pub iss: String,
+    /// Audience the token is valid for
+    pub aud: String,
+}
+
+/// How long an invitation link stays valid.
+const INVITE_EXPIRY_HOURS: i64 = 72;
+/// Number of entries returned from a user's login history.
+const LOGIN_HISTORY_LIMIT: i64 = 50;
+/// How long failed login attempts are remembered.
+const LOGIN_FAILURE_WINDOW_SECONDS: u64 = 24 * 3600;
+
+/// Seconds a client must wait after `failures` consecutive failed logins.
+///
+/// The first `free_attempts` failures are not delayed; each one after that
+/// doubles the wait, starting at one second and capped at `max_seconds`.
+fn login_backoff_seconds(failures: u32, free_attempts: u32, max_seconds: u64) -> Option<u64> {
+    let excess = failures.checked_sub(free_attempts).filter(|n| *n > 0)?;
+    Some(
+        1u64.checked_shl(excess - 1)
+            .unwrap_or(u64::MAX)
+            .min(max_seconds),
+    )
+}
+
+/// Whether a token carrying `token_version` survives the user's `current` version.
+fn token_version_current(token_version: i32, current: i32) -> bool {
+    token_version >= current
+}
+
+/// Decide what a failed Redis revocation check means: with `degraded_mode`
+/// on, a Redis error is returned as `Ok(Some(error))` so the caller falls back
+/// to [`degraded_version_check`]; every other outcome stands as it is.
+fn degraded_fallback(
+    redis_check: Result<(), AppError>,
+    degraded_mode: bool,
+) -> Result<Option<String>, AppError> {
+    match redis_check {
+        Err(AppError::RedisError(err)) if degraded_mode => Ok(Some(err)),
+        result => result.map(|()| None),
+    }
+}
+
+/// Revocation check while Redis is unavailable: the token must carry the
+/// user's `current` token version from Postgres (`None` once the user is gone).
+fn degraded_version_check(token_version: i32, current: Option<i32>) -> Result<(), AppError> {
+    match current {
+        Some(current) if token_version_current(token_version, current) => Ok(()),
+        _ => Err(AppError::JwtError("Token has been revoked".to_string())),
+    }
+}
+
+/// Hash verified when the login email is unknown, so the request does the
+/// same Argon2 work as a wrong password.
+fn dummy_password_hash() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(b"dummy-password", &salt)
+            .map(|hash| hash.to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// Access tokens accepted in degraded mode since startup.
+#[derive(Default)]
+struct DegradedStats {
+    validations: AtomicU64,
+    /// Unix seconds of the latest one (0 = never)
+    last_at: AtomicI64,
 }
 
 /// Authentication service.
 #[derive(Clone)]
 pub struct AuthService {
     config: Config,
+    keys: JwtKeys,
     user_repo: UserRepository,
     role_repo: RoleRepository,
+    login_event_repo: LoginEventRepository,
     redis: redis::aio::ConnectionManager,
+    email_service: EmailService,
+    notification_service: NotificationService,
+    geoip: GeoIp,
+    password_policy: PasswordPolicy,
+    degraded: Arc<DegradedStats>,
 }
 
 impl AuthService {
     /// Create a new auth service.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: Config,
+        keys: JwtKeys,
         user_repo: UserRepository,
         role_repo: RoleRepository,
+        login_event_repo: LoginEventRepository,
         redis: redis::aio::ConnectionManager,
+        email_service: EmailService,
+        notification_service: NotificationService,
+        geoip: GeoIp,
     ) -> Self {
         Self {
+            password_policy: PasswordPolicy::from_config(&config),
             config,
+            keys,
             user_repo,
             role_repo,
+            login_event_repo,
             redis,
+            email_service,
+            notification_service,
+            geoip,
+            degraded: Arc::default(),
         }
     }
 
@@ -82,22 +209,409 @@ impl AuthService {
     }
 
     /// Login user and return tokens.
-    pub async fn login(&self, email: &str, password: &str) -> Result<LoginResponse, AppError> {
+    ///
+    /// Repeated failures for the same email and IP are throttled with an
+    /// exponential backoff, and unknown emails cost as much as wrong passwords
+    /// so response times do not reveal which accounts exist. Successful logins
+    /// are recorded in the user's login history.
+    pub async fn login(
+        &self,
+        email: &str,
+        password: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginResponse, AppError> {
+        let ip = client.ip;
+        self.check_login_lockout(email, &ip).await?;
+
         // Find user by email with role
+        let user = self.user_repo.find_by_email_with_role(email).await?;
+
+        // Verify password, against a dummy hash when the user does not exist
+        let verified = match &user {
+            Some(user) => self.verify_password(password, &user.password_hash)?,
+            None => {
+                self.verify_password(password, dummy_password_hash())?;
+                false
+            }
+        };
+        let user = match user {
+            Some(user) if verified => user,
+            other => {
+                self.record_login_failure(email, &ip, other.as_ref())
+                    .await?;
+                return Err(AppError::Unauthorized);
+            }
+        };
+        self.clear_login_failures(email, &ip).await?;
+
+        // Deactivated users cannot sign in
+        if !user.is_active {
+            return Err(AppError::AccountDeactivated);
+        }
+
+        // Self-registered users wait for an admin
+        if user.approved_at.is_none() {
+            return Err(AppError::Forbidden(
+                "Account is awaiting approval".to_string(),
+            ));
+        }
+
+        self.record_login(&user, client).await?;
+        // Having just entered the password counts as a recent confirmation,
+        // but only for the session it signs in
+        self.issue_tokens(user, true).await
+    }
+
+    /// Get a user's most recent successful logins.
+    pub async fn login_history(&self, user_id: Uuid) -> Result<Vec<LoginEvent>, AppError> {
+        self.login_event_repo
+            .find_recent_by_user(user_id, LOGIN_HISTORY_LIMIT)
+            .await
+    }
+
+    /// Register a new account through the public sign-up form.
+    ///
+    /// Only available when `REGISTRATION_ENABLED` is set. The account is
+    /// created with the configured registration role but cannot sign in
+    /// until an admin approves it.
+    pub async fn register(&self, email: &str, name: &str, password: &str) -> Result<(), AppError> {
+        if !self.config.registration_enabled {
+            return Err(AppError::Forbidden("Registration is disabled".to_string()));
+        }
+
+        let email = email.trim();
+        let name = name.trim();
+        if email.parse::<lettre::Address>().is_err() {
+            return Err(AppError::ValidationError("email is invalid".to_string()));
+        }
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "name cannot be empty".to_string(),
+            ));
+        }
+        self.check_new_password("password", password, &[email, name])
+            .await?;
+        if self
+            .user_repo
+            .find_by_email_with_role(email)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::Conflict("Email already registered".to_string()));
+        }
+
+        let role = self
+            .role_repo
+            .find_by_slug(&self.config.registration_role)
+            .await?
+            .ok_or_else(|| {
+                AppError::InternalError(format!(
+                    "Registration role '{}' does not exist",
+                    self.config.registration_role
+                ))
+            })?;
+        let password_hash = self.hash_password(password)?;
+        self.user_repo
+            .create_pending(email, &password_hash, name, role.id)
+            .await?;
+        Ok(())
+    }
+
+    /// Approve a pending self-registered account and send a welcome email.
+    pub async fn approve(&self, user_id: Uuid) -> Result<UserWithRole, AppError> {
         let user = self
+            .user_repo
+            .find_by_id_with_role(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        if user.approved_at.is_some() || !self.user_repo.approve(user_id).await? {
+            return Err(AppError::Conflict(
+                "User is not awaiting approval".to_string(),
+            ));
+        }
+
+        let login_url = format!("{}/login", self.config.app_base_url.trim_end_matches('/'));
+        self.email_service
+            .send(
+                &user.email,
+                EmailTemplate::AccountApproved,
+                serde_json::json!({ "name": user.name, "login_url": login_url }),
+            )
+            .await?;
+
+        self.user_repo
+            .find_by_id_with_role(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+    }
+
+    /// Deactivate or reactivate an account. Deactivated users cannot sign
+    /// in and are signed out everywhere; their content stays untouched.
+    pub async fn set_active(&self, user_id: Uuid, active: bool) -> Result<UserWithRole, AppError> {
+        let user = self
+            .user_repo
+            .find_by_id_with_role(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        if user.is_active == active {
+            return Ok(user);
+        }
+
+        self.user_repo
+            .update(user_id, None, None, None, Some(active))
+            .await?;
+        if !active {
+            self.revoke_user_tokens(user_id).await?;
+        }
+
+        self.user_repo
+            .find_by_id_with_role(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+    }
+
+    /// ID of the role given to new users when none is picked (`DEFAULT_ROLE`).
+    pub async fn default_role_id(&self) -> Result<Uuid, AppError> {
+        let role = self
+            .role_repo
+            .find_by_slug(&self.config.default_role)
+            .await?
+            .ok_or_else(|| {
+                AppError::InternalError(format!(
+                    "Default role '{}' does not exist",
+                    self.config.default_role
+                ))
+            })?;
+        Ok(role.id)
+    }
+
+    /// Invite someone by email to register with a pre-assigned role, or the
+    /// default role when `role_id` is not given.
+    ///
+    /// The emailed link carries a signed, single-use token; the invitee picks
+    /// their own name and password when accepting it.
+    pub async fn invite(
+        &self,
+        email: &str,
+        role_id: Option<Uuid>,
+    ) -> Result<InvitationResponse, AppError> {
+        let email = email.trim();
+        if email.parse::<lettre::Address>().is_err() {
+            return Err(AppError::ValidationError("email is invalid".to_string()));
+        }
+        if self
             .user_repo
             .find_by_email_with_role(email)
             .await?
-            .ok_or(AppError::Unauthorized)?;
+            .is_some()
+        {
+            return Err(AppError::Conflict("Email already registered".to_string()));
+        }
+        let role_id = match role_id {
+            Some(role_id) => {
+                self.role_repo
+                    .find_by_id(role_id)
+                    .await?
+                    .ok_or_else(|| AppError::ValidationError("Role not found".to_string()))?;
+                role_id
+            }
+            None => self.default_role_id().await?,
+        };
+
+        let jti = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let expires_at = now + Duration::hours(INVITE_EXPIRY_HOURS);
+        let claims = InviteClaims {
+            sub: email.to_string(),
+            role_id: role_id.to_string(),
+            jti: jti.clone(),
+            exp: expires_at.timestamp(),
+            iat: now.timestamp(),
+            token_type: "invite".to_string(),
+            iss: self.config.jwt_issuer.clone(),
+            aud: self.config.jwt_audience.clone(),
+        };
+        let token = self.keys.encode(&claims)?;
+
+        let mut redis = self.redis.clone();
+        let _: () = redis
+            .set_ex(
+                keys::invitation(&jti),
+                email,
+                (INVITE_EXPIRY_HOURS * 3600) as u64,
+            )
+            .await?;
 
-        // Verify password
+        let link = format!(
+            "{}/accept-invite?token={}",
+            self.config.app_base_url.trim_end_matches('/'),
+            token
+        );
+        self.email_service
+            .send(
+                email,
+                EmailTemplate::Invitation,
+                serde_json::json!({ "invite_url": link, "expiry_hours": INVITE_EXPIRY_HOURS }),
+            )
+            .await?;
+
+        Ok(InvitationResponse {
+            email: email.to_string(),
+            role_id,
+            expires_at,
+        })
+    }
+
+    /// Complete registration from an invitation and sign the new user in.
+    pub async fn accept_invite(
+        &self,
+        token: &str,
+        name: &str,
+        password: &str,
+    ) -> Result<LoginResponse, AppError> {
+        let claims = self.keys.decode::<InviteClaims>(token)?;
+        if claims.token_type != "invite" {
+            return Err(AppError::JwtError("Invalid token type".to_string()));
+        }
+
+        // Invitations are single use
+        let key = keys::invitation(&claims.jti);
+        let mut redis = self.redis.clone();
+        let exists: bool = redis.exists(&key).await?;
+        if !exists {
+            return Err(AppError::JwtError(
+                "Invitation has been used or revoked".to_string(),
+            ));
+        }
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "name cannot be empty".to_string(),
+            ));
+        }
+        self.check_new_password("password", password, &[&claims.sub, name])
+            .await?;
+        if self
+            .user_repo
+            .find_by_email_with_role(&claims.sub)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::Conflict("Email already registered".to_string()));
+        }
+
+        let role_id = Uuid::parse_str(&claims.role_id)
+            .map_err(|_| AppError::JwtError("Invalid role ID in token".to_string()))?;
+        let password_hash = self.hash_password(password)?;
+        let created = self
+            .user_repo
+            .create(&claims.sub, &password_hash, name, role_id)
+            .await?;
+        let _: () = redis.del(&key).await?;
+
+        let user = self
+            .user_repo
+            .find_by_id_with_role(created.id)
+            .await?
+            .ok_or_else(|| AppError::InternalError("Failed to fetch created user".to_string()))?;
+        self.issue_tokens(user, false).await
+    }
+
+    /// Check a new password against the password policy.
+    ///
+    /// Problems are reported against `field`; `user_inputs` (email, name) are
+    /// treated as easy to guess.
+    pub async fn check_new_password(
+        &self,
+        field: &str,
+        password: &str,
+        user_inputs: &[&str],
+    ) -> Result<(), AppError> {
+        self.password_policy
+            .check(field, password, user_inputs)
+            .await
+    }
+
+    /// Enter sudo mode by confirming the password.
+    ///
+    /// Destructive actions guarded by `sudo_middleware` are allowed until the
+    /// returned expiry, for the session whose access token is `jti` only.
+    pub async fn sudo(
+        &self,
+        user_id: Uuid,
+        jti: &str,
+        password: &str,
+    ) -> Result<SudoResponse, AppError> {
+        let user = self
+            .user_repo
+            .find_by_id_with_role(user_id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
         if !self.verify_password(password, &user.password_hash)? {
-            return Err(AppError::Unauthorized);
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "password",
+                "is incorrect",
+            )]));
         }
+        self.grant_sudo(jti).await
+    }
 
+    /// Whether the password was confirmed recently enough for sudo mode in the
+    /// session whose access token is `jti`.
+    pub async fn has_sudo(&self, jti: &str) -> Result<bool, AppError> {
+        let mut redis = self.redis.clone();
+        Ok(redis.exists(keys::sudo(jti)).await?)
+    }
+
+    /// Change a user's password after confirming the current one.
+    ///
+    /// All of the user's tokens are revoked, so every session has to sign in
+    /// again with the new password.
+    pub async fn change_password(
+        &self,
+        user_id: Uuid,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<(), AppError> {
+        let user = self
+            .user_repo
+            .find_by_id_with_role(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        if !self.verify_password(current_password, &user.password_hash)? {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "current_password",
+                "is incorrect",
+            )]));
+        }
+        if new_password == current_password {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "new_password",
+                "must differ from the current password",
+            )]));
+        }
+        self.check_new_password("new_password", new_password, &[&user.email, &user.name])
+            .await?;
+
+        let password_hash = self.hash_password(new_password)?;
+        self.user_repo
+            .update_password(user_id, &password_hash)
+            .await?;
+        self.revoke_user_tokens(user_id).await
+    }
+
+    /// Create and store an access/refresh token pair for a user, in sudo mode
+    /// if `sudo` is set.
+    async fn issue_tokens(
+        &self,
+        user: UserWithRole,
+        sudo: bool,
+    ) -> Result<LoginResponse, AppError> {
         // Generate tokens
-        let (access_token, access_jti) = self.create_access_token(&user)?;
-        let (refresh_token, refresh_jti) = self.create_refresh_token(&user)?;
+        let version = self.token_version(user.id).await?;
+        let (access_token, access_jti) = self.create_access_token(&user, version)?;
+        let (refresh_token, refresh_jti) = self.create_refresh_token(&user, version)?;
 
         // Store tokens in Redis
         self.store_token(
@@ -114,6 +628,9 @@ impl AuthService {
             self.config.jwt_refresh_expiry_days * 86400,
         )
         .await?;
+        if sudo {
+            self.grant_sudo(&access_jti).await?;
+        }
 
         Ok(LoginResponse {
             access_token,
@@ -152,9 +669,13 @@ impl AuthService {
             .find_by_id_with_role(user_id)
             .await?
             .ok_or(AppError::NotFound("User not found".to_string()))?;
+        if !user.is_active {
+            return Err(AppError::AccountDeactivated);
+        }
+        self.check_token_version(&claims).await?;
 
         // Generate new access token
-        let (access_token, access_jti) = self.create_access_token(&user)?;
+        let (access_token, access_jti) = self.create_access_token(&user, claims.ver)?;
         self.store_token(
             &access_jti,
             &user.id,
@@ -172,26 +693,41 @@ impl AuthService {
 
     /// Logout user by revoking all tokens.
     pub async fn logout(&self, user_id: Uuid) -> Result<(), AppError> {
-        let mut redis = self.redis.clone();
-        let user_tokens_key = keys::user_tokens(&user_id);
+        self.revoke_user_tokens(user_id).await
+    }
 
-        // Get all token IDs for this user
-        let token_ids: Vec<String> = redis.smembers(&user_tokens_key).await?;
+    /// Revoke every access and refresh token issued to a user.
+    ///
+    /// Bumps the user's token version, so outstanding tokens are rejected
+    /// without having to look each of them up. Their Redis entries are left
+    /// to expire on their own.
+    pub async fn revoke_user_tokens(&self, user_id: Uuid) -> Result<(), AppError> {
+        let version = self.user_repo.bump_token_version(user_id).await?;
+        self.cache_token_version(user_id, version).await?;
 
-        // Delete all tokens
-        for token_id in token_ids {
-            let access_key = keys::access_token(&token_id);
-            let refresh_key = keys::refresh_token(&token_id);
-            let _: () = redis.del(&[&access_key, &refresh_key]).await?;
-        }
+        // Delete user tokens set; sudo grants die with the tokens they belong to
+        let mut redis = self.redis.clone();
+        let _: () = redis.del(keys::user_tokens(&user_id)).await?;
 
-        // Delete user tokens set
-        let _: () = redis.del(&user_tokens_key).await?;
+        Ok(())
+    }
 
+    /// Revoke the tokens of every user holding a role.
+    ///
+    /// Used when the role's slug changes, as the slug is embedded in tokens.
+    pub async fn revoke_role_tokens(&self, role_id: Uuid) -> Result<(), AppError> {
+        let versions = self.user_repo.bump_token_versions_by_role(role_id).await?;
+        for (user_id, version) in versions {
+            self.cache_token_version(user_id, Some(version)).await?;
+        }
         Ok(())
     }
 
     /// Validate an access token and return claims.
+    ///
+    /// With `AUTH_DEGRADED_MODE` on, a Redis outage does not reject the
+    /// token: only its signature, expiry and the user's token version in
+    /// Postgres are checked, and every such fallback is logged and counted.
     pub async fn validate_access_token(&self, token: &str) -> Result<Claims, AppError> {
         let claims = self.validate_token(token)?;
 
@@ -199,17 +735,26 @@ impl AuthService {
             return Err(AppError::JwtError("Invalid token type".to_string()));
         }
 
-        // Check if token is in Redis (not revoked)
-        let key = keys::access_token(&claims.jti);
-        let mut redis = self.redis.clone();
-        let exists: bool = redis.exists(&key).await?;
-        if !exists {
-            return Err(AppError::JwtError("Token has been revoked".to_string()));
+        let redis_check = self.check_access_token_revoked(&claims).await;
+        if let Some(err) = degraded_fallback(redis_check, self.config.auth_degraded_mode)? {
+            self.check_token_version_degraded(&claims, &err).await?;
         }
 
         Ok(claims)
     }
 
+    /// Degraded mode setting and how often it has been relied on.
+    pub fn status(&self) -> AuthStatus {
+        let last_at = self.degraded.last_at.load(Ordering::Relaxed);
+        AuthStatus {
+            degraded_mode: self.config.auth_degraded_mode,
+            degraded_validations: self.degraded.validations.load(Ordering::Relaxed),
+            last_degraded_at: (last_at > 0)
+                .then(|| DateTime::from_timestamp(last_at, 0))
+                .flatten(),
+        }
+    }
+
     /// Get user permissions by role ID.
     pub async fn get_user_permissions(&self, role_id: Uuid) -> Result<Vec<String>, AppError> {
         self.role_repo.get_permissions(role_id).await
@@ -221,9 +766,220 @@ impl AuthService {
         Ok(permissions.iter().any(|p| p == permission))
     }
 
+    /// Public verification keys for other services (empty for HS256).
+    pub fn jwks(&self) -> JwkSet {
+        self.keys.jwks()
+    }
+
     // Private helper methods
 
-    fn create_access_token(&self, user: &UserWithRole) -> Result<(String, String), AppError> {
+    /// Current token version of a user, cached in Redis.
+    ///
+    /// Tokens of users that no longer exist are treated as revoked.
+    async fn token_version(&self, user_id: Uuid) -> Result<i32, AppError> {
+        let key = keys::token_version(&user_id);
+        let mut redis = self.redis.clone();
+        if let Some(version) = redis.get::<_, Option<i32>>(&key).await? {
+            return Ok(version);
+        }
+
+        let version = self
+            .user_repo
+            .find_token_version(user_id)
+            .await?
+            .ok_or_else(|| AppError::JwtError("Token has been revoked".to_string()))?;
+        // NX so a concurrent bump is never overwritten with the stale value
+        let _: () = redis::cmd("SET")
+            .arg(&key)
+            .arg(version)
+            .arg("NX")
+            .arg("EX")
+            .arg(self.token_version_ttl_seconds())
+            .query_async(&mut redis)
+            .await?;
+        Ok(version)
+    }
+
+    async fn cache_token_version(
+        &self,
+        user_id: Uuid,
+        version: Option<i32>,
+    ) -> Result<(), AppError> {
+        let key = keys::token_version(&user_id);
+        let mut redis = self.redis.clone();
+        let _: () = match version {
+            Some(version) => {
+                redis
+                    .set_ex(&key, version, self.token_version_ttl_seconds())
+                    .await?
+            }
+            None => redis.del(&key).await?,
+        };
+        Ok(())
+    }
+
+    async fn check_access_token_revoked(&self, claims: &Claims) -> Result<(), AppError> {
+        // Check if token is in Redis (not revoked)
+        let key = keys::access_token(&claims.jti);
+        let mut redis = self.redis.clone();
+        let exists: bool = redis.exists(&key).await?;
+        if !exists {
+            return Err(AppError::JwtError("Token has been revoked".to_string()));
+        }
+        self.check_token_version(claims).await
+    }
+
+    /// Revocation check used while Redis is unreachable: compares the token
+    /// version against Postgres, skipping the per-token lookup.
+    async fn check_token_version_degraded(
+        &self,
+        claims: &Claims,
+        redis_error: &str,
+    ) -> Result<(), AppError> {
+        let validations = self.degraded.validations.fetch_add(1, Ordering::Relaxed) + 1;
+        self.degraded
+            .last_at
+            .store(Utc::now().timestamp(), Ordering::Relaxed);
+        tracing::error!(
+            error = redis_error,
+            user_id = %claims.sub,
+            degraded_validations = validations,
+            "Redis is unavailable; accepting access token without revocation checks"
+        );
+
+        let user_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::JwtError("Invalid user ID".to_string()))?;
+        let current = self.user_repo.find_token_version(user_id).await?;
+        degraded_version_check(claims.ver, current)
+    }
+
+    /// Reject tokens issued before the user's latest token version bump.
+    async fn check_token_version(&self, claims: &Claims) -> Result<(), AppError> {
+        let user_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::JwtError("Invalid user ID".to_string()))?;
+        if !token_version_current(claims.ver, self.token_version(user_id).await?) {
+            return Err(AppError::JwtError("Token has been revoked".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Cached versions outlive every token they could be compared against.
+    fn token_version_ttl_seconds(&self) -> u64 {
+        (self.config.jwt_refresh_expiry_days * 86400)
+            .max(self.config.jwt_access_expiry_hours * 3600) as u64
+    }
+
+    async fn grant_sudo(&self, jti: &str) -> Result<SudoResponse, AppError> {
+        let ttl_seconds = self.config.sudo_ttl_minutes * 60;
+        let mut redis = self.redis.clone();
+        let _: () = redis.set_ex(keys::sudo(jti), 1, ttl_seconds).await?;
+        Ok(SudoResponse {
+            expires_at: Utc::now() + Duration::seconds(ttl_seconds as i64),
+        })
+    }
+
+    async fn check_login_lockout(&self, email: &str, ip: &IpAddr) -> Result<(), AppError> {
+        let mut redis = self.redis.clone();
+        let ttl: i64 = redis.ttl(keys::login_lockout(email, ip)).await?;
+        if ttl > 0 {
+            return Err(AppError::TooManyRequests(ttl as u64));
+        }
+        Ok(())
+    }
+
+    async fn record_login_failure(
+        &self,
+        email: &str,
+        ip: &IpAddr,
+        user: Option<&UserWithRole>,
+    ) -> Result<(), AppError> {
+        let key = keys::login_failures(email, ip);
+        let mut redis = self.redis.clone();
+        let failures: u32 = redis.incr(&key, 1).await?;
+        let _: () = redis
+            .expire(&key, LOGIN_FAILURE_WINDOW_SECONDS as i64)
+            .await?;
+
+        if let Some(delay) = login_backoff_seconds(
+            failures,
+            self.config.login_free_attempts,
+            self.config.login_max_backoff_seconds,
+        ) {
+            let _: () = redis
+                .set_ex(keys::login_lockout(email, ip), failures, delay)
+                .await?;
+        }
+        tracing::warn!(email, %ip, failures, "Failed login attempt");
+
+        // Alert the owner in the background so the response time stays the same
+        if let Some(user) = user.filter(|_| self.config.login_alert_threshold == Some(failures)) {
+            self.notification_service
+                .notify(
+                    user.id,
+                    &user.email,
+                    NotificationKind::SecurityAlert,
+                    EmailTemplate::FailedLogins,
+                    serde_json::json!({ "name": user.name, "failures": failures, "ip": ip }),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Store a login event and email the owner if it comes from an
+    /// unfamiliar device or country.
+    async fn record_login(&self, user: &UserWithRole, client: &ClientInfo) -> Result<(), AppError> {
+        let user_agent = client.user_agent.as_deref();
+        let location = self
+            .geoip
+            .locate_client(client.ip, client.country.as_deref());
+        let country = location.as_ref().map(|l| l.country.as_str());
+        let region = location.as_ref().and_then(|l| l.region.as_deref());
+        let origin = self
+            .login_event_repo
+            .origin(user.id, user_agent, country)
+            .await?;
+        self.login_event_repo
+            .create(user.id, &client.ip.to_string(), user_agent, country, region)
+            .await?;
+
+        if origin.is_unfamiliar(country.is_some()) {
+            self.notification_service
+                .notify(
+                    user.id,
+                    &user.email,
+                    NotificationKind::SecurityAlert,
+                    EmailTemplate::NewLogin,
+                    serde_json::json!({
+                        "name": user.name,
+                        "ip": client.ip,
+                        "country": country.unwrap_or("unknown"),
+                        "device": user_agent.unwrap_or("unknown"),
+                    }),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn clear_login_failures(&self, email: &str, ip: &IpAddr) -> Result<(), AppError> {
+        let mut redis = self.redis.clone();
+        let _: () = redis
+            .del(&[
+                keys::login_failures(email, ip),
+                keys::login_lockout(email, ip),
+            ])
+            .await?;
+        Ok(())
+    }
+
+    fn create_access_token(
+        &self,
+        user: &UserWithRole,
+        version: i32,
+    ) -> Result<(String, String), AppError> {
         let jti = Uuid::new_v4().to_string();
         let now = Utc::now();
         let exp = now + Duration::hours(self.config.jwt_access_expiry_hours);
@@ -237,18 +993,21 @@ impl AuthService {
             exp: exp.timestamp(),
             iat: now.timestamp(),
             token_type: "access".to_string(),
+            iss: self.config.jwt_issuer.clone(),
+            aud: self.config.jwt_audience.clone(),
+            ver: version,
         };
 
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.config.jwt_secret.as_bytes()),
-        )?;
+        let token = self.keys.encode(&claims)?;
 
         Ok((token, jti))
     }
 
-    fn create_refresh_token(&self, user: &UserWithRole) -> Result<(String, String), AppError> {
+    fn create_refresh_token(
+        &self,
+        user: &UserWithRole,
+        version: i32,
+    ) -> Result<(String, String), AppError> {
         let jti = Uuid::new_v4().to_string();
         let now = Utc::now();
         let exp = now + Duration::days(self.config.jwt_refresh_expiry_days);
@@ -262,25 +1021,18 @@ impl AuthService {
             exp: exp.timestamp(),
             iat: now.timestamp(),
             token_type: "refresh".to_string(),
+            iss: self.config.jwt_issuer.clone(),
+            aud: self.config.jwt_audience.clone(),
+            ver: version,
         };
 
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.config.jwt_secret.as_bytes()),
-        )?;
+        let token = self.keys.encode(&claims)?;
 
         Ok((token, jti))
     }
 
     fn validate_token(&self, token: &str) -> Result<Claims, AppError> {
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(self.config.jwt_secret.as_bytes()),
-            &Validation::default(),
-        )?;
-
-        Ok(token_data.claims)
+        self.keys.decode::<Claims>(token)
     }
 
     async fn store_token(
@@ -338,6 +1090,27 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn test_invite_claims_not_accepted_as_session_claims() {
+        let config = Config::default();
+        let keys = JwtKeys::from_config(&config).unwrap();
+        let token = keys
+            .encode(&InviteClaims {
+                sub: "new@example.com".to_string(),
+                role_id: Uuid::new_v4().to_string(),
+                jti: Uuid::new_v4().to_string(),
+                exp: (Utc::now() + Duration::hours(1)).timestamp(),
+                iat: Utc::now().timestamp(),
+                token_type: "invite".to_string(),
+                iss: config.jwt_issuer.clone(),
+                aud: config.jwt_audience.clone(),
+            })
+            .unwrap();
+
+        assert!(keys.decode::<InviteClaims>(&token).is_ok());
+        assert!(keys.decode::<Claims>(&token).is_err());
+    }
+
     #[test]
     fn test_claims_serialization() {
         let claims = Claims {
@@ -349,6 +1122,9 @@ mod tests {
             exp: 1234567890,
             iat: 1234567800,
             token_type: "access".to_string(),
+            iss: "personal-website".to_string(),
+            aud: "personal-website-api".to_string(),
+            ver: 3,
         };
 
         let json = serde_json::to_string(&claims).unwrap();
@@ -356,4 +1132,77 @@ mod tests {
         assert!(json.contains("admin"));
         assert!(json.contains("role_slug"));
     }
+
+    #[test]
+    fn test_claims_without_version_default_to_zero() {
+        let json = r#"{"sub":"u","email":"e","role_id":"r","role_slug":"admin","jti":"j","exp":2,"iat":1,"token_type":"access","iss":"i","aud":"a"}"#;
+        let claims: Claims = serde_json::from_str(json).unwrap();
+        assert_eq!(claims.ver, 0);
+    }
+
+    #[test]
+    fn test_token_version_current() {
+        assert!(token_version_current(0, 0));
+        assert!(token_version_current(2, 2));
+        assert!(!token_version_current(1, 2));
+    }
+
+    #[test]
+    fn test_login_backoff_seconds() {
+        assert_eq!(login_backoff_seconds(0, 5, 900), None);
+        assert_eq!(login_backoff_seconds(5, 5, 900), None);
+        assert_eq!(login_backoff_seconds(6, 5, 900), Some(1));
+        assert_eq!(login_backoff_seconds(8, 5, 900), Some(4));
+        assert_eq!(login_backoff_seconds(20, 5, 900), Some(900));
+        assert_eq!(login_backoff_seconds(200, 5, 900), Some(900));
+    }
+
+    #[test]
+    fn test_dummy_password_hash_parses() {
+        assert!(PasswordHash::new(dummy_password_hash()).is_ok());
+    }
+
+    fn redis_down() -> Result<(), AppError> {
+        Err(AppError::RedisError("Connection refused".to_string()))
+    }
+
+    #[test]
+    fn test_degraded_fallback_off() {
+        assert!(matches!(
+            degraded_fallback(redis_down(), false),
+            Err(AppError::RedisError(_))
+        ));
+        assert!(matches!(degraded_fallback(Ok(()), false), Ok(None)));
+    }
+
+    #[test]
+    fn test_degraded_fallback_on() {
+        assert_eq!(
+            degraded_fallback(redis_down(), true).unwrap().as_deref(),
+            Some("Connection refused")
+        );
+        assert!(matches!(degraded_fallback(Ok(()), true), Ok(None)));
+        // Revocations Redis could answer still reject the token
+        let revoked = Err(AppError::JwtError("Token has been revoked".to_string()));
+        assert!(matches!(
+            degraded_fallback(revoked, true),
+            Err(AppError::JwtError(_))
+        ));
+        assert!(degraded_version_check(3, Some(3)).is_ok());
+    }
+
+    #[test]
+    fn test_degraded_revoked_token_version() {
+        assert!(degraded_fallback(redis_down(), true).unwrap().is_some());
+        // Password changed or sessions revoked: the version in Postgres moved on
+        assert!(matches!(
+            degraded_version_check(3, Some(4)),
+            Err(AppError::JwtError(_))
+        ));
+        // User deleted
+        assert!(matches!(
+            degraded_version_check(3, None),
+            Err(AppError::JwtError(_))
+        ));
+    }
 }
@@ -0,0 +1,451 @@
+//! Blocklist service deciding which clients may use public write endpoints.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+use hyper::Method;
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, FieldError};
+use crate::models::{BlocklistEntry, BlocklistKind, BlocklistQuery, CreateBlocklistEntryRequest};
+use crate::pkg::http_client;
+use crate::pkg::redis::keys;
+use crate::repositories::BlocklistRepository;
+use crate::response::Meta;
+
+/// AbuseIPDB endpoint reporting the abuse confidence score of an address.
+const ABUSEIPDB_CHECK_URL: &str = "https://api.abuseipdb.com/api/v2/check";
+/// Longest an AbuseIPDB lookup may hold up a request before it is let through.
+const ABUSEIPDB_TIMEOUT: Duration = Duration::from_secs(2);
+/// Days of AbuseIPDB reports taken into account.
+const ABUSEIPDB_MAX_AGE_DAYS: u32 = 90;
+/// Longest domain name.
+const MAX_DOMAIN_LEN: usize = 253;
+/// Cached verdict of a blocked address.
+const BLOCKED: &str = "blocked";
+/// Cached verdict of an allowed address.
+const ALLOWED: &str = "allowed";
+
+/// Service for the IP and email domain blocklist.
+///
+/// Verdicts per IP address combine the admin-managed entries with the
+/// AbuseIPDB score (when an API key is configured) and are cached in Redis.
+/// The cache is best effort: without Redis every check goes to the database.
+#[derive(Clone)]
+pub struct BlocklistService {
+    repo: BlocklistRepository,
+    redis: redis::aio::ConnectionManager,
+    cache_seconds: u64,
+    abuseipdb_api_key: Option<String>,
+    abuseipdb_min_score: u8,
+    abuseipdb_timeout: Duration,
+}
+
+impl BlocklistService {
+    /// Create a new blocklist service.
+    pub fn new(
+        config: &Config,
+        repo: BlocklistRepository,
+        redis: redis::aio::ConnectionManager,
+    ) -> Self {
+        Self {
+            repo,
+            redis,
+            cache_seconds: config.blocklist_cache_seconds,
+            abuseipdb_api_key: config.abuseipdb_api_key.clone(),
+            abuseipdb_min_score: config.abuseipdb_min_score,
+            abuseipdb_timeout: config.http_client_timeout().min(ABUSEIPDB_TIMEOUT),
+        }
+    }
+
+    /// List blocklist entries, newest first.
+    pub async fn list(
+        &self,
+        query: BlocklistQuery,
+    ) -> Result<(Vec<BlocklistEntry>, Meta), AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+        let offset = (page - 1) * per_page;
+
+        let entries = self.repo.find_all(query.kind, per_page, offset).await?;
+        let total = self.repo.count(query.kind).await?;
+        Ok((entries, Meta::new(page, per_page, total)))
+    }
+
+    /// Block an IP address, CIDR range or email domain.
+    pub async fn create(
+        &self,
+        created_by: Uuid,
+        request: CreateBlocklistEntryRequest,
+    ) -> Result<BlocklistEntry, AppError> {
+        let value = match request.kind {
+            BlocklistKind::Ip => normalize_network(&request.value).ok_or_else(|| {
+                invalid_value("must be an IP address or a CIDR range like 10.0.0.0/8")
+            })?,
+            BlocklistKind::EmailDomain => normalize_domain(&request.value)
+                .ok_or_else(|| invalid_value("must be a domain name like example.com"))?,
+        };
+        if self
+            .repo
+            .find_by_value(request.kind, &value)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::Conflict(format!(
+                "'{}' is already blocked",
+                value
+            )));
+        }
+
+        let reason = request
+            .reason
+            .as_deref()
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty());
+        let entry = self
+            .repo
+            .create(request.kind, &value, reason, created_by)
+            .await?;
+        if entry.kind == BlocklistKind::Ip {
+            self.clear_verdicts().await;
+        }
+        Ok(entry)
+    }
+
+    /// Remove a blocklist entry.
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        let entry = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Blocklist entry not found".to_string()))?;
+        self.repo.delete(id).await?;
+        if entry.kind == BlocklistKind::Ip {
+            self.clear_verdicts().await;
+        }
+        Ok(())
+    }
+
+    /// Whether requests from `ip` are blocked.
+    ///
+    /// A failed or slow AbuseIPDB lookup lets the request through uncached,
+    /// so the lookup is tried again on the next request.
+    pub async fn is_ip_blocked(&self, ip: IpAddr) -> Result<bool, AppError> {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            IpAddr::V4(_) => ip,
+        };
+        let key = keys::ip_verdict(&ip);
+        let mut redis = self.redis.clone();
+        let cached: Option<String> = redis.get(&key).await.ok().flatten();
+        if let Some(verdict) = cached {
+            return Ok(verdict == BLOCKED);
+        }
+
+        let mut blocked = self.repo.find_ip_match(ip).await?.is_some();
+        if !blocked && is_public(ip) {
+            if let Some(api_key) = &self.abuseipdb_api_key {
+                let lookup = abuse_score(ABUSEIPDB_CHECK_URL, api_key, ip, self.abuseipdb_timeout);
+                match lookup.await {
+                    Ok(score) => blocked = score >= self.abuseipdb_min_score,
+                    Err(err) => {
+                        // Not cached, so the lookup is tried again on the next request
+                        tracing::warn!(error = %err, %ip, "AbuseIPDB lookup failed");
+                        return Ok(false);
+                    }
+                }
+            }
+        }
+
+        let verdict = if blocked { BLOCKED } else { ALLOWED };
+        let _: Result<(), _> = redis.set_ex(&key, verdict, self.cache_seconds).await;
+        Ok(blocked)
+    }
+
+    /// Reject `email` when its domain, or a parent domain, is blocked.
+    pub async fn check_email(&self, email: &str) -> Result<(), AppError> {
+        let Some((_, domain)) = email.trim().rsplit_once('@') else {
+            return Ok(());
+        };
+        let domains = domain_suffixes(&domain.to_ascii_lowercase());
+        if self.repo.find_email_domain_match(&domains).await?.is_some() {
+            return Err(AppError::Forbidden(
+                "Email addresses from this domain are not accepted".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // Private helper methods
+
+    /// Drop every cached IP verdict after the IP entries changed.
+    async fn clear_verdicts(&self) {
+        let mut redis = self.redis.clone();
+        let pattern = format!("{}*", keys::IP_VERDICT_PREFIX);
+        let keys: Vec<String> = match redis.scan_match::<_, String>(&pattern).await {
+            Ok(mut iter) => {
+                let mut keys = Vec::new();
+                while let Some(key) = iter.next_item().await {
+                    keys.push(key);
+                }
+                keys
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to clear cached blocklist verdicts");
+                return;
+            }
+        };
+        if keys.is_empty() {
+            return;
+        }
+        if let Err(err) = redis.del::<_, ()>(keys).await {
+            tracing::warn!(error = %err, "Failed to clear cached blocklist verdicts");
+        }
+    }
+}
+
+/// Abuse confidence score (0-100) the AbuseIPDB check endpoint at `url`
+/// reports for `ip`.
+async fn abuse_score(
+    url: &str,
+    api_key: &str,
+    ip: IpAddr,
+    timeout: Duration,
+) -> Result<u8, String> {
+    let url = format!(
+        "{}?ipAddress={}&maxAgeInDays={}",
+        url, ip, ABUSEIPDB_MAX_AGE_DAYS
+    );
+    let headers = [("Key", api_key), ("Accept", "application/json")];
+    let response = http_client::send(Method::GET, &url, &headers, None, timeout).await?;
+    if !response.is_success() {
+        return Err(format!("AbuseIPDB returned {}", response.status));
+    }
+    parse_abuse_score(&response.body).ok_or_else(|| "Unexpected AbuseIPDB response".to_string())
+}
+
+fn parse_abuse_score(body: &str) -> Option<u8> {
+    let body: serde_json::Value = serde_json::from_str(body).ok()?;
+    let score = body["data"]["abuseConfidenceScore"].as_u64()?;
+    u8::try_from(score.min(100)).ok()
+}
+
+/// Whether `ip` is routable on the internet, i.e. worth a reputation lookup.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast())
+        }
+        IpAddr::V6(v6) => {
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || first & 0xfe00 == 0xfc00
+                || first & 0xffc0 == 0xfe80)
+        }
+    }
+}
+
+/// Canonical form of an IP address or CIDR range, with host bits cleared.
+///
+/// A range covering one address is stored as the plain address.
+fn normalize_network(value: &str) -> Option<String> {
+    let value = value.trim();
+    let (address, prefix) = match value.split_once('/') {
+        Some((address, prefix)) => (address.parse::<IpAddr>().ok()?, Some(prefix)),
+        None => (value.parse::<IpAddr>().ok()?, None),
+    };
+    let bits = if address.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(prefix) if prefix.bytes().all(|b| b.is_ascii_digit()) => prefix.parse().ok()?,
+        Some(_) => return None,
+        None => bits,
+    };
+    if prefix > bits {
+        return None;
+    }
+    if prefix == bits {
+        return Some(address.to_string());
+    }
+
+    let network = match address {
+        IpAddr::V4(v4) => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(u32::from(v4) & mask))
+        }
+        IpAddr::V6(v6) => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(u128::from(v6) & mask))
+        }
+    };
+    Some(format!("{}/{}", network, prefix))
+}
+
+/// Lowercase domain name without a leading `@` or `*.`.
+fn normalize_domain(value: &str) -> Option<String> {
+    let domain = value
+        .trim()
+        .trim_start_matches('@')
+        .trim_start_matches("*.");
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    let valid = domain.len() <= MAX_DOMAIN_LEN
+        && domain.contains('.')
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        });
+    valid.then_some(domain)
+}
+
+/// `domain` and each of its parent domains, e.g. `a.example.com`, `example.com`, `com`.
+fn domain_suffixes(domain: &str) -> Vec<String> {
+    let domain = domain.trim_end_matches('.');
+    let mut suffixes = vec![domain.to_string()];
+    let mut rest = domain;
+    while let Some((_, parent)) = rest.split_once('.') {
+        suffixes.push(parent.to_string());
+        rest = parent;
+    }
+    suffixes
+}
+
+fn invalid_value(message: &str) -> AppError {
+    AppError::InvalidFields(vec![FieldError::new("value", message)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_normalize_network() {
+        assert_eq!(
+            normalize_network(" 203.0.113.7 ").as_deref(),
+            Some("203.0.113.7")
+        );
+        assert_eq!(
+            normalize_network("203.0.113.7/24").as_deref(),
+            Some("203.0.113.0/24")
+        );
+        assert_eq!(
+            normalize_network("10.1.2.3/32").as_deref(),
+            Some("10.1.2.3")
+        );
+        assert_eq!(normalize_network("0.0.0.0/0").as_deref(), Some("0.0.0.0/0"));
+        assert_eq!(
+            normalize_network("2001:db8::1/32").as_deref(),
+            Some("2001:db8::/32")
+        );
+        for invalid in [
+            "",
+            "example.com",
+            "10.0.0.0/33",
+            "10.0.0.0/-1",
+            "10.0.0/8",
+            "::/129",
+        ] {
+            assert_eq!(normalize_network(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_normalize_domain() {
+        assert_eq!(
+            normalize_domain(" @Spam.Example.COM. ").as_deref(),
+            Some("spam.example.com")
+        );
+        assert_eq!(
+            normalize_domain("*.example.com").as_deref(),
+            Some("example.com")
+        );
+        for invalid in [
+            "",
+            "localhost",
+            "exa mple.com",
+            "-bad.com",
+            "a..com",
+            "a@b.com",
+        ] {
+            assert_eq!(normalize_domain(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_domain_suffixes() {
+        assert_eq!(
+            domain_suffixes("mail.spam.example.com"),
+            vec![
+                "mail.spam.example.com",
+                "spam.example.com",
+                "example.com",
+                "com"
+            ]
+        );
+        assert_eq!(domain_suffixes("example.com"), vec!["example.com", "com"]);
+    }
+
+    #[test]
+    fn test_is_public() {
+        assert!(is_public("203.0.113.7".parse().unwrap()));
+        assert!(is_public("2001:db8::1".parse().unwrap()));
+        for private in [
+            "10.0.0.1",
+            "192.168.1.1",
+            "127.0.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+        ] {
+            assert!(!is_public(private.parse().unwrap()), "{}", private);
+        }
+    }
+
+    #[test]
+    fn test_parse_abuse_score() {
+        assert_eq!(
+            parse_abuse_score(r#"{"data":{"ipAddress":"1.2.3.4","abuseConfidenceScore":87}}"#),
+            Some(87)
+        );
+        assert_eq!(
+            parse_abuse_score(r#"{"errors":[{"detail":"Bad key"}]}"#),
+            None
+        );
+        assert_eq!(parse_abuse_score("not json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_abuse_score_times_out() {
+        // Accepts the connection but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(stream);
+        });
+
+        let url = format!("http://{}/api/v2/check", addr);
+        let ip = IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8));
+        let started = std::time::Instant::now();
+        let err = abuse_score(&url, "key", ip, Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert!(err.contains("timed out"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        server.abort();
+    }
+}
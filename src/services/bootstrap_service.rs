@@ -0,0 +1,159 @@
+//! Bootstrap service assembling the frontend's first-paint payload.
+//!
+//! Menus and feature flags come from the site's settings. The category list
+//! is cached per site under the content modification time, so any successful
+//! write through the API starts a new entry instead of serving a stale one.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{Bootstrap, BootstrapUser, CategoryWithCount, Site};
+use crate::pkg::redis::keys;
+use crate::pkg::{CachePolicy, ResponseCache};
+use crate::services::{CacheService, CategoryService, ProfileService};
+
+/// Entries are keyed by content version, so they only need to outlive it briefly.
+const BOOTSTRAP_CACHE: CachePolicy = CachePolicy {
+    fresh_for: Duration::from_secs(60 * 60),
+    stale_for: Duration::from_secs(60),
+};
+
+/// Service for the frontend bootstrap payload.
+#[derive(Clone)]
+pub struct BootstrapService {
+    category_service: CategoryService,
+    profile_service: ProfileService,
+    cache_service: CacheService,
+    cache: ResponseCache,
+    registration_enabled: bool,
+}
+
+impl BootstrapService {
+    /// Create a new bootstrap service.
+    pub fn new(
+        config: &Config,
+        category_service: CategoryService,
+        profile_service: ProfileService,
+        cache_service: CacheService,
+        cache: ResponseCache,
+    ) -> Self {
+        Self {
+            category_service,
+            profile_service,
+            cache_service,
+            cache,
+            registration_enabled: config.registration_enabled,
+        }
+    }
+
+    /// Everything the frontend needs for `site` and the signed-in user, if any.
+    pub async fn load(
+        &self,
+        site: Site,
+        auth_user: Option<&AuthUser>,
+    ) -> Result<Bootstrap, AppError> {
+        let user = async {
+            let Some(auth_user) = auth_user else {
+                return Ok(None);
+            };
+            let profile = self.profile_service.get(auth_user.id).await?;
+            Ok::<_, AppError>(Some(BootstrapUser {
+                profile,
+                is_admin: auth_user.is_admin(),
+                permissions: auth_user.permissions.clone(),
+            }))
+        };
+        let (categories, user) = tokio::try_join!(self.categories(&site), user)?;
+
+        Ok(Bootstrap {
+            menus: menus(&site),
+            features: features(&site, self.registration_enabled),
+            categories,
+            user,
+            site,
+        })
+    }
+
+    /// The site's categories, cached until content next changes.
+    async fn categories(&self, site: &Site) -> Result<Vec<CategoryWithCount>, AppError> {
+        let version = self
+            .cache_service
+            .last_modified(site.id)
+            .await?
+            .map_or(0, |modified| modified.timestamp());
+        let site_id = site.id;
+        let category_service = self.category_service.clone();
+        self.cache
+            .get_or_load(
+                keys::bootstrap(&site_id, version),
+                BOOTSTRAP_CACHE,
+                move || async move { category_service.list(site_id).await },
+            )
+            .await
+    }
+}
+
+/// The site's `menus` setting, or an empty object.
+fn menus(site: &Site) -> serde_json::Value {
+    site.settings
+        .get("menus")
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}))
+}
+
+/// Boolean entries of the site's `features` setting, overridden by
+/// installation-wide flags.
+fn features(site: &Site, registration_enabled: bool) -> BTreeMap<String, bool> {
+    let mut features: BTreeMap<String, bool> = site
+        .settings
+        .get("features")
+        .and_then(serde_json::Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| Some((name.clone(), value.as_bool()?)))
+        .collect();
+    features.insert("registration".to_string(), registration_enabled);
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    fn site(settings: serde_json::Value) -> Site {
+        Site {
+            id: Uuid::new_v4(),
+            name: "Blog".to_string(),
+            host: "blog.example.com".to_string(),
+            is_default: true,
+            settings: Json(serde_json::from_value(settings).unwrap()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_features() {
+        let site = site(serde_json::json!({
+            "features": { "comments": true, "newsletter": "soon", "registration": true }
+        }));
+        let features = features(&site, false);
+        assert_eq!(features.get("comments"), Some(&true));
+        assert_eq!(features.get("newsletter"), None);
+        assert_eq!(features.get("registration"), Some(&false));
+    }
+
+    #[test]
+    fn test_menus() {
+        let header = serde_json::json!({ "header": [{ "label": "Blog", "url": "/posts" }] });
+        assert_eq!(menus(&site(serde_json::json!({ "menus": header }))), header);
+        assert_eq!(menus(&site(serde_json::json!({}))), serde_json::json!({}));
+    }
+}
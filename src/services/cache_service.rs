@@ -0,0 +1,84 @@
+//! Cache service deciding how public responses may be cached.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::pkg::redis::keys;
+
+/// Service holding the route cache policies and when each site's public
+/// content last changed.
+///
+/// The modification times are shared through Redis so every instance reports
+/// the same `Last-Modified`.
+#[derive(Clone)]
+pub struct CacheService {
+    redis: redis::aio::ConnectionManager,
+    policies: Arc<Vec<(String, u64)>>,
+}
+
+impl CacheService {
+    /// Create a new cache service.
+    pub fn new(config: &Config, redis: redis::aio::ConnectionManager) -> Self {
+        Self {
+            redis,
+            policies: Arc::new(config.cache_policies.clone()),
+        }
+    }
+
+    /// Max-age in seconds for `path`, if a policy covers it.
+    pub fn max_age(&self, path: &str) -> Option<u64> {
+        policy_max_age(&self.policies, path)
+    }
+
+    /// When a site's public content last changed, or `None` when no write
+    /// has been recorded for it yet.
+    pub async fn last_modified(&self, site_id: Uuid) -> Result<Option<DateTime<Utc>>, AppError> {
+        let mut redis = self.redis.clone();
+        let modified: Option<i64> = redis.get(keys::content_modified(&site_id)).await?;
+        Ok(modified.and_then(|modified| DateTime::from_timestamp(modified, 0)))
+    }
+
+    /// Record that a site's public content changed just now.
+    pub async fn touch(&self, site_id: Uuid) -> Result<(), AppError> {
+        let mut redis = self.redis.clone();
+        let _: () = redis
+            .set(keys::content_modified(&site_id), Utc::now().timestamp())
+            .await?;
+        Ok(())
+    }
+}
+
+/// Max-age of the longest policy path that `path` equals or lies below.
+fn policy_max_age(policies: &[(String, u64)], path: &str) -> Option<u64> {
+    policies
+        .iter()
+        .filter(|(prefix, _)| {
+            path.strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, max_age)| *max_age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_max_age() {
+        let policies = vec![
+            ("/api/posts".to_string(), 60),
+            ("/api/posts/trending".to_string(), 300),
+        ];
+        assert_eq!(policy_max_age(&policies, "/api/posts"), Some(60));
+        assert_eq!(policy_max_age(&policies, "/api/posts/slug/hello"), Some(60));
+        assert_eq!(policy_max_age(&policies, "/api/posts/trending"), Some(300));
+        assert_eq!(policy_max_age(&policies, "/api/postscript"), None);
+        assert_eq!(policy_max_age(&policies, "/api/tags"), None);
+    }
+}
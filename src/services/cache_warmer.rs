@@ -0,0 +1,55 @@
+//! Cache warmer filling the Redis caches before visitors ask for them.
+//!
+//! Run at startup (unless `CACHE_WARM_ON_STARTUP=false`) and on demand by
+//! admins, so the first requests after a deploy do not wait on the database.
+
+use std::time::Instant;
+
+use chrono::{Datelike, Utc};
+
+use crate::error::AppError;
+use crate::models::{CacheWarmReport, TrendingWindow};
+use crate::repositories::SiteRepository;
+use crate::services::{ArchiveService, TrendingService};
+
+/// Service recomputing every cached trending list and this year's archives.
+#[derive(Clone)]
+pub struct CacheWarmer {
+    site_repo: SiteRepository,
+    trending_service: TrendingService,
+    archive_service: ArchiveService,
+}
+
+impl CacheWarmer {
+    /// Create a new cache warmer.
+    pub fn new(
+        site_repo: SiteRepository,
+        trending_service: TrendingService,
+        archive_service: ArchiveService,
+    ) -> Self {
+        Self {
+            site_repo,
+            trending_service,
+            archive_service,
+        }
+    }
+
+    /// Recompute and cache the trending lists and current year's archive of
+    /// every site.
+    pub async fn warm(&self) -> Result<CacheWarmReport, AppError> {
+        let started = Instant::now();
+        let year = Utc::now().year();
+        let sites = self.site_repo.find_all().await?;
+        for site in &sites {
+            self.trending_service.refresh_site(site.id).await?;
+            self.archive_service.refresh(site.id, year).await?;
+        }
+
+        Ok(CacheWarmReport {
+            sites: sites.len(),
+            trending_lists: sites.len() * TrendingWindow::ALL.len(),
+            archives: sites.len(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+}
@@ -2,74 +2,120 @@
 
 use uuid::Uuid;
 
-use crate::error::AppError;
-use crate::models::{Category, CategoryWithCount, CreateCategoryRequest, UpdateCategoryRequest};
+use crate::config::Config;
+use crate::error::{AppError, FieldError};
+use crate::models::{
+    Category, CategoryMergeResponse, CategoryWithCount, CreateCategoryRequest,
+    MergeCategoryRequest, UpdateCategoryRequest,
+};
+use crate::pkg::{slug, SlugGenerator};
 use crate::repositories::CategoryRepository;
 
+/// Longest category slug (the `categories.slug` column is `VARCHAR(100)`).
+const MAX_SLUG_LEN: usize = 100;
+
 /// Service for category operations.
 #[derive(Clone)]
 pub struct CategoryService {
     repo: CategoryRepository,
+    slugs: SlugGenerator,
+    block_delete_in_use: bool,
 }
 
 impl CategoryService {
     /// Create a new category service.
-    pub fn new(repo: CategoryRepository) -> Self {
-        Self { repo }
+    pub fn new(config: &Config, repo: CategoryRepository) -> Self {
+        Self {
+            repo,
+            slugs: SlugGenerator::from_config(config),
+            block_delete_in_use: config.taxonomy_block_delete_in_use,
+        }
     }
 
-    /// List all categories with post counts.
-    pub async fn list(&self) -> Result<Vec<CategoryWithCount>, AppError> {
-        self.repo.find_all_with_count().await
+    /// List a site's categories with post counts.
+    pub async fn list(&self, site_id: Uuid) -> Result<Vec<CategoryWithCount>, AppError> {
+        self.repo.find_all_with_count(site_id).await
     }
 
     /// Get a single category by ID.
-    pub async fn get_by_id(&self, id: Uuid) -> Result<Category, AppError> {
+    pub async fn get_by_id(&self, site_id: Uuid, id: Uuid) -> Result<Category, AppError> {
         self.repo
-            .find_by_id(id)
+            .find_by_id(site_id, id)
             .await?
             .ok_or_else(|| AppError::NotFound("Category not found".to_string()))
     }
 
     /// Get a single category by slug.
-    pub async fn get_by_slug(&self, slug: &str) -> Result<Category, AppError> {
+    pub async fn get_by_slug(&self, site_id: Uuid, slug: &str) -> Result<Category, AppError> {
         self.repo
-            .find_by_slug(slug)
+            .find_by_slug(site_id, slug)
             .await?
             .ok_or_else(|| AppError::NotFound("Category not found".to_string()))
     }
 
-    /// Create a new category.
-    pub async fn create(&self, request: CreateCategoryRequest) -> Result<Category, AppError> {
-        let slug = request.slug.unwrap_or_else(|| Self::slugify(&request.name));
-
-        // Check if slug already exists
-        if self.repo.find_by_slug(&slug).await?.is_some() {
-            return Err(AppError::Conflict(
-                "Category slug already exists".to_string(),
-            ));
+    /// Create a new category on a site.
+    pub async fn create(
+        &self,
+        site_id: Uuid,
+        request: CreateCategoryRequest,
+    ) -> Result<Category, AppError> {
+        // Generate a free slug if not provided
+        let slug = match request.slug {
+            Some(slug) => {
+                self.slugs.check_reserved("slug", &slug)?;
+                if self.repo.find_by_slug(site_id, &slug).await?.is_some() {
+                    return Err(AppError::Conflict(
+                        "Category slug already exists".to_string(),
+                    ));
+                }
+                slug
+            }
+            None => {
+                let (repo, slugs) = (&self.repo, &self.slugs);
+                let max_len = slugs.max_length().min(MAX_SLUG_LEN);
+                slug::unique(
+                    &slugs.generate(&request.name),
+                    max_len,
+                    |candidate| async move {
+                        Ok(slugs.is_reserved(&candidate)
+                            || repo.find_by_slug(site_id, &candidate).await?.is_some())
+                    },
+                )
+                .await?
+            }
+        };
+        if let Some(parent_id) = request.parent_id {
+            self.ensure_parent(site_id, parent_id).await?;
         }
 
         self.repo
-            .create(&request.name, &slug, request.description.as_deref())
+            .create(
+                site_id,
+                &request.name,
+                &slug,
+                request.description.as_deref(),
+                request.parent_id,
+            )
             .await
     }
 
     /// Update an existing category.
     pub async fn update(
         &self,
+        site_id: Uuid,
         id: Uuid,
         request: UpdateCategoryRequest,
     ) -> Result<Category, AppError> {
         // Check if category exists
         self.repo
-            .find_by_id(id)
+            .find_by_id(site_id, id)
             .await?
             .ok_or_else(|| AppError::NotFound("Category not found".to_string()))?;
 
         // Check slug uniqueness if updating
         if let Some(ref slug) = request.slug {
-            if let Some(existing) = self.repo.find_by_slug(slug).await? {
+            self.slugs.check_reserved("slug", slug)?;
+            if let Some(existing) = self.repo.find_by_slug(site_id, slug).await? {
                 if existing.id != id {
                     return Err(AppError::Conflict(
                         "Category slug already exists".to_string(),
@@ -78,50 +124,113 @@ impl CategoryService {
             }
         }
 
+        // A category cannot be moved under itself or one of its descendants
+        if let Some(Some(parent_id)) = request.parent_id {
+            self.ensure_parent(site_id, parent_id).await?;
+            if self.repo.is_self_or_ancestor(id, parent_id).await? {
+                return Err(AppError::InvalidFields(vec![FieldError::new(
+                    "parent_id",
+                    "cannot be the category itself or one of its subcategories",
+                )]));
+            }
+        }
+
         self.repo
             .update(
                 id,
                 request.name.as_deref(),
                 request.slug.as_deref(),
                 request.description.as_deref(),
+                request.parent_id,
             )
             .await
     }
 
-    /// Delete a category.
-    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+    /// Soft delete a category. Its posts show no category until it is restored.
+    pub async fn delete(&self, site_id: Uuid, id: Uuid) -> Result<bool, AppError> {
         // Check if category exists
         self.repo
-            .find_by_id(id)
+            .find_by_id(site_id, id)
             .await?
             .ok_or_else(|| AppError::NotFound("Category not found".to_string()))?;
 
+        if self.block_delete_in_use {
+            let posts = self.repo.count_posts(id).await?;
+            if posts > 0 {
+                return Err(AppError::Conflict(format!(
+                    "Category is used by {} post(s); move them or merge the category instead",
+                    posts
+                )));
+            }
+        }
+
         self.repo.delete(id).await
     }
 
-    fn slugify(text: &str) -> String {
-        text.to_lowercase()
-            .chars()
-            .map(|c| if c.is_alphanumeric() { c } else { '-' })
-            .collect::<String>()
-            .split('-')
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<_>>()
-            .join("-")
+    /// List a site's soft-deleted categories.
+    pub async fn list_deleted(&self, site_id: Uuid) -> Result<Vec<Category>, AppError> {
+        self.repo.find_deleted(site_id).await
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_slugify() {
-        assert_eq!(CategoryService::slugify("Technology"), "technology");
-        assert_eq!(
-            CategoryService::slugify("Web Development"),
-            "web-development"
-        );
-        assert_eq!(CategoryService::slugify("Rust & Go"), "rust-go");
+    /// Restore a soft-deleted category, unless its slug was taken meanwhile.
+    pub async fn restore(&self, site_id: Uuid, id: Uuid) -> Result<Category, AppError> {
+        let deleted = self
+            .repo
+            .find_deleted_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Deleted category not found".to_string()))?;
+        if self
+            .repo
+            .find_by_slug(site_id, &deleted.slug)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::Conflict(format!(
+                "Cannot restore: category slug '{}' is already in use",
+                deleted.slug
+            )));
+        }
+
+        self.repo.restore(id).await?;
+        self.get_by_id(site_id, id).await
+    }
+
+    /// Merge a category into `request.target_id`, moving its posts.
+    pub async fn merge(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        request: MergeCategoryRequest,
+    ) -> Result<CategoryMergeResponse, AppError> {
+        if id == request.target_id {
+            return Err(AppError::ValidationError(
+                "Cannot merge a category into itself".to_string(),
+            ));
+        }
+        self.repo
+            .find_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Category not found".to_string()))?;
+        let target = self
+            .repo
+            .find_by_id(site_id, request.target_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Target category not found".to_string()))?;
+
+        let posts_moved = self.repo.merge(id, target.id).await?;
+        Ok(CategoryMergeResponse {
+            target,
+            posts_moved,
+        })
+    }
+
+    async fn ensure_parent(&self, site_id: Uuid, parent_id: Uuid) -> Result<(), AppError> {
+        match self.repo.find_by_id(site_id, parent_id).await? {
+            Some(_) => Ok(()),
+            None => Err(AppError::InvalidFields(vec![FieldError::new(
+                "parent_id",
+                "does not exist on this site",
+            )])),
+        }
     }
 }
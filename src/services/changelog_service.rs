@@ -0,0 +1,407 @@
+//! Changelog service for "what's new" entries and their Atom feed.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use uuid::Uuid;
+
+use crate::error::{AppError, FieldError};
+use crate::models::{
+    ChangelogEntry, ChangelogQuery, CreateChangelogEntryRequest, Site, UpdateChangelogEntryRequest,
+};
+use crate::repositories::ChangelogRepository;
+use crate::response::Meta;
+
+/// Longest entry title (the `title` column is `VARCHAR(200)`).
+const MAX_TITLE_LEN: usize = 200;
+/// Longest version label (the `version` column is `VARCHAR(50)`).
+const MAX_VERSION_LEN: usize = 50;
+/// Entries in the Atom feed.
+const FEED_ENTRIES: i64 = 20;
+
+/// Service for changelog operations.
+#[derive(Clone)]
+pub struct ChangelogService {
+    repo: ChangelogRepository,
+}
+
+impl ChangelogService {
+    /// Create a new changelog service.
+    pub fn new(repo: ChangelogRepository) -> Self {
+        Self { repo }
+    }
+
+    /// List a site's published entries, newest first.
+    pub async fn list_published(
+        &self,
+        site_id: Uuid,
+        query: ChangelogQuery,
+    ) -> Result<(Vec<ChangelogEntry>, Meta), AppError> {
+        self.list(site_id, Some(Utc::now()), query).await
+    }
+
+    /// List all of a site's entries, drafts and scheduled ones included.
+    pub async fn list_all(
+        &self,
+        site_id: Uuid,
+        query: ChangelogQuery,
+    ) -> Result<(Vec<ChangelogEntry>, Meta), AppError> {
+        self.list(site_id, None, query).await
+    }
+
+    /// Atom feed of a site's latest published entries.
+    pub async fn feed(&self, site: &Site) -> Result<String, AppError> {
+        let entries = self
+            .repo
+            .find_all(site.id, Some(Utc::now()), FEED_ENTRIES, 0)
+            .await?;
+        Ok(Self::render_atom(site, &entries))
+    }
+
+    /// When the Atom feed last changed: the latest edit or publication of a
+    /// published entry, removal of one from the feed, or change to the site.
+    pub async fn feed_modified(&self, site: &Site) -> Result<DateTime<Utc>, AppError> {
+        let published = self.repo.last_published_change(site.id, Utc::now()).await?;
+        let removed = self.repo.feed_changed_at(site.id).await?;
+        Ok(latest_change(site.updated_at, [published, removed]))
+    }
+
+    /// Create a changelog entry on a site.
+    pub async fn create(
+        &self,
+        site_id: Uuid,
+        created_by: Uuid,
+        request: CreateChangelogEntryRequest,
+    ) -> Result<ChangelogEntry, AppError> {
+        Self::validate(
+            Some(&request.title),
+            Some(&request.body),
+            request.version.as_deref(),
+        )?;
+
+        self.repo
+            .create(
+                site_id,
+                request.title.trim(),
+                request.body.trim(),
+                request.version.as_deref().map(str::trim),
+                request.published_at,
+                created_by,
+            )
+            .await
+    }
+
+    /// Update a changelog entry.
+    pub async fn update(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        request: UpdateChangelogEntryRequest,
+    ) -> Result<ChangelogEntry, AppError> {
+        let before = self.find(site_id, id).await?;
+        Self::validate(
+            request.title.as_deref(),
+            request.body.as_deref(),
+            request.version.as_deref(),
+        )?;
+
+        let entry = self
+            .repo
+            .update(
+                id,
+                request.title.as_deref().map(str::trim),
+                request.body.as_deref().map(str::trim),
+                request.version.as_deref().map(str::trim),
+                request.published_at,
+            )
+            .await?;
+        let now = Utc::now();
+        if is_published(&before, now) && !is_published(&entry, now) {
+            self.repo.touch_feed(site_id).await?;
+        }
+        Ok(entry)
+    }
+
+    /// Delete a changelog entry.
+    pub async fn delete(&self, site_id: Uuid, id: Uuid) -> Result<bool, AppError> {
+        let entry = self.find(site_id, id).await?;
+        let deleted = self.repo.delete(id).await?;
+        if deleted && is_published(&entry, Utc::now()) {
+            self.repo.touch_feed(site_id).await?;
+        }
+        Ok(deleted)
+    }
+
+    // Private helper methods
+
+    async fn find(&self, site_id: Uuid, id: Uuid) -> Result<ChangelogEntry, AppError> {
+        self.repo
+            .find_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Changelog entry not found".to_string()))
+    }
+
+    async fn list(
+        &self,
+        site_id: Uuid,
+        published_before: Option<DateTime<Utc>>,
+        query: ChangelogQuery,
+    ) -> Result<(Vec<ChangelogEntry>, Meta), AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+        let offset = (page - 1) * per_page;
+
+        let entries = self
+            .repo
+            .find_all(site_id, published_before, per_page, offset)
+            .await?;
+        let total = self.repo.count(site_id, published_before).await?;
+        Ok((entries, Meta::new(page, per_page, total)))
+    }
+
+    /// Check the sent fields.
+    fn validate(
+        title: Option<&str>,
+        body: Option<&str>,
+        version: Option<&str>,
+    ) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if let Some(title) = title.map(str::trim) {
+            if title.is_empty() || title.chars().count() > MAX_TITLE_LEN {
+                errors.push(FieldError::new(
+                    "title",
+                    format!("must be 1 to {} characters", MAX_TITLE_LEN),
+                ));
+            }
+        }
+        if body.is_some_and(|body| body.trim().is_empty()) {
+            errors.push(FieldError::new("body", "must not be empty"));
+        }
+        if let Some(version) = version.map(str::trim) {
+            if version.is_empty() || version.chars().count() > MAX_VERSION_LEN {
+                errors.push(FieldError::new(
+                    "version",
+                    format!("must be 1 to {} characters", MAX_VERSION_LEN),
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(errors))
+        }
+    }
+
+    /// Render entries as an Atom feed titled after the site's `title` setting
+    /// (or its name) and linking to its host.
+    fn render_atom(site: &Site, entries: &[ChangelogEntry]) -> String {
+        let title = site
+            .settings
+            .get("title")
+            .and_then(|title| title.as_str())
+            .unwrap_or(&site.name);
+        let updated = entries
+            .iter()
+            .map(|entry| entry.updated_at)
+            .max()
+            .unwrap_or(site.updated_at);
+        let base = format!("https://{}", site.host);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        let _ = writeln!(xml, "  <id>urn:uuid:{}</id>", site.id);
+        let _ = writeln!(xml, "  <title>{} changelog</title>", escape_xml(title));
+        let _ = writeln!(xml, "  <updated>{}</updated>", rfc3339(updated));
+        let _ = writeln!(xml, "  <link href=\"{}/\"/>", escape_xml(&base));
+        let _ = writeln!(
+            xml,
+            "  <link rel=\"self\" href=\"{}/api/changelog/feed.xml\"/>",
+            escape_xml(&base)
+        );
+
+        for entry in entries {
+            let title = match &entry.version {
+                Some(version) => format!("{}: {}", version, entry.title),
+                None => entry.title.clone(),
+            };
+            xml.push_str("  <entry>\n");
+            let _ = writeln!(xml, "    <id>urn:uuid:{}</id>", entry.id);
+            let _ = writeln!(xml, "    <title>{}</title>", escape_xml(&title));
+            if let Some(published_at) = entry.published_at {
+                let _ = writeln!(xml, "    <published>{}</published>", rfc3339(published_at));
+            }
+            let _ = writeln!(xml, "    <updated>{}</updated>", rfc3339(entry.updated_at));
+            let _ = writeln!(
+                xml,
+                "    <content type=\"text\">{}</content>",
+                escape_xml(&entry.body)
+            );
+            xml.push_str("  </entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+}
+
+/// Whether an entry is in the feed at `now`.
+fn is_published(entry: &ChangelogEntry, now: DateTime<Utc>) -> bool {
+    entry
+        .published_at
+        .is_some_and(|published_at| published_at <= now)
+}
+
+/// The latest of the site's own change and the feed's entry changes.
+fn latest_change(
+    site_updated_at: DateTime<Utc>,
+    changes: [Option<DateTime<Utc>>; 2],
+) -> DateTime<Utc> {
+    changes
+        .into_iter()
+        .flatten()
+        .fold(site_updated_at, DateTime::max)
+}
+
+/// Escape text for XML content and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn rfc3339(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use axum::http::{header, HeaderMap};
+    use chrono::TimeZone;
+    use sqlx::types::Json;
+
+    use crate::middleware::{last_modified_header, not_modified_since};
+
+    fn fields(result: Result<(), AppError>) -> Vec<String> {
+        match result {
+            Err(AppError::InvalidFields(errors)) => errors.into_iter().map(|e| e.field).collect(),
+            other => panic!("expected field errors, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ChangelogService::validate(Some("Dark mode"), Some("Done."), Some("v2")).is_ok());
+        assert!(ChangelogService::validate(None, None, None).is_ok());
+        assert_eq!(
+            fields(ChangelogService::validate(
+                Some(" "),
+                Some("\n"),
+                Some(&"9".repeat(MAX_VERSION_LEN + 1)),
+            )),
+            vec!["title", "body", "version"]
+        );
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_feed_modified_after_delete() {
+        let site_updated = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let published = Utc.with_ymd_and_hms(2026, 5, 1, 12, 0, 0).unwrap();
+        let last_modified = latest_change(site_updated, [Some(published), None]);
+        assert_eq!(last_modified, published);
+
+        // A poller revalidates with what it got, then the newest entry is deleted
+        let mut poll = HeaderMap::new();
+        poll.insert(
+            header::IF_MODIFIED_SINCE,
+            last_modified_header(last_modified).unwrap(),
+        );
+        assert!(not_modified_since(&poll, last_modified));
+
+        let older = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let deleted_at = Utc.with_ymd_and_hms(2026, 5, 2, 9, 0, 0).unwrap();
+        let last_modified = latest_change(site_updated, [Some(older), Some(deleted_at)]);
+        assert_eq!(last_modified, deleted_at);
+        assert!(!not_modified_since(&poll, last_modified));
+    }
+
+    #[test]
+    fn test_is_published() {
+        let now = Utc.with_ymd_and_hms(2026, 5, 1, 12, 0, 0).unwrap();
+        let entry = |published_at| ChangelogEntry {
+            id: Uuid::nil(),
+            site_id: Uuid::nil(),
+            title: "Dark mode".to_string(),
+            body: "Done.".to_string(),
+            version: None,
+            published_at,
+            created_by: None,
+            created_at: now,
+            updated_at: now,
+        };
+        assert!(is_published(&entry(Some(now)), now));
+        assert!(!is_published(
+            &entry(Some(now + chrono::Duration::hours(1))),
+            now
+        ));
+        assert!(!is_published(&entry(None), now));
+    }
+
+    #[test]
+    fn test_render_atom() {
+        let time = Utc.with_ymd_and_hms(2026, 5, 1, 12, 0, 0).unwrap();
+        let site = Site {
+            id: Uuid::nil(),
+            name: "Blog".to_string(),
+            host: "example.com".to_string(),
+            is_default: true,
+            settings: Json(BTreeMap::from([(
+                "title".to_string(),
+                serde_json::json!("Tom & Jerry"),
+            )])),
+            created_at: time,
+            updated_at: time,
+        };
+        let entry = ChangelogEntry {
+            id: Uuid::nil(),
+            site_id: site.id,
+            title: "Dark mode".to_string(),
+            body: "Follows <your> theme".to_string(),
+            version: Some("v2.1".to_string()),
+            published_at: Some(time),
+            created_by: None,
+            created_at: time,
+            updated_at: time,
+        };
+
+        let xml = ChangelogService::render_atom(&site, &[entry]);
+        assert!(xml.contains("<title>Tom &amp; Jerry changelog</title>"));
+        assert!(xml
+            .contains("<link rel=\"self\" href=\"https://example.com/api/changelog/feed.xml\"/>"));
+        assert!(xml.contains("<title>v2.1: Dark mode</title>"));
+        assert!(xml.contains("<published>2026-05-01T12:00:00Z</published>"));
+        assert!(xml.contains("<content type=\"text\">Follows &lt;your&gt; theme</content>"));
+        assert_eq!(xml.matches("<entry>").count(), 1);
+    }
+}
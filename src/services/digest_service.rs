@@ -0,0 +1,131 @@
+//! Weekly stats digest emailed to authors who opted in.
+//!
+//! Each digest covers the seven days before the scheduled run: human views
+//! of the author's posts against the week before, posts they published and
+//! their most read posts. Authors with nothing to report get no email.
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use redis::aio::ConnectionManager;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{DigestRecipient, NotificationKind, WeeklyDigest, DIGEST_TOP_POSTS};
+use crate::pkg::redis::keys;
+use crate::pkg::EmailTemplate;
+use crate::repositories::{NotificationRepository, PostRepository};
+use crate::services::NotificationService;
+
+/// How long a claimed schedule slot is remembered.
+const SLOT_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+/// Service compiling and sending weekly author digests.
+#[derive(Clone)]
+pub struct DigestService {
+    notification_repo: NotificationRepository,
+    post_repo: PostRepository,
+    notification_service: NotificationService,
+    redis: ConnectionManager,
+}
+
+impl DigestService {
+    /// Create a new digest service.
+    pub fn new(
+        notification_repo: NotificationRepository,
+        post_repo: PostRepository,
+        notification_service: NotificationService,
+        redis: ConnectionManager,
+    ) -> Self {
+        Self {
+            notification_repo,
+            post_repo,
+            notification_service,
+            redis,
+        }
+    }
+
+    /// Send the digests due at `slot` unless another instance claimed it.
+    /// Returns how many were sent.
+    pub async fn run_scheduled(&self, slot: DateTime<Utc>) -> Result<Option<usize>, AppError> {
+        if !self.claim(&keys::digest_slot(slot.timestamp())).await {
+            return Ok(None);
+        }
+        self.send_all(slot.date_naive()).await.map(Some)
+    }
+
+    /// Compile one author's digest for the week ending `week_end`.
+    pub async fn compile(
+        &self,
+        author_id: Uuid,
+        week_end: NaiveDate,
+    ) -> Result<WeeklyDigest, AppError> {
+        let week_start = week_end - Days::new(6);
+        let (stats, top_posts) = tokio::try_join!(
+            self.post_repo
+                .find_author_week_stats(author_id, week_start, week_end),
+            self.post_repo
+                .find_author_top_posts(author_id, week_start, week_end, DIGEST_TOP_POSTS),
+        )?;
+        Ok(WeeklyDigest {
+            week_start,
+            week_end,
+            stats,
+            top_posts,
+        })
+    }
+
+    // Private helper methods
+
+    async fn send_all(&self, today: NaiveDate) -> Result<usize, AppError> {
+        let week_end = today - Days::new(1);
+        let mut sent = 0;
+        for recipient in self.notification_repo.find_digest_recipients().await? {
+            match self.send(&recipient, week_end).await {
+                Ok(true) => sent += 1,
+                Ok(false) => {}
+                Err(err) => {
+                    tracing::warn!(error = %err, user_id = %recipient.id, "Weekly digest failed")
+                }
+            }
+        }
+        Ok(sent)
+    }
+
+    async fn send(
+        &self,
+        recipient: &DigestRecipient,
+        week_end: NaiveDate,
+    ) -> Result<bool, AppError> {
+        let digest = self.compile(recipient.id, week_end).await?;
+        if digest.is_empty() {
+            return Ok(false);
+        }
+        self.notification_service
+            .notify(
+                recipient.id,
+                &recipient.email,
+                NotificationKind::WeeklyDigest,
+                EmailTemplate::WeeklyDigest,
+                digest.variables(&recipient.name),
+            )
+            .await
+    }
+
+    async fn claim(&self, key: &str) -> bool {
+        let mut redis = self.redis.clone();
+        let claimed: Result<Option<String>, _> = redis::cmd("SET")
+            .arg(key)
+            .arg(Utc::now().timestamp())
+            .arg("NX")
+            .arg("EX")
+            .arg(SLOT_TTL_SECONDS)
+            .query_async(&mut redis)
+            .await;
+        match claimed {
+            Ok(reply) => reply.is_some(),
+            Err(err) => {
+                tracing::warn!(error = %err, key = %key, "Failed to claim digest slot");
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Email service rendering transactional emails from their templates.
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::error::{AppError, FieldError};
+use crate::models::{
+    EmailTemplateOverride, EmailTemplateResponse, PreviewEmailTemplateRequest, Setting,
+    UpdateEmailTemplateRequest,
+};
+use crate::pkg::email::{self, RenderedEmail, TemplateSource};
+use crate::pkg::{EmailTemplate, Mailer};
+use crate::repositories::SettingsRepository;
+
+/// Settings key prefix of template overrides.
+const OVERRIDE_KEY_PREFIX: &str = "email_template.";
+/// Longest template subject.
+const MAX_SUBJECT_LEN: usize = 200;
+/// Longest template body.
+const MAX_BODY_LEN: usize = 20_000;
+
+/// Service for rendering, sending and customizing transactional emails.
+#[derive(Clone)]
+pub struct EmailService {
+    settings_repo: SettingsRepository,
+    mailer: Mailer,
+}
+
+impl EmailService {
+    /// Create a new email service.
+    pub fn new(settings_repo: SettingsRepository, mailer: Mailer) -> Self {
+        Self {
+            settings_repo,
+            mailer,
+        }
+    }
+
+    /// Render `template` with `variables` and send it to `to`.
+    pub async fn send(
+        &self,
+        to: &str,
+        template: EmailTemplate,
+        variables: Value,
+    ) -> Result<(), AppError> {
+        let email = self.render(template, variables).await?;
+        self.send_rendered(to, email).await
+    }
+
+    /// Send an already rendered email to `to`.
+    pub async fn send_rendered(&self, to: &str, email: RenderedEmail) -> Result<(), AppError> {
+        self.mailer.send(to, &email.subject, email.body).await
+    }
+
+    /// Render `template` with `variables`, using the override when there is one.
+    ///
+    /// An override that no longer renders (e.g. a variable was renamed) is
+    /// skipped with a warning, so the email still goes out with the default.
+    pub async fn render(
+        &self,
+        template: EmailTemplate,
+        variables: Value,
+    ) -> Result<RenderedEmail, AppError> {
+        if let Some(custom) = self.find_override(template).await? {
+            match email::render(&custom, &variables) {
+                Ok(email) => return Ok(email),
+                Err(err) => tracing::warn!(
+                    template = %template,
+                    error = %err,
+                    "Email template override failed to render; using the default"
+                ),
+            }
+        }
+        email::render(&template.default_source(), &variables).map_err(|err| {
+            AppError::InternalError(format!("Email template {} failed: {}", template, err))
+        })
+    }
+
+    /// List every template with its override.
+    pub async fn list_templates(&self) -> Result<Vec<EmailTemplateResponse>, AppError> {
+        let overrides = self
+            .settings_repo
+            .find_by_prefix(OVERRIDE_KEY_PREFIX)
+            .await?;
+        Ok(EmailTemplate::ALL
+            .into_iter()
+            .map(|template| {
+                let key = override_key(template);
+                let setting = overrides.iter().find(|setting| setting.key == key);
+                Self::response(template, setting)
+            })
+            .collect())
+    }
+
+    /// Get a template with its override.
+    pub async fn get_template(&self, name: &str) -> Result<EmailTemplateResponse, AppError> {
+        let template = parse_name(name)?;
+        let setting = self.settings_repo.find(&override_key(template)).await?;
+        Ok(Self::response(template, setting.as_ref()))
+    }
+
+    /// Replace a template's subject and body, after checking they render.
+    pub async fn set_override(
+        &self,
+        name: &str,
+        request: UpdateEmailTemplateRequest,
+        updated_by: Uuid,
+    ) -> Result<EmailTemplateResponse, AppError> {
+        let template = parse_name(name)?;
+        let source = TemplateSource {
+            subject: request.subject.trim().to_string(),
+            body: request.body,
+        };
+        Self::validate(template, &source)?;
+
+        let value = serde_json::to_value(&source)
+            .map_err(|e| AppError::InternalError(format!("Failed to encode template: {}", e)))?;
+        let setting = self
+            .settings_repo
+            .upsert(&override_key(template), &value, updated_by)
+            .await?;
+        tracing::info!(template = %template, %updated_by, "Email template overridden");
+        Ok(Self::response(template, Some(&setting)))
+    }
+
+    /// Remove a template's override, restoring the default.
+    pub async fn delete_override(&self, name: &str) -> Result<(), AppError> {
+        let template = parse_name(name)?;
+        if self.settings_repo.delete(&override_key(template)).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(
+                "Email template has no override".to_string(),
+            ))
+        }
+    }
+
+    /// Render a template, or a draft of one, with sample or given variables.
+    pub async fn preview(
+        &self,
+        name: &str,
+        request: PreviewEmailTemplateRequest,
+    ) -> Result<RenderedEmail, AppError> {
+        let template = parse_name(name)?;
+        let current = match self.find_override(template).await? {
+            Some(custom) => custom,
+            None => template.default_source(),
+        };
+        let source = TemplateSource {
+            subject: request.subject.unwrap_or(current.subject),
+            body: request.body.unwrap_or(current.body),
+        };
+        let mut variables = template.sample_context();
+        if let (Value::Object(context), Some(given)) = (&mut variables, request.variables) {
+            context.extend(given);
+        }
+        email::render(&source, &variables).map_err(|err| {
+            AppError::InvalidFields(vec![FieldError::new(field_of(&err), err.clone())])
+        })
+    }
+
+    // Private helper methods
+
+    async fn find_override(
+        &self,
+        template: EmailTemplate,
+    ) -> Result<Option<TemplateSource>, AppError> {
+        let Some(setting) = self.settings_repo.find(&override_key(template)).await? else {
+            return Ok(None);
+        };
+        match serde_json::from_value(setting.value) {
+            Ok(source) => Ok(Some(source)),
+            Err(err) => {
+                tracing::warn!(template = %template, error = %err, "Invalid email template override");
+                Ok(None)
+            }
+        }
+    }
+
+    fn response(template: EmailTemplate, setting: Option<&Setting>) -> EmailTemplateResponse {
+        let custom = setting.and_then(|setting| {
+            let source: TemplateSource = serde_json::from_value(setting.value.clone()).ok()?;
+            Some(EmailTemplateOverride {
+                subject: source.subject,
+                body: source.body,
+                updated_by: setting.updated_by,
+                updated_at: setting.updated_at,
+            })
+        });
+        EmailTemplateResponse {
+            name: template.name(),
+            description: template.description(),
+            variables: template.variables().iter().map(|(name, _)| *name).collect(),
+            default: template.default_source(),
+            custom,
+        }
+    }
+
+    /// Check an override's size and that it renders with the template's variables.
+    fn validate(template: EmailTemplate, source: &TemplateSource) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if source.subject.is_empty() || source.subject.chars().count() > MAX_SUBJECT_LEN {
+            errors.push(FieldError::new(
+                "subject",
+                format!("must be 1 to {} characters", MAX_SUBJECT_LEN),
+            ));
+        }
+        if source.body.trim().is_empty() || source.body.chars().count() > MAX_BODY_LEN {
+            errors.push(FieldError::new(
+                "body",
+                format!("must be 1 to {} characters", MAX_BODY_LEN),
+            ));
+        }
+        if errors.is_empty() {
+            if let Err(err) = email::render(source, &template.sample_context()) {
+                errors.push(FieldError::new(field_of(&err), err));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(errors))
+        }
+    }
+}
+
+fn override_key(template: EmailTemplate) -> String {
+    format!("{}{}", OVERRIDE_KEY_PREFIX, template.name())
+}
+
+fn parse_name(name: &str) -> Result<EmailTemplate, AppError> {
+    name.parse().map_err(AppError::NotFound)
+}
+
+/// Request field a render error is about.
+fn field_of(error: &str) -> &'static str {
+    if error.starts_with("subject:") {
+        "subject"
+    } else {
+        "body"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(subject: &str, body: &str) -> TemplateSource {
+        TemplateSource {
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_override() {
+        let template = EmailTemplate::AccountApproved;
+        assert!(
+            EmailService::validate(template, &source("Welcome {{ name }}", "{{ login_url }}"))
+                .is_ok()
+        );
+
+        // Unknown variables are rejected before they are saved
+        let err = EmailService::validate(template, &source("Hi", "{{ invite_url }}")).unwrap_err();
+        assert!(matches!(err, AppError::InvalidFields(ref errors) if errors[0].field == "body"));
+        let err = EmailService::validate(template, &source("{{ nme }}", "Hi")).unwrap_err();
+        assert!(matches!(err, AppError::InvalidFields(ref errors) if errors[0].field == "subject"));
+        assert!(EmailService::validate(template, &source("", " ")).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! In-process bus for domain events.
+//!
+//! Services publish what happened as a [`DomainEvent`] instead of calling
+//! every subsystem that reacts to it; search indexing, frontend revalidation
+//! and Git export subscribe once at startup. Subscribers run synchronously
+//! in the publishing request, so they only queue work for their own
+//! background workers. Events that must survive a crash, like the webhook
+//! events in the outbox, are still written in the same transaction as the
+//! change itself.
+
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::models::Post;
+
+/// Where a change came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventOrigin {
+    /// The API or admin UI.
+    Api,
+    /// A Markdown file in the synced Git repository.
+    Git,
+}
+
+/// Something that happened in the domain.
+#[derive(Debug, Clone)]
+pub enum DomainEvent {
+    /// A post was created (`before` is `None`) or updated.
+    PostSaved {
+        before: Option<Post>,
+        after: Post,
+        origin: EventOrigin,
+        /// User who made the change; `None` for Git sync
+        actor_id: Option<Uuid>,
+    },
+    /// A post was deleted.
+    PostDeleted {
+        post: Post,
+        origin: EventOrigin,
+        actor_id: Option<Uuid>,
+    },
+}
+
+impl DomainEvent {
+    /// The post before and after the change (`None` when created or deleted).
+    pub fn post_change(&self) -> (Option<&Post>, Option<&Post>) {
+        match self {
+            Self::PostSaved { before, after, .. } => (before.as_ref(), Some(after)),
+            Self::PostDeleted { post, .. } => (Some(post), None),
+        }
+    }
+
+    /// Where the change came from.
+    pub fn origin(&self) -> EventOrigin {
+        match self {
+            Self::PostSaved { origin, .. } | Self::PostDeleted { origin, .. } => *origin,
+        }
+    }
+
+    /// User who made the change, if it came through the API.
+    pub fn actor_id(&self) -> Option<Uuid> {
+        match self {
+            Self::PostSaved { actor_id, .. } | Self::PostDeleted { actor_id, .. } => *actor_id,
+        }
+    }
+}
+
+/// Subsystem reacting to domain events.
+pub trait EventSubscriber: Send + Sync {
+    /// Handle one event; must not block.
+    fn handle(&self, event: &DomainEvent);
+}
+
+/// Handle for publishing domain events to every subscriber.
+#[derive(Clone)]
+pub struct EventBus {
+    subscribers: Arc<[Arc<dyn EventSubscriber>]>,
+}
+
+impl EventBus {
+    /// Create a bus delivering events to `subscribers`, in order.
+    pub fn new(subscribers: Vec<Arc<dyn EventSubscriber>>) -> Self {
+        Self {
+            subscribers: subscribers.into(),
+        }
+    }
+
+    /// Deliver `event` to every subscriber.
+    pub fn publish(&self, event: DomainEvent) {
+        for subscriber in self.subscribers.iter() {
+            subscriber.handle(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use chrono::Utc;
+
+    use crate::models::{PostStatus, PostVisibility};
+
+    /// Ids of the post before and after, and the origin, of a seen event.
+    type Seen = (Option<Uuid>, Option<Uuid>, EventOrigin);
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<Seen>>);
+
+    impl EventSubscriber for Recorder {
+        fn handle(&self, event: &DomainEvent) {
+            let (before, after) = event.post_change();
+            self.0.lock().unwrap().push((
+                before.map(|post| post.id),
+                after.map(|post| post.id),
+                event.origin(),
+            ));
+        }
+    }
+
+    fn post() -> Post {
+        Post {
+            id: Uuid::new_v4(),
+            site_id: Uuid::new_v4(),
+            title: "Title".to_string(),
+            slug: "title".to_string(),
+            content: String::new(),
+            excerpt: None,
+            status: PostStatus::Published,
+            visibility: PostVisibility::default(),
+            author_id: Uuid::new_v4(),
+            category_id: None,
+            published_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_publish() {
+        let first = Arc::new(Recorder::default());
+        let second = Arc::new(Recorder::default());
+        let bus = EventBus::new(vec![first.clone(), second.clone()]);
+        let post = post();
+
+        bus.publish(DomainEvent::PostSaved {
+            before: None,
+            after: post.clone(),
+            origin: EventOrigin::Api,
+            actor_id: Some(post.author_id),
+        });
+        bus.publish(DomainEvent::PostDeleted {
+            post: post.clone(),
+            origin: EventOrigin::Git,
+            actor_id: None,
+        });
+
+        let expected = vec![
+            (None, Some(post.id), EventOrigin::Api),
+            (Some(post.id), None, EventOrigin::Git),
+        ];
+        assert_eq!(*first.0.lock().unwrap(), expected);
+        assert_eq!(*second.0.lock().unwrap(), expected);
+    }
+}
@@ -0,0 +1,206 @@
+//! Relay delivering domain events from the outbox.
+//!
+//! Changes write their events to the `outbox` table in the same transaction
+//! (see [`OutboxRepository::insert`]), so an event exists exactly when its
+//! change committed, whatever happens to Redis or the network afterwards. The
+//! relay polls the table and POSTs each event to `EVENT_WEBHOOK_URL`, signed
+//! with `EVENT_WEBHOOK_SECRET`. Delivery is at least once: failed events are
+//! retried with backoff and dead-lettered once attempts run out, and receivers
+//! should drop repeated event IDs. Several instances can relay at once; each
+//! claims a different batch. Every attempt is kept in the webhook log.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::{EventEnvelope, Job, JobKind, OutboxEvent};
+use crate::pkg::signature;
+use crate::repositories::{FailedJobRepository, OutboxRepository};
+use crate::services::WebhookLog;
+use crate::startup::backoff_delay;
+
+/// Events delivered per relay round.
+const BATCH_SIZE: i64 = 50;
+/// How long claimed events stay hidden from other relays.
+const LEASE_SECONDS: i64 = 5 * 60;
+/// Delivery attempts per event before it is dead-lettered.
+const MAX_ATTEMPTS: u32 = 10;
+/// Longest wait between delivery attempts.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+/// Time allowed for the webhook to answer one delivery.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Header carrying the HMAC-SHA256 signature of the body.
+const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// Webhook receiving events and the secret signing them.
+struct Webhook {
+    url: String,
+    secret: String,
+    log: WebhookLog,
+}
+
+/// Service relaying outbox events to the event webhook.
+#[derive(Clone)]
+pub struct EventRelay {
+    outbox_repo: OutboxRepository,
+    failed_jobs: FailedJobRepository,
+    webhook: Option<Arc<Webhook>>,
+}
+
+impl EventRelay {
+    /// Create a relay delivering to `EVENT_WEBHOOK_URL`.
+    pub fn new(
+        config: &Config,
+        outbox_repo: OutboxRepository,
+        failed_jobs: FailedJobRepository,
+        log: WebhookLog,
+    ) -> Self {
+        let webhook = config
+            .event_webhook_url
+            .clone()
+            .zip(config.event_webhook_secret.clone())
+            .map(|(url, secret)| Arc::new(Webhook { url, secret, log }));
+        Self {
+            outbox_repo,
+            failed_jobs,
+            webhook,
+        }
+    }
+
+    /// Deliver due events until none are left, returning how many were handled.
+    ///
+    /// Without a webhook, events are discarded so the outbox does not grow.
+    pub async fn relay(&self) -> usize {
+        let mut handled = 0;
+        loop {
+            let events = match self.outbox_repo.claim(BATCH_SIZE, LEASE_SECONDS).await {
+                Ok(events) => events,
+                Err(err) => {
+                    tracing::warn!(error = %err, "Failed to read the outbox");
+                    return handled;
+                }
+            };
+            let claimed = events.len();
+            for event in events {
+                self.handle(event).await;
+            }
+            handled += claimed;
+            if claimed < BATCH_SIZE as usize {
+                return handled;
+            }
+        }
+    }
+
+    /// Deliver one event right away, without retrying, e.g. to retry a failed job.
+    pub async fn deliver_now(&self, job: &Job) -> Result<(), String> {
+        let Job::Event {
+            event_id,
+            event_type,
+            created_at,
+            data,
+        } = job
+        else {
+            return Err("Not an event".to_string());
+        };
+        let webhook = self
+            .webhook
+            .as_ref()
+            .ok_or("The event webhook is not configured")?;
+        deliver(
+            webhook,
+            &EventEnvelope::new(*event_id, event_type, *created_at, data),
+        )
+        .await
+    }
+
+    // Private helper methods
+
+    async fn handle(&self, event: OutboxEvent) {
+        let result = match &self.webhook {
+            Some(webhook) => {
+                let envelope = EventEnvelope::new(
+                    event.id,
+                    &event.event_type,
+                    event.created_at,
+                    &event.payload,
+                );
+                deliver(webhook, &envelope).await
+            }
+            None => Ok(()),
+        };
+
+        let outcome = match result {
+            Ok(()) => {
+                tracing::debug!(id = %event.id, event = %event.event_type, "Event delivered");
+                self.outbox_repo.delete(event.id).await
+            }
+            Err(error) => self.failed(event, &error).await,
+        };
+        if let Err(err) = outcome {
+            tracing::warn!(error = %err, "Failed to update the outbox");
+        }
+    }
+
+    /// Reschedule a failed event, or dead-letter it once attempts run out.
+    async fn failed(&self, event: OutboxEvent, error: &str) -> Result<(), AppError> {
+        let attempts = event.attempts as u32 + 1;
+        if attempts < MAX_ATTEMPTS {
+            let delay = backoff_delay(attempts, MAX_RETRY_DELAY);
+            tracing::warn!(
+                id = %event.id,
+                event = %event.event_type,
+                attempt = attempts,
+                retry_in_seconds = delay.as_secs(),
+                error = %error,
+                "Event delivery failed"
+            );
+            let next_attempt_at = Utc::now() + chrono::Duration::seconds(delay.as_secs() as i64);
+            return self
+                .outbox_repo
+                .reschedule(event.id, error, next_attempt_at)
+                .await;
+        }
+
+        tracing::error!(
+            id = %event.id,
+            event = %event.event_type,
+            error = %error,
+            "Event delivery failed; giving up"
+        );
+        let job = Job::Event {
+            event_id: event.id,
+            event_type: event.event_type,
+            created_at: event.created_at,
+            data: event.payload,
+        };
+        self.failed_jobs
+            .create(&job, error, attempts as i32)
+            .await?;
+        self.outbox_repo.delete(event.id).await
+    }
+}
+
+/// POST one event to the webhook.
+async fn deliver(webhook: &Webhook, envelope: &EventEnvelope<'_>) -> Result<(), String> {
+    let body =
+        serde_json::to_string(envelope).map_err(|e| format!("Failed to encode event: {}", e))?;
+    let signature = signature::sign(webhook.secret.as_bytes(), body.as_bytes());
+    let event_id = envelope.id.to_string();
+    webhook
+        .log
+        .post(
+            JobKind::Event,
+            &webhook.url,
+            &[
+                (SIGNATURE_HEADER, signature.as_str()),
+                ("X-Event-Id", event_id.as_str()),
+                ("X-Event-Type", envelope.event_type),
+            ],
+            body,
+            REQUEST_TIMEOUT,
+        )
+        .await
+}
@@ -0,0 +1,593 @@
+//! Two-way sync between posts and a Git repository of Markdown files.
+//!
+//! Pushes to the configured branch arrive as webhooks and queue an import of
+//! the files they changed; post writes in the CMS queue an export that commits
+//! the post's file and pushes it back. A single worker owns the working copy
+//! and runs both in order. When both sides edit the same post before syncing,
+//! the repository wins.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::models::{Post, PostFrontMatter, PostResponse};
+use crate::pkg::git::{FileChange, GitRepo};
+use crate::pkg::QueueDepth;
+use crate::repositories::{SiteRepository, UserRepository};
+use crate::services::{DomainEvent, EventOrigin, EventSubscriber, PostService};
+
+/// Name on commits exported from the CMS.
+const COMMIT_AUTHOR_NAME: &str = "Website CMS";
+
+#[derive(Debug)]
+enum SyncJob {
+    /// Import what was pushed to the branch
+    Import,
+    /// Write a changed post back to the repository
+    Export(PostExport),
+}
+
+#[derive(Debug)]
+struct PostExport {
+    site_id: Uuid,
+    id: Uuid,
+    /// Slug before the change, whose file is replaced or removed
+    old_slug: Option<String>,
+    deleted: bool,
+}
+
+/// Handle for queueing Git imports and exports.
+#[derive(Clone)]
+pub struct GitSync {
+    sender: Option<mpsc::UnboundedSender<SyncJob>>,
+    depth: QueueDepth,
+    branch_ref: Arc<str>,
+}
+
+/// Worker owning the working copy, started once the post service exists.
+pub struct GitSyncWorker {
+    receiver: mpsc::UnboundedReceiver<SyncJob>,
+    depth: QueueDepth,
+    repo: GitRepo,
+    remote: String,
+    branch: String,
+    path: String,
+    site_host: String,
+    author_email: String,
+}
+
+impl GitSync {
+    /// Set up syncing, returning the worker to start unless no repository is configured.
+    pub fn new(config: &Config) -> (Self, Option<GitSyncWorker>) {
+        let branch_ref = format!("refs/heads/{}", config.git_sync_branch).into();
+        let depth = QueueDepth::new();
+        let Some(remote) = config.git_sync_repo.clone() else {
+            let disabled = Self {
+                sender: None,
+                depth,
+                branch_ref,
+            };
+            return (disabled, None);
+        };
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let worker = GitSyncWorker {
+            receiver,
+            depth: depth.clone(),
+            repo: GitRepo::new(&config.git_sync_dir),
+            remote,
+            branch: config.git_sync_branch.clone(),
+            path: config.git_sync_path.clone(),
+            site_host: config.git_sync_site.clone().unwrap_or_default(),
+            author_email: config.git_sync_author_email.clone().unwrap_or_default(),
+        };
+        let git_sync = Self {
+            sender: Some(sender),
+            depth,
+            branch_ref,
+        };
+        (git_sync, Some(worker))
+    }
+
+    /// Gauge of imports and exports waiting to run.
+    pub fn queue_depth(&self) -> QueueDepth {
+        self.depth.clone()
+    }
+
+    /// Whether a repository is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// Queue an import after a push to `git_ref`; pushes to other branches are ignored.
+    pub fn push_received(&self, git_ref: &str) -> bool {
+        git_ref == self.branch_ref.as_ref() && self.send(SyncJob::Import)
+    }
+
+    /// Queue an export of a post going from `before` to `after`
+    /// (`None` when it was just created or deleted).
+    pub fn post_changed(&self, before: Option<&Post>, after: Option<&Post>) {
+        let Some(post) = after.or(before) else {
+            return;
+        };
+        self.send(SyncJob::Export(PostExport {
+            site_id: post.site_id,
+            id: post.id,
+            old_slug: before.map(|post| post.slug.clone()),
+            deleted: after.is_none(),
+        }));
+    }
+
+    // Private helper methods
+
+    fn send(&self, job: SyncJob) -> bool {
+        let Some(sender) = &self.sender else {
+            return false;
+        };
+        if sender.send(job).is_err() {
+            tracing::warn!("Git sync worker is not running");
+            return false;
+        }
+        self.depth.queued();
+        true
+    }
+}
+
+impl EventSubscriber for GitSync {
+    fn handle(&self, event: &DomainEvent) {
+        // Changes imported from the repository are already there
+        if event.origin() == EventOrigin::Api {
+            let (before, after) = event.post_change();
+            self.post_changed(before, after);
+        }
+    }
+}
+
+impl GitSyncWorker {
+    /// Start syncing posts of the configured site through `post_service`.
+    pub fn spawn(
+        self,
+        post_service: PostService,
+        site_repo: SiteRepository,
+        user_repo: UserRepository,
+    ) {
+        tokio::spawn(run(self, post_service, site_repo, user_repo));
+    }
+}
+
+/// Everything a sync run needs once the site and author are known.
+struct Syncer {
+    repo: GitRepo,
+    remote: String,
+    branch: String,
+    path: String,
+    author_email: String,
+    site_id: Uuid,
+    author_id: Uuid,
+    post_service: PostService,
+}
+
+async fn run(
+    worker: GitSyncWorker,
+    post_service: PostService,
+    site_repo: SiteRepository,
+    user_repo: UserRepository,
+) {
+    let GitSyncWorker {
+        mut receiver,
+        depth,
+        repo,
+        remote,
+        branch,
+        path,
+        site_host,
+        author_email,
+    } = worker;
+
+    let site = match site_repo.find_for_host(&site_host).await {
+        Ok(Some(site)) => site,
+        Ok(None) => {
+            tracing::error!(host = %site_host, "Git sync disabled: no site to sync");
+            return;
+        }
+        Err(err) => {
+            tracing::error!(error = %err, "Git sync disabled: failed to load site");
+            return;
+        }
+    };
+    let author = match user_repo.find_by_email_with_role(&author_email).await {
+        Ok(Some(author)) => author,
+        Ok(None) => {
+            tracing::error!(email = %author_email, "Git sync disabled: author does not exist");
+            return;
+        }
+        Err(err) => {
+            tracing::error!(error = %err, "Git sync disabled: failed to load author");
+            return;
+        }
+    };
+
+    let syncer = Syncer {
+        repo,
+        remote,
+        branch,
+        path,
+        author_email,
+        site_id: site.id,
+        author_id: author.id,
+        post_service,
+    };
+    if let Err(err) = syncer.checkout().await {
+        tracing::warn!(error = %err, "Git sync checkout failed");
+    }
+
+    while let Some(job) = receiver.recv().await {
+        let mut jobs = vec![job];
+        while let Ok(job) = receiver.try_recv() {
+            jobs.push(job);
+        }
+        depth.taken(jobs.len());
+
+        let mut import = false;
+        let mut exports = Vec::new();
+        for job in jobs {
+            match job {
+                SyncJob::Import => import = true,
+                SyncJob::Export(export) if export.site_id == syncer.site_id => exports.push(export),
+                SyncJob::Export(_) => {}
+            }
+        }
+        if let Err(err) = syncer.sync(import, &exports).await {
+            tracing::warn!(error = %err, "Git sync failed");
+        }
+    }
+}
+
+impl Syncer {
+    async fn sync(&self, import: bool, exports: &[PostExport]) -> Result<(), String> {
+        self.checkout().await?;
+        if import {
+            self.pull().await?;
+        }
+        if !exports.is_empty() {
+            self.export(exports).await?;
+        }
+        Ok(())
+    }
+
+    /// Clone the repository when there is no working copy yet, importing every post file.
+    async fn checkout(&self) -> Result<(), String> {
+        if self.repo.exists() {
+            return Ok(());
+        }
+        self.repo.clone_from(&self.remote, &self.branch).await?;
+        tracing::info!(dir = %self.repo.dir().display(), "Cloned Git sync repository");
+
+        let dir = self.repo.dir().join(&self.path);
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(format!("Failed to read {}: {}", dir.display(), err)),
+        };
+        for entry in entries.flatten() {
+            let file = format!("{}/{}", self.path, entry.file_name().to_string_lossy());
+            self.apply(FileChange::Changed(file)).await;
+        }
+        Ok(())
+    }
+
+    /// Bring in remote commits and import the post files they changed.
+    async fn pull(&self) -> Result<(), String> {
+        let before = self.repo.head().await?;
+        self.repo.fetch(&self.branch).await?;
+        if self.repo.rebase_onto_remote(&self.branch).await? {
+            tracing::warn!("Local Git sync commits conflicted with the remote and were dropped");
+        }
+        let after = self.repo.head().await?;
+        if before == after {
+            return Ok(());
+        }
+
+        for change in self.repo.changes(&before, &after, &self.path).await? {
+            self.apply(change).await;
+        }
+        Ok(())
+    }
+
+    /// Import or delete the post behind one changed file, logging failures.
+    async fn apply(&self, change: FileChange) {
+        let (FileChange::Changed(file) | FileChange::Deleted(file)) = &change;
+        let Some(slug) = file_slug(&self.path, file) else {
+            return;
+        };
+
+        let result = match &change {
+            FileChange::Changed(_) => self.import_file(file, slug).await,
+            FileChange::Deleted(_) => self
+                .post_service
+                .delete_from_git(self.site_id, slug)
+                .await
+                .map(drop)
+                .map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(()) => tracing::info!(file = %file, "Synced post from Git"),
+            Err(err) => tracing::warn!(file = %file, error = %err, "Failed to sync post from Git"),
+        }
+    }
+
+    async fn import_file(&self, file: &str, slug: &str) -> Result<(), String> {
+        let text = std::fs::read_to_string(self.repo.dir().join(file))
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        let (mut front_matter, content) = parse_post_file(&text)?;
+        front_matter.slug.get_or_insert_with(|| slug.to_string());
+
+        self.post_service
+            .sync_from_git(self.site_id, self.author_id, front_matter, content)
+            .await
+            .map(drop)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Write changed posts to their files, commit and push.
+    async fn export(&self, exports: &[PostExport]) -> Result<(), String> {
+        let ids: Vec<Uuid> = exports
+            .iter()
+            .filter(|export| !export.deleted)
+            .map(|export| export.id)
+            .collect();
+        let mut posts = self
+            .post_service
+            .get_many_by_ids(self.site_id, &ids)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut slugs = Vec::new();
+        for export in exports {
+            let post = if export.deleted {
+                None
+            } else {
+                posts
+                    .iter()
+                    .position(|post| post.id == export.id)
+                    .map(|index| posts.swap_remove(index))
+            };
+
+            if let Some(old_file) = export.old_slug.as_deref().and_then(|s| self.post_file(s)) {
+                if post.as_ref().map(|post| post.slug.as_str()) != export.old_slug.as_deref() {
+                    remove_file(&old_file)?;
+                }
+            }
+            if let Some(post) = &post {
+                let Some(file) = self.post_file(&post.slug) else {
+                    tracing::warn!(slug = %post.slug, "Post slug cannot be used as a file name");
+                    continue;
+                };
+                if let Some(parent) = file.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+                }
+                std::fs::write(&file, render_post_file(post))
+                    .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+            }
+            slugs.push(match (&post, &export.old_slug) {
+                (Some(post), _) => format!("Update post {}", post.slug),
+                (None, Some(slug)) => format!("Delete post {}", slug),
+                (None, None) => continue,
+            });
+        }
+
+        let message = match slugs.as_slice() {
+            [] => return Ok(()),
+            [single] => single.clone(),
+            _ => format!("Update {} posts", slugs.len()),
+        };
+        let committed = self
+            .repo
+            .commit_all(&self.path, &message, COMMIT_AUTHOR_NAME, &self.author_email)
+            .await?;
+        if !committed {
+            return Ok(());
+        }
+
+        // A rejected push usually means the remote moved on; catch up and retry once
+        if self.repo.push(&self.branch).await.is_err() {
+            self.pull().await?;
+            self.repo.push(&self.branch).await?;
+        }
+        tracing::info!(message = %message, "Exported posts to Git");
+        Ok(())
+    }
+
+    /// Path of a post's file in the working copy, unless the slug is not a plain file name.
+    fn post_file(&self, slug: &str) -> Option<PathBuf> {
+        if slug.is_empty() || slug.starts_with('.') || slug.contains(['/', '\\']) {
+            return None;
+        }
+        Some(
+            self.repo
+                .dir()
+                .join(&self.path)
+                .join(format!("{}.md", slug)),
+        )
+    }
+}
+
+fn remove_file(file: &std::path::Path) -> Result<(), String> {
+    match std::fs::remove_file(file) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove {}: {}", file.display(), err))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Slug of a post file directly inside `path`, i.e. its name without `.md`.
+fn file_slug<'a>(path: &str, file: &'a str) -> Option<&'a str> {
+    let name = file.strip_prefix(path)?.strip_prefix('/')?;
+    if name.contains('/') {
+        return None;
+    }
+    name.strip_suffix(".md").filter(|slug| !slug.is_empty())
+}
+
+/// Split a Markdown file into its YAML front matter and body.
+fn parse_post_file(text: &str) -> Result<(PostFrontMatter, &str), String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let rest = text
+        .strip_prefix("---")
+        .and_then(|rest| {
+            rest.strip_prefix('\n')
+                .or_else(|| rest.strip_prefix("\r\n"))
+        })
+        .ok_or("File does not start with --- front matter")?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let front_matter = config::Config::builder()
+                .add_source(config::File::from_str(
+                    &rest[..offset],
+                    config::FileFormat::Yaml,
+                ))
+                .build()
+                .and_then(|yaml| yaml.try_deserialize::<PostFrontMatter>())
+                .map_err(|e| format!("Invalid front matter: {}", e))?;
+            let body = rest[offset + line.len()..].trim_matches(['\r', '\n']);
+            return Ok((front_matter, body));
+        }
+        offset += line.len();
+    }
+    Err("Front matter is not closed with ---".to_string())
+}
+
+/// Render a post as a Markdown file with YAML front matter.
+///
+/// Values are written as JSON strings, which YAML reads as double-quoted scalars.
+fn render_post_file(post: &PostResponse) -> String {
+    let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+
+    let mut text = String::from("---\n");
+    text.push_str(&format!("id: {}\n", quote(&post.id.to_string())));
+    text.push_str(&format!("title: {}\n", quote(&post.title)));
+    text.push_str(&format!("slug: {}\n", quote(&post.slug)));
+    text.push_str(&format!("status: {}\n", post.status));
+    text.push_str(&format!("visibility: {}\n", post.visibility));
+    if let Some(excerpt) = &post.excerpt {
+        text.push_str(&format!("excerpt: {}\n", quote(excerpt)));
+    }
+    if let Some(category) = &post.category {
+        text.push_str(&format!("category: {}\n", quote(&category.slug)));
+    }
+    let tags: Vec<String> = post.tags.iter().map(|tag| quote(&tag.name)).collect();
+    text.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+    if let Some(author) = &post.author {
+        text.push_str(&format!("author: {}\n", quote(&author.email)));
+    }
+    text.push_str("---\n\n");
+    text.push_str(post.content.trim_end_matches(['\r', '\n']));
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+
+    use crate::models::{PostStatus, PostVisibility, Tag};
+
+    #[test]
+    fn test_file_slug() {
+        assert_eq!(
+            file_slug("posts", "posts/hello-world.md"),
+            Some("hello-world")
+        );
+        assert_eq!(file_slug("content/posts", "content/posts/a.md"), Some("a"));
+        assert_eq!(file_slug("posts", "posts/drafts/a.md"), None);
+        assert_eq!(file_slug("posts", "posts/image.png"), None);
+        assert_eq!(file_slug("posts", "postscript/a.md"), None);
+        assert_eq!(file_slug("posts", "posts/.md"), None);
+    }
+
+    #[test]
+    fn test_parse_post_file() {
+        let text = "---\ntitle: Hello World\nstatus: published\ntags:\n  - Rust\n  - Web Dev\n\
+                    date: 2024-01-01\n---\n\n# Hello\n\nBody text.\n";
+        let (front_matter, body) = parse_post_file(text).unwrap();
+        assert_eq!(front_matter.title, "Hello World");
+        assert_eq!(front_matter.status, Some(PostStatus::Published));
+        assert_eq!(front_matter.tags, vec!["Rust", "Web Dev"]);
+        assert_eq!(front_matter.slug, None);
+        assert_eq!(body, "# Hello\n\nBody text.");
+
+        assert!(parse_post_file("# No front matter").is_err());
+        assert!(parse_post_file("---\ntitle: Unclosed\n").is_err());
+        assert!(parse_post_file("---\nslug: no-title\n---\n").is_err());
+    }
+
+    #[test]
+    fn test_render_post_file_roundtrip() {
+        let post = PostResponse {
+            id: Uuid::new_v4(),
+            title: "Quotes \"and\" colons: #1".to_string(),
+            slug: "quotes".to_string(),
+            content: "Line one\n\n---\n\nLine two\n".to_string(),
+            excerpt: Some("Short".to_string()),
+            status: PostStatus::Draft,
+            visibility: PostVisibility::Members,
+            author: None,
+            category: None,
+            tags: vec![Tag {
+                id: Uuid::new_v4(),
+                name: "Rust".to_string(),
+                slug: "rust".to_string(),
+                created_at: Utc::now(),
+                deleted_at: None,
+            }],
+            polls: vec![],
+            attachments: vec![],
+            published_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            structured_data: None,
+        };
+
+        let text = render_post_file(&post);
+        let (front_matter, body) = parse_post_file(&text).unwrap();
+        assert_eq!(
+            front_matter,
+            PostFrontMatter {
+                id: Some(post.id),
+                title: post.title.clone(),
+                slug: Some("quotes".to_string()),
+                excerpt: Some("Short".to_string()),
+                status: Some(PostStatus::Draft),
+                visibility: Some(PostVisibility::Members),
+                category: None,
+                tags: vec!["Rust".to_string()],
+                author: None,
+            }
+        );
+        assert_eq!(body, "Line one\n\n---\n\nLine two");
+    }
+
+    #[test]
+    fn test_push_received() {
+        let config = Config {
+            git_sync_repo: Some("https://example.com/content.git".to_string()),
+            ..Config::default()
+        };
+        let (git_sync, _worker) = GitSync::new(&config);
+        assert!(git_sync.push_received("refs/heads/main"));
+        assert!(!git_sync.push_received("refs/heads/feature"));
+
+        let (disabled, worker) = GitSync::new(&Config::default());
+        assert!(worker.is_none());
+        assert!(!disabled.push_received("refs/heads/main"));
+    }
+}
@@ -0,0 +1,225 @@
+//! Inbox for webhooks from third-party services.
+//!
+//! Each integration posts to `/api/hooks/{integration}` and is verified with
+//! its own secret and signature scheme. Verified deliveries are stored
+//! verbatim before being routed to their handler, so a delivery that failed
+//! or arrived before its handler existed can be inspected and replayed by an
+//! admin. Unverified requests are rejected without being stored, and a
+//! delivery whose sender ID was seen before is acknowledged without being
+//! handled again, so retries don't repeat their effects.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::{
+    GitPushEvent, HookDelivery, HookDeliveryQuery, HookIntegration, HookReceipt, HookStatus,
+};
+use crate::pkg::signature;
+use crate::repositories::HookRepository;
+use crate::response::Meta;
+use crate::services::GitSync;
+
+/// How far a Stripe signature's timestamp may be from now, as Stripe's own libraries allow.
+const STRIPE_TOLERANCE_SECONDS: i64 = 5 * 60;
+
+/// Service receiving, storing and replaying inbound webhooks.
+#[derive(Clone)]
+pub struct HookService {
+    repo: HookRepository,
+    git_sync: GitSync,
+    secrets: Arc<HashMap<HookIntegration, Vec<String>>>,
+}
+
+impl HookService {
+    /// Create a new hook service with the integrations that have a secret configured.
+    ///
+    /// GitHub deliveries may also be signed with `GIT_SYNC_WEBHOOK_SECRET`, for
+    /// webhooks still pointing at `/api/webhooks/git`.
+    pub fn new(repo: HookRepository, git_sync: GitSync, config: &Config) -> Self {
+        let mut secrets: HashMap<HookIntegration, Vec<String>> = HashMap::new();
+        for (integration, secret) in [
+            (HookIntegration::Github, &config.hook_github_secret),
+            (HookIntegration::Github, &config.git_sync_webhook_secret),
+            (HookIntegration::Stripe, &config.hook_stripe_secret),
+            (HookIntegration::Newsletter, &config.hook_newsletter_secret),
+        ] {
+            if let Some(secret) = secret {
+                secrets.entry(integration).or_default().push(secret.clone());
+            }
+        }
+        Self {
+            repo,
+            git_sync,
+            secrets: Arc::new(secrets),
+        }
+    }
+
+    /// Whether `integration` has a secret and accepts deliveries.
+    pub fn is_enabled(&self, integration: HookIntegration) -> bool {
+        self.secrets.contains_key(&integration)
+    }
+
+    /// Whether `signature`, the integration's signature header, signs `body`
+    /// with one of its secrets.
+    pub fn verify(&self, integration: HookIntegration, body: &[u8], signature: &str) -> bool {
+        let Some(secrets) = self.secrets.get(&integration) else {
+            return false;
+        };
+        secrets.iter().any(|secret| match integration {
+            HookIntegration::Stripe => signature::verify_stripe(
+                secret.as_bytes(),
+                body,
+                signature,
+                Utc::now().timestamp(),
+                STRIPE_TOLERANCE_SECONDS,
+            ),
+            HookIntegration::Github | HookIntegration::Newsletter => {
+                signature::verify(secret.as_bytes(), body, signature)
+            }
+        })
+    }
+
+    /// Store a verified delivery and hand it to its integration's handler,
+    /// unless a delivery with the same sender ID was stored before.
+    pub async fn receive(
+        &self,
+        integration: HookIntegration,
+        delivery_header: Option<&str>,
+        event_header: Option<&str>,
+        headers: Value,
+        body: &[u8],
+    ) -> Result<HookReceipt, AppError> {
+        let payload = std::str::from_utf8(body)
+            .map_err(|_| AppError::ValidationError("Payload must be UTF-8".to_string()))?;
+        let json: Value = serde_json::from_str(payload)
+            .map_err(|e| AppError::ValidationError(format!("Invalid JSON payload: {}", e)))?;
+        let delivery_id = integration.delivery_id(delivery_header, &json);
+        let event_type = integration.event_type(event_header, &json);
+
+        let created = self
+            .repo
+            .create(
+                integration,
+                delivery_id.as_deref(),
+                event_type.as_deref(),
+                headers,
+                payload,
+            )
+            .await?;
+        let (delivery, duplicate) = match (created, delivery_id) {
+            (Some(delivery), _) => (self.handle(&delivery).await?, false),
+            (None, Some(delivery_id)) => {
+                let delivery = self
+                    .repo
+                    .find_by_delivery_id(integration, &delivery_id)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::InternalError("Duplicate webhook delivery vanished".to_string())
+                    })?;
+                tracing::info!(
+                    id = %delivery.id,
+                    integration = %integration,
+                    delivery_id,
+                    "Ignoring repeated webhook delivery"
+                );
+                (delivery, true)
+            }
+            (None, None) => {
+                return Err(AppError::InternalError(
+                    "Webhook delivery was not stored".to_string(),
+                ))
+            }
+        };
+        Ok(HookReceipt {
+            id: delivery.id,
+            status: delivery.status,
+            duplicate,
+        })
+    }
+
+    /// List deliveries, newest first.
+    pub async fn list(
+        &self,
+        query: HookDeliveryQuery,
+    ) -> Result<(Vec<HookDelivery>, Meta), AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+        let offset = (page - 1) * per_page;
+
+        let deliveries = self
+            .repo
+            .find_all(query.integration, query.status, per_page, offset)
+            .await?;
+        let total = self.repo.count(query.integration, query.status).await?;
+        Ok((deliveries, Meta::new(page, per_page, total)))
+    }
+
+    /// Get a delivery with its headers and payload.
+    pub async fn get(&self, id: Uuid) -> Result<HookDelivery, AppError> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Webhook delivery not found".to_string()))
+    }
+
+    /// Hand a stored delivery to its handler again, e.g. after fixing what made it fail.
+    pub async fn replay(&self, id: Uuid) -> Result<HookDelivery, AppError> {
+        let delivery = self.get(id).await?;
+        self.handle(&delivery).await
+    }
+
+    /// Run a delivery's handler and record the outcome.
+    async fn handle(&self, delivery: &HookDelivery) -> Result<HookDelivery, AppError> {
+        let (status, error) = match self.dispatch(delivery) {
+            Ok(status) => (status, None),
+            Err(error) => {
+                tracing::warn!(
+                    id = %delivery.id,
+                    integration = %delivery.integration,
+                    error = %error,
+                    "Failed to handle webhook"
+                );
+                (HookStatus::Failed, Some(error))
+            }
+        };
+        self.repo
+            .record_attempt(delivery.id, status, error.as_deref())
+            .await
+    }
+
+    /// Route a delivery to the handler of its integration.
+    fn dispatch(&self, delivery: &HookDelivery) -> Result<HookStatus, String> {
+        match delivery.integration {
+            HookIntegration::Github => self.handle_github(delivery),
+            // Kept for replay once billing and newsletter features handle them
+            HookIntegration::Stripe | HookIntegration::Newsletter => Ok(HookStatus::Ignored),
+        }
+    }
+
+    /// Queue a Git sync import for pushes to the synced branch.
+    fn handle_github(&self, delivery: &HookDelivery) -> Result<HookStatus, String> {
+        match delivery.event_type.as_deref() {
+            Some("ping") => Ok(HookStatus::Processed),
+            Some("push") => {
+                let event: GitPushEvent = serde_json::from_str(&delivery.payload)
+                    .map_err(|e| format!("Invalid push event: {}", e))?;
+                let queued = event
+                    .git_ref
+                    .as_deref()
+                    .is_some_and(|git_ref| self.git_sync.push_received(git_ref));
+                Ok(if queued {
+                    HookStatus::Processed
+                } else {
+                    HookStatus::Ignored
+                })
+            }
+            _ => Ok(HookStatus::Ignored),
+        }
+    }
+}
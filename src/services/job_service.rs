@@ -0,0 +1,241 @@
+//! Background job failures and the dead-letter queue.
+//!
+//! Deliveries that run out of attempts (frontend revalidations, background
+//! emails, outbox events) are stored in `failed_jobs` with their payload and last error
+//! instead of only being logged, so admins can inspect them, retry them once
+//! the receiving end is back, or purge them. Every webhook attempt, successful
+//! or not, is also kept for a while in the webhook log and can be sent again.
+
+use chrono::{Duration, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    FailedJob, FailedJobQuery, Job, PurgeFailedJobsQuery, RetryResult, WebhookAttempt,
+    WebhookAttemptQuery,
+};
+use crate::pkg::email::RenderedEmail;
+use crate::pkg::EmailTemplate;
+use crate::repositories::{FailedJobRepository, WebhookAttemptRepository};
+use crate::response::{BulkResponse, Meta};
+use crate::services::{EmailService, EventRelay, Revalidator};
+
+/// Most jobs retried by one retry-all request, oldest failures first.
+const MAX_RETRY_ALL: i64 = 500;
+
+/// Service running background jobs and managing those that failed.
+#[derive(Clone)]
+pub struct JobService {
+    repo: FailedJobRepository,
+    webhook_attempts: WebhookAttemptRepository,
+    email_service: EmailService,
+    revalidator: Revalidator,
+    event_relay: EventRelay,
+}
+
+impl JobService {
+    /// Create a new job service.
+    pub fn new(
+        repo: FailedJobRepository,
+        webhook_attempts: WebhookAttemptRepository,
+        email_service: EmailService,
+        revalidator: Revalidator,
+        event_relay: EventRelay,
+    ) -> Self {
+        Self {
+            repo,
+            webhook_attempts,
+            email_service,
+            revalidator,
+            event_relay,
+        }
+    }
+
+    /// Render and send an email in the background, dead-lettering it if delivery fails.
+    pub fn send_email(&self, to: &str, template: EmailTemplate, variables: Value) {
+        let service = self.clone();
+        let to = to.to_string();
+        tokio::spawn(async move {
+            let email = match service.email_service.render(template, variables).await {
+                Ok(email) => email,
+                Err(err) => {
+                    tracing::error!(error = %err, template = %template, "Failed to render email");
+                    return;
+                }
+            };
+            let job = Job::Email {
+                to,
+                subject: email.subject,
+                body: email.body,
+            };
+            if let Err(error) = service.execute(&job).await {
+                tracing::warn!(error = %error, template = %template, "Failed to send email");
+                service.dead_letter(&job, &error).await;
+            }
+        });
+    }
+
+    /// List failed jobs, most recently failed first.
+    pub async fn list_failed(
+        &self,
+        query: FailedJobQuery,
+    ) -> Result<(Vec<FailedJob>, Meta), AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+        let offset = (page - 1) * per_page;
+
+        let jobs = self.repo.find_all(query.kind, per_page, offset).await?;
+        let total = self.repo.count(query.kind).await?;
+        Ok((jobs, Meta::new(page, per_page, total)))
+    }
+
+    /// Run a failed job again, removing it from the queue if it succeeds.
+    pub async fn retry(&self, id: Uuid) -> Result<RetryResult, AppError> {
+        let failed = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Failed job not found".to_string()))?;
+
+        let outcome = match Job::from_failed(&failed) {
+            Ok(job) => self.execute(&job).await,
+            Err(err) => Err(err),
+        };
+        match outcome {
+            Ok(()) => {
+                self.repo.delete(id).await?;
+                tracing::info!(%id, kind = ?failed.kind, "Retried failed job");
+                Ok(RetryResult {
+                    id,
+                    succeeded: true,
+                    error: None,
+                })
+            }
+            Err(error) => {
+                self.repo.record_attempt(id, &error).await?;
+                Ok(RetryResult {
+                    id,
+                    succeeded: false,
+                    error: Some(error),
+                })
+            }
+        }
+    }
+
+    /// Retry every failed job, oldest first, one at a time, reporting each.
+    ///
+    /// A job that fails again, or was purged meanwhile, counts as a failed item.
+    pub async fn retry_all(&self) -> Result<BulkResponse<RetryResult>, AppError> {
+        let mut response = BulkResponse::default();
+        for id in self.repo.find_oldest_ids(MAX_RETRY_ALL).await? {
+            let result = self.retry(id).await.and_then(|result| match &result.error {
+                Some(error) => Err(AppError::ServiceUnavailable(format!(
+                    "Job failed again: {}",
+                    error
+                ))),
+                None => Ok(result),
+            });
+            response.push(Some(id.to_string()), result);
+        }
+        Ok(response)
+    }
+
+    /// Delete a failed job without running it.
+    pub async fn delete_failed(&self, id: Uuid) -> Result<(), AppError> {
+        if self.repo.delete(id).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound("Failed job not found".to_string()))
+        }
+    }
+
+    /// Delete failed jobs that last failed more than `older_than_days` ago, or all of them.
+    pub async fn purge_failed(&self, query: PurgeFailedJobsQuery) -> Result<u64, AppError> {
+        let before = match query.older_than_days {
+            Some(days) if days < 0 => {
+                return Err(AppError::ValidationError(
+                    "older_than_days must not be negative".to_string(),
+                ))
+            }
+            Some(days) => Some(Utc::now() - Duration::days(days)),
+            None => None,
+        };
+        self.repo.delete_older_than(before).await
+    }
+
+    /// List logged webhook attempts, newest first.
+    pub async fn list_webhook_attempts(
+        &self,
+        query: WebhookAttemptQuery,
+    ) -> Result<(Vec<WebhookAttempt>, Meta), AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+        let offset = (page - 1) * per_page;
+
+        let attempts = self
+            .webhook_attempts
+            .find_all(query.kind, query.failed, per_page, offset)
+            .await?;
+        let total = self
+            .webhook_attempts
+            .count(query.kind, query.failed)
+            .await?;
+        Ok((attempts, Meta::new(page, per_page, total)))
+    }
+
+    /// Get a logged webhook attempt with its request and response.
+    pub async fn get_webhook_attempt(&self, id: Uuid) -> Result<WebhookAttempt, AppError> {
+        self.webhook_attempts
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Webhook attempt not found".to_string()))
+    }
+
+    /// Send a logged delivery again, freshly signed; the new attempt is logged too.
+    pub async fn redeliver_webhook(&self, id: Uuid) -> Result<RetryResult, AppError> {
+        let attempt = self.get_webhook_attempt(id).await?;
+        let job = attempt.job().map_err(AppError::ValidationError)?;
+        let outcome = self.execute(&job).await;
+        tracing::info!(%id, kind = ?attempt.kind, succeeded = outcome.is_ok(), "Redelivered webhook");
+        Ok(RetryResult {
+            id,
+            succeeded: outcome.is_ok(),
+            error: outcome.err(),
+        })
+    }
+
+    /// Delete webhook attempts older than `retention_days`.
+    pub async fn prune_webhook_attempts(&self, retention_days: u64) -> Result<u64, AppError> {
+        let before = Utc::now() - Duration::days(retention_days as i64);
+        self.webhook_attempts.delete_older_than(before).await
+    }
+
+    // Private helper methods
+
+    /// Run a job once.
+    async fn execute(&self, job: &Job) -> Result<(), String> {
+        match job {
+            Job::Revalidation { site_id, paths } => {
+                self.revalidator.deliver_now(*site_id, paths).await
+            }
+            Job::Email { to, subject, body } => {
+                let email = RenderedEmail {
+                    subject: subject.clone(),
+                    body: body.clone(),
+                };
+                self.email_service
+                    .send_rendered(to, email)
+                    .await
+                    .map_err(|err| err.to_string())
+            }
+            Job::Event { .. } => self.event_relay.deliver_now(job).await,
+        }
+    }
+
+    async fn dead_letter(&self, job: &Job, error: &str) {
+        if let Err(err) = self.repo.create(job, error, 1).await {
+            tracing::error!(error = %err, kind = ?job.kind(), "Failed to dead-letter job");
+        }
+    }
+}
@@ -0,0 +1,710 @@
+//! Media service for storing uploaded files.
+
+use std::collections::HashSet;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration as StdDuration, SystemTime};
+
+use chrono::{Duration, Utc};
+use redis::AsyncCommands;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, FieldError};
+use crate::models::{
+    CreateUploadRequest, Media, MediaQuery, MediaResponse, MediaUsage, PostStatus,
+    UpdateMediaRequest, UploadProgress, UploadSession,
+};
+use crate::pkg::redis::keys;
+use crate::repositories::MediaRepository;
+use crate::response::Meta;
+
+/// Image types accepted for avatars, with the extension they are stored under.
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+];
+
+/// Types accepted in the media library besides images, e.g. for post attachments.
+const FILE_TYPES: &[(&str, &str)] = &[
+    ("application/pdf", "pdf"),
+    ("application/zip", "zip"),
+    ("application/vnd.ms-powerpoint", "ppt"),
+    (
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "pptx",
+    ),
+    ("application/vnd.oasis.opendocument.presentation", "odp"),
+    ("video/mp4", "mp4"),
+    ("video/webm", "webm"),
+];
+
+/// How long the lock held while writing a chunk lasts if never released.
+const UPLOAD_LOCK_TTL_SECONDS: u64 = 5 * 60;
+
+/// Longest filename (the `filename` column is `VARCHAR(255)`).
+const MAX_FILENAME_LEN: usize = 255;
+/// Longest alt text (the `alt_text` column is `VARCHAR(500)`).
+const MAX_ALT_TEXT_LEN: usize = 500;
+
+/// Service for uploaded media on local disk.
+#[derive(Clone)]
+pub struct MediaService {
+    media_repo: MediaRepository,
+    redis: redis::aio::ConnectionManager,
+    upload_dir: PathBuf,
+    max_upload_bytes: usize,
+    partial_dir: PathBuf,
+    max_chunked_upload_bytes: u64,
+    upload_session_ttl_seconds: u64,
+    writers: ChunkWriters,
+}
+
+impl MediaService {
+    /// Create a new media service.
+    pub fn new(
+        config: &Config,
+        media_repo: MediaRepository,
+        redis: redis::aio::ConnectionManager,
+    ) -> Self {
+        Self {
+            media_repo,
+            redis,
+            upload_dir: PathBuf::from(&config.upload_dir),
+            max_upload_bytes: config.max_upload_bytes,
+            partial_dir: PathBuf::from(&config.upload_partial_dir),
+            max_chunked_upload_bytes: config.max_chunked_upload_bytes,
+            upload_session_ttl_seconds: config.upload_session_ttl_seconds,
+            writers: ChunkWriters::default(),
+        }
+    }
+
+    /// Directory uploaded files are written to.
+    pub fn upload_dir(&self) -> &PathBuf {
+        &self.upload_dir
+    }
+
+    /// Maximum accepted upload size in bytes.
+    pub fn max_upload_bytes(&self) -> usize {
+        self.max_upload_bytes
+    }
+
+    /// Get a media item by ID.
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Media, AppError> {
+        self.media_repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Media not found".to_string()))
+    }
+
+    /// List media, newest first, optionally filtered by content type and usage.
+    pub async fn list(&self, query: MediaQuery) -> Result<(Vec<Media>, Meta), AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+        let offset = (page - 1) * per_page;
+        let content_type = query
+            .content_type
+            .as_deref()
+            .map(|content_type| content_type.trim().to_ascii_lowercase())
+            .filter(|content_type| !content_type.is_empty());
+
+        let media = self
+            .media_repo
+            .find_all(content_type.as_deref(), query.usage, per_page, offset)
+            .await?;
+        let total = self
+            .media_repo
+            .count(content_type.as_deref(), query.usage)
+            .await?;
+        Ok((media, Meta::new(page, per_page, total)))
+    }
+
+    /// Rename a media item or edit its alt text.
+    pub async fn update(&self, id: Uuid, request: UpdateMediaRequest) -> Result<Media, AppError> {
+        self.get_by_id(id).await?;
+        let filename = request.filename.as_deref().map(str::trim);
+        let alt_text = request.alt_text.as_deref().map(str::trim);
+        Self::validate(filename, alt_text)?;
+
+        self.media_repo.update(id, filename, alt_text).await
+    }
+
+    /// Posts and avatars using a media item.
+    pub async fn usage(&self, id: Uuid) -> Result<MediaUsage, AppError> {
+        let media = self.get_by_id(id).await?;
+        Ok(MediaUsage {
+            media_id: media.id,
+            posts: self.media_repo.find_referencing_posts(&media).await?,
+            avatar_users: self.media_repo.count_avatar_users(media.id).await?,
+        })
+    }
+
+    /// Delete a media item and its file.
+    ///
+    /// Refused while a published post links to or attaches the file; drafts
+    /// and avatars using it lose the reference.
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        let media = self.get_by_id(id).await?;
+        let published: Vec<String> = self
+            .media_repo
+            .find_referencing_posts(&media)
+            .await?
+            .into_iter()
+            .filter(|post| post.status == PostStatus::Published)
+            .map(|post| post.slug)
+            .collect();
+        if !published.is_empty() {
+            return Err(AppError::Conflict(format!(
+                "Media is used by published posts: {}",
+                published.join(", ")
+            )));
+        }
+
+        self.media_repo.delete(media.id).await?;
+        self.remove_file(&media.storage_key).await
+    }
+
+    /// Count a download of a media item and return it with the path of its file.
+    pub async fn record_download(&self, id: Uuid) -> Result<(Media, PathBuf), AppError> {
+        let media = self
+            .media_repo
+            .record_download(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Media not found".to_string()))?;
+        let path = self.upload_dir.join(&media.storage_key);
+        Ok((media, path))
+    }
+
+    /// Get a media item with the path of its file, without counting a download.
+    pub async fn file(&self, id: Uuid) -> Result<(Media, PathBuf), AppError> {
+        let media = self.get_by_id(id).await?;
+        let path = self.upload_dir.join(&media.storage_key);
+        Ok((media, path))
+    }
+
+    /// Store an uploaded image and record it.
+    pub async fn store_image(
+        &self,
+        uploader_id: Uuid,
+        filename: &str,
+        content_type: &str,
+        bytes: &[u8],
+    ) -> Result<Media, AppError> {
+        let extension = Self::image_extension(content_type).ok_or_else(|| {
+            AppError::ValidationError(format!("Unsupported image type: {}", content_type))
+        })?;
+        self.store(uploader_id, filename, content_type, extension, bytes)
+            .await
+    }
+
+    /// Store an uploaded image or another library file type and record it.
+    pub async fn store_file(
+        &self,
+        uploader_id: Uuid,
+        filename: &str,
+        content_type: &str,
+        bytes: &[u8],
+    ) -> Result<Media, AppError> {
+        let extension = Self::file_extension(content_type).ok_or_else(|| {
+            AppError::ValidationError(format!("Unsupported file type: {}", content_type))
+        })?;
+        self.store(uploader_id, filename, content_type, extension, bytes)
+            .await
+    }
+
+    /// Start a chunked upload of an image or another library file type.
+    pub async fn start_upload(
+        &self,
+        uploader_id: Uuid,
+        request: CreateUploadRequest,
+    ) -> Result<UploadSession, AppError> {
+        if Self::file_extension(&request.content_type).is_none() {
+            return Err(AppError::ValidationError(format!(
+                "Unsupported file type: {}",
+                request.content_type
+            )));
+        }
+        if request.size_bytes == 0 || request.size_bytes > self.max_chunked_upload_bytes {
+            return Err(AppError::ValidationError(format!(
+                "Upload size must be between 1 and {} bytes",
+                self.max_chunked_upload_bytes
+            )));
+        }
+
+        let session = UploadSession {
+            id: Uuid::new_v4().simple().to_string(),
+            uploader_id,
+            filename: Self::sanitize_filename(&request.filename).to_string(),
+            content_type: request.content_type.trim().to_ascii_lowercase(),
+            size_bytes: request.size_bytes,
+            offset: 0,
+            expires_at: Utc::now() + Duration::seconds(self.upload_session_ttl_seconds as i64),
+        };
+        tokio::fs::create_dir_all(&self.partial_dir)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to create upload dir: {}", e)))?;
+        tokio::fs::File::create(self.partial_dir.join(&session.id))
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to start upload: {}", e)))?;
+        self.save_session(&session).await?;
+        Ok(session)
+    }
+
+    /// Get the uploader's chunked upload, e.g. to find where to resume.
+    pub async fn get_upload(&self, id: &str, uploader_id: Uuid) -> Result<UploadSession, AppError> {
+        self.load_session(id)
+            .await?
+            .filter(|session| session.uploader_id == uploader_id)
+            .ok_or_else(|| AppError::NotFound("Upload not found or expired".to_string()))
+    }
+
+    /// Append a chunk starting at `offset`; the last chunk stores the file as media.
+    ///
+    /// A chunk not starting where the previous one ended is rejected with a
+    /// conflict, so the client can ask for the offset and resume from there.
+    pub async fn append_chunk(
+        &self,
+        id: &str,
+        uploader_id: Uuid,
+        offset: u64,
+        bytes: &[u8],
+    ) -> Result<UploadProgress, AppError> {
+        // Chunks sent to this instance are turned away without a round trip,
+        // and the Redis lock covers instances sharing the partial directory
+        let _writer = self.writers.claim(id).ok_or_else(chunk_in_progress)?;
+        let mut redis = self.redis.clone();
+        let lock = keys::upload_lock(id);
+        let locked: Option<String> = redis::cmd("SET")
+            .arg(&lock)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(UPLOAD_LOCK_TTL_SECONDS)
+            .query_async(&mut redis)
+            .await?;
+        if locked.is_none() {
+            return Err(chunk_in_progress());
+        }
+
+        let result = self.write_chunk(id, uploader_id, offset, bytes).await;
+        let _: Result<(), _> = redis.del(&lock).await;
+        result
+    }
+
+    /// Abandon a chunked upload and remove what was received.
+    pub async fn cancel_upload(&self, id: &str, uploader_id: Uuid) -> Result<(), AppError> {
+        let session = self.get_upload(id, uploader_id).await?;
+        let mut redis = self.redis.clone();
+        let _: () = redis.del(keys::upload_session(&session.id)).await?;
+        self.remove_partial(&session.id).await
+    }
+
+    /// Remove partial files of uploads that expired without completing.
+    pub async fn purge_stale_uploads(&self) -> Result<usize, AppError> {
+        let mut entries = match tokio::fs::read_dir(&self.partial_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(io_error(e)),
+        };
+        let cutoff = SystemTime::now() - StdDuration::from_secs(self.upload_session_ttl_seconds);
+        let mut purged = 0;
+        while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+            let modified = entry.metadata().await.and_then(|m| m.modified());
+            if modified.is_ok_and(|modified| modified < cutoff) {
+                tokio::fs::remove_file(entry.path())
+                    .await
+                    .map_err(io_error)?;
+                purged += 1;
+            }
+        }
+        Ok(purged)
+    }
+
+    /// Remove a stored file from disk; a missing file is not an error.
+    pub async fn remove_file(&self, storage_key: &str) -> Result<(), AppError> {
+        match tokio::fs::remove_file(self.upload_dir.join(storage_key)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(AppError::InternalError(
+                format!("Failed to remove upload: {}", e),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // Private helper methods
+
+    /// Write `bytes` under a new storage key with `extension` and record the file.
+    async fn store(
+        &self,
+        uploader_id: Uuid,
+        filename: &str,
+        content_type: &str,
+        extension: &str,
+        bytes: &[u8],
+    ) -> Result<Media, AppError> {
+        if bytes.is_empty() {
+            return Err(AppError::ValidationError(
+                "Uploaded file is empty".to_string(),
+            ));
+        }
+        if bytes.len() > self.max_upload_bytes {
+            return Err(AppError::ValidationError(format!(
+                "Uploaded file exceeds {} bytes",
+                self.max_upload_bytes
+            )));
+        }
+
+        let storage_key = format!("{}.{}", Uuid::new_v4().simple(), extension);
+        tokio::fs::create_dir_all(&self.upload_dir)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to create upload dir: {}", e)))?;
+        tokio::fs::write(self.upload_dir.join(&storage_key), bytes)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to store upload: {}", e)))?;
+
+        self.media_repo
+            .create(
+                uploader_id,
+                Self::sanitize_filename(filename),
+                &storage_key,
+                content_type,
+                bytes.len() as i64,
+            )
+            .await
+    }
+
+    async fn write_chunk(
+        &self,
+        id: &str,
+        uploader_id: Uuid,
+        offset: u64,
+        bytes: &[u8],
+    ) -> Result<UploadProgress, AppError> {
+        let mut session = self.get_upload(id, uploader_id).await?;
+        Self::check_chunk(&session, offset, bytes.len())?;
+        Self::write_partial(&self.partial_dir.join(&session.id), offset, bytes).await?;
+
+        session.offset += bytes.len() as u64;
+        if !session.is_complete() {
+            self.save_session(&session).await?;
+            return Ok(UploadProgress {
+                upload: session,
+                media: None,
+            });
+        }
+
+        let media = self.finish_upload(&session).await?;
+        Ok(UploadProgress {
+            upload: session,
+            media: Some(MediaResponse::from(media)),
+        })
+    }
+
+    /// Move a complete upload into the media library.
+    async fn finish_upload(&self, session: &UploadSession) -> Result<Media, AppError> {
+        let extension = Self::file_extension(&session.content_type).unwrap_or("bin");
+        let storage_key = format!("{}.{}", Uuid::new_v4().simple(), extension);
+        tokio::fs::create_dir_all(&self.upload_dir)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to create upload dir: {}", e)))?;
+        Self::move_partial(
+            &self.partial_dir.join(&session.id),
+            &self.upload_dir.join(&storage_key),
+        )
+        .await?;
+
+        let mut redis = self.redis.clone();
+        let _: () = redis.del(keys::upload_session(&session.id)).await?;
+        self.media_repo
+            .create(
+                session.uploader_id,
+                &session.filename,
+                &storage_key,
+                &session.content_type,
+                session.size_bytes as i64,
+            )
+            .await
+    }
+
+    async fn load_session(&self, id: &str) -> Result<Option<UploadSession>, AppError> {
+        let mut redis = self.redis.clone();
+        let json: Option<String> = redis.get(keys::upload_session(id)).await?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// Store the session until it expires.
+    async fn save_session(&self, session: &UploadSession) -> Result<(), AppError> {
+        let ttl = (session.expires_at - Utc::now()).num_seconds();
+        if ttl <= 0 {
+            return Err(AppError::NotFound(
+                "Upload not found or expired".to_string(),
+            ));
+        }
+        let json = serde_json::to_string(session)
+            .map_err(|e| AppError::InternalError(format!("Failed to encode upload: {}", e)))?;
+        let mut redis = self.redis.clone();
+        let _: () = redis
+            .set_ex(keys::upload_session(&session.id), json, ttl as u64)
+            .await?;
+        Ok(())
+    }
+
+    /// Check that a chunk of `len` bytes continues the upload at `offset`
+    /// without going past its declared size.
+    fn check_chunk(session: &UploadSession, offset: u64, len: usize) -> Result<(), AppError> {
+        if offset != session.offset {
+            return Err(AppError::Conflict(format!(
+                "Upload is at offset {}",
+                session.offset
+            )));
+        }
+        if len == 0 || offset + len as u64 > session.size_bytes {
+            return Err(AppError::ValidationError(format!(
+                "Chunk must hold 1 to {} bytes",
+                session.size_bytes - offset
+            )));
+        }
+        Ok(())
+    }
+
+    /// Write a chunk into the partial file at `offset`.
+    async fn write_partial(path: &Path, offset: u64, bytes: &[u8]) -> Result<(), AppError> {
+        // Cut off anything written by an attempt that failed before it was recorded
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .await
+            .map_err(io_error)?;
+        file.set_len(offset).await.map_err(io_error)?;
+        file.seek(SeekFrom::Start(offset)).await.map_err(io_error)?;
+        file.write_all(bytes).await.map_err(io_error)?;
+        file.sync_all().await.map_err(io_error)
+    }
+
+    /// Move a complete partial file to where the media library stores it.
+    async fn move_partial(partial: &Path, target: &Path) -> Result<(), AppError> {
+        if tokio::fs::rename(partial, target).await.is_ok() {
+            return Ok(());
+        }
+        // The directories may be on different file systems
+        tokio::fs::copy(partial, target)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to store upload: {}", e)))?;
+        tokio::fs::remove_file(partial).await.map_err(io_error)
+    }
+
+    async fn remove_partial(&self, id: &str) -> Result<(), AppError> {
+        match tokio::fs::remove_file(self.partial_dir.join(id)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn file_extension(content_type: &str) -> Option<&'static str> {
+        Self::image_extension(content_type).or_else(|| {
+            FILE_TYPES
+                .iter()
+                .find(|(mime, _)| mime.eq_ignore_ascii_case(content_type.trim()))
+                .map(|(_, extension)| *extension)
+        })
+    }
+
+    /// Check the sent fields.
+    fn validate(filename: Option<&str>, alt_text: Option<&str>) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if let Some(filename) = filename {
+            if filename.is_empty()
+                || filename.chars().count() > MAX_FILENAME_LEN
+                || filename.contains(['/', '\\'])
+            {
+                errors.push(FieldError::new(
+                    "filename",
+                    format!(
+                        "must be 1 to {} characters without path separators",
+                        MAX_FILENAME_LEN
+                    ),
+                ));
+            }
+        }
+        if alt_text.is_some_and(|alt_text| alt_text.chars().count() > MAX_ALT_TEXT_LEN) {
+            errors.push(FieldError::new(
+                "alt_text",
+                format!("must be at most {} characters", MAX_ALT_TEXT_LEN),
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(errors))
+        }
+    }
+
+    fn image_extension(content_type: &str) -> Option<&'static str> {
+        IMAGE_TYPES
+            .iter()
+            .find(|(mime, _)| mime.eq_ignore_ascii_case(content_type.trim()))
+            .map(|(_, extension)| *extension)
+    }
+
+    fn sanitize_filename(filename: &str) -> &str {
+        let name = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+        if name.is_empty() {
+            "upload"
+        } else {
+            name
+        }
+    }
+}
+
+/// Uploads a chunk is being written to by this instance.
+#[derive(Clone, Default)]
+struct ChunkWriters(Arc<Mutex<HashSet<String>>>);
+
+impl ChunkWriters {
+    /// Claim an upload for writing a chunk, or `None` if another one is.
+    fn claim(&self, id: &str) -> Option<ChunkWriter> {
+        let mut ids = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        ids.insert(id.to_string()).then(|| ChunkWriter {
+            writers: self.clone(),
+            id: id.to_string(),
+        })
+    }
+}
+
+/// Claim on an upload, released when dropped (also when a request is cancelled).
+struct ChunkWriter {
+    writers: ChunkWriters,
+    id: String,
+}
+
+impl Drop for ChunkWriter {
+    fn drop(&mut self) {
+        let mut ids = self.writers.0.lock().unwrap_or_else(|e| e.into_inner());
+        ids.remove(&self.id);
+    }
+}
+
+fn chunk_in_progress() -> AppError {
+    AppError::Conflict("Another chunk of this upload is being written".to_string())
+}
+
+fn io_error(err: std::io::Error) -> AppError {
+    AppError::InternalError(format!("Upload storage error: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_extension() {
+        assert_eq!(MediaService::image_extension("image/png"), Some("png"));
+        assert_eq!(MediaService::image_extension("IMAGE/JPEG"), Some("jpg"));
+        assert_eq!(MediaService::image_extension("application/pdf"), None);
+        assert_eq!(MediaService::file_extension("application/pdf"), Some("pdf"));
+        assert_eq!(MediaService::file_extension("image/webp"), Some("webp"));
+        assert_eq!(MediaService::file_extension("text/html"), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(MediaService::validate(Some("cover.png"), Some("")).is_ok());
+        assert!(MediaService::validate(None, None).is_ok());
+        let result = MediaService::validate(Some("a/b.png"), Some(&"x".repeat(501)));
+        match result {
+            Err(AppError::InvalidFields(errors)) => {
+                let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+                assert_eq!(fields, vec!["filename", "alt_text"]);
+            }
+            other => panic!("expected field errors, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(
+            MediaService::sanitize_filename("../../etc/passwd"),
+            "passwd"
+        );
+        assert_eq!(
+            MediaService::sanitize_filename("C:\\pics\\me.png"),
+            "me.png"
+        );
+        assert_eq!(MediaService::sanitize_filename(""), "upload");
+    }
+
+    fn session(size_bytes: u64, offset: u64) -> UploadSession {
+        UploadSession {
+            id: Uuid::new_v4().simple().to_string(),
+            uploader_id: Uuid::new_v4(),
+            filename: "talk.pdf".to_string(),
+            content_type: "application/pdf".to_string(),
+            size_bytes,
+            offset,
+            expires_at: Utc::now() + Duration::minutes(10),
+        }
+    }
+
+    #[test]
+    fn test_check_chunk() {
+        let upload = session(10, 4);
+        assert!(MediaService::check_chunk(&upload, 4, 6).is_ok());
+
+        // A chunk resent from an older offset, or one skipping ahead
+        for offset in [0, 6] {
+            assert!(matches!(
+                MediaService::check_chunk(&upload, offset, 2),
+                Err(AppError::Conflict(message)) if message == "Upload is at offset 4"
+            ));
+        }
+        // Past the declared size, or empty
+        for len in [7, 0] {
+            assert!(matches!(
+                MediaService::check_chunk(&upload, 4, len),
+                Err(AppError::ValidationError(message)) if message == "Chunk must hold 1 to 6 bytes"
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_assemble_chunks() {
+        let dir = std::env::temp_dir().join(format!("pw-uploads-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("media")).unwrap();
+        let partial = dir.join("partial");
+        let target = dir.join("media/talk.pdf");
+        std::fs::File::create(&partial).unwrap();
+
+        MediaService::write_partial(&partial, 0, b"%PDF-")
+            .await
+            .unwrap();
+        // A retried chunk replaces what a failed attempt left past its offset
+        MediaService::write_partial(&partial, 5, b"garbage!")
+            .await
+            .unwrap();
+        MediaService::write_partial(&partial, 5, b"1.7")
+            .await
+            .unwrap();
+        MediaService::move_partial(&partial, &target).await.unwrap();
+
+        assert_eq!(std::fs::read(&target).unwrap(), b"%PDF-1.7");
+        assert!(!partial.exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_chunk_writers() {
+        let writers = ChunkWriters::default();
+        let first = writers.claim("a").unwrap();
+        assert!(writers.claim("a").is_none());
+        // Other uploads are not held up
+        assert!(writers.claim("b").is_some());
+
+        drop(first);
+        assert!(writers.claim("a").is_some());
+    }
+}
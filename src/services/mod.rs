@@ -1,11 +1,85 @@
 //! Service modules containing business logic.
 
+pub mod access_token_service;
+pub mod account_service;
+pub mod activity_service;
+pub mod archive_service;
 pub mod auth_service;
+pub mod backup_service;
+pub mod blocklist_service;
+pub mod bootstrap_service;
+pub mod cache_service;
+pub mod cache_warmer;
 pub mod category_service;
+pub mod changelog_service;
+pub mod digest_service;
+pub mod email_service;
+pub mod event_bus;
+pub mod event_relay;
+pub mod git_sync;
+pub mod hook_service;
+pub mod job_service;
+pub mod media_service;
+pub mod notification_service;
+pub mod poll_service;
+pub mod post_lock_service;
+pub mod post_note_service;
 pub mod post_service;
+pub mod post_template_service;
+pub mod preview_service;
+pub mod profile_service;
+pub mod quota_service;
+pub mod revalidator;
+pub mod revision_service;
+pub mod search_indexer;
+pub mod search_service;
+pub mod site_service;
+pub mod status_monitor;
+pub mod sync_service;
 pub mod tag_service;
+pub mod taxonomy_service;
+pub mod title_test_service;
+pub mod trending_service;
+pub mod webhook_log;
 
+pub use access_token_service::AccessTokenService;
+pub use account_service::AccountService;
+pub use activity_service::ActivityService;
+pub use archive_service::ArchiveService;
 pub use auth_service::{AuthService, Claims};
+pub use backup_service::BackupService;
+pub use blocklist_service::BlocklistService;
+pub use bootstrap_service::BootstrapService;
+pub use cache_service::CacheService;
+pub use cache_warmer::CacheWarmer;
 pub use category_service::CategoryService;
+pub use changelog_service::ChangelogService;
+pub use digest_service::DigestService;
+pub use email_service::EmailService;
+pub use event_bus::{DomainEvent, EventBus, EventOrigin, EventSubscriber};
+pub use event_relay::EventRelay;
+pub use git_sync::{GitSync, GitSyncWorker};
+pub use hook_service::HookService;
+pub use job_service::JobService;
+pub use media_service::MediaService;
+pub use notification_service::NotificationService;
+pub use poll_service::PollService;
+pub use post_lock_service::PostLockService;
+pub use post_note_service::PostNoteService;
 pub use post_service::PostService;
+pub use post_template_service::PostTemplateService;
+pub use preview_service::PreviewService;
+pub use profile_service::ProfileService;
+pub use quota_service::QuotaService;
+pub use revalidator::Revalidator;
+pub use revision_service::RevisionService;
+pub use search_indexer::SearchIndexer;
+pub use search_service::SearchService;
+pub use site_service::SiteService;
+pub use status_monitor::StatusMonitor;
+pub use sync_service::SyncService;
 pub use tag_service::TagService;
+pub use taxonomy_service::TaxonomyService;
+pub use title_test_service::TitleTestService;
+pub use trending_service::TrendingService;
+pub use webhook_log::WebhookLog;
@@ -0,0 +1,64 @@
+//! Notification service sending emails users have not opted out of.
+//!
+//! Every email about something that happened to a user's account or content
+//! goes through [`NotificationService::notify`], which checks the user's
+//! preferences first. Emails a flow cannot work without, like verification
+//! links and invitations, are sent directly instead.
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    NotificationKind, NotificationPreferences, UpdateNotificationPreferencesRequest,
+};
+use crate::pkg::EmailTemplate;
+use crate::repositories::NotificationRepository;
+use crate::services::JobService;
+
+/// Service for notification preferences and dispatch.
+#[derive(Clone)]
+pub struct NotificationService {
+    repo: NotificationRepository,
+    job_service: JobService,
+}
+
+impl NotificationService {
+    /// Create a new notification service.
+    pub fn new(repo: NotificationRepository, job_service: JobService) -> Self {
+        Self { repo, job_service }
+    }
+
+    /// A user's preferences, the defaults until they change any.
+    pub async fn preferences(&self, user_id: Uuid) -> Result<NotificationPreferences, AppError> {
+        Ok(self.repo.find(user_id).await?.unwrap_or_default())
+    }
+
+    /// Change some of a user's preferences.
+    pub async fn update_preferences(
+        &self,
+        user_id: Uuid,
+        request: UpdateNotificationPreferencesRequest,
+    ) -> Result<NotificationPreferences, AppError> {
+        let preferences = self.preferences(user_id).await?.apply(&request);
+        self.repo.upsert(user_id, &preferences).await?;
+        Ok(preferences)
+    }
+
+    /// Email `template` to a user in the background if they want
+    /// notifications of `kind`. Returns whether it was sent.
+    pub async fn notify(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        kind: NotificationKind,
+        template: EmailTemplate,
+        variables: Value,
+    ) -> Result<bool, AppError> {
+        if !self.preferences(user_id).await?.allows(kind) {
+            return Ok(false);
+        }
+        self.job_service.send_email(email, template, variables);
+        Ok(true)
+    }
+}
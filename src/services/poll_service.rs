@@ -0,0 +1,387 @@
+//! Poll service for reader polls and voting.
+
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::error::{AppError, FieldError};
+use crate::models::{
+    embedded_poll_ids, CreatePollRequest, Poll, PollOption, PollQuery, PollResponse, PollVoter,
+    UpdatePollRequest,
+};
+use crate::pkg::redis::keys;
+use crate::repositories::PollRepository;
+use crate::response::Meta;
+
+/// Longest poll question (the `question` column is `VARCHAR(500)`).
+const MAX_QUESTION_LEN: usize = 500;
+/// Longest option label (the `label` column is `VARCHAR(200)`).
+const MAX_OPTION_LEN: usize = 200;
+/// Fewest options a poll may have.
+const MIN_OPTIONS: usize = 2;
+/// Most options a poll may have.
+const MAX_OPTIONS: usize = 20;
+
+/// Service for poll operations.
+///
+/// Vote counts live in Postgres; Redis remembers who voted in each poll so
+/// every user or, for anonymous readers, IP address votes once.
+#[derive(Clone)]
+pub struct PollService {
+    repo: PollRepository,
+    redis: redis::aio::ConnectionManager,
+}
+
+impl PollService {
+    /// Create a new poll service.
+    pub fn new(repo: PollRepository, redis: redis::aio::ConnectionManager) -> Self {
+        Self { repo, redis }
+    }
+
+    /// List a site's polls with their results, newest first.
+    pub async fn list(
+        &self,
+        site_id: Uuid,
+        query: PollQuery,
+    ) -> Result<(Vec<PollResponse>, Meta), AppError> {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+        let offset = (page - 1) * per_page;
+
+        let polls = self.repo.find_all(site_id, per_page, offset).await?;
+        let total = self.repo.count(site_id).await?;
+        Ok((
+            self.with_results(polls).await?,
+            Meta::new(page, per_page, total),
+        ))
+    }
+
+    /// Get a poll with its current results.
+    pub async fn get(&self, site_id: Uuid, id: Uuid) -> Result<PollResponse, AppError> {
+        let poll = self.find(site_id, id).await?;
+        let mut responses = self.with_results(vec![poll]).await?;
+        Ok(responses.remove(0))
+    }
+
+    /// Polls embedded in each of `contents` with the `[poll id="…"]`
+    /// shortcode, in order of appearance; shortcodes naming unknown polls are
+    /// skipped. The polls of every post are loaded together.
+    pub async fn embedded(
+        &self,
+        site_id: Uuid,
+        contents: &[&str],
+    ) -> Result<Vec<Vec<PollResponse>>, AppError> {
+        let embedded: Vec<Vec<Uuid>> = contents
+            .iter()
+            .map(|content| embedded_poll_ids(content))
+            .collect();
+        let mut ids: Vec<Uuid> = embedded.iter().flatten().copied().collect();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Ok(vec![vec![]; contents.len()]);
+        }
+
+        let polls = self.repo.find_by_ids(site_id, &ids).await?;
+        let polls = self.with_results(polls).await?;
+        Ok(Self::group_embedded(&embedded, &polls))
+    }
+
+    /// Create a poll on a site.
+    pub async fn create(
+        &self,
+        site_id: Uuid,
+        created_by: Uuid,
+        request: CreatePollRequest,
+    ) -> Result<PollResponse, AppError> {
+        let options = Self::validate(
+            Some(&request.question),
+            Some(&request.options),
+            request.opens_at,
+            request.closes_at,
+        )?
+        .unwrap_or_default();
+
+        let poll = self
+            .repo
+            .create(
+                site_id,
+                request.question.trim(),
+                request.opens_at,
+                request.closes_at,
+                created_by,
+                &options,
+            )
+            .await?;
+        self.get(site_id, poll.id).await
+    }
+
+    /// Update a poll; options can only be replaced before the first vote.
+    pub async fn update(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        request: UpdatePollRequest,
+    ) -> Result<PollResponse, AppError> {
+        let existing = self.find(site_id, id).await?;
+        let options = Self::validate(
+            request.question.as_deref(),
+            request.options.as_deref(),
+            request.opens_at.or(existing.opens_at),
+            request.closes_at.or(existing.closes_at),
+        )?;
+
+        if let Some(options) = options {
+            if !self.repo.replace_options(id, &options).await? {
+                return Err(AppError::Conflict(
+                    "Options cannot be changed after votes were cast".to_string(),
+                ));
+            }
+        }
+        self.repo
+            .update(
+                id,
+                request.question.as_deref().map(str::trim),
+                request.opens_at,
+                request.closes_at,
+            )
+            .await?;
+        self.get(site_id, id).await
+    }
+
+    /// Delete a poll, its options and its voter record.
+    pub async fn delete(&self, site_id: Uuid, id: Uuid) -> Result<bool, AppError> {
+        self.find(site_id, id).await?;
+
+        let deleted = self.repo.delete(id).await?;
+        if deleted {
+            let mut redis = self.redis.clone();
+            let _: () = redis.del(keys::poll_voters(&id)).await?;
+        }
+        Ok(deleted)
+    }
+
+    /// Cast `voter`'s vote for an option, returning the updated results.
+    pub async fn vote(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        option_id: Uuid,
+        voter: PollVoter,
+    ) -> Result<PollResponse, AppError> {
+        let poll = self.find(site_id, id).await?;
+        if !poll.is_open_at(Utc::now()) {
+            return Err(AppError::Conflict(
+                "Poll is not open for voting".to_string(),
+            ));
+        }
+
+        let mut redis = self.redis.clone();
+        let key = keys::poll_voters(&id);
+        let voter = voter.to_string();
+        let added: i64 = redis.sadd(&key, &voter).await?;
+        if added == 0 {
+            return Err(AppError::Conflict(
+                "You have already voted in this poll".to_string(),
+            ));
+        }
+        // Nobody can vote once the poll closes, so the record is no longer needed
+        if let Some(closes_at) = poll.closes_at {
+            let _: bool = redis.expire_at(&key, closes_at.timestamp()).await?;
+        }
+
+        let recorded = match self.repo.record_vote(id, option_id).await {
+            Ok(recorded) => recorded,
+            Err(err) => {
+                let _: i64 = redis.srem(&key, &voter).await?;
+                return Err(err);
+            }
+        };
+        if !recorded {
+            let _: i64 = redis.srem(&key, &voter).await?;
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "option_id",
+                "is not an option of this poll",
+            )]));
+        }
+
+        self.get(site_id, id).await
+    }
+
+    // Private helper methods
+
+    async fn find(&self, site_id: Uuid, id: Uuid) -> Result<Poll, AppError> {
+        self.repo
+            .find_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Poll not found".to_string()))
+    }
+
+    /// Attach each poll's options and results, keeping the polls' order.
+    async fn with_results(&self, polls: Vec<Poll>) -> Result<Vec<PollResponse>, AppError> {
+        let ids: Vec<Uuid> = polls.iter().map(|poll| poll.id).collect();
+        let mut options = self.repo.find_options(&ids).await?;
+        let now = Utc::now();
+
+        Ok(polls
+            .into_iter()
+            .map(|poll| {
+                let (own, rest): (Vec<PollOption>, Vec<PollOption>) = options
+                    .drain(..)
+                    .partition(|option| option.poll_id == poll.id);
+                options = rest;
+                PollResponse::new(poll, own, now)
+            })
+            .collect())
+    }
+
+    /// Pick each post's polls out of `polls`, in the order its content embeds them.
+    fn group_embedded(embedded: &[Vec<Uuid>], polls: &[PollResponse]) -> Vec<Vec<PollResponse>> {
+        embedded
+            .iter()
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| polls.iter().find(|poll| poll.id == *id).cloned())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Check the sent fields, returning trimmed option labels when options were sent.
+    fn validate(
+        question: Option<&str>,
+        options: Option<&[String]>,
+        opens_at: Option<DateTime<Utc>>,
+        closes_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Vec<String>>, AppError> {
+        let mut errors = Vec::new();
+
+        if let Some(question) = question.map(str::trim) {
+            if question.is_empty() || question.chars().count() > MAX_QUESTION_LEN {
+                errors.push(FieldError::new(
+                    "question",
+                    format!("must be 1 to {} characters", MAX_QUESTION_LEN),
+                ));
+            }
+        }
+
+        let options = options.map(|options| {
+            options
+                .iter()
+                .map(|option| option.trim().to_string())
+                .collect::<Vec<_>>()
+        });
+        if let Some(options) = &options {
+            if !(MIN_OPTIONS..=MAX_OPTIONS).contains(&options.len()) {
+                errors.push(FieldError::new(
+                    "options",
+                    format!("must have {} to {} entries", MIN_OPTIONS, MAX_OPTIONS),
+                ));
+            } else if options
+                .iter()
+                .any(|option| option.is_empty() || option.chars().count() > MAX_OPTION_LEN)
+            {
+                errors.push(FieldError::new(
+                    "options",
+                    format!("must each be 1 to {} characters", MAX_OPTION_LEN),
+                ));
+            } else if options
+                .iter()
+                .enumerate()
+                .any(|(i, option)| options[..i].contains(option))
+            {
+                errors.push(FieldError::new("options", "must not repeat"));
+            }
+        }
+
+        if let (Some(opens_at), Some(closes_at)) = (opens_at, closes_at) {
+            if closes_at <= opens_at {
+                errors.push(FieldError::new("closes_at", "must be after opens_at"));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(options)
+        } else {
+            Err(AppError::InvalidFields(errors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Duration;
+
+    fn fields(result: Result<Option<Vec<String>>, AppError>) -> Vec<String> {
+        match result {
+            Err(AppError::InvalidFields(errors)) => errors.into_iter().map(|e| e.field).collect(),
+            other => panic!("expected field errors, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let options = vec![" Tabs ".to_string(), "Spaces".to_string()];
+        assert_eq!(
+            PollService::validate(Some("Tabs or spaces?"), Some(&options), None, None).unwrap(),
+            Some(vec!["Tabs".to_string(), "Spaces".to_string()])
+        );
+        assert_eq!(PollService::validate(None, None, None, None).unwrap(), None);
+
+        let now = Utc::now();
+        assert_eq!(
+            fields(PollService::validate(
+                Some("  "),
+                Some(&["Only".to_string()]),
+                Some(now),
+                Some(now - Duration::hours(1)),
+            )),
+            vec!["question", "options", "closes_at"]
+        );
+        assert_eq!(
+            fields(PollService::validate(
+                None,
+                Some(&["Tabs".to_string(), " Tabs".to_string()]),
+                None,
+                None,
+            )),
+            vec!["options"]
+        );
+    }
+
+    #[test]
+    fn test_group_embedded() {
+        let poll = |question: &str| PollResponse {
+            id: Uuid::new_v4(),
+            question: question.to_string(),
+            opens_at: None,
+            closes_at: None,
+            is_open: true,
+            total_votes: 0,
+            options: vec![],
+            created_at: Utc::now(),
+        };
+        let (tabs, vim) = (poll("Tabs or spaces?"), poll("Vim or Emacs?"));
+        let unknown = Uuid::new_v4();
+        let polls = vec![tabs.clone(), vim.clone()];
+
+        let grouped = PollService::group_embedded(
+            &[vec![vim.id, tabs.id], vec![], vec![unknown, tabs.id]],
+            &polls,
+        );
+        let questions: Vec<Vec<&str>> = grouped
+            .iter()
+            .map(|polls| polls.iter().map(|poll| poll.question.as_str()).collect())
+            .collect();
+        assert_eq!(
+            questions,
+            vec![
+                vec!["Vim or Emacs?", "Tabs or spaces?"],
+                vec![],
+                vec!["Tabs or spaces?"],
+            ]
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Post lock service keeping two editors from overwriting each other.
+
+use chrono::{Duration, SecondsFormat, Utc};
+use redis::{AsyncCommands, Script};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{PostLock, PostLockGrant};
+use crate::pkg::redis::keys;
+use crate::services::PostService;
+
+/// Replace a lock with `ARGV[2]` for `ARGV[3]` seconds while `ARGV[1]` still holds it.
+const RENEW_SCRIPT: &str = r"
+local current = redis.call('GET', KEYS[1])
+if current and cjson.decode(current)['lock_id'] == ARGV[1] then
+    redis.call('SET', KEYS[1], ARGV[2], 'EX', ARGV[3])
+    return 1
+end
+return 0
+";
+
+/// Delete a lock while `ARGV[1]` still holds it.
+const RELEASE_SCRIPT: &str = r"
+local current = redis.call('GET', KEYS[1])
+if current and cjson.decode(current)['lock_id'] == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+";
+
+/// Service handing out short-lived editing locks on posts.
+///
+/// An editor takes the lock when opening a post and renews it with a
+/// heartbeat; a lock nobody renews expires after `POST_LOCK_TTL_SECONDS`.
+/// Updates sent without the lock while someone else holds it are refused.
+/// Locks live in Redis only, so posts changed through Git sync ignore them.
+#[derive(Clone)]
+pub struct PostLockService {
+    redis: redis::aio::ConnectionManager,
+    post_service: PostService,
+    ttl_seconds: u64,
+}
+
+impl PostLockService {
+    /// Create a new post lock service.
+    pub fn new(
+        config: &Config,
+        redis: redis::aio::ConnectionManager,
+        post_service: PostService,
+    ) -> Self {
+        Self {
+            redis,
+            post_service,
+            ttl_seconds: config.post_lock_ttl_seconds,
+        }
+    }
+
+    /// Who is editing a post the user may edit, if anyone.
+    pub async fn get(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<Option<PostLock>, AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+        Ok(self.current(post.id).await?.map(|grant| grant.lock))
+    }
+
+    /// Take the editing lock of a post, or renew it when `lock_id` still holds it.
+    pub async fn acquire(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+        lock_id: Option<&str>,
+    ) -> Result<PostLockGrant, AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+        let key = keys::post_lock(&post.id);
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(self.ttl_seconds as i64);
+        let mut redis = self.redis.clone();
+
+        // Heartbeat: extend the lock this session already holds
+        if let Some(lock_id) = lock_id {
+            let held = self
+                .current(post.id)
+                .await?
+                .filter(|grant| grant.lock_id == lock_id && grant.lock.user_id == auth_user.id);
+            if let Some(mut grant) = held {
+                grant.lock.expires_at = expires_at;
+                let renewed: i64 = Script::new(RENEW_SCRIPT)
+                    .key(&key)
+                    .arg(lock_id)
+                    .arg(encode(&grant)?)
+                    .arg(self.ttl_seconds)
+                    .invoke_async(&mut redis)
+                    .await?;
+                if renewed == 1 {
+                    return Ok(grant);
+                }
+            }
+        }
+
+        let grant = PostLockGrant {
+            lock_id: Uuid::new_v4().simple().to_string(),
+            lock: PostLock {
+                post_id: post.id,
+                user_id: auth_user.id,
+                user_email: auth_user.email.clone(),
+                acquired_at: now,
+                expires_at,
+            },
+        };
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(encode(&grant)?)
+            .arg("NX")
+            .arg("EX")
+            .arg(self.ttl_seconds)
+            .query_async(&mut redis)
+            .await?;
+        if acquired.is_none() {
+            let holder = self.current(post.id).await?;
+            return Err(held_elsewhere(
+                holder.as_ref().map(|grant| &grant.lock),
+                auth_user,
+            ));
+        }
+
+        tracing::info!(post_id = %post.id, user_id = %auth_user.id, "Post lock acquired");
+        Ok(grant)
+    }
+
+    /// Give up the editing lock `lock_id` holds; releasing a lock not held is a no-op.
+    pub async fn release(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+        lock_id: &str,
+    ) -> Result<(), AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+        let mut redis = self.redis.clone();
+        let _: i64 = Script::new(RELEASE_SCRIPT)
+            .key(keys::post_lock(&post.id))
+            .arg(lock_id)
+            .invoke_async(&mut redis)
+            .await?;
+        Ok(())
+    }
+
+    /// Refuse a change to a post when a session other than `lock_id`'s holds its lock.
+    pub async fn check(
+        &self,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+        lock_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        match self.current(post_id).await? {
+            Some(grant) if Some(grant.lock_id.as_str()) != lock_id => {
+                Err(held_elsewhere(Some(&grant.lock), auth_user))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Private helper methods
+
+    async fn current(&self, post_id: Uuid) -> Result<Option<PostLockGrant>, AppError> {
+        let mut redis = self.redis.clone();
+        let json: Option<String> = redis.get(keys::post_lock(&post_id)).await?;
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+}
+
+fn encode(grant: &PostLockGrant) -> Result<String, AppError> {
+    serde_json::to_string(grant)
+        .map_err(|e| AppError::InternalError(format!("Failed to encode post lock: {}", e)))
+}
+
+/// Conflict telling `auth_user` who holds the lock (`None` when it just changed hands).
+fn held_elsewhere(lock: Option<&PostLock>, auth_user: &AuthUser) -> AppError {
+    let message = match lock {
+        Some(lock) => {
+            let until = lock.expires_at.to_rfc3339_opts(SecondsFormat::Secs, true);
+            if lock.user_id == auth_user.id {
+                format!(
+                    "You are editing this post in another session until {}",
+                    until
+                )
+            } else {
+                format!(
+                    "Post is being edited by {} until {}",
+                    lock.user_email, until
+                )
+            }
+        }
+        None => "Post is being edited in another session".to_string(),
+    };
+    AppError::Conflict(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    fn user(email: &str) -> AuthUser {
+        AuthUser {
+            id: Uuid::new_v4(),
+            email: email.to_string(),
+            role_id: Uuid::new_v4(),
+            role_slug: "editor".to_string(),
+            permissions: vec![],
+            token_id: None,
+            session_id: None,
+            permission_audit: None,
+        }
+    }
+
+    #[test]
+    fn test_grant_keeps_lock_id_at_top_level() {
+        // The Redis scripts read `lock_id` from the stored JSON
+        let grant = PostLockGrant {
+            lock_id: "abc".to_string(),
+            lock: PostLock {
+                post_id: Uuid::new_v4(),
+                user_id: Uuid::new_v4(),
+                user_email: "ada@example.com".to_string(),
+                acquired_at: Utc::now(),
+                expires_at: Utc::now(),
+            },
+        };
+        let json: serde_json::Value = serde_json::from_str(&encode(&grant).unwrap()).unwrap();
+        assert_eq!(json["lock_id"], "abc");
+        assert_eq!(json["user_email"], "ada@example.com");
+        let decoded: PostLockGrant = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.lock.post_id, grant.lock.post_id);
+    }
+
+    #[test]
+    fn test_held_elsewhere() {
+        let ada = user("ada@example.com");
+        let lock = PostLock {
+            post_id: Uuid::new_v4(),
+            user_id: ada.id,
+            user_email: ada.email.clone(),
+            acquired_at: Utc::now(),
+            expires_at: Utc.with_ymd_and_hms(2026, 5, 1, 8, 0, 0).unwrap(),
+        };
+
+        let message = held_elsewhere(Some(&lock), &user("bob@example.com")).to_string();
+        assert!(message.contains("edited by ada@example.com until 2026-05-01T08:00:00Z"));
+        let message = held_elsewhere(Some(&lock), &ada).to_string();
+        assert!(message.contains("another session until"));
+        assert!(matches!(held_elsewhere(None, &ada), AppError::Conflict(_)));
+    }
+}
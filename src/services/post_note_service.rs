@@ -0,0 +1,152 @@
+//! Post note service for internal editorial discussion on posts.
+//!
+//! Notes stay with the post and are only shown to those who may edit it.
+//! Mentioning `@email` of someone else who may edit the post emails them the
+//! note, unless they turned off mention emails.
+
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::error::{AppError, FieldError};
+use crate::middleware::AuthUser;
+use crate::models::{mentioned_emails, CreatePostNoteRequest, NotificationKind, Post, PostNote};
+use crate::pkg::EmailTemplate;
+use crate::repositories::PostNoteRepository;
+use crate::services::{NotificationService, PostService};
+
+/// Longest note, in characters.
+const MAX_NOTE_LEN: usize = 10_000;
+
+/// Service for internal post notes.
+#[derive(Clone)]
+pub struct PostNoteService {
+    repo: PostNoteRepository,
+    post_service: PostService,
+    notifications: NotificationService,
+}
+
+impl PostNoteService {
+    /// Create a new post note service.
+    pub fn new(
+        repo: PostNoteRepository,
+        post_service: PostService,
+        notifications: NotificationService,
+    ) -> Self {
+        Self {
+            repo,
+            post_service,
+            notifications,
+        }
+    }
+
+    /// Notes on a post the user may edit, oldest first.
+    pub async fn list(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<Vec<PostNote>, AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+        self.repo.find_by_post(post.id).await
+    }
+
+    /// Add a note to a post the user may edit, notifying those it mentions.
+    pub async fn create(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+        request: CreatePostNoteRequest,
+    ) -> Result<PostNote, AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+        let body = request.body.trim();
+        if body.is_empty() {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "body",
+                "Note cannot be empty",
+            )]));
+        }
+        if body.chars().count() > MAX_NOTE_LEN {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "body",
+                format!("Note is longer than {} characters", MAX_NOTE_LEN),
+            )]));
+        }
+
+        let note = self.repo.create(post.id, auth_user.id, body).await?;
+        if let Err(err) = self.notify_mentions(&post, &note, auth_user).await {
+            tracing::warn!(error = %err, note_id = %note.id, "Failed to notify note mentions");
+        }
+        Ok(note)
+    }
+
+    /// Delete a note; only its author and admins may.
+    pub async fn delete(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        note_id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<(), AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+        let note = self
+            .repo
+            .find_by_id(post.id, note_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Note not found".to_string()))?;
+        if note.author_id != Some(auth_user.id) && !auth_user.is_admin() {
+            return Err(AppError::Forbidden(
+                "Cannot delete notes by other users".to_string(),
+            ));
+        }
+        self.repo.delete(note.id).await?;
+        Ok(())
+    }
+
+    // Private helper methods
+
+    async fn notify_mentions(
+        &self,
+        post: &Post,
+        note: &PostNote,
+        auth_user: &AuthUser,
+    ) -> Result<(), AppError> {
+        let emails = mentioned_emails(&note.body);
+        if emails.is_empty() {
+            return Ok(());
+        }
+        let author = note
+            .author_name
+            .clone()
+            .unwrap_or_else(|| auth_user.email.clone());
+        for user in self.repo.find_mentionable(&emails, post.author_id).await? {
+            if user.id == auth_user.id {
+                continue;
+            }
+            self.notifications
+                .notify(
+                    user.id,
+                    &user.email,
+                    NotificationKind::Mention,
+                    EmailTemplate::NoteMention,
+                    json!({
+                        "name": user.name,
+                        "author": author,
+                        "title": post.title,
+                        "note": note.body,
+                    }),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -1,14 +1,35 @@
 //! Post service for blog post business logic.
 
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
 use uuid::Uuid;
 
-use crate::error::AppError;
+use crate::config::Config;
+use crate::error::{AppError, FieldError};
+use crate::middleware::AuthUser;
 use crate::models::{
-    AuthorResponse, Category, CreatePostRequest, Post, PostListItem, PostQuery, PostResponse,
-    PostStatus, Tag, UpdatePostRequest,
+    blog_posting_json_ld, site_base_url, AttachmentResponse, AuthorResponse, BatchPostsRequest,
+    Category, CreatePostRequest, NotificationKind, Post, PostFrontMatter, PostListItem, PostQuery,
+    PostResponse, PostReview, PostStatus, PostTags, PostViewer, PostVisibility, Site, Tag,
+    UpdatePostRequest,
+};
+use crate::pkg::search::{SearchEngine, SearchQuery};
+use crate::pkg::slug::{self, slugify};
+use crate::pkg::{EmailTemplate, SlugGenerator};
+use crate::repositories::{
+    CategoryRepository, MediaRepository, PostRepository, TagRepository, UserRepository,
 };
-use crate::repositories::{CategoryRepository, PostRepository, TagRepository, UserRepository};
 use crate::response::Meta;
+use crate::services::{DomainEvent, EventBus, EventOrigin, NotificationService, PollService};
+
+/// Longest tag name or slug (the `tags` columns are `VARCHAR(50)`).
+const MAX_TAG_LEN: usize = 50;
+/// Longest post slug (the `posts.slug` column is `VARCHAR(255)`).
+const MAX_SLUG_LEN: usize = 255;
+/// Most posts fetched by one batch request.
+const MAX_BATCH_POSTS: usize = 50;
 
 /// Service for blog post operations.
 #[derive(Clone)]
@@ -17,116 +38,273 @@ pub struct PostService {
     user_repo: UserRepository,
     category_repo: CategoryRepository,
     tag_repo: TagRepository,
+    media_repo: MediaRepository,
+    search: Arc<dyn SearchEngine>,
+    events: EventBus,
+    polls: PollService,
+    notifications: NotificationService,
+    slugs: SlugGenerator,
+    max_tags_per_post: usize,
+    post_path: String,
 }
 
 impl PostService {
     /// Create a new post service.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
+        config: &Config,
         post_repo: PostRepository,
         user_repo: UserRepository,
         category_repo: CategoryRepository,
         tag_repo: TagRepository,
+        media_repo: MediaRepository,
+        search: Arc<dyn SearchEngine>,
+        events: EventBus,
+        polls: PollService,
+        notifications: NotificationService,
     ) -> Self {
         Self {
             post_repo,
             user_repo,
             category_repo,
             tag_repo,
+            media_repo,
+            search,
+            events,
+            polls,
+            notifications,
+            slugs: SlugGenerator::from_config(config),
+            max_tags_per_post: config.max_tags_per_post,
+            post_path: config.revalidate_post_path.clone(),
         }
     }
 
-    /// List posts with pagination and filters.
+    /// List a site's posts visible to `viewer`, with pagination and filters.
     pub async fn list(
         &self,
+        site_id: Uuid,
         query: PostQuery,
-        is_admin: bool,
+        viewer: PostViewer,
     ) -> Result<(Vec<PostListItem>, Meta), AppError> {
         let page = query.page.unwrap_or(1).max(1);
         let per_page = query.per_page.unwrap_or(10).clamp(1, 100);
         let offset = (page - 1) * per_page;
 
         // Non-admin users can only see published posts
-        let status = if is_admin {
+        let status = if viewer.is_admin() {
             query.status
         } else {
             Some(PostStatus::Published)
         };
 
+        if let Some(text) = query
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+        {
+            let hits = self
+                .search
+                .search(&SearchQuery {
+                    site_id,
+                    viewer,
+                    text,
+                    status,
+                    category_id: query.category_id,
+                    limit: per_page,
+                    offset,
+                })
+                .await?;
+            let ids: Vec<Uuid> = hits.hits.iter().map(|hit| hit.id).collect();
+            let mut posts = self.post_repo.find_list_items_by_ids(site_id, &ids).await?;
+            posts.sort_by_key(|post| ids.iter().position(|id| *id == post.id));
+            for post in &mut posts {
+                post.snippet = hits
+                    .hits
+                    .iter()
+                    .find(|hit| hit.id == post.id)
+                    .and_then(|hit| hit.snippet.clone());
+            }
+            return Ok((posts, Meta::new(page, per_page, hits.total)));
+        }
+
         let posts = self
             .post_repo
-            .find_all(status, query.category_id, per_page, offset)
+            .find_all(site_id, viewer, status, query.category_id, per_page, offset)
             .await?;
 
-        let total = self.post_repo.count(status, query.category_id).await?;
+        let total = self
+            .post_repo
+            .count(site_id, viewer, status, query.category_id)
+            .await?;
 
         Ok((posts, Meta::new(page, per_page, total)))
     }
 
     /// Get a single post by slug.
-    pub async fn get_by_slug(&self, slug: &str, is_admin: bool) -> Result<PostResponse, AppError> {
+    pub async fn get_by_slug(
+        &self,
+        site_id: Uuid,
+        slug: &str,
+        viewer: PostViewer,
+    ) -> Result<PostResponse, AppError> {
         let post = self
             .post_repo
-            .find_by_slug(slug)
+            .find_by_slug(site_id, slug)
             .await?
             .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
 
-        // Non-admin users can only see published posts
-        if !is_admin && post.status != PostStatus::Published {
-            return Err(AppError::NotFound("Post not found".to_string()));
-        }
+        Self::ensure_visible(&post, viewer)?;
 
         self.build_post_response(post).await
     }
 
     /// Get a single post by ID.
-    pub async fn get_by_id(&self, id: Uuid, is_admin: bool) -> Result<PostResponse, AppError> {
+    pub async fn get_by_id(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        viewer: PostViewer,
+    ) -> Result<PostResponse, AppError> {
         let post = self
             .post_repo
-            .find_by_id(id)
+            .find_by_id(site_id, id)
             .await?
             .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
 
-        // Non-admin users can only see published posts
-        if !is_admin && post.status != PostStatus::Published {
-            return Err(AppError::NotFound("Post not found".to_string()));
-        }
+        Self::ensure_visible(&post, viewer)?;
 
         self.build_post_response(post).await
     }
 
-    /// Create a new post.
+    /// Get several posts by ID and/or slug, in the order asked for (IDs
+    /// first). Posts that do not exist or `viewer` may not see are left out.
+    pub async fn get_batch(
+        &self,
+        site_id: Uuid,
+        request: BatchPostsRequest,
+        viewer: PostViewer,
+    ) -> Result<Vec<PostResponse>, AppError> {
+        let requested = request.ids.len() + request.slugs.len();
+        if requested == 0 {
+            return Ok(vec![]);
+        }
+        if requested > MAX_BATCH_POSTS {
+            return Err(AppError::ValidationError(format!(
+                "At most {} posts can be fetched at once",
+                MAX_BATCH_POSTS
+            )));
+        }
+
+        let found = self
+            .post_repo
+            .find_by_ids_or_slugs(site_id, &request.ids, &request.slugs)
+            .await?;
+        let mut posts: Vec<Post> = Vec::with_capacity(found.len());
+        for post in found {
+            if Self::ensure_visible(&post, viewer).is_ok() {
+                posts.push(post);
+            }
+        }
+        posts.sort_by_key(|post| Self::batch_position(&request, post));
+
+        self.build_post_responses(posts).await
+    }
+
+    /// Get a site's posts among `ids` with their details, for admin use.
+    /// Posts that do not exist are left out.
+    pub async fn get_many_by_ids(
+        &self,
+        site_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<PostResponse>, AppError> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let posts = self.post_repo.find_by_ids(site_id, ids).await?;
+        self.build_post_responses(posts).await
+    }
+
+    /// Get a post the user may edit, e.g. to preview or lock it.
+    pub async fn get_editable(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<Post, AppError> {
+        let post = self
+            .post_repo
+            .find_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+        Self::authorize_owner(auth_user, post.author_id, "update")?;
+        Ok(post)
+    }
+
+    /// Create a new post on a site, authored by `auth_user`.
     pub async fn create(
         &self,
-        author_id: Uuid,
+        site_id: Uuid,
+        auth_user: &AuthUser,
         request: CreatePostRequest,
     ) -> Result<PostResponse, AppError> {
-        // Generate slug if not provided
-        let slug = request
-            .slug
-            .unwrap_or_else(|| Self::slugify(&request.title));
+        let status = request.status.unwrap_or_default();
+        Self::authorize_transition(auth_user, None, status)?;
 
-        // Check if slug already exists
-        if self.post_repo.find_by_slug(&slug).await?.is_some() {
-            return Err(AppError::Conflict("Slug already exists".to_string()));
-        }
+        // Generate a free slug if not provided
+        let slug = match request.slug {
+            Some(slug) => {
+                self.slugs.check_reserved("slug", &slug)?;
+                if self.post_repo.find_by_slug(site_id, &slug).await?.is_some() {
+                    return Err(AppError::Conflict("Slug already exists".to_string()));
+                }
+                slug
+            }
+            None => self.generate_slug(site_id, &request.title).await?,
+        };
+        self.ensure_site_references(site_id, request.category_id, request.tag_ids.as_deref())
+            .await?;
+        let attachment_ids = self
+            .check_attachments(request.attachment_ids.as_deref())
+            .await?;
+        let tag_names = request
+            .tag_names
+            .as_deref()
+            .map(Self::normalize_tag_names)
+            .transpose()?;
+        Self::check_tag_count(
+            self.max_tags_per_post,
+            request.tag_ids.as_deref(),
+            tag_names.as_deref(),
+        )?;
 
         let post = self
             .post_repo
             .create(
+                site_id,
                 &request.title,
                 &slug,
                 &request.content,
                 request.excerpt.as_deref(),
-                request.status.unwrap_or_default(),
-                author_id,
+                status,
+                request.visibility.unwrap_or_default(),
+                auth_user.id,
                 request.category_id,
+                PostTags::from_request(request.tag_ids, tag_names).as_ref(),
             )
             .await?;
 
-        // Set tags if provided
-        if let Some(tag_ids) = request.tag_ids {
-            self.post_repo.set_tags(post.id, &tag_ids).await?;
+        if let Some(attachment_ids) = attachment_ids {
+            self.media_repo
+                .set_post_attachments(post.id, &attachment_ids)
+                .await?;
         }
+        self.events.publish(DomainEvent::PostSaved {
+            before: None,
+            after: post.clone(),
+            origin: EventOrigin::Api,
+            actor_id: Some(auth_user.id),
+        });
 
         self.build_post_response(post).await
     }
@@ -134,23 +312,54 @@ impl PostService {
     /// Update an existing post.
     pub async fn update(
         &self,
+        site_id: Uuid,
         id: Uuid,
+        auth_user: &AuthUser,
         request: UpdatePostRequest,
     ) -> Result<PostResponse, AppError> {
-        // Check if post exists
-        self.post_repo
-            .find_by_id(id)
+        // Check if post exists and the user may edit it
+        let existing = self
+            .post_repo
+            .find_by_id(site_id, id)
             .await?
             .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+        Self::authorize_owner(auth_user, existing.author_id, "update")?;
+        if let Some(since) = request.unmodified_since {
+            if Self::is_modified_since(&existing, since) {
+                return Err(AppError::PreconditionFailed(format!(
+                    "Post was changed at {}; reload it before saving",
+                    existing.updated_at.to_rfc3339()
+                )));
+            }
+        }
+        if let Some(status) = request.status {
+            Self::authorize_transition(auth_user, Some(existing.status), status)?;
+        }
 
         // Check slug uniqueness if updating
         if let Some(ref slug) = request.slug {
-            if let Some(existing) = self.post_repo.find_by_slug(slug).await? {
+            self.slugs.check_reserved("slug", slug)?;
+            if let Some(existing) = self.post_repo.find_by_slug(site_id, slug).await? {
                 if existing.id != id {
                     return Err(AppError::Conflict("Slug already exists".to_string()));
                 }
             }
         }
+        self.ensure_site_references(site_id, request.category_id, request.tag_ids.as_deref())
+            .await?;
+        let attachment_ids = self
+            .check_attachments(request.attachment_ids.as_deref())
+            .await?;
+        let tag_names = request
+            .tag_names
+            .as_deref()
+            .map(Self::normalize_tag_names)
+            .transpose()?;
+        Self::check_tag_count(
+            self.max_tags_per_post,
+            request.tag_ids.as_deref(),
+            tag_names.as_deref(),
+        )?;
 
         let post = self
             .post_repo
@@ -161,72 +370,660 @@ impl PostService {
                 request.content.as_deref(),
                 request.excerpt.as_deref(),
                 request.status,
+                request.visibility,
                 request.category_id,
+                PostTags::from_request(request.tag_ids, tag_names).as_ref(),
             )
             .await?;
 
-        // Update tags if provided
-        if let Some(tag_ids) = request.tag_ids {
-            self.post_repo.set_tags(post.id, &tag_ids).await?;
+        if let Some(attachment_ids) = attachment_ids {
+            self.media_repo
+                .set_post_attachments(post.id, &attachment_ids)
+                .await?;
         }
+        self.events.publish(DomainEvent::PostSaved {
+            before: Some(existing),
+            after: post.clone(),
+            origin: EventOrigin::Api,
+            actor_id: Some(auth_user.id),
+        });
 
         self.build_post_response(post).await
     }
 
+    /// Add `BlogPosting` JSON-LD for `post` on `site`, pointing at its frontend page.
+    pub fn with_structured_data(&self, site: &Site, mut post: PostResponse) -> PostResponse {
+        let path = self.post_path.replace("{slug}", &post.slug);
+        post.structured_data = Some(blog_posting_json_ld(
+            &post,
+            &site_base_url(site),
+            &path,
+            &site.name,
+        ));
+        post
+    }
+
+    /// Publish a draft, as `update` with only the status set.
+    pub async fn publish(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<PostResponse, AppError> {
+        let request = UpdatePostRequest {
+            status: Some(PostStatus::Published),
+            ..Default::default()
+        };
+        self.update(site_id, id, auth_user, request).await
+    }
+
+    /// Submit a draft for review, as `update` with only the status set.
+    pub async fn submit_for_review(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<PostResponse, AppError> {
+        let request = UpdatePostRequest {
+            status: Some(PostStatus::InReview),
+            ..Default::default()
+        };
+        self.update(site_id, id, auth_user, request).await
+    }
+
+    /// A site's posts waiting for review, paginated like `list`.
+    pub async fn review_queue(
+        &self,
+        site_id: Uuid,
+        query: PostQuery,
+    ) -> Result<(Vec<PostListItem>, Meta), AppError> {
+        let query = PostQuery {
+            status: Some(PostStatus::InReview),
+            search: None,
+            ..query
+        };
+        self.list(site_id, query, PostViewer::Admin).await
+    }
+
+    /// Approve a post in review, publishing it.
+    pub async fn approve(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        auth_user: &AuthUser,
+        note: Option<&str>,
+    ) -> Result<PostResponse, AppError> {
+        let note = note.map(str::trim).filter(|note| !note.is_empty());
+        let post = self
+            .decide(site_id, id, auth_user, PostStatus::Published, note)
+            .await?;
+        self.build_post_response(post).await
+    }
+
+    /// Reject a post in review, sending it back to draft and `note` to its author.
+    pub async fn reject(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        auth_user: &AuthUser,
+        note: &str,
+    ) -> Result<PostResponse, AppError> {
+        let note = note.trim();
+        if note.is_empty() {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "note",
+                "Explain what needs to change",
+            )]));
+        }
+        let post = self
+            .decide(site_id, id, auth_user, PostStatus::Draft, Some(note))
+            .await?;
+        if post.author_id != auth_user.id {
+            if let Err(err) = self.notify_rejected(&post, auth_user, note).await {
+                tracing::warn!(error = %err, post_id = %post.id, "Failed to notify author of rejection");
+            }
+        }
+        self.build_post_response(post).await
+    }
+
+    /// A post's review history, for its author and reviewers.
+    pub async fn reviews(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<Vec<PostReview>, AppError> {
+        let post = self
+            .post_repo
+            .find_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+        if !auth_user.has_permission("posts:review") {
+            Self::authorize_owner(auth_user, post.author_id, "update")?;
+        }
+        self.post_repo.find_reviews(post.id).await
+    }
+
     /// Delete a post.
-    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
-        self.post_repo.delete(id).await
+    pub async fn delete(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<bool, AppError> {
+        let post = self
+            .post_repo
+            .find_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+        Self::authorize_owner(auth_user, post.author_id, "delete")?;
+
+        let deleted = self.post_repo.delete(id).await?;
+        if deleted {
+            self.events.publish(DomainEvent::PostDeleted {
+                post,
+                origin: EventOrigin::Api,
+                actor_id: Some(auth_user.id),
+            });
+        }
+        Ok(deleted)
+    }
+
+    /// Create or update a post from a Markdown file in the synced Git repository.
+    ///
+    /// The file is matched to a post by its front matter `id`, then by slug.
+    /// New posts belong to the front matter `author` when it is a known user,
+    /// otherwise to `default_author_id`. Changes made here are not exported
+    /// back to the repository they came from.
+    pub async fn sync_from_git(
+        &self,
+        site_id: Uuid,
+        default_author_id: Uuid,
+        front_matter: PostFrontMatter,
+        content: &str,
+    ) -> Result<Post, AppError> {
+        let slug = front_matter
+            .slug
+            .clone()
+            .unwrap_or_else(|| slugify(&front_matter.title));
+        let existing = match front_matter.id {
+            Some(id) => self.post_repo.find_by_id(site_id, id).await?,
+            None => None,
+        };
+        let existing = match existing {
+            Some(post) => Some(post),
+            None => self.post_repo.find_by_slug(site_id, &slug).await?,
+        };
+        self.slugs.check_reserved("slug", &slug)?;
+        if let Some(other) = self.post_repo.find_by_slug(site_id, &slug).await? {
+            if existing.as_ref().is_some_and(|post| post.id != other.id) {
+                return Err(AppError::Conflict("Slug already exists".to_string()));
+            }
+        }
+
+        let category_id = match front_matter.category.as_deref() {
+            Some(category) => Some(
+                self.category_repo
+                    .find_by_slug(site_id, category)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::InvalidFields(vec![FieldError::new(
+                            "category",
+                            "does not exist on this site",
+                        )])
+                    })?
+                    .id,
+            ),
+            None => None,
+        };
+        let tags = PostTags {
+            ids: Vec::new(),
+            names: Self::normalize_tag_names(&front_matter.tags)?,
+        };
+
+        let post = match &existing {
+            Some(existing) => {
+                self.post_repo
+                    .update(
+                        existing.id,
+                        Some(&front_matter.title),
+                        Some(&slug),
+                        Some(content),
+                        front_matter.excerpt.as_deref(),
+                        front_matter.status,
+                        front_matter.visibility,
+                        category_id,
+                        Some(&tags),
+                    )
+                    .await?
+            }
+            None => {
+                let author_id = match front_matter.author.as_deref() {
+                    Some(email) => self
+                        .user_repo
+                        .find_by_email_with_role(email)
+                        .await?
+                        .map(|user| user.id),
+                    None => None,
+                };
+                self.post_repo
+                    .create(
+                        site_id,
+                        &front_matter.title,
+                        &slug,
+                        content,
+                        front_matter.excerpt.as_deref(),
+                        front_matter.status.unwrap_or_default(),
+                        front_matter.visibility.unwrap_or_default(),
+                        author_id.unwrap_or(default_author_id),
+                        category_id,
+                        Some(&tags),
+                    )
+                    .await?
+            }
+        };
+
+        self.events.publish(DomainEvent::PostSaved {
+            before: existing,
+            after: post.clone(),
+            origin: EventOrigin::Git,
+            actor_id: None,
+        });
+
+        Ok(post)
+    }
+
+    /// Delete the post whose Markdown file was removed from the synced Git repository.
+    pub async fn delete_from_git(&self, site_id: Uuid, slug: &str) -> Result<bool, AppError> {
+        let Some(post) = self.post_repo.find_by_slug(site_id, slug).await? else {
+            return Ok(false);
+        };
+
+        let deleted = self.post_repo.delete(post.id).await?;
+        if deleted {
+            self.events.publish(DomainEvent::PostDeleted {
+                post,
+                origin: EventOrigin::Git,
+                actor_id: None,
+            });
+        }
+        Ok(deleted)
     }
 
     // Private helper methods
 
-    async fn build_post_response(&self, post: Post) -> Result<PostResponse, AppError> {
-        // Get author
-        let author: Option<AuthorResponse> = self
+    /// Hide drafts from non-admins and posts outside the viewer's visibility.
+    ///
+    /// Anonymous readers of a members-only post are asked to sign in; anything
+    /// else they may not read looks like it does not exist.
+    fn ensure_visible(post: &Post, viewer: PostViewer) -> Result<(), AppError> {
+        if viewer.is_admin() {
+            return Ok(());
+        }
+        if post.status != PostStatus::Published {
+            return Err(AppError::NotFound("Post not found".to_string()));
+        }
+        if !viewer.can_see(post.visibility, post.author_id) {
+            if viewer == PostViewer::Anonymous && post.visibility == PostVisibility::Members {
+                return Err(AppError::Unauthorized);
+            }
+            return Err(AppError::NotFound("Post not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Move a post out of review to `next` and record the decision.
+    async fn decide(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        auth_user: &AuthUser,
+        next: PostStatus,
+        note: Option<&str>,
+    ) -> Result<Post, AppError> {
+        let existing = self
+            .post_repo
+            .find_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Post not found".to_string()))?;
+        if existing.status != PostStatus::InReview {
+            return Err(AppError::Conflict(format!(
+                "Post is {}, not in review",
+                existing.status
+            )));
+        }
+        Self::authorize_transition(auth_user, Some(existing.status), next)?;
+
+        let post = self
+            .post_repo
+            .update(id, None, None, None, None, Some(next), None, None, None)
+            .await?;
+        self.post_repo
+            .create_review(id, auth_user.id, next == PostStatus::Published, note)
+            .await?;
+        self.events.publish(DomainEvent::PostSaved {
+            before: Some(existing),
+            after: post.clone(),
+            origin: EventOrigin::Api,
+            actor_id: Some(auth_user.id),
+        });
+        Ok(post)
+    }
+
+    /// Email a post's author that a reviewer sent it back, unless they opted out.
+    async fn notify_rejected(
+        &self,
+        post: &Post,
+        auth_user: &AuthUser,
+        note: &str,
+    ) -> Result<(), AppError> {
+        let Some(author) = self.user_repo.find_by_id(post.author_id).await? else {
+            return Ok(());
+        };
+        let reviewer = self
             .user_repo
-            .find_by_id(post.author_id)
+            .find_by_id(auth_user.id)
             .await?
-            .map(|user| user.into());
+            .map(|user| user.name)
+            .unwrap_or_else(|| auth_user.email.clone());
+        self.notifications
+            .notify(
+                author.id,
+                &author.email,
+                NotificationKind::Review,
+                EmailTemplate::PostRejected,
+                json!({
+                    "name": author.name,
+                    "title": post.title,
+                    "reviewer": reviewer,
+                    "note": note,
+                }),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Reject a category and tags that are not on the site, naming each missing ID.
+    pub async fn ensure_site_references(
+        &self,
+        site_id: Uuid,
+        category_id: Option<Uuid>,
+        tag_ids: Option<&[Uuid]>,
+    ) -> Result<(), AppError> {
+        let tag_ids = tag_ids.unwrap_or_default();
+        if category_id.is_none() && tag_ids.is_empty() {
+            return Ok(());
+        }
+        let (category_missing, missing_tags) = self
+            .post_repo
+            .find_missing_taxonomy(site_id, category_id, tag_ids)
+            .await?;
+
+        let mut errors = Vec::new();
+        if let Some(category_id) = category_id.filter(|_| category_missing) {
+            errors.push(FieldError::new(
+                "category_id",
+                format!("{} does not exist on this site", category_id),
+            ));
+        }
+        if !missing_tags.is_empty() {
+            let mut missing: Vec<Uuid> = Vec::new();
+            for id in tag_ids.iter().filter(|id| missing_tags.contains(id)) {
+                if !missing.contains(id) {
+                    missing.push(*id);
+                }
+            }
+            let missing: Vec<String> = missing.iter().map(Uuid::to_string).collect();
+            errors.push(FieldError::new(
+                "tag_ids",
+                format!("include tags not on this site: {}", missing.join(", ")),
+            ));
+        }
 
-        // Get category
-        let category: Option<Category> = if let Some(cat_id) = post.category_id {
-            self.category_repo.find_by_id(cat_id).await?
+        if errors.is_empty() {
+            Ok(())
         } else {
-            None
+            Err(AppError::InvalidFields(errors))
+        }
+    }
+
+    /// Check that every attachment exists, dropping repeats but keeping the order.
+    async fn check_attachments(
+        &self,
+        attachment_ids: Option<&[Uuid]>,
+    ) -> Result<Option<Vec<Uuid>>, AppError> {
+        let Some(attachment_ids) = attachment_ids else {
+            return Ok(None);
+        };
+        let mut ids: Vec<Uuid> = Vec::with_capacity(attachment_ids.len());
+        for id in attachment_ids {
+            if !ids.contains(id) {
+                ids.push(*id);
+            }
+        }
+        if !ids.is_empty() && self.media_repo.find_by_ids(&ids).await?.len() != ids.len() {
+            return Err(AppError::InvalidFields(vec![FieldError::new(
+                "attachment_ids",
+                "include media that does not exist",
+            )]));
+        }
+        Ok(Some(ids))
+    }
+
+    async fn build_post_response(&self, post: Post) -> Result<PostResponse, AppError> {
+        let mut responses = self.build_post_responses(vec![post]).await?;
+        responses
+            .pop()
+            .ok_or_else(|| AppError::InternalError("Post response was not built".to_string()))
+    }
+
+    /// Build responses for posts of one site, loading authors, categories,
+    /// tags and attachments for all of them at once.
+    async fn build_post_responses(&self, posts: Vec<Post>) -> Result<Vec<PostResponse>, AppError> {
+        let Some(site_id) = posts.first().map(|post| post.site_id) else {
+            return Ok(vec![]);
         };
+        let post_ids: Vec<Uuid> = posts.iter().map(|post| post.id).collect();
+        let mut author_ids: Vec<Uuid> = posts.iter().map(|post| post.author_id).collect();
+        author_ids.sort_unstable();
+        author_ids.dedup();
+        let mut category_ids: Vec<Uuid> =
+            posts.iter().filter_map(|post| post.category_id).collect();
+        category_ids.sort_unstable();
+        category_ids.dedup();
+
+        let contents: Vec<&str> = posts.iter().map(|post| post.content.as_str()).collect();
+
+        let (authors, categories, tags, attachments, polls) = tokio::try_join!(
+            self.user_repo.find_by_ids(&author_ids),
+            self.category_repo.find_by_ids(site_id, &category_ids),
+            self.tag_repo.find_by_posts(site_id, &post_ids),
+            self.media_repo.find_by_posts(&post_ids),
+            self.polls.embedded(site_id, &contents),
+        )?;
+
+        let mut responses = Vec::with_capacity(posts.len());
+        for (post, polls) in posts.into_iter().zip(polls) {
+            let author: Option<AuthorResponse> = authors
+                .iter()
+                .find(|user| user.id == post.author_id)
+                .cloned()
+                .map(|user| user.into());
+            let category: Option<Category> = post.category_id.and_then(|id| {
+                categories
+                    .iter()
+                    .find(|category| category.id == id)
+                    .cloned()
+            });
+            let tags: Vec<Tag> = tags
+                .iter()
+                .filter(|tag| tag.post_id == post.id)
+                .map(|tag| tag.tag.clone())
+                .collect();
+            let attachments = attachments
+                .iter()
+                .filter(|attachment| attachment.post_id == post.id)
+                .map(|attachment| AttachmentResponse::from(attachment.media.clone()))
+                .collect();
+
+            responses.push(PostResponse {
+                id: post.id,
+                title: post.title,
+                slug: post.slug,
+                content: post.content,
+                excerpt: post.excerpt,
+                status: post.status,
+                visibility: post.visibility,
+                author,
+                category,
+                tags,
+                polls,
+                attachments,
+                published_at: post.published_at,
+                created_at: post.created_at,
+                updated_at: post.updated_at,
+                structured_data: None,
+            });
+        }
+
+        Ok(responses)
+    }
+
+    /// Where `post` was first asked for in a batch request.
+    fn batch_position(request: &BatchPostsRequest, post: &Post) -> usize {
+        request
+            .ids
+            .iter()
+            .position(|id| *id == post.id)
+            .or_else(|| {
+                request
+                    .slugs
+                    .iter()
+                    .position(|slug| *slug == post.slug)
+                    .map(|index| request.ids.len() + index)
+            })
+            .unwrap_or(usize::MAX)
+    }
 
-        // Get tags
-        let tag_ids = self.post_repo.get_tag_ids(post.id).await?;
-        let tags: Vec<Tag> = if !tag_ids.is_empty() {
-            self.tag_repo.find_by_ids(&tag_ids).await?
+    /// Allow `action` via `posts:<action>_any`, or `posts:<action>_own` for the author.
+    fn authorize_owner(
+        auth_user: &AuthUser,
+        author_id: Uuid,
+        action: &str,
+    ) -> Result<(), AppError> {
+        if auth_user.has_permission(&format!("posts:{}_any", action)) {
+            return Ok(());
+        }
+        if auth_user.id == author_id && auth_user.has_permission(&format!("posts:{}_own", action)) {
+            return Ok(());
+        }
+        Err(AppError::Forbidden(format!(
+            "Cannot {} posts by other authors",
+            action
+        )))
+    }
+
+    /// Check that `auth_user` may move a post from `from` (`None` for a new
+    /// post, which starts as a draft) to `to`.
+    ///
+    /// Taking a post out of review, by approving or rejecting it, requires
+    /// `posts:review`. Publishing and unpublishing, including archiving a
+    /// published post or approving one in review, require `posts:publish`.
+    fn authorize_transition(
+        auth_user: &AuthUser,
+        from: Option<PostStatus>,
+        to: PostStatus,
+    ) -> Result<(), AppError> {
+        let from_status = from.unwrap_or_default();
+        if !from_status.can_transition_to(to) {
+            return Err(AppError::Conflict(format!(
+                "Cannot move a post from {} to {}",
+                from_status, to
+            )));
+        }
+        if from_status == PostStatus::InReview
+            && to != PostStatus::InReview
+            && !auth_user.has_permission("posts:review")
+        {
+            return Err(AppError::Forbidden(
+                "Cannot approve or reject posts in review".to_string(),
+            ));
+        }
+        let publishing = (from_status == PostStatus::Published) != (to == PostStatus::Published);
+        if publishing && !auth_user.can_publish() {
+            return Err(AppError::Forbidden(
+                "Cannot publish or unpublish posts".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reject more than `max` tags, counting distinct IDs and names apart
+    /// (a name may turn out to be one of the IDs, so this is an upper bound).
+    fn check_tag_count(
+        max: usize,
+        tag_ids: Option<&[Uuid]>,
+        tag_names: Option<&[(String, String)]>,
+    ) -> Result<(), AppError> {
+        let mut ids = tag_ids.unwrap_or_default().to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        let count = ids.len() + tag_names.map_or(0, |names| names.len());
+        if count <= max {
+            return Ok(());
+        }
+        let field = if tag_names.is_some() {
+            "tag_names"
         } else {
-            vec![]
-        };
-
-        Ok(PostResponse {
-            id: post.id,
-            title: post.title,
-            slug: post.slug,
-            content: post.content,
-            excerpt: post.excerpt,
-            status: post.status,
-            author,
-            category,
-            tags,
-            created_at: post.created_at,
-            updated_at: post.updated_at,
+            "tag_ids"
+        };
+        Err(AppError::InvalidFields(vec![FieldError::new(
+            field,
+            format!("a post can have at most {} tags, got {}", max, count),
+        )]))
+    }
+
+    /// Slug from a post title that is not reserved and no other post of the
+    /// site uses.
+    async fn generate_slug(&self, site_id: Uuid, title: &str) -> Result<String, AppError> {
+        let (post_repo, slugs) = (&self.post_repo, &self.slugs);
+        let max_len = slugs.max_length().min(MAX_SLUG_LEN);
+        slug::unique(&slugs.generate(title), max_len, |candidate| async move {
+            Ok(slugs.is_reserved(&candidate)
+                || post_repo.find_by_slug(site_id, &candidate).await?.is_some())
         })
+        .await
+    }
+
+    /// Trim tag names and pair each with its slug, dropping blanks and
+    /// names that repeat an earlier slug.
+    fn normalize_tag_names(names: &[String]) -> Result<Vec<(String, String)>, AppError> {
+        let mut normalized: Vec<(String, String)> = Vec::new();
+        for name in names
+            .iter()
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+        {
+            let slug = slugify(name);
+            if slug.is_empty() || name.chars().count() > MAX_TAG_LEN || slug.len() > MAX_TAG_LEN {
+                return Err(AppError::InvalidFields(vec![FieldError::new(
+                    "tag_names",
+                    format!(
+                        "\"{}\" must contain letters or digits and be at most {} characters",
+                        name, MAX_TAG_LEN
+                    ),
+                )]));
+            }
+            if !normalized.iter().any(|(_, existing)| *existing == slug) {
+                normalized.push((name.to_string(), slug));
+            }
+        }
+        Ok(normalized)
     }
 
-    fn slugify(text: &str) -> String {
-        text.to_lowercase()
-            .chars()
-            .map(|c| if c.is_alphanumeric() { c } else { '-' })
-            .collect::<String>()
-            .split('-')
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<_>>()
-            .join("-")
+    /// Whether the post changed after `since`, compared in whole seconds as HTTP dates are.
+    fn is_modified_since(post: &Post, since: DateTime<Utc>) -> bool {
+        post.updated_at.timestamp() > since.timestamp()
     }
 }
 
@@ -235,11 +1032,159 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_slugify() {
-        assert_eq!(PostService::slugify("Hello World"), "hello-world");
-        assert_eq!(PostService::slugify("Hello  World"), "hello-world");
-        assert_eq!(PostService::slugify("Hello World!"), "hello-world");
-        assert_eq!(PostService::slugify("  Hello   World  "), "hello-world");
-        assert_eq!(PostService::slugify("Rust 2024"), "rust-2024");
+    fn test_check_tag_count() {
+        let ids = [Uuid::new_v4(), Uuid::new_v4()];
+        let repeated = [ids[0], ids[1], ids[0]];
+        let names = vec![("Rust".to_string(), "rust".to_string())];
+
+        assert!(PostService::check_tag_count(2, Some(&repeated), None).is_ok());
+        assert!(PostService::check_tag_count(3, Some(&ids), Some(&names)).is_ok());
+        assert!(PostService::check_tag_count(1, Some(&ids), None).is_err());
+        assert!(PostService::check_tag_count(2, Some(&ids), Some(&names)).is_err());
+        assert!(PostService::check_tag_count(0, None, None).is_ok());
+    }
+
+    #[test]
+    fn test_normalize_tag_names() {
+        let names = ["Rust", " rust ", "", "Web Dev", "web-dev"].map(String::from);
+        let normalized = PostService::normalize_tag_names(&names).unwrap();
+        assert_eq!(
+            normalized,
+            vec![
+                ("Rust".to_string(), "rust".to_string()),
+                ("Web Dev".to_string(), "web-dev".to_string()),
+            ]
+        );
+
+        assert!(PostService::normalize_tag_names(&["!!!".to_string()]).is_err());
+        assert!(PostService::normalize_tag_names(&["x".repeat(51)]).is_err());
+    }
+
+    #[test]
+    fn test_authorize_owner() {
+        let user = |role_slug: &str, permissions: &[&str]| AuthUser {
+            id: Uuid::new_v4(),
+            email: "user@test.com".to_string(),
+            role_id: Uuid::new_v4(),
+            role_slug: role_slug.to_string(),
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            token_id: None,
+            session_id: None,
+            permission_audit: None,
+        };
+        let other_author = Uuid::new_v4();
+
+        let writer = user("writer", &["posts:update_own", "posts:delete_own"]);
+        assert!(PostService::authorize_owner(&writer, writer.id, "update").is_ok());
+        assert!(PostService::authorize_owner(&writer, writer.id, "delete").is_ok());
+        assert!(matches!(
+            PostService::authorize_owner(&writer, other_author, "update"),
+            Err(AppError::Forbidden(_))
+        ));
+
+        let editor = user("editor", &["posts:update_any"]);
+        assert!(PostService::authorize_owner(&editor, other_author, "update").is_ok());
+        assert!(PostService::authorize_owner(&editor, other_author, "delete").is_err());
+
+        let admin = user("admin", &[]);
+        assert!(PostService::authorize_owner(&admin, other_author, "delete").is_ok());
+    }
+
+    #[test]
+    fn test_authorize_transition() {
+        use PostStatus::*;
+
+        let user = |permissions: &[&str]| AuthUser {
+            id: Uuid::new_v4(),
+            email: "user@test.com".to_string(),
+            role_id: Uuid::new_v4(),
+            role_slug: "writer".to_string(),
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            token_id: None,
+            session_id: None,
+            permission_audit: None,
+        };
+        let writer = user(&["posts:update_own"]);
+        let publisher = user(&["posts:update_own", "posts:publish"]);
+
+        assert!(PostService::authorize_transition(&writer, None, Draft).is_ok());
+        assert!(PostService::authorize_transition(&writer, Some(Draft), Draft).is_ok());
+        assert!(PostService::authorize_transition(&writer, Some(Archived), Draft).is_ok());
+        for (from, to) in [
+            (None, Published),
+            (Some(Draft), Published),
+            (Some(Published), Draft),
+            (Some(Published), Archived),
+        ] {
+            assert!(matches!(
+                PostService::authorize_transition(&writer, from, to),
+                Err(AppError::Forbidden(_))
+            ));
+            assert!(PostService::authorize_transition(&publisher, from, to).is_ok());
+        }
+        // Writers submit for review; only reviewers take posts out of it
+        let reviewer = user(&["posts:update_own", "posts:review"]);
+        let editor = user(&["posts:update_own", "posts:review", "posts:publish"]);
+        assert!(PostService::authorize_transition(&writer, Some(Draft), InReview).is_ok());
+        for to in [Published, Draft] {
+            assert!(matches!(
+                PostService::authorize_transition(&publisher, Some(InReview), to),
+                Err(AppError::Forbidden(_))
+            ));
+            assert!(PostService::authorize_transition(&editor, Some(InReview), to).is_ok());
+        }
+        // Approving publishes the post, so reviewers without posts:publish can only reject
+        assert!(PostService::authorize_transition(&reviewer, Some(InReview), Draft).is_ok());
+        for user in [&writer, &reviewer] {
+            assert!(matches!(
+                PostService::authorize_transition(user, Some(InReview), Published),
+                Err(AppError::Forbidden(_))
+            ));
+        }
+        assert!(PostService::authorize_transition(&writer, Some(InReview), InReview).is_ok());
+        // Editing a published post without touching its status needs no extra permission
+        assert!(PostService::authorize_transition(&writer, Some(Published), Published).is_ok());
+        assert!(matches!(
+            PostService::authorize_transition(&publisher, None, Archived),
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            PostService::authorize_transition(&publisher, Some(Archived), Published),
+            Err(AppError::Conflict(_))
+        ));
+        for user in [&writer, &editor] {
+            assert!(matches!(
+                PostService::authorize_transition(user, Some(Archived), InReview),
+                Err(AppError::Conflict(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_is_modified_since() {
+        let updated_at = Utc::now();
+        let post = Post {
+            id: Uuid::new_v4(),
+            site_id: Uuid::new_v4(),
+            title: "Title".to_string(),
+            slug: "title".to_string(),
+            content: String::new(),
+            excerpt: None,
+            status: PostStatus::Draft,
+            visibility: PostVisibility::default(),
+            author_id: Uuid::new_v4(),
+            category_id: None,
+            published_at: None,
+            created_at: updated_at,
+            updated_at,
+        };
+        // An HTTP date drops the fraction of a second
+        let http_date = DateTime::from_timestamp(updated_at.timestamp(), 0).unwrap();
+        assert!(!PostService::is_modified_since(&post, http_date));
+        assert!(!PostService::is_modified_since(&post, updated_at));
+        assert!(PostService::is_modified_since(
+            &post,
+            http_date - chrono::Duration::seconds(1)
+        ));
     }
 }
@@ -0,0 +1,169 @@
+//! Post template service for recurring post formats.
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::error::{AppError, FieldError};
+use crate::middleware::AuthUser;
+use crate::models::{
+    CreatePostRequest, CreatePostTemplateRequest, PostFromTemplateRequest, PostResponse,
+    PostTemplate, UpdatePostTemplateRequest,
+};
+use crate::repositories::PostTemplateRepository;
+use crate::services::PostService;
+
+/// Longest template name (the `name` column is `VARCHAR(100)`).
+const MAX_NAME_LEN: usize = 100;
+/// Longest title pattern (the `title_pattern` column is `VARCHAR(255)`).
+const MAX_TITLE_PATTERN_LEN: usize = 255;
+
+/// Service for post templates.
+#[derive(Clone)]
+pub struct PostTemplateService {
+    repo: PostTemplateRepository,
+    post_service: PostService,
+}
+
+impl PostTemplateService {
+    /// Create a new post template service.
+    pub fn new(repo: PostTemplateRepository, post_service: PostService) -> Self {
+        Self { repo, post_service }
+    }
+
+    /// List a site's templates by name.
+    pub async fn list(&self, site_id: Uuid) -> Result<Vec<PostTemplate>, AppError> {
+        self.repo.find_all(site_id).await
+    }
+
+    /// Create a template on a site.
+    pub async fn create(
+        &self,
+        site_id: Uuid,
+        created_by: Uuid,
+        request: CreatePostTemplateRequest,
+    ) -> Result<PostTemplate, AppError> {
+        Self::validate(Some(&request.name), Some(&request.title_pattern))?;
+        self.post_service
+            .ensure_site_references(site_id, request.category_id, Some(&request.tag_ids))
+            .await?;
+
+        self.repo
+            .create(
+                site_id,
+                request.name.trim(),
+                request.title_pattern.trim(),
+                &request.content,
+                request.category_id,
+                &dedup(request.tag_ids),
+                created_by,
+            )
+            .await
+    }
+
+    /// Update a template.
+    pub async fn update(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        request: UpdatePostTemplateRequest,
+    ) -> Result<PostTemplate, AppError> {
+        self.find(site_id, id).await?;
+        Self::validate(request.name.as_deref(), request.title_pattern.as_deref())?;
+        self.post_service
+            .ensure_site_references(site_id, request.category_id, request.tag_ids.as_deref())
+            .await?;
+
+        self.repo
+            .update(
+                id,
+                request.name.as_deref().map(str::trim),
+                request.title_pattern.as_deref().map(str::trim),
+                request.content.as_deref(),
+                request.category_id,
+                request.tag_ids.map(dedup).as_deref(),
+            )
+            .await
+    }
+
+    /// Delete a template; posts created from it are kept.
+    pub async fn delete(&self, site_id: Uuid, id: Uuid) -> Result<bool, AppError> {
+        self.find(site_id, id).await?;
+        self.repo.delete(id).await
+    }
+
+    /// Create a draft from a template, with its title rendered for today
+    /// unless `request` gives one.
+    pub async fn create_post(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        auth_user: &AuthUser,
+        request: PostFromTemplateRequest,
+    ) -> Result<PostResponse, AppError> {
+        let template = self.find(site_id, id).await?;
+        let title = request
+            .title
+            .unwrap_or_else(|| template.render_title(Utc::now()));
+        let post = CreatePostRequest {
+            title,
+            slug: request.slug,
+            content: template.content,
+            excerpt: None,
+            status: None,
+            visibility: None,
+            category_id: template.category_id,
+            tag_ids: (!template.tag_ids.is_empty()).then_some(template.tag_ids),
+            tag_names: None,
+            attachment_ids: None,
+        };
+        self.post_service.create(site_id, auth_user, post).await
+    }
+
+    // Private helper methods
+
+    async fn find(&self, site_id: Uuid, id: Uuid) -> Result<PostTemplate, AppError> {
+        self.repo
+            .find_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Post template not found".to_string()))
+    }
+
+    /// Check the sent fields.
+    fn validate(name: Option<&str>, title_pattern: Option<&str>) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if let Some(name) = name.map(str::trim) {
+            if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+                errors.push(FieldError::new(
+                    "name",
+                    format!("must be 1 to {} characters", MAX_NAME_LEN),
+                ));
+            }
+        }
+        if let Some(pattern) = title_pattern.map(str::trim) {
+            if pattern.is_empty() || pattern.chars().count() > MAX_TITLE_PATTERN_LEN {
+                errors.push(FieldError::new(
+                    "title_pattern",
+                    format!("must be 1 to {} characters", MAX_TITLE_PATTERN_LEN),
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidFields(errors))
+        }
+    }
+}
+
+/// Tag IDs without repeats, in their first order.
+fn dedup(tag_ids: Vec<Uuid>) -> Vec<Uuid> {
+    let mut unique = Vec::with_capacity(tag_ids.len());
+    for id in tag_ids {
+        if !unique.contains(&id) {
+            unique.push(id);
+        }
+    }
+    unique
+}
@@ -0,0 +1,129 @@
+//! Preview service granting the frontend read access to single drafts.
+
+use chrono::{Duration, Utc};
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{
+    PostViewer, PreviewGrant, PreviewResponse, PreviewToken, PREVIEW_TOKEN_PREFIX,
+};
+use crate::pkg::redis::keys;
+use crate::services::PostService;
+
+/// Service minting and exchanging draft preview tokens.
+///
+/// A token grants read access to one post until it expires, without signing
+/// in; it carries none of its creator's other permissions. Grants and their
+/// use counts live in Redis and expire together.
+#[derive(Clone)]
+pub struct PreviewService {
+    redis: redis::aio::ConnectionManager,
+    post_service: PostService,
+    ttl_minutes: u64,
+}
+
+impl PreviewService {
+    /// Create a new preview service.
+    pub fn new(
+        config: &Config,
+        redis: redis::aio::ConnectionManager,
+        post_service: PostService,
+    ) -> Self {
+        Self {
+            redis,
+            post_service,
+            ttl_minutes: config.preview_token_ttl_minutes,
+        }
+    }
+
+    /// Mint a preview token for a post the user may edit.
+    pub async fn mint(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<PreviewToken, AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+
+        let token = generate_token();
+        let expires_at = Utc::now() + Duration::minutes(self.ttl_minutes as i64);
+        let grant = PreviewGrant {
+            post_id: post.id,
+            site_id,
+            created_by: auth_user.id,
+            expires_at,
+        };
+        let json = serde_json::to_string(&grant)
+            .map_err(|e| AppError::InternalError(format!("Failed to encode preview: {}", e)))?;
+        let mut redis = self.redis.clone();
+        let _: () = redis
+            .set_ex(keys::preview(&token), json, self.ttl_minutes * 60)
+            .await?;
+
+        tracing::info!(post_id = %post.id, user_id = %auth_user.id, "Preview token minted");
+        Ok(PreviewToken {
+            token,
+            post_id: post.id,
+            expires_at,
+        })
+    }
+
+    /// Fetch the post a token grants access to, whatever its status, and count the use.
+    pub async fn exchange(&self, token: &str) -> Result<PreviewResponse, AppError> {
+        let invalid = || AppError::NotFound("Preview link is invalid or has expired".to_string());
+        if !token.starts_with(PREVIEW_TOKEN_PREFIX) {
+            return Err(invalid());
+        }
+
+        let mut redis = self.redis.clone();
+        let json: Option<String> = redis.get(keys::preview(token)).await?;
+        let grant: PreviewGrant = json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .ok_or_else(invalid)?;
+
+        let uses_key = keys::preview_uses(token);
+        let uses: u64 = redis.incr(&uses_key, 1).await?;
+        let _: () = redis
+            .expire_at(&uses_key, grant.expires_at.timestamp())
+            .await?;
+
+        let post = self
+            .post_service
+            .get_by_id(grant.site_id, grant.post_id, PostViewer::Admin)
+            .await?;
+        Ok(PreviewResponse {
+            post,
+            uses,
+            expires_at: grant.expires_at,
+        })
+    }
+}
+
+/// Generate a new token: the prefix followed by 64 random hex digits.
+fn generate_token() -> String {
+    format!(
+        "{}{}{}",
+        PREVIEW_TOKEN_PREFIX,
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_token() {
+        let token = generate_token();
+        assert!(token.starts_with(PREVIEW_TOKEN_PREFIX));
+        assert_eq!(token.len(), PREVIEW_TOKEN_PREFIX.len() + 64);
+        assert_ne!(token, generate_token());
+    }
+}
@@ -0,0 +1,421 @@
+//! Profile service for self-service account management.
+
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::{Media, UpdateProfileRequest, UserProfile};
+use crate::pkg::redis::keys;
+use crate::pkg::EmailTemplate;
+use crate::repositories::UserRepository;
+use crate::services::{AuthService, EmailService};
+
+/// How long an email verification link stays valid.
+const EMAIL_VERIFICATION_TTL_SECONDS: u64 = 24 * 3600;
+/// Maximum length of a profile bio.
+const MAX_BIO_LEN: usize = 1000;
+/// Maximum number of social links on a profile.
+const MAX_SOCIAL_LINKS: usize = 10;
+
+/// Service for the authenticated user's own profile.
+#[derive(Clone)]
+pub struct ProfileService {
+    config: Config,
+    user_repo: UserRepository,
+    redis: redis::aio::ConnectionManager,
+    email_service: EmailService,
+    auth_service: AuthService,
+}
+
+impl ProfileService {
+    /// Create a new profile service.
+    pub fn new(
+        config: Config,
+        user_repo: UserRepository,
+        redis: redis::aio::ConnectionManager,
+        email_service: EmailService,
+        auth_service: AuthService,
+    ) -> Self {
+        Self {
+            config,
+            user_repo,
+            redis,
+            email_service,
+            auth_service,
+        }
+    }
+
+    /// Get a user's profile.
+    pub async fn get(&self, user_id: Uuid) -> Result<UserProfile, AppError> {
+        self.user_repo
+            .find_profile(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+    }
+
+    /// Update a user's profile.
+    ///
+    /// A changed email is stored as pending and a verification link is sent
+    /// to the new address; the account email only changes once it is used.
+    /// The current address is told about the change, with a link to cancel
+    /// it in case the request came from a stolen session.
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        request: UpdateProfileRequest,
+    ) -> Result<UserProfile, AppError> {
+        Self::validate(&request)?;
+        let profile = self.get(user_id).await?;
+
+        self.user_repo
+            .update_profile(
+                user_id,
+                request.name.as_deref().map(str::trim),
+                request.bio.as_deref().map(str::trim),
+                request.avatar_url.as_deref().map(str::trim),
+                request.social_links.as_ref(),
+            )
+            .await?;
+
+        if let Some(email) = request.email.as_deref().map(str::trim) {
+            if !email.eq_ignore_ascii_case(&profile.email) {
+                self.request_email_change(&profile, email).await?;
+            }
+        }
+
+        self.get(user_id).await
+    }
+
+    /// Use an uploaded image as the user's avatar.
+    pub async fn set_avatar(&self, user_id: Uuid, media: &Media) -> Result<UserProfile, AppError> {
+        self.user_repo
+            .set_avatar(user_id, Some(media.id), Some(&media.url()))
+            .await?;
+        self.get(user_id).await
+    }
+
+    /// Remove the user's avatar so the Gravatar fallback is shown.
+    pub async fn clear_avatar(&self, user_id: Uuid) -> Result<UserProfile, AppError> {
+        self.user_repo.set_avatar(user_id, None, None).await?;
+        self.get(user_id).await
+    }
+
+    /// Confirm an email change with the token from the verification link,
+    /// signing the user out everywhere. The cancellation link sent to the old
+    /// address stops working.
+    pub async fn verify_email(&self, token: &str) -> Result<(), AppError> {
+        let mut redis = self.redis.clone();
+        let value: Option<String> = redis.get(keys::email_verification(token)).await?;
+        let value = value.ok_or_else(|| {
+            AppError::ValidationError("Invalid or expired verification token".to_string())
+        })?;
+        let (user_id, email, used_keys) = confirmation(token, &value)?;
+
+        if self.email_taken_by_other(user_id, email).await? {
+            return Err(AppError::Conflict("Email already in use".to_string()));
+        }
+        if !self.user_repo.confirm_pending_email(user_id, email).await? {
+            return Err(AppError::ValidationError(
+                "Verification token no longer matches the pending email".to_string(),
+            ));
+        }
+
+        let _: () = redis.del(&used_keys).await?;
+        self.auth_service.revoke_user_tokens(user_id).await
+    }
+
+    /// Cancel a pending email change with the token from the notice sent to
+    /// the current address, signing the user out everywhere.
+    pub async fn cancel_email_change(&self, token: &str) -> Result<(), AppError> {
+        let mut redis = self.redis.clone();
+        let value: Option<String> = redis.get(keys::email_change_cancel(token)).await?;
+        let value = value.ok_or_else(|| {
+            AppError::ValidationError("Invalid or expired cancellation token".to_string())
+        })?;
+        let (_, verify_token) = parse_token_value(&value)?;
+        let pending: Option<String> = redis.get(keys::email_verification(verify_token)).await?;
+        let (user_id, email, used_keys) = cancellation(token, &value, pending.as_deref())?;
+
+        if let Some(email) = email {
+            self.user_repo.clear_pending_email(user_id, email).await?;
+        }
+
+        let _: () = redis.del(&used_keys).await?;
+        self.auth_service.revoke_user_tokens(user_id).await
+    }
+
+    // Private helper methods
+
+    async fn request_email_change(
+        &self,
+        profile: &UserProfile,
+        email: &str,
+    ) -> Result<(), AppError> {
+        let user_id = profile.id;
+        if self.email_taken_by_other(user_id, email).await? {
+            return Err(AppError::Conflict("Email already in use".to_string()));
+        }
+        self.user_repo.set_pending_email(user_id, email).await?;
+
+        let token = Uuid::new_v4().simple().to_string();
+        let cancel_token = Uuid::new_v4().simple().to_string();
+        let mut redis = self.redis.clone();
+        let _: () = redis
+            .set_ex(
+                keys::email_verification(&token),
+                format!("{} {} {}", user_id, cancel_token, email),
+                EMAIL_VERIFICATION_TTL_SECONDS,
+            )
+            .await?;
+        let _: () = redis
+            .set_ex(
+                keys::email_change_cancel(&cancel_token),
+                format!("{} {}", user_id, token),
+                EMAIL_VERIFICATION_TTL_SECONDS,
+            )
+            .await?;
+
+        let base_url = self.config.app_base_url.trim_end_matches('/');
+        self.email_service
+            .send(
+                &profile.email,
+                EmailTemplate::EmailChangeNotice,
+                serde_json::json!({
+                    "name": profile.name,
+                    "new_email": email,
+                    "cancel_url": format!("{}/cancel-email-change?token={}", base_url, cancel_token),
+                }),
+            )
+            .await?;
+        self.email_service
+            .send(
+                email,
+                EmailTemplate::EmailChange,
+                serde_json::json!({
+                    "verify_url": format!("{}/verify-email?token={}", base_url, token),
+                }),
+            )
+            .await
+    }
+
+    async fn email_taken_by_other(&self, user_id: Uuid, email: &str) -> Result<bool, AppError> {
+        Ok(self
+            .user_repo
+            .find_by_email_with_role(email)
+            .await?
+            .is_some_and(|other| other.id != user_id))
+    }
+
+    fn validate(request: &UpdateProfileRequest) -> Result<(), AppError> {
+        if request.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+            return Err(AppError::ValidationError(
+                "name cannot be empty".to_string(),
+            ));
+        }
+        if let Some(email) = request.email.as_deref().map(str::trim) {
+            if email.parse::<lettre::Address>().is_err() {
+                return Err(AppError::ValidationError("email is invalid".to_string()));
+            }
+        }
+        if request
+            .bio
+            .as_deref()
+            .is_some_and(|bio| bio.chars().count() > MAX_BIO_LEN)
+        {
+            return Err(AppError::ValidationError(format!(
+                "bio must be at most {} characters",
+                MAX_BIO_LEN
+            )));
+        }
+        if let Some(url) = request.avatar_url.as_deref().map(str::trim) {
+            if !url.is_empty() && !is_http_url(url) {
+                return Err(AppError::ValidationError(
+                    "avatar_url must be an http(s) URL".to_string(),
+                ));
+            }
+        }
+        if let Some(links) = &request.social_links {
+            if links.len() > MAX_SOCIAL_LINKS {
+                return Err(AppError::ValidationError(format!(
+                    "At most {} social links are allowed",
+                    MAX_SOCIAL_LINKS
+                )));
+            }
+            if let Some((network, _)) = links.iter().find(|(_, url)| !is_http_url(url)) {
+                return Err(AppError::ValidationError(format!(
+                    "social_links.{} must be an http(s) URL",
+                    network
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split a stored `"<user id> <value>"` token value.
+fn parse_token_value(value: &str) -> Result<(Uuid, &str), AppError> {
+    value
+        .split_once(' ')
+        .and_then(|(id, rest)| Uuid::parse_str(id).ok().map(|id| (id, rest)))
+        .ok_or_else(|| AppError::InternalError("Malformed email change token".to_string()))
+}
+
+/// Split a verification token's `"<user id> <cancel token> <email>"` value.
+///
+/// Tokens issued before the cancel token was stored alongside hold only
+/// `"<user id> <email>"`.
+fn parse_verification_value(value: &str) -> Result<(Uuid, Option<&str>, &str), AppError> {
+    let (user_id, rest) = parse_token_value(value)?;
+    Ok(match rest.split_once(' ') {
+        Some((cancel_token, email)) => (user_id, Some(cancel_token), email),
+        None => (user_id, None, rest),
+    })
+}
+
+/// The user, new email and Redis keys used up by confirming the change with
+/// verification `token`, whose stored value is `value`.
+fn confirmation<'a>(token: &str, value: &'a str) -> Result<(Uuid, &'a str, Vec<String>), AppError> {
+    let (user_id, cancel_token, email) = parse_verification_value(value)?;
+    let mut used_keys = vec![keys::email_verification(token)];
+    used_keys.extend(cancel_token.map(keys::email_change_cancel));
+    Ok((user_id, email, used_keys))
+}
+
+/// The user, pending email (`None` once the verification link expired) and
+/// Redis keys used up by cancelling the change with cancellation `token`.
+fn cancellation<'a>(
+    token: &str,
+    value: &'a str,
+    pending: Option<&'a str>,
+) -> Result<(Uuid, Option<&'a str>, Vec<String>), AppError> {
+    let (user_id, verify_token) = parse_token_value(value)?;
+    let email = match pending {
+        Some(pending) => Some(parse_verification_value(pending)?.2),
+        None => None,
+    };
+    let used_keys = vec![
+        keys::email_change_cancel(token),
+        keys::email_verification(verify_token),
+    ];
+    Ok((user_id, email, used_keys))
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_validate_profile_update() {
+        assert!(ProfileService::validate(&UpdateProfileRequest::default()).is_ok());
+
+        let valid = UpdateProfileRequest {
+            name: Some("Budhi".to_string()),
+            email: Some("me@example.com".to_string()),
+            avatar_url: Some(String::new()),
+            social_links: Some(BTreeMap::from([(
+                "github".to_string(),
+                "https://github.com/budhilaw".to_string(),
+            )])),
+            ..Default::default()
+        };
+        assert!(ProfileService::validate(&valid).is_ok());
+
+        for invalid in [
+            UpdateProfileRequest {
+                name: Some("  ".to_string()),
+                ..Default::default()
+            },
+            UpdateProfileRequest {
+                email: Some("nope".to_string()),
+                ..Default::default()
+            },
+            UpdateProfileRequest {
+                bio: Some("x".repeat(MAX_BIO_LEN + 1)),
+                ..Default::default()
+            },
+            UpdateProfileRequest {
+                avatar_url: Some("javascript:alert(1)".to_string()),
+                ..Default::default()
+            },
+            UpdateProfileRequest {
+                social_links: Some(BTreeMap::from([("x".to_string(), "ftp://x".to_string())])),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                ProfileService::validate(&invalid),
+                Err(AppError::ValidationError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_token_value() {
+        let user_id = Uuid::new_v4();
+        let value = format!("{} abc123", user_id);
+        assert_eq!(parse_token_value(&value).unwrap(), (user_id, "abc123"));
+
+        for malformed in ["", "abc123", "not-a-uuid abc123"] {
+            assert!(matches!(
+                parse_token_value(malformed),
+                Err(AppError::InternalError(_))
+            ));
+        }
+
+        let value = format!("{} cancel123 new@example.com", user_id);
+        assert_eq!(
+            parse_verification_value(&value).unwrap(),
+            (user_id, Some("cancel123"), "new@example.com")
+        );
+        let value = format!("{} new@example.com", user_id);
+        assert_eq!(
+            parse_verification_value(&value).unwrap(),
+            (user_id, None, "new@example.com")
+        );
+    }
+
+    #[test]
+    fn test_confirmation_uses_up_cancel_token() {
+        let user_id = Uuid::new_v4();
+        let value = format!("{} cancel123 new@example.com", user_id);
+        let (id, email, used_keys) = confirmation("verify123", &value).unwrap();
+        assert_eq!((id, email), (user_id, "new@example.com"));
+        assert_eq!(
+            used_keys,
+            vec![
+                keys::email_verification("verify123"),
+                keys::email_change_cancel("cancel123"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let user_id = Uuid::new_v4();
+        let value = format!("{} verify123", user_id);
+        let used_keys = vec![
+            keys::email_change_cancel("cancel123"),
+            keys::email_verification("verify123"),
+        ];
+
+        let pending = format!("{} cancel123 new@example.com", user_id);
+        assert_eq!(
+            cancellation("cancel123", &value, Some(&pending)).unwrap(),
+            (user_id, Some("new@example.com"), used_keys.clone())
+        );
+
+        // The verification link already expired: nothing pending to clear
+        assert_eq!(
+            cancellation("cancel123", &value, None).unwrap(),
+            (user_id, None, used_keys)
+        );
+
+        assert!(cancellation("cancel123", "garbage", None).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! Quota service counting requests per client in fixed windows.
+
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::{QuotaClient, QuotaStatus};
+use crate::pkg::redis::keys;
+
+/// Service tracking request quotas in Redis.
+///
+/// Windows are aligned to multiples of `QUOTA_WINDOW_SECONDS` since the Unix
+/// epoch, so every client's usage resets at the same moments.
+#[derive(Clone)]
+pub struct QuotaService {
+    redis: redis::aio::ConnectionManager,
+    window_seconds: u64,
+    anonymous_limit: u64,
+    user_limit: u64,
+}
+
+impl QuotaService {
+    /// Create a new quota service.
+    pub fn new(config: &Config, redis: redis::aio::ConnectionManager) -> Self {
+        Self {
+            redis,
+            window_seconds: config.quota_window_seconds,
+            anonymous_limit: config.quota_anonymous_limit,
+            user_limit: config.quota_user_limit,
+        }
+    }
+
+    /// Count one request against the client and return the resulting usage.
+    ///
+    /// Clients without a limit are not counted.
+    pub async fn consume(&self, client: QuotaClient) -> Result<QuotaStatus, AppError> {
+        let limit = self.limit_for(client);
+        let window_start = self.window_start(Utc::now());
+        if limit == 0 {
+            return Ok(self.build_status(client, limit, 0, window_start));
+        }
+
+        let key = keys::quota(&client.to_string(), window_start);
+        let mut redis = self.redis.clone();
+        let used: u64 = redis.incr(&key, 1).await?;
+        if used == 1 {
+            let _: () = redis.expire(&key, self.window_seconds as i64).await?;
+        }
+
+        Ok(self.build_status(client, limit, used, window_start))
+    }
+
+    /// Current usage without counting a request.
+    pub async fn status(&self, client: QuotaClient) -> Result<QuotaStatus, AppError> {
+        let window_start = self.window_start(Utc::now());
+        let mut redis = self.redis.clone();
+        let used: Option<u64> = redis
+            .get(keys::quota(&client.to_string(), window_start))
+            .await?;
+
+        Ok(self.build_status(
+            client,
+            self.limit_for(client),
+            used.unwrap_or(0),
+            window_start,
+        ))
+    }
+
+    /// Clear the client's usage in the current window.
+    pub async fn reset(&self, client: QuotaClient) -> Result<QuotaStatus, AppError> {
+        let window_start = self.window_start(Utc::now());
+        let mut redis = self.redis.clone();
+        let _: () = redis
+            .del(keys::quota(&client.to_string(), window_start))
+            .await?;
+
+        Ok(self.build_status(client, self.limit_for(client), 0, window_start))
+    }
+
+    // Private helper methods
+
+    fn limit_for(&self, client: QuotaClient) -> u64 {
+        match client {
+            QuotaClient::Ip(_) => self.anonymous_limit,
+            QuotaClient::User(_) => self.user_limit,
+        }
+    }
+
+    fn window_start(&self, now: DateTime<Utc>) -> u64 {
+        window_start(now, self.window_seconds)
+    }
+
+    fn build_status(
+        &self,
+        client: QuotaClient,
+        limit: u64,
+        used: u64,
+        window_start: u64,
+    ) -> QuotaStatus {
+        let reset_at = DateTime::from_timestamp((window_start + self.window_seconds) as i64, 0)
+            .unwrap_or_else(Utc::now);
+        QuotaStatus {
+            client: client.to_string(),
+            limit,
+            used,
+            remaining: limit.saturating_sub(used),
+            reset_at,
+        }
+    }
+}
+
+/// Start of the window containing `now`, in Unix seconds.
+fn window_start(now: DateTime<Utc>, window_seconds: u64) -> u64 {
+    let now = now.timestamp().max(0) as u64;
+    now - now % window_seconds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_start() {
+        let now = DateTime::from_timestamp(1_700_000_123, 0).unwrap();
+        assert_eq!(window_start(now, 3600), 1_699_999_200);
+        assert_eq!(window_start(now, 1), 1_700_000_123);
+    }
+}
@@ -0,0 +1,282 @@
+//! Frontend revalidation after published posts change.
+//!
+//! Post writes queue the frontend paths they affect without waiting; a single
+//! worker posts them in batches to `REVALIDATE_URL` so a statically generated
+//! frontend (e.g. Next.js ISR) regenerates those pages within seconds. Failed
+//! deliveries are retried with exponential backoff while later changes queue
+//! up behind them, and dead-lettered once attempts run out. Every attempt is
+//! kept in the webhook log.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::models::{Job, JobKind, Post, PostStatus};
+use crate::pkg::QueueDepth;
+use crate::repositories::FailedJobRepository;
+use crate::services::{DomainEvent, EventSubscriber, WebhookLog};
+use crate::startup::backoff_delay;
+
+/// Delivery attempts per batch before it is dead-lettered.
+const MAX_ATTEMPTS: u32 = 5;
+/// Longest wait between delivery attempts.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// Time allowed for the frontend to answer one delivery.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Body sent to the revalidation endpoint.
+#[derive(Debug, Serialize)]
+struct RevalidateRequest<'a> {
+    site_id: Uuid,
+    paths: &'a [String],
+}
+
+/// Revalidation endpoint and the secret it expects.
+struct Endpoint {
+    url: String,
+    authorization: String,
+    log: WebhookLog,
+}
+
+/// Handle for queueing frontend revalidations.
+#[derive(Clone)]
+pub struct Revalidator {
+    sender: Option<mpsc::UnboundedSender<(Uuid, Vec<String>)>>,
+    endpoint: Option<Arc<Endpoint>>,
+    depth: QueueDepth,
+    post_path: Arc<str>,
+    list_paths: Arc<[String]>,
+}
+
+impl Revalidator {
+    /// Start the delivery worker, unless no revalidation endpoint is configured.
+    ///
+    /// Batches that run out of attempts are stored in `failed_jobs`.
+    pub fn spawn(config: &Config, failed_jobs: FailedJobRepository, log: WebhookLog) -> Self {
+        let depth = QueueDepth::new();
+        let endpoint = config
+            .revalidate_url
+            .clone()
+            .zip(config.revalidate_secret.as_ref())
+            .map(|(url, secret)| {
+                Arc::new(Endpoint {
+                    url,
+                    authorization: format!("Bearer {}", secret),
+                    log,
+                })
+            });
+        let sender = endpoint.clone().map(|endpoint| {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            tokio::spawn(run(endpoint, receiver, depth.clone(), failed_jobs));
+            sender
+        });
+        Self {
+            sender,
+            endpoint,
+            depth,
+            post_path: config.revalidate_post_path.as_str().into(),
+            list_paths: config.revalidate_paths.as_slice().into(),
+        }
+    }
+
+    /// Queue the pages affected by a post going from `before` to `after`
+    /// (`None` when it was just created or deleted).
+    pub fn post_changed(&self, before: Option<&Post>, after: Option<&Post>) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let Some(site_id) = after.or(before).map(|post| post.site_id) else {
+            return;
+        };
+        let paths = affected_paths(&self.post_path, &self.list_paths, before, after);
+        if paths.is_empty() {
+            return;
+        }
+        if sender.send((site_id, paths)).is_err() {
+            tracing::warn!("Frontend revalidation worker is not running");
+        } else {
+            self.depth.queued();
+        }
+    }
+
+    /// Gauge of revalidations waiting to be delivered.
+    pub fn queue_depth(&self) -> QueueDepth {
+        self.depth.clone()
+    }
+
+    /// Deliver one batch right away, without retrying, e.g. to retry a failed job.
+    pub async fn deliver_now(&self, site_id: Uuid, paths: &[String]) -> Result<(), String> {
+        let endpoint = self
+            .endpoint
+            .as_ref()
+            .ok_or("Frontend revalidation is not configured")?;
+        attempt(endpoint, site_id, paths).await
+    }
+}
+
+impl EventSubscriber for Revalidator {
+    fn handle(&self, event: &DomainEvent) {
+        let (before, after) = event.post_change();
+        self.post_changed(before, after);
+    }
+}
+
+/// Pages showing the post before or after the change, if it was published on
+/// either side; drafts never reach the frontend.
+fn affected_paths(
+    post_path: &str,
+    list_paths: &[String],
+    before: Option<&Post>,
+    after: Option<&Post>,
+) -> Vec<String> {
+    let published: Vec<&Post> = [before, after]
+        .into_iter()
+        .flatten()
+        .filter(|post| post.status == PostStatus::Published)
+        .collect();
+    if published.is_empty() {
+        return Vec::new();
+    }
+
+    let mut paths: BTreeSet<String> = list_paths.iter().cloned().collect();
+    paths.extend(
+        published
+            .iter()
+            .map(|post| post_path.replace("{slug}", &post.slug)),
+    );
+    paths.into_iter().collect()
+}
+
+async fn run(
+    endpoint: Arc<Endpoint>,
+    mut receiver: mpsc::UnboundedReceiver<(Uuid, Vec<String>)>,
+    depth: QueueDepth,
+    failed_jobs: FailedJobRepository,
+) {
+    while let Some((site_id, paths)) = receiver.recv().await {
+        let mut batches: BTreeMap<Uuid, BTreeSet<String>> = BTreeMap::new();
+        batches.entry(site_id).or_default().extend(paths);
+        let mut taken = 1;
+        while let Ok((site_id, paths)) = receiver.try_recv() {
+            batches.entry(site_id).or_default().extend(paths);
+            taken += 1;
+        }
+        depth.taken(taken);
+
+        for (site_id, paths) in batches {
+            let paths: Vec<String> = paths.into_iter().collect();
+            if let Err(error) = deliver(&endpoint, site_id, &paths).await {
+                let job = Job::Revalidation { site_id, paths };
+                if let Err(err) = failed_jobs.create(&job, &error, MAX_ATTEMPTS as i32).await {
+                    tracing::error!(error = %err, ?job, "Failed to dead-letter revalidation");
+                }
+            }
+        }
+    }
+}
+
+/// Send one batch, retrying with backoff until it is accepted or attempts run
+/// out, then returning the last error.
+async fn deliver(endpoint: &Endpoint, site_id: Uuid, paths: &[String]) -> Result<(), String> {
+    let mut attempts = 1;
+    loop {
+        let error = match attempt(endpoint, site_id, paths).await {
+            Ok(()) => {
+                tracing::debug!(%site_id, ?paths, "Frontend revalidated");
+                return Ok(());
+            }
+            Err(error) => error,
+        };
+
+        if attempts < MAX_ATTEMPTS {
+            let delay = backoff_delay(attempts, MAX_RETRY_DELAY);
+            tracing::warn!(
+                attempt = attempts,
+                retry_in_seconds = delay.as_secs(),
+                error = %error,
+                "Frontend revalidation failed"
+            );
+            tokio::time::sleep(delay).await;
+            attempts += 1;
+        } else {
+            tracing::error!(
+                %site_id,
+                ?paths,
+                error = %error,
+                "Frontend revalidation failed; giving up"
+            );
+            return Err(error);
+        }
+    }
+}
+
+/// Send one batch once.
+async fn attempt(endpoint: &Endpoint, site_id: Uuid, paths: &[String]) -> Result<(), String> {
+    let body = serde_json::to_string(&RevalidateRequest { site_id, paths })
+        .map_err(|e| format!("Failed to encode revalidation request: {}", e))?;
+    endpoint
+        .log
+        .post(
+            JobKind::Revalidation,
+            &endpoint.url,
+            &[("Authorization", endpoint.authorization.as_str())],
+            body,
+            REQUEST_TIMEOUT,
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+
+    use crate::models::PostVisibility;
+
+    fn post(slug: &str, status: PostStatus) -> Post {
+        Post {
+            id: Uuid::new_v4(),
+            site_id: Uuid::new_v4(),
+            title: "Title".to_string(),
+            slug: slug.to_string(),
+            content: String::new(),
+            excerpt: None,
+            status,
+            visibility: PostVisibility::default(),
+            author_id: Uuid::new_v4(),
+            category_id: None,
+            published_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_affected_paths() {
+        let lists = vec!["/".to_string(), "/blog".to_string()];
+        let draft = post("draft", PostStatus::Draft);
+        let old = post("old-slug", PostStatus::Published);
+        let new = post("new-slug", PostStatus::Published);
+
+        assert!(affected_paths("/blog/{slug}", &lists, None, Some(&draft)).is_empty());
+        assert_eq!(
+            affected_paths("/blog/{slug}", &lists, Some(&old), Some(&new)),
+            vec!["/", "/blog", "/blog/new-slug", "/blog/old-slug"]
+        );
+        // Unpublishing and deleting drop the old page
+        assert_eq!(
+            affected_paths("/blog/{slug}", &lists, Some(&old), Some(&draft)),
+            vec!["/", "/blog", "/blog/old-slug"]
+        );
+        assert_eq!(
+            affected_paths("/blog/{slug}", &lists, Some(&old), None),
+            vec!["/", "/blog", "/blog/old-slug"]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Revision service keeping the history of post text and diffing it.
+//!
+//! Like the activity feed, revisions are recorded from domain events, so
+//! edits through the API and Git sync both land in the history. They are
+//! written in the background and a failed write is only logged.
+
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{PostRevision, RevisionDiff, RevisionSummary};
+use crate::pkg::diff::{diff_text, DiffStats};
+use crate::repositories::RevisionRepository;
+use crate::services::{DomainEvent, EventSubscriber};
+
+/// Service recording and comparing post revisions.
+#[derive(Clone)]
+pub struct RevisionService {
+    repo: RevisionRepository,
+}
+
+impl RevisionService {
+    /// Create a new revision service.
+    pub fn new(repo: RevisionRepository) -> Self {
+        Self { repo }
+    }
+
+    /// A post's revisions, newest first.
+    pub async fn list(&self, post_id: Uuid) -> Result<Vec<RevisionSummary>, AppError> {
+        self.repo.find_by_post(post_id).await
+    }
+
+    /// What changed in a post from revision `from` to revision `to`.
+    pub async fn diff(
+        &self,
+        post_id: Uuid,
+        from: Uuid,
+        to: Uuid,
+    ) -> Result<RevisionDiff, AppError> {
+        let (old, new) = tokio::try_join!(self.find(post_id, from), self.find(post_id, to),)?;
+        let title = diff_text(&old.title, &new.title);
+        let excerpt = diff_text(
+            old.excerpt.as_deref().unwrap_or_default(),
+            new.excerpt.as_deref().unwrap_or_default(),
+        );
+        let content = diff_text(&old.content, &new.content);
+        let mut stats = DiffStats::default();
+        for segments in [&title, &excerpt, &content] {
+            stats.add(segments);
+        }
+        Ok(RevisionDiff {
+            from: RevisionSummary::from(&old),
+            to: RevisionSummary::from(&new),
+            title,
+            excerpt,
+            content,
+            stats,
+        })
+    }
+
+    // Private helper methods
+
+    async fn find(&self, post_id: Uuid, id: Uuid) -> Result<PostRevision, AppError> {
+        self.repo
+            .find_by_id(post_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Revision {} not found", id)))
+    }
+}
+
+impl EventSubscriber for RevisionService {
+    fn handle(&self, event: &DomainEvent) {
+        let DomainEvent::PostSaved { before, after, .. } = event else {
+            return;
+        };
+        if !PostRevision::changed(before.as_ref(), after) {
+            return;
+        }
+        let post = after.clone();
+        let edited_by = event.actor_id();
+        let repo = self.repo.clone();
+        tokio::spawn(async move {
+            if let Err(err) = repo.create(&post, edited_by).await {
+                tracing::warn!(error = %err, post_id = %post.id, "Failed to record revision");
+            }
+        });
+    }
+}
@@ -0,0 +1,189 @@
+//! Background sync of post writes into the search engine.
+//!
+//! Post saves and deletes are queued without waiting; a single worker drains
+//! the queue in batches and pushes the changes to engines that keep their own
+//! index. On startup the worker re-sends every post so an empty or stale index
+//! catches up.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::pkg::{QueueDepth, SearchEngine};
+use crate::repositories::PostRepository;
+use crate::services::{DomainEvent, EventSubscriber};
+
+/// Posts sent to the engine per request during a full rebuild.
+const REBUILD_BATCH_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IndexEvent {
+    Saved(Uuid),
+    Deleted(Uuid),
+}
+
+/// Handle for queueing search index updates.
+#[derive(Clone)]
+pub struct SearchIndexer {
+    sender: Option<mpsc::UnboundedSender<IndexEvent>>,
+    depth: QueueDepth,
+}
+
+impl SearchIndexer {
+    /// Start the indexer worker, unless `engine` reads posts directly.
+    pub fn spawn(engine: Arc<dyn SearchEngine>, post_repo: PostRepository) -> Self {
+        let depth = QueueDepth::new();
+        if !engine.needs_sync() {
+            return Self {
+                sender: None,
+                depth,
+            };
+        }
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run(engine, post_repo, receiver, depth.clone()));
+        Self {
+            sender: Some(sender),
+            depth,
+        }
+    }
+
+    /// Gauge of updates waiting to be indexed.
+    pub fn queue_depth(&self) -> QueueDepth {
+        self.depth.clone()
+    }
+
+    /// Queue a created or updated post for indexing.
+    pub fn post_saved(&self, id: Uuid) {
+        self.send(IndexEvent::Saved(id));
+    }
+
+    /// Queue a deleted post for removal from the index.
+    pub fn post_deleted(&self, id: Uuid) {
+        self.send(IndexEvent::Deleted(id));
+    }
+
+    // Private helper methods
+
+    fn send(&self, event: IndexEvent) {
+        if let Some(sender) = &self.sender {
+            if sender.send(event).is_err() {
+                tracing::warn!(?event, "Search indexer is not running");
+            } else {
+                self.depth.queued();
+            }
+        }
+    }
+}
+
+impl EventSubscriber for SearchIndexer {
+    fn handle(&self, event: &DomainEvent) {
+        match event {
+            DomainEvent::PostSaved { after, .. } => self.post_saved(after.id),
+            DomainEvent::PostDeleted { post, .. } => self.post_deleted(post.id),
+        }
+    }
+}
+
+async fn run(
+    engine: Arc<dyn SearchEngine>,
+    post_repo: PostRepository,
+    mut receiver: mpsc::UnboundedReceiver<IndexEvent>,
+    depth: QueueDepth,
+) {
+    match rebuild(engine.as_ref(), &post_repo).await {
+        Ok(count) => tracing::info!(engine = engine.name(), count, "Search index rebuilt"),
+        Err(err) => tracing::warn!(error = %err, "Search index rebuild failed"),
+    }
+
+    while let Some(event) = receiver.recv().await {
+        let mut events = vec![event];
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+        depth.taken(events.len());
+
+        let (saved, deleted) = coalesce(&events);
+        if let Err(err) = sync(engine.as_ref(), &post_repo, &saved, deleted).await {
+            tracing::warn!(error = %err, "Search index sync failed");
+        }
+    }
+}
+
+/// Send every post to the engine, returning how many were indexed.
+async fn rebuild(engine: &dyn SearchEngine, post_repo: &PostRepository) -> Result<usize, AppError> {
+    engine.prepare().await?;
+
+    let mut count = 0;
+    let mut after = None;
+    loop {
+        let documents = post_repo
+            .find_search_documents_after(after, REBUILD_BATCH_SIZE)
+            .await?;
+        let Some(last) = documents.last() else {
+            return Ok(count);
+        };
+        after = Some(last.id);
+        count += documents.len();
+        engine.upsert(&documents).await?;
+    }
+}
+
+async fn sync(
+    engine: &dyn SearchEngine,
+    post_repo: &PostRepository,
+    saved: &[Uuid],
+    mut deleted: Vec<Uuid>,
+) -> Result<(), AppError> {
+    let documents = post_repo.find_search_documents(saved).await?;
+    // A post deleted right after saving no longer has a row
+    deleted.extend(
+        saved
+            .iter()
+            .filter(|id| !documents.iter().any(|doc| doc.id == **id)),
+    );
+
+    engine.upsert(&documents).await?;
+    engine.remove(&deleted).await
+}
+
+/// Split a batch into posts to index and posts to remove; the last event per post wins.
+fn coalesce(events: &[IndexEvent]) -> (Vec<Uuid>, Vec<Uuid>) {
+    let latest: BTreeMap<Uuid, bool> = events
+        .iter()
+        .map(|event| match *event {
+            IndexEvent::Saved(id) => (id, true),
+            IndexEvent::Deleted(id) => (id, false),
+        })
+        .collect();
+
+    let (saved, deleted): (Vec<_>, Vec<_>) = latest.into_iter().partition(|(_, saved)| *saved);
+    (
+        saved.into_iter().map(|(id, _)| id).collect(),
+        deleted.into_iter().map(|(id, _)| id).collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coalesce() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let events = [
+            IndexEvent::Saved(a),
+            IndexEvent::Saved(b),
+            IndexEvent::Saved(a),
+            IndexEvent::Deleted(b),
+        ];
+
+        let (saved, deleted) = coalesce(&events);
+        assert_eq!(saved, vec![a]);
+        assert_eq!(deleted, vec![b]);
+    }
+}
@@ -0,0 +1,110 @@
+//! Search service for the admin command palette.
+
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    AdminSearchGroup, AdminSearchItem, AdminSearchQuery, AdminSearchResponse, AdminSearchRow,
+    SearchResultKind,
+};
+use crate::pkg::sql::escape_like;
+use crate::repositories::SearchRepository;
+
+/// Service for searching across entity types.
+#[derive(Clone)]
+pub struct SearchService {
+    repo: SearchRepository,
+}
+
+impl SearchService {
+    /// Create a new search service.
+    pub fn new(repo: SearchRepository) -> Self {
+        Self { repo }
+    }
+
+    /// Search posts, users, categories and tags by name in one query.
+    ///
+    /// Every type is always present in the response, with an empty list when
+    /// nothing matched, so clients can render a fixed set of sections.
+    pub async fn admin_search(
+        &self,
+        site_id: Uuid,
+        query: AdminSearchQuery,
+    ) -> Result<AdminSearchResponse, AppError> {
+        let term = query.q.unwrap_or_default().trim().to_string();
+        let limit = query.limit.unwrap_or(5).clamp(1, 20);
+
+        let rows = if term.is_empty() {
+            Vec::new()
+        } else {
+            let escaped = escape_like(&term);
+            self.repo
+                .admin_search(
+                    site_id,
+                    &format!("%{}%", escaped),
+                    &format!("{}%", escaped),
+                    limit,
+                )
+                .await?
+        };
+
+        Ok(AdminSearchResponse {
+            query: term,
+            groups: Self::group(rows),
+        })
+    }
+
+    // Private helper methods
+
+    /// Split rows into one group per kind, keeping the query's ranking.
+    fn group(rows: Vec<AdminSearchRow>) -> Vec<AdminSearchGroup> {
+        let mut groups: Vec<AdminSearchGroup> = SearchResultKind::ALL
+            .into_iter()
+            .map(|kind| AdminSearchGroup {
+                kind,
+                items: Vec::new(),
+            })
+            .collect();
+
+        for row in rows {
+            let Ok(kind) = row.kind.parse::<SearchResultKind>() else {
+                continue;
+            };
+            if let Some(group) = groups.iter_mut().find(|group| group.kind == kind) {
+                group.items.push(AdminSearchItem {
+                    id: row.id,
+                    title: row.title,
+                    subtitle: row.subtitle,
+                });
+            }
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group() {
+        let row = |kind: &str, title: &str| AdminSearchRow {
+            kind: kind.to_string(),
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            subtitle: None,
+        };
+        let groups = SearchService::group(vec![
+            row("category", "Rust"),
+            row("post", "Rust in production"),
+            row("post", "Rusty tools"),
+        ]);
+
+        assert_eq!(groups.len(), 4);
+        assert_eq!(groups[0].kind, SearchResultKind::Post);
+        assert_eq!(groups[0].items.len(), 2);
+        assert_eq!(groups[0].items[1].title, "Rusty tools");
+        assert!(groups[1].items.is_empty());
+        assert_eq!(groups[2].items[0].title, "Rust");
+    }
+}
@@ -0,0 +1,110 @@
+//! Site service for resolving and managing the sites served by this deployment.
+
+use uuid::Uuid;
+
+use crate::error::{AppError, FieldError};
+use crate::models::{normalize_host, CreateSiteRequest, Site, UpdateSiteRequest};
+use crate::repositories::SiteRepository;
+
+/// Service for site operations.
+#[derive(Clone)]
+pub struct SiteService {
+    repo: SiteRepository,
+}
+
+impl SiteService {
+    /// Create a new site service.
+    pub fn new(repo: SiteRepository) -> Self {
+        Self { repo }
+    }
+
+    /// Resolve the site serving a request `host`, or the default site.
+    pub async fn resolve(&self, host: Option<&str>) -> Result<Site, AppError> {
+        let host = host.map(normalize_host).unwrap_or_default();
+        self.repo
+            .find_for_host(&host)
+            .await?
+            .ok_or_else(|| AppError::NotFound("No site is configured for this host".to_string()))
+    }
+
+    /// List all sites.
+    pub async fn list(&self) -> Result<Vec<Site>, AppError> {
+        self.repo.find_all().await
+    }
+
+    /// Create a new site.
+    pub async fn create(&self, request: CreateSiteRequest) -> Result<Site, AppError> {
+        let host = Self::validate_host(&request.host)?;
+        if self.repo.find_by_host(&host).await?.is_some() {
+            return Err(AppError::Conflict("Site host already exists".to_string()));
+        }
+
+        self.repo
+            .create(request.name.trim(), &host, &request.settings)
+            .await
+    }
+
+    /// Update an existing site.
+    pub async fn update(&self, id: Uuid, request: UpdateSiteRequest) -> Result<Site, AppError> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Site not found".to_string()))?;
+
+        let host = request
+            .host
+            .as_deref()
+            .map(Self::validate_host)
+            .transpose()?;
+        if let Some(ref host) = host {
+            if let Some(existing) = self.repo.find_by_host(host).await? {
+                if existing.id != id {
+                    return Err(AppError::Conflict("Site host already exists".to_string()));
+                }
+            }
+        }
+
+        self.repo
+            .update(
+                id,
+                request.name.as_deref().map(str::trim),
+                host.as_deref(),
+                request.settings.as_ref(),
+            )
+            .await
+    }
+
+    /// Normalise a host and check it looks like a host name.
+    fn validate_host(host: &str) -> Result<String, AppError> {
+        let host = normalize_host(host);
+        let valid = !host.is_empty()
+            && host.len() <= 255
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'));
+        if valid {
+            Ok(host)
+        } else {
+            Err(AppError::InvalidFields(vec![FieldError::new(
+                "host",
+                "must be a host name such as blog.example.com",
+            )]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_host() {
+        assert_eq!(
+            SiteService::validate_host("Side.Example.com:443").unwrap(),
+            "side.example.com"
+        );
+        assert!(SiteService::validate_host("").is_err());
+        assert!(SiteService::validate_host("https://example.com/").is_err());
+        assert!(SiteService::validate_host("exa mple.com").is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Periodic self-checks behind the public status page.
+//!
+//! Each server process checks its own view of the database, Redis, its
+//! background job queues and the last successful backup, and keeps the
+//! results in memory for the configured history. Nothing is persisted, so
+//! the checks keep working while the database or Redis is down, and the
+//! history starts over when the process restarts.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use redis::aio::ConnectionManager;
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::models::{StatusResponse, StatusSample};
+use crate::pkg::QueueDepth;
+
+/// How long each dependency may take to answer a check.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Checks missed in a row before the current state is reported as unknown.
+const STALE_AFTER_CHECKS: u32 = 3;
+
+/// Service recording self-checks and summarizing them for the status page.
+#[derive(Clone)]
+pub struct StatusMonitor {
+    db_pool: PgPool,
+    redis: ConnectionManager,
+    queues: Arc<[QueueDepth]>,
+    backup_marker: Option<PathBuf>,
+    backup_max_age: chrono::Duration,
+    interval: chrono::Duration,
+    capacity: usize,
+    samples: Arc<Mutex<VecDeque<StatusSample>>>,
+}
+
+impl StatusMonitor {
+    /// Create a status monitor summing the depth of `queues`.
+    pub fn new(
+        config: &Config,
+        db_pool: PgPool,
+        redis: ConnectionManager,
+        queues: Vec<QueueDepth>,
+    ) -> Self {
+        let interval = config.status_check_interval_seconds.max(1);
+        Self {
+            db_pool,
+            redis,
+            queues: queues.into(),
+            backup_marker: config.status_backup_marker.as_ref().map(PathBuf::from),
+            backup_max_age: chrono::Duration::hours(config.status_backup_max_age_hours as i64),
+            interval: chrono::Duration::seconds(interval as i64),
+            capacity: (config.status_history_hours * 60 * 60 / interval).max(1) as usize,
+            samples: Arc::default(),
+        }
+    }
+
+    /// Run one round of checks and add it to the history.
+    pub async fn check(&self) {
+        let checked_at = Utc::now();
+
+        let database = timed(sqlx::query("SELECT 1").execute(&self.db_pool)).await;
+        let mut redis = self.redis.clone();
+        let ping = redis::cmd("PING");
+        let cache = timed(ping.query_async::<String>(&mut redis)).await;
+        if database.is_none() {
+            tracing::warn!("Status check: database is unreachable");
+        }
+        if cache.is_none() {
+            tracing::warn!("Status check: Redis is unreachable");
+        }
+
+        self.record(StatusSample {
+            checked_at,
+            database_ms: database,
+            redis_ms: cache,
+            queue_depth: self.queues.iter().map(QueueDepth::get).sum(),
+            last_backup_at: self.last_backup_at().await,
+        });
+    }
+
+    /// Current status and uptime over the kept history.
+    pub fn status(&self) -> StatusResponse {
+        let samples: Vec<StatusSample> = self
+            .samples
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect();
+        StatusResponse::from_samples(
+            &samples,
+            Utc::now(),
+            self.interval * STALE_AFTER_CHECKS as i32,
+            self.backup_marker.as_ref().map(|_| self.backup_max_age),
+        )
+    }
+
+    // Private helper methods
+
+    fn record(&self, sample: StatusSample) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        while samples.len() >= self.capacity {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    /// Modification time of the marker file the backup job touches on success.
+    async fn last_backup_at(&self) -> Option<DateTime<Utc>> {
+        let marker = self.backup_marker.as_ref()?;
+        let modified = tokio::fs::metadata(marker).await.ok()?.modified().ok()?;
+        Some(modified.into())
+    }
+}
+
+/// Milliseconds `check` took to succeed, or `None` if it failed or timed out.
+async fn timed<T, E>(check: impl std::future::Future<Output = Result<T, E>>) -> Option<f64> {
+    let started = Instant::now();
+    match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(Ok(_)) => Some(started.elapsed().as_secs_f64() * 1000.0),
+        _ => None,
+    }
+}
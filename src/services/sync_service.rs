@@ -0,0 +1,46 @@
+//! Sync service listing content changes for incremental mirrors.
+
+use chrono::Duration;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{SyncChanges, SyncChangesQuery};
+use crate::repositories::SyncRepository;
+
+/// How far `until` trails the database clock: the longest a write transaction
+/// is expected to stay open.
+///
+/// `updated_at` is stamped with the transaction's start time, so a change
+/// committed after a sync can carry a time before that sync's `until`. Leaving
+/// this margin lets such changes commit before their time is handed out.
+const COMMIT_MARGIN_SECONDS: i64 = 60;
+
+/// Service for syncing a site's content incrementally.
+#[derive(Clone)]
+pub struct SyncService {
+    repo: SyncRepository,
+}
+
+impl SyncService {
+    /// Create a new sync service.
+    pub fn new(repo: SyncRepository) -> Self {
+        Self { repo }
+    }
+
+    /// Posts, categories and tags changed since `query.since`, up to
+    /// [`COMMIT_MARGIN_SECONDS`] ago.
+    ///
+    /// The response's `until` is read from the database clock, whatever the
+    /// mirror's own clock says. Passing it back as `since` picks up every
+    /// change exactly once, as long as no write transaction stays open longer
+    /// than the margin; the most recent changes show up a sync later.
+    pub async fn changes(
+        &self,
+        site_id: Uuid,
+        query: SyncChangesQuery,
+    ) -> Result<SyncChanges, AppError> {
+        let until = self.repo.now().await? - Duration::seconds(COMMIT_MARGIN_SECONDS);
+        let rows = self.repo.find_changes(site_id, query.since, until).await?;
+        Ok(SyncChanges::new(query.since, until, rows))
+    }
+}
@@ -1,67 +1,142 @@
 //! Tag service for tag business logic.
 
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
+use crate::config::Config;
 use crate::error::AppError;
-use crate::models::{CreateTagRequest, Tag, TagWithCount, UpdateTagRequest};
+use crate::models::{
+    CreateTagRequest, MergeTagRequest, Tag, TagCloudItem, TagCloudQuery, TagMergeResponse,
+    TagSearchQuery, TagWithCount, UpdateTagRequest,
+};
+use crate::pkg::slug::{self, slugify};
+use crate::pkg::sql::escape_like;
 use crate::repositories::TagRepository;
 
+/// Tags younger than this are never treated as orphaned, so a tag created
+/// while a post is being saved is not removed before the post is tagged.
+const ORPHAN_GRACE_PERIOD_HOURS: i64 = 24;
+/// Longest tag slug (the `tags.slug` column is `VARCHAR(50)`).
+const MAX_SLUG_LEN: usize = 50;
+
 /// Service for tag operations.
 #[derive(Clone)]
 pub struct TagService {
     repo: TagRepository,
+    block_delete_in_use: bool,
 }
 
 impl TagService {
     /// Create a new tag service.
-    pub fn new(repo: TagRepository) -> Self {
-        Self { repo }
+    pub fn new(config: &Config, repo: TagRepository) -> Self {
+        Self {
+            repo,
+            block_delete_in_use: config.taxonomy_block_delete_in_use,
+        }
+    }
+
+    /// List a site's tags with post counts.
+    pub async fn list(&self, site_id: Uuid) -> Result<Vec<TagWithCount>, AppError> {
+        self.repo.find_all_with_count(site_id).await
+    }
+
+    /// Autocomplete tag names for the editor's tag picker.
+    pub async fn search(&self, site_id: Uuid, query: TagSearchQuery) -> Result<Vec<Tag>, AppError> {
+        let term = query.q.as_deref().unwrap_or("").trim();
+        if term.is_empty() {
+            return Ok(vec![]);
+        }
+        let limit = query.limit.unwrap_or(10).clamp(1, 50);
+        let prefix_pattern = format!("{}%", escape_like(term));
+
+        self.repo
+            .search(site_id, term, &prefix_pattern, limit)
+            .await
     }
 
-    /// List all tags with post counts.
-    pub async fn list(&self) -> Result<Vec<TagWithCount>, AppError> {
-        self.repo.find_all_with_count().await
+    /// Most used tags with weights normalised for rendering a tag cloud.
+    pub async fn cloud(
+        &self,
+        site_id: Uuid,
+        query: TagCloudQuery,
+    ) -> Result<Vec<TagCloudItem>, AppError> {
+        let limit = query.limit.unwrap_or(50).clamp(1, 200);
+        let half_life_days = match query.half_life_days {
+            Some(days) if !(days.is_finite() && days > 0.0) => {
+                return Err(AppError::ValidationError(
+                    "half_life_days must be a positive number".to_string(),
+                ));
+            }
+            days => days,
+        };
+
+        let mut tags = self.repo.find_cloud(site_id, half_life_days, limit).await?;
+        let scores: Vec<f64> = tags.iter().map(|tag| tag.score).collect();
+        for (tag, weight) in tags.iter_mut().zip(Self::cloud_weights(&scores)) {
+            tag.weight = weight;
+        }
+        Ok(tags)
     }
 
     /// Get a single tag by ID.
-    pub async fn get_by_id(&self, id: Uuid) -> Result<Tag, AppError> {
+    pub async fn get_by_id(&self, site_id: Uuid, id: Uuid) -> Result<Tag, AppError> {
         self.repo
-            .find_by_id(id)
+            .find_by_id(site_id, id)
             .await?
             .ok_or_else(|| AppError::NotFound("Tag not found".to_string()))
     }
 
     /// Get a single tag by slug.
-    pub async fn get_by_slug(&self, slug: &str) -> Result<Tag, AppError> {
+    pub async fn get_by_slug(&self, site_id: Uuid, slug: &str) -> Result<Tag, AppError> {
         self.repo
-            .find_by_slug(slug)
+            .find_by_slug(site_id, slug)
             .await?
             .ok_or_else(|| AppError::NotFound("Tag not found".to_string()))
     }
 
-    /// Create a new tag.
-    pub async fn create(&self, request: CreateTagRequest) -> Result<Tag, AppError> {
-        let slug = request.slug.unwrap_or_else(|| Self::slugify(&request.name));
-
-        // Check if slug already exists
-        if self.repo.find_by_slug(&slug).await?.is_some() {
-            return Err(AppError::Conflict("Tag slug already exists".to_string()));
-        }
+    /// Create a new tag on a site.
+    pub async fn create(&self, site_id: Uuid, request: CreateTagRequest) -> Result<Tag, AppError> {
+        // Generate a free slug if not provided, the way posts derive slugs
+        // from tag names
+        let slug = match request.slug {
+            Some(slug) => {
+                if self.repo.find_by_slug(site_id, &slug).await?.is_some() {
+                    return Err(AppError::Conflict("Tag slug already exists".to_string()));
+                }
+                slug
+            }
+            None => {
+                let repo = &self.repo;
+                slug::unique(
+                    &slugify(&request.name),
+                    MAX_SLUG_LEN,
+                    |candidate| async move {
+                        Ok(repo.find_by_slug(site_id, &candidate).await?.is_some())
+                    },
+                )
+                .await?
+            }
+        };
 
-        self.repo.create(&request.name, &slug).await
+        self.repo.create(site_id, &request.name, &slug).await
     }
 
     /// Update an existing tag.
-    pub async fn update(&self, id: Uuid, request: UpdateTagRequest) -> Result<Tag, AppError> {
+    pub async fn update(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        request: UpdateTagRequest,
+    ) -> Result<Tag, AppError> {
         // Check if tag exists
         self.repo
-            .find_by_id(id)
+            .find_by_id(site_id, id)
             .await?
             .ok_or_else(|| AppError::NotFound("Tag not found".to_string()))?;
 
         // Check slug uniqueness if updating
         if let Some(ref slug) = request.slug {
-            if let Some(existing) = self.repo.find_by_slug(slug).await? {
+            if let Some(existing) = self.repo.find_by_slug(site_id, slug).await? {
                 if existing.id != id {
                     return Err(AppError::Conflict("Tag slug already exists".to_string()));
                 }
@@ -73,26 +148,126 @@ impl TagService {
             .await
     }
 
-    /// Delete a tag.
-    pub async fn delete(&self, id: Uuid) -> Result<bool, AppError> {
+    /// Soft delete a tag. Its posts stop showing it until it is restored.
+    pub async fn delete(&self, site_id: Uuid, id: Uuid) -> Result<bool, AppError> {
         // Check if tag exists
         self.repo
-            .find_by_id(id)
+            .find_by_id(site_id, id)
             .await?
             .ok_or_else(|| AppError::NotFound("Tag not found".to_string()))?;
 
+        if self.block_delete_in_use {
+            let posts = self.repo.count_posts(id).await?;
+            if posts > 0 {
+                return Err(AppError::Conflict(format!(
+                    "Tag is used by {} post(s); untag them or merge the tag instead",
+                    posts
+                )));
+            }
+        }
+
         self.repo.delete(id).await
     }
 
-    fn slugify(text: &str) -> String {
-        text.to_lowercase()
-            .chars()
-            .map(|c| if c.is_alphanumeric() { c } else { '-' })
-            .collect::<String>()
-            .split('-')
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<_>>()
-            .join("-")
+    /// List a site's soft-deleted tags.
+    pub async fn list_deleted(&self, site_id: Uuid) -> Result<Vec<Tag>, AppError> {
+        self.repo.find_deleted(site_id).await
+    }
+
+    /// Restore a soft-deleted tag, unless its slug was taken meanwhile.
+    pub async fn restore(&self, site_id: Uuid, id: Uuid) -> Result<Tag, AppError> {
+        let deleted = self
+            .repo
+            .find_deleted_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Deleted tag not found".to_string()))?;
+        if self
+            .repo
+            .find_by_slug(site_id, &deleted.slug)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::Conflict(format!(
+                "Cannot restore: tag slug '{}' is already in use",
+                deleted.slug
+            )));
+        }
+
+        self.repo.restore(id).await?;
+        self.get_by_id(site_id, id).await
+    }
+
+    /// Merge a tag into `request.target_id`, retagging its posts.
+    pub async fn merge(
+        &self,
+        site_id: Uuid,
+        id: Uuid,
+        request: MergeTagRequest,
+    ) -> Result<TagMergeResponse, AppError> {
+        if id == request.target_id {
+            return Err(AppError::ValidationError(
+                "Cannot merge a tag into itself".to_string(),
+            ));
+        }
+        self.repo
+            .find_by_id(site_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Tag not found".to_string()))?;
+        let target = self
+            .repo
+            .find_by_id(site_id, request.target_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Target tag not found".to_string()))?;
+
+        let posts_moved = self.repo.merge(id, target.id).await?;
+        Ok(TagMergeResponse {
+            target,
+            posts_moved,
+        })
+    }
+
+    /// List a site's tags that no post uses.
+    pub async fn orphans(&self, site_id: Uuid) -> Result<Vec<Tag>, AppError> {
+        self.repo
+            .find_orphans(Some(site_id), Self::orphan_cutoff())
+            .await
+    }
+
+    /// Delete a site's tags that no post uses, returning them.
+    pub async fn delete_orphans(&self, site_id: Uuid) -> Result<Vec<Tag>, AppError> {
+        self.repo
+            .delete_orphans(Some(site_id), Self::orphan_cutoff())
+            .await
+    }
+
+    /// Find, and with `delete` remove, orphaned tags on every site.
+    pub async fn cleanup_orphans(&self, delete: bool) -> Result<Vec<Tag>, AppError> {
+        if delete {
+            self.repo.delete_orphans(None, Self::orphan_cutoff()).await
+        } else {
+            self.repo.find_orphans(None, Self::orphan_cutoff()).await
+        }
+    }
+
+    fn orphan_cutoff() -> DateTime<Utc> {
+        Utc::now() - Duration::hours(ORPHAN_GRACE_PERIOD_HOURS)
+    }
+
+    /// Scale scores logarithmically onto 0.0..=1.0 so a few very popular tags
+    /// do not flatten the rest of the cloud.
+    fn cloud_weights(scores: &[f64]) -> Vec<f64> {
+        let logs: Vec<f64> = scores.iter().map(|score| score.max(0.0).ln_1p()).collect();
+        let min = logs.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        logs.iter()
+            .map(|log| {
+                if (max - min).abs() < f64::EPSILON {
+                    1.0
+                } else {
+                    ((log - min) / (max - min) * 1000.0).round() / 1000.0
+                }
+            })
+            .collect()
     }
 }
 
@@ -101,9 +276,13 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_slugify() {
-        assert_eq!(TagService::slugify("Rust"), "rust");
-        assert_eq!(TagService::slugify("Web Dev"), "web-dev");
-        assert_eq!(TagService::slugify("C++"), "c");
+    fn test_cloud_weights() {
+        assert!(TagService::cloud_weights(&[]).is_empty());
+        assert_eq!(TagService::cloud_weights(&[3.0, 3.0]), vec![1.0, 1.0]);
+
+        let weights = TagService::cloud_weights(&[99.0, 9.0, 0.0]);
+        assert_eq!(weights[0], 1.0);
+        assert_eq!(weights[1], 0.5);
+        assert_eq!(weights[2], 0.0);
     }
 }
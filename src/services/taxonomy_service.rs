@@ -0,0 +1,355 @@
+//! Taxonomy service for bulk category and tag imports.
+
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::{
+    ImportFormat, ImportRowResult, ImportRowStatus, NewCategory, NewTag, TaxonomyImportReport,
+    TaxonomyImportRow, TaxonomyKind,
+};
+use crate::pkg::slug::slugify;
+use crate::pkg::SlugGenerator;
+use crate::repositories::TaxonomyRepository;
+
+/// Most rows accepted in one import.
+const MAX_IMPORT_ROWS: usize = 1000;
+/// Longest category name or slug (`VARCHAR(100)`).
+const MAX_CATEGORY_LEN: usize = 100;
+/// Longest tag name or slug (`VARCHAR(50)`).
+const MAX_TAG_LEN: usize = 50;
+
+/// Validated import: per-row results and the terms to create.
+#[derive(Debug, Default)]
+struct ImportPlan {
+    results: Vec<ImportRowResult>,
+    categories: Vec<NewCategory>,
+    tags: Vec<NewTag>,
+}
+
+/// Service for bulk taxonomy operations.
+#[derive(Clone)]
+pub struct TaxonomyService {
+    repo: TaxonomyRepository,
+    slugs: SlugGenerator,
+}
+
+impl TaxonomyService {
+    /// Create a new taxonomy service.
+    pub fn new(config: &Config, repo: TaxonomyRepository) -> Self {
+        Self {
+            repo,
+            slugs: SlugGenerator::from_config(config),
+        }
+    }
+
+    /// Import categories and tags from a CSV or JSON file.
+    ///
+    /// Every row is validated first; terms are only created, all in one
+    /// transaction, when no row is invalid. Slugs already on the site are
+    /// skipped, so re-running an import is harmless.
+    pub async fn import(
+        &self,
+        site_id: Uuid,
+        format: ImportFormat,
+        data: &[u8],
+    ) -> Result<TaxonomyImportReport, AppError> {
+        let rows = Self::parse(format, data)?;
+        let (existing_categories, existing_tags) = self.repo.existing_slugs(site_id).await?;
+        let plan = Self::plan(rows, &existing_categories, &existing_tags, &self.slugs);
+
+        let committed = plan
+            .results
+            .iter()
+            .all(|result| result.status != ImportRowStatus::Invalid);
+        if committed && !(plan.categories.is_empty() && plan.tags.is_empty()) {
+            self.repo
+                .import(site_id, &plan.categories, &plan.tags)
+                .await?;
+        }
+
+        Ok(TaxonomyImportReport {
+            committed,
+            categories_created: if committed { plan.categories.len() } else { 0 },
+            tags_created: if committed { plan.tags.len() } else { 0 },
+            rows: plan.results,
+        })
+    }
+
+    // Private helper methods
+
+    /// Parse a file into rows, keeping per-row parse errors.
+    fn parse(
+        format: ImportFormat,
+        data: &[u8],
+    ) -> Result<Vec<Result<TaxonomyImportRow, String>>, AppError> {
+        let rows: Vec<Result<TaxonomyImportRow, String>> = match format {
+            ImportFormat::Csv => csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(data)
+                .deserialize()
+                .map(|row| row.map_err(|e| e.to_string()))
+                .collect(),
+            ImportFormat::Json => serde_json::from_slice::<Vec<serde_json::Value>>(data)
+                .map_err(|e| AppError::ValidationError(format!("Invalid JSON: {}", e)))?
+                .into_iter()
+                .map(|value| serde_json::from_value(value).map_err(|e| e.to_string()))
+                .collect(),
+        };
+
+        if rows.is_empty() {
+            return Err(AppError::ValidationError(
+                "Import file has no rows".to_string(),
+            ));
+        }
+        if rows.len() > MAX_IMPORT_ROWS {
+            return Err(AppError::ValidationError(format!(
+                "Import files are limited to {} rows",
+                MAX_IMPORT_ROWS
+            )));
+        }
+        Ok(rows)
+    }
+
+    /// Validate rows against each other and the slugs already on the site.
+    fn plan(
+        rows: Vec<Result<TaxonomyImportRow, String>>,
+        existing_categories: &HashSet<String>,
+        existing_tags: &HashSet<String>,
+        slugs: &SlugGenerator,
+    ) -> ImportPlan {
+        let mut plan = ImportPlan::default();
+        let mut seen: HashSet<(TaxonomyKind, String)> = HashSet::new();
+        // New category slug -> (index in `plan.results`, parent slug)
+        let mut new_categories: HashMap<String, (usize, Option<String>)> = HashMap::new();
+
+        for (index, row) in rows.into_iter().enumerate() {
+            let mut result = ImportRowResult {
+                row: index + 1,
+                kind: None,
+                slug: None,
+                status: ImportRowStatus::Invalid,
+                message: None,
+            };
+            let row = match row {
+                Ok(row) => row,
+                Err(message) => {
+                    result.message = Some(message);
+                    plan.results.push(result);
+                    continue;
+                }
+            };
+            result.kind = Some(row.kind);
+
+            match Self::check_row(&row) {
+                Err(message) => result.message = Some(message),
+                Ok((name, slug)) => {
+                    result.slug = Some(slug.clone());
+                    let existing = match row.kind {
+                        TaxonomyKind::Category => existing_categories,
+                        TaxonomyKind::Tag => existing_tags,
+                    };
+                    if row.kind == TaxonomyKind::Category && slugs.is_reserved(&slug) {
+                        result.message = Some("Slug is reserved for site routes".to_string());
+                    } else if !seen.insert((row.kind, slug.clone())) {
+                        result.message = Some("Duplicate slug earlier in the file".to_string());
+                    } else if existing.contains(&slug) {
+                        result.status = ImportRowStatus::Exists;
+                    } else {
+                        result.status = ImportRowStatus::Created;
+                        match row.kind {
+                            TaxonomyKind::Category => {
+                                let parent = Self::non_empty(row.parent);
+                                new_categories
+                                    .insert(slug.clone(), (plan.results.len(), parent.clone()));
+                                plan.categories.push(NewCategory {
+                                    name,
+                                    slug,
+                                    description: Self::non_empty(row.description),
+                                    parent_slug: parent,
+                                });
+                            }
+                            TaxonomyKind::Tag => plan.tags.push(NewTag { name, slug }),
+                        }
+                    }
+                }
+            }
+            plan.results.push(result);
+        }
+
+        // Parents must exist (on the site or in the file) and must not form a cycle
+        for (slug, (index, parent)) in &new_categories {
+            let Some(parent) = parent else {
+                continue;
+            };
+            let message =
+                if !existing_categories.contains(parent) && !new_categories.contains_key(parent) {
+                    Some(format!("Parent category '{}' does not exist", parent))
+                } else if Self::has_cycle(slug, &new_categories) {
+                    Some("Parent categories form a cycle".to_string())
+                } else {
+                    None
+                };
+            if let Some(message) = message {
+                plan.results[*index].status = ImportRowStatus::Invalid;
+                plan.results[*index].message = Some(message);
+            }
+        }
+
+        plan
+    }
+
+    /// Check a single row, returning its trimmed name and slug.
+    fn check_row(row: &TaxonomyImportRow) -> Result<(String, String), String> {
+        let max_len = match row.kind {
+            TaxonomyKind::Category => MAX_CATEGORY_LEN,
+            TaxonomyKind::Tag => MAX_TAG_LEN,
+        };
+        if row.kind == TaxonomyKind::Tag
+            && (Self::non_empty(row.description.clone()).is_some()
+                || Self::non_empty(row.parent.clone()).is_some())
+        {
+            return Err("Tags cannot have a description or parent".to_string());
+        }
+
+        let name = row.name.trim();
+        if name.is_empty() {
+            return Err("Name is required".to_string());
+        }
+        if name.chars().count() > max_len {
+            return Err(format!("Name must be at most {} characters", max_len));
+        }
+
+        let slug = Self::non_empty(row.slug.clone()).unwrap_or_else(|| slugify(name));
+        if slug.is_empty() || slugify(&slug) != slug {
+            return Err("Slug may only contain lowercase letters, digits and hyphens".to_string());
+        }
+        if slug.len() > max_len {
+            return Err(format!("Slug must be at most {} characters", max_len));
+        }
+
+        Ok((name.to_string(), slug))
+    }
+
+    /// Whether following parents from `slug` through new categories leads back to it.
+    fn has_cycle(slug: &str, new_categories: &HashMap<String, (usize, Option<String>)>) -> bool {
+        let mut current = slug;
+        for _ in 0..new_categories.len() {
+            match new_categories.get(current) {
+                Some((_, Some(parent))) if parent == slug => return true,
+                Some((_, Some(parent))) => current = parent,
+                _ => return false,
+            }
+        }
+        false
+    }
+
+    fn non_empty(value: Option<String>) -> Option<String> {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan_csv(csv: &str, existing_categories: &[&str]) -> ImportPlan {
+        let rows = TaxonomyService::parse(ImportFormat::Csv, csv.as_bytes()).unwrap();
+        let existing_categories = existing_categories.iter().map(|s| s.to_string()).collect();
+        TaxonomyService::plan(
+            rows,
+            &existing_categories,
+            &HashSet::new(),
+            &SlugGenerator::new(80, &[], &[]),
+        )
+    }
+
+    #[test]
+    fn test_plan_reserved_category_slug() {
+        let plan = plan_csv(
+            "type,name,slug,description,parent\n\
+             category,Feed,,,\n\
+             tag,Feed,,,\n",
+            &[],
+        );
+        let statuses: Vec<ImportRowStatus> = plan.results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![ImportRowStatus::Invalid, ImportRowStatus::Created]
+        );
+    }
+
+    #[test]
+    fn test_plan_valid_import() {
+        let plan = plan_csv(
+            "type,name,slug,description,parent\n\
+             category,Rust Lang,,Systems programming,web-dev\n\
+             category,Web Dev,web-dev,,\n\
+             category,Technology,,,\n\
+             tag,Async,,,\n",
+            &["technology"],
+        );
+        let statuses: Vec<ImportRowStatus> = plan.results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ImportRowStatus::Created,
+                ImportRowStatus::Created,
+                ImportRowStatus::Exists,
+                ImportRowStatus::Created,
+            ]
+        );
+        assert_eq!(plan.categories[0].slug, "rust-lang");
+        assert_eq!(plan.categories[0].parent_slug.as_deref(), Some("web-dev"));
+        assert_eq!(
+            plan.tags,
+            vec![NewTag {
+                name: "Async".to_string(),
+                slug: "async".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_reports_invalid_rows() {
+        let plan = plan_csv(
+            "type,name,slug,description,parent\n\
+             category,A,a,,b\n\
+             category,B,b,,a\n\
+             category,C,c,,missing\n\
+             tag,Rust,,,a\n\
+             tag,Go,Bad Slug,,\n\
+             widget,X,,,\n\
+             tag,Go,go,,\n\
+             tag,Golang,go,,\n",
+            &[],
+        );
+        let invalid: Vec<usize> = plan
+            .results
+            .iter()
+            .filter(|r| r.status == ImportRowStatus::Invalid)
+            .map(|r| r.row)
+            .collect();
+        assert_eq!(invalid, vec![1, 2, 3, 4, 5, 6, 8]);
+        assert!(plan.results[2]
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("missing"));
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let json = r#"[{"type":"tag","name":"Rust"},{"type":"category"}]"#;
+        let rows = TaxonomyService::parse(ImportFormat::Json, json.as_bytes()).unwrap();
+        assert!(rows[0].is_ok());
+        assert!(rows[1].is_err());
+
+        assert!(TaxonomyService::parse(ImportFormat::Json, b"[]").is_err());
+        assert!(TaxonomyService::parse(ImportFormat::Json, b"{").is_err());
+    }
+}
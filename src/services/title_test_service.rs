@@ -0,0 +1,325 @@
+//! Title test service for A/B testing post headlines.
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, FieldError};
+use crate::middleware::{AuthUser, ClientInfo};
+use crate::models::{
+    variant_for, visitor_key, PickTitleRequest, PostListItem, PostResponse, PostViewer,
+    SetTitleVariantsRequest, TitleTestResponse, TitleVariant, TitleVariantResult,
+    UpdatePostRequest,
+};
+use crate::pkg::{TrafficFilter, ViewSource};
+use crate::repositories::TitleTestRepository;
+use crate::services::PostService;
+
+/// Longest title (the `title` columns are `VARCHAR(255)`).
+const MAX_TITLE_LEN: usize = 255;
+/// Most alternative titles tested next to the original.
+const MAX_ALTERNATIVES: usize = 4;
+
+/// Service for post title A/B tests.
+///
+/// Public readers are shown one headline per post, picked from the visitor's
+/// IP address and user agent so they see the same one on every visit. Being
+/// shown a headline in a post list counts an impression, reading the post
+/// with it a click; crawlers and spam referrers are not counted.
+#[derive(Clone)]
+pub struct TitleTestService {
+    repo: TitleTestRepository,
+    post_service: PostService,
+    traffic_filter: TrafficFilter,
+}
+
+impl TitleTestService {
+    /// Create a new title test service.
+    pub fn new(config: &Config, repo: TitleTestRepository, post_service: PostService) -> Self {
+        Self {
+            repo,
+            post_service,
+            traffic_filter: TrafficFilter::new(
+                &config.referrer_spam_domains,
+                &config.bot_user_agents,
+            ),
+        }
+    }
+
+    /// Results of the title test of a post the user may edit.
+    pub async fn get(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<TitleTestResponse, AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+        let variants = self.repo.find_by_posts(&[post.id]).await?;
+        if variants.is_empty() {
+            return Err(AppError::NotFound("Post has no title test".to_string()));
+        }
+        Ok(Self::response(post.id, &post.title, variants))
+    }
+
+    /// Start testing alternative titles of a post, restarting any running test.
+    pub async fn start(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+        request: SetTitleVariantsRequest,
+    ) -> Result<TitleTestResponse, AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+        let titles = Self::validate(&post.title, &request.titles)?;
+        let variants = self.repo.replace(post.id, &titles).await?;
+        Ok(Self::response(post.id, &post.title, variants))
+    }
+
+    /// End a post's title test, keeping its current title.
+    pub async fn stop(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+    ) -> Result<(), AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+        if !self.repo.delete(post.id).await? {
+            return Err(AppError::NotFound("Post has no title test".to_string()));
+        }
+        Ok(())
+    }
+
+    /// End a post's title test, making the winning headline its title.
+    pub async fn pick(
+        &self,
+        site_id: Uuid,
+        post_id: Uuid,
+        auth_user: &AuthUser,
+        request: PickTitleRequest,
+    ) -> Result<PostResponse, AppError> {
+        let post = self
+            .post_service
+            .get_editable(site_id, post_id, auth_user)
+            .await?;
+        let variant = self
+            .repo
+            .find_by_posts(&[post.id])
+            .await?
+            .into_iter()
+            .find(|variant| variant.id == request.variant_id)
+            .ok_or_else(|| {
+                AppError::InvalidFields(vec![FieldError::new(
+                    "variant_id",
+                    "is not a variant of this post's title test",
+                )])
+            })?;
+
+        let response = match variant.title {
+            Some(title) => {
+                let request = UpdatePostRequest {
+                    title: Some(title),
+                    ..Default::default()
+                };
+                self.post_service
+                    .update(site_id, post.id, auth_user, request)
+                    .await?
+            }
+            // The user may edit the post, so they may see it whatever its status
+            None => {
+                self.post_service
+                    .get_by_id(site_id, post.id, PostViewer::Admin)
+                    .await?
+            }
+        };
+        self.repo.delete(post.id).await?;
+        Ok(response)
+    }
+
+    /// Show each post under test with the visitor's headline, counting
+    /// impressions for human visitors.
+    ///
+    /// Returns whether any of the posts is under test.
+    pub async fn serve_list(
+        &self,
+        posts: &mut [PostListItem],
+        client: &ClientInfo,
+        referrer: Option<&str>,
+    ) -> Result<bool, AppError> {
+        let titles = posts.iter_mut().map(|post| (post.id, &mut post.title));
+        self.serve_titles(titles.collect(), client, referrer).await
+    }
+
+    /// Like [`Self::serve_list`], for posts shown in full, e.g. as cards
+    /// from a batch request.
+    pub async fn serve_batch(
+        &self,
+        posts: &mut [PostResponse],
+        client: &ClientInfo,
+        referrer: Option<&str>,
+    ) -> Result<bool, AppError> {
+        let titles = posts.iter_mut().map(|post| (post.id, &mut post.title));
+        self.serve_titles(titles.collect(), client, referrer).await
+    }
+
+    /// Show a post with the visitor's headline if it is under test, counting
+    /// a click for human visitors.
+    ///
+    /// Returns whether the post is under test.
+    pub async fn serve_post(
+        &self,
+        post: &mut PostResponse,
+        client: &ClientInfo,
+        referrer: Option<&str>,
+    ) -> Result<bool, AppError> {
+        let Some(variants) = self.tests(&[post.id]).await?.remove(&post.id) else {
+            return Ok(false);
+        };
+
+        let visitor = visitor_key(&client.ip.to_string(), client.user_agent.as_deref());
+        let variant = &variants[variant_for(&visitor, post.id, variants.len())];
+        if let Some(title) = &variant.title {
+            post.title = title.clone();
+        }
+        if self.is_human(client, referrer) {
+            let repo = self.repo.clone();
+            let variant_id = variant.id;
+            tokio::spawn(async move {
+                if let Err(err) = repo.record_click(variant_id).await {
+                    tracing::warn!(error = %err, %variant_id, "Failed to record title click");
+                }
+            });
+        }
+        Ok(true)
+    }
+
+    // Private helper methods
+
+    /// Swap in the visitor's headline for each post under test, counting an
+    /// impression for human visitors.
+    async fn serve_titles(
+        &self,
+        mut titles: Vec<(Uuid, &mut String)>,
+        client: &ClientInfo,
+        referrer: Option<&str>,
+    ) -> Result<bool, AppError> {
+        let ids: Vec<Uuid> = titles.iter().map(|(id, _)| *id).collect();
+        let mut tests = self.tests(&ids).await?;
+        if tests.is_empty() {
+            return Ok(false);
+        }
+
+        let visitor = visitor_key(&client.ip.to_string(), client.user_agent.as_deref());
+        let mut shown = Vec::new();
+        for (post_id, post_title) in titles.iter_mut() {
+            if let Some(variants) = tests.remove(post_id) {
+                let variant = &variants[variant_for(&visitor, *post_id, variants.len())];
+                if let Some(title) = &variant.title {
+                    **post_title = title.clone();
+                }
+                shown.push(variant.id);
+            }
+        }
+        if self.is_human(client, referrer) {
+            let repo = self.repo.clone();
+            tokio::spawn(async move {
+                if let Err(err) = repo.record_impressions(&shown).await {
+                    tracing::warn!(error = %err, "Failed to record title impressions");
+                }
+            });
+        }
+        Ok(true)
+    }
+
+    /// Variants of the posts among `post_ids` that are under test.
+    async fn tests(&self, post_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<TitleVariant>>, AppError> {
+        let mut tests: HashMap<Uuid, Vec<TitleVariant>> = HashMap::new();
+        if post_ids.is_empty() {
+            return Ok(tests);
+        }
+        for variant in self.repo.find_by_posts(post_ids).await? {
+            tests.entry(variant.post_id).or_default().push(variant);
+        }
+        Ok(tests)
+    }
+
+    fn is_human(&self, client: &ClientInfo, referrer: Option<&str>) -> bool {
+        self.traffic_filter
+            .classify(client.user_agent.as_deref(), referrer)
+            == ViewSource::Human
+    }
+
+    fn response(post_id: Uuid, post_title: &str, variants: Vec<TitleVariant>) -> TitleTestResponse {
+        TitleTestResponse {
+            post_id,
+            started_at: variants
+                .first()
+                .map(|variant| variant.created_at)
+                .unwrap_or_default(),
+            variants: variants
+                .into_iter()
+                .map(|variant| TitleVariantResult::new(variant, post_title))
+                .collect(),
+        }
+    }
+
+    /// Trimmed alternative titles, each distinct and different from `current`.
+    fn validate(current: &str, titles: &[String]) -> Result<Vec<String>, AppError> {
+        let titles: Vec<String> = titles
+            .iter()
+            .map(|title| title.trim().to_string())
+            .collect();
+        let problem = if !(1..=MAX_ALTERNATIVES).contains(&titles.len()) {
+            Some(format!("must have 1 to {} entries", MAX_ALTERNATIVES))
+        } else if titles
+            .iter()
+            .any(|title| title.is_empty() || title.chars().count() > MAX_TITLE_LEN)
+        {
+            Some(format!("must each be 1 to {} characters", MAX_TITLE_LEN))
+        } else if titles
+            .iter()
+            .enumerate()
+            .any(|(i, title)| title == current.trim() || titles[..i].contains(title))
+        {
+            Some("must differ from each other and from the current title".to_string())
+        } else {
+            None
+        };
+
+        match problem {
+            Some(message) => Err(AppError::InvalidFields(vec![FieldError::new(
+                "titles", message,
+            )])),
+            None => Ok(titles),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let titles = |titles: &[&str]| titles.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            TitleTestService::validate("Hello", &titles(&[" Hi there ", "Howdy"])).unwrap(),
+            titles(&["Hi there", "Howdy"])
+        );
+        assert!(TitleTestService::validate("Hello", &[]).is_err());
+        assert!(TitleTestService::validate("Hello", &titles(&["a", "b", "c", "d", "e"])).is_err());
+        assert!(TitleTestService::validate("Hello", &titles(&["  "])).is_err());
+        assert!(TitleTestService::validate("Hello", &titles(&["Hello"])).is_err());
+        assert!(TitleTestService::validate("Hello", &titles(&["Hi", "Hi"])).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! Trending service ranking posts by recent views.
+//!
+//! Lists are rolled up periodically (see [`crate::jobs`]) and cached in Redis
+//! per site and window, so serving them is a single cache read. A list the
+//! rollup has not refreshed in time is still served while it is recomputed.
+
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::middleware::ClientInfo;
+use crate::models::{DailyViews, LocationViews, TrendingPost, TrendingWindow};
+use crate::pkg::redis::keys;
+use crate::pkg::{CachePolicy, GeoIp, ResponseCache, TrafficFilter, ViewSource};
+use crate::repositories::{PostRepository, SiteRepository};
+
+/// Posts kept per cached list; requests may ask for fewer.
+const TRENDING_LIST_SIZE: i64 = 50;
+
+/// Service for trending posts.
+#[derive(Clone)]
+pub struct TrendingService {
+    post_repo: PostRepository,
+    site_repo: SiteRepository,
+    cache: ResponseCache,
+    cache_policy: CachePolicy,
+    traffic_filter: TrafficFilter,
+    geoip: GeoIp,
+}
+
+impl TrendingService {
+    /// Create a new trending service.
+    pub fn new(
+        config: &Config,
+        post_repo: PostRepository,
+        site_repo: SiteRepository,
+        cache: ResponseCache,
+        geoip: GeoIp,
+    ) -> Self {
+        Self {
+            post_repo,
+            site_repo,
+            cache,
+            cache_policy: Self::cache_policy(config.trending_refresh_minutes),
+            traffic_filter: TrafficFilter::new(
+                &config.referrer_spam_domains,
+                &config.bot_user_agents,
+            ),
+            geoip,
+        }
+    }
+
+    /// Count a view of a published post. Failures are logged, not returned,
+    /// and the write does not delay the response.
+    ///
+    /// Views from crawlers and spam referrers are counted apart and never
+    /// trend. Human views are also counted by the client's country and region
+    /// when known.
+    pub fn record_view(&self, post_id: Uuid, client: &ClientInfo, referrer: Option<&str>) {
+        let source = self
+            .traffic_filter
+            .classify(client.user_agent.as_deref(), referrer);
+        let location = (source == ViewSource::Human)
+            .then(|| {
+                self.geoip
+                    .locate_client(client.ip, client.country.as_deref())
+            })
+            .flatten();
+        let post_repo = self.post_repo.clone();
+        tokio::spawn(async move {
+            if let Err(err) = post_repo.record_view(post_id, source).await {
+                tracing::warn!(error = %err, %post_id, "Failed to record post view");
+            }
+            if let Some(location) = location {
+                if let Err(err) = post_repo.record_view_location(post_id, &location).await {
+                    tracing::warn!(error = %err, %post_id, "Failed to record post view location");
+                }
+            }
+        });
+    }
+
+    /// Human views of a site's posts by country and region over the last `days` days.
+    pub async fn view_locations(
+        &self,
+        site_id: Uuid,
+        days: i32,
+    ) -> Result<Vec<LocationViews>, AppError> {
+        self.post_repo.find_view_locations(site_id, days).await
+    }
+
+    /// Human, bot and spam views of a site's posts per day over the last `days` days.
+    pub async fn daily_views(&self, site_id: Uuid, days: i32) -> Result<Vec<DailyViews>, AppError> {
+        self.post_repo.find_daily_views(site_id, days).await
+    }
+
+    /// Most viewed posts of a site within `window`, from the cache when possible.
+    pub async fn trending(
+        &self,
+        site_id: Uuid,
+        window: TrendingWindow,
+        limit: usize,
+    ) -> Result<Vec<TrendingPost>, AppError> {
+        let post_repo = self.post_repo.clone();
+        let mut posts: Vec<TrendingPost> = self
+            .cache
+            .get_or_load(
+                keys::trending(&site_id, &window.to_string()),
+                self.cache_policy,
+                move || async move {
+                    post_repo
+                        .find_trending(site_id, window.days(), TRENDING_LIST_SIZE)
+                        .await
+                },
+            )
+            .await?;
+        posts.truncate(limit);
+        Ok(posts)
+    }
+
+    /// Recompute and cache every window for every site.
+    pub async fn refresh_all(&self) -> Result<(), AppError> {
+        for site in self.site_repo.find_all().await? {
+            self.refresh_site(site.id).await?;
+        }
+        Ok(())
+    }
+
+    /// Recompute and cache every window for a site.
+    pub async fn refresh_site(&self, site_id: Uuid) -> Result<(), AppError> {
+        for window in TrendingWindow::ALL {
+            self.refresh(site_id, window).await?;
+        }
+        Ok(())
+    }
+
+    // Private helper methods
+
+    async fn refresh(&self, site_id: Uuid, window: TrendingWindow) -> Result<(), AppError> {
+        let posts = self
+            .post_repo
+            .find_trending(site_id, window.days(), TRENDING_LIST_SIZE)
+            .await?;
+        self.cache
+            .put(
+                &keys::trending(&site_id, &window.to_string()),
+                &posts,
+                self.cache_policy,
+            )
+            .await
+    }
+
+    /// Lists are fresh for one rollup period and kept for another, so a slow
+    /// run never leaves a gap; without the job, they are recomputed in the
+    /// background when read 15 minutes after the last time.
+    fn cache_policy(refresh_minutes: u64) -> CachePolicy {
+        let period = Duration::from_secs(match refresh_minutes {
+            0 => 15 * 60,
+            minutes => minutes * 60,
+        });
+        CachePolicy {
+            fresh_for: period,
+            stale_for: period,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_policy() {
+        let policy = TrendingService::cache_policy(15);
+        assert_eq!(policy.fresh_for, Duration::from_secs(900));
+        assert_eq!(policy.stale_for, Duration::from_secs(900));
+        assert_eq!(
+            TrendingService::cache_policy(0).fresh_for,
+            Duration::from_secs(900)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Log of outgoing webhook requests.
+//!
+//! Frontend revalidations and event deliveries send their requests through
+//! [`WebhookLog::post`], which stores each attempt with what was sent, the
+//! response status and the time it took, so a receiver that misbehaves can
+//! be debugged from the admin API and a delivery sent again.
+
+use std::time::{Duration, Instant};
+
+use hyper::Method;
+use serde_json::{Map, Value};
+
+use crate::models::{JobKind, NewWebhookAttempt};
+use crate::pkg::http_client;
+use crate::repositories::WebhookAttemptRepository;
+
+/// Longest part of a response body kept with an attempt, in characters.
+const MAX_RESPONSE_BODY_CHARS: usize = 2_000;
+/// Headers whose values are not stored.
+const REDACTED_HEADERS: [&str; 1] = ["authorization"];
+/// Header the HTTP client adds to JSON bodies.
+const CONTENT_TYPE: (&str, &str) = ("Content-Type", "application/json");
+
+/// Handle sending webhook requests and logging every attempt.
+#[derive(Clone)]
+pub struct WebhookLog {
+    repo: WebhookAttemptRepository,
+}
+
+impl WebhookLog {
+    /// Create a new webhook log.
+    pub fn new(repo: WebhookAttemptRepository) -> Self {
+        Self { repo }
+    }
+
+    /// POST `body` as JSON to `url` once, log the attempt, and fail unless
+    /// the response is a 2xx within `timeout`.
+    pub async fn post(
+        &self,
+        kind: JobKind,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: String,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        let started = Instant::now();
+        let result =
+            http_client::send(Method::POST, url, headers, Some(body.clone()), timeout).await;
+        let duration_ms = started.elapsed().as_millis().min(i32::MAX as u128) as i32;
+
+        let (response, outcome) = match result {
+            Ok(response) if response.is_success() => (Some(response), Ok(())),
+            Ok(response) => {
+                let error = format!("status {}", response.status);
+                (Some(response), Err(error))
+            }
+            Err(err) => (None, Err(err)),
+        };
+        let attempt = NewWebhookAttempt {
+            kind,
+            url,
+            request_headers: recorded_headers(&[headers, &[CONTENT_TYPE]].concat()),
+            payload: &body,
+            response_status: response.as_ref().map(|response| response.status as i32),
+            response_body: response.map(|response| {
+                response
+                    .body
+                    .chars()
+                    .take(MAX_RESPONSE_BODY_CHARS)
+                    .collect()
+            }),
+            error: outcome.as_ref().err().map(String::as_str),
+            duration_ms,
+        };
+        if let Err(err) = self.repo.create(attempt).await {
+            tracing::warn!(error = %err, kind = ?kind, "Failed to log webhook attempt");
+        }
+        outcome
+    }
+}
+
+/// Headers as a JSON object, with credentials redacted.
+fn recorded_headers(headers: &[(&str, &str)]) -> Value {
+    let recorded: Map<String, Value> = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if REDACTED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                "[redacted]"
+            } else {
+                value
+            };
+            (name.to_string(), Value::String(value.to_string()))
+        })
+        .collect();
+    Value::Object(recorded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_headers() {
+        assert_eq!(
+            recorded_headers(&[
+                ("Authorization", "Bearer secret"),
+                ("X-Event-Type", "post.published"),
+            ]),
+            serde_json::json!({
+                "Authorization": "[redacted]",
+                "X-Event-Type": "post.published",
+            })
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Startup: connecting to dependencies and serving requests while doing so.
+//!
+//! The listener is bound before Postgres and Redis are reachable. Until the
+//! application router is installed, `/api/health` reports the process as
+//! alive and every other request, including `/api/health/ready`, is answered
+//! with `503`, so orchestrators hold traffic back instead of restarting the
+//! container in a loop.
+
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use axum::{
+    extract::Request,
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use tower::Service;
+
+use crate::error::AppError;
+use crate::response::{ApiResponse, HealthResponse};
+
+/// Path answered as alive while dependencies are still being connected.
+const LIVENESS_PATH: &str = "/api/health";
+
+/// Delay before retry number `attempt` (1-based): one second, doubling up to `max`.
+pub fn backoff_delay(attempt: u32, max: Duration) -> Duration {
+    let seconds = 1u64
+        .checked_shl(attempt.saturating_sub(1))
+        .unwrap_or(u64::MAX);
+    Duration::from_secs(seconds).min(max)
+}
+
+/// Run `connect` until it succeeds, backing off exponentially between attempts.
+///
+/// Gives up with the last error after `max_attempts` failures; `0` retries forever.
+pub async fn retry_with_backoff<T, E, F, Fut>(
+    what: &str,
+    max_attempts: u32,
+    max_delay: Duration,
+    mut connect: F,
+) -> Result<T, E>
+where
+    E: Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match connect().await {
+            Ok(value) => return Ok(value),
+            Err(err) if max_attempts != 0 && attempt >= max_attempts => return Err(err),
+            Err(err) => {
+                let delay = backoff_delay(attempt, max_delay);
+                tracing::warn!(
+                    attempt,
+                    retry_in_seconds = delay.as_secs(),
+                    error = %err,
+                    "Failed to connect to {}",
+                    what
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Slot receiving the application router once startup completes.
+#[derive(Clone, Default)]
+pub struct AppSlot {
+    router: Arc<OnceLock<Router>>,
+}
+
+impl AppSlot {
+    /// Create an empty slot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Install the application router; later calls are ignored.
+    pub fn install(&self, router: Router) {
+        let _ = self.router.set(router);
+    }
+
+    /// Router served from process start: forwards to the application router
+    /// once installed and reports the server as starting until then.
+    pub fn router(&self) -> Router {
+        let slot = self.clone();
+        Router::new().fallback(move |request: Request| {
+            let slot = slot.clone();
+            async move { slot.dispatch(request).await }
+        })
+    }
+
+    async fn dispatch(&self, request: Request) -> Response {
+        match self.router.get() {
+            Some(router) => match router.clone().call(request).await {
+                Ok(response) => response,
+                Err(infallible) => match infallible {},
+            },
+            None if request.uri().path() == LIVENESS_PATH => {
+                Json(ApiResponse::success(HealthResponse::default())).into_response()
+            }
+            None => AppError::ServiceUnavailable("Server is starting".to_string()).into_response(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::{body::Body, http::StatusCode, routing::get};
+
+    #[test]
+    fn test_backoff_delay() {
+        let max = Duration::from_secs(30);
+        assert_eq!(backoff_delay(1, max), Duration::from_secs(1));
+        assert_eq!(backoff_delay(2, max), Duration::from_secs(2));
+        assert_eq!(backoff_delay(5, max), Duration::from_secs(16));
+        assert_eq!(backoff_delay(6, max), max);
+        assert_eq!(backoff_delay(100, max), max);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff_gives_up() {
+        let mut calls = 0;
+        let result: Result<(), String> = retry_with_backoff("test", 3, Duration::ZERO, || {
+            calls += 1;
+            async { Err("down".to_string()) }
+        })
+        .await;
+        assert_eq!(result.unwrap_err(), "down");
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_app_slot_dispatch() {
+        let slot = AppSlot::new();
+        let request = |path: &str| Request::builder().uri(path).body(Body::empty()).unwrap();
+
+        assert_eq!(
+            slot.dispatch(request("/api/health")).await.status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            slot.dispatch(request("/api/health/ready")).await.status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        slot.install(Router::new().route("/api/health/ready", get(|| async { "ready" })));
+        assert_eq!(
+            slot.dispatch(request("/api/health/ready")).await.status(),
+            StatusCode::OK
+        );
+    }
+}